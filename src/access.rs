@@ -0,0 +1,101 @@
+//! Which tools each API key may use, from the `[access]` section of the config, so that one
+//! deployment can serve several users: read-only block and transaction tools to public keys,
+//! say, and the node's tools only to admins.
+//!
+//! A key's role is resolved once per request (`--api-key`), and the registry leaves out every
+//! tool the role doesn't allow before the agent is built.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Stands for every tool in a role's list.
+pub const ALL_TOOLS: &str = "*";
+
+/// Roles, the tools they may use, and the API keys holding them.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccessConfig {
+    /// The tools each role may use, by role: tool names, or `*` for all of them.
+    #[serde(default)]
+    pub roles: BTreeMap<String, Vec<String>>,
+    /// The role of each API key.
+    #[serde(default)]
+    pub keys: BTreeMap<String, String>,
+    /// The role of requests made without a key, which are refused if it isn't set.
+    pub default_role: Option<String>,
+}
+
+/// Captures the errors that may occur while resolving a caller's role.
+#[derive(Debug, thiserror::Error)]
+pub enum AccessError {
+    #[error("An API key is required to ask questions")]
+    MissingKey,
+    #[error("The API key is not in the `[access.keys]` of the config")]
+    UnknownKey,
+    #[error("Role `{0}` is not defined in the `[access.roles]` of the config")]
+    UnknownRole(String),
+}
+
+/// The tools a caller may use.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Allowed {
+    /// Every tool, as when no access is configured
+    #[default]
+    All,
+    /// Only the named tools
+    Only(Vec<String>),
+}
+
+impl Allowed {
+    /// Whether the tool named `tool` may be used.
+    pub fn permits(&self, tool: &str) -> bool {
+        match self {
+            Allowed::All => true,
+            Allowed::Only(tools) => tools.iter().any(|name| name == tool),
+        }
+    }
+}
+
+impl AccessConfig {
+    /// Whether any access control is configured.
+    pub fn is_configured(&self) -> bool {
+        !self.roles.is_empty() || !self.keys.is_empty() || self.default_role.is_some()
+    }
+
+    /// The tools the holder of `key` may use, or those of the default role without a key.
+    ///
+    /// Every tool is allowed when no access is configured.
+    pub fn allowed(&self, key: Option<&str>) -> Result<Allowed, AccessError> {
+        if !self.is_configured() {
+            return Ok(Allowed::All);
+        }
+        let role = match key {
+            // Every key is compared, so the time taken doesn't tell how close a guess was
+            Some(key) => self
+                .keys
+                .iter()
+                .fold(None, |found, (known, role)| {
+                    match constant_time_eq(known.as_bytes(), key.as_bytes()) {
+                        true => Some(role),
+                        false => found,
+                    }
+                })
+                .ok_or(AccessError::UnknownKey)?,
+            None => self.default_role.as_ref().ok_or(AccessError::MissingKey)?,
+        };
+        let tools = self
+            .roles
+            .get(role)
+            .ok_or_else(|| AccessError::UnknownRole(role.clone()))?;
+        match tools.iter().any(|tool| tool == ALL_TOOLS) {
+            true => Ok(Allowed::All),
+            false => Ok(Allowed::Only(tools.clone())),
+        }
+    }
+}
+
+/// Compares secrets in a time that depends only on their lengths.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use rig::providers::openai;
+use serde::Deserialize;
+
+use crate::gemini;
+use crate::provider;
+
+/// Token counts reported by the provider for a single completion call.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
+
+/// Implemented by provider responses that report how many tokens were used.
+pub trait ReportsUsage {
+    fn token_usage(&self) -> Option<TokenUsage>;
+}
+
+impl ReportsUsage for openai::CompletionResponse {
+    fn token_usage(&self) -> Option<TokenUsage> {
+        self.usage.as_ref().map(|usage| TokenUsage {
+            prompt_tokens: usage.prompt_tokens as u64,
+            completion_tokens: usage.total_tokens.saturating_sub(usage.prompt_tokens) as u64,
+        })
+    }
+}
+
+impl ReportsUsage for gemini::GenerateContentResponse {
+    fn token_usage(&self) -> Option<TokenUsage> {
+        self.usage_metadata.as_ref().map(|usage| TokenUsage {
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count,
+        })
+    }
+}
+
+impl ReportsUsage for provider::Response {
+    fn token_usage(&self) -> Option<TokenUsage> {
+        match self {
+            Self::OpenAi(response) => response.token_usage(),
+            Self::Gemini(response) => response.token_usage(),
+            // Served from a recording, so no tokens were spent
+            Self::Replayed(_) => None,
+        }
+    }
+}
+
+/// The price of a model, in USD per million tokens.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ModelPrice {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt
+            + usage.completion_tokens as f64 * self.completion)
+            / 1_000_000.0
+    }
+}
+
+/// Per-model prices used to estimate the cost of a session.
+#[derive(Clone, Debug)]
+pub struct PriceTable {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        let prices = [
+            ("gpt-4o-mini", 0.15, 0.60),
+            ("gpt-4o", 2.50, 10.00),
+            ("gpt-4-turbo", 10.00, 30.00),
+            ("gpt-4", 30.00, 60.00),
+            ("gpt-3.5-turbo", 0.50, 1.50),
+            ("gemini-2.0-flash", 0.10, 0.40),
+            ("gemini-1.5-pro", 1.25, 5.00),
+            ("gemini-1.5-flash", 0.075, 0.30),
+        ]
+        .into_iter()
+        .map(|(model, prompt, completion)| (model.to_string(), ModelPrice { prompt, completion }))
+        .collect();
+
+        Self { prices }
+    }
+}
+
+impl PriceTable {
+    /// Loads the default table, overridden by the JSON file at `CELESTIA_PRICE_TABLE` if set.
+    ///
+    /// The file maps model names to prices, e.g. `{"gpt-4o": {"prompt": 2.5, "completion": 10.0}}`.
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut table = Self::default();
+
+        if let Ok(path) = std::env::var("CELESTIA_PRICE_TABLE") {
+            let text = std::fs::read_to_string(path)?;
+            let overrides: HashMap<String, ModelPrice> = serde_json::from_str(&text)?;
+            table.prices.extend(overrides);
+        }
+
+        Ok(table)
+    }
+
+    pub fn get(&self, model: &str) -> Option<&ModelPrice> {
+        self.prices.get(model)
+    }
+}
+
+/// Why a session may not spend more on the model.
+#[derive(Debug, thiserror::Error)]
+pub enum BudgetError {
+    #[error(
+        "The session has spent ${spent:.4} on {model}, reaching its limit of ${limit:.4}; start \
+         a new session to ask more"
+    )]
+    Exceeded {
+        model: String,
+        spent: f64,
+        limit: f64,
+    },
+    #[error(
+        "{0} has no price in the price table, so the session's spending limit can't be enforced"
+    )]
+    Unpriced(String),
+}
+
+/// Accumulates the token usage of every turn in a session.
+pub struct Ledger {
+    model: String,
+    turns: Vec<TokenUsage>,
+}
+
+impl Ledger {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            turns: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, usage: TokenUsage) {
+        self.turns.push(usage);
+    }
+
+    /// Adds the turns of another ledger, such as one kept while answering a question concurrently.
+    pub fn merge(&mut self, other: Ledger) {
+        self.turns.extend(other.turns);
+    }
+
+    /// Fails once the session has spent `limit` USD, priced with `prices`, so that no more turns
+    /// are answered. A model missing from the table can't be priced, so its sessions fail too
+    /// rather than going unchecked.
+    pub fn within_budget(&self, prices: &PriceTable, limit: f64) -> Result<(), BudgetError> {
+        let summary = self.summary(prices);
+        let Some(spent) = summary.cost else {
+            return Err(BudgetError::Unpriced(summary.model));
+        };
+        if spent >= limit {
+            return Err(BudgetError::Exceeded {
+                model: summary.model,
+                spent,
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Totals the session, pricing it with the given table.
+    pub fn summary(&self, prices: &PriceTable) -> SessionSummary {
+        let usage = self
+            .turns
+            .iter()
+            .fold(TokenUsage::default(), |acc, turn| TokenUsage {
+                prompt_tokens: acc.prompt_tokens + turn.prompt_tokens,
+                completion_tokens: acc.completion_tokens + turn.completion_tokens,
+            });
+
+        SessionSummary {
+            model: self.model.clone(),
+            turns: self.turns.len(),
+            usage,
+            cost: prices.get(&self.model).map(|price| price.cost(&usage)),
+        }
+    }
+}
+
+/// The token usage and estimated cost of a whole session.
+pub struct SessionSummary {
+    pub model: String,
+    pub turns: usize,
+    pub usage: TokenUsage,
+    /// `None` if the model is missing from the price table.
+    pub cost: Option<f64>,
+}
+
+impl fmt::Display for SessionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Session usage ({}, {} turn(s)): {} prompt + {} completion = {} tokens",
+            self.model,
+            self.turns,
+            self.usage.prompt_tokens,
+            self.usage.completion_tokens,
+            self.usage.total()
+        )?;
+
+        match self.cost {
+            Some(cost) => write!(f, ", estimated cost ${:.6}", cost),
+            None => write!(f, ", no price configured for this model"),
+        }
+    }
+}
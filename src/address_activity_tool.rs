@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::address_tool::validate_address;
+use crate::amount::Utia;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::estimate_time_tool::timestamp;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The most transactions bucketed by a single call.
+pub const MAX_TXS: u64 = 1000;
+
+/// How many transactions are requested at a time.
+const PAGE: u64 = 100;
+
+/// How an address's transactions are bucketed.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bucketing {
+    /// By hour of the day (UTC), to find when the address is usually active
+    #[default]
+    Hour,
+    /// By calendar day (UTC)
+    Day,
+}
+
+/// The address whose activity to bucket.
+#[derive(Deserialize)]
+pub struct AddressActivityArgs {
+    /// The `celestia1...` account address.
+    address: String,
+    #[serde(default)]
+    by: Bucketing,
+    /// How many of the newest transactions to bucket.
+    #[serde(default = "default_limit")]
+    limit: u64,
+    /// The networks to look the address up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_limit() -> u64 {
+    500
+}
+
+/// Buckets an address's recent transactions by hour of the day or by day, as rows to chart.
+pub struct AddressActivityTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl AddressActivityTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn bucket(&self, args: AddressActivityArgs) -> Result<String, CelestiaSearchError> {
+        let address = validate_address(&args.address)?;
+        let limit = args.limit.clamp(1, MAX_TXS);
+        self.blocks
+            .across(&args.network, |blocks| {
+                activity(blocks, address, args.by, limit)
+            })
+            .await
+    }
+}
+
+/// What a bucket of transactions did
+#[derive(Default)]
+struct Activity {
+    txs: u64,
+    txs_with_blobs: u64,
+    fees: Utia,
+}
+
+/// The address's newest `limit` transactions, bucketed as `by` says, as a JSON array of rows
+async fn activity(
+    blocks: &CelestiaSearchTool,
+    address: &str,
+    by: Bucketing,
+    limit: u64,
+) -> Result<String, CelestiaSearchError> {
+    let mut txs: Vec<Value> = Vec::new();
+    while (txs.len() as u64) < limit {
+        let page = PAGE.min(limit - txs.len() as u64);
+        let data = blocks
+            .fetch(&format!(
+                "/address/{}/txs?limit={}&offset={}&sort=desc",
+                address,
+                page,
+                txs.len()
+            ))
+            .await?;
+        let fetched: Vec<Value> = data.as_array().cloned().unwrap_or_default();
+        let last = (fetched.len() as u64) < page;
+        txs.extend(fetched);
+        if last {
+            break;
+        }
+    }
+    if txs.is_empty() {
+        return Ok(format!("Address {} has no transactions.", address));
+    }
+
+    // Every hour is listed, so quiet ones show in the chart too
+    let mut buckets: BTreeMap<String, Activity> = match by {
+        Bucketing::Hour => (0..24)
+            .map(|hour| (format!("{:02}:00", hour), Activity::default()))
+            .collect(),
+        Bucketing::Day => BTreeMap::new(),
+    };
+    for tx in &txs {
+        let Some(time) = timestamp(&tx["time"]) else {
+            continue;
+        };
+        let label = match by {
+            Bucketing::Hour => time.format("%H:00").to_string(),
+            Bucketing::Day => time.format("%Y-%m-%d").to_string(),
+        };
+        let bucket = buckets.entry(label).or_default();
+        bucket.txs += 1;
+        let paid_for_blobs = tx["message_types"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(|kind| kind == "MsgPayForBlobs");
+        if paid_for_blobs {
+            bucket.txs_with_blobs += 1;
+        }
+        bucket.fees += Utia::from_value(&tx["fee"]).unwrap_or_default();
+    }
+
+    let label = match by {
+        Bucketing::Hour => "hour",
+        Bucketing::Day => "day",
+    };
+    let rows: Vec<Value> = buckets
+        .into_iter()
+        .map(|(bucket, activity)| {
+            json!({
+                label: bucket,
+                "txs": activity.txs,
+                "txs_with_blobs": activity.txs_with_blobs,
+                "utia_fees": activity.fees.to_string(),
+            })
+        })
+        .collect();
+    Ok(Value::Array(rows).to_string())
+}
+
+impl Tool for AddressActivityTool {
+    const NAME: &'static str = "address_activity";
+
+    type Args = AddressActivityArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Bucket an address's newest transactions, up to {}, by hour of the day or by \
+                 day (UTC), returning rows with each bucket's transaction count, how many of \
+                 them paid for blobs, and their fees in utia. Use it for when an address such as \
+                 a rollup sequencer is most active.",
+                MAX_TXS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "address": {
+                        "type": "string",
+                        "description": "The account address",
+                        "examples": ["celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p"],
+                    },
+                    "by": {
+                        "type": "string",
+                        "enum": ["hour", "day"],
+                        "description": "Bucket by hour of the day, or by day (hour by default)",
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_TXS,
+                        "description": "How many of the newest transactions to bucket",
+                        "examples": [500],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["address"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.bucket(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::cursor;
+use crate::enums::Enums;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The most transactions listed by a single call.
+pub const MAX_LIMIT: u64 = 100;
+
+/// The page of an address's transactions to list.
+#[derive(Deserialize)]
+pub struct AddressTxsArgs {
+    /// The `celestia1...` account address.
+    address: String,
+    /// How many transactions to list.
+    #[serde(default = "default_limit")]
+    limit: u64,
+    /// How many of the newest transactions to skip, to page through older ones.
+    #[serde(default)]
+    offset: u64,
+    /// The cursor ending the previous page, continuing from there instead of `offset`.
+    cursor: Option<String>,
+    /// Only list transactions with a message of this type, e.g. `MsgPayForBlobs`.
+    message_type: Option<String>,
+    /// The networks to look the address up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_limit() -> u64 {
+    10
+}
+
+/// Lists an address's recent transactions, newest first.
+pub struct AddressTxsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl AddressTxsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn list(&self, args: AddressTxsArgs) -> Result<String, CelestiaSearchError> {
+        let address = validate_address(&args.address)?;
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+        let message_type = args.message_type.as_deref().map(str::trim);
+        if let (Some(enums), Some(message_type)) = (self.blocks.enums(), message_type) {
+            Enums::check("message type", &enums.message_types, message_type)?;
+        }
+        let next = args.cursor.as_deref();
+        self.blocks
+            .across(&args.network, |blocks| async move {
+                let offset = match next {
+                    Some(next) => cursor::decode(
+                        next,
+                        AddressTxsTool::NAME,
+                        &scope(blocks, address, message_type),
+                    )?,
+                    None => args.offset,
+                };
+                list_txs(blocks, address, limit, offset, message_type).await
+            })
+            .await
+    }
+}
+
+/// Checks that `address` is an account address, as Celenium and the REST API want them.
+pub(crate) fn validate_address(address: &str) -> Result<&str, CelestiaSearchError> {
+    let address = address.trim();
+    if address.starts_with("celestia1") {
+        Ok(address)
+    } else {
+        Err(CelestiaSearchError::InvalidAddress(address.to_string()))
+    }
+}
+
+/// What a cursor of the address's transactions continues listing
+fn scope(blocks: &CelestiaSearchTool, address: &str, message_type: Option<&str>) -> String {
+    format!(
+        "{}/{}/{}",
+        blocks.network(),
+        address,
+        message_type.unwrap_or_default()
+    )
+}
+
+/// Summarizes a page of the address's transactions, then lists them.
+pub(crate) async fn list_txs(
+    blocks: &CelestiaSearchTool,
+    address: &str,
+    limit: u64,
+    offset: u64,
+    message_type: Option<&str>,
+) -> Result<String, CelestiaSearchError> {
+    let mut endpoint = format!(
+        "/address/{}/txs?limit={}&offset={}&sort=desc",
+        address, limit, offset
+    );
+    if let Some(message_type) = message_type {
+        endpoint.push_str(&format!("&msg_type={}", message_type));
+    }
+    let txs = blocks.fetch(&endpoint).await?;
+    let txs: Vec<&Value> = txs.as_array().into_iter().flatten().collect();
+    if txs.is_empty() {
+        return Ok(match offset {
+            0 => format!("Address {} has no transactions.", address),
+            _ => format!(
+                "Address {} has no transactions beyond the newest {}.",
+                address, offset
+            ),
+        });
+    }
+
+    let failed = txs.iter().filter(|tx| tx["status"] != "success").count();
+    let fees: Utia = txs
+        .iter()
+        .filter_map(|tx| Utia::from_value(&tx["fee"]))
+        .sum();
+    let mut types: BTreeMap<&str, usize> = BTreeMap::new();
+    for tx in &txs {
+        for kind in tx["message_types"].as_array().into_iter().flatten() {
+            *types.entry(kind.as_str().unwrap_or("unknown")).or_default() += 1;
+        }
+    }
+    let mut types: Vec<(&str, usize)> = types.into_iter().collect();
+    types.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let types: Vec<String> = types
+        .iter()
+        .map(|(kind, count)| format!("{} {}", count, kind))
+        .collect();
+
+    let mut output = format!(
+        "Transactions {} to {} of address {}, newest first ({} succeeded, {} failed), paying {} \
+         in fees; messages: {}.",
+        offset + 1,
+        offset + txs.len() as u64,
+        address,
+        txs.len() - failed,
+        failed,
+        price::describe(fees, None),
+        types.join(", ")
+    );
+    for tx in &txs {
+        let kinds: Vec<&str> = tx["message_types"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        output.push_str(&format!(
+            "\n- height {} ({}): {}, fee {} utia, {}, hash {}",
+            tx["height"],
+            tx["time"].as_str().unwrap_or("unknown time"),
+            kinds.join(" + "),
+            tx["fee"].as_str().unwrap_or("0"),
+            tx["status"].as_str().unwrap_or("unknown status"),
+            tx["hash"].as_str().unwrap_or("unknown")
+        ));
+    }
+    if txs.len() as u64 == limit {
+        let next = cursor::encode(
+            AddressTxsTool::NAME,
+            &scope(blocks, address, message_type),
+            offset + limit,
+        );
+        output.push_str(&format!(
+            "\nOlder transactions may follow; call {} with cursor `{}` for the next page.",
+            AddressTxsTool::NAME,
+            next
+        ));
+    }
+
+    Ok(output)
+}
+
+impl Tool for AddressTxsTool {
+    const NAME: &'static str = "address_txs";
+
+    type Args = AddressTxsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "List a Celestia address's transactions, newest first, with their message types, \
+                 heights, fees and status, plus a summary of the page. Returns up to {} at a \
+                 time; a full page ends with a cursor to pass back for the next one.",
+                MAX_LIMIT
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "address": {
+                        "type": "string",
+                        "description": "The account address",
+                        "examples": ["celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p"],
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": "How many transactions to list",
+                        "examples": [10],
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "How many of the newest transactions to skip",
+                        "examples": [0, 10],
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "The cursor ending the previous page, to list the next one",
+                    },
+                    "message_type": {
+                        "type": "string",
+                        "description": "Only list transactions with a message of this type",
+                        "examples": ["MsgPayForBlobs", "MsgSend", "MsgDelegate"],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["address"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.list(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
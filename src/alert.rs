@@ -0,0 +1,201 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::celestia_search_tool::CelestiaResponseFields;
+use crate::price::UTIA_PER_TIA;
+use crate::watch::CHANGES;
+
+/// The block stats fields conditions can be written against.
+pub const FIELDS: &[&str] = &[
+    "blobs_count",
+    "blobs_size",
+    "block_time",
+    "bytes_in_block",
+    "commissions",
+    "events_count",
+    "fee",
+    "fill_rate",
+    "gas_limit",
+    "gas_used",
+    "inflation_rate",
+    "rewards",
+    "square_size",
+    "supply_change",
+    "tx_count",
+];
+
+/// The fields denominated in utia, which accept a `TIA` or `utia` unit.
+const MONETARY_FIELDS: &[&str] = &["commissions", "fee", "rewards", "supply_change"];
+
+/// Captures the errors that may occur while parsing a condition.
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ConditionError {
+    #[error("Condition `{0}` has no comparison operator (one of >, >=, <, <=, ==, !=)")]
+    MissingOperator(String),
+    #[error("Unknown field `{0}` (available: {})", FIELDS.join(", "))]
+    UnknownField(String),
+    #[error("Unknown change `{0}` (available: {})", CHANGES.join(", "))]
+    UnknownChange(String),
+    #[error("Invalid threshold `{0}`")]
+    InvalidThreshold(String),
+    #[error("Unit `{0}` only applies to {}", MONETARY_FIELDS.join(", "))]
+    InvalidUnit(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    /// Longer operators first, so `>=` isn't read as `>`
+    const ALL: [(&'static str, Op); 6] = [
+        (">=", Op::Ge),
+        ("<=", Op::Le),
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        (">", Op::Gt),
+        ("<", Op::Lt),
+    ];
+
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Op::Gt => value > threshold,
+            Op::Ge => value >= threshold,
+            Op::Lt => value < threshold,
+            Op::Le => value <= threshold,
+            Op::Eq => value == threshold,
+            Op::Ne => value != threshold,
+        }
+    }
+}
+
+/// A comparison of a block stats field against a threshold, such as `fill_rate > 0.9` or
+/// `fee >= 5 TIA`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    source: String,
+    field: String,
+    op: Op,
+    /// In the field's own unit (utia for monetary fields)
+    threshold: f64,
+}
+
+impl Condition {
+    pub fn parse(source: &str) -> Result<Self, ConditionError> {
+        Self::parse_over(
+            source,
+            FIELDS,
+            MONETARY_FIELDS,
+            ConditionError::UnknownField,
+        )
+    }
+
+    /// Parses a condition on one of `fields`, of which `monetary` accept a unit.
+    pub(crate) fn parse_over(
+        source: &str,
+        fields: &[&str],
+        monetary: &[&str],
+        unknown: fn(String) -> ConditionError,
+    ) -> Result<Self, ConditionError> {
+        let (index, symbol, op) = Op::ALL
+            .iter()
+            .filter_map(|&(symbol, op)| source.find(symbol).map(|index| (index, symbol, op)))
+            .min_by_key(|&(index, symbol, _)| (index, std::cmp::Reverse(symbol.len())))
+            .ok_or_else(|| ConditionError::MissingOperator(source.to_string()))?;
+
+        let field = source[..index].trim();
+        if !fields.contains(&field) {
+            return Err(unknown(field.to_string()));
+        }
+
+        let mut rhs = source[index + symbol.len()..].split_whitespace();
+        let number = rhs.next().unwrap_or_default();
+        let mut threshold: f64 = number
+            .parse()
+            .map_err(|_| ConditionError::InvalidThreshold(number.to_string()))?;
+
+        if let Some(unit) = rhs.next() {
+            let invalid = || ConditionError::InvalidUnit(unit.to_string());
+            if !monetary.contains(&field) {
+                return Err(invalid());
+            }
+            match unit.to_ascii_lowercase().as_str() {
+                "utia" => {}
+                "tia" => threshold *= UTIA_PER_TIA,
+                _ => return Err(invalid()),
+            }
+        }
+        if let Some(extra) = rhs.next() {
+            return Err(ConditionError::InvalidThreshold(extra.to_string()));
+        }
+
+        Ok(Self {
+            source: source.trim().to_string(),
+            field: field.to_string(),
+            op,
+            threshold,
+        })
+    }
+
+    /// Returns the observed value of the field if the condition holds for the block.
+    pub fn evaluate(&self, stats: &CelestiaResponseFields) -> Option<f64> {
+        let stats = serde_json::to_value(stats).ok()?;
+        self.evaluate_value(&stats)
+    }
+
+    /// Returns the value of the field in `fields`, a JSON object, if the condition holds for it.
+    pub(crate) fn evaluate_value(&self, fields: &Value) -> Option<f64> {
+        let value = match fields.get(&self.field)? {
+            Value::Number(n) => n.as_f64()?,
+            Value::String(s) => s.parse().ok()?,
+            _ => return None,
+        };
+
+        self.op.holds(value, self.threshold).then_some(value)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// A condition that held for a block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alert {
+    pub height: u64,
+    pub condition: String,
+    pub value: f64,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Block {}: {} (observed {})",
+            self.height, self.condition, self.value
+        )
+    }
+}
+
+/// Evaluates every condition against a block, returning the alerts that fired.
+pub fn check(conditions: &[Condition], height: u64, stats: &CelestiaResponseFields) -> Vec<Alert> {
+    conditions
+        .iter()
+        .filter_map(|condition| {
+            condition.evaluate(stats).map(|value| Alert {
+                height,
+                condition: condition.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
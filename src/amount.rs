@@ -0,0 +1,188 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The fractional digits kept, matching the precision of the Cosmos SDK's decimals.
+pub const DECIMALS: u32 = 18;
+
+/// One utia in the base units of [`Utia`].
+const SCALE: i128 = 10i128.pow(DECIMALS);
+
+/// The number of utia in one TIA.
+const UTIA_PER_TIA: i128 = 1_000_000;
+
+/// Captures the errors that may occur while parsing an amount.
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum AmountError {
+    #[error("`{0}` is not a decimal amount")]
+    Invalid(String),
+    #[error("`{0}` is too large an amount")]
+    Overflow(String),
+}
+
+/// An exact amount of utia, such as a fee, a reward or a change in supply.
+///
+/// Celenium reports these as decimal strings that can outgrow a `u64` or carry fractions of a
+/// utia, so they're held as a signed count of 10^-18 utia rather than parsed into floats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utia(i128);
+
+impl Utia {
+    pub const ZERO: Utia = Utia(0);
+
+    /// An amount of whole utia.
+    pub fn new(utia: i64) -> Self {
+        Utia(utia as i128 * SCALE)
+    }
+
+    /// Reads an amount sent as a decimal string or a number.
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        Utia::deserialize(value).ok()
+    }
+
+    /// Reads a Cosmos SDK decimal as gRPC sends it: an integer count of 10^-18 utia, without a
+    /// decimal point.
+    pub fn from_atomics(atomics: &str) -> Result<Self, AmountError> {
+        let atomics = atomics.trim();
+        let digits = atomics.strip_prefix('-').unwrap_or(atomics);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AmountError::Invalid(atomics.to_string()));
+        }
+        atomics
+            .parse()
+            .map(Utia)
+            .map_err(|_| AmountError::Overflow(atomics.to_string()))
+    }
+
+    /// The amount in whole utia, dropping any fraction.
+    pub fn truncate(self) -> Self {
+        Utia(self.0 / SCALE * SCALE)
+    }
+
+    /// The amount as a float, for statistics where exactness doesn't matter.
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    /// The amount in TIA, rounded to the nearest utia and without trailing zeros.
+    pub fn tia(self) -> String {
+        let utia = (self.0 + self.0.signum() * SCALE / 2) / SCALE;
+        let sign = if utia < 0 { "-" } else { "" };
+        let (whole, fraction) = (utia.abs() / UTIA_PER_TIA, utia.abs() % UTIA_PER_TIA);
+        let fraction = format!("{:06}", fraction);
+        match fraction.trim_end_matches('0') {
+            "" => format!("{}{}", sign, whole),
+            fraction => format!("{}{}.{}", sign, whole, fraction),
+        }
+    }
+}
+
+impl Add for Utia {
+    type Output = Utia;
+
+    fn add(self, other: Utia) -> Utia {
+        Utia(self.0 + other.0)
+    }
+}
+
+impl Sub for Utia {
+    type Output = Utia;
+
+    fn sub(self, other: Utia) -> Utia {
+        Utia(self.0 - other.0)
+    }
+}
+
+impl AddAssign for Utia {
+    fn add_assign(&mut self, other: Utia) {
+        self.0 += other.0;
+    }
+}
+
+impl Sum for Utia {
+    fn sum<I: Iterator<Item = Utia>>(iter: I) -> Utia {
+        iter.fold(Utia::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Utia> for Utia {
+    fn sum<I: Iterator<Item = &'a Utia>>(iter: I) -> Utia {
+        iter.copied().sum()
+    }
+}
+
+impl From<u64> for Utia {
+    /// An amount of whole utia too large for [`Utia::new`], such as a fee at an extreme gas
+    /// price.
+    fn from(utia: u64) -> Self {
+        Utia(utia as i128 * SCALE)
+    }
+}
+
+impl FromStr for Utia {
+    type Err = AmountError;
+
+    /// Parses a decimal such as `2340972`, `-5` or `110326765.952`; digits beyond
+    /// [`DECIMALS`] are truncated.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AmountError::Invalid(s.to_string());
+        let overflow = || AmountError::Overflow(s.to_string());
+
+        let (negative, digits) = match s.trim().strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.trim().strip_prefix('+').unwrap_or(s.trim())),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return Err(invalid());
+        }
+
+        let whole: i128 = match whole {
+            "" => 0,
+            whole => whole.parse().map_err(|_| overflow())?,
+        };
+        let fraction = &fraction[..fraction.len().min(DECIMALS as usize)];
+        let fraction: i128 = format!("{:0<width$}", fraction, width = DECIMALS as usize)
+            .parse()
+            .map_err(|_| invalid())?;
+        let units = whole
+            .checked_mul(SCALE)
+            .and_then(|units| units.checked_add(fraction))
+            .ok_or_else(overflow)?;
+
+        Ok(Utia(if negative { -units } else { units }))
+    }
+}
+
+impl fmt::Display for Utia {
+    /// Writes the amount in utia, without trailing zeros.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let (whole, fraction) = (
+            self.0.unsigned_abs() / SCALE as u128,
+            self.0.unsigned_abs() % SCALE as u128,
+        );
+        let fraction = format!("{:0width$}", fraction, width = DECIMALS as usize);
+        match fraction.trim_end_matches('0') {
+            "" => write!(f, "{}{}", sign, whole),
+            fraction => write!(f, "{}{}.{}", sign, whole, fraction),
+        }
+    }
+}
+
+/// Amounts serialize as decimal strings, as Celenium sends them, so no precision is lost.
+impl Serialize for Utia {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Utia {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::de::string_or_number(deserializer)
+    }
+}
@@ -0,0 +1,180 @@
+/// The direction a series is heading in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Flat,
+}
+
+impl Trend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rising => "rising",
+            Self::Falling => "falling",
+            Self::Flat => "flat",
+        }
+    }
+}
+
+/// Summary statistics of a series of samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// The change across the series according to a least-squares fit, from the first sample
+    /// to the last.
+    pub fitted_change: f64,
+}
+
+impl Summary {
+    /// Classifies the fitted change, treating changes within `tolerance` as flat.
+    pub fn trend(&self, tolerance: f64) -> Trend {
+        if self.fitted_change > tolerance {
+            Trend::Rising
+        } else if self.fitted_change < -tolerance {
+            Trend::Falling
+        } else {
+            Trend::Flat
+        }
+    }
+}
+
+/// Summarizes samples taken at evenly spaced points, or returns `None` if there are none.
+pub fn summarize(samples: &[f64]) -> Option<Summary> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    // Least-squares slope against the sample index
+    let mean_x = (n - 1.0) / 2.0;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for (x, y) in samples.iter().enumerate() {
+        let dx = x as f64 - mean_x;
+        covariance += dx * (y - mean);
+        variance += dx * dx;
+    }
+    let slope = if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    };
+
+    Some(Summary {
+        min,
+        max,
+        mean,
+        fitted_change: slope * (n - 1.0),
+    })
+}
+
+/// Returns the `p`th percentile (0 to 100) of `samples` by the nearest-rank method, or `None`
+/// if there are no samples.
+pub fn percentile(samples: &[f64], p: f64) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Returns the Pearson correlation (-1 to 1) of paired samples, or `None` if there are fewer
+/// than two pairs or either series is constant.
+pub fn correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return None;
+    }
+
+    let mean = |samples: &[f64]| samples[..n].iter().sum::<f64>() / n as f64;
+    let (mean_x, mean_y) = (mean(xs), mean(ys));
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+/// Returns the least-squares line `y = intercept + slope * x` through paired samples, as
+/// `(intercept, slope)`, or `None` if there are fewer than two pairs or `xs` is constant.
+pub fn fit(xs: &[f64], ys: &[f64]) -> Option<(f64, f64)> {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return None;
+    }
+
+    let mean = |samples: &[f64]| samples[..n].iter().sum::<f64>() / n as f64;
+    let (mean_x, mean_y) = (mean(xs), mean(ys));
+    let (mut covariance, mut variance_x) = (0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        let dx = x - mean_x;
+        covariance += dx * (y - mean_y);
+        variance_x += dx * dx;
+    }
+    if variance_x == 0.0 {
+        return None;
+    }
+
+    let slope = covariance / variance_x;
+    Some((mean_y - slope * mean_x, slope))
+}
+
+/// One bucket of a histogram: the range of values it holds, and how many samples fell in it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bucket {
+    pub low: f64,
+    /// The upper bound, included in the last bucket only.
+    pub high: f64,
+    pub count: usize,
+}
+
+/// Counts `samples` in `buckets` buckets of equal width spanning their range, or in a single
+/// bucket if they are all equal. Returns no buckets if there are no samples.
+pub fn histogram(samples: &[f64], buckets: usize) -> Vec<Bucket> {
+    let Some(summary) = summarize(samples) else {
+        return Vec::new();
+    };
+    let (min, max) = (summary.min, summary.max);
+    if buckets <= 1 || max == min {
+        return vec![Bucket {
+            low: min,
+            high: max,
+            count: samples.len(),
+        }];
+    }
+
+    let width = (max - min) / buckets as f64;
+    let mut counts = vec![0; buckets];
+    for sample in samples {
+        let bucket = ((sample - min) / width) as usize;
+        counts[bucket.min(buckets - 1)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| Bucket {
+            low: min + width * i as f64,
+            high: match i + 1 == buckets {
+                true => max,
+                false => min + width * (i + 1) as f64,
+            },
+            count,
+        })
+        .collect()
+}
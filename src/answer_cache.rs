@@ -0,0 +1,177 @@
+//! Answers kept for questions asked again, so a daemon, a batch or a bot repeating a question is
+//! served without another round-trip to the model (`--answer-cache`).
+//!
+//! Questions match once normalized: case, spacing, trailing punctuation and digit separators
+//! don't count. How long an answer is kept depends on its data: one drawn only from past blocks
+//! can't change and is kept for [`IMMUTABLE_TTL`], one following the head, prices or the node
+//! only for the live TTL.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::assistant::{ToolCall, Turn, CALL_ERROR};
+use crate::lru::LruCache;
+use crate::metrics::metrics;
+use crate::router::Route;
+use crate::time::Instant;
+use crate::tool_timeout;
+
+/// How long answers drawn only from past blocks are kept.
+pub const IMMUTABLE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The most answers kept.
+pub const MAX_ANSWERS: usize = 1024;
+
+/// The most memory the kept answers may take.
+const MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// Tools whose results follow the head, the market or the node, whatever their arguments.
+const LIVE_TOOLS: &[&str] = &[
+    "countdown",
+    "estimate_time",
+    "mempool",
+    "node_retention",
+    "node_status",
+    "pending_rewards",
+    "pending_unbondings",
+    "sampling_status",
+    "tia_price",
+];
+
+/// Whether an answer's data may change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Freshness {
+    /// Drawn only from past blocks and transactions, or from no tool at all
+    Immutable,
+    /// Drawn from the head, prices, the node or the latest of a listing
+    Live,
+}
+
+/// The form questions are matched in: lowercased, with single spaces, no trailing punctuation
+/// and no separators in numbers, so "Fee of block 2,000,000?" matches "fee of block 2000000".
+pub fn normalize(question: &str) -> String {
+    let lowered = question.to_lowercase();
+    let chars: Vec<char> = lowered.chars().collect();
+    let mut normalized = String::with_capacity(lowered.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let between_digits = i > 0
+            && chars[i - 1].is_ascii_digit()
+            && chars.get(i + 1).is_some_and(char::is_ascii_digit);
+        if matches!(c, ',' | '_') && between_digits {
+            continue;
+        }
+        match c.is_whitespace() {
+            true if !normalized.ends_with(' ') => normalized.push(' '),
+            true => {}
+            false => normalized.push(c),
+        }
+    }
+    normalized
+        .trim()
+        .trim_end_matches(['?', '!', '.', ' '])
+        .to_string()
+}
+
+/// Whether the data `turn` answered from may change: it can't if every tool call named the past
+/// blocks or transaction it looked up and succeeded, and no amount was valued in fiat, which
+/// follows the price feed.
+pub fn freshness(turn: &Turn) -> Freshness {
+    let historical = |call: &ToolCall| {
+        let args = &call.args;
+        let output = call.output.as_deref().unwrap_or_default();
+        !LIVE_TOOLS.contains(&call.name.as_str())
+            && (args.get("height").is_some()
+                || args.get("hash").is_some()
+                || (args.get("from").is_some() && args.get("to").is_some()))
+            // A failed or timed out call may well succeed when asked again
+            && !output.starts_with(CALL_ERROR)
+            && !tool_timeout::timed_out(output)
+            && !fiat(output)
+    };
+    match !fiat(&turn.output) && turn.steps.iter().chain(&turn.tool_call).all(historical) {
+        true => Freshness::Immutable,
+        false => Freshness::Live,
+    }
+}
+
+/// Whether `text` values something in US dollars
+fn fiat(text: &str) -> bool {
+    text.contains("USD")
+        || text
+            .match_indices('$')
+            .any(|(i, _)| text[i + 1..].starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// An answer kept, with how long it is served for
+struct Kept {
+    route: Route,
+    turn: Turn,
+    kept_at: Instant,
+    ttl: Duration,
+}
+
+/// The answers kept for questions, by their settings and normalized question.
+pub struct AnswerCache {
+    answers: Mutex<LruCache<Kept>>,
+    live_ttl: Duration,
+}
+
+impl AnswerCache {
+    /// Keeps answers drawn from live data for `live_ttl`.
+    pub fn new(live_ttl: Duration) -> Self {
+        Self {
+            answers: Mutex::new(LruCache::new("answers", MAX_ANSWERS, MAX_BYTES)),
+            live_ttl,
+        }
+    }
+
+    /// The answer kept for `question` under `scope`, the settings that shape answers such as the
+    /// model and network, if it hasn't expired. It spent no tokens.
+    pub fn get(&self, scope: &str, question: &str) -> Option<(Route, Turn)> {
+        let key = key(scope, question);
+        let mut answers = self.answers.lock().unwrap();
+        let kept = answers
+            .get(&key)
+            .filter(|kept| kept.kept_at.elapsed() < kept.ttl);
+        let result = if kept.is_some() { "hit" } else { "miss" };
+        metrics().cache_lookups.inc(&["answers", result]);
+
+        let kept = kept?;
+        let turn = Turn {
+            usage: Default::default(),
+            ..kept.turn.clone()
+        };
+        Some((kept.route, turn))
+    }
+
+    /// Keeps `turn`, the answer to `question` under `scope`, for as long as its data holds.
+    ///
+    /// Turns with calls only planned in dry-run mode aren't kept, as they answered nothing.
+    pub fn insert(&self, scope: &str, question: &str, route: Route, turn: &Turn) {
+        let planned = turn
+            .steps
+            .iter()
+            .chain(&turn.tool_call)
+            .any(|call| call.output.is_none());
+        if planned {
+            return;
+        }
+        let ttl = match freshness(turn) {
+            Freshness::Immutable => IMMUTABLE_TTL,
+            Freshness::Live => self.live_ttl,
+        };
+        let key = key(scope, question);
+        let size = key.len() + turn.output.len();
+        let kept = Kept {
+            route,
+            turn: turn.clone(),
+            kept_at: Instant::now(),
+            ttl,
+        };
+        self.answers.lock().unwrap().insert(&key, kept, size);
+    }
+}
+
+fn key(scope: &str, question: &str) -> String {
+    format!("{}\n{}", scope, normalize(question))
+}
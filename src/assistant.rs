@@ -0,0 +1,813 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rig::completion::{
+    CompletionError, CompletionModel, Document, Message, ModelChoice, PromptError, ToolDefinition,
+};
+use rig::tool::{Tool, ToolDyn, ToolError, ToolSetError};
+use rig::vector_store::{NoIndex, VectorStoreIndex};
+
+use crate::accounting::{ReportsUsage, TokenUsage};
+use crate::metrics::metrics;
+use crate::provider::ReportsToolCalls;
+use crate::summarize::{self, DEFAULT_RESULT_TOKENS};
+use crate::time::Instant;
+use crate::verify::{self, Verification};
+use crate::{audit, structured, trace};
+
+/// How many times a malformed response is re-prompted before giving up, by default.
+pub const DEFAULT_MAX_REPROMPTS: usize = 2;
+
+/// How many tool calls a turn may chain before the last result is taken as the answer, by
+/// default: one, so the result of the first call is the answer.
+pub const DEFAULT_MAX_ITERATIONS: usize = 1;
+
+/// A turn's model asked for more tool calls than the turn may make.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The question needed more than the {limit} tool call(s) a turn may make; ask a narrower one"
+)]
+pub struct ToolCallLimitExceeded {
+    pub limit: usize,
+}
+
+/// What the output of a failed call among several starts with, before the error.
+pub const CALL_ERROR: &str = "Error: ";
+
+/// Asks the model to go on after a tool call, when it may chain another.
+const CONTINUE: &str = "Answer the question from the tool results above, or call another tool \
+                        if they are not enough to answer it. Don't repeat a call already made.";
+
+/// Sampling parameters sent with every completion request, left to the provider when unset.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GenerationParams {
+    pub temperature: Option<f64>,
+    pub max_tokens: Option<u64>,
+    pub top_p: Option<f64>,
+}
+
+impl GenerationParams {
+    /// The parameters rig has no dedicated setter for, as provider request fields
+    fn additional_params(&self) -> Option<serde_json::Value> {
+        let mut params = serde_json::Map::new();
+        if let Some(max_tokens) = self.max_tokens {
+            params.insert("max_tokens".to_string(), max_tokens.into());
+        }
+        if let Some(top_p) = self.top_p {
+            params.insert("top_p".to_string(), top_p.into());
+        }
+        (!params.is_empty()).then_some(serde_json::Value::Object(params))
+    }
+}
+
+/// A tool call requested by the model.
+#[derive(Clone, Default)]
+pub struct ToolCall {
+    pub name: String,
+    pub args: serde_json::Value,
+    /// The tool's output, or `None` if the call was skipped in dry-run mode.
+    pub output: Option<String>,
+    /// How long the call took, or `None` if it wasn't made: skipped in dry-run mode, or
+    /// answered from an earlier call of the turn.
+    pub latency: Option<Duration>,
+    /// The upstream URLs the call requested, in order, including those answered from cache.
+    pub upstream: Vec<String>,
+}
+
+/// The outcome of a single prompt sent to the assistant.
+#[derive(Clone)]
+pub struct Turn {
+    /// The model's message, the output of the tool it chose to call, or in dry-run mode a
+    /// description of the planned call.
+    pub output: String,
+    /// The last tool call the model made, whose result the output answers from, if any.
+    pub tool_call: Option<ToolCall>,
+    /// The tool calls made before the last one, in order, when the model chained several.
+    pub steps: Vec<ToolCall>,
+    /// Tokens spent on the completion calls, including any re-prompts.
+    pub usage: TokenUsage,
+    /// Numbers of the answer the tool result doesn't back, or that were corrected from it.
+    pub warnings: Vec<String>,
+}
+
+/// An LLM agent driving the Celestia tools.
+///
+/// This plays the role of rig's `Agent`, but drives the completion call itself so that
+/// the raw provider response (and thus token usage) is available after every turn. Like
+/// rig's `RagAgent`, it can pull context documents relevant to the prompt from an index.
+pub struct Assistant<M: CompletionModel, I: VectorStoreIndex = NoIndex> {
+    model: M,
+    model_name: String,
+    preamble: String,
+    tools: Vec<Box<dyn ToolDyn>>,
+    dynamic_context: Option<(usize, I)>,
+    params: GenerationParams,
+    max_reprompts: usize,
+    max_iterations: usize,
+    max_tool_calls: Option<usize>,
+    result_tokens: usize,
+    structured: bool,
+    verification: Verification,
+    dry_run: bool,
+}
+
+/// What the model responded to a single completion request with.
+enum Step {
+    Answer(String),
+    /// Tool calls, executed unless in dry-run mode: several when the model requested
+    /// independent ones at once, which are made concurrently
+    Call(Vec<ToolCall>),
+    /// A tool call already made in the turn, with its earlier result
+    Repeat(ToolCall),
+}
+
+impl<M: CompletionModel> Assistant<M> {
+    pub fn builder(model: M, model_name: &str) -> AssistantBuilder<M> {
+        AssistantBuilder {
+            model,
+            model_name: model_name.to_string(),
+            preamble: None,
+            tools: Vec::new(),
+            dynamic_context: None,
+            params: GenerationParams::default(),
+            max_reprompts: DEFAULT_MAX_REPROMPTS,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            max_tool_calls: None,
+            result_tokens: DEFAULT_RESULT_TOKENS,
+            structured: false,
+            verification: Verification::default(),
+            dry_run: false,
+        }
+    }
+}
+
+impl<M: CompletionModel, I: VectorStoreIndex> Assistant<M, I>
+where
+    M::Response: ReportsUsage + ReportsToolCalls,
+{
+    /// Sends the prompt to the model, executing the tool call it responds with (if any).
+    ///
+    /// Responses the model could fix with a second try (a malformed or unknown tool call, or an
+    /// empty message) are re-prompted with a reminder of the expected format, up to the
+    /// configured number of times. So are structured answers that don't match their schema.
+    pub async fn prompt(&self, prompt: &str) -> Result<Turn, PromptError> {
+        self.chat(prompt, &[]).await
+    }
+
+    /// Like [`prompt`](Self::prompt), continuing the conversation in `history`.
+    pub async fn chat(&self, prompt: &str, history: &[Message]) -> Result<Turn, PromptError> {
+        let span = trace::span("agent.turn")
+            .with("llm.model", self.model_name.clone())
+            .with("agent.history_messages", history.len());
+        trace::traced(span, self.turn(prompt, history)).await
+    }
+
+    async fn turn(&self, prompt: &str, history: &[Message]) -> Result<Turn, PromptError> {
+        let mut usage = TokenUsage::default();
+        let mut steps: Vec<ToolCall> = Vec::new();
+        let mut warnings = Vec::new();
+        let mut messages = history.to_vec();
+        let mut request = prompt.to_string();
+        let mut rounds = 0;
+        // The results of calls made at once, when they are passed on as the answer
+        let mut aggregated = None;
+
+        let (output, tool_call) = loop {
+            let step = self
+                .step(prompt, &request, &messages, &steps, &mut usage)
+                .await?;
+            let mut calls = match step {
+                Step::Answer(answer) => break (answer, steps.pop()),
+                Step::Call(calls) => calls,
+                // Calling a tool again with the same arguments only loops
+                Step::Repeat(call) => {
+                    warnings.push(format!(
+                        "The model called `{}` again with the same arguments, so its earlier \
+                         result is the answer",
+                        call.name
+                    ));
+                    break (call.output.clone().unwrap_or_default(), Some(call));
+                }
+            };
+            rounds += 1;
+            let call = calls.pop().expect("a step calls at least one tool");
+            let Some(output) = call.output.clone() else {
+                let plan = calls
+                    .iter()
+                    .chain([&call])
+                    .map(|call| format!("{}({})", call.name, call.args))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                steps.extend(calls);
+                break (plan, Some(call));
+            };
+            let output = match calls.is_empty() {
+                true => output,
+                false => aggregate(calls.iter().chain([&call])),
+            };
+            if rounds >= self.max_iterations {
+                if !calls.is_empty() {
+                    aggregated = Some(output.clone());
+                }
+                steps.extend(calls);
+                break (output, Some(call));
+            }
+
+            // Later requests see the question and every result so far
+            if steps.is_empty() {
+                messages.push(Message {
+                    role: "user".to_string(),
+                    content: prompt.to_string(),
+                });
+            }
+            let content = match calls.len() {
+                0 => format!(
+                    "Called `{}` with {}, which returned:\n{}",
+                    call.name, call.args, output
+                ),
+                n => format!(
+                    "Called {} tools at once, which returned:\n\n{}",
+                    n + 1,
+                    output
+                ),
+            };
+            messages.push(Message {
+                role: "assistant".to_string(),
+                content,
+            });
+            request = CONTINUE.to_string();
+            steps.extend(calls);
+            steps.push(call);
+        };
+
+        // Planned tool calls have no result to answer from
+        let planned = tool_call.as_ref().is_some_and(|call| call.output.is_none());
+        let output = match self.structured && !planned {
+            true => {
+                self.structure(prompt, history, &output, tool_call.as_ref(), &mut usage)
+                    .await?
+            }
+            false => output,
+        };
+        // Results passed on as they are need no checking
+        let (output, checked) = match aggregated.as_ref() == Some(&output) {
+            true => (output, Vec::new()),
+            false => self.verify(prompt, output, tool_call.as_ref(), &steps),
+        };
+        warnings.extend(checked);
+        Ok(Turn {
+            output,
+            tool_call,
+            steps,
+            usage,
+            warnings,
+        })
+    }
+
+    /// Sends `request`, re-prompting responses the model could fix with a second try
+    async fn step(
+        &self,
+        prompt: &str,
+        request: &str,
+        history: &[Message],
+        steps: &[ToolCall],
+        usage: &mut TokenUsage,
+    ) -> Result<Step, PromptError> {
+        let mut retry = request.to_string();
+        let mut reprompts = 0;
+
+        loop {
+            match self.attempt(prompt, &retry, history, steps, usage).await {
+                Ok(step) => return Ok(step),
+                Err(err) => match malformed_reason(&err) {
+                    Some(reason) if reprompts < self.max_reprompts => {
+                        reprompts += 1;
+                        retry = format!(
+                            "{}\n\nYour previous response could not be used: {}. Reply with \
+                             either a plain-text answer or a single call to one of the provided \
+                             tools, with arguments matching its JSON schema.",
+                            request, reason
+                        );
+                    }
+                    _ => return Err(err),
+                },
+            }
+        }
+    }
+
+    /// Sends a single completion request with the tools, executing the tool call it responds
+    /// with unless it repeats one of the turn's `steps`, or all of them if it requested several
+    async fn attempt(
+        &self,
+        prompt: &str,
+        request: &str,
+        history: &[Message],
+        steps: &[ToolCall],
+        usage: &mut TokenUsage,
+    ) -> Result<Step, PromptError> {
+        let mut definitions = Vec::with_capacity(self.tools.len());
+        for tool in &self.tools {
+            definitions.push(tool.definition(prompt.to_string()).await);
+        }
+
+        let (choice, requested) = self
+            .complete(prompt, request, history, definitions, usage)
+            .await?;
+        let step = match choice {
+            ModelChoice::Message(message) if message.trim().is_empty() => {
+                return Err(CompletionError::ResponseError("Response was empty".into()).into())
+            }
+            ModelChoice::Message(message) => Step::Answer(message),
+            ModelChoice::ToolCall(..) if requested.len() > 1 => {
+                let new = requested.iter().filter(|(name, args)| {
+                    !steps
+                        .iter()
+                        .any(|step| &step.name == name && &step.args == args)
+                });
+                self.within_tool_calls(steps.len() + new.count())?;
+                Step::Call(self.call_tools(requested, steps).await?)
+            }
+            ModelChoice::ToolCall(name, args) => {
+                if let Some(earlier) = steps
+                    .iter()
+                    .find(|step| step.name == name && step.args == args)
+                {
+                    return Ok(Step::Repeat(ToolCall {
+                        name,
+                        args,
+                        output: earlier.output.clone(),
+                        ..ToolCall::default()
+                    }));
+                }
+                self.within_tool_calls(steps.len() + 1)?;
+                if self.dry_run {
+                    return Ok(Step::Call(vec![ToolCall {
+                        name,
+                        args,
+                        ..ToolCall::default()
+                    }]));
+                }
+                let (result, latency, upstream) = self.call_tool(&name, args.to_string()).await;
+                Step::Call(vec![ToolCall {
+                    name,
+                    args,
+                    output: Some(result?),
+                    latency: Some(latency),
+                    upstream,
+                }])
+            }
+        };
+
+        Ok(step)
+    }
+
+    /// Fails if making `calls` tool calls in the turn would go over its limit
+    fn within_tool_calls(&self, calls: usize) -> Result<(), ToolSetError> {
+        match self.max_tool_calls {
+            Some(limit) if calls > limit => Err(ToolSetError::ToolCallError(
+                ToolError::ToolCallError(Box::new(ToolCallLimitExceeded { limit })),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Makes tool calls the model requested at once concurrently, reusing the results of those
+    /// already made in the turn. A failed call leaves its error as its output, for the model to
+    /// see beside the other results, unless they all failed.
+    async fn call_tools(
+        &self,
+        requested: Vec<(String, serde_json::Value)>,
+        steps: &[ToolCall],
+    ) -> Result<Vec<ToolCall>, ToolSetError> {
+        if self.dry_run {
+            let calls = requested.into_iter().map(|(name, args)| ToolCall {
+                name,
+                args,
+                ..ToolCall::default()
+            });
+            return Ok(calls.collect());
+        }
+
+        let results = futures::future::join_all(requested.iter().map(|(name, args)| async move {
+            match steps
+                .iter()
+                .find(|step| &step.name == name && &step.args == args)
+            {
+                Some(earlier) => (
+                    Ok(earlier.output.clone().unwrap_or_default()),
+                    None,
+                    Vec::new(),
+                ),
+                None => {
+                    let (result, latency, upstream) = self.call_tool(name, args.to_string()).await;
+                    (result, Some(latency), upstream)
+                }
+            }
+        }))
+        .await;
+        if results.iter().all(|(result, ..)| result.is_err()) {
+            let error = results.into_iter().find_map(|(result, ..)| result.err());
+            return Err(error.expect("several calls were requested"));
+        }
+
+        let calls = requested.into_iter().zip(results).map(
+            |((name, args), (result, latency, upstream))| ToolCall {
+                name,
+                args,
+                output: Some(result.unwrap_or_else(|e| format!("{}{}", CALL_ERROR, e))),
+                latency,
+                upstream,
+            },
+        );
+        Ok(calls.collect())
+    }
+
+    /// Has the model restate the turn's answer as a structured one, re-prompting replies that
+    /// don't match the schema
+    async fn structure(
+        &self,
+        prompt: &str,
+        history: &[Message],
+        output: &str,
+        tool_call: Option<&ToolCall>,
+        usage: &mut TokenUsage,
+    ) -> Result<String, PromptError> {
+        // A direct answer may already be structured, as the preamble asks
+        if tool_call.is_none() {
+            if let Ok(answer) = structured::parse(output) {
+                return Ok(serde_json::to_string(&answer).expect("answers should serialize"));
+            }
+        }
+
+        let evidence = match tool_call {
+            Some(call) => format!(
+                "The `{}` tool, called with {}, returned:\n{}",
+                call.name,
+                call.args,
+                summarize::fit(output, self.result_tokens)
+            ),
+            None => format!("Your draft answer:\n{}", output),
+        };
+        let question = structured::request(prompt, &evidence);
+        let mut request = question.clone();
+        let mut reprompts = 0;
+
+        loop {
+            let reason = match self
+                .complete(prompt, &request, history, Vec::new(), usage)
+                .await?
+                .0
+            {
+                ModelChoice::Message(reply) => match structured::parse(&reply) {
+                    Ok(answer) => {
+                        return Ok(serde_json::to_string(&answer).expect("answers should serialize"))
+                    }
+                    Err(reason) => reason,
+                },
+                ModelChoice::ToolCall(name, _) => format!("it called `{}` instead", name),
+            };
+            if reprompts == self.max_reprompts {
+                let reason = format!("The answer did not match the schema: {}", reason);
+                return Err(CompletionError::ResponseError(reason).into());
+            }
+            reprompts += 1;
+            request = format!(
+                "{}\n\nYour previous response could not be used: {}.",
+                question, reason
+            );
+        }
+    }
+
+    /// Checks the numbers of an answer the model wrote from a tool's result against it (and the
+    /// question and earlier `steps`), correcting them if configured to
+    fn verify(
+        &self,
+        prompt: &str,
+        output: String,
+        tool_call: Option<&ToolCall>,
+        steps: &[ToolCall],
+    ) -> (String, Vec<String>) {
+        let Some((tool, result)) =
+            tool_call.and_then(|call| Some((call.name.as_str(), call.output.as_deref()?)))
+        else {
+            return (output, Vec::new());
+        };
+        // Tool results passed on as they are need no checking
+        if self.verification == Verification::Off || output == result {
+            return (output, Vec::new());
+        }
+
+        let mut sources = vec![result, prompt];
+        sources.extend(steps.iter().filter_map(|step| step.output.as_deref()));
+        let mut warnings = Vec::new();
+        let mut check = |text: &str| {
+            let discrepancies = verify::check(text, &sources);
+            let correct = self.verification == Verification::Correct;
+            for discrepancy in &discrepancies {
+                let warning = discrepancy.describe(tool, correct && discrepancy.closest.is_some());
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+            }
+            match correct {
+                true => verify::correct(text, &discrepancies),
+                false => text.to_string(),
+            }
+        };
+
+        let output = match structured::parse(&output) {
+            Ok(mut answer) => {
+                answer.answer = check(&answer.answer);
+                for value in answer.values.values_mut() {
+                    if value.is_number() {
+                        let checked = check(&value.to_string());
+                        *value = serde_json::from_str(&checked).unwrap_or(value.take());
+                    }
+                }
+                serde_json::to_string(&answer).expect("answers should serialize")
+            }
+            Err(_) => check(&output),
+        };
+        (output, warnings)
+    }
+
+    /// Sends a single completion request, adding the tokens it used to `usage`, and returns
+    /// the model's choice with every tool call it requested
+    async fn complete(
+        &self,
+        prompt: &str,
+        request: &str,
+        history: &[Message],
+        tools: Vec<ToolDefinition>,
+        usage: &mut TokenUsage,
+    ) -> Result<(ModelChoice, Vec<(String, serde_json::Value)>), PromptError> {
+        let mut span =
+            trace::client_span("llm.completion").with("llm.model", self.model_name.clone());
+        let response = self
+            .model
+            .completion_request(request)
+            .preamble(self.preamble.clone())
+            .messages(self.fit_history(history))
+            .documents(self.context_documents(prompt).await?)
+            .tools(tools)
+            .temperature_opt(self.params.temperature)
+            .additional_params_opt(self.params.additional_params())
+            .send()
+            .await
+            .inspect_err(|e| span.fail(e))?;
+
+        // Record token usage before the tool call, so it's counted even if the tool fails
+        let response_usage = response.raw_response.token_usage().unwrap_or_default();
+        let llm_tokens = &metrics().llm_tokens;
+        llm_tokens.inc_by(&[&self.model_name, "prompt"], response_usage.prompt_tokens);
+        llm_tokens.inc_by(
+            &[&self.model_name, "completion"],
+            response_usage.completion_tokens,
+        );
+        *usage += response_usage;
+        span.set("llm.prompt_tokens", response_usage.prompt_tokens);
+        span.set("llm.completion_tokens", response_usage.completion_tokens);
+
+        let requested = response.raw_response.tool_calls();
+        Ok((response.choice, requested))
+    }
+
+    /// The chat history with the answers over the result budget (long tool results, in
+    /// practice) summarized
+    fn fit_history(&self, history: &[Message]) -> Vec<Message> {
+        history
+            .iter()
+            .map(|message| match message.role.as_str() {
+                "assistant" => Message {
+                    role: message.role.clone(),
+                    content: summarize::fit(&message.content, self.result_tokens).into_owned(),
+                },
+                _ => message.clone(),
+            })
+            .collect()
+    }
+
+    /// Retrieves the indexed documents most relevant to the prompt
+    async fn context_documents(&self, prompt: &str) -> Result<Vec<Document>, CompletionError> {
+        let Some((samples, index)) = &self.dynamic_context else {
+            return Ok(Vec::new());
+        };
+
+        let documents = index
+            .top_n_from_query(prompt, *samples)
+            .await
+            .map_err(|e| CompletionError::RequestError(Box::new(e)))?
+            .into_iter()
+            .map(|(_, doc)| Document {
+                id: doc.id,
+                text: doc
+                    .document
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| doc.document.to_string()),
+                additional_props: HashMap::new(),
+            })
+            .collect();
+
+        Ok(documents)
+    }
+
+    /// Calls the tool named `name`, with how long the call took and the upstream URLs it
+    /// requested
+    async fn call_tool(
+        &self,
+        name: &str,
+        args: String,
+    ) -> (Result<String, ToolSetError>, Duration, Vec<String>) {
+        let started = Instant::now();
+        let (result, upstream) = audit::collecting(async {
+            let tool = self
+                .tools
+                .iter()
+                .find(|tool| tool.name() == name)
+                .ok_or_else(|| ToolSetError::ToolNotFoundError(name.to_string()))?;
+
+            let span = trace::span("tool.call").with("tool.name", name);
+            Ok(trace::traced(span, tool.call(args)).await?)
+        })
+        .await;
+        (result, started.elapsed(), upstream)
+    }
+}
+
+/// The results of tool calls made at once, each under the call it came from.
+fn aggregate<'a>(calls: impl IntoIterator<Item = &'a ToolCall>) -> String {
+    calls
+        .into_iter()
+        .map(|call| {
+            format!(
+                "`{}` with {}:\n{}",
+                call.name,
+                call.args,
+                call.output.as_deref().unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Describes why a response could not be used, if re-prompting the model may fix it.
+fn malformed_reason(err: &PromptError) -> Option<String> {
+    match err {
+        PromptError::CompletionError(CompletionError::JsonError(e)) => Some(format!(
+            "the tool call arguments were not valid JSON ({})",
+            e
+        )),
+        PromptError::CompletionError(CompletionError::ResponseError(e)) => Some(e.to_lowercase()),
+        PromptError::ToolError(ToolSetError::ToolNotFoundError(name)) => {
+            Some(format!("there is no tool named `{}`", name))
+        }
+        PromptError::ToolError(ToolSetError::ToolCallError(ToolError::JsonError(e))) => Some(
+            format!("the tool call arguments did not match its schema ({})", e),
+        ),
+        _ => None,
+    }
+}
+
+/// A builder for an [`Assistant`], mirroring rig's `AgentBuilder`.
+pub struct AssistantBuilder<M: CompletionModel, I: VectorStoreIndex = NoIndex> {
+    model: M,
+    model_name: String,
+    preamble: Option<String>,
+    tools: Vec<Box<dyn ToolDyn>>,
+    dynamic_context: Option<(usize, I)>,
+    params: GenerationParams,
+    max_reprompts: usize,
+    max_iterations: usize,
+    max_tool_calls: Option<usize>,
+    result_tokens: usize,
+    structured: bool,
+    verification: Verification,
+    dry_run: bool,
+}
+
+impl<M: CompletionModel> AssistantBuilder<M> {
+    /// Add the `sample` documents of `index` most relevant to each prompt to its context
+    pub fn dynamic_context<I: VectorStoreIndex>(
+        self,
+        sample: usize,
+        index: I,
+    ) -> AssistantBuilder<M, I> {
+        AssistantBuilder {
+            model: self.model,
+            model_name: self.model_name,
+            preamble: self.preamble,
+            tools: self.tools,
+            dynamic_context: Some((sample, index)),
+            params: self.params,
+            max_reprompts: self.max_reprompts,
+            max_iterations: self.max_iterations,
+            max_tool_calls: self.max_tool_calls,
+            result_tokens: self.result_tokens,
+            structured: self.structured,
+            verification: self.verification,
+            dry_run: self.dry_run,
+        }
+    }
+}
+
+impl<M: CompletionModel, I: VectorStoreIndex> AssistantBuilder<M, I> {
+    /// Set the preamble (system prompt) of the assistant
+    pub fn preamble(mut self, preamble: &str) -> Self {
+        self.preamble = Some(preamble.to_string());
+        self
+    }
+
+    /// Add a tool the assistant may call
+    pub fn tool(mut self, tool: impl Tool + 'static) -> Self {
+        self.tools.push(Box::new(tool));
+        self
+    }
+
+    /// Add tools built elsewhere, such as by a [`ToolRegistry`](crate::registry::ToolRegistry)
+    pub fn tools(mut self, tools: impl IntoIterator<Item = Box<dyn ToolDyn>>) -> Self {
+        self.tools.extend(tools);
+        self
+    }
+
+    /// Set the sampling parameters of every completion request
+    pub fn params(mut self, params: GenerationParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Set how many times a malformed response is re-prompted before giving up
+    pub fn max_reprompts(mut self, max_reprompts: usize) -> Self {
+        self.max_reprompts = max_reprompts;
+        self
+    }
+
+    /// Set how many tool calls a turn may chain, each seeing the results of those before it;
+    /// the result of the last one allowed is taken as the answer
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations.max(1);
+        self
+    }
+
+    /// Fail turns whose model asks for more than `limit` tool calls in all, rather than letting
+    /// untrusted questions fan out into unbounded indexer traffic. Calls repeating one already
+    /// made in the turn reuse its result, so they aren't counted.
+    pub fn max_tool_calls(mut self, limit: usize) -> Self {
+        self.max_tool_calls = Some(limit);
+        self
+    }
+
+    /// Set how many tokens each earlier answer may take up when resent as chat history; tool
+    /// results over the budget are summarized
+    pub fn result_budget(mut self, tokens: usize) -> Self {
+        self.result_tokens = tokens;
+        self
+    }
+
+    /// Make every answer a [`StructuredAnswer`](structured::StructuredAnswer), as JSON in the
+    /// turn's output
+    pub fn structured(mut self, structured: bool) -> Self {
+        self.structured = structured;
+        self
+    }
+
+    /// Set how the numbers of answers restated from a tool's result are checked against it
+    pub fn verification(mut self, verification: Verification) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    /// Report the tool calls the model requests instead of executing them
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Append to the preamble of the assistant
+    pub fn append_preamble(mut self, preamble: &str) -> Self {
+        self.preamble = Some(format!(
+            "{}\n{}",
+            self.preamble.unwrap_or_default(),
+            preamble
+        ));
+        self
+    }
+
+    pub fn build(self) -> Assistant<M, I> {
+        Assistant {
+            model: self.model,
+            model_name: self.model_name,
+            preamble: self.preamble.unwrap_or_default(),
+            tools: self.tools,
+            dynamic_context: self.dynamic_context,
+            params: self.params,
+            max_reprompts: self.max_reprompts,
+            max_iterations: self.max_iterations,
+            max_tool_calls: self.max_tool_calls,
+            result_tokens: self.result_tokens,
+            structured: self.structured,
+            verification: self.verification,
+            dry_run: self.dry_run,
+        }
+    }
+}
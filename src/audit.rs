@@ -0,0 +1,207 @@
+//! A log of every question answered, one JSON line per turn (`--audit-log`), for deployments
+//! that must account for what the assistant told users and where its data came from: the
+//! question, each tool call with its arguments, latency and the upstream URLs it requested, the
+//! tokens spent and the answer, or the error.
+//!
+//! The log is rotated once it would grow past its size limit: `audit.jsonl` becomes
+//! `audit.jsonl.1`, older files shift up one, and the oldest beyond the number kept is deleted.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::log::{rotated, AuditLog, Record};
+
+thread_local! {
+    /// Where the upstream URLs requested by the future being polled on this thread go, if any
+    static COLLECTING: RefCell<Option<Arc<Mutex<Vec<String>>>>> = const { RefCell::new(None) };
+}
+
+/// Notes that `url` was requested upstream, for the call being collected, if any.
+pub fn upstream(url: &str) {
+    COLLECTING.with(|collecting| {
+        if let Some(urls) = &*collecting.borrow() {
+            let mut urls = urls.lock().unwrap();
+            if !urls.iter().any(|known| known == url) {
+                urls.push(url.to_string());
+            }
+        }
+    });
+}
+
+/// Runs `future`, collecting the upstream URLs requested while it is polled, in order and
+/// without repeats.
+pub async fn collecting<F: Future>(future: F) -> (F::Output, Vec<String>) {
+    let urls = Arc::new(Mutex::new(Vec::new()));
+    let output = Collecting {
+        future: Box::pin(future),
+        urls: urls.clone(),
+    }
+    .await;
+    let urls = std::mem::take(&mut *urls.lock().unwrap());
+    (output, urls)
+}
+
+/// Makes a collection the current one while its future is polled
+struct Collecting<F> {
+    future: Pin<Box<F>>,
+    urls: Arc<Mutex<Vec<String>>>,
+}
+
+impl<F: Future> Future for Collecting<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let urls = self.urls.clone();
+        let outer = COLLECTING.with(|collecting| collecting.replace(Some(urls)));
+        let poll = self.future.as_mut().poll(cx);
+        COLLECTING.with(|collecting| *collecting.borrow_mut() = outer);
+        poll
+    }
+}
+
+/// The log itself, which only the agent writes, so it isn't built for the browser
+#[cfg(not(target_arch = "wasm32"))]
+mod log {
+    use std::ffi::OsString;
+    use std::fs::{self, OpenOptions};
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use crate::accounting::TokenUsage;
+    use crate::assistant::{ToolCall, Turn};
+    use crate::network::Network;
+    use crate::router::Route;
+
+    /// One question and how it was answered, as it is logged.
+    pub struct Record<'a> {
+        pub prompt: &'a str,
+        pub model: &'a str,
+        pub network: Network,
+        /// How long answering took, from the question to the answer or the error.
+        pub elapsed: Duration,
+        /// The route and turn of the answer, or why there is none.
+        pub outcome: Result<(Route, &'a Turn), String>,
+        /// Whether the answer was kept from an earlier question, spending no tokens.
+        pub cached: bool,
+    }
+
+    impl Record<'_> {
+        /// The record as a log line's JSON.
+        pub fn to_json(&self) -> Value {
+            let mut record = json!({
+                "time": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                "prompt": self.prompt,
+                "model": self.model,
+                "network": self.network.name(),
+                "elapsed_ms": self.elapsed.as_millis() as u64,
+                "cached": self.cached,
+            });
+            match &self.outcome {
+                Ok((route, turn)) => {
+                    record["route"] = json!(route.name());
+                    record["tool_calls"] =
+                        turn.steps.iter().chain(&turn.tool_call).map(call).collect();
+                    record["tokens"] = tokens(turn.usage);
+                    record["answer"] = json!(turn.output);
+                    record["warnings"] = json!(turn.warnings);
+                }
+                Err(error) => record["error"] = json!(error),
+            }
+            record
+        }
+    }
+
+    fn call(call: &ToolCall) -> Value {
+        json!({
+            "name": call.name,
+            "args": call.args,
+            "latency_ms": call.latency.map(|latency| latency.as_millis() as u64),
+            "upstream": call.upstream,
+        })
+    }
+
+    fn tokens(usage: TokenUsage) -> Value {
+        json!({
+            "prompt": usage.prompt_tokens,
+            "completion": usage.completion_tokens,
+            "total": usage.total(),
+        })
+    }
+
+    /// An audit log file, rotated by size.
+    pub struct AuditLog {
+        path: PathBuf,
+        max_bytes: u64,
+        keep: usize,
+        /// Held while writing, so concurrent turns neither interleave records nor race rotation
+        writing: Mutex<()>,
+    }
+
+    impl AuditLog {
+        /// Logs to `path`, rotating it before it would grow past `max_bytes` and keeping `keep`
+        /// rotated files.
+        pub fn new(path: impl Into<PathBuf>, max_bytes: u64, keep: usize) -> Self {
+            Self {
+                path: path.into(),
+                max_bytes,
+                keep,
+                writing: Mutex::new(()),
+            }
+        }
+
+        /// Appends `record` as a line, rotating the log first if it's full.
+        pub fn write(&self, record: &Record) -> io::Result<()> {
+            let mut line = record.to_json().to_string();
+            line.push('\n');
+
+            let _writing = self.writing.lock().unwrap();
+            let size = fs::metadata(&self.path).map_or(0, |metadata| metadata.len());
+            // A record larger than the limit still gets a file of its own
+            if size > 0 && size + line.len() as u64 > self.max_bytes {
+                self.rotate()?;
+            }
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?
+                .write_all(line.as_bytes())
+        }
+
+        /// Shifts the rotated files up one, dropping the oldest, and moves the log to the first
+        fn rotate(&self) -> io::Result<()> {
+            if self.keep == 0 {
+                return fs::remove_file(&self.path);
+            }
+            ignore_missing(fs::remove_file(rotated(&self.path, self.keep)))?;
+            for n in (1..self.keep).rev() {
+                ignore_missing(fs::rename(
+                    rotated(&self.path, n),
+                    rotated(&self.path, n + 1),
+                ))?;
+            }
+            fs::rename(&self.path, rotated(&self.path, 1))
+        }
+    }
+
+    /// The `n`th rotated file of the log at `path`, such as `audit.jsonl.1`.
+    pub fn rotated(path: &Path, n: usize) -> PathBuf {
+        let mut name = OsString::from(path.as_os_str());
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn ignore_missing(result: io::Result<()>) -> io::Result<()> {
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use rig::completion::{self, CompletionError, CompletionRequest};
+use rig::providers::openai;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// The Azure OpenAI REST API version requested unless another one is configured.
+pub const DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/// An Azure OpenAI resource serving the assistant's models, from the `[azure]` section of the
+/// config.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AzureConfig {
+    /// The resource's endpoint, e.g. `https://my-resource.openai.azure.com`.
+    pub endpoint: String,
+    /// The deployment answering questions; the `--model` name is used if left out.
+    pub deployment: Option<String>,
+    /// The REST API version, instead of [`DEFAULT_API_VERSION`].
+    pub api_version: Option<String>,
+    /// The resource's key; read from `AZURE_OPENAI_API_KEY` if left out.
+    pub api_key: Option<String>,
+}
+
+impl AzureConfig {
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key
+            .clone()
+            .or_else(|| std::env::var("AZURE_OPENAI_API_KEY").ok())
+    }
+
+    pub fn api_version(&self) -> &str {
+        self.api_version.as_deref().unwrap_or(DEFAULT_API_VERSION)
+    }
+}
+
+/// A client of the deployments of an Azure OpenAI resource.
+///
+/// Azure serves OpenAI's chat completions API, but per deployment rather than per model, with
+/// the API version in the URL and the key in an `api-key` header.
+#[derive(Clone, Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+    api_version: String,
+}
+
+impl Client {
+    pub fn new(endpoint: &str, api_key: &str, api_version: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            api_version: api_version.to_string(),
+        }
+    }
+
+    /// The completion model served by `deployment`.
+    pub fn completion_model(&self, deployment: &str) -> CompletionModel {
+        CompletionModel {
+            client: self.clone(),
+            deployment: deployment.to_string(),
+        }
+    }
+}
+
+/// The model behind an Azure OpenAI deployment.
+#[derive(Clone, Debug)]
+pub struct CompletionModel {
+    client: Client,
+    pub deployment: String,
+}
+
+impl CompletionModel {
+    /// The chat completions endpoint of the deployment
+    fn url(&self) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            self.client.endpoint, self.deployment, self.client.api_version
+        )
+    }
+}
+
+impl completion::CompletionModel for CompletionModel {
+    type Response = openai::CompletionResponse;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
+        let response = self
+            .client
+            .http
+            .post(self.url())
+            .header("api-key", &self.client.api_key)
+            .json(&chat_request(request))
+            .send()
+            .await
+            .map_err(http_error)?;
+
+        // Azure explains rejected requests (such as filtered content) in the body
+        let status = response.status();
+        let body: Value = response.json().await.map_err(http_error)?;
+        if let Some(error) = body.get("error") {
+            let message = error["message"].as_str().unwrap_or("unknown error");
+            return Err(CompletionError::ProviderError(format!(
+                "Azure OpenAI answered with status {}: {}",
+                status, message
+            )));
+        }
+        if !status.is_success() {
+            return Err(CompletionError::ProviderError(format!(
+                "Azure OpenAI answered with status {}",
+                status
+            )));
+        }
+        serde_json::from_value::<openai::CompletionResponse>(body)?.try_into()
+    }
+}
+
+/// rig's HTTP errors are those of an older reqwest, so ours are passed on boxed
+fn http_error(error: reqwest::Error) -> CompletionError {
+    CompletionError::RequestError(Box::new(error))
+}
+
+/// The body of a chat completions request, laid out as rig lays out OpenAI's
+fn chat_request(request: CompletionRequest) -> Value {
+    let mut messages = Vec::new();
+    if let Some(preamble) = request.preamble {
+        messages.push(json!({ "role": "system", "content": preamble }));
+    }
+    for document in &request.documents {
+        let content = serde_json::to_string(document).expect("Document should serialize");
+        messages.push(json!({ "role": "system", "content": content }));
+    }
+    for message in request.chat_history {
+        messages.push(json!({ "role": message.role, "content": message.content }));
+    }
+    messages.push(json!({ "role": "user", "content": request.prompt }));
+
+    let mut body = json!({
+        "messages": messages,
+        "temperature": request.temperature,
+    });
+    if !request.tools.is_empty() {
+        let tools: Vec<openai::ToolDefinition> =
+            request.tools.into_iter().map(Into::into).collect();
+        body["tools"] = json!(tools);
+        body["tool_choice"] = "auto".into();
+    }
+    if let Some(params) = request.additional_params {
+        body = rig::json_utils::merge(body, params);
+    }
+    body
+}
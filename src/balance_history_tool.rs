@@ -0,0 +1,155 @@
+use futures::{StreamExt, TryStreamExt};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::address_tool;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::DEFAULT_CONCURRENCY;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price::UTIA_PER_TIA;
+use crate::rest::RestClient;
+
+/// The most balances sampled by a single call.
+pub const MAX_SAMPLES: u64 = 50;
+
+/// The address and window to trace the balance of.
+#[derive(Deserialize)]
+pub struct BalanceHistoryArgs {
+    /// The `celestia1...` account address.
+    address: String,
+    /// How many balances to sample, ending at the chain head.
+    #[serde(default = "default_samples")]
+    samples: u64,
+    /// The distance between sampled heights.
+    #[serde(default = "default_step")]
+    step: u64,
+    /// The networks to trace the balance on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_samples() -> u64 {
+    10
+}
+
+/// About a day of blocks.
+fn default_step() -> u64 {
+    14_400
+}
+
+/// Traces an address's spendable TIA balance over a window of recent heights.
+///
+/// Balances are read from the REST API, or celestia-app over gRPC, as of each sampled height,
+/// which needs an archive node for heights it would otherwise have pruned.
+pub struct BalanceHistoryTool {
+    blocks: CelestiaSearchTool,
+    rest: RestClient,
+}
+
+impl BalanceHistoryTool {
+    pub fn new(blocks: CelestiaSearchTool, rest: RestClient) -> Self {
+        Self { blocks, rest }
+    }
+
+    async fn trace(&self, args: BalanceHistoryArgs) -> Result<String, CelestiaSearchError> {
+        let address = address_tool::validate_address(&args.address)?;
+        let samples = args.samples.clamp(1, MAX_SAMPLES);
+        let step = args.step.max(1);
+        self.blocks
+            .across(&args.network, |blocks| {
+                balance_series(blocks, &self.rest, address, samples, step)
+            })
+            .await
+    }
+}
+
+/// The balance at `samples` heights `step` apart, ending at the chain head, as JSON rows the
+/// chart renderer can draw
+async fn balance_series(
+    blocks: &CelestiaSearchTool,
+    rest: &RestClient,
+    address: &str,
+    samples: u64,
+    step: u64,
+) -> Result<String, CelestiaSearchError> {
+    let head = blocks.chain_head().await?;
+    let mut heights: Vec<u64> = (0..samples)
+        .map_while(|i| head.checked_sub(i * step).filter(|&height| height > 0))
+        .collect();
+    heights.reverse();
+
+    let rows: Vec<_> = futures::stream::iter(heights)
+        .map(|height| async move {
+            let utia = rest
+                .balance(blocks.network(), address, Some(height))
+                .await?;
+            Ok::<_, CelestiaSearchError>(json!({
+                "height": height,
+                "balance_tia": utia.as_f64() / UTIA_PER_TIA,
+            }))
+        })
+        .buffered(DEFAULT_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    Ok(json!(rows).to_string())
+}
+
+impl Tool for BalanceHistoryTool {
+    const NAME: &'static str = "balance_history";
+
+    type Args = BalanceHistoryArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Trace a Celestia address's spendable TIA balance (excluding delegations) at up \
+                 to {} heights ending at the chain head, as JSON rows of height and balance. \
+                 Blocks come roughly every 6 seconds, so a step of 14400 is about a day and 30 \
+                 samples cover a month.",
+                MAX_SAMPLES
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "address": {
+                        "type": "string",
+                        "description": "The account address",
+                        "examples": ["celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p"],
+                    },
+                    "samples": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_SAMPLES,
+                        "description": "How many balances to sample",
+                        "examples": [10, 30],
+                    },
+                    "step": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "How many blocks apart the samples are",
+                        "examples": [14400],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["address"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.trace(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
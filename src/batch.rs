@@ -0,0 +1,57 @@
+use std::future::Future;
+
+use futures::{stream, StreamExt};
+use serde_json::{json, Value};
+
+use crate::assistant::Turn;
+use crate::format;
+
+/// The questions in a batch file: one per line, skipping blank lines and `#` comments.
+pub fn questions(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The outcome of one question of a batch.
+pub struct Answer {
+    pub prompt: String,
+    pub turn: Result<Turn, String>,
+}
+
+impl Answer {
+    /// The answer as a JSON Lines record: the [`format::to_json`] document, or the prompt and the
+    /// error if it failed.
+    pub fn to_json(&self) -> Value {
+        match &self.turn {
+            Ok(turn) => format::to_json(&self.prompt, turn),
+            Err(e) => json!({ "prompt": self.prompt, "error": e }),
+        }
+    }
+}
+
+/// Answers every question independently with `ask`, keeping up to `concurrency` in flight.
+///
+/// Answers are returned in the order of `questions`, and a failed question doesn't stop the
+/// others.
+pub async fn run<F, Fut>(questions: Vec<String>, concurrency: usize, ask: F) -> Vec<Answer>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Turn, String>>,
+{
+    stream::iter(questions)
+        .map(|prompt| {
+            let turn = ask(prompt.clone());
+            async move {
+                Answer {
+                    prompt,
+                    turn: turn.await,
+                }
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
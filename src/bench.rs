@@ -0,0 +1,215 @@
+//! Latency benchmarks of the tools and the backends they read from, for the `bench` subcommand.
+//!
+//! Each call is repeated, and its first run is reported apart from the rest: it is the one that
+//! finds the caches cold, so comparing it with the later runs shows what caching saves.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use rig::tool::ToolDyn;
+use serde_json::{json, Value};
+
+use crate::analytics;
+use crate::registry::ToolContext;
+
+/// How many times each call is made unless told otherwise.
+pub const DEFAULT_RUNS: usize = 5;
+
+/// The blocks ending at the benchmarked height that range tools are called over.
+pub const WINDOW: u64 = 20;
+
+/// The arguments `tool` is benchmarked with at `height`, or `None` if it needs inputs (an
+/// address, a namespace, a transaction) no height can stand in for.
+pub fn args(tool: &str, height: u64) -> Option<Value> {
+    let range = json!({ "from": height.saturating_sub(WINDOW - 1).max(1), "to": height });
+    let args = match tool {
+        "search_blocks" | "block_rewards" | "who_proposed" | "block_txs" | "fee_histogram"
+        | "block_square" | "estimate_time" => {
+            json!({ "height": height })
+        }
+        "search_anything" => json!({ "query": height.to_string() }),
+        "countdown" => json!({ "height": height + 1000 }),
+        "compare_blocks" => json!({ "from": height.saturating_sub(1).max(1), "to": height }),
+        "gas_percentiles"
+        | "gas_efficiency"
+        | "blob_fees"
+        | "square_size_distribution"
+        | "block_limits" => range,
+        "event_search" => {
+            let mut args = range;
+            args["query"] = json!("message.action");
+            args
+        }
+        "query_block_store" => json!({ "sql": "SELECT COUNT(*) FROM block_stats" }),
+        "staking_yield" | "top_namespaces" => json!({ "days": 7 }),
+        "fill_rate_trend"
+        | "blockspace_forecast"
+        | "chain_params"
+        | "chain_upgrades"
+        | "top_accounts"
+        | "slashing_events"
+        | "mempool"
+        | "sampling_status"
+        | "node_status"
+        | "node_retention"
+        | "capabilities"
+        | "tia_price" => json!({}),
+        _ => return None,
+    };
+    Some(args)
+}
+
+/// The latencies of the runs of one call.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    /// What was called.
+    pub name: String,
+    /// How long each run took, in order, failed ones included.
+    pub runs: Vec<Duration>,
+    /// How many of the runs failed.
+    pub failures: usize,
+    /// The error of the last failed run.
+    pub error: Option<String>,
+}
+
+impl Timings {
+    /// The first run, which found the caches cold.
+    pub fn first(&self) -> Option<Duration> {
+        self.runs.first().copied()
+    }
+
+    /// The `p`th percentile of the runs after the first, or of the first if it's the only one.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        let warm = match self.runs.len() {
+            0 | 1 => &self.runs[..],
+            _ => &self.runs[1..],
+        };
+        let seconds: Vec<f64> = warm.iter().map(Duration::as_secs_f64).collect();
+        analytics::percentile(&seconds, p).map(Duration::from_secs_f64)
+    }
+
+    /// How many times faster the median later run was than the first.
+    pub fn speedup(&self) -> Option<f64> {
+        if self.runs.len() < 2 {
+            return None;
+        }
+        let warm = self.percentile(50.0)?.as_secs_f64();
+        let first = self.first()?.as_secs_f64();
+        (warm > 0.0).then(|| first / warm)
+    }
+}
+
+/// Times `runs` sequential runs of `call`.
+pub async fn time<F, Fut, T, E>(name: &str, runs: usize, mut call: F) -> Timings
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let mut timings = Timings {
+        name: name.to_string(),
+        ..Timings::default()
+    };
+    for _ in 0..runs.max(1) {
+        let started = Instant::now();
+        let result = call().await;
+        timings.runs.push(started.elapsed());
+        if let Err(e) = result {
+            timings.failures += 1;
+            timings.error = Some(e.to_string());
+        }
+    }
+    timings
+}
+
+/// Times a cheap request to each backend of the context: the Celenium API of its network, the
+/// REST API, and the celestia-node if one is configured.
+pub async fn endpoints(ctx: &ToolContext, runs: usize) -> Vec<Timings> {
+    let blocks = ctx.block_tool();
+    let rest = ctx.rest_client();
+    let mut timings = Vec::new();
+    timings.push(time("celenium /head", runs, || blocks.fetch("/head")).await);
+    timings.push(
+        time("rest latest block", runs, || {
+            rest.get_json(
+                ctx.network,
+                "/cosmos/base/tendermint/v1beta1/blocks/latest",
+                None,
+            )
+        })
+        .await,
+    );
+    #[cfg(feature = "node-rpc")]
+    if let Some(node) = &ctx.node {
+        timings.push(
+            time("node header.NetworkHead", runs, || {
+                node.call::<Value>("header.NetworkHead", json!([]))
+            })
+            .await,
+        );
+    }
+    timings
+}
+
+/// Times `runs` calls of each of `tools` at `height`, returning their timings and the names of
+/// the tools without a benchmark.
+pub async fn tools(
+    tools: &[Box<dyn ToolDyn>],
+    height: u64,
+    runs: usize,
+) -> (Vec<Timings>, Vec<String>) {
+    let mut timings = Vec::new();
+    let mut skipped = Vec::new();
+    for tool in tools {
+        let name = tool.name();
+        let Some(args) = args(&name, height) else {
+            skipped.push(name);
+            continue;
+        };
+        let args = args.to_string();
+        timings.push(time(&name, runs, || tool.call(args.clone())).await);
+    }
+    (timings, skipped)
+}
+
+/// Renders timings as a table of the first run, the median and 95th percentile of the rest,
+/// and the speedup between them.
+pub fn report(timings: &[Timings]) -> String {
+    let width = timings
+        .iter()
+        .map(|timing| timing.name.len())
+        .chain(["call".len()])
+        .max()
+        .unwrap_or_default();
+    let mut output = format!(
+        "{:<width$}  {:>10}  {:>10}  {:>10}  {:>7}",
+        "call", "first", "p50", "p95", "speedup"
+    );
+    for timing in timings {
+        let millis = |duration: Option<Duration>| match duration {
+            Some(duration) => format!("{:.1} ms", duration.as_secs_f64() * 1000.0),
+            None => "-".to_string(),
+        };
+        let speedup = match timing.speedup() {
+            Some(speedup) => format!("{:.1}x", speedup),
+            None => "-".to_string(),
+        };
+        output.push_str(&format!(
+            "\n{:<width$}  {:>10}  {:>10}  {:>10}  {:>7}",
+            timing.name,
+            millis(timing.first()),
+            millis(timing.percentile(50.0)),
+            millis(timing.percentile(95.0)),
+            speedup
+        ));
+        if let Some(error) = &timing.error {
+            output.push_str(&format!(
+                " ({} of {} runs failed: {})",
+                timing.failures,
+                timing.runs.len(),
+                error
+            ));
+        }
+    }
+    output
+}
@@ -0,0 +1,233 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::byte_size;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::namespace_tool::validate_namespace;
+use crate::network::{self, Network};
+use crate::pfb_gas;
+use crate::price;
+
+/// The most namespaces a single estimate covers.
+pub const MAX_NAMESPACES: usize = 32;
+
+/// The largest blob estimated, the most a block of the largest square can hold.
+pub const MAX_BLOB_SIZE: u64 = 8 * 1024 * 1024;
+
+/// The gas-price tiers of Celenium's gas tracker, cheapest first.
+const TIERS: [&str; 3] = ["slow", "median", "fast"];
+
+/// The blobs to price.
+#[derive(Deserialize)]
+pub struct BlobCostArgs {
+    /// The namespaces a blob is posted to, in hex.
+    namespaces: Vec<String>,
+    /// The size of each blob, in bytes.
+    blob_size: u64,
+    /// A gas price in utia to price at, instead of the gas tracker's current ones.
+    gas_price: Option<f64>,
+    /// The networks to price on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Estimates what posting a blob to each of several namespaces costs at current gas prices,
+/// batched in one PFB and posted separately.
+pub struct BlobCostTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl BlobCostTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn estimate(&self, args: BlobCostArgs) -> Result<String, CelestiaSearchError> {
+        if args.namespaces.is_empty() || args.namespaces.len() > MAX_NAMESPACES {
+            return Err(CelestiaSearchError::InvalidEstimate(format!(
+                "between 1 and {} namespaces can be priced at once, not {}",
+                MAX_NAMESPACES,
+                args.namespaces.len()
+            )));
+        }
+        if args.blob_size == 0 || args.blob_size > MAX_BLOB_SIZE {
+            return Err(CelestiaSearchError::InvalidEstimate(format!(
+                "blobs are between 1 byte and {}, not {} bytes",
+                byte_size::format(MAX_BLOB_SIZE),
+                args.blob_size
+            )));
+        }
+        if args
+            .gas_price
+            .is_some_and(|price| !price.is_finite() || price <= 0.0)
+        {
+            return Err(CelestiaSearchError::InvalidEstimate(
+                "the gas price must be a positive number of utia".to_string(),
+            ));
+        }
+        let namespaces = args
+            .namespaces
+            .iter()
+            .map(|namespace| validate_namespace(namespace))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let args = &args;
+        let namespaces = &namespaces;
+        self.blocks
+            .across(&args.network, |blocks| async move {
+                let prices = match args.gas_price {
+                    Some(price) => vec![("given", price)],
+                    None => gas_prices(blocks).await?,
+                };
+                let usd_per_tia = blocks.usd_per_tia().await;
+                Ok(report(
+                    namespaces.len(),
+                    args.blob_size,
+                    &prices,
+                    usd_per_tia,
+                ))
+            })
+            .await
+    }
+}
+
+/// The gas tracker's current prices, in utia per gas, by tier
+async fn gas_prices(
+    blocks: &CelestiaSearchTool,
+) -> Result<Vec<(&'static str, f64)>, CelestiaSearchError> {
+    let data = blocks.fetch("/gas/price").await?;
+    TIERS
+        .iter()
+        .map(|&tier| {
+            let price = match &data[tier] {
+                Value::String(price) => price.parse().ok(),
+                price => price.as_f64(),
+            };
+            price
+                .map(|price| (tier, price))
+                .ok_or_else(|| CelestiaSearchError::Deserialization {
+                    url: "/gas/price".to_string(),
+                    reason: format!("missing the `{}` gas price", tier),
+                })
+        })
+        .collect()
+}
+
+fn report(
+    namespaces: usize,
+    blob_size: u64,
+    prices: &[(&str, f64)],
+    usd_per_tia: Option<f64>,
+) -> String {
+    let sizes = vec![blob_size; namespaces];
+    let batched = pfb_gas::estimate_gas(&sizes);
+    let single = pfb_gas::estimate_gas(&[blob_size]);
+    let fee = |gas, price| price::describe(Utia::from(pfb_gas::fee(gas, price)), usd_per_tia);
+
+    let mut output = format!(
+        "Posting a {} blob ({} shares) to {} namespace(s)",
+        byte_size::format(blob_size),
+        pfb_gas::shares_needed(blob_size),
+        namespaces
+    );
+    if namespaces == 1 {
+        output.push_str(&format!(" takes a PFB of {} gas:", single));
+        for (tier, price) in prices {
+            output.push_str(&format!(
+                "\n- at the {} gas price of {} utia: {}",
+                tier,
+                price,
+                fee(single, *price)
+            ));
+        }
+    } else {
+        let separate = single * namespaces as u64;
+        output.push_str(&format!(
+            " takes {} gas batched in one PFB, or {} gas in a separate PFB per namespace ({} \
+             each):",
+            batched, separate, single
+        ));
+        for (tier, price) in prices {
+            output.push_str(&format!(
+                "\n- at the {} gas price of {} utia: {} batched, {} separately",
+                tier,
+                price,
+                fee(batched, *price),
+                fee(separate, *price)
+            ));
+        }
+    }
+    output.push_str(&format!(
+        "\nThis uses celestia-app's default gas parameters ({} gas per blob byte, {} per \
+         transaction byte and {} per PFB), which governance can change; wallets usually add a \
+         margin on top of the estimated gas.",
+        pfb_gas::GAS_PER_BLOB_BYTE,
+        pfb_gas::TX_SIZE_COST_PER_BYTE,
+        pfb_gas::PFB_GAS_FIXED_COST
+    ));
+    output
+}
+
+impl Tool for BlobCostTool {
+    const NAME: &'static str = "blob_cost";
+
+    type Args = BlobCostArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Estimate what posting a blob of a given size to each of several \
+                          namespaces would cost, from the PFB gas formula and the gas \
+                          tracker's current slow, median and fast prices, batched in one PFB \
+                          and posted separately. Use it for rollup teams planning posting \
+                          costs; use blob_fees for what was actually paid."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "namespaces": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "minItems": 1,
+                        "maxItems": MAX_NAMESPACES,
+                        "description": "The namespaces a blob is posted to, in hex (28 or 29 \
+                                        bytes)",
+                        "examples": [["00000000000000000000000000000000000000000000000000736f76"]],
+                    },
+                    "blob_size": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_BLOB_SIZE,
+                        "description": "The size of the blob posted to each namespace, in bytes",
+                        "examples": [102400],
+                    },
+                    "gas_price": {
+                        "type": "number",
+                        "exclusiveMinimum": 0,
+                        "description": "A gas price in utia to price at, instead of the current \
+                                        ones",
+                        "examples": [0.004],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["namespaces", "blob_size"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.estimate(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,189 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::analytics::{self, correlation, Trend};
+use crate::byte_size;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The maximum number of blocks in a single range.
+pub const MAX_RANGE: u64 = 500;
+
+/// Changes in the price per byte within this share of its mean count as flat.
+const FLAT_TOLERANCE: f64 = 0.05;
+
+const BYTES_PER_MIB: f64 = 1024.0 * 1024.0;
+
+/// The block range to analyze the blob fee market over.
+#[derive(Deserialize)]
+pub struct BlobFeesArgs {
+    /// The first height of the range.
+    from: u64,
+    /// The last height of the range (inclusive).
+    to: u64,
+    /// The networks to analyze, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Relates the blob data posted over a block range to the fees paid, for the price of DA.
+///
+/// A block's fees include those of its transactions without blobs, so the prices are upper
+/// bounds; the fitted marginal price per byte separates out what doesn't grow with blob size.
+pub struct BlobFeesTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl BlobFeesTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn analyze(&self, args: BlobFeesArgs) -> Result<String, CelestiaSearchError> {
+        if args.from == 0 || args.from > args.to {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {}",
+                args.from, args.to
+            )));
+        }
+        if args.to - args.from >= MAX_RANGE {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {} spans more than {} blocks",
+                args.from, args.to, MAX_RANGE
+            )));
+        }
+
+        self.blocks
+            .across(&args.network, |blocks| report(blocks, args.from, args.to))
+            .await
+    }
+}
+
+/// Prices the blob data of blocks `from..=to` by the fees paid alongside it
+async fn report(
+    blocks: &CelestiaSearchTool,
+    from: u64,
+    to: u64,
+) -> Result<String, CelestiaSearchError> {
+    let range = fetcher::fetch_range_partial(blocks, from..=to, DEFAULT_CONCURRENCY).await?;
+    let rows = &range.rows;
+    let rows: Vec<_> = rows.iter().filter(|(_, s)| s.blobs_size > 0).collect();
+    if rows.is_empty() {
+        return Ok(range.annotate(format!(
+            "No blobs were posted in blocks {} to {}.",
+            from, to
+        )));
+    }
+
+    let sizes: Vec<f64> = rows.iter().map(|(_, s)| s.blobs_size as f64).collect();
+    let fees: Vec<f64> = rows.iter().map(|(_, s)| s.fee.as_f64()).collect();
+    let prices: Vec<f64> = fees
+        .iter()
+        .zip(&sizes)
+        .map(|(fee, size)| fee / size)
+        .collect();
+    let bytes: f64 = sizes.iter().sum();
+    let total_fees: f64 = fees.iter().sum();
+    let blobs: u64 = rows.iter().map(|(_, s)| s.blobs_count).sum();
+    let price = total_fees / bytes;
+
+    let mut output = format!(
+        "Blob fees over blocks {} to {} ({} blocks with blobs):\n\
+         - blob data: {} in {} blob(s)\n\
+         - fees paid in those blocks: {}\n\
+         - effective price: {:.4} utia per byte ({:.4} TIA per MiB)",
+        from,
+        to,
+        rows.len(),
+        byte_size::format(bytes as u64),
+        blobs,
+        price::describe_utia(&format!("{:.0}", total_fees), None),
+        price,
+        price * BYTES_PER_MIB / 1_000_000.0
+    );
+    if let Some((intercept, slope)) = analytics::fit(&sizes, &fees) {
+        output.push_str(&format!(
+            "\n- marginal price: {:.4} utia per byte, on top of {:.0} utia per block that \
+             doesn't grow with blob size",
+            slope, intercept
+        ));
+    }
+    if let Some(r) = correlation(&sizes, &fees) {
+        output.push_str(&format!(
+            "\n- correlation between blob size and fees: {:.2}",
+            r
+        ));
+    }
+    if let Some(summary) = analytics::summarize(&prices).filter(|_| prices.len() > 1) {
+        let trend = match summary.trend(summary.mean * FLAT_TOLERANCE) {
+            Trend::Flat => "flat".to_string(),
+            trend => format!(
+                "{} ({:+.2}% across the range)",
+                trend.name(),
+                summary.fitted_change / summary.mean * 100.0
+            ),
+        };
+        output.push_str(&format!(
+            "\n- price per byte by block: {:.4} to {:.4} utia, trend {}",
+            summary.min, summary.max, trend
+        ));
+    }
+
+    Ok(range.annotate(output))
+}
+
+impl Tool for BlobFeesTool {
+    const NAME: &'static str = "blob_fees";
+
+    type Args = BlobFeesArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Analyze the blob fee market over a range of Celestia blocks: the blob data \
+                 posted, the fees paid, the effective price of data availability per byte and \
+                 per MiB, the marginal price per byte fitted from blob size against fees, their \
+                 correlation, and the trend of the price. Use it to budget blob posting costs. \
+                 Ranges span at most {} blocks.",
+                MAX_RANGE
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "First height of the range",
+                        "examples": [2000000],
+                    },
+                    "to": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Last height of the range (inclusive)",
+                        "examples": [2000099],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.analyze(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::byte_size;
+use crate::celestia_search_tool::{
+    CelestiaResponseFields, CelestiaSearchError, CelestiaSearchTool,
+};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::pfb_gas::CONTINUATION_SHARE_CONTENT;
+
+/// The limit eras bundled with the assistant.
+const LIMITS: &str = include_str!("../data/block_limits.toml");
+
+/// The maximum number of blocks in a single range.
+pub const MAX_RANGE: u64 = 500;
+
+/// A span of heights over which the block size limits held, from the bundled dataset.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Era {
+    pub name: String,
+    pub summary: String,
+    /// The widest data square, in shares per side (the blob module's `gov_max_square_size`).
+    pub max_square_size: u64,
+    /// The largest block, in bytes (the consensus `max_bytes`).
+    pub max_bytes: u64,
+    /// The height the era started at on each network.
+    #[serde(default)]
+    pub heights: BTreeMap<Network, u64>,
+}
+
+#[derive(Deserialize)]
+struct Dataset {
+    era: Vec<Era>,
+}
+
+/// The bundled limit eras.
+pub fn eras() -> &'static [Era] {
+    static ERAS_PARSED: OnceLock<Vec<Era>> = OnceLock::new();
+    ERAS_PARSED.get_or_init(|| {
+        toml::from_str::<Dataset>(LIMITS)
+            .expect("the bundled block limits are valid")
+            .era
+    })
+}
+
+/// The eras recorded on `network`, with the heights they started at, earliest first.
+pub fn eras_on(network: Network) -> Vec<(u64, &'static Era)> {
+    let mut eras: Vec<(u64, &Era)> = eras()
+        .iter()
+        .filter_map(|era| era.heights.get(&network).map(|&height| (height, era)))
+        .collect();
+    eras.sort_by_key(|(height, _)| *height);
+    eras
+}
+
+/// The chain's current `gov_max_square_size`, from Celenium's constants
+fn gov_max_square_size(constants: &Value) -> Option<u64> {
+    match &constants["module"]["blob"]["gov_max_square_size"] {
+        Value::String(size) => size.parse().ok(),
+        size => size.as_u64(),
+    }
+}
+
+/// The limits recorded for a network, to measure blocks against the ceiling of their height.
+pub struct Ceilings {
+    eras: Vec<(u64, &'static Era)>,
+    /// The start of the latest recorded era, if the chain has since left it for limits the
+    /// record doesn't hold
+    stale_from: Option<u64>,
+}
+
+impl Ceilings {
+    /// The limits recorded for the network of `blocks`, checked against its current
+    /// `gov_max_square_size` if the indexer reports it.
+    pub async fn fetch(blocks: &CelestiaSearchTool) -> Self {
+        let eras = eras_on(blocks.network());
+        let current = match blocks.fetch("/constants").await {
+            Ok(constants) => gov_max_square_size(&constants),
+            Err(_) => None,
+        };
+        let stale_from = match (current, eras.last()) {
+            (Some(size), Some((height, era))) if size != era.max_square_size => Some(*height),
+            _ => None,
+        };
+        Self { eras, stale_from }
+    }
+
+    /// The era in force at `height`, unless none is recorded that early or the chain has left
+    /// the latest era at an unrecorded height since.
+    pub fn at(&self, height: u64) -> Option<&'static Era> {
+        if self.stale_from.is_some_and(|from| height >= from) {
+            return None;
+        }
+        self.eras
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= height)
+            .map(|(_, era)| *era)
+    }
+}
+
+/// Splits `from..=to` at the heights where one of `eras` (by start height, earliest first)
+/// starts, pairing each part with the era in force over it: none before the first.
+pub fn split<'a>(eras: &[(u64, &'a Era)], from: u64, to: u64) -> Vec<(u64, u64, Option<&'a Era>)> {
+    let mut current = eras
+        .iter()
+        .rev()
+        .find(|(height, _)| *height <= from)
+        .map(|(_, era)| *era);
+    let mut start = from;
+    let mut parts = Vec::new();
+    for &(height, era) in eras
+        .iter()
+        .filter(|(height, _)| *height > from && *height <= to)
+    {
+        parts.push((start, height - 1, current));
+        start = height;
+        current = Some(era);
+    }
+    parts.push((start, to, current));
+    parts
+}
+
+/// The block range to put against its limits.
+#[derive(Deserialize)]
+pub struct BlockLimitsArgs {
+    /// The first height of the range.
+    from: u64,
+    /// The last height of the range (inclusive).
+    to: u64,
+    /// The networks to analyze, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Reports the block size limits in force over a block range, era by era, with how much of
+/// them the blocks used.
+pub struct BlockLimitsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl BlockLimitsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn analyze(&self, args: BlockLimitsArgs) -> Result<String, CelestiaSearchError> {
+        if args.from == 0 || args.from > args.to {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {}",
+                args.from, args.to
+            )));
+        }
+        if args.to - args.from >= MAX_RANGE {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {} spans more than {} blocks",
+                args.from, args.to, MAX_RANGE
+            )));
+        }
+
+        self.blocks
+            .across(&args.network, |blocks| {
+                utilization(blocks, args.from, args.to)
+            })
+            .await
+    }
+}
+
+/// How much of the limits in force the blocks `from..=to` used, era by era
+async fn utilization(
+    blocks: &CelestiaSearchTool,
+    from: u64,
+    to: u64,
+) -> Result<String, CelestiaSearchError> {
+    let (range, constants) = futures::try_join!(
+        fetcher::fetch_range_partial(blocks, from..=to, DEFAULT_CONCURRENCY),
+        blocks.fetch("/constants"),
+    )?;
+    let current = gov_max_square_size(&constants);
+
+    let network = blocks.network();
+    let recorded = eras_on(network);
+    // Without a recorded history, the chain's current square size is all there is to go by
+    let unrecorded = current.map(|size| Era {
+        name: "current".to_string(),
+        summary: "The chain's limits now.".to_string(),
+        max_square_size: size,
+        max_bytes: size * size * CONTINUATION_SHARE_CONTENT,
+        heights: BTreeMap::new(),
+    });
+    let eras = match (&unrecorded, recorded.is_empty()) {
+        (Some(era), true) => vec![(1, era)],
+        _ => recorded.clone(),
+    };
+
+    let mut output = format!("Block size limits over blocks {} to {}:", from, to);
+    for (start, end, era) in split(&eras, from, to) {
+        let rows: Vec<&(u64, CelestiaResponseFields)> = range
+            .rows
+            .iter()
+            .filter(|(height, _)| (start..=end).contains(height))
+            .collect();
+        output.push_str(&format!("\n- Blocks {} to {}", start, end));
+        match era {
+            Some(era) => output.push_str(&format!(
+                ", {} era (squares up to {}x{}, blocks up to {}): {}",
+                era.name,
+                era.max_square_size,
+                era.max_square_size,
+                byte_size::format(era.max_bytes),
+                describe(&rows, era)
+            )),
+            None => output.push_str(": no limits are recorded this early."),
+        }
+    }
+
+    match (
+        current,
+        recorded.last(),
+        unrecorded.is_some() && recorded.is_empty(),
+    ) {
+        (_, _, true) => output.push_str(&format!(
+            "\nNo limit history is recorded for {}, so its current `gov_max_square_size` is \
+             applied to the whole range, with the maximum block size celestia-app derives from \
+             it.",
+            network
+        )),
+        (Some(size), Some((height, era)), _) if size != era.max_square_size => {
+            output.push_str(&format!(
+                "\nThe chain's current `gov_max_square_size` is {}, but the latest recorded era \
+                 ({}, from height {}) has {}: it changed at a height the bundled history doesn't \
+                 record yet, so limits after height {} may be off.",
+                size, era.name, height, era.max_square_size, height
+            ))
+        }
+        (None, None, _) => output.push_str(&format!(
+            "\nNo limit history is recorded for {}, and the chain doesn't report its \
+             `gov_max_square_size`.",
+            network
+        )),
+        _ => {}
+    }
+
+    Ok(range.annotate(output))
+}
+
+/// How much of `era`'s limits the blocks of `rows` used
+fn describe(rows: &[&(u64, CelestiaResponseFields)], era: &Era) -> String {
+    if rows.is_empty() {
+        return "no blocks fetched.".to_string();
+    }
+    let n = rows.len() as f64;
+    let bytes: Vec<f64> = rows
+        .iter()
+        .map(|(_, stats)| stats.bytes_in_block as f64 / era.max_bytes as f64)
+        .collect();
+    let squares: Vec<f64> = rows
+        .iter()
+        .map(|(_, stats)| (stats.square_size as f64 / era.max_square_size as f64).powi(2))
+        .collect();
+    let fill_rate: f64 = rows.iter().map(|(_, stats)| stats.fill_rate).sum::<f64>() / n;
+    let largest = rows
+        .iter()
+        .map(|(_, stats)| stats.square_size)
+        .max()
+        .unwrap_or(0);
+    let peak = bytes.iter().copied().fold(0.0, f64::max);
+
+    let mut output =
+        format!(
+        "{} block(s) averaging {} ({:.2}% of the limit, peaking at {:.2}%), with squares up to \
+         {}x{} using {:.2}% of the widest square's shares on average. The indexer's fill rate \
+         averaged {:.2}%.",
+        rows.len(),
+        byte_size::format(
+            (rows.iter().map(|(_, stats)| stats.bytes_in_block).sum::<u64>() as f64 / n).round()
+                as u64
+        ),
+        bytes.iter().sum::<f64>() / n * 100.0,
+        peak * 100.0,
+        largest,
+        largest,
+        squares.iter().sum::<f64>() / n * 100.0,
+        fill_rate * 100.0
+    );
+    let over = rows
+        .iter()
+        .filter(|(_, stats)| {
+            stats.square_size > era.max_square_size || stats.bytes_in_block > era.max_bytes
+        })
+        .count();
+    if over > 0 {
+        output.push_str(&format!(
+            " {} block(s) exceed these limits, so they were raised earlier than recorded.",
+            over
+        ));
+    }
+    output
+}
+
+impl Tool for BlockLimitsTool {
+    const NAME: &'static str = "block_limits";
+
+    type Args = BlockLimitsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Report the block size limits (maximum square size and maximum block bytes) in \
+                 force over a range of Celestia blocks, split where governance or an upgrade \
+                 changed them, and how much of each era's limits the blocks used. Use it for \
+                 utilization over old ranges, which is measured against the ceiling of their own \
+                 era rather than today's. Ranges span at most {} blocks.",
+                MAX_RANGE
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "First height of the range",
+                        "examples": [2000000],
+                    },
+                    "to": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Last height of the range (inclusive)",
+                        "examples": [2000499],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.analyze(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
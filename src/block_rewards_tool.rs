@@ -0,0 +1,156 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The block whose rewards to interpret.
+#[derive(Deserialize)]
+pub struct BlockRewardsArgs {
+    /// The height of the block.
+    height: u64,
+    /// The networks to look the block up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Interprets a block's rewards and commissions, and estimates what its proposer earned.
+pub struct BlockRewardsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl BlockRewardsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn interpret(&self, args: BlockRewardsArgs) -> Result<String, CelestiaSearchError> {
+        self.blocks
+            .across(&args.network, |blocks| interpret(blocks, args.height))
+            .await
+    }
+}
+
+/// Explains how block `height`'s rewards were split, and the proposer's part of them
+async fn interpret(
+    blocks: &CelestiaSearchTool,
+    height: u64,
+) -> Result<String, CelestiaSearchError> {
+    let (stats, proposer, head) = futures::try_join!(
+        blocks.fetch_stats(height),
+        blocks.fetch_proposer(height),
+        blocks.fetch("/head"),
+    )?;
+
+    let delegators = stats.rewards - stats.commissions;
+    let mut output = format!(
+        "Block {} distributed {} in staking rewards to the bonded validators, by stake: {} kept \
+         by the validators as commission, and {} for their delegators.",
+        height,
+        price::describe(stats.rewards, None),
+        price::describe(stats.commissions, None),
+        price::describe(delegators, None)
+    );
+
+    let proposer = proposer.unwrap_or_default();
+    let Some(name) = proposer.name() else {
+        output.push_str(" Celenium doesn't report the block's proposer.");
+        return Ok(output);
+    };
+    output.push_str(&format!(
+        " The block was proposed by {}, which earns no bonus for it, only its share of the \
+         rewards by stake",
+        name
+    ));
+
+    let info = match proposer.id {
+        Some(id) => blocks.fetch(&format!("/validator/{}", id)).await?,
+        None => Value::Null,
+    };
+    let (Some(stake), Some(total_stake)) = (number(&info["stake"]), number(&head["total_stake"]))
+    else {
+        output.push('.');
+        return Ok(output);
+    };
+    if total_stake <= 0.0 {
+        output.push('.');
+        return Ok(output);
+    }
+
+    let share = stake / total_stake;
+    let earned = stats.rewards.as_f64() * share;
+    output.push_str(&format!(
+        ": with {:.2}% of the stake, about {}",
+        share * 100.0,
+        utia(earned)
+    ));
+    match number(&info["rate"]) {
+        Some(rate) => output.push_str(&format!(
+            ", of which its {:.2}% commission is about {} and the rest goes to its delegators.",
+            rate * 100.0,
+            utia(earned * rate)
+        )),
+        None => output.push('.'),
+    }
+
+    Ok(output)
+}
+
+/// Numbers, and strings holding numbers (as Celenium reports stakes and rates)
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// An estimated amount, rounded to whole utia
+fn utia(amount: f64) -> String {
+    price::describe_utia(&format!("{:.0}", amount), None)
+}
+
+impl Tool for BlockRewardsTool {
+    const NAME: &'static str = "block_rewards";
+
+    type Args = BlockRewardsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Explain the staking rewards and commissions a block paid out, in utia \
+                          and TIA, how they split between validators and delegators, and \
+                          estimate what the block's proposer earned from it."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The block height",
+                        "examples": [2000000],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["height"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.interpret(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
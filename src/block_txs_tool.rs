@@ -0,0 +1,236 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::cursor;
+use crate::enums::Enums;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The most transactions listed by a single call.
+pub const MAX_LIMIT: u64 = 100;
+
+/// The page of a block's transactions to list.
+#[derive(Deserialize)]
+pub struct BlockTxsArgs {
+    /// The height of the block.
+    height: u64,
+    /// Only list transactions with a message of this type, e.g. `MsgPayForBlobs`.
+    message_type: Option<String>,
+    /// How many transactions to list.
+    #[serde(default = "default_limit")]
+    limit: u64,
+    /// How many of the block's first transactions to skip, to page through the rest.
+    #[serde(default)]
+    offset: u64,
+    /// The cursor ending the previous page, continuing from there instead of `offset`.
+    cursor: Option<String>,
+    /// The networks to look the block up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_limit() -> u64 {
+    20
+}
+
+/// Lists the transactions of a block in block order, optionally of a single message type.
+pub struct BlockTxsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl BlockTxsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn list(&self, args: BlockTxsArgs) -> Result<String, CelestiaSearchError> {
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+        let message_type = args.message_type.as_deref().map(str::trim);
+        if let (Some(enums), Some(message_type)) = (self.blocks.enums(), message_type) {
+            Enums::check("message type", &enums.message_types, message_type)?;
+        }
+        let next = args.cursor.as_deref();
+        self.blocks
+            .across(&args.network, |blocks| async move {
+                let offset = match next {
+                    Some(next) => cursor::decode(
+                        next,
+                        BlockTxsTool::NAME,
+                        &scope(blocks, args.height, message_type),
+                    )?,
+                    None => args.offset,
+                };
+                list_txs(blocks, args.height, limit, offset, message_type).await
+            })
+            .await
+    }
+}
+
+/// What a cursor of the block's transactions continues listing
+fn scope(blocks: &CelestiaSearchTool, height: u64, message_type: Option<&str>) -> String {
+    format!(
+        "{}/{}/{}",
+        blocks.network(),
+        height,
+        message_type.unwrap_or_default()
+    )
+}
+
+/// Summarizes a page of the block's transactions, then lists them with their fees and signers.
+async fn list_txs(
+    blocks: &CelestiaSearchTool,
+    height: u64,
+    limit: u64,
+    offset: u64,
+    message_type: Option<&str>,
+) -> Result<String, CelestiaSearchError> {
+    let mut endpoint = format!(
+        "/tx?height={}&limit={}&offset={}&sort=asc",
+        height, limit, offset
+    );
+    if let Some(message_type) = message_type {
+        endpoint.push_str(&format!("&msg_type={}", message_type));
+    }
+    let txs = blocks.fetch(&endpoint).await?;
+    let txs: Vec<&Value> = txs.as_array().into_iter().flatten().collect();
+    let filter = message_type
+        .map(|message_type| format!(" with a {} message", message_type))
+        .unwrap_or_default();
+    if txs.is_empty() {
+        return Ok(match offset {
+            0 => format!("Block {} has no transactions{}.", height, filter),
+            _ => format!(
+                "Block {} has no transactions{} beyond the first {}.",
+                height, filter, offset
+            ),
+        });
+    }
+
+    let failed = txs.iter().filter(|tx| tx["status"] != "success").count();
+    let fees: Utia = txs
+        .iter()
+        .filter_map(|tx| Utia::from_value(&tx["fee"]))
+        .sum();
+    let mut output = format!(
+        "Transactions {} to {} of block {}{}, in block order ({} succeeded, {} failed), paying {} \
+         in fees:",
+        offset + 1,
+        offset + txs.len() as u64,
+        height,
+        filter,
+        txs.len() - failed,
+        failed,
+        price::describe(fees, None)
+    );
+    for tx in &txs {
+        let kinds: Vec<&str> = tx["message_types"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        // Celenium lists signers as addresses, or as objects holding them
+        let signers: Vec<&str> = tx["signers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|signer| signer.as_str().or(signer["hash"].as_str()))
+            .collect();
+        output.push_str(&format!(
+            "\n- {}: fee {} utia, signed by {}, {}, hash {}",
+            kinds.join(" + "),
+            tx["fee"].as_str().unwrap_or("0"),
+            match signers.is_empty() {
+                true => "unknown signers".to_string(),
+                false => signers.join(", "),
+            },
+            tx["status"].as_str().unwrap_or("unknown status"),
+            tx["hash"].as_str().unwrap_or("unknown")
+        ));
+    }
+    if txs.len() as u64 == limit {
+        let next = cursor::encode(
+            BlockTxsTool::NAME,
+            &scope(blocks, height, message_type),
+            offset + limit,
+        );
+        output.push_str(&format!(
+            "\nMore transactions may follow; call {} with cursor `{}` for the next page.",
+            BlockTxsTool::NAME,
+            next
+        ));
+    }
+
+    Ok(output)
+}
+
+impl Tool for BlockTxsTool {
+    const NAME: &'static str = "block_txs";
+
+    type Args = BlockTxsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "List the transactions of a Celestia block in block order, optionally only those \
+                 with a message of one type, with each one's message types, fee, signers and \
+                 status. Returns up to {} at a time; a full page ends with a cursor to pass back \
+                 for the next one.",
+                MAX_LIMIT
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The block height",
+                        "examples": [2000000],
+                    },
+                    "message_type": {
+                        "type": "string",
+                        "description": "Only list transactions with a message of this type",
+                        "examples": ["MsgPayForBlobs", "MsgSend", "MsgDelegate"],
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": "How many transactions to list",
+                        "examples": [20],
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "How many of the block's first transactions to skip",
+                        "examples": [0, 20],
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "The cursor ending the previous page, to list the next one",
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["height"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.list(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
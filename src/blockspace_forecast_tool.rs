@@ -0,0 +1,221 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::analytics;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::estimate_time_tool::span;
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The maximum number of blocks sampled by a single call.
+pub const MAX_SAMPLES: u64 = 200;
+
+/// The block time assumed when the sampled blocks don't report theirs.
+const DEFAULT_BLOCK_SECONDS: f64 = 6.0;
+
+/// Runways longer than this are only reported as exceeding it, being no forecast at all.
+const MAX_RUNWAY_DAYS: f64 = 36525.0;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// The window of recent blocks whose trend to extrapolate.
+#[derive(Deserialize)]
+pub struct BlockspaceForecastArgs {
+    /// How many blocks to sample, ending at the chain head.
+    #[serde(default = "default_samples")]
+    samples: u64,
+    /// The distance between sampled blocks, to cover longer periods.
+    #[serde(default = "default_step")]
+    step: u64,
+    /// The networks to forecast, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_samples() -> u64 {
+    100
+}
+
+/// About a week of blocks with the default samples
+fn default_step() -> u64 {
+    1000
+}
+
+/// Extrapolates the recent fill-rate and blob-size trends to when blocks would fill the
+/// maximum square size.
+pub struct BlockspaceForecastTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl BlockspaceForecastTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn forecast(&self, args: BlockspaceForecastArgs) -> Result<String, CelestiaSearchError> {
+        let samples = args.samples.clamp(2, MAX_SAMPLES);
+        let step = args.step.max(1);
+
+        self.blocks
+            .across(&args.network, |blocks| forecast(blocks, samples, step))
+            .await
+    }
+}
+
+/// Forecasts from `samples` blocks `step` apart, ending at the chain head
+async fn forecast(
+    blocks: &CelestiaSearchTool,
+    samples: u64,
+    step: u64,
+) -> Result<String, CelestiaSearchError> {
+    let head = blocks.chain_head().await?;
+    let mut heights: Vec<u64> = (0..samples)
+        .map_while(|i| head.checked_sub(i * step).filter(|&height| height > 0))
+        .collect();
+    heights.reverse();
+
+    let range =
+        fetcher::fetch_range_partial(blocks, heights.iter().copied(), DEFAULT_CONCURRENCY).await?;
+    let rows = &range.rows;
+    let xs: Vec<f64> = rows.iter().map(|(height, _)| *height as f64).collect();
+    let fill_rates: Vec<f64> = rows.iter().map(|(_, stats)| stats.fill_rate).collect();
+    let Some((intercept, slope)) = analytics::fit(&xs, &fill_rates) else {
+        return Ok("Too few blocks to forecast from; sample at least two.".to_string());
+    };
+    let (first, last) = (rows[0].0, rows[rows.len() - 1].0);
+
+    let block_times: Vec<f64> = rows
+        .iter()
+        .map(|(_, stats)| stats.block_time as f64 / 1000.0)
+        .filter(|&seconds| seconds > 0.0)
+        .collect();
+    let block_seconds = analytics::summarize(&block_times)
+        .map(|summary| summary.mean)
+        .unwrap_or(DEFAULT_BLOCK_SECONDS);
+    let blocks_per_day = SECONDS_PER_DAY / block_seconds;
+
+    let now = (intercept + slope * last as f64).max(0.0);
+    let mean = fill_rates.iter().sum::<f64>() / fill_rates.len() as f64;
+    let mut output = format!(
+        "Blockspace forecast from {} blocks from {} to {} (every {} block(s)), about {} of \
+         history: the fill rate is {:.2}% of the maximum square size on its fitted trend \
+         (average {:.2}%), changing by {:+.3} percentage points a day.",
+        rows.len(),
+        first,
+        last,
+        step,
+        span((last - first) as f64 * block_seconds),
+        now * 100.0,
+        mean * 100.0,
+        slope * blocks_per_day * 100.0
+    );
+
+    // Blob bytes compound, so their growth is fitted to their logarithm
+    let sized: Vec<(f64, f64)> = rows
+        .iter()
+        .filter(|(_, stats)| stats.blobs_size > 0)
+        .map(|(height, stats)| (*height as f64, (stats.blobs_size as f64).ln()))
+        .collect();
+    let (heights, logs): (Vec<f64>, Vec<f64>) = sized.into_iter().unzip();
+    let growth = analytics::fit(&heights, &logs).map(|(_, rate)| rate);
+    if let Some(rate) = growth {
+        output.push_str(&format!(
+            " Blob bytes per block change by {:+.2}% a day.",
+            ((rate * blocks_per_day).exp() - 1.0) * 100.0
+        ));
+    }
+
+    if now >= 1.0 {
+        output.push_str(" Blocks already fill the maximum square size.");
+    } else if slope <= 0.0 {
+        output.push_str(
+            " The fill rate isn't rising, so at this trend blocks never fill the maximum square \
+             size.",
+        );
+    } else {
+        let blocks_left = (1.0 - now) / slope;
+        output.push_str(&format!(
+            " At the linear fill-rate trend, blocks would fill it in {}",
+            runway(blocks_left / blocks_per_day)
+        ));
+        if blocks_left / blocks_per_day <= MAX_RUNWAY_DAYS {
+            output.push_str(&format!(
+                ", around block {}",
+                last + blocks_left.round() as u64
+            ));
+        }
+        output.push('.');
+    }
+    // Blob bytes would have to grow by the inverse of the fill rate for blocks to be full
+    if let Some(rate) = growth.filter(|&rate| rate > 0.0 && now > 0.0 && now < 1.0) {
+        output.push_str(&format!(
+            " At the compounding growth of blob bytes, in {}.",
+            runway((1.0 / now).ln() / rate / blocks_per_day)
+        ));
+    }
+    output.push_str(
+        " This extrapolates the sampled trend only; raising the maximum square size would extend \
+         the runway.",
+    );
+
+    Ok(range.annotate(output))
+}
+
+/// A forecast number of days, as a span of time
+fn runway(days: f64) -> String {
+    if days > MAX_RUNWAY_DAYS {
+        return "more than 100 years".to_string();
+    }
+    format!("about {}", span(days * SECONDS_PER_DAY))
+}
+
+impl Tool for BlockspaceForecastTool {
+    const NAME: &'static str = "blockspace_forecast";
+
+    type Args = BlockspaceForecastArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Forecast when blockspace demand would saturate the maximum square \
+                size, extrapolating the fill-rate trend and the growth of blob bytes over a \
+                window of recent blocks ending at the chain head. Blocks come roughly every 6 \
+                seconds, so the default 100 samples with a step of 1000 cover about a week."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "samples": {
+                        "type": "integer",
+                        "minimum": 2,
+                        "maximum": MAX_SAMPLES,
+                        "description": "How many blocks to sample",
+                        "examples": [100],
+                    },
+                    "step": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Heights between consecutive samples",
+                        "examples": [1000, 10000],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.forecast(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,70 @@
+//! Human-readable sizes for the byte counts Celenium reports, such as `blobs_size` and
+//! `bytes_in_block`, in the units chosen with `--byte-units`.
+
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+/// The units sizes are shown in, once set at startup.
+static UNITS: OnceLock<ByteUnits> = OnceLock::new();
+
+/// The units byte counts are shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteUnits {
+    /// Powers of 1024: KiB, MiB and GiB
+    #[default]
+    Binary,
+    /// Powers of 1000: kB, MB and GB
+    Decimal,
+}
+
+impl ByteUnits {
+    fn base(self) -> f64 {
+        match self {
+            ByteUnits::Binary => 1024.0,
+            ByteUnits::Decimal => 1000.0,
+        }
+    }
+
+    fn prefixes(self) -> [&'static str; 4] {
+        match self {
+            ByteUnits::Binary => ["KiB", "MiB", "GiB", "TiB"],
+            ByteUnits::Decimal => ["kB", "MB", "GB", "TB"],
+        }
+    }
+}
+
+/// Sets the units sizes are shown in for the rest of the process. Only the first call has an
+/// effect.
+pub fn set_units(units: ByteUnits) {
+    let _ = UNITS.set(units);
+}
+
+/// The units sizes are shown in: binary unless set otherwise.
+pub fn units() -> ByteUnits {
+    UNITS.get().copied().unwrap_or_default()
+}
+
+/// Formats `bytes` in the configured units, as in `1.42 MiB` or `800 B`.
+pub fn format(bytes: u64) -> String {
+    format_in(bytes, units())
+}
+
+/// Formats `bytes` in `units`, with two decimals from the first unit above bytes.
+pub fn format_in(bytes: u64, units: ByteUnits) -> String {
+    let base = units.base();
+    if (bytes as f64) < base {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / base;
+    let mut prefixes = units.prefixes().into_iter().peekable();
+    while let Some(prefix) = prefixes.next() {
+        // Rounding may carry a size up to the next unit
+        if (size * 100.0).round() < base * 100.0 || prefixes.peek().is_none() {
+            return format!("{:.2} {}", size, prefix);
+        }
+        size /= base;
+    }
+    unreachable!("there is always a last prefix")
+}
@@ -0,0 +1,142 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use rig::completion::ToolDefinition;
+use rig::tool::{ToolDyn, ToolError};
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::HEAD_TTL;
+use crate::metrics::metrics;
+use crate::network::Network;
+use crate::registry::{ToolContext, ToolKind};
+
+/// Describes, as JSON, the tools the agent was given, the networks it can query and how fresh
+/// its data is, so "what can you do?" is answered from the registry rather than from memory.
+pub struct CapabilitiesTool {
+    tools: Vec<(ToolKind, Arc<dyn ToolDyn>)>,
+    network: Network,
+    networks: Vec<Network>,
+    offline: bool,
+}
+
+impl CapabilitiesTool {
+    pub const NAME: &'static str = "capabilities";
+
+    /// Shares `tools` between the agent and a capabilities tool describing them, added last.
+    pub fn describing(
+        tools: Vec<(ToolKind, Box<dyn ToolDyn>)>,
+        ctx: &ToolContext,
+    ) -> Vec<(ToolKind, Box<dyn ToolDyn>)> {
+        let tools: Vec<(ToolKind, Arc<dyn ToolDyn>)> = tools
+            .into_iter()
+            .map(|(kind, tool)| (kind, Arc::from(tool)))
+            .collect();
+        let capabilities = Self {
+            tools: tools.clone(),
+            network: ctx.network,
+            networks: ctx.clients.keys().copied().collect(),
+            offline: ctx.offline,
+        };
+        tools
+            .into_iter()
+            .map(|(kind, tool)| (kind, Box::new(Shared(tool)) as Box<dyn ToolDyn>))
+            .chain([(ToolKind::Meta, Box::new(capabilities) as Box<dyn ToolDyn>)])
+            .collect()
+    }
+
+    async fn describe(&self) -> String {
+        let mut tools = Vec::new();
+        for (kind, tool) in &self.tools {
+            let definition = tool.definition(String::new()).await;
+            let properties = definition.parameters["properties"].as_object();
+            tools.push(json!({
+                "name": definition.name,
+                "kind": kind.name(),
+                "description": definition.description,
+                "parameters": properties.map(|properties| properties.keys().collect::<Vec<_>>()),
+                "required": definition.parameters["required"],
+            }));
+        }
+        let freshness = match self.offline {
+            true => "offline: answers only come from responses cached earlier and the local block \
+                     store, so nothing newer than them is known"
+                .to_string(),
+            false => format!(
+                "live: block data comes from the Celenium indexer, which follows the chain within \
+                 a block or so; the chain head is refreshed at most every {}s, and responses \
+                 about past blocks are kept for the session as they can't change",
+                HEAD_TTL.as_secs()
+            ),
+        };
+        let description = json!({
+            "network": self.network.name(),
+            "networks": self.networks.iter().map(|network| network.name()).collect::<Vec<_>>(),
+            "freshness": freshness,
+            "tools": tools,
+        });
+        serde_json::to_string_pretty(&description).expect("JSON values should serialize")
+    }
+}
+
+impl ToolDyn for CapabilitiesTool {
+    fn name(&self) -> String {
+        Self::NAME.to_string()
+    }
+
+    fn definition(
+        &self,
+        _prompt: String,
+    ) -> Pin<Box<dyn Future<Output = ToolDefinition> + Send + Sync + '_>> {
+        Box::pin(async move {
+            ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: "Describe what this assistant can do: every tool it has, with what \
+                              each is for and its parameters, the networks it can query, and how \
+                              fresh its data is. Use it when the user asks what you can do or \
+                              whether you can answer some kind of question."
+                    .to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {},
+                    "additionalProperties": false,
+                }),
+            }
+        })
+    }
+
+    fn call(
+        &self,
+        _args: String,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ToolError>> + Send + Sync + '_>> {
+        Box::pin(async move {
+            let description = self.describe().await;
+            metrics().tool_invocations.inc(&[Self::NAME, "ok"]);
+            // Tools answer with JSON-encoded strings
+            Ok(Value::String(description).to_string())
+        })
+    }
+}
+
+/// A tool the agent shares with the capabilities tool
+struct Shared(Arc<dyn ToolDyn>);
+
+impl ToolDyn for Shared {
+    fn name(&self) -> String {
+        self.0.name()
+    }
+
+    fn definition(
+        &self,
+        prompt: String,
+    ) -> Pin<Box<dyn Future<Output = ToolDefinition> + Send + Sync + '_>> {
+        self.0.definition(prompt)
+    }
+
+    fn call(
+        &self,
+        args: String,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ToolError>> + Send + Sync + '_>> {
+        self.0.call(args)
+    }
+}
@@ -0,0 +1,759 @@
+use std::collections::{HashMap, VecDeque};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::{FutureExt, Shared};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, ETAG, IF_NONE_MATCH,
+    RETRY_AFTER,
+};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::audit;
+use crate::celestia_search_tool::{CelestiaSearchError, REQUEST_TIMEOUT};
+use crate::compat::{self, ApiVersion};
+use crate::lru::LruCache;
+use crate::metrics::metrics;
+use crate::network::Network;
+use crate::replay::Tape;
+use crate::time::{self, Instant};
+use crate::trace;
+
+/// Identifies the assistant to Celenium unless another user agent is given.
+pub const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// The wait before the first retry; each later retry waits twice as long as the previous one.
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// The longest a `Retry-After` header is honoured for before giving up on a retry.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(10);
+
+/// The window over which the requests of [`CeleniumClientBuilder::max_requests_per_minute`] are
+/// counted.
+const REQUEST_WINDOW: Duration = Duration::from_secs(60);
+
+/// How many times a request rate-limited for a known time is resumed after waiting it out, on
+/// top of the configured retries.
+const RATE_LIMIT_RESUMES: u32 = 2;
+
+/// `X-RateLimit-Reset` values above this are Unix timestamps rather than seconds to wait
+const MIN_RESET_TIMESTAMP: u64 = 1_000_000_000;
+
+/// The encodings Celenium is asked to compress responses with, best first.
+const ACCEPTED_ENCODINGS: &str = "br, gzip";
+
+/// How many responses are kept to revalidate by their `ETag`, unless configured otherwise.
+pub const DEFAULT_CACHE_ENTRIES: usize = 256;
+
+/// How many bytes of JSON the responses kept to revalidate may take, unless configured otherwise.
+pub const DEFAULT_CACHE_BYTES: usize = 16 * 1024 * 1024;
+
+/// How many responses of endpoints that never change, such as a block's stats, are kept.
+pub const BLOCK_CACHE_ENTRIES: usize = 2048;
+
+/// How many bytes of JSON the responses of endpoints that never change may take.
+pub const BLOCK_CACHE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Options applied to the Celenium client of every network, from the `[celenium]` section of
+/// the config.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CeleniumConfig {
+    /// The Celenium API key; read from `CELENIUM_API_KEY` if left out.
+    pub api_key: Option<String>,
+    /// Seconds to wait for a response before giving up on a request.
+    pub timeout_secs: Option<u64>,
+    /// How many times transient failures are retried.
+    #[serde(default)]
+    pub retries: u32,
+    /// The user agent sent with requests, instead of [`DEFAULT_USER_AGENT`].
+    pub user_agent: Option<String>,
+    /// Whether to ask for compressed responses (the default).
+    pub compression: Option<bool>,
+    /// How many responses are kept to revalidate, instead of [`DEFAULT_CACHE_ENTRIES`].
+    pub cache_entries: Option<usize>,
+    /// How many bytes of JSON those responses may take, instead of [`DEFAULT_CACHE_BYTES`].
+    pub cache_bytes: Option<usize>,
+    /// How many requests a minute may be sent to the indexer of each network; unlimited if
+    /// left out.
+    pub max_requests_per_minute: Option<u32>,
+}
+
+impl CeleniumConfig {
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key
+            .clone()
+            .or_else(|| std::env::var("CELENIUM_API_KEY").ok())
+    }
+
+    /// A builder of the client of `network`, with these options.
+    pub fn builder(&self, network: Network) -> CeleniumClientBuilder {
+        let mut builder = CeleniumClient::builder()
+            .network(network)
+            .retries(self.retries);
+        if let Some(api_key) = self.api_key() {
+            builder = builder.api_key(api_key);
+        }
+        if let Some(secs) = self.timeout_secs {
+            builder = builder.timeout(Duration::from_secs(secs));
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(compression) = self.compression {
+            builder = builder.compression(compression);
+        }
+        if let Some(entries) = self.cache_entries {
+            builder = builder.cache_entries(entries);
+        }
+        if let Some(bytes) = self.cache_bytes {
+            builder = builder.cache_bytes(bytes);
+        }
+        if let Some(limit) = self.max_requests_per_minute {
+            builder = builder.max_requests_per_minute(limit);
+        }
+        builder
+    }
+}
+
+/// A connection to the Celenium API of one network.
+///
+/// Clones share the underlying connection pool, cached responses and requests in flight, so every
+/// tool querying a network can be built from the same client.
+#[derive(Clone, Debug)]
+pub struct CeleniumClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_version: ApiVersion,
+    network: Network,
+    timeout: Duration,
+    retries: u32,
+    compression: bool,
+    /// Responses that came with an `ETag`, so that endpoints which can change (the head, the
+    /// latest blocks, validator sets) are revalidated with a conditional request rather than
+    /// refetched
+    etags: Arc<Mutex<LruCache<(String, Value)>>>,
+    /// Responses of endpoints that never change once they answer, served without a request
+    immutable: Arc<Mutex<LruCache<Value>>>,
+    in_flight: Arc<Mutex<HashMap<String, InFlight>>>,
+    /// When the indexer said its rate limit resets, as the time it said so and the wait, so that
+    /// requests by every tool hold off until then instead of being rejected
+    paused: Arc<Mutex<Option<(Instant, Duration)>>>,
+    /// How many requests a minute may be sent, if limited
+    max_requests_per_minute: Option<u32>,
+    /// When the requests of the last minute were sent, oldest first
+    sent: Arc<Mutex<VecDeque<Instant>>>,
+    /// The recording responses are added to or served from, if any
+    tape: Option<Tape>,
+}
+
+/// A request whose result is shared by every caller asking for the same URL while it runs.
+type InFlight = Shared<RequestFuture<'static, Result<Value, Arc<CelestiaSearchError>>>>;
+
+// Browser futures are not `Send`, as the requests they await aren't
+#[cfg(not(target_arch = "wasm32"))]
+type RequestFuture<'a, T> = futures::future::BoxFuture<'a, T>;
+#[cfg(target_arch = "wasm32")]
+type RequestFuture<'a, T> = futures::future::LocalBoxFuture<'a, T>;
+
+/// Configures a [`CeleniumClient`].
+#[derive(Clone, Debug)]
+pub struct CeleniumClientBuilder {
+    base_url: Option<String>,
+    network: Network,
+    api_key: Option<String>,
+    timeout: Duration,
+    retries: u32,
+    user_agent: String,
+    compression: bool,
+    cache_entries: usize,
+    cache_bytes: usize,
+    max_requests_per_minute: Option<u32>,
+}
+
+impl Default for CeleniumClientBuilder {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            network: Network::default(),
+            api_key: None,
+            timeout: REQUEST_TIMEOUT,
+            retries: 0,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            compression: true,
+            cache_entries: DEFAULT_CACHE_ENTRIES,
+            cache_bytes: DEFAULT_CACHE_BYTES,
+            max_requests_per_minute: None,
+        }
+    }
+}
+
+impl CeleniumClientBuilder {
+    /// Queries the API at `base_url` (e.g., a mirror or a mock server) instead of the
+    /// network's public one.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// The network the API serves (mainnet by default).
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Sends `api_key` with every request, for Celenium's higher rate limits.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// How long to wait for a response before giving up on a request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How many times a request that timed out, was rate-limited or hit a server error is
+    /// retried (none by default). Rate limits that say when they reset are waited out besides.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Whether to ask for gzip or brotli compressed responses (on by default). Range queries
+    /// fetch a lot of JSON, which compresses well.
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// How many responses are kept to revalidate by their `ETag`; the least recently used ones
+    /// are dropped first.
+    pub fn cache_entries(mut self, entries: usize) -> Self {
+        self.cache_entries = entries;
+        self
+    }
+
+    /// How many bytes of JSON the responses kept to revalidate may take, so long-running watch
+    /// and server modes stay within a memory budget.
+    pub fn cache_bytes(mut self, bytes: usize) -> Self {
+        self.cache_bytes = bytes;
+        self
+    }
+
+    /// How many requests a minute may be sent to the indexer, across every tool using the
+    /// client; requests over the limit fail rather than wait, so a runaway session can't exhaust
+    /// the API key's quota for everyone else.
+    pub fn max_requests_per_minute(mut self, limit: u32) -> Self {
+        self.max_requests_per_minute = Some(limit);
+        self
+    }
+
+    pub fn build(self) -> Result<CeleniumClient, CelestiaSearchError> {
+        let mut headers = HeaderMap::new();
+        if let Some(api_key) = &self.api_key {
+            let mut value = HeaderValue::from_str(api_key).map_err(|_| {
+                CelestiaSearchError::HttpRequestFailed("invalid Celenium API key".to_string())
+            })?;
+            value.set_sensitive(true);
+            headers.insert("apikey", value);
+        }
+        // The timeout is set per request, as browsers' fetch-based clients have no default one
+        let http = reqwest::Client::builder()
+            .user_agent(self.user_agent)
+            .default_headers(headers)
+            .build()
+            .map_err(|e| CelestiaSearchError::HttpRequestFailed(e.to_string()))?;
+
+        let base_url = self.base_url.unwrap_or_else(|| self.network.base_url());
+        Ok(CeleniumClient {
+            http,
+            api_version: ApiVersion::detect(&base_url),
+            base_url,
+            network: self.network,
+            timeout: self.timeout,
+            retries: self.retries,
+            compression: self.compression,
+            etags: Arc::new(Mutex::new(LruCache::new(
+                "etag",
+                self.cache_entries,
+                self.cache_bytes,
+            ))),
+            immutable: Arc::new(Mutex::new(LruCache::new(
+                "immutable",
+                BLOCK_CACHE_ENTRIES,
+                BLOCK_CACHE_BYTES,
+            ))),
+            in_flight: Arc::default(),
+            paused: Arc::default(),
+            max_requests_per_minute: self.max_requests_per_minute,
+            sent: Arc::default(),
+            tape: None,
+        })
+    }
+}
+
+impl CeleniumClient {
+    pub fn builder() -> CeleniumClientBuilder {
+        CeleniumClientBuilder::default()
+    }
+
+    /// The base URL requests are sent to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The version of the API, detected from the base URL; responses are adapted to version 1.
+    pub fn api_version(&self) -> ApiVersion {
+        self.api_version
+    }
+
+    /// The network the API serves.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Adds every response to the recording of `tape`, or serves them from it instead of
+    /// querying the indexer.
+    pub fn with_tape(mut self, tape: Tape) -> Self {
+        self.tape = Some(tape);
+        self
+    }
+
+    /// The last response to `endpoint` that was kept, if one is still cached.
+    pub(crate) fn cached(&self, endpoint: &str) -> Option<Value> {
+        let url = format!("{}{}", self.base_url, endpoint);
+        if let Some(recording) = self.tape.as_ref().and_then(Tape::replaying) {
+            return recording.response(&url).cloned();
+        }
+        if let Some(data) = self.immutable.lock().unwrap().get(&url) {
+            return Some(data.clone());
+        }
+        let mut etags = self.etags.lock().unwrap();
+        etags.get(&url).map(|(_, data)| data.clone())
+    }
+
+    /// Fetches `endpoint` like [`get`](Self::get), unless it has answered before: its response
+    /// must never change, as those of past blocks don't.
+    pub(crate) async fn get_immutable(&self, endpoint: &str) -> Result<Value, CelestiaSearchError> {
+        let url = format!("{}{}", self.base_url, endpoint);
+        audit::upstream(&url);
+        let cached = self.immutable.lock().unwrap().get(&url).cloned();
+        let result = if cached.is_some() { "hit" } else { "miss" };
+        metrics().cache_lookups.inc(&["immutable", result]);
+        if let Some(data) = cached {
+            return Ok(data);
+        }
+
+        let data = self.get(endpoint).await?;
+        let size = url.len() + data.to_string().len();
+        self.immutable
+            .lock()
+            .unwrap()
+            .insert(&url, data.clone(), size);
+        Ok(data)
+    }
+
+    /// Records that the API serves `network`, keeping its base URL.
+    pub(crate) fn set_network(&mut self, network: Network) {
+        self.network = network;
+    }
+
+    /// Fetches `endpoint`, such as `/head`, retrying transient failures as configured.
+    ///
+    /// Concurrent calls for the same endpoint, as range queries and several users of the server
+    /// make, wait for a single request to the indexer and share its result.
+    pub async fn get(&self, endpoint: &str) -> Result<Value, CelestiaSearchError> {
+        let url = format!("{}{}", self.base_url, endpoint);
+        audit::upstream(&url);
+        if let Some(recording) = self.tape.as_ref().and_then(Tape::replaying) {
+            return match recording.response(&url) {
+                Some(data) => Ok(data.clone()),
+                None => Err(CelestiaSearchError::NotRecorded { url }),
+            };
+        }
+        let request = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let joined = in_flight.get(&url).cloned();
+            let result = if joined.is_some() { "hit" } else { "miss" };
+            metrics().cache_lookups.inc(&["in_flight", result]);
+
+            joined.unwrap_or_else(|| {
+                let (client, key) = (self.clone(), url.clone());
+                let request = async move {
+                    let result = client.get_retrying(&key).await.map_err(Arc::new);
+                    client.in_flight.lock().unwrap().remove(&key);
+                    result
+                };
+                #[cfg(not(target_arch = "wasm32"))]
+                let request = request.boxed();
+                #[cfg(target_arch = "wasm32")]
+                let request = request.boxed_local();
+                let request = request.shared();
+                in_flight.insert(url, request.clone());
+                request
+            })
+        };
+
+        request
+            .await
+            .map_err(|error| Arc::try_unwrap(error).unwrap_or_else(|shared| duplicate(&shared)))
+    }
+
+    /// Requests `url` until it succeeds, fails for good or runs out of retries
+    async fn get_retrying(&self, url: &str) -> Result<Value, CelestiaSearchError> {
+        let (mut attempt, mut resumes) = (0, 0);
+        loop {
+            // Wait for the rate limit to reset, unless it resets too late to be worth waiting
+            if let Some(pause) = self.pause() {
+                if pause > MAX_RETRY_AFTER {
+                    return Err(CelestiaSearchError::RateLimited {
+                        url: url.to_string(),
+                        retry_after: Some(whole_secs(pause)),
+                    });
+                }
+                time::sleep(pause).await;
+            }
+
+            let span = trace::client_span("celenium.request")
+                .with("url.full", url)
+                .with("http.request.resend_count", attempt);
+            let error = match trace::traced(span, self.get_once(url)).await {
+                Ok(data) => {
+                    if let Some(Tape::Record(recording)) = &self.tape {
+                        let mut recording = recording.lock().unwrap();
+                        recording.responses.insert(url.to_string(), data.clone());
+                    }
+                    return Ok(data);
+                }
+                Err(error) => error,
+            };
+
+            let wait = match &error {
+                // The pause ahead of the next attempt waits for the limit to reset
+                CelestiaSearchError::RateLimited {
+                    retry_after: Some(secs),
+                    ..
+                } if resumes < RATE_LIMIT_RESUMES
+                    && Duration::from_secs(*secs) <= MAX_RETRY_AFTER =>
+                {
+                    resumes += 1;
+                    continue;
+                }
+                CelestiaSearchError::RateLimited {
+                    retry_after: Some(_),
+                    ..
+                } => None,
+                CelestiaSearchError::RateLimited { .. }
+                | CelestiaSearchError::Timeout { .. }
+                | CelestiaSearchError::HttpRequestFailed(_) => {
+                    Some(RETRY_BACKOFF * 2u32.pow(attempt))
+                }
+                CelestiaSearchError::Status { status, .. } if *status >= 500 => {
+                    Some(RETRY_BACKOFF * 2u32.pow(attempt))
+                }
+                _ => None,
+            };
+            match wait {
+                Some(wait) if attempt < self.retries => {
+                    time::sleep(wait).await;
+                    attempt += 1;
+                }
+                _ => return Err(error),
+            }
+        }
+    }
+
+    /// Sends a GET request to the indexer and parses the JSON body, classifying failures
+    async fn get_once(&self, url: &str) -> Result<Value, CelestiaSearchError> {
+        match self.get_conditional(url, true).await {
+            // A 304 that no cached response stands behind, such as one sent by a cache in
+            // between, is asked again for the full payload
+            Err(CelestiaSearchError::Status { status: 304, .. }) => {
+                self.get_conditional(url, false).await
+            }
+            result => result,
+        }
+    }
+
+    /// Sends a GET request to the indexer, revalidating the response cached for `url` by its
+    /// ETag if `conditional`, and otherwise asking caches in between not to answer it
+    async fn get_conditional(
+        &self,
+        url: &str,
+        conditional: bool,
+    ) -> Result<Value, CelestiaSearchError> {
+        let request_error = |e: reqwest::Error| {
+            if e.is_timeout() {
+                CelestiaSearchError::Timeout {
+                    url: url.to_string(),
+                }
+            } else {
+                CelestiaSearchError::HttpRequestFailed(e.to_string())
+            }
+        };
+
+        self.take_request(url)?;
+
+        // Make the API request, timing it until the body has been read
+        let started = Instant::now();
+        let mut request = self.http.get(url).timeout(self.timeout);
+        // The browser negotiates the encoding itself, and decompresses what it gets
+        if self.compression && cfg!(not(target_arch = "wasm32")) {
+            request = request.header(ACCEPT_ENCODING, ACCEPTED_ENCODINGS);
+        }
+        let cached = match conditional {
+            true => self.etags.lock().unwrap().get(url).cloned(),
+            false => None,
+        };
+        match &cached {
+            Some((etag, _)) => request = request.header(IF_NONE_MATCH, etag),
+            None if !conditional => request = request.header(CACHE_CONTROL, "no-cache"),
+            None => {}
+        }
+        let response = request.send().await.map_err(request_error)?;
+
+        // Get the status and headers before consuming the response
+        let status = response.status();
+        let limited = rate_limit(response.headers(), status);
+        if let Some(wait) = limited {
+            self.pause_for(wait);
+        }
+        let retry_after = limited.map(whole_secs);
+        let encoding = response
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_ascii_lowercase);
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let body = response.bytes().await.map_err(request_error)?;
+        metrics().indexer_latency.observe(started.elapsed());
+
+        let url = url.to_string();
+        let text = decode(encoding.as_deref(), &body).map_err(|reason| {
+            CelestiaSearchError::Deserialization {
+                url: url.clone(),
+                reason,
+            }
+        })?;
+        metrics()
+            .indexer_bytes
+            .inc_by(&["received"], body.len() as u64);
+        metrics()
+            .indexer_bytes
+            .inc_by(&["decoded"], text.len() as u64);
+
+        // An unchanged response costs a 304 rather than its payload
+        if let Some((_, data)) = cached {
+            let modified = status != StatusCode::NOT_MODIFIED;
+            let result = if modified { "miss" } else { "hit" };
+            metrics().cache_lookups.inc(&["etag", result]);
+            if !modified {
+                return Ok(data);
+            }
+        }
+
+        match status {
+            StatusCode::NOT_FOUND => return Err(CelestiaSearchError::NotFound { url }),
+            StatusCode::TOO_MANY_REQUESTS => {
+                return Err(CelestiaSearchError::RateLimited { url, retry_after })
+            }
+            status if !status.is_success() => {
+                return Err(CelestiaSearchError::Status {
+                    status: status.as_u16(),
+                    url,
+                    body: text,
+                })
+            }
+            _ => {}
+        }
+
+        let data: Value =
+            serde_json::from_str(&text).map_err(|e| CelestiaSearchError::Deserialization {
+                url: url.clone(),
+                reason: e.to_string(),
+            })?;
+
+        // Check for API errors in the JSON response
+        if let Some(error) = data.get("error") {
+            let error_message = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown error");
+            return Err(CelestiaSearchError::ApiError(error_message.to_string()));
+        }
+        let data = compat::normalize(self.api_version, data);
+
+        if let Some(etag) = etag {
+            let size = url.len() + etag.len() + text.len();
+            let mut etags = self.etags.lock().unwrap();
+            etags.insert(&url, (etag, data.clone()), size);
+        }
+        Ok(data)
+    }
+
+    /// How much longer requests should hold off for the rate limit to reset, if they should
+    fn pause(&self) -> Option<Duration> {
+        let mut paused = self.paused.lock().unwrap();
+        let remaining = paused.and_then(|(at, wait)| wait.checked_sub(at.elapsed()));
+        if remaining.is_none() {
+            *paused = None;
+        }
+        remaining.filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Counts a request to `url` against the limit of requests a minute, failing if it's used up
+    fn take_request(&self, url: &str) -> Result<(), CelestiaSearchError> {
+        let Some(limit) = self.max_requests_per_minute else {
+            return Ok(());
+        };
+        let mut sent = self.sent.lock().unwrap();
+        while sent
+            .front()
+            .is_some_and(|at| at.elapsed() >= REQUEST_WINDOW)
+        {
+            sent.pop_front();
+        }
+        if sent.len() >= limit as usize {
+            let oldest = sent.front().map(|at| at.elapsed()).unwrap_or_default();
+            return Err(CelestiaSearchError::RequestBudgetExceeded {
+                url: url.to_string(),
+                limit,
+                retry_after: whole_secs(REQUEST_WINDOW.saturating_sub(oldest)).max(1),
+            });
+        }
+        sent.push_back(Instant::now());
+        Ok(())
+    }
+
+    /// Holds off requests for `wait`, unless they already are for longer
+    fn pause_for(&self, wait: Duration) {
+        if self.pause().is_none_or(|pause| pause < wait) {
+            *self.paused.lock().unwrap() = Some((Instant::now(), wait));
+        }
+    }
+}
+
+/// How long the indexer asks to wait before the next request, from the `Retry-After` header of
+/// a rate-limited response or the `X-RateLimit-*` headers of one that used up the limit.
+fn rate_limit(headers: &HeaderMap, status: StatusCode) -> Option<Duration> {
+    let header = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headers.get(*name))
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+    let now = chrono::Utc::now();
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        // Seconds to wait, or an HTTP date
+        let retry_after = header(&[RETRY_AFTER.as_str()]).and_then(|value| match value.parse() {
+            Ok(secs) => Some(Duration::from_secs(secs)),
+            Err(_) => chrono::DateTime::parse_from_rfc2822(value)
+                .ok()
+                .map(|at| (at.to_utc() - now).to_std().unwrap_or_default()),
+        });
+        if retry_after.is_some() {
+            return retry_after;
+        }
+    }
+
+    let exhausted = status == StatusCode::TOO_MANY_REQUESTS
+        || header(&["x-ratelimit-remaining", "ratelimit-remaining"]) == Some("0");
+    if !exhausted {
+        return None;
+    }
+    // Seconds until the limit resets, or the Unix time it does
+    let reset: u64 = header(&["x-ratelimit-reset", "ratelimit-reset"])?
+        .parse()
+        .ok()?;
+    match reset {
+        reset if reset > MIN_RESET_TIMESTAMP => {
+            let now = now.timestamp().max(0) as u64;
+            Some(Duration::from_secs(reset.saturating_sub(now)))
+        }
+        reset => Some(Duration::from_secs(reset)),
+    }
+}
+
+/// `duration` in seconds, rounded up so that waiting them out is long enough
+fn whole_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// A copy of an error shared by the callers of a coalesced request
+fn duplicate(error: &CelestiaSearchError) -> CelestiaSearchError {
+    match error {
+        CelestiaSearchError::HttpRequestFailed(reason) => {
+            CelestiaSearchError::HttpRequestFailed(reason.clone())
+        }
+        CelestiaSearchError::Timeout { url } => CelestiaSearchError::Timeout { url: url.clone() },
+        CelestiaSearchError::NotFound { url } => CelestiaSearchError::NotFound { url: url.clone() },
+        CelestiaSearchError::RateLimited { url, retry_after } => CelestiaSearchError::RateLimited {
+            url: url.clone(),
+            retry_after: *retry_after,
+        },
+        CelestiaSearchError::RequestBudgetExceeded {
+            url,
+            limit,
+            retry_after,
+        } => CelestiaSearchError::RequestBudgetExceeded {
+            url: url.clone(),
+            limit: *limit,
+            retry_after: *retry_after,
+        },
+        CelestiaSearchError::Status { status, url, body } => CelestiaSearchError::Status {
+            status: *status,
+            url: url.clone(),
+            body: body.clone(),
+        },
+        CelestiaSearchError::Deserialization { url, reason } => {
+            CelestiaSearchError::Deserialization {
+                url: url.clone(),
+                reason: reason.clone(),
+            }
+        }
+        CelestiaSearchError::ApiError(message) => CelestiaSearchError::ApiError(message.clone()),
+        // Requests fail with the errors above only
+        error => CelestiaSearchError::HttpRequestFailed(error.to_string()),
+    }
+}
+
+/// Decompresses a response body sent with the `Content-Encoding` `encoding`
+#[cfg(not(target_arch = "wasm32"))]
+fn decode(encoding: Option<&str>, body: &[u8]) -> Result<String, String> {
+    let mut text = String::new();
+    let read = match encoding {
+        None | Some("identity") => {
+            return String::from_utf8(body.to_vec()).map_err(|e| e.to_string())
+        }
+        Some("gzip") => flate2::read::GzDecoder::new(body).read_to_string(&mut text),
+        Some("br") => brotli::Decompressor::new(body, 4096).read_to_string(&mut text),
+        Some(encoding) => return Err(format!("unsupported content encoding `{}`", encoding)),
+    };
+    read.map(|_| text).map_err(|e| e.to_string())
+}
+
+/// Reads a response body as text. The browser's fetch has already decompressed it, even if its
+/// `Content-Encoding` header is still visible.
+#[cfg(target_arch = "wasm32")]
+fn decode(_encoding: Option<&str>, body: &[u8]) -> Result<String, String> {
+    String::from_utf8(body.to_vec()).map_err(|e| e.to_string())
+}
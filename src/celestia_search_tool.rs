@@ -1,35 +1,134 @@
+#[cfg(not(target_arch = "wasm32"))]
 use rig::completion::ToolDefinition;
+#[cfg(not(target_arch = "wasm32"))]
 use rig::tool::Tool;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-const API_ENDPOINT: &str = "https://api-mainnet.celenium.io/v1/block";
+use crate::amount::Utia;
+use crate::byte_size;
+use crate::celenium::CeleniumClient;
+use crate::compat;
+use crate::de::string_or_number;
+use crate::enums::Enums;
+use crate::metrics::metrics;
+use crate::network::Network;
+use crate::price::PriceFeed;
+use crate::sanitize;
+#[cfg(feature = "sqlite-cache")]
+use crate::store::{BlockStore, StoreError};
+use crate::time::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::validator_directory::{self, Listed, LIST_TTL};
+
+/// The Celenium mainnet API, used unless a different base URL is injected.
+pub const DEFAULT_BASE_URL: &str = "https://api-mainnet.celenium.io/v1";
+
+/// How long to wait for the indexer before giving up on a request.
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long the fetched chain head is trusted before it is refreshed.
+pub(crate) const HEAD_TTL: Duration = Duration::from_secs(10);
 
 /// The query parameters that the agent will inject into the search.
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Deserialize)]
 pub struct CelestiaQueryArgs {
     /// The block height at which to query.
     height: u64,
+    /// The networks to query, instead of the configured one.
+    #[serde(default, deserialize_with = "crate::network::deserialize_networks")]
+    network: Vec<Network>,
 }
 
-/// The fields that are received in the search response.
-#[derive(Serialize)]
+/// The stats of a block, as received in the search response.
+///
+/// Missing fields are left at zero, but fields that are present must hold a number. Stats
+/// fetched through [`CelestiaSearchTool::fetch_stats`] carry the height and network queried.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct CelestiaResponseFields {
-    blobs_count: u64,
-    blobs_size: u64,
-    block_time: u64,
-    bytes_in_block: u64,
-    commissions: String,
-    events_count: u64,
-    fee: String,
-    fill_rate: String,
-    gas_limit: u64,
-    gas_used: u64,
-    inflation_rate: String,
-    rewards: String,
-    square_size: u64,
-    supply_change: String,
-    tx_count: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub height: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub blobs_count: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub blobs_size: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub block_time: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub bytes_in_block: u64,
+    pub commissions: Utia,
+    #[serde(deserialize_with = "string_or_number")]
+    pub events_count: u64,
+    pub fee: Utia,
+    #[serde(deserialize_with = "string_or_number")]
+    pub fill_rate: f64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub gas_limit: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub gas_used: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub inflation_rate: String,
+    pub rewards: Utia,
+    #[serde(deserialize_with = "string_or_number")]
+    pub square_size: u64,
+    pub supply_change: Utia,
+    #[serde(deserialize_with = "string_or_number")]
+    pub tx_count: u64,
+    /// Not part of the response; set from the tool that fetched the stats.
+    pub network: Network,
+}
+
+impl CelestiaResponseFields {
+    /// Populates the fields from a block stats response
+    pub fn from_json(data: &Value) -> Result<Self, serde_json::Error> {
+        Self::deserialize(data)
+    }
+}
+
+impl fmt::Display for CelestiaResponseFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Block {} on {}: {} txs, {} blobs ({}), fill rate {}, fee {} utia",
+            self.height,
+            self.network,
+            self.tx_count,
+            self.blobs_count,
+            byte_size::format(self.blobs_size),
+            self.fill_rate,
+            self.fee
+        )
+    }
+}
+
+/// The validator that proposed a block, as Celenium reports it with the block.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Proposer {
+    /// Celenium's id of the validator, to look it up with `/validator/{id}`.
+    pub id: Option<u64>,
+    pub moniker: Option<String>,
+    /// The validator's operator address (`celestiavaloper1...`), if reported with the block.
+    pub address: Option<String>,
+    /// The validator's consensus address (`celestiavalcons1...`).
+    pub cons_address: Option<String>,
+}
+
+impl Proposer {
+    /// The proposer's moniker, or its consensus address if it has none.
+    pub fn name(&self) -> Option<&str> {
+        self.moniker
+            .as_deref()
+            .filter(|moniker| !moniker.is_empty())
+            .or(self.cons_address.as_deref())
+    }
 }
 
 /// Captures the possible types of errors that may occur while searching.
@@ -37,12 +136,110 @@ pub struct CelestiaResponseFields {
 pub enum CelestiaSearchError {
     #[error("HTTP request failed: {0}")]
     HttpRequestFailed(String),
+    #[error("Request to {url} timed out")]
+    Timeout { url: String },
+    #[error("Not found: {url}")]
+    NotFound { url: String },
+    #[error(
+        "The indexer is rate-limiting requests, try again{}",
+        .retry_after.map(|secs| format!(" in {}s", secs)).unwrap_or_default()
+    )]
+    RateLimited {
+        url: String,
+        /// Seconds to wait, from the `Retry-After` or `X-RateLimit-Reset` header
+        retry_after: Option<u64>,
+    },
+    #[error(
+        "The assistant may send at most {limit} requests a minute to the indexer, try again in \
+         {retry_after}s"
+    )]
+    RequestBudgetExceeded {
+        url: String,
+        limit: u32,
+        retry_after: u64,
+    },
+    #[error("Indexer returned status {status} for {url}: {body}")]
+    Status {
+        status: u16,
+        url: String,
+        body: String,
+    },
+    #[error("Unexpected response from {url}: {reason}")]
+    Deserialization { url: String, reason: String },
     #[error("API error: {0}")]
     ApiError(String),
+    #[cfg(feature = "sqlite-cache")]
+    #[error("Local store error: {0}")]
+    StoreError(#[from] StoreError),
+    #[error("Invalid block range: {0}")]
+    InvalidRange(String),
+    #[error("Height {height} is beyond the current head ({head})")]
+    HeightBeyondHead { height: u64, head: u64 },
+    #[error("No validator matches `{0}`")]
+    UnknownValidator(String),
+    #[error("`{name}` matches several validators: {candidates}. Ask which one is meant, or give its operator address")]
+    AmbiguousValidator { name: String, candidates: String },
+    #[error("No indexer is configured for {0}")]
+    NetworkUnavailable(Network),
+    #[error("{url} is not cached, and the assistant is offline")]
+    NotCached { url: String },
+    #[error("{url} was not requested in the recorded session being replayed")]
+    NotRecorded { url: String },
+    #[error("`{0}` is not a Celestia account address (`celestia1...`)")]
+    InvalidAddress(String),
+    #[error("`{0}` is not a namespace (expected 28 or 29 bytes in hex)")]
+    InvalidNamespace(String),
+    #[error("`{0}` is not a transaction hash (expected 32 bytes in hex)")]
+    InvalidTxHash(String),
+    #[error("`{0}` does not continue this listing; pass back the cursor of the previous page with the same arguments")]
+    InvalidCursor(String),
+    #[error("Invalid event query {0}")]
+    InvalidEventQuery(String),
+    #[error("Invalid estimate: {0}")]
+    InvalidEstimate(String),
+    #[error("Unknown {kind} `{value}` (expected one of: {known})")]
+    UnknownValue {
+        kind: &'static str,
+        value: String,
+        known: String,
+    },
 }
 
-pub struct CelestiaSearchTool;
+/// The latest chain head seen, and when it was fetched.
+#[derive(Default)]
+struct ChainHead {
+    height: u64,
+    fetched_at: Option<Instant>,
+}
+
+/// The validators last listed, and when they were fetched.
+#[cfg(not(target_arch = "wasm32"))]
+struct ValidatorList {
+    validators: Arc<Vec<Listed>>,
+    fetched_at: Instant,
+}
 
+pub struct CelestiaSearchTool {
+    client: CeleniumClient,
+    /// Tools for the other networks a call may ask for
+    peers: HashMap<Network, Arc<CelestiaSearchTool>>,
+    #[cfg(feature = "sqlite-cache")]
+    store: Option<Arc<BlockStore>>,
+    price_feed: Option<Arc<PriceFeed>>,
+    enums: Option<Arc<Enums>>,
+    head: Mutex<ChainHead>,
+    #[cfg(not(target_arch = "wasm32"))]
+    validators: Mutex<Option<ValidatorList>>,
+    offline: bool,
+}
+
+impl Default for CelestiaSearchTool {
+    fn default() -> Self {
+        Self::with_base_url(DEFAULT_BASE_URL)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 impl Tool for CelestiaSearchTool {
     const NAME: &'static str = "search_blocks";
 
@@ -54,152 +251,370 @@ impl Tool for CelestiaSearchTool {
     async fn definition(&self, _prompt: String) -> ToolDefinition {
         ToolDefinition {
             name: Self::NAME.to_string(),
-            description: "Search for info on Celestia blocks".to_string(),
-            parameters: json!({
+            description: "Look up the stats of a single Celestia block by height, such as its \
+                total fee in utia. Heights start at 1 and cannot exceed the current chain head."
+                .to_string(),
+            parameters: serde_json::json!({
                 "type": "object",
                 "properties": {
-                    "height": { "type": "integer", "description": "Height of the block to search for (e.g., '10000')" },
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Height of the block to search for, as an integer",
+                        "examples": [10000, 2000000],
+                    },
+                    "network": crate::network::schema(),
                 },
-                "required": ["height"]
+                "required": ["height"],
+                "additionalProperties": false,
             }),
         }
     }
 
     /// Specifies how the agent should respond to user prompts
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        // Format the search URL
-        let url = format!("{}/{}/stats", API_ENDPOINT, args.height);
+        let result = self.search(args).await;
 
-        // Make the API request
-        let response = reqwest::get(url)
-            .await
-            .map_err(|e| CelestiaSearchError::HttpRequestFailed(e.to_string()))?;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
 
-        // Get the status code before consuming the response
-        let status = response.status();
+        result
+    }
+}
 
-        // Consume the response and read the response text
-        let text = response
-            .text()
-            .await
-            .map_err(|e| CelestiaSearchError::HttpRequestFailed(e.to_string()))?;
-
-        // Check if the response is an error
-        if !status.is_success() {
-            return Err(CelestiaSearchError::ApiError(format!(
-                "Status: {}, Response: {}",
-                status, text
-            )));
+impl CelestiaSearchTool {
+    /// Creates a tool that queries Celenium through `client`.
+    pub fn new(client: CeleniumClient) -> Self {
+        Self {
+            client,
+            peers: HashMap::new(),
+            #[cfg(feature = "sqlite-cache")]
+            store: None,
+            price_feed: None,
+            enums: None,
+            head: Mutex::new(ChainHead::default()),
+            #[cfg(not(target_arch = "wasm32"))]
+            validators: Mutex::new(None),
+            offline: false,
         }
+    }
+
+    /// Creates a tool that queries the Celenium API at `base_url` (e.g., a mirror or a mock server).
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self::new(
+            CeleniumClient::builder()
+                .base_url(base_url)
+                .build()
+                .expect("Celenium client with default options should build"),
+        )
+    }
+
+    /// Records which network `base_url` serves (mainnet by default).
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.client.set_network(network);
+        self
+    }
+
+    /// The network this tool queries.
+    pub fn network(&self) -> Network {
+        self.client.network()
+    }
 
-        // Parse the response JSON
-        let data: Value = serde_json::from_str(&text)
-            .map_err(|e| CelestiaSearchError::HttpRequestFailed(e.to_string()))?;
-
-        // Check for API errors in the JSON response
-        if let Some(error) = data.get("error") {
-            let error_message = error
-                .get("message")
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown error");
-            return Err(CelestiaSearchError::ApiError(error_message.to_string()));
+    /// Answers calls asking for `network` with `tool`.
+    pub fn with_peer(mut self, network: Network, tool: CelestiaSearchTool) -> Self {
+        self.peers.insert(network, Arc::new(tool));
+        self
+    }
+
+    /// The tool querying `network`.
+    pub fn on(&self, network: Network) -> Result<&CelestiaSearchTool, CelestiaSearchError> {
+        if network == self.network() {
+            return Ok(self);
+        }
+        self.peers
+            .get(&network)
+            .map(Arc::as_ref)
+            .ok_or(CelestiaSearchError::NetworkUnavailable(network))
+    }
+
+    /// Runs `query` on each of `networks` concurrently, labelling each output with its network,
+    /// or only on this tool's network if none are given.
+    pub async fn across<'a, F, Fut>(
+        &'a self,
+        networks: &[Network],
+        query: F,
+    ) -> Result<String, CelestiaSearchError>
+    where
+        F: Fn(&'a CelestiaSearchTool) -> Fut,
+        Fut: Future<Output = Result<String, CelestiaSearchError>>,
+    {
+        if networks.is_empty() {
+            return query(self).await;
         }
 
-        // Populate the CelestiaResponseFields type with fields from the response
-        let tx_count = data
-            .get("tx_count")
-            .and_then(|tc| tc.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let block_time = data
-            .get("block_time")
-            .and_then(|bt| bt.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let gas_limit = data
-            .get("gas_limit")
-            .and_then(|gl| gl.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let gas_used = data
-            .get("gas_used")
-            .and_then(|gu| gu.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let square_size = data
-            .get("square_size")
-            .and_then(|ss| ss.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let bytes_in_block = data
-            .get("bytes_in_block")
-            .and_then(|bib| bib.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let events_count = data
-            .get("events_count")
-            .and_then(|ec| ec.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let blobs_count = data
-            .get("blobs_count")
-            .and_then(|bc| bc.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let blobs_size = data
-            .get("blobs_size")
-            .and_then(|bs| bs.as_str())
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-        let fee = data.get("fee").and_then(|f| f.as_str()).unwrap_or("0");
-        let supply_change = data
-            .get("supply_change")
-            .and_then(|sc| sc.as_str())
-            .unwrap_or("0");
-        let inflation_rate = data
-            .get("inflation_rate")
-            .and_then(|ir| ir.as_str())
-            .unwrap_or("0");
-        let fill_rate = data
-            .get("fill_rate")
-            .and_then(|fr| fr.as_str())
-            .unwrap_or("0");
-        let rewards = data.get("rewards").and_then(|r| r.as_str()).unwrap_or("0");
-        let commissions = data
-            .get("commissions")
-            .and_then(|c| c.as_str())
-            .unwrap_or("0");
-
-        let celestia_resonse = CelestiaResponseFields {
-            blobs_count,
-            blobs_size,
-            block_time,
-            bytes_in_block,
-            commissions: commissions.to_string(),
-            events_count,
-            fee: fee.to_string(),
-            fill_rate: fill_rate.to_string(),
-            gas_limit,
-            gas_used,
-            inflation_rate: inflation_rate.to_string(),
-            rewards: rewards.to_string(),
-            square_size,
-            supply_change: supply_change.to_string(),
-            tx_count,
-        };
+        let outputs = networks.iter().map(|&network| {
+            let tool = self.on(network);
+            let query = &query;
+            async move {
+                let output = query(tool?).await?;
+                Ok::<_, CelestiaSearchError>(format!("On {}: {}", network, output.trim_start()))
+            }
+        });
+        Ok(futures::future::try_join_all(outputs).await?.join("\n\n"))
+    }
+
+    /// Persists every fetched response in `store`, and serves repeated queries from it.
+    #[cfg(feature = "sqlite-cache")]
+    pub fn with_store(mut self, store: Arc<BlockStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Answers only from the local store and the responses already in memory, failing with
+    /// [`CelestiaSearchError::NotCached`] instead of querying Celenium.
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /// Checks arguments against Celenium's vocabularies.
+    pub fn with_enums(mut self, enums: Arc<Enums>) -> Self {
+        self.enums = Some(enums);
+        self
+    }
+
+    /// Celenium's vocabularies, if they were fetched.
+    pub fn enums(&self) -> Option<&Enums> {
+        self.enums.as_deref()
+    }
+
+    /// Adds the TIA and USD equivalents of fees to the output, using `price_feed`.
+    pub fn with_price_feed(mut self, price_feed: Arc<PriceFeed>) -> Self {
+        self.price_feed = Some(price_feed);
+        self
+    }
+
+    /// The price of a TIA in USD, if a price feed is set and answers.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn usd_per_tia(&self) -> Option<f64> {
+        self.price_feed.as_ref()?.usd_per_tia().await.ok()
+    }
+
+    /// Queries the block stats endpoint and formats the response
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn search(&self, args: CelestiaQueryArgs) -> Result<String, CelestiaSearchError> {
+        self.across(&args.network, |tool| tool.describe_fee(args.height))
+            .await
+    }
+
+    /// Formats the fee of the block at `height`
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn describe_fee(&self, height: u64) -> Result<String, CelestiaSearchError> {
+        let fee = self.fee(height).await?;
 
         let mut output = String::new();
-        output.push_str(&format!("    The gas fee is: {}", celestia_resonse.fee));
+        output.push_str(&format!("    The gas fee is: {}", fee));
 
         Ok(output)
     }
+
+    /// The fee of the block at `height`, with its TIA and USD equivalents if a price feed is set
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn fee(&self, height: u64) -> Result<String, CelestiaSearchError> {
+        let celestia_response = self.fetch_stats(height).await?;
+
+        // The price is a nicety, so the fee is still reported if it can't be fetched
+        Ok(match &self.price_feed {
+            Some(feed) => {
+                crate::price::describe(celestia_response.fee, feed.usd_per_tia().await.ok())
+            }
+            None => celestia_response.fee.to_string(),
+        })
+    }
+
+    /// Fetches the stats of the block at `height`
+    pub async fn fetch_stats(
+        &self,
+        height: u64,
+    ) -> Result<CelestiaResponseFields, CelestiaSearchError> {
+        // Serve the block from the local store if it has been fetched before
+        #[cfg(feature = "sqlite-cache")]
+        if let Some(store) = &self.store {
+            let cached = store.get(self.network(), height)?;
+            let result = if cached.is_some() { "hit" } else { "miss" };
+            metrics().cache_lookups.inc(&["block_store", result]);
+
+            if let Some(mut stats) = cached {
+                (stats.height, stats.network) = (height, self.network());
+                return Ok(stats);
+            }
+        }
+
+        // Offline, the chain head is only the latest block known, so don't reject what's beyond
+        if !self.offline {
+            self.check_height(height).await?;
+        }
+
+        // A block's stats don't change once it's produced, so they are only fetched once
+        let endpoint = format!("/block/{}/stats", height);
+        let data = match self.offline {
+            true => self.get(&endpoint).await?,
+            false => self.client.get_immutable(&endpoint).await?,
+        };
+
+        let deserialization = |reason| CelestiaSearchError::Deserialization {
+            url: format!("{}{}", self.client.base_url(), endpoint),
+            reason,
+        };
+        // Renamed fields would otherwise be read as zero
+        let data = compat::block_stats(self.client.api_version(), data).map_err(deserialization)?;
+        let mut stats =
+            CelestiaResponseFields::from_json(&data).map_err(|e| deserialization(e.to_string()))?;
+        (stats.height, stats.network) = (height, self.network());
+        #[cfg(feature = "sqlite-cache")]
+        if let Some(store) = &self.store {
+            store.put(self.network(), height, &data)?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Fetches the validator that proposed the block at `height`, or `None` if Celenium doesn't
+    /// report it
+    pub async fn fetch_proposer(
+        &self,
+        height: u64,
+    ) -> Result<Option<Proposer>, CelestiaSearchError> {
+        if !self.offline {
+            self.check_height(height).await?;
+        }
+
+        // Like its stats, a block's proposer doesn't change once it's produced
+        let endpoint = format!("/block/{}", height);
+        let mut data = match self.offline {
+            true => self.get(&endpoint).await?,
+            false => self.client.get_immutable(&endpoint).await?,
+        };
+        // Monikers are chosen by the validators
+        sanitize::value(&mut data);
+
+        match &data["proposer"] {
+            Value::Null => Ok(None),
+            proposer => Proposer::deserialize(proposer).map(Some).map_err(|e| {
+                CelestiaSearchError::Deserialization {
+                    url: format!("{}{}", self.client.base_url(), endpoint),
+                    reason: e.to_string(),
+                }
+            }),
+        }
+    }
+
+    /// Returns the height of the latest block, refreshing it at most every [`HEAD_TTL`]
+    pub async fn chain_head(&self) -> Result<u64, CelestiaSearchError> {
+        {
+            let head = self.head.lock().unwrap();
+            let fresh = head.fetched_at.is_some_and(|at| at.elapsed() < HEAD_TTL);
+            metrics()
+                .cache_lookups
+                .inc(&["chain_head", if fresh { "hit" } else { "miss" }]);
+
+            if fresh {
+                return Ok(head.height);
+            }
+        }
+
+        let data = match self.get("/head").await {
+            // Offline, the highest block stored is the latest one known
+            #[cfg(feature = "sqlite-cache")]
+            Err(CelestiaSearchError::NotCached { url }) if self.store.is_some() => {
+                let store = self.store.as_ref().expect("the store was just checked");
+                return store
+                    .max_height(self.network())?
+                    .ok_or(CelestiaSearchError::NotCached { url });
+            }
+            data => data?,
+        };
+        let height = data
+            .get("last_height")
+            .and_then(|height| string_or_number(height).ok())
+            .ok_or_else(|| CelestiaSearchError::Deserialization {
+                url: format!("{}/head", self.client.base_url()),
+                reason: "missing or invalid `last_height`".to_string(),
+            })?;
+
+        *self.head.lock().unwrap() = ChainHead {
+            height,
+            fetched_at: Some(Instant::now()),
+        };
+
+        Ok(height)
+    }
+
+    /// Returns every validator the indexer lists, refreshing them at most every [`LIST_TTL`]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn validator_list(&self) -> Result<Arc<Vec<Listed>>, CelestiaSearchError> {
+        {
+            let list = self.validators.lock().unwrap();
+            let fresh = list
+                .as_ref()
+                .filter(|list| list.fetched_at.elapsed() < LIST_TTL);
+            metrics()
+                .cache_lookups
+                .inc(&["validators", if fresh.is_some() { "hit" } else { "miss" }]);
+
+            if let Some(list) = fresh {
+                return Ok(list.validators.clone());
+            }
+        }
+
+        let validators = validator_directory::fetch_all(self).await?;
+        *self.validators.lock().unwrap() = Some(ValidatorList {
+            validators: validators.clone(),
+            fetched_at: Instant::now(),
+        });
+        Ok(validators)
+    }
+
+    /// Fetches another Celenium endpoint, such as `/validator/1`, with the same error handling
+    /// and its strings sanitized
+    pub(crate) fn fetch(
+        &self,
+        endpoint: &str,
+    ) -> impl Future<Output = Result<Value, CelestiaSearchError>> + '_ {
+        let endpoint = endpoint.to_string();
+        async move {
+            // Whatever the indexer returns may have been written to manipulate the model
+            let mut data = self.get(&endpoint).await?;
+            sanitize::value(&mut data);
+            Ok(data)
+        }
+    }
+
+    /// Queries Celenium, or only its responses cached in memory when offline
+    async fn get(&self, endpoint: &str) -> Result<Value, CelestiaSearchError> {
+        if !self.offline {
+            return self.client.get(endpoint).await;
+        }
+        self.client
+            .cached(endpoint)
+            .ok_or_else(|| CelestiaSearchError::NotCached {
+                url: format!("{}{}", self.client.base_url(), endpoint),
+            })
+    }
+
+    /// Rejects heights above the chain head, so the agent can tell the user why
+    async fn check_height(&self, height: u64) -> Result<(), CelestiaSearchError> {
+        // Heights at or below a head seen before remain valid
+        if height <= self.head.lock().unwrap().height {
+            return Ok(());
+        }
+
+        // The check is best effort: if the head is unavailable, let the stats query decide
+        match self.chain_head().await {
+            Ok(head) if height > head => {
+                Err(CelestiaSearchError::HeightBeyondHead { height, head })
+            }
+            _ => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,162 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::estimate_time_tool::{self, BLOCK_TIME_WINDOW};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The parameters to report.
+#[derive(Deserialize)]
+pub struct ChainParamsArgs {
+    /// Only report the parameters of this module, e.g. `blob` or `staking`.
+    module: Option<String>,
+    /// The networks to report on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Reports the chain's identity, genesis and on-chain module parameters.
+pub struct ChainParamsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl ChainParamsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn report(&self, args: ChainParamsArgs) -> Result<String, CelestiaSearchError> {
+        self.blocks
+            .across(&args.network, |blocks| {
+                describe_params(blocks, args.module.as_deref())
+            })
+            .await
+    }
+}
+
+/// Describes the chain and the parameters of its modules, or of `module` only
+async fn describe_params(
+    blocks: &CelestiaSearchTool,
+    module: Option<&str>,
+) -> Result<String, CelestiaSearchError> {
+    let (head, constants, genesis) = futures::try_join!(
+        blocks.fetch("/head"),
+        blocks.fetch("/constants"),
+        blocks.fetch("/block/1"),
+    )?;
+    let modules = constants["module"].as_object().cloned().unwrap_or_default();
+
+    if let Some(name) = module {
+        let Some(params) = modules.get(name.trim()) else {
+            let known: Vec<&str> = modules.keys().map(String::as_str).collect();
+            return Ok(format!(
+                "There is no `{}` module. Modules with parameters: {}.",
+                name,
+                known.join(", ")
+            ));
+        };
+        return Ok(format!(
+            "Parameters of the {} module:{}",
+            name,
+            list(params)
+        ));
+    }
+
+    let mut output = format!(
+        "Chain {} started at {} (block 1).",
+        head["chain_id"].as_str().unwrap_or("with an unknown id"),
+        genesis["time"].as_str().unwrap_or("an unknown time")
+    );
+    if let Some(size) = modules
+        .get("blob")
+        .map(|blob| &blob["gov_max_square_size"])
+        .filter(|size| !size.is_null())
+    {
+        output.push_str(&format!(
+            " The maximum data square size is {} (the blob module's `gov_max_square_size`).",
+            display(size)
+        ));
+    }
+
+    // The block time target isn't an on-chain parameter, so report what blocks actually take
+    if let Some(average) = estimate_time_tool::block_time(blocks, &head, BLOCK_TIME_WINDOW).await? {
+        output.push_str(&format!(
+            " Blocks currently come every {:.2}s on average (over the last {} blocks).",
+            average.seconds, average.blocks
+        ));
+    }
+
+    output.push_str("\nModule parameters:");
+    for (name, params) in &modules {
+        let params: Vec<String> = params
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| format!("{} = {}", key, display(value)))
+            .collect();
+        output.push_str(&format!("\n- {}: {}", name, params.join(", ")));
+    }
+
+    Ok(output)
+}
+
+/// A module's parameters, one per line
+fn list(params: &Value) -> String {
+    params
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| format!("\n- {}: {}", key, display(value)))
+        .collect()
+}
+
+/// Parameters without the quotes of JSON strings
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+impl Tool for ChainParamsTool {
+    const NAME: &'static str = "chain_params";
+
+    type Args = ChainParamsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Report Celestia's protocol configuration as it is on chain: the chain \
+                          id, genesis time, maximum square size, the observed block time, and \
+                          the parameters of each module (blob, staking, consensus, ...). Use \
+                          it instead of recalling protocol values from memory."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "module": {
+                        "type": "string",
+                        "description": "Only report the parameters of this module",
+                        "examples": ["blob", "staking"],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,135 @@
+use serde_json::Value;
+
+/// The fewest points worth charting.
+const MIN_POINTS: usize = 3;
+
+/// Up to this many points are drawn as labelled bars, beyond that as a sparkline.
+const MAX_BARS: usize = 20;
+
+/// The width of the longest bar, in characters.
+const BAR_WIDTH: usize = 40;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A numeric series found in a tool output.
+#[derive(Debug, PartialEq)]
+pub struct Series {
+    /// The name of the charted metric.
+    pub metric: String,
+    /// The label and value of each point, in order.
+    pub points: Vec<(String, f64)>,
+}
+
+/// Finds a series in a tool output, if it's a JSON array of at least [`MIN_POINTS`] rows sharing
+/// a numeric column.
+///
+/// Points are labelled by the `height` column if there is one, then by the first column of
+/// text (such as an hour or a date), and by their position otherwise. The first other numeric
+/// column is charted.
+pub fn detect(output: &str) -> Option<Series> {
+    let Ok(Value::Array(rows)) = serde_json::from_str::<Value>(output) else {
+        return None;
+    };
+    if rows.len() < MIN_POINTS {
+        return None;
+    }
+
+    let first = rows[0].as_object()?;
+    let label = match first.get("height") {
+        Some(_) => Some("height".to_string()),
+        None => first
+            .iter()
+            .find(|(_, value)| value.is_string() && as_number(value).is_none())
+            .map(|(name, _)| name.clone()),
+    };
+    let metric = first
+        .iter()
+        .find(|(name, value)| Some(*name) != label.as_ref() && as_number(value).is_some())
+        .map(|(name, _)| name.clone())?;
+
+    let points = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let value = as_number(row.get(&metric)?)?;
+            let label = match label.as_ref().and_then(|label| row.get(label)) {
+                Some(Value::String(text)) => text.clone(),
+                Some(height) => height.to_string(),
+                None => (i + 1).to_string(),
+            };
+            Some((label, value))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Series { metric, points })
+}
+
+/// Numbers, and strings holding numbers (as monetary fields are)
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Draws the series as labelled bars if it's short enough, or as a sparkline otherwise.
+pub fn render(series: &Series) -> String {
+    if series.points.len() <= MAX_BARS {
+        bars(series)
+    } else {
+        let values: Vec<f64> = series.points.iter().map(|(_, value)| *value).collect();
+        format!("{}: {}", series.metric, sparkline(&values))
+    }
+}
+
+/// Draws one bar per point, scaled to the largest value.
+pub fn bars(series: &Series) -> String {
+    let max = series
+        .points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(0.0, f64::max);
+    let label_width = series
+        .points
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut chart = format!("{}\n", series.metric);
+    for (label, value) in &series.points {
+        let width = if max > 0.0 {
+            (value.max(0.0) / max * BAR_WIDTH as f64).round() as usize
+        } else {
+            0
+        };
+        chart.push_str(&format!(
+            "{:>label_width$} │{} {}\n",
+            label,
+            "█".repeat(width),
+            value
+        ));
+    }
+    chart.pop();
+    chart
+}
+
+/// Draws the values as a single line of block characters, scaled between their min and max.
+pub fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|value| {
+            let level = if range > 0.0 {
+                ((value - min) / range * (SPARKS.len() - 1) as f64).round() as usize
+            } else {
+                SPARKS.len() / 2
+            };
+            SPARKS[level]
+        })
+        .collect()
+}
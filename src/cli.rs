@@ -0,0 +1,407 @@
+use std::path::PathBuf;
+
+use celestia_search_assistant::bench;
+use celestia_search_assistant::byte_size::ByteUnits;
+use celestia_search_assistant::fetcher::DEFAULT_CONCURRENCY;
+use celestia_search_assistant::format::{Detail, OutputFormat};
+use celestia_search_assistant::locale::{self, Language};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::preamble::PreambleMode;
+use celestia_search_assistant::provider::Provider;
+use celestia_search_assistant::summarize::DEFAULT_RESULT_TOKENS;
+use celestia_search_assistant::template;
+use celestia_search_assistant::verify::Verification;
+use celestia_search_assistant::watch::DEFAULT_INTERVAL;
+use clap::{Parser, Subcommand};
+
+/// Ask questions about the Celestia blockchain in natural language.
+#[derive(Clone, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// The question to ask the agent, or `-` to read it from stdin (as when input is piped)
+    #[arg(long)]
+    pub prompt: Option<String>,
+
+    /// Ask a built-in prompt template instead of a prompt: daily-summary, block-deep-dive or
+    /// validator-report
+    #[arg(long, conflicts_with_all = ["prompt", "batch"])]
+    pub template: Option<String>,
+
+    /// A parameter of the template, as name=value (e.g. height=2000000); may be repeated
+    #[arg(long = "param", requires = "template", value_parser = template::parse_param)]
+    pub params: Vec<(String, String)>,
+
+    /// Answer each line of this file as an independent question, instead of a single prompt
+    #[arg(long, conflicts_with = "prompt")]
+    pub batch: Option<PathBuf>,
+
+    /// Write the batch answers to this file, as JSON Lines, instead of stdout
+    #[arg(long, requires = "batch")]
+    pub batch_out: Option<PathBuf>,
+
+    /// Maximum number of batch questions answered at once
+    #[arg(long, requires = "batch", default_value_t = 1)]
+    pub batch_concurrency: usize,
+
+    /// The service serving the model, with its settings from the config for Azure and Gemini
+    #[arg(long, global = true, value_enum, env = "CELESTIA_PROVIDER", default_value_t = Provider::OpenAi)]
+    pub provider: Provider,
+
+    /// The model answering questions; with Azure, the deployment unless one is configured, and
+    /// with Gemini a Gemini model such as gemini-2.0-flash unless one is configured
+    #[arg(
+        long,
+        global = true,
+        env = "CELESTIA_MODEL",
+        default_value = "gpt-4o-mini"
+    )]
+    pub model: String,
+
+    /// The Celestia network to query
+    #[arg(long, global = true, value_enum, env = "CELESTIA_NETWORK", default_value_t = Network::Mainnet)]
+    pub network: Network,
+
+    /// Print the tool calls the agent plans to make instead of executing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Print each tool invocation and its raw result alongside the answer
+    #[arg(long)]
+    pub show_tools: bool,
+
+    /// Report the indexer bandwidth used, and what compression saved, on exit
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Answer only from block stats in `--db` and responses already cached, without network calls
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Write the session's tool calls, indexer responses and model completions to this file,
+    /// for `--replay`
+    #[arg(long, global = true, conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Re-run the agent against the tool calls, indexer responses and model completions
+    /// recorded with `--record`, without querying the indexer, the model or any other service
+    #[arg(long, global = true)]
+    pub replay: Option<PathBuf>,
+
+    /// With `--replay`, ask the model again at temperature 0 instead of serving its recorded
+    /// completions, to see how it now answers the same data
+    #[arg(long, global = true, requires = "replay")]
+    pub replay_model: bool,
+
+    /// Fetch the latest blocks' stats in the background at startup, up to this many, so
+    /// questions about recent activity are answered from the cache
+    #[arg(
+        long,
+        global = true,
+        env = "CELESTIA_PREFETCH_BLOCKS",
+        default_value_t = 0
+    )]
+    pub prefetch_blocks: u64,
+
+    /// Tell the agent the chain head's height and time at the start of each turn, so questions
+    /// relative to now resolve without a tool call
+    #[arg(long, global = true, env = "CELESTIA_HEAD_CONTEXT")]
+    pub head_context: bool,
+
+    /// Answer a question asked again from the answer kept for it, without asking the model;
+    /// answers about past blocks are kept for a day, those about live data for `--answer-ttl`
+    #[arg(
+        long,
+        global = true,
+        env = "CELESTIA_ANSWER_CACHE",
+        conflicts_with_all = ["record", "replay"]
+    )]
+    pub answer_cache: bool,
+
+    /// Seconds `--answer-cache` keeps answers drawn from the head, prices or the node
+    #[arg(
+        long,
+        global = true,
+        env = "CELESTIA_ANSWER_TTL",
+        default_value_t = 60,
+        requires = "answer_cache"
+    )]
+    pub answer_ttl: u64,
+
+    /// Append a JSON line for every question answered to this file: the question, each tool
+    /// call with its arguments, latency and upstream URLs, the tokens spent, and the answer
+    #[arg(long, global = true, env = "CELESTIA_AUDIT_LOG")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Rotate the audit log before it grows past this many bytes
+    #[arg(
+        long,
+        global = true,
+        env = "CELESTIA_AUDIT_MAX_BYTES",
+        default_value_t = 10 * 1024 * 1024,
+        requires = "audit_log"
+    )]
+    pub audit_max_bytes: u64,
+
+    /// How many rotated audit logs to keep, as `<file>.1` (the newest) and up
+    #[arg(
+        long,
+        global = true,
+        env = "CELESTIA_AUDIT_KEEP",
+        default_value_t = 5,
+        requires = "audit_log"
+    )]
+    pub audit_keep: usize,
+
+    /// Sampling temperature; keep it near 0 for factual answers about chain data
+    #[arg(long, env = "CELESTIA_TEMPERATURE", default_value_t = 0.0)]
+    pub temperature: f64,
+
+    /// Upper bound on the number of tokens the model may generate per turn
+    #[arg(long, env = "CELESTIA_MAX_TOKENS")]
+    pub max_tokens: Option<u64>,
+
+    /// Tokens an earlier answer may take up when resent with a follow-up question; longer tool
+    /// results are aggregated or truncated
+    #[arg(long, env = "CELESTIA_RESULT_TOKENS", default_value_t = DEFAULT_RESULT_TOKENS)]
+    pub result_tokens: usize,
+
+    /// How many tool calls the agent may chain to answer a question, each seeing the results of
+    /// those before it; 1 answers with the result of the first call
+    #[arg(long, env = "CELESTIA_MAX_ITERATIONS", default_value_t = 5)]
+    pub max_iterations: usize,
+
+    /// Fail a question whose answer needs more tool calls than this, counting those made at once
+    /// separately; unlimited by default
+    #[arg(long, env = "CELESTIA_MAX_TOOL_CALLS")]
+    pub max_tool_calls: Option<usize>,
+
+    /// Refuse further questions once the session has spent this many USD on the model, as
+    /// priced by the price table
+    #[arg(long, env = "CELESTIA_MAX_SESSION_USD")]
+    pub max_session_usd: Option<f64>,
+
+    /// The caller's API key, whose role in the config's `[access]` section decides which tools
+    /// the agent is given
+    #[arg(long, global = true, env = "CELESTIA_API_KEY")]
+    pub api_key: Option<String>,
+
+    /// Nucleus sampling probability mass
+    #[arg(long, env = "CELESTIA_TOP_P")]
+    pub top_p: Option<f64>,
+
+    /// Read the agent's preamble from this file instead of using the built-in one
+    #[arg(long, env = "CELESTIA_PREAMBLE_FILE")]
+    pub preamble_file: Option<PathBuf>,
+
+    /// Whether `--preamble-file` replaces the built-in preamble or extends it (replace by
+    /// default)
+    #[arg(long, value_enum, env = "CELESTIA_PREAMBLE_MODE")]
+    pub preamble_mode: Option<PreambleMode>,
+
+    /// Only offer these tools to the agent (comma-separated; all tools by default)
+    #[arg(long, value_delimiter = ',', env = "CELESTIA_TOOLS")]
+    pub tools: Option<Vec<String>>,
+
+    /// Don't call the price feed, for USD equivalents of fees or market data
+    #[arg(long, env = "CELESTIA_NO_FIAT")]
+    pub no_fiat: bool,
+
+    /// Answer with a single agent holding every tool, instead of routing the question to a
+    /// specialized one
+    #[arg(long)]
+    pub no_route: bool,
+
+    /// Send every question to the agent, including those simple enough to be looked up and
+    /// answered directly, such as "gas fee of block 9999" or "current height"
+    #[arg(long, env = "CELESTIA_NO_FAST_PATH")]
+    pub no_fast_path: bool,
+
+    /// How the numbers of answers restated from tool results are checked against them
+    #[arg(long, value_enum, default_value_t = Verification::Warn)]
+    pub verify: Verification,
+
+    /// Whether byte counts are shown in powers of 1024 (KiB, MiB) or of 1000 (kB, MB)
+    #[arg(long, global = true, value_enum, env = "CELESTIA_BYTE_UNITS", default_value_t = ByteUnits::Binary)]
+    pub byte_units: ByteUnits,
+
+    /// The format of the final answer
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Follow text and markdown answers with the explorer pages of the blocks, transactions and
+    /// accounts their tool calls looked up
+    #[arg(long, env = "CELESTIA_SOURCES")]
+    pub sources: bool,
+
+    /// Answer in a single sentence with the number asked for, as dashboards show
+    #[arg(long, conflicts_with = "detailed")]
+    pub concise: bool,
+
+    /// Answer with a full breakdown, followed in text output by the data of each tool call
+    #[arg(long)]
+    pub detailed: bool,
+
+    /// Answer in this language (e.g. de, fr, ja), with numbers written its way
+    #[arg(long, global = true, env = "CELESTIA_LANG", value_parser = locale::parse)]
+    pub lang: Option<Language>,
+
+    /// Continue the named conversation, so follow-up questions can refer to earlier answers
+    #[arg(long, env = "CELESTIA_SESSION")]
+    pub session: Option<String>,
+
+    /// Write the questions, tool calls, data and answers to this file when done: a Markdown
+    /// report, or an HTML page if it ends in .html (as /export does in chat)
+    #[arg(long)]
+    pub transcript_out: Option<PathBuf>,
+
+    /// Where sessions are stored (`~/.celestia-search-assistant/sessions` by default)
+    #[arg(long, global = true, env = "CELESTIA_SESSIONS_DIR")]
+    pub sessions_dir: Option<PathBuf>,
+
+    /// Read settings, such as alert notifiers, from this TOML file
+    #[arg(long, global = true, env = "CELESTIA_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Persist fetched block stats in a local SQLite index at this path
+    #[cfg(feature = "sqlite-cache")]
+    #[arg(long, global = true, env = "CELESTIA_DB")]
+    pub db: Option<PathBuf>,
+
+    /// Answer conceptual questions from a documentation index built with `index-docs`
+    #[arg(long, env = "CELESTIA_DOCS_INDEX")]
+    pub docs_index: Option<PathBuf>,
+}
+
+impl Cli {
+    /// How much answers say, as `--concise` or `--detailed` ask.
+    pub fn detail(&self) -> Detail {
+        if self.concise {
+            Detail::Concise
+        } else if self.detailed {
+            Detail::Detailed
+        } else {
+            Detail::Normal
+        }
+    }
+}
+
+#[derive(Clone, Subcommand)]
+pub enum Command {
+    /// Export per-block stats for a height range as CSV
+    Export {
+        /// First height of the range
+        #[arg(long)]
+        from: u64,
+
+        /// Last height of the range (inclusive)
+        #[arg(long)]
+        to: u64,
+
+        /// Write the CSV to this file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Maximum number of requests in flight
+        #[arg(long, default_value_t = DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+    },
+    /// Follow new blocks as they are produced, printing their stats
+    Watch {
+        /// Start at this height instead of the current head
+        #[arg(long)]
+        from: Option<u64>,
+
+        /// Seconds between polls of the chain head
+        #[arg(long, default_value_t = DEFAULT_INTERVAL.as_secs())]
+        interval: u64,
+
+        /// Print how each block differs from the one before it (fee, fill rate, new namespaces)
+        /// rather than its stats
+        #[arg(long)]
+        diff: bool,
+
+        /// Only print the changes matching a condition, e.g. "fee_change > 1 TIA" or
+        /// "new_namespaces > 0" (repeatable; any may match)
+        #[arg(long = "only", requires = "diff")]
+        only: Vec<String>,
+    },
+    /// Follow new blocks and notify the configured sinks of those matching any condition
+    Alert {
+        /// A condition on a block stats field, e.g. "fill_rate > 0.9" or "fee >= 5 TIA"
+        /// (repeatable)
+        #[arg(long = "when", required = true)]
+        conditions: Vec<String>,
+
+        /// Start at this height instead of the current head
+        #[arg(long)]
+        from: Option<u64>,
+
+        /// Seconds between polls of the chain head
+        #[arg(long, default_value_t = DEFAULT_INTERVAL.as_secs())]
+        interval: u64,
+
+        /// Instead of polling, check the blocks and transactions explorers post to `/webhook` on
+        /// this address (e.g. 0.0.0.0:8080)
+        #[cfg(feature = "server")]
+        #[arg(long, conflicts_with = "from")]
+        listen: Option<std::net::SocketAddr>,
+
+        /// Only accept webhooks sending this as `Authorization: Bearer <secret>`
+        #[cfg(feature = "server")]
+        #[arg(long, env = "CELESTIA_WEBHOOK_SECRET", requires = "listen")]
+        webhook_secret: Option<String>,
+    },
+    /// Ask questions interactively, with slash commands (see /help) to change settings
+    Chat,
+    /// Manage the conversations kept with `--session`
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsCommand,
+    },
+    /// Run the queries scheduled in the config file, reporting their answers
+    Daemon,
+    /// Open a dashboard with the chat, the latest blocks and recent tool calls
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Seconds between polls of the chain head
+        #[arg(long, default_value_t = DEFAULT_INTERVAL.as_secs())]
+        interval: u64,
+    },
+    /// Check the config, API key, endpoints and local directories, and diagnose problems
+    Doctor,
+    /// Time each backend and each tool (restricted by --tools) over repeated runs, reporting
+    /// p50/p95 latencies and what caching saves after the first run
+    Bench {
+        /// The height tools are benchmarked at, instead of one just below the chain head
+        #[arg(long)]
+        height: Option<u64>,
+
+        /// How many times each call is made
+        #[arg(long, default_value_t = bench::DEFAULT_RUNS)]
+        runs: usize,
+    },
+    /// Embed the bundled Celestia documentation (plus any extra docs) into an index file
+    IndexDocs {
+        /// Where to write the index
+        #[arg(long, default_value = "celestia-docs-index.json")]
+        out: PathBuf,
+
+        /// Directories of additional markdown or text documents to index
+        #[arg(long)]
+        dir: Vec<PathBuf>,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+pub enum SessionsCommand {
+    /// List the stored sessions, most recently updated first
+    List,
+    /// Delete a session and its history
+    Delete {
+        /// The name of the session
+        name: String,
+    },
+}
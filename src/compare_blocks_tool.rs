@@ -0,0 +1,152 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::byte_size;
+use crate::celestia_search_tool::{
+    CelestiaResponseFields, CelestiaSearchError, CelestiaSearchTool,
+};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// Reads a numeric field from block stats.
+type Field = fn(&CelestiaResponseFields) -> f64;
+
+/// Shows a value of a field.
+type Show = fn(f64) -> String;
+
+/// The fields compared, with how to read them from the stats and show their values.
+const FIELDS: &[(&str, Field, Show)] = &[
+    ("fee", |s| s.fee.as_f64(), |v| format!("{} utia", v)),
+    ("gas_used", |s| s.gas_used as f64, plain),
+    ("gas_limit", |s| s.gas_limit as f64, plain),
+    ("tx_count", |s| s.tx_count as f64, plain),
+    ("blobs_count", |s| s.blobs_count as f64, plain),
+    (
+        "blobs_size",
+        |s| s.blobs_size as f64,
+        |v| byte_size::format(v as u64),
+    ),
+    ("fill_rate", |s| s.fill_rate, plain),
+    ("square_size", |s| s.square_size as f64, plain),
+];
+
+fn plain(value: f64) -> String {
+    value.to_string()
+}
+
+/// The two blocks to compare.
+#[derive(Deserialize)]
+pub struct CompareBlocksArgs {
+    /// The height compared against.
+    from: u64,
+    /// The height compared.
+    to: u64,
+    /// The networks to compare the blocks on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Compares the stats of two blocks field by field.
+pub struct CompareBlocksTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl CompareBlocksTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn compare(&self, args: CompareBlocksArgs) -> Result<String, CelestiaSearchError> {
+        self.blocks
+            .across(&args.network, |blocks| {
+                diff_blocks(blocks, args.from, args.to)
+            })
+            .await
+    }
+}
+
+/// Diffs the stats of blocks `from` and `to`
+async fn diff_blocks(
+    blocks: &CelestiaSearchTool,
+    from_height: u64,
+    to_height: u64,
+) -> Result<String, CelestiaSearchError> {
+    let (from, to) = futures::try_join!(
+        blocks.fetch_stats(from_height),
+        blocks.fetch_stats(to_height)
+    )?;
+
+    let mut output = format!("Block {} compared to block {}:", to_height, from_height);
+    for (name, read, show) in FIELDS {
+        let (before, after) = (read(&from), read(&to));
+        output.push_str(&format!(
+            "\n- {}: {} -> {} ({})",
+            name,
+            show(before),
+            show(after),
+            change(before, after)
+        ));
+    }
+
+    Ok(output)
+}
+
+/// The relative change from `before` to `after`, as a signed percentage
+fn change(before: f64, after: f64) -> String {
+    if before == after {
+        "unchanged".to_string()
+    } else if before == 0.0 {
+        "up from zero".to_string()
+    } else {
+        format!("{:+.2}%", (after - before) / before * 100.0)
+    }
+}
+
+impl Tool for CompareBlocksTool {
+    const NAME: &'static str = "compare_blocks";
+
+    type Args = CompareBlocksArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Compare two Celestia blocks field by field (fee, gas, transactions, \
+                          blobs, fill rate and square size), with the percentage change of \
+                          each from the first block to the second."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Height of the block compared against",
+                        "examples": [2000000],
+                    },
+                    "to": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Height of the block compared",
+                        "examples": [2500000],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.compare(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
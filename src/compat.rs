@@ -0,0 +1,154 @@
+//! Adapts Celenium responses across API versions to the shapes the tools parse.
+//!
+//! The version is read from the base URL (`.../v1`, `.../v2`). Version 1 responses are used
+//! as they are. Version 2 responses have their `data` envelope unwrapped and their camelCase
+//! fields renamed to snake_case. Block stats additionally go through [`block_stats`], which
+//! maps the renamed fields the crate knows of and, rather than leaving the fields it can't find
+//! at zero, rejects a response in which expected fields are missing while unknown ones appeared.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// A version of the Celenium API.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ApiVersion {
+    #[default]
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    /// The version served at `base_url`, by its last path segment; version 1 unless it names
+    /// version 2.
+    pub fn detect(base_url: &str) -> Self {
+        match base_url.trim_end_matches('/').rsplit('/').next() {
+            Some("v2") => Self::V2,
+            _ => Self::V1,
+        }
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V1 => write!(f, "v1"),
+            Self::V2 => write!(f, "v2"),
+        }
+    }
+}
+
+/// The block stats fields the crate parses.
+const STATS_FIELDS: [&str; 17] = [
+    "height",
+    "blobs_count",
+    "blobs_size",
+    "block_time",
+    "bytes_in_block",
+    "commissions",
+    "events_count",
+    "fee",
+    "fill_rate",
+    "gas_limit",
+    "gas_used",
+    "inflation_rate",
+    "rewards",
+    "square_size",
+    "supply_change",
+    "tx_count",
+    // Part of the stats the crate doesn't use
+    "time",
+];
+
+/// Names block stats fields are known to go by, with the name the crate parses
+const STATS_ALIASES: [(&str, &str); 6] = [
+    ("fees", "fee"),
+    ("blob_count", "blobs_count"),
+    ("blob_size", "blobs_size"),
+    ("txs_count", "tx_count"),
+    ("event_count", "events_count"),
+    ("commission", "commissions"),
+];
+
+/// `response` in the shape version 1 of the API gives it.
+pub fn normalize(version: ApiVersion, response: Value) -> Value {
+    match version {
+        ApiVersion::V1 => response,
+        ApiVersion::V2 => {
+            let response = match response {
+                Value::Object(mut object) if object.len() == 1 && object.contains_key("data") => {
+                    object
+                        .remove("data")
+                        .expect("the envelope was just checked")
+                }
+                other => other,
+            };
+            snake_case_keys(response)
+        }
+    }
+}
+
+/// Block stats with their fields under the names the crate parses, or why they can't be.
+pub fn block_stats(version: ApiVersion, stats: Value) -> Result<Value, String> {
+    let Value::Object(mut object) = stats else {
+        return Err("block stats are not a JSON object".to_string());
+    };
+    for (alias, field) in STATS_ALIASES {
+        if !object.contains_key(field) {
+            if let Some(value) = object.remove(alias) {
+                object.insert(field.to_string(), value);
+            }
+        }
+    }
+
+    // A field that is missing while unknown ones appeared was most likely renamed
+    let missing: Vec<&str> = STATS_FIELDS
+        .iter()
+        .copied()
+        .filter(|field| !object.contains_key(*field))
+        .collect();
+    let unknown: Vec<&str> = object
+        .keys()
+        .map(String::as_str)
+        .filter(|key| !STATS_FIELDS.contains(key))
+        .collect();
+    if !missing.is_empty() && !unknown.is_empty() {
+        return Err(format!(
+            "block stats lack {} but have unknown fields {}; the {} API may have renamed them",
+            missing.join(", "),
+            unknown.join(", "),
+            version
+        ));
+    }
+
+    Ok(Value::Object(object))
+}
+
+fn snake_case_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| (snake_case(&key), snake_case_keys(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(snake_case_keys).collect()),
+        other => other,
+    }
+}
+
+/// `lastHeight` as `last_height`
+fn snake_case(key: &str) -> String {
+    let mut snake = String::with_capacity(key.len() + 4);
+    for (i, c) in key.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::access::AccessConfig;
+use crate::azure::AzureConfig;
+use crate::celenium::{CeleniumClient, CeleniumConfig};
+use crate::celestia_search_tool::CelestiaSearchError;
+use crate::gemini::GeminiConfig;
+use crate::network::Network;
+#[cfg(feature = "node-rpc")]
+use crate::node::NodeConfig;
+use crate::notify::NotifierConfig;
+use crate::preamble::PreambleConfig;
+use crate::schedule::ScheduleConfig;
+use crate::tool_timeout::ToolTimeouts;
+
+/// Settings read from the TOML config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Where alerts and reports are sent. They are printed to stdout if none are configured.
+    #[serde(default)]
+    pub notifiers: Vec<NotifierConfig>,
+    /// The queries run by the `daemon` subcommand.
+    #[serde(default)]
+    pub schedules: Vec<ScheduleConfig>,
+    /// Options of the Celenium clients, such as an API key.
+    #[serde(default)]
+    pub celenium: CeleniumConfig,
+    /// Celenium API base URLs replacing the public ones, by network.
+    #[serde(default)]
+    pub networks: BTreeMap<Network, String>,
+    /// Cosmos REST API base URLs replacing the public ones, by network.
+    #[serde(default)]
+    pub rest: BTreeMap<Network, String>,
+    /// celestia-app gRPC endpoints balances and rewards are read from instead of REST, by
+    /// network.
+    #[cfg(feature = "grpc")]
+    #[serde(default)]
+    pub grpc: BTreeMap<Network, String>,
+    /// The celestia-node used for proofs and sampling status, if there is one.
+    #[cfg(feature = "node-rpc")]
+    pub node: Option<NodeConfig>,
+    /// The Azure OpenAI resource models are served by with `--provider azure`.
+    pub azure: Option<AzureConfig>,
+    /// The Gemini model and safety settings used with `--provider gemini`.
+    pub gemini: Option<GeminiConfig>,
+    /// A custom preamble for the agent, unless `--preamble-file` gives one.
+    pub preamble: Option<PreambleConfig>,
+    /// How long tool calls may take, and what the agent does when one runs over.
+    #[serde(default)]
+    pub tools: ToolTimeouts,
+    /// The tools each API key's role may use; every tool is allowed if it's left out.
+    #[serde(default)]
+    pub access: AccessConfig,
+}
+
+/// Captures the errors that may occur while loading the config.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Failed to read the config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Config is malformed: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+impl Config {
+    /// Reads the config at `path`.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// The Celenium API of `network`, as configured or the public one.
+    pub fn base_url(&self, network: Network) -> String {
+        self.networks
+            .get(&network)
+            .cloned()
+            .unwrap_or_else(|| network.base_url())
+    }
+
+    /// A client of the Celenium API of `network`, with the configured options.
+    pub fn celenium_client(&self, network: Network) -> Result<CeleniumClient, CelestiaSearchError> {
+        self.celenium
+            .builder(network)
+            .base_url(&self.base_url(network))
+            .build()
+    }
+
+    /// The Cosmos REST API of `network`, as configured or the public one.
+    pub fn rest_url(&self, network: Network) -> String {
+        self.rest
+            .get(&network)
+            .cloned()
+            .unwrap_or_else(|| network.rest_url())
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        Ok(toml::from_str(text)?)
+    }
+}
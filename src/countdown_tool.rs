@@ -0,0 +1,203 @@
+use chrono::Duration;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::estimate_time_tool::{self, BlockTime, BLOCK_TIME_WINDOW};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The longer window the block time is measured over, steadier than the recent one.
+pub const LONG_BLOCK_TIME_WINDOW: u64 = 1000;
+
+/// The height to count down to.
+#[derive(Deserialize)]
+pub struct CountdownArgs {
+    /// The target height, such as an upgrade height.
+    height: u64,
+    /// What happens at the height, e.g. `the v3 upgrade`.
+    label: Option<String>,
+    /// The networks to count down on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Counts down to a future height from the block time measured over recent blocks.
+pub struct CountdownTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl CountdownTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn count_down(&self, args: CountdownArgs) -> Result<String, CelestiaSearchError> {
+        let name = match &args.label {
+            Some(label) => format!("{} (block {})", capitalized(label.trim()), args.height),
+            None => format!("Block {}", args.height),
+        };
+        self.blocks
+            .across(&args.network, |blocks| {
+                count_down(blocks, args.height, &name)
+            })
+            .await
+    }
+}
+
+async fn count_down(
+    blocks: &CelestiaSearchTool,
+    height: u64,
+    name: &str,
+) -> Result<String, CelestiaSearchError> {
+    let head = blocks.fetch("/head").await?;
+    let (recent, long) = futures::try_join!(
+        estimate_time_tool::block_time(blocks, &head, BLOCK_TIME_WINDOW),
+        estimate_time_tool::block_time(blocks, &head, LONG_BLOCK_TIME_WINDOW),
+    )?;
+    let Some(average) = long.or(recent) else {
+        return Ok("The indexer has no block times to count down with.".to_string());
+    };
+
+    if height <= average.height {
+        return Ok(format!(
+            "{} has already been reached; the chain is {} blocks past it (block {} at {}).",
+            name,
+            average.height - height,
+            average.height,
+            estimate_time_tool::rfc3339(average.time)
+        ));
+    }
+
+    let remaining = height - average.height;
+    let seconds = remaining as f64 * average.seconds;
+    let Some(reached) = at(&average, seconds) else {
+        return Ok(format!(
+            "{} is {} blocks away, too far in the future to estimate when it will be reached.",
+            name, remaining
+        ));
+    };
+    let mut output = format!(
+        "{} is {} blocks away: about {} to go, around {} (at {:.2}s per block, the average of \
+         the last {} blocks).",
+        name,
+        remaining,
+        countdown(seconds),
+        estimate_time_tool::rfc3339(reached),
+        average.seconds,
+        average.blocks
+    );
+
+    // Blocks coming faster or slower lately would move the target
+    if let Some(recent) = recent.filter(|recent| recent.blocks < average.blocks) {
+        let recent_seconds = remaining as f64 * recent.seconds;
+        let recent_reached = at(&average, recent_seconds);
+        if let Some(recent_reached) =
+            recent_reached.filter(|_| (recent_seconds - seconds).abs() >= 60.0)
+        {
+            output.push_str(&format!(
+                " At the {:.2}s per block of the last {} blocks, it would be reached around {} \
+                 instead.",
+                recent.seconds,
+                recent.blocks,
+                estimate_time_tool::rfc3339(recent_reached)
+            ));
+        }
+    }
+
+    Ok(output)
+}
+
+/// The time `seconds` after the head, unless it's past the calendar
+fn at(head: &BlockTime, seconds: f64) -> Option<chrono::DateTime<chrono::Utc>> {
+    let ahead = Duration::try_milliseconds((seconds * 1000.0) as i64)?;
+    head.time.checked_add_signed(ahead)
+}
+
+/// `seconds` in days, hours and minutes, e.g. `2 days, 3 hours and 15 minutes`
+fn countdown(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds < 60 {
+        return plural(seconds, "second");
+    }
+    let minutes = seconds.div_ceil(60);
+    let parts: Vec<String> = [
+        (minutes / 1440, "day"),
+        (minutes % 1440 / 60, "hour"),
+        (minutes % 60, "minute"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, unit)| plural(count, unit))
+    .collect();
+    match parts.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => unreachable!("a minute or more has a part"),
+    }
+}
+
+/// `the v3 upgrade` as `The v3 upgrade`, to start a sentence with
+fn capitalized(label: &str) -> String {
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn plural(count: u64, unit: &str) -> String {
+    match count {
+        1 => format!("1 {}", unit),
+        count => format!("{} {}s", count, unit),
+    }
+}
+
+impl Tool for CountdownTool {
+    const NAME: &'static str = "countdown";
+
+    type Args = CountdownArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Count down to a future block height, such as a network upgrade \
+                          height: how many blocks and how much time remain, and when the height \
+                          should be reached, from the block time measured over the last 1000 \
+                          and 100 blocks."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The height to count down to",
+                        "examples": [5000000],
+                    },
+                    "label": {
+                        "type": "string",
+                        "description": "What happens at the height, to name it in the answer",
+                        "examples": ["the v3 upgrade"],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["height"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.count_down(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,35 @@
+//! Continuation tokens for tools that list long results a page at a time.
+//!
+//! A tool ends a full page with a cursor, which the model passes back to the same tool to get
+//! the next page. The cursor names the tool, the offset of the next page and a digest of what
+//! is being listed (such as the network and the address), so that one passed to another tool,
+//! or with other arguments, is rejected rather than silently continuing the wrong listing.
+
+use crate::celestia_search_tool::CelestiaSearchError;
+
+/// The cursor continuing `tool`'s listing of `scope` at `offset`.
+pub fn encode(tool: &str, scope: &str, offset: u64) -> String {
+    format!("{}:{}:{:08x}", tool, offset, digest(scope))
+}
+
+/// The offset `cursor` continues `tool`'s listing of `scope` at.
+pub fn decode(cursor: &str, tool: &str, scope: &str) -> Result<u64, CelestiaSearchError> {
+    let invalid = || CelestiaSearchError::InvalidCursor(cursor.to_string());
+    let mut parts = cursor.trim().split(':');
+    let (Some(name), Some(offset), Some(checksum), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    if name != tool || checksum != format!("{:08x}", digest(scope)) {
+        return Err(invalid());
+    }
+    offset.parse().map_err(|_| invalid())
+}
+
+/// 32-bit FNV-1a, which is stable across builds, unlike the standard library's hasher
+fn digest(scope: &str) -> u32 {
+    scope.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
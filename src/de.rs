@@ -0,0 +1,31 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Deserializes a value that Celenium sends either as a JSON number or as a string holding
+/// one, such as `tx_count` or `fee`.
+///
+/// Values that are neither, or that don't parse, are errors rather than zeroes.
+pub fn string_or_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let text = match Value::deserialize(deserializer)? {
+        Value::String(text) => text,
+        Value::Number(number) => number.to_string(),
+        other => {
+            return Err(D::Error::custom(format!(
+                "expected a number, got {}",
+                other
+            )))
+        }
+    };
+    text.trim()
+        .parse()
+        .map_err(|e| D::Error::custom(format!("invalid number `{}`: {}", text, e)))
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::azure::AzureConfig;
+use crate::celenium::CeleniumClient;
+use crate::celestia_search_tool::REQUEST_TIMEOUT;
+use crate::gemini::{GeminiConfig, GEMINI_API_URL};
+
+/// The OpenAI API the assistant's models are served from.
+pub const OPENAI_API_URL: &str = "https://api.openai.com/v1";
+
+/// How a health check turned out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// Something works less well than it could, but questions can still be answered.
+    Warning,
+    /// Something the assistant needs is broken.
+    Failed,
+}
+
+/// The outcome of one health check, such as reaching an endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+}
+
+impl Check {
+    pub fn ok(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, Status::Ok, detail)
+    }
+
+    pub fn warning(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, Status::Warning, detail)
+    }
+
+    pub fn failed(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, Status::Failed, detail)
+    }
+
+    fn new(name: impl Into<String>, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    /// Reports a failure as a warning, for things the assistant can do without.
+    pub fn optional(mut self) -> Self {
+        if self.status == Status::Failed {
+            self.status = Status::Warning;
+        }
+        self
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self.status {
+            Status::Ok => "ok",
+            Status::Warning => "warn",
+            Status::Failed => "FAIL",
+        };
+        write!(f, "[{:>4}] {}: {}", mark, self.name, self.detail)
+    }
+}
+
+/// Checks that the OpenAI API at `base_url` accepts `api_key`.
+pub async fn check_openai(base_url: &str, api_key: Option<&str>) -> Check {
+    const NAME: &str = "OpenAI API key";
+    let Some(api_key) = api_key.filter(|key| !key.trim().is_empty()) else {
+        return Check::failed(NAME, "OPENAI_API_KEY is not set");
+    };
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/models", base_url.trim_end_matches('/')))
+        .bearer_auth(api_key)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => Check::ok(NAME, "accepted"),
+        Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+            Check::failed(NAME, "rejected by the API; check OPENAI_API_KEY")
+        }
+        Ok(response) => Check::failed(
+            NAME,
+            format!("{} answered with status {}", base_url, response.status()),
+        ),
+        Err(e) => Check::failed(NAME, format!("could not reach {}: {}", base_url, e)),
+    }
+}
+
+/// Checks that the Azure OpenAI resource of `config` accepts its key.
+pub async fn check_azure(config: Option<&AzureConfig>) -> Check {
+    const NAME: &str = "Azure OpenAI";
+    let Some(config) = config else {
+        return Check::failed(NAME, "the config has no [azure] section");
+    };
+    let Some(api_key) = config.api_key().filter(|key| !key.trim().is_empty()) else {
+        return Check::failed(NAME, "AZURE_OPENAI_API_KEY is not set");
+    };
+
+    let endpoint = config.endpoint.trim_end_matches('/');
+    let response = reqwest::Client::new()
+        .get(format!("{}/openai/models", endpoint))
+        .query(&[("api-version", config.api_version())])
+        .header("api-key", api_key)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => Check::ok(NAME, "accepted"),
+        Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+            Check::failed(NAME, "rejected by the resource; check AZURE_OPENAI_API_KEY")
+        }
+        Ok(response) => Check::failed(
+            NAME,
+            format!("{} answered with status {}", endpoint, response.status()),
+        ),
+        Err(e) => Check::failed(NAME, format!("could not reach {}: {}", endpoint, e)),
+    }
+}
+
+/// Checks that the Gemini API accepts the key of `config`.
+pub async fn check_gemini(config: Option<&GeminiConfig>) -> Check {
+    const NAME: &str = "Gemini API key";
+    let config = config.cloned().unwrap_or_default();
+    let Some(api_key) = config.api_key().filter(|key| !key.trim().is_empty()) else {
+        return Check::failed(NAME, "GEMINI_API_KEY is not set");
+    };
+
+    let base_url = config.base_url.as_deref().unwrap_or(GEMINI_API_URL);
+    let response = reqwest::Client::new()
+        .get(format!("{}/models", base_url.trim_end_matches('/')))
+        .header("x-goog-api-key", api_key)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => Check::ok(NAME, "accepted"),
+        // Gemini answers an invalid key as a bad request
+        Ok(response)
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::BAD_REQUEST
+                    | reqwest::StatusCode::UNAUTHORIZED
+                    | reqwest::StatusCode::FORBIDDEN
+            ) =>
+        {
+            Check::failed(NAME, "rejected by the API; check GEMINI_API_KEY")
+        }
+        Ok(response) => Check::failed(
+            NAME,
+            format!("{} answered with status {}", base_url, response.status()),
+        ),
+        Err(e) => Check::failed(NAME, format!("could not reach {}: {}", base_url, e)),
+    }
+}
+
+/// Checks that the Celenium API behind `client` answers with a chain head.
+pub async fn check_celenium(client: &CeleniumClient) -> Check {
+    let name = format!("Celenium {}", client.network());
+    match client.get("/head").await {
+        Ok(head) => match head["last_height"].as_u64() {
+            Some(height) => Check::ok(
+                name,
+                format!(
+                    "{} ({} API) is at height {}",
+                    client.base_url(),
+                    client.api_version(),
+                    height
+                ),
+            ),
+            None => Check::failed(
+                name,
+                format!("{} answered without a chain head", client.base_url()),
+            ),
+        },
+        Err(e) => Check::failed(name, format!("{}: {}", client.base_url(), e)),
+    }
+}
+
+/// Checks that files can be written to the directory at `path`, creating it if needed.
+pub fn check_dir(name: &str, path: &Path) -> Check {
+    let probe = path.join(".doctor-probe");
+    let result = std::fs::create_dir_all(path)
+        .and_then(|()| std::fs::write(&probe, b"ok"))
+        .and_then(|()| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::ok(name, format!("{} is writable", path.display())),
+        Err(e) => Check::failed(name, format!("cannot write to {}: {}", path.display(), e)),
+    }
+}
+
+/// The number of checks that failed, and that only warned.
+pub fn problems(checks: &[Check]) -> (usize, usize) {
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+    (count(Status::Failed), count(Status::Warning))
+}
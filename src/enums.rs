@@ -0,0 +1,62 @@
+use serde::Deserialize;
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+
+/// Celenium's vocabularies, fetched once so that tool arguments can be checked before a call
+/// and the model knows the exact names to use.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Enums {
+    #[serde(default, rename = "message_type")]
+    pub message_types: Vec<String>,
+    #[serde(default, rename = "event_type")]
+    pub event_types: Vec<String>,
+    #[serde(default, rename = "categories")]
+    pub rollup_categories: Vec<String>,
+    #[serde(default, rename = "status")]
+    pub statuses: Vec<String>,
+}
+
+impl Enums {
+    /// Fetches the vocabularies from the `/enums` endpoint.
+    pub async fn fetch(blocks: &CelestiaSearchTool) -> Result<Self, CelestiaSearchError> {
+        let data = blocks.fetch("/enums").await?;
+        serde_json::from_value(data).map_err(|e| CelestiaSearchError::Deserialization {
+            url: "/enums".to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Checks that `value` is one of the `known` values of `kind`, or passes if none are known.
+    pub fn check(
+        kind: &'static str,
+        known: &[String],
+        value: &str,
+    ) -> Result<(), CelestiaSearchError> {
+        if known.is_empty() || known.iter().any(|name| name == value) {
+            return Ok(());
+        }
+        Err(CelestiaSearchError::UnknownValue {
+            kind,
+            value: value.to_string(),
+            known: known.join(", "),
+        })
+    }
+
+    /// Lists the vocabularies for the preamble.
+    pub fn preamble(&self) -> String {
+        let mut preamble =
+            "Celenium names these exactly as follows; use these names in tool arguments:"
+                .to_string();
+        for (name, values) in [
+            ("Message types", &self.message_types),
+            ("Event types", &self.event_types),
+            ("Rollup categories", &self.rollup_categories),
+            ("Transaction statuses", &self.statuses),
+        ] {
+            if !values.is_empty() {
+                preamble.push_str(&format!("\n- {}: {}", name, values.join(", ")));
+            }
+        }
+        preamble
+    }
+}
@@ -0,0 +1,289 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// How many recent blocks the average block time is taken over.
+pub const BLOCK_TIME_WINDOW: u64 = 100;
+
+/// The height or time to convert.
+#[derive(Deserialize)]
+pub struct EstimateTimeArgs {
+    /// The height to estimate the time of.
+    height: Option<u64>,
+    /// The time to estimate the height at, in RFC 3339, as a date, or relative to now.
+    time: Option<String>,
+    /// The networks to estimate on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Converts between heights and wall-clock times using the average recent block time.
+pub struct EstimateTimeTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl EstimateTimeTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn estimate(&self, args: EstimateTimeArgs) -> Result<String, CelestiaSearchError> {
+        let target = match (args.height, args.time.as_deref()) {
+            (Some(height), None) => Target::Height(height),
+            (None, Some(time)) => match parse_time(time, Utc::now()) {
+                Some(time) => Target::Time(time),
+                None => {
+                    return Ok(format!(
+                        "`{}` is not a time; give one such as 2025-01-01T00:00:00Z, 2025-01-01, \
+                         midnight or +6h.",
+                        time
+                    ))
+                }
+            },
+            _ => return Ok("Give a height or a time to convert, but not both.".to_string()),
+        };
+
+        self.blocks
+            .across(&args.network, |blocks| estimate(blocks, target))
+            .await
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Height(u64),
+    Time(DateTime<Utc>),
+}
+
+/// The latest block, and the seconds blocks took on average over the window before it
+#[derive(Clone, Copy)]
+pub(crate) struct BlockTime {
+    pub height: u64,
+    pub time: DateTime<Utc>,
+    pub seconds: f64,
+    pub blocks: u64,
+}
+
+/// Averages the time of the `window` blocks up to the head, if the indexer has their times.
+pub(crate) async fn block_time(
+    blocks: &CelestiaSearchTool,
+    head: &Value,
+    window: u64,
+) -> Result<Option<BlockTime>, CelestiaSearchError> {
+    let (Some(height), Some(time)) = (head["last_height"].as_u64(), timestamp(&head["last_time"]))
+    else {
+        return Ok(None);
+    };
+    let from = height.saturating_sub(window).max(1);
+    if from == height {
+        return Ok(None);
+    }
+    let earlier = blocks.fetch(&format!("/block/{}", from)).await?;
+    Ok(timestamp(&earlier["time"]).map(|start| BlockTime {
+        height,
+        time,
+        seconds: (time - start).num_milliseconds() as f64 / 1000.0 / (height - from) as f64,
+        blocks: height - from,
+    }))
+}
+
+async fn estimate(
+    blocks: &CelestiaSearchTool,
+    target: Target,
+) -> Result<String, CelestiaSearchError> {
+    let head = blocks.fetch("/head").await?;
+
+    // Blocks already produced have a recorded time
+    if let Target::Height(height) = target {
+        if head["last_height"]
+            .as_u64()
+            .is_some_and(|head| height <= head)
+        {
+            let block = blocks.fetch(&format!("/block/{}", height)).await?;
+            return Ok(match timestamp(&block["time"]) {
+                Some(time) => format!(
+                    "Block {} was produced at {} (its recorded time).",
+                    height,
+                    rfc3339(time)
+                ),
+                None => format!("The indexer has no time recorded for block {}.", height),
+            });
+        }
+    }
+
+    let Some(average) = block_time(blocks, &head, BLOCK_TIME_WINDOW).await? else {
+        return Ok("The indexer has no block times to estimate from.".to_string());
+    };
+    let basis = format!(
+        "{:.2}s per block on average over the last {} blocks, from block {} at {}",
+        average.seconds,
+        average.blocks,
+        average.height,
+        rfc3339(average.time)
+    );
+
+    Ok(match target {
+        Target::Height(height) => {
+            let seconds = (height - average.height) as f64 * average.seconds;
+            let Some(time) = chrono::Duration::try_milliseconds((seconds * 1000.0) as i64)
+                .and_then(|ahead| average.time.checked_add_signed(ahead))
+            else {
+                return Ok(format!(
+                    "Block {} is too far in the future to estimate when it will be produced.",
+                    height
+                ));
+            };
+            format!(
+                "Block {} is {} blocks past the head and should be produced around {}, in about \
+                 {} (at {}).",
+                height,
+                height - average.height,
+                rfc3339(time),
+                span(seconds),
+                basis
+            )
+        }
+        Target::Time(time) => {
+            let seconds = (time - average.time).num_milliseconds() as f64 / 1000.0;
+            let blocks = (seconds / average.seconds).round() as i64;
+            let height = (average.height as i64 + blocks).max(1);
+            let tense = if seconds >= 0.0 { "should be" } else { "was" };
+            format!(
+                "At {} the chain {} at about height {} ({} {} the head, at {}).",
+                rfc3339(time),
+                tense,
+                height,
+                span(seconds.abs()),
+                if seconds >= 0.0 { "after" } else { "before" },
+                basis
+            )
+        }
+    })
+}
+
+/// A time in RFC 3339, a date taken as its midnight UTC, or `now`, the coming `midnight` (UTC)
+/// and offsets from now such as `+6h` or `-2d`, as the model doesn't know the date
+fn parse_time(time: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let time = time.trim();
+    match time {
+        "now" => return Some(now),
+        "midnight" => {
+            return Some(
+                (now.date_naive().succ_opt()?)
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc(),
+            )
+        }
+        _ => {}
+    }
+    if let Some(sign @ ('+' | '-')) = time.chars().next() {
+        let offset = &time[1..];
+        let (count, unit) = offset.split_at(offset.find(|c: char| !c.is_ascii_digit())?);
+        let unit = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86400,
+            _ => return None,
+        };
+        let seconds = count.parse::<i64>().ok()?.checked_mul(unit)?;
+        let offset = chrono::Duration::try_seconds(seconds)?;
+        return match sign {
+            '+' => now.checked_add_signed(offset),
+            _ => now.checked_sub_signed(offset),
+        };
+    }
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.to_utc())
+        .ok()
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(time, "%Y-%m-%d").ok()?;
+            Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+        })
+}
+
+pub(crate) fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_str()?)
+        .ok()
+        .map(|time| time.to_utc())
+}
+
+pub(crate) fn rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// `seconds` in the two largest units, e.g. `3d 4h` or `12m 5s`
+pub(crate) fn span(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    let units = [(86400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+    let Some(first) = units.iter().position(|(unit, _)| seconds >= *unit) else {
+        return "0s".to_string();
+    };
+    let mut remaining = seconds;
+    units[first..]
+        .iter()
+        .take(2)
+        .map(|(unit, suffix)| {
+            let count = remaining / unit;
+            remaining %= unit;
+            format!("{}{}", count, suffix)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Tool for EstimateTimeTool {
+    const NAME: &'static str = "estimate_time";
+
+    type Args = EstimateTimeArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Convert between block heights and wall-clock times: when a block was \
+                          or will be produced, or what height the chain was or will be at a \
+                          given time. Past blocks get their recorded time; everything else is \
+                          estimated from the average time of recent blocks. Give either a \
+                          height or a time."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The height to find the time of",
+                        "examples": [1000000],
+                    },
+                    "time": {
+                        "type": "string",
+                        "description": "The time to find the height at, in RFC 3339 (UTC \
+                                        unless an offset is given), a date for its midnight \
+                                        UTC, `now`, `midnight` for the coming midnight UTC, or \
+                                        an offset from now in s, m, h or d",
+                        "examples": ["2025-01-01T00:00:00Z", "2025-01-01", "midnight", "+6h"],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.estimate(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,571 @@
+#[cfg(feature = "node-rpc")]
+use std::sync::Arc;
+
+use futures::{StreamExt, TryStreamExt};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::DEFAULT_CONCURRENCY;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+#[cfg(feature = "node-rpc")]
+use crate::node::NodeClient;
+
+/// The most matching transactions listed by a single call.
+pub const MAX_LIMIT: usize = 100;
+
+/// How many blocks are searched when no range is given, through a consensus node.
+pub const DEFAULT_RANGE: u64 = 10_000;
+
+/// The most blocks scanned through the indexer, which lists events block by block.
+pub const MAX_SCAN_BLOCKS: u64 = 20;
+
+/// How many events the indexer lists per page.
+const EVENTS_PAGE: usize = 100;
+
+/// The most pages of events read per block, enough for blocks with a few hundred transactions.
+const MAX_EVENT_PAGES: usize = 10;
+
+/// The events to search for, and over which blocks.
+#[derive(Deserialize)]
+pub struct EventSearchArgs {
+    /// Conditions on events joined by `AND`, each `type.attribute=value` or `type.attribute`.
+    query: String,
+    /// The first block searched.
+    from: Option<u64>,
+    /// The last block searched, the chain head by default.
+    to: Option<u64>,
+    /// How many matching transactions to list.
+    #[serde(default = "default_limit")]
+    limit: usize,
+    /// The networks to search, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+/// A condition on the events of a transaction, such as `transfer.recipient=celestia1...`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventCondition {
+    pub event_type: String,
+    pub attribute: String,
+    /// The value the attribute must have, or `None` if it only has to be present
+    pub value: Option<String>,
+}
+
+impl EventCondition {
+    /// Whether the event of `event_type` with `attributes` meets the condition.
+    pub fn matches(&self, event_type: &str, attributes: &[(String, String)]) -> bool {
+        event_type == self.event_type
+            && attributes.iter().any(|(key, value)| {
+                key == &self.attribute && self.value.as_ref().is_none_or(|wanted| wanted == value)
+            })
+    }
+
+    /// The condition in CometBFT's query language.
+    #[cfg(feature = "node-rpc")]
+    fn to_query(&self) -> String {
+        match &self.value {
+            Some(value) => format!("{}.{}='{}'", self.event_type, self.attribute, value),
+            None => format!("{}.{} EXISTS", self.event_type, self.attribute),
+        }
+    }
+}
+
+/// Parses conditions joined by `AND`, such as `transfer.recipient=celestia1... AND
+/// message.action=/cosmos.bank.v1beta1.MsgSend`.
+pub fn parse_query(query: &str) -> Result<Vec<EventCondition>, CelestiaSearchError> {
+    let invalid = |reason: &str| {
+        CelestiaSearchError::InvalidEventQuery(format!("`{}`: {}", query.trim(), reason))
+    };
+    let mut conditions = Vec::new();
+    for part in split_and(query) {
+        let (key, value) = match part.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim())),
+            None => (part.trim(), None),
+        };
+        let Some((event_type, attribute)) = key.rsplit_once('.') else {
+            return Err(invalid(
+                "each condition names an event type and an attribute, as in \
+                                `transfer.recipient=celestia1...`",
+            ));
+        };
+        let name = |name: &str| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        };
+        if !name(event_type) || !name(attribute) {
+            return Err(invalid(
+                "event types and attributes are letters, digits, `_` and `-`",
+            ));
+        }
+        // Quotes would let a value break out of the node's query
+        let value = value.map(|value| value.trim_matches(|c| c == '\'' || c == '"'));
+        if value.is_some_and(|value| value.is_empty() || value.contains(['\'', '"'])) {
+            return Err(invalid("values can't be empty or hold quotes"));
+        }
+        conditions.push(EventCondition {
+            event_type: event_type.to_string(),
+            attribute: attribute.to_string(),
+            value: value.map(str::to_string),
+        });
+    }
+    match conditions.is_empty() {
+        true => Err(invalid("there is no condition")),
+        false => Ok(conditions),
+    }
+}
+
+/// The parts of `query` between `AND`s, whatever their case
+fn split_and(query: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = query;
+    while let Some(index) = rest
+        .char_indices()
+        .find(|(i, _)| rest[*i..].len() >= 5 && rest[*i..*i + 5].eq_ignore_ascii_case(" and "))
+        .map(|(i, _)| i)
+    {
+        parts.push(&rest[..index]);
+        rest = &rest[index + 5..];
+    }
+    parts.push(rest);
+    parts
+        .into_iter()
+        .filter(|part| !part.trim().is_empty())
+        .collect()
+}
+
+/// An event's type and attributes.
+type Event = (String, Vec<(String, String)>);
+
+/// A transaction with events meeting every condition of the query.
+struct Match {
+    height: u64,
+    hash: Option<String>,
+    failed: bool,
+    /// The events of the types the query asks about, with their attributes
+    events: Vec<Event>,
+}
+
+impl Match {
+    /// Keeps the transaction if its events meet every condition
+    fn new(
+        height: u64,
+        hash: Option<String>,
+        events: Vec<Event>,
+        conditions: &[EventCondition],
+    ) -> Option<Self> {
+        let met = conditions.iter().all(|condition| {
+            events
+                .iter()
+                .any(|(event_type, attributes)| condition.matches(event_type, attributes))
+        });
+        met.then(|| Self::listed(height, hash, false, events, conditions))
+    }
+
+    /// The transaction with only the events of the types `conditions` ask about
+    fn listed(
+        height: u64,
+        hash: Option<String>,
+        failed: bool,
+        mut events: Vec<Event>,
+        conditions: &[EventCondition],
+    ) -> Self {
+        events.retain(|(event_type, _)| conditions.iter().any(|c| &c.event_type == event_type));
+        Self {
+            height,
+            hash,
+            failed,
+            events,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let events: Vec<String> = self
+            .events
+            .iter()
+            .map(|(event_type, attributes)| {
+                let attributes: Vec<String> = attributes
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                format!("{}{{{}}}", event_type, attributes.join(", "))
+            })
+            .collect();
+        format!(
+            "- {} at height {}{}: {}",
+            match &self.hash {
+                Some(hash) => format!("tx {}", hash),
+                None => "block event".to_string(),
+            },
+            self.height,
+            if self.failed { " (failed)" } else { "" },
+            events.join("; ")
+        )
+    }
+}
+
+/// Searches the events of transactions by type and attribute, through a consensus node's
+/// `tx_search` when one is configured, or by scanning blocks' events in the indexer otherwise.
+pub struct EventSearchTool {
+    blocks: CelestiaSearchTool,
+    #[cfg(feature = "node-rpc")]
+    node: Option<Arc<NodeClient>>,
+}
+
+impl EventSearchTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self {
+            blocks,
+            #[cfg(feature = "node-rpc")]
+            node: None,
+        }
+    }
+
+    /// Searches the configured network through `node`'s consensus node.
+    #[cfg(feature = "node-rpc")]
+    pub fn with_node(mut self, node: Arc<NodeClient>) -> Self {
+        self.node = Some(node);
+        self
+    }
+
+    async fn search(&self, args: EventSearchArgs) -> Result<String, CelestiaSearchError> {
+        let conditions = parse_query(&args.query)?;
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+
+        // The consensus node only serves the configured network
+        #[cfg(feature = "node-rpc")]
+        if let Some(node) = self.node.as_ref().filter(|_| args.network.is_empty()) {
+            let to = match args.to {
+                Some(to) => to,
+                None => self.blocks.chain_head().await?,
+            };
+            let from = args
+                .from
+                .unwrap_or_else(|| to.saturating_sub(DEFAULT_RANGE - 1).max(1));
+            check_range(from, to)?;
+            return tx_search(node, &conditions, from, to, limit).await;
+        }
+
+        let conditions = &conditions;
+        self.blocks
+            .across(&args.network, |blocks| async move {
+                let to = match args.to {
+                    Some(to) => to,
+                    None => blocks.chain_head().await?,
+                };
+                let from = args
+                    .from
+                    .unwrap_or_else(|| to.saturating_sub(MAX_SCAN_BLOCKS - 1).max(1));
+                check_range(from, to)?;
+                if to - from + 1 > MAX_SCAN_BLOCKS {
+                    return Err(CelestiaSearchError::InvalidRange(format!(
+                        "without a consensus node, events are searched block by block through \
+                         the indexer, at most {} blocks at a time; narrow the range or configure \
+                         the node's `consensus_url`",
+                        MAX_SCAN_BLOCKS
+                    )));
+                }
+                scan(blocks, conditions, from, to, limit).await
+            })
+            .await
+    }
+}
+
+fn check_range(from: u64, to: u64) -> Result<(), CelestiaSearchError> {
+    if from > to {
+        return Err(CelestiaSearchError::InvalidRange(format!(
+            "`from` ({}) is above `to` ({})",
+            from, to
+        )));
+    }
+    Ok(())
+}
+
+/// Searches through CometBFT's index of transaction events, latest first
+#[cfg(feature = "node-rpc")]
+async fn tx_search(
+    node: &NodeClient,
+    conditions: &[EventCondition],
+    from: u64,
+    to: u64,
+    limit: usize,
+) -> Result<String, CelestiaSearchError> {
+    let mut query: Vec<String> = conditions.iter().map(EventCondition::to_query).collect();
+    query.push(format!("tx.height>={}", from));
+    query.push(format!("tx.height<={}", to));
+    let params = json!({
+        "query": query.join(" AND "),
+        "prove": false,
+        "page": "1",
+        "per_page": limit.to_string(),
+        "order_by": "desc",
+    });
+    let result: Value = node
+        .call_consensus("tx_search", params)
+        .await
+        .map_err(|e| CelestiaSearchError::ApiError(e.to_string()))?;
+
+    let total = match &result["total_count"] {
+        Value::String(total) => total.parse().unwrap_or(0),
+        total => total.as_u64().unwrap_or(0),
+    };
+    let matches: Vec<Match> = result["txs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|tx| {
+            let height = crate::sampling_tool::height(&tx["height"])?;
+            let hash = tx["hash"].as_str().map(str::to_string);
+            let failed = tx["tx_result"]["code"].as_u64().unwrap_or(0) != 0;
+            let events = tx["tx_result"]["events"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|event| {
+                    let attributes = event["attributes"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|attribute| {
+                            let key = attribute["key"].as_str()?;
+                            let value = attribute["value"].as_str().unwrap_or_default();
+                            Some(decode_attribute(key, value))
+                        })
+                        .collect();
+                    (
+                        event["type"].as_str().unwrap_or_default().to_string(),
+                        attributes,
+                    )
+                })
+                .collect();
+            // The node already matched the query
+            Some(Match::listed(height, hash, failed, events, conditions))
+        })
+        .collect();
+
+    Ok(report(
+        &matches,
+        total,
+        from,
+        to,
+        conditions.len(),
+        "the consensus node's index",
+    ))
+}
+
+/// The attribute as text: nodes on CometBFT 0.34 encode keys and values in base64, later
+/// versions don't
+#[cfg(feature = "node-rpc")]
+fn decode_attribute(key: &str, value: &str) -> (String, String) {
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+
+    let decode = |text: &str| {
+        let bytes = BASE64.decode(text).ok()?;
+        String::from_utf8(bytes).ok()
+    };
+    // A key decoding to a plain name was encoded, as was its value
+    match decode(key) {
+        Some(decoded)
+            if !decoded.is_empty()
+                && decoded
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) =>
+        {
+            let value = decode(value).unwrap_or_else(|| value.to_string());
+            (decoded, value)
+        }
+        _ => (key.to_string(), value.to_string()),
+    }
+}
+
+/// Reads the events of each block in the range from the indexer, grouped by transaction
+async fn scan(
+    blocks: &CelestiaSearchTool,
+    conditions: &[EventCondition],
+    from: u64,
+    to: u64,
+    limit: usize,
+) -> Result<String, CelestiaSearchError> {
+    let pages: Vec<(u64, Vec<Value>)> = futures::stream::iter(from..=to)
+        .map(|height| async move {
+            Ok::<_, CelestiaSearchError>((height, block_events(blocks, height).await?))
+        })
+        .buffered(DEFAULT_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let mut matches = Vec::new();
+    // Latest first, as the node lists them
+    for (height, events) in pages.into_iter().rev() {
+        let mut by_tx: Vec<(Option<u64>, Vec<Event>)> = Vec::new();
+        for event in &events {
+            let tx = event["tx_id"].as_u64();
+            let attributes = event["data"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| {
+                    let text = value.as_str().map(str::to_string);
+                    (key.clone(), text.unwrap_or_else(|| value.to_string()))
+                })
+                .collect();
+            let parsed = (
+                event["type"].as_str().unwrap_or_default().to_string(),
+                attributes,
+            );
+            match by_tx.iter_mut().find(|(id, _)| *id == tx) {
+                Some((_, events)) => events.push(parsed),
+                None => by_tx.push((tx, vec![parsed])),
+            }
+        }
+        matches.extend(by_tx.into_iter().filter_map(|(tx, events)| {
+            // The indexer identifies transactions by its own ids in the events
+            let hash = tx.map(|id| format!("with indexer id {}", id));
+            Match::new(height, hash, events, conditions)
+        }));
+    }
+
+    let total = matches.len() as u64;
+    matches.truncate(limit);
+    Ok(report(
+        &matches,
+        total,
+        from,
+        to,
+        conditions.len(),
+        "the indexer's block events",
+    ))
+}
+
+/// Every event of the block at `height`, a page at a time
+async fn block_events(
+    blocks: &CelestiaSearchTool,
+    height: u64,
+) -> Result<Vec<Value>, CelestiaSearchError> {
+    let mut events = Vec::new();
+    for page in 0..MAX_EVENT_PAGES {
+        let endpoint = format!(
+            "/block/{}/events?limit={}&offset={}",
+            height,
+            EVENTS_PAGE,
+            page * EVENTS_PAGE
+        );
+        let data = blocks.fetch(&endpoint).await?;
+        let page: Vec<Value> = data.as_array().cloned().unwrap_or_default();
+        let full = page.len() == EVENTS_PAGE;
+        events.extend(page);
+        if !full {
+            break;
+        }
+    }
+    Ok(events)
+}
+
+fn report(
+    matches: &[Match],
+    total: u64,
+    from: u64,
+    to: u64,
+    conditions: usize,
+    source: &str,
+) -> String {
+    if matches.is_empty() {
+        return format!(
+            "No transaction between blocks {} and {} has events meeting the {} condition(s), \
+             according to {}.",
+            from, to, conditions, source
+        );
+    }
+    let mut output = format!(
+        "{} transaction(s) between blocks {} and {} have events meeting the {} condition(s), \
+         according to {}",
+        total, from, to, conditions, source
+    );
+    match total > matches.len() as u64 {
+        true => output.push_str(&format!("; the latest {} are listed:", matches.len())),
+        false => output.push(':'),
+    }
+    for found in matches {
+        output.push('\n');
+        output.push_str(&found.describe());
+    }
+    output
+}
+
+impl Tool for EventSearchTool {
+    const NAME: &'static str = "event_search";
+
+    type Args = EventSearchArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Search the Cosmos events emitted by transactions for those of a type with an \
+                 attribute, such as transfers to an address, over a range of blocks: for ad-hoc \
+                 forensic questions the other tools don't cover. Lists up to {} matching \
+                 transactions, latest first, with their matching events. Without a consensus \
+                 node configured, at most {} blocks are searched.",
+                MAX_LIMIT, MAX_SCAN_BLOCKS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Conditions joined by AND, each `type.attribute=value` or \
+                                        `type.attribute` for the attribute to be present",
+                        "examples": [
+                            "transfer.recipient=celestia1qnk2n4nlkpw9xfqntladh74w6ujtulwnmxnh3k",
+                            "message.action=/cosmos.staking.v1beta1.MsgDelegate AND \
+                             delegate.validator=celestiavaloper1q3v5cugc8cdpud87u4zwy0a74uxkk6u4q4gx4p",
+                        ],
+                    },
+                    "from": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The first block searched",
+                        "examples": [2000000],
+                    },
+                    "to": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The last block searched, the chain head by default",
+                        "examples": [2010000],
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": "How many matching transactions to list",
+                        "examples": [20],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["query"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.search(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,49 @@
+use std::io::{self, Write};
+
+use serde_json::Value;
+
+use crate::celestia_search_tool::CelestiaResponseFields;
+
+/// Writes per-block stats as CSV, one row per block, starting with its `height`.
+pub fn write_csv<'a>(
+    rows: impl IntoIterator<Item = &'a CelestiaResponseFields>,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let mut header_written = false;
+
+    for stats in rows {
+        // Serializing through a JSON object keeps the columns in step with the model's fields
+        let Value::Object(mut fields) = serde_json::to_value(stats)? else {
+            return Err(io::Error::other(
+                "block stats did not serialize to an object",
+            ));
+        };
+        fields.remove("height");
+
+        if !header_written {
+            let header: Vec<&str> = fields.keys().map(String::as_str).collect();
+            writeln!(writer, "height,{}", header.join(","))?;
+            header_written = true;
+        }
+
+        let values: Vec<String> = fields
+            .values()
+            .map(|value| match value {
+                Value::String(s) => escape_field(s),
+                other => other.to_string(),
+            })
+            .collect();
+        writeln!(writer, "{},{}", stats.height, values.join(","))?;
+    }
+
+    writer.flush()
+}
+
+/// Quotes a field if it contains characters that are significant in CSV.
+fn escape_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,148 @@
+//! Answers to the simplest questions, such as "gas fee of block 9999" or "current height",
+//! looked up and phrased locally instead of by the model, so they cost no tokens and no
+//! round-trip to it. Anything else, and any shortcut whose lookup fails, is left to the agent.
+//!
+//! Questions are matched by their words once normalized, ignoring filler such as "what is the",
+//! so only questions asking for exactly one of the shortcuts are taken.
+
+use rig::tool::Tool;
+use serde_json::json;
+
+use crate::accounting::TokenUsage;
+use crate::answer_cache;
+use crate::assistant::{ToolCall, Turn};
+use crate::audit;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::time::Instant;
+
+/// The name the chain head lookup is recorded under, as no tool of the agent makes it.
+pub const CHAIN_HEAD: &str = "chain_head";
+
+/// Words that don't change what a simple question asks.
+const FILLER: &[&str] = &[
+    "a",
+    "at",
+    "celestia",
+    "celestia's",
+    "for",
+    "get",
+    "give",
+    "in",
+    "is",
+    "me",
+    "number",
+    "of",
+    "please",
+    "show",
+    "tell",
+    "the",
+    "was",
+    "what",
+    "what's",
+    "whats",
+];
+
+/// Words a fee question may have besides "fee", "block" and the height.
+const FEE_EXTRAS: &[&str] = &["gas", "height", "total"];
+
+/// The words of a chain head question, of which it must have one of [`HEAD_MARKERS`].
+const HEAD_WORDS: &[&str] = &[
+    "block", "chain", "current", "head", "height", "latest", "tip",
+];
+
+const HEAD_MARKERS: &[&str] = &["current", "head", "latest", "tip"];
+
+/// A question simple enough to be answered without the model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shortcut {
+    /// The fee of the block at a height
+    BlockFee(u64),
+    /// The height of the latest block
+    ChainHead,
+}
+
+impl Shortcut {
+    /// The shortcut `question` asks for, if it asks for nothing else.
+    pub fn parse(question: &str) -> Option<Self> {
+        let normalized = answer_cache::normalize(&question.replace('\u{2019}', "'"));
+        let words: Vec<&str> = normalized
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\''))
+            .filter(|word| !word.is_empty() && !FILLER.contains(word))
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+
+        if words.iter().all(|word| HEAD_WORDS.contains(word)) {
+            return words
+                .iter()
+                .any(|word| HEAD_MARKERS.contains(word))
+                .then_some(Self::ChainHead);
+        }
+
+        let mut rest: Vec<&str> = words
+            .into_iter()
+            .filter(|word| !FEE_EXTRAS.contains(word))
+            .collect();
+        rest.sort_unstable();
+        match rest.as_slice() {
+            [number, "block", "fee"] => number
+                .parse()
+                .ok()
+                .filter(|&height| height > 0)
+                .map(Self::BlockFee),
+            _ => None,
+        }
+    }
+
+    /// The tool the shortcut looks its data up with, which the caller must be allowed to use.
+    pub fn tool(self) -> &'static str {
+        CelestiaSearchTool::NAME
+    }
+
+    /// Looks the data up with `tool` and phrases the answer, as the agent's turn would hold it
+    /// with the lookup as its tool call. It spent no tokens.
+    pub async fn answer(self, tool: &CelestiaSearchTool) -> Result<Turn, CelestiaSearchError> {
+        let started = Instant::now();
+        let network = tool.network();
+        let (looked_up, upstream) = audit::collecting(async {
+            Ok::<_, CelestiaSearchError>(match self {
+                Self::BlockFee(height) => {
+                    let fee = tool.fee(height).await?;
+                    (
+                        format!("The gas fee of block {} on {} is {}.", height, network, fee),
+                        CelestiaSearchTool::NAME,
+                        json!({ "height": height }),
+                        fee,
+                    )
+                }
+                Self::ChainHead => {
+                    let head = tool.chain_head().await?;
+                    (
+                        format!("The latest block on {} is at height {}.", network, head),
+                        CHAIN_HEAD,
+                        json!({}),
+                        head.to_string(),
+                    )
+                }
+            })
+        })
+        .await;
+        let (output, name, args, result) = looked_up?;
+
+        Ok(Turn {
+            output,
+            tool_call: Some(ToolCall {
+                name: name.to_string(),
+                args,
+                output: Some(result),
+                latency: Some(started.elapsed()),
+                upstream,
+            }),
+            steps: Vec::new(),
+            usage: TokenUsage::default(),
+            warnings: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,173 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::analytics::{self, percentile};
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The most transactions of a block whose fees are read.
+pub const MAX_TXS: usize = 1000;
+
+/// The most buckets the fees may be counted in.
+pub const MAX_BUCKETS: usize = 10;
+
+/// How many transactions are read per request.
+const PAGE: usize = 100;
+
+/// The block whose fees to break down.
+#[derive(Deserialize)]
+pub struct FeeHistogramArgs {
+    /// The height of the block.
+    height: u64,
+    /// How many buckets to count the fees in.
+    #[serde(default = "default_buckets")]
+    buckets: usize,
+    /// The networks to look the block up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_buckets() -> usize {
+    5
+}
+
+/// Computes the distribution of the fees the transactions of a block paid.
+pub struct FeeHistogramTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl FeeHistogramTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn analyze(&self, args: FeeHistogramArgs) -> Result<String, CelestiaSearchError> {
+        let buckets = args.buckets.clamp(1, MAX_BUCKETS);
+        self.blocks
+            .across(&args.network, |blocks| {
+                distribution(blocks, args.height, buckets)
+            })
+            .await
+    }
+}
+
+/// Summarizes the fees paid by the transactions of block `height`, and buckets them
+async fn distribution(
+    blocks: &CelestiaSearchTool,
+    height: u64,
+    buckets: usize,
+) -> Result<String, CelestiaSearchError> {
+    // Checks the height, and gives how many transactions there are to read
+    let stats = blocks.fetch_stats(height).await?;
+    let mut fees: Vec<f64> = Vec::new();
+    while fees.len() < MAX_TXS {
+        let txs = blocks
+            .fetch(&format!(
+                "/tx?height={}&limit={}&offset={}&sort=asc",
+                height,
+                PAGE,
+                fees.len()
+            ))
+            .await?;
+        let txs: Vec<&Value> = txs.as_array().into_iter().flatten().collect();
+        fees.extend(
+            txs.iter()
+                .map(|tx| Utia::from_value(&tx["fee"]).map_or(0.0, Utia::as_f64)),
+        );
+        if txs.len() < PAGE {
+            break;
+        }
+    }
+    fees.truncate(MAX_TXS);
+    if fees.is_empty() {
+        return Ok(format!("Block {} has no transactions.", height));
+    }
+
+    let utia = |amount: f64| price::describe_utia(&format!("{:.0}", amount), None);
+    let quantile = |p| percentile(&fees, p).unwrap_or_default();
+    let summary = analytics::summarize(&fees).expect("there are fees");
+    let mut output = format!(
+        "Fees of the {} transaction(s) in block {}: min {}, median {}, p90 {}, max {}, mean {}.",
+        fees.len(),
+        height,
+        utia(summary.min),
+        utia(quantile(50.0)),
+        utia(quantile(90.0)),
+        utia(summary.max),
+        utia(summary.mean)
+    );
+    if (stats.tx_count as usize) > fees.len() {
+        output.push_str(&format!(
+            " Only the first {} of its {} transactions were read.",
+            fees.len(),
+            stats.tx_count
+        ));
+    }
+    for bucket in analytics::histogram(&fees, buckets) {
+        output.push_str(&format!(
+            "\n- {:.0} to {:.0} utia: {} transaction(s) ({:.2}%)",
+            bucket.low,
+            bucket.high,
+            bucket.count,
+            bucket.count as f64 / fees.len() as f64 * 100.0
+        ));
+    }
+
+    Ok(output)
+}
+
+impl Tool for FeeHistogramTool {
+    const NAME: &'static str = "fee_histogram";
+
+    type Args = FeeHistogramArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Compute the distribution of the fees paid by the transactions of a Celestia \
+                 block: min, median, p90, max and mean in utia and TIA, and a histogram of how \
+                 many paid within each range. Use it for what transactions typically paid, \
+                 rather than the block's total fee. Reads up to {} transactions.",
+                MAX_TXS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The block height",
+                        "examples": [2000000],
+                    },
+                    "buckets": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_BUCKETS,
+                        "description": "How many equal ranges to count the fees in",
+                        "examples": [5],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["height"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.analyze(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
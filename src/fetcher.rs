@@ -0,0 +1,115 @@
+use futures::{stream, StreamExt, TryStreamExt};
+
+use crate::celestia_search_tool::{
+    CelestiaResponseFields, CelestiaSearchError, CelestiaSearchTool,
+};
+
+/// The number of requests kept in flight when no limit is given.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Fetches the stats of every block in `heights`, keeping up to `concurrency` requests in flight.
+///
+/// Results are returned in the order of `heights`. The first failing height aborts the whole fetch.
+pub async fn fetch_range(
+    tool: &CelestiaSearchTool,
+    heights: impl IntoIterator<Item = u64>,
+    concurrency: usize,
+) -> Result<Vec<(u64, CelestiaResponseFields)>, CelestiaSearchError> {
+    stream::iter(heights)
+        .map(|height| async move { Ok((height, tool.fetch_stats(height).await?)) })
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await
+}
+
+/// The share of a range's heights that may fail to fetch before the whole range is given up
+/// on, rather than summarized without them.
+pub const MAX_MISSING_SHARE: f64 = 0.1;
+
+/// The most missing heights a caveat lists one by one.
+const MAX_LISTED: usize = 10;
+
+/// The blocks of a range that could be fetched, and the heights that couldn't.
+#[derive(Default)]
+pub struct PartialRange {
+    /// The stats fetched, in the order of the heights asked for.
+    pub rows: Vec<(u64, CelestiaResponseFields)>,
+    /// The heights that failed to fetch, in order.
+    pub missing: Vec<u64>,
+    /// Why the first missing height failed to fetch.
+    pub reason: Option<String>,
+}
+
+impl PartialRange {
+    /// Names the heights left out and why, for the agent to caveat its answer with, unless
+    /// none are.
+    pub fn caveat(&self) -> Option<String> {
+        if self.missing.is_empty() {
+            return None;
+        }
+        let mut heights: Vec<String> = self
+            .missing
+            .iter()
+            .take(MAX_LISTED)
+            .map(u64::to_string)
+            .collect();
+        if self.missing.len() > MAX_LISTED {
+            heights.push(format!("and {} more", self.missing.len() - MAX_LISTED));
+        }
+        Some(format!(
+            "Caveat: {} of the {} blocks could not be fetched and are left out of these figures \
+             (heights {}): {}",
+            self.missing.len(),
+            self.rows.len() + self.missing.len(),
+            heights.join(", "),
+            self.reason.as_deref().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Appends the caveat, if there is one, to a tool's output.
+    pub fn annotate(&self, output: String) -> String {
+        match self.caveat() {
+            Some(caveat) => format!("{}\n{}", output, caveat),
+            None => output,
+        }
+    }
+}
+
+/// Fetches the stats of every block in `heights` like [`fetch_range`], but carries on past
+/// heights that fail, reporting them as missing.
+///
+/// The fetch still fails, with the first error, if no height could be fetched or more than
+/// [`MAX_MISSING_SHARE`] of them couldn't: figures over what's left would mislead.
+pub async fn fetch_range_partial(
+    tool: &CelestiaSearchTool,
+    heights: impl IntoIterator<Item = u64>,
+    concurrency: usize,
+) -> Result<PartialRange, CelestiaSearchError> {
+    let results: Vec<(u64, Result<CelestiaResponseFields, CelestiaSearchError>)> =
+        stream::iter(heights)
+            .map(|height| async move { (height, tool.fetch_stats(height).await) })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+    let total = results.len();
+    let mut range = PartialRange::default();
+    let mut first_error = None;
+    for (height, result) in results {
+        match result {
+            Ok(stats) => range.rows.push((height, stats)),
+            Err(error) => {
+                range.missing.push(height);
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    if let Some(error) = first_error {
+        if range.rows.is_empty() || range.missing.len() as f64 > total as f64 * MAX_MISSING_SHARE {
+            return Err(error);
+        }
+        range.reason = Some(error.to_string());
+    }
+
+    Ok(range)
+}
@@ -0,0 +1,180 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::analytics::{self, Trend};
+use crate::block_limits_tool::Ceilings;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The maximum number of blocks sampled by a single call.
+pub const MAX_SAMPLES: u64 = 200;
+
+/// Fitted changes smaller than this (one percentage point) are reported as flat.
+const FLAT_TOLERANCE: f64 = 0.01;
+
+/// The window of recent blocks to analyze.
+#[derive(Deserialize)]
+pub struct FillRateTrendArgs {
+    /// How many blocks to sample, ending at the chain head.
+    #[serde(default = "default_samples")]
+    samples: u64,
+    /// The distance between sampled blocks, to cover longer periods.
+    #[serde(default = "default_step")]
+    step: u64,
+    /// The networks to analyze, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_samples() -> u64 {
+    100
+}
+
+fn default_step() -> u64 {
+    1
+}
+
+/// Computes fill-rate statistics over a sliding window of recent blocks.
+pub struct FillRateTrendTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl FillRateTrendTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn analyze(&self, args: FillRateTrendArgs) -> Result<String, CelestiaSearchError> {
+        let samples = args.samples.clamp(1, MAX_SAMPLES);
+        let step = args.step.max(1);
+
+        self.blocks
+            .across(&args.network, |blocks| {
+                analyze_window(blocks, samples, step)
+            })
+            .await
+    }
+}
+
+/// Summarizes the fill rate of `samples` blocks `step` apart, ending at the chain head
+async fn analyze_window(
+    blocks: &CelestiaSearchTool,
+    samples: u64,
+    step: u64,
+) -> Result<String, CelestiaSearchError> {
+    let head = blocks.chain_head().await?;
+    let mut heights: Vec<u64> = (0..samples)
+        .map_while(|i| head.checked_sub(i * step).filter(|&height| height > 0))
+        .collect();
+    heights.reverse();
+
+    let (range, ceilings) = futures::join!(
+        fetcher::fetch_range_partial(blocks, heights.iter().copied(), DEFAULT_CONCURRENCY),
+        Ceilings::fetch(blocks),
+    );
+    let range = range?;
+    let rows = &range.rows;
+    // Blocks are measured against the limit of their height, as limits were raised over time
+    let fill_rates: Vec<f64> = rows
+        .iter()
+        .map(|(height, stats)| match ceilings.at(*height) {
+            Some(era) => stats.bytes_in_block as f64 / era.max_bytes as f64,
+            None => stats.fill_rate,
+        })
+        .collect();
+    let measured = rows
+        .iter()
+        .filter(|(height, _)| ceilings.at(*height).is_some())
+        .count();
+
+    let Some(summary) = analytics::summarize(&fill_rates) else {
+        return Ok("No blocks to analyze.".to_string());
+    };
+    let trend = match summary.trend(FLAT_TOLERANCE) {
+        Trend::Flat => "flat".to_string(),
+        trend => format!(
+            "{} ({:+.2} percentage points across the window)",
+            trend.name(),
+            summary.fitted_change * 100.0
+        ),
+    };
+
+    let basis = if measured == rows.len() {
+        " Each block is measured against the block size limit in force at its height.".to_string()
+    } else if measured == 0 {
+        " No block size limit is recorded for these heights, so the indexer's fill rate is used."
+            .to_string()
+    } else {
+        format!(
+            " {} block(s) are measured against the block size limit in force at their height, \
+             the others by the indexer's fill rate, as no limit is recorded for them.",
+            measured
+        )
+    };
+
+    Ok(range.annotate(format!(
+        "Fill rate of {} blocks from {} to {} (every {} block(s)): min {:.2}%, max {:.2}%, \
+         average {:.2}%. The trend is {}.{}",
+        rows.len(),
+        heights[0],
+        head,
+        step,
+        summary.min * 100.0,
+        summary.max * 100.0,
+        summary.mean * 100.0,
+        trend,
+        basis
+    )))
+}
+
+impl Tool for FillRateTrendTool {
+    const NAME: &'static str = "fill_rate_trend";
+
+    type Args = FillRateTrendArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Compute block fill-rate statistics (min, max, average and trend \
+                direction) over a window of recent blocks ending at the chain head, to tell \
+                whether Celestia block space is getting more or less utilized. Blocks come \
+                roughly every 6 seconds, so 100 samples with a step of 1000 cover about a week."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "samples": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_SAMPLES,
+                        "description": "How many blocks to sample",
+                        "examples": [100],
+                    },
+                    "step": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Heights between consecutive samples",
+                        "examples": [1, 1000],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.analyze(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
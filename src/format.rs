@@ -0,0 +1,233 @@
+use serde_json::{json, Value};
+
+use crate::assistant::Turn;
+use crate::locale::Language;
+use crate::{chart, postprocess, structured};
+
+/// How the final answer is printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Plain text
+    #[default]
+    Text,
+    /// A JSON document with the answer and the underlying tool data
+    Json,
+    /// A markdown table, for pasting into reports
+    Markdown,
+    /// The answer as a JSON object with the values it quotes and their sources, validated
+    /// against a schema
+    Structured,
+}
+
+/// How much an answer says.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Detail {
+    /// A single sentence with the number asked for, as dashboards show
+    Concise,
+    /// As much as the question calls for
+    #[default]
+    Normal,
+    /// A full breakdown, followed in text output by the data of each tool call
+    Detailed,
+}
+
+impl Detail {
+    /// What the agent is told about the length of its answers, if anything.
+    pub fn instructions(self) -> Option<&'static str> {
+        match self {
+            Detail::Concise => Some(
+                "Answer in a single sentence stating the number asked for, with its unit, and \
+                 nothing else: no breakdown, caveats or restating of the question.",
+            ),
+            Detail::Normal => None,
+            Detail::Detailed => Some(
+                "Give a full breakdown: the answer first, then each figure it was worked out \
+                 from, with its unit and the blocks or period it covers, and any caveats the tool \
+                 results mention.",
+            ),
+        }
+    }
+}
+
+/// The first sentence of `answer`, or its first line if that ends sooner.
+///
+/// Sentences end at a full stop, question or exclamation mark followed by whitespace, so the
+/// decimal point of an amount such as `1.5 TIA` doesn't end one.
+pub fn first_sentence(answer: &str) -> &str {
+    let answer = answer.trim();
+    let mut chars = answer.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next);
+        if c == '\n' {
+            return answer[..i].trim_end();
+        }
+        if matches!(c, '.' | '?' | '!') && next.is_none_or(char::is_whitespace) {
+            return &answer[..i + c.len_utf8()];
+        }
+    }
+    answer
+}
+
+/// Renders the answer to `prompt` in the requested format, at the requested detail, with the
+/// numbers of text and markdown answers written the way `language` does.
+pub fn render(
+    prompt: &str,
+    turn: &Turn,
+    format: OutputFormat,
+    detail: Detail,
+    language: Language,
+) -> Result<String, serde_json::Error> {
+    let answer = match (answer(turn), detail) {
+        (Some(answer), Detail::Concise) => Some(first_sentence(&answer).to_string()),
+        (answer, _) => answer,
+    };
+    // JSON is read by programs, which expect numbers as tools give them
+    let localized = answer.as_deref().map(|answer| language.localize(answer));
+
+    // Chart tool outputs holding a series; JSON output already carries the data itself, and
+    // concise answers are only the sentence
+    let chart = turn
+        .tool_call
+        .as_ref()
+        .filter(|_| detail != Detail::Concise)
+        .and_then(|call| call.output.as_deref())
+        .and_then(|output| serde_json::from_str::<String>(output).ok())
+        .and_then(|output| chart::detect(&output))
+        .map(|series| chart::render(&series));
+
+    match format {
+        OutputFormat::Text => Ok(match (detail, &localized) {
+            // Dashboards show the sentence alone
+            (Detail::Concise, Some(answer)) => answer.clone(),
+            (Detail::Detailed, _) => with_tool_data(
+                render_text(turn, localized.as_deref(), chart.as_deref()),
+                turn,
+            ),
+            _ => render_text(turn, localized.as_deref(), chart.as_deref()),
+        }),
+        OutputFormat::Json => {
+            let mut json = to_json(prompt, turn);
+            json["answer"] = answer.into();
+            serde_json::to_string_pretty(&json)
+        }
+        OutputFormat::Markdown => Ok(render_markdown(
+            prompt,
+            turn,
+            localized.as_deref(),
+            chart.as_deref(),
+        )),
+        // Only planned tool calls aren't answered in structured mode
+        OutputFormat::Structured => match structured::parse(&turn.output) {
+            Ok(answer) => serde_json::to_string_pretty(&answer),
+            Err(_) => serde_json::to_string_pretty(&to_json(prompt, turn)),
+        },
+    }
+}
+
+/// The answer is absent when the tool call was skipped in dry-run mode
+fn answer(turn: &Turn) -> Option<String> {
+    match &turn.tool_call {
+        Some(call) if call.output.is_none() => None,
+        _ => Some(postprocess::answer(&turn.output)),
+    }
+}
+
+fn render_text(turn: &Turn, answer: Option<&str>, chart: Option<&str>) -> String {
+    let text = match (answer, &turn.tool_call) {
+        (Some(answer), _) => format!("Agent response:\n{}", answer),
+        (None, Some(call)) => format!("Planned tool call: {} {}", call.name, call.args),
+        (None, None) => String::new(),
+    };
+
+    match chart {
+        Some(chart) => format!("{}\n\n{}", text, chart),
+        None => text,
+    }
+}
+
+/// `text` followed by the data of each tool call the turn made
+fn with_tool_data(mut text: String, turn: &Turn) -> String {
+    for call in turn.steps.iter().chain(&turn.tool_call) {
+        let Some(output) = &call.output else {
+            continue;
+        };
+        // Tool outputs are JSON-encoded strings
+        let output = serde_json::from_str::<String>(output).unwrap_or_else(|_| output.clone());
+        text.push_str(&format!(
+            "\n\nData from `{}` with {}:\n{}",
+            call.name,
+            call.args,
+            output.trim_end()
+        ));
+    }
+    text
+}
+
+/// The answer to `prompt` and the underlying tool data, as rendered by [`OutputFormat::Json`].
+pub fn to_json(prompt: &str, turn: &Turn) -> Value {
+    let answer = answer(turn);
+    let tool_call = match &turn.tool_call {
+        Some(call) => {
+            // Tool outputs are JSON-encoded, so embed them as values rather than strings
+            let output = match &call.output {
+                Some(output) => {
+                    serde_json::from_str(output).unwrap_or_else(|_| Value::from(output.as_str()))
+                }
+                None => Value::Null,
+            };
+            json!({
+                "name": call.name,
+                "args": call.args,
+                "executed": call.output.is_some(),
+                "output": output,
+            })
+        }
+        None => Value::Null,
+    };
+
+    json!({
+        "prompt": prompt,
+        "answer": answer,
+        "tool_call": tool_call,
+    })
+}
+
+fn render_markdown(prompt: &str, turn: &Turn, answer: Option<&str>, chart: Option<&str>) -> String {
+    let mut rows = vec![("Question".to_string(), prompt.to_string())];
+
+    if let Some(call) = &turn.tool_call {
+        rows.push(("Tool".to_string(), format!("`{}`", call.name)));
+        if let Some(args) = call.args.as_object() {
+            for (name, value) in args {
+                rows.push((format!("`{}`", name), value.to_string()));
+            }
+        }
+    }
+
+    match answer {
+        Some(answer) => rows.push(("Answer".to_string(), answer.to_string())),
+        None => rows.push((
+            "Answer".to_string(),
+            "_dry run, tool not called_".to_string(),
+        )),
+    }
+
+    let mut table = String::from("| Field | Value |\n| --- | --- |\n");
+    for (field, value) in rows {
+        table.push_str(&format!(
+            "| {} | {} |\n",
+            escape_cell(&field),
+            escape_cell(&value)
+        ));
+    }
+
+    if let Some(chart) = chart {
+        table.push_str(&format!("\n```\n{}\n```\n", chart));
+    }
+    table
+}
+
+/// Escapes characters that would break a markdown table cell.
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', "<br>")
+}
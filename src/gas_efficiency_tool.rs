@@ -0,0 +1,177 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::analytics::correlation;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The maximum number of blocks in a single range.
+pub const MAX_RANGE: u64 = 500;
+
+/// The block range to report gas efficiency over.
+#[derive(Deserialize)]
+pub struct GasEfficiencyArgs {
+    /// The first height of the range.
+    from: u64,
+    /// The last height of the range (inclusive).
+    to: u64,
+    /// The networks to analyze, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Reports how much of the gas requested over a block range went unused, and what it cost.
+pub struct GasEfficiencyTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl GasEfficiencyTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn analyze(&self, args: GasEfficiencyArgs) -> Result<String, CelestiaSearchError> {
+        if args.from == 0 || args.from > args.to {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {}",
+                args.from, args.to
+            )));
+        }
+        if args.to - args.from >= MAX_RANGE {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {} spans more than {} blocks",
+                args.from, args.to, MAX_RANGE
+            )));
+        }
+
+        self.blocks
+            .across(&args.network, |blocks| report(blocks, args.from, args.to))
+            .await
+    }
+}
+
+/// Totals the gas requested, used and paid for over blocks `from..=to`
+async fn report(
+    blocks: &CelestiaSearchTool,
+    from: u64,
+    to: u64,
+) -> Result<String, CelestiaSearchError> {
+    let range = fetcher::fetch_range_partial(blocks, from..=to, DEFAULT_CONCURRENCY).await?;
+    let rows = &range.rows;
+    let rows: Vec<_> = rows.iter().filter(|(_, s)| s.gas_limit > 0).collect();
+    if rows.is_empty() {
+        return Ok(range.annotate(format!(
+            "No transactions requested gas in blocks {} to {}.",
+            from, to
+        )));
+    }
+
+    let limit: u64 = rows.iter().map(|(_, s)| s.gas_limit).sum();
+    let used: u64 = rows.iter().map(|(_, s)| s.gas_used.min(s.gas_limit)).sum();
+    let fees: Vec<f64> = rows.iter().map(|(_, s)| s.fee.as_f64()).collect();
+    let utilization: Vec<f64> = rows
+        .iter()
+        .map(|(_, s)| s.gas_used as f64 / s.gas_limit as f64)
+        .collect();
+
+    // Fees are paid on the gas requested, so the unused part of each block's gas was overpaid
+    let overpaid: f64 = fees
+        .iter()
+        .zip(&utilization)
+        .map(|(fee, used)| fee * (1.0 - used.min(1.0)))
+        .sum();
+    let total_fees: f64 = fees.iter().sum();
+    let (worst, worst_utilization) = rows
+        .iter()
+        .zip(&utilization)
+        .map(|((height, _), used)| (*height, *used))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("rows is not empty");
+
+    let mut output = format!(
+        "Gas efficiency over blocks {} to {} ({} blocks with transactions):\n\
+         - gas used: {} of {} requested ({:.2}%)\n\
+         - wasted gas: {}\n\
+         - fees: {}, of which an estimated {} ({:.2}%) paid for unused gas\n\
+         - least efficient block: {} ({:.2}% of its gas used)",
+        from,
+        to,
+        rows.len(),
+        used,
+        limit,
+        used as f64 / limit as f64 * 100.0,
+        limit - used,
+        price::describe_utia(&format!("{:.0}", total_fees), None),
+        price::describe_utia(&format!("{:.0}", overpaid), None),
+        if total_fees > 0.0 {
+            overpaid / total_fees * 100.0
+        } else {
+            0.0
+        },
+        worst,
+        worst_utilization * 100.0
+    );
+    if let Some(r) = correlation(&utilization, &fees) {
+        output.push_str(&format!(
+            "\n- correlation between gas utilization and fees: {:.2}",
+            r
+        ));
+    }
+
+    Ok(range.annotate(output))
+}
+
+impl Tool for GasEfficiencyTool {
+    const NAME: &'static str = "gas_efficiency";
+
+    type Args = GasEfficiencyArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Report how efficiently gas was used over a range of Celestia blocks: gas used \
+                 against gas requested, the gas wasted, an estimate of the fees overpaid for \
+                 unused gas, the least efficient block, and how utilization correlates with \
+                 fees. Ranges span at most {} blocks.",
+                MAX_RANGE
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "First height of the range",
+                        "examples": [2000000],
+                    },
+                    "to": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Last height of the range (inclusive)",
+                        "examples": [2000099],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.analyze(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
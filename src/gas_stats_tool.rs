@@ -0,0 +1,152 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::analytics::percentile;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The maximum number of blocks in a single range.
+pub const MAX_RANGE: u64 = 500;
+
+/// The percentiles reported for each metric.
+const PERCENTILES: [f64; 3] = [50.0, 90.0, 99.0];
+
+/// The block range to compute gas statistics over.
+#[derive(Deserialize)]
+pub struct GasStatsArgs {
+    /// The first height of the range.
+    from: u64,
+    /// The last height of the range (inclusive).
+    to: u64,
+    /// The networks to analyze, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Computes percentiles of gas usage over a block range.
+pub struct GasStatsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl GasStatsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn analyze(&self, args: GasStatsArgs) -> Result<String, CelestiaSearchError> {
+        if args.from == 0 || args.from > args.to {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {}",
+                args.from, args.to
+            )));
+        }
+        if args.to - args.from >= MAX_RANGE {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {} spans more than {} blocks",
+                args.from, args.to, MAX_RANGE
+            )));
+        }
+
+        self.blocks
+            .across(&args.network, |blocks| {
+                describe_range(blocks, args.from, args.to)
+            })
+            .await
+    }
+}
+
+/// Computes the gas percentiles of blocks `from..=to`
+async fn describe_range(
+    blocks: &CelestiaSearchTool,
+    from: u64,
+    to: u64,
+) -> Result<String, CelestiaSearchError> {
+    let range = fetcher::fetch_range_partial(blocks, from..=to, DEFAULT_CONCURRENCY).await?;
+    let rows = &range.rows;
+    let gas_used: Vec<f64> = rows.iter().map(|(_, s)| s.gas_used as f64).collect();
+    let utilization: Vec<f64> = rows
+        .iter()
+        .filter(|(_, s)| s.gas_limit > 0)
+        .map(|(_, s)| s.gas_used as f64 / s.gas_limit as f64)
+        .collect();
+
+    let describe = |samples: &[f64], format: &dyn Fn(f64) -> String| {
+        PERCENTILES
+            .iter()
+            .filter_map(|&p| {
+                percentile(samples, p).map(|value| format!("p{} {}", p, format(value)))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut output = format!(
+        "Gas over blocks {} to {} ({} blocks): gas used {}",
+        from,
+        to,
+        rows.len(),
+        describe(&gas_used, &|value| format!("{:.0}", value))
+    );
+    if !utilization.is_empty() {
+        output.push_str(&format!(
+            "; gas used/limit {}",
+            describe(&utilization, &|value| format!("{:.2}%", value * 100.0))
+        ));
+    }
+    output.push('.');
+
+    Ok(range.annotate(output))
+}
+
+impl Tool for GasStatsTool {
+    const NAME: &'static str = "gas_percentiles";
+
+    type Args = GasStatsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Compute the p50, p90 and p99 of gas used and of the gas used/gas limit ratio \
+                 over a range of Celestia blocks, e.g. to tune the gas settings of blob \
+                 submissions. Ranges span at most {} blocks.",
+                MAX_RANGE
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "First height of the range",
+                        "examples": [2000000],
+                    },
+                    "to": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Last height of the range (inclusive)",
+                        "examples": [2000099],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.analyze(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
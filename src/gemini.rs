@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+
+use rig::completion::{self, CompletionError, CompletionRequest, ModelChoice};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+/// The Gemini API models are served from unless another base URL is configured.
+pub const GEMINI_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// JSON Schema keywords tools use that Gemini's function declarations reject.
+const UNSUPPORTED_SCHEMA_KEYWORDS: [&str; 4] =
+    ["$schema", "additionalProperties", "default", "examples"];
+
+/// Google's Gemini API, from the `[gemini]` section of the config.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GeminiConfig {
+    /// The model answering questions, e.g. `gemini-2.0-flash`; the `--model` name is used if
+    /// left out.
+    pub model: Option<String>,
+    /// The API key; read from `GEMINI_API_KEY` if left out.
+    pub api_key: Option<String>,
+    /// The API's base URL, instead of [`GEMINI_API_URL`].
+    pub base_url: Option<String>,
+    /// Blocking thresholds by harm category, such as
+    /// `HARM_CATEGORY_DANGEROUS_CONTENT = "BLOCK_ONLY_HIGH"`; Gemini's defaults apply to the
+    /// categories left out.
+    #[serde(default)]
+    pub safety_settings: BTreeMap<String, String>,
+}
+
+impl GeminiConfig {
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key
+            .clone()
+            .or_else(|| std::env::var("GEMINI_API_KEY").ok())
+    }
+}
+
+/// A client of the Gemini API.
+#[derive(Clone, Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    safety_settings: BTreeMap<String, String>,
+}
+
+impl Client {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            safety_settings: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a client of the configured base URL, sending the configured safety settings
+    /// with every request.
+    pub fn from_config(config: &GeminiConfig, api_key: &str) -> Self {
+        let mut client = Self::new(
+            config.base_url.as_deref().unwrap_or(GEMINI_API_URL),
+            api_key,
+        );
+        client.safety_settings = config.safety_settings.clone();
+        client
+    }
+
+    pub fn completion_model(&self, model: &str) -> CompletionModel {
+        CompletionModel {
+            client: self.clone(),
+            model: model.to_string(),
+        }
+    }
+}
+
+/// A Gemini model, such as `gemini-2.0-flash`.
+#[derive(Clone, Debug)]
+pub struct CompletionModel {
+    client: Client,
+    pub model: String,
+}
+
+/// The response to a `generateContent` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentResponse {
+    #[serde(default)]
+    pub candidates: Vec<Candidate>,
+    pub usage_metadata: Option<UsageMetadata>,
+    pub prompt_feedback: Option<PromptFeedback>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub content: Option<Content>,
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Content {
+    #[serde(default)]
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Part {
+    pub text: Option<String>,
+    pub function_call: Option<FunctionCall>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetadata {
+    #[serde(default)]
+    pub prompt_token_count: u64,
+    #[serde(default)]
+    pub candidates_token_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFeedback {
+    pub block_reason: Option<String>,
+}
+
+impl CompletionModel {
+    fn url(&self) -> String {
+        format!(
+            "{}/models/{}:generateContent",
+            self.client.base_url, self.model
+        )
+    }
+}
+
+impl completion::CompletionModel for CompletionModel {
+    type Response = GenerateContentResponse;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
+        let body = generate_request(request, &self.client.safety_settings);
+        let response = self
+            .client
+            .http
+            .post(self.url())
+            .header("x-goog-api-key", &self.client.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(http_error)?;
+
+        let status = response.status();
+        let body: Value = response.json().await.map_err(http_error)?;
+        if let Some(error) = body.get("error") {
+            let message = error["message"].as_str().unwrap_or("unknown error");
+            return Err(CompletionError::ProviderError(format!(
+                "Gemini answered with status {}: {}",
+                status, message
+            )));
+        }
+        if !status.is_success() {
+            return Err(CompletionError::ProviderError(format!(
+                "Gemini answered with status {}",
+                status
+            )));
+        }
+
+        let response: GenerateContentResponse = serde_json::from_value(body)?;
+        let choice = choice(&response)?;
+        Ok(completion::CompletionResponse {
+            choice,
+            raw_response: response,
+        })
+    }
+}
+
+/// The message or function call of the first candidate
+fn choice(response: &GenerateContentResponse) -> Result<ModelChoice, CompletionError> {
+    if let Some(reason) = response
+        .prompt_feedback
+        .as_ref()
+        .and_then(|feedback| feedback.block_reason.as_deref())
+    {
+        return Err(CompletionError::ProviderError(format!(
+            "Gemini blocked the prompt ({})",
+            reason
+        )));
+    }
+    let candidate = response
+        .candidates
+        .first()
+        .ok_or_else(|| CompletionError::ResponseError("Response had no candidates".into()))?;
+    let parts = candidate
+        .content
+        .as_ref()
+        .map(|content| content.parts.as_slice())
+        .unwrap_or_default();
+
+    if let Some(call) = parts.iter().find_map(|part| part.function_call.as_ref()) {
+        return Ok(ModelChoice::ToolCall(call.name.clone(), call.args.clone()));
+    }
+    let text: String = parts
+        .iter()
+        .filter_map(|part| part.text.as_deref())
+        .collect();
+    match (text.is_empty(), candidate.finish_reason.as_deref()) {
+        (true, Some("SAFETY")) => Err(CompletionError::ProviderError(
+            "Gemini withheld the response for safety".into(),
+        )),
+        _ => Ok(ModelChoice::Message(text)),
+    }
+}
+
+/// rig's HTTP errors are those of an older reqwest, so ours are passed on boxed
+fn http_error(error: reqwest::Error) -> CompletionError {
+    CompletionError::RequestError(Box::new(error))
+}
+
+/// The body of a `generateContent` request
+///
+/// The preamble and context documents become the system instruction, and the sampling
+/// parameters sent OpenAI's way (`max_tokens`, `top_p`) Gemini's generation config.
+fn generate_request(
+    request: CompletionRequest,
+    safety_settings: &BTreeMap<String, String>,
+) -> Value {
+    let mut system: Vec<Value> = request
+        .preamble
+        .into_iter()
+        .map(|preamble| json!({ "text": preamble }))
+        .collect();
+    for document in &request.documents {
+        let text = serde_json::to_string(document).expect("Document should serialize");
+        system.push(json!({ "text": text }));
+    }
+
+    let mut contents: Vec<Value> = request
+        .chat_history
+        .into_iter()
+        .map(|message| {
+            let role = if message.role == "assistant" {
+                "model"
+            } else {
+                "user"
+            };
+            json!({ "role": role, "parts": [{ "text": message.content }] })
+        })
+        .collect();
+    contents.push(json!({ "role": "user", "parts": [{ "text": request.prompt }] }));
+
+    let mut generation = Map::new();
+    if let Some(temperature) = request.temperature {
+        generation.insert("temperature".to_string(), temperature.into());
+    }
+    let params = request.additional_params.unwrap_or_default();
+    if let Some(max_tokens) = params.get("max_tokens") {
+        generation.insert("maxOutputTokens".to_string(), max_tokens.clone());
+    }
+    if let Some(top_p) = params.get("top_p") {
+        generation.insert("topP".to_string(), top_p.clone());
+    }
+
+    let mut body = json!({ "contents": contents, "generationConfig": generation });
+    if !system.is_empty() {
+        body["systemInstruction"] = json!({ "parts": system });
+    }
+    if !request.tools.is_empty() {
+        let declarations: Vec<Value> = request
+            .tools
+            .into_iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": supported_schema(tool.parameters),
+                })
+            })
+            .collect();
+        body["tools"] = json!([{ "functionDeclarations": declarations }]);
+    }
+    if !safety_settings.is_empty() {
+        body["safetySettings"] = safety_settings
+            .iter()
+            .map(|(category, threshold)| json!({ "category": category, "threshold": threshold }))
+            .collect();
+    }
+    body
+}
+
+/// A tool's parameter schema without the keywords Gemini rejects, so tools are defined once
+/// for every provider
+fn supported_schema(schema: Value) -> Value {
+    match schema {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .filter(|(key, _)| !UNSUPPORTED_SCHEMA_KEYWORDS.contains(&key.as_str()))
+                .map(|(key, value)| match key.as_str() {
+                    // Property names are not keywords, whatever they are called
+                    "properties" => (key, property_schemas(value)),
+                    _ => (key, supported_schema(value)),
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(supported_schema).collect()),
+        other => other,
+    }
+}
+
+fn property_schemas(properties: Value) -> Value {
+    match properties {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(name, schema)| (name, supported_schema(schema)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use cosmos_sdk_proto::cosmos::bank::v1beta1::query_client::QueryClient as BankClient;
+use cosmos_sdk_proto::cosmos::bank::v1beta1::QueryBalanceRequest;
+use cosmos_sdk_proto::cosmos::distribution::v1beta1::query_client::QueryClient as DistributionClient;
+use cosmos_sdk_proto::cosmos::distribution::v1beta1::QueryDelegationTotalRewardsRequest;
+use tokio::task::JoinError;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::Code;
+
+use crate::amount::{AmountError, Utia};
+use crate::audit;
+use crate::celestia_search_tool::{CelestiaSearchError, REQUEST_TIMEOUT};
+use crate::network::Network;
+
+/// The metadata asking celestia-app to answer as of a past height.
+const HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+/// A client of the cosmos-sdk gRPC query services of a celestia-app node per network.
+///
+/// Connections are opened on first use and shared by clones.
+#[derive(Clone, Debug)]
+pub struct GrpcClient {
+    channels: BTreeMap<Network, (String, Channel)>,
+}
+
+impl GrpcClient {
+    /// Prepares a connection to the endpoint of each network, such as `http://localhost:9090`.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new(endpoints: BTreeMap<Network, String>) -> Result<Self, CelestiaSearchError> {
+        let channels = endpoints
+            .into_iter()
+            .map(|(network, url)| {
+                let invalid = |e: tonic::transport::Error| {
+                    CelestiaSearchError::HttpRequestFailed(format!(
+                        "invalid gRPC endpoint {}: {}",
+                        url, e
+                    ))
+                };
+                let mut endpoint = Endpoint::from_shared(url.clone())
+                    .map_err(invalid)?
+                    .timeout(REQUEST_TIMEOUT);
+                if url.starts_with("https://") {
+                    endpoint = endpoint
+                        .tls_config(ClientTlsConfig::new().with_native_roots())
+                        .map_err(invalid)?;
+                }
+                Ok((network, (url.clone(), endpoint.connect_lazy())))
+            })
+            .collect::<Result<_, CelestiaSearchError>>()?;
+        Ok(Self { channels })
+    }
+
+    /// Whether an endpoint is configured for `network`.
+    pub fn serves(&self, network: Network) -> bool {
+        self.channels.contains_key(&network)
+    }
+
+    /// The `denom` balance of `address` on `network`, as of `height` if one is given.
+    pub async fn balance(
+        &self,
+        network: Network,
+        address: &str,
+        denom: &str,
+        height: Option<u64>,
+    ) -> Result<Utia, CelestiaSearchError> {
+        let (url, channel) = self.channel(network)?;
+        audit::upstream(&format!("{} cosmos.bank.v1beta1.Query/Balance", url));
+        let mut request = tonic::Request::new(QueryBalanceRequest {
+            address: address.to_string(),
+            denom: denom.to_string(),
+        });
+        if let Some(height) = height {
+            request
+                .metadata_mut()
+                .insert(HEIGHT_HEADER, MetadataValue::from(height));
+        }
+        let call = tokio::spawn(async move { BankClient::new(channel).balance(request).await });
+        let response = joined(url, call.await)?;
+
+        match response.balance {
+            Some(coin) => {
+                coin.amount
+                    .parse()
+                    .map_err(|e: AmountError| CelestiaSearchError::Deserialization {
+                        url: url.to_string(),
+                        reason: e.to_string(),
+                    })
+            }
+            None => Ok(Utia::ZERO),
+        }
+    }
+
+    /// The utia `delegator` can claim from each validator on `network`, fractions included.
+    pub async fn delegation_rewards(
+        &self,
+        network: Network,
+        delegator: &str,
+    ) -> Result<Vec<(String, Utia)>, CelestiaSearchError> {
+        let (url, channel) = self.channel(network)?;
+        audit::upstream(&format!(
+            "{} cosmos.distribution.v1beta1.Query/DelegationTotalRewards",
+            url
+        ));
+        let request = QueryDelegationTotalRewardsRequest {
+            delegator_address: delegator.to_string(),
+        };
+        let call = tokio::spawn(async move {
+            let mut client = DistributionClient::new(channel);
+            client.delegation_total_rewards(request).await
+        });
+        let response = joined(url, call.await)?;
+
+        response
+            .rewards
+            .into_iter()
+            .map(|reward| {
+                let amount = reward
+                    .reward
+                    .iter()
+                    .filter(|coin| coin.denom == "utia")
+                    .map(|coin| Utia::from_atomics(&coin.amount))
+                    .sum::<Result<Utia, _>>()
+                    .map_err(|e| CelestiaSearchError::Deserialization {
+                        url: url.to_string(),
+                        reason: e.to_string(),
+                    })?;
+                Ok((reward.validator_address, amount))
+            })
+            .collect()
+    }
+
+    fn channel(&self, network: Network) -> Result<(&str, Channel), CelestiaSearchError> {
+        self.channels
+            .get(&network)
+            .map(|(url, channel)| (url.as_str(), channel.clone()))
+            .ok_or(CelestiaSearchError::NetworkUnavailable(network))
+    }
+}
+
+/// The response of a call run on its own task.
+///
+/// Calls are spawned because tonic's response futures aren't `Sync`, which the futures of
+/// tools have to be.
+fn joined<T>(
+    url: &str,
+    result: Result<Result<tonic::Response<T>, tonic::Status>, JoinError>,
+) -> Result<T, CelestiaSearchError> {
+    match result {
+        Ok(Ok(response)) => Ok(response.into_inner()),
+        Ok(Err(status)) => Err(status_error(url, status)),
+        Err(e) => Err(CelestiaSearchError::HttpRequestFailed(format!(
+            "gRPC request to {} failed: {}",
+            url, e
+        ))),
+    }
+}
+
+/// Classifies a failed call the way the HTTP clients classify failed requests
+fn status_error(url: &str, status: tonic::Status) -> CelestiaSearchError {
+    let url = url.to_string();
+    match status.code() {
+        Code::DeadlineExceeded => CelestiaSearchError::Timeout { url },
+        Code::NotFound => CelestiaSearchError::NotFound { url },
+        _ => CelestiaSearchError::HttpRequestFailed(format!(
+            "gRPC request to {} failed: {}",
+            url,
+            status.message()
+        )),
+    }
+}
@@ -0,0 +1,72 @@
+//! The chain head, put in the agent's context at the start of each turn with `--head-context`,
+//! so questions relative to now ("the block from 5 minutes ago") resolve without a tool call.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::estimate_time_tool::{self, BlockTime, BLOCK_TIME_WINDOW};
+use crate::network::Network;
+use crate::time::Instant;
+
+/// How long a head is reused for the turns that follow before it's fetched again.
+pub const TTL: Duration = Duration::from_secs(30);
+
+/// The latest head fetched on each network, and when.
+static HEADS: OnceLock<Mutex<HashMap<Network, (Instant, BlockTime)>>> = OnceLock::new();
+
+/// Describes the chain head of `blocks`' network and the recent block time, as of `now`.
+pub async fn describe(
+    blocks: &CelestiaSearchTool,
+    now: DateTime<Utc>,
+) -> Result<String, CelestiaSearchError> {
+    let network = blocks.network();
+    let heads = HEADS.get_or_init(Mutex::default);
+    let cached = heads
+        .lock()
+        .unwrap()
+        .get(&network)
+        .filter(|(fetched_at, _)| fetched_at.elapsed() < TTL)
+        .map(|(_, head)| *head);
+
+    let head = match cached {
+        Some(head) => head,
+        None => {
+            let data = blocks.fetch("/head").await?;
+            let Some(head) =
+                estimate_time_tool::block_time(blocks, &data, BLOCK_TIME_WINDOW).await?
+            else {
+                // Without block times, the height is all there is to go on
+                let height = blocks.chain_head().await?;
+                return Ok(format!(
+                    "The chain head on {} is block {}; it is now {}.",
+                    network,
+                    height,
+                    estimate_time_tool::rfc3339(now)
+                ));
+            };
+            heads
+                .lock()
+                .unwrap()
+                .insert(network, (Instant::now(), head));
+            head
+        }
+    };
+
+    let age = (now - head.time).num_milliseconds().max(0) as f64 / 1000.0;
+    Ok(format!(
+        "The chain head on {} was block {} at the start of this turn, produced at {} ({} before \
+         now, {}). Blocks took {:.2}s on average over the last {} blocks, so work out heights \
+         relative to now from these rather than calling a tool.",
+        network,
+        head.height,
+        estimate_time_tool::rfc3339(head.time),
+        estimate_time_tool::span(age),
+        estimate_time_tool::rfc3339(now),
+        head.seconds,
+        head.blocks
+    ))
+}
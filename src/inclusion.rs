@@ -0,0 +1,424 @@
+//! Verifies that a blob is included in a block, from proofs served by a celestia-node.
+//!
+//! A blob's shares are rebuilt from its data and hashed into the namespaced Merkle tree (NMT)
+//! of each row they occupy, using the node's range proofs for the rest of the row. The row roots
+//! must match the block's data availability header, whose roots must in turn hash to the data
+//! root committed to in the block header. The shares must also hash to the commitment asked
+//! for, so that the node can't answer with another blob. Nothing but the header's data root is
+//! trusted.
+
+use std::ops::RangeInclusive;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Deserializer};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::de::string_or_number;
+use crate::node::{NodeClient, NodeError};
+
+/// The size of a share, in bytes.
+pub const SHARE_SIZE: usize = 512;
+
+/// The size of a namespace (its version byte and 28-byte id).
+pub const NAMESPACE_SIZE: usize = 29;
+
+/// The size of a signer address, embedded in the first share of version 1 blobs.
+const SIGNER_SIZE: usize = 20;
+
+/// The number of subtree roots past which a blob's commitment is built from wider subtrees, as
+/// set by celestia-app (ADR-013).
+const SUBTREE_ROOT_THRESHOLD: usize = 64;
+
+/// The namespace of the parity shares of the extended square.
+const PARITY_NAMESPACE: [u8; NAMESPACE_SIZE] = [0xFF; NAMESPACE_SIZE];
+
+/// Captures the errors that keep a blob from being checked at all.
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    #[error(transparent)]
+    Node(#[from] NodeError),
+    #[error("The node's response is malformed: {0}")]
+    Malformed(String),
+    #[error("Invalid blob reference: {0}")]
+    InvalidInput(String),
+}
+
+/// The outcome of checking a blob's inclusion.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    /// The blob's shares are proven to be part of the block's data root.
+    Included {
+        shares: usize,
+        rows: RangeInclusive<usize>,
+        /// The data root, in hex.
+        data_root: String,
+    },
+    /// The proofs don't hold, for the given reason.
+    NotIncluded(String),
+}
+
+#[derive(Deserialize)]
+struct ExtendedHeader {
+    header: RawHeader,
+    dah: DataAvailabilityHeader,
+}
+
+#[derive(Deserialize)]
+struct RawHeader {
+    data_hash: String,
+}
+
+#[derive(Deserialize)]
+struct DataAvailabilityHeader {
+    #[serde(deserialize_with = "base64_list")]
+    row_roots: Vec<Vec<u8>>,
+    #[serde(deserialize_with = "base64_list")]
+    column_roots: Vec<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct Blob {
+    #[serde(deserialize_with = "base64_bytes")]
+    data: Vec<u8>,
+    #[serde(default)]
+    share_version: u8,
+    #[serde(default, deserialize_with = "base64_optional")]
+    signer: Option<Vec<u8>>,
+    /// The blob's first share in the original data square, or -1 if the node doesn't know it
+    #[serde(default = "unknown_index", deserialize_with = "string_or_number")]
+    index: i64,
+}
+
+fn unknown_index() -> i64 {
+    -1
+}
+
+/// A proof of a range of leaves of one row's NMT.
+#[derive(Deserialize)]
+struct RangeProof {
+    #[serde(default, deserialize_with = "string_or_number")]
+    start: usize,
+    #[serde(default, deserialize_with = "string_or_number")]
+    end: usize,
+    #[serde(default, deserialize_with = "base64_list")]
+    nodes: Vec<Vec<u8>>,
+}
+
+fn base64_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    BASE64.decode(text).map_err(serde::de::Error::custom)
+}
+
+fn base64_optional<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<u8>>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(text) if !text.is_empty() => BASE64
+            .decode(text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+fn base64_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error> {
+    Option::<Vec<String>>::deserialize(deserializer)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|text| BASE64.decode(text).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Checks that the blob with `commitment` in `namespace` is included in the block at `height`.
+pub async fn verify(
+    node: &NodeClient,
+    height: u64,
+    namespace: &[u8],
+    commitment: &[u8],
+) -> Result<Verdict, VerifyError> {
+    let namespace_param = BASE64.encode(namespace);
+    let commitment_param = BASE64.encode(commitment);
+    let (header, blob, proofs) = futures::try_join!(
+        node.call::<ExtendedHeader>("header.GetByHeight", json!([height])),
+        node.call::<Blob>(
+            "blob.Get",
+            json!([height, namespace_param, commitment_param])
+        ),
+        node.call::<Vec<RangeProof>>(
+            "blob.GetProof",
+            json!([height, namespace_param, commitment_param])
+        ),
+    )?;
+
+    // The roots of the data availability header must hash to the header's data root
+    let expected = hex::decode(&header.header.data_hash)
+        .map_err(|e| VerifyError::Malformed(format!("data root is not hex: {}", e)))?;
+    let dah = header.dah;
+    if data_root(dah.row_roots.iter().chain(&dah.column_roots)) != expected.as_slice() {
+        return Ok(Verdict::NotIncluded(
+            "the row and column roots don't hash to the header's data root".to_string(),
+        ));
+    }
+
+    let width = dah.row_roots.len() / 2;
+    let start = usize::try_from(blob.index)
+        .map_err(|_| VerifyError::Malformed("the blob has no share index".to_string()))?;
+    if width == 0 {
+        return Err(VerifyError::Malformed("the square is empty".to_string()));
+    }
+    let shares = blob_shares(
+        namespace,
+        &blob.data,
+        blob.share_version,
+        blob.signer.as_deref(),
+    );
+    if share_commitment(namespace, &shares).as_slice() != commitment {
+        return Ok(Verdict::NotIncluded(
+            "the blob the node served doesn't hash to the commitment".to_string(),
+        ));
+    }
+    let end = start + shares.len();
+    let rows = start / width..=(end - 1) / width;
+    if proofs.len() != rows.clone().count() {
+        return Ok(Verdict::NotIncluded(format!(
+            "expected a proof for each of rows {} to {}, got {}",
+            rows.start(),
+            rows.end(),
+            proofs.len()
+        )));
+    }
+    if *rows.end() >= width {
+        return Ok(Verdict::NotIncluded(format!(
+            "the blob's shares end beyond the {}x{} square",
+            width, width
+        )));
+    }
+
+    // Each row's shares, with the row's proof, must hash to the row root
+    let mut remaining = shares.iter();
+    for (row, proof) in rows.clone().zip(&proofs) {
+        let first = if row == *rows.start() {
+            start % width
+        } else {
+            0
+        };
+        let last = if row == *rows.end() {
+            (end - 1) % width
+        } else {
+            width - 1
+        };
+        if (proof.start, proof.end) != (first, last + 1) {
+            return Ok(Verdict::NotIncluded(format!(
+                "the proof of row {} covers shares {} to {} rather than {} to {}",
+                row,
+                proof.start,
+                proof.end,
+                first,
+                last + 1
+            )));
+        }
+        let leaves: Vec<Vec<u8>> = remaining
+            .by_ref()
+            .take(last + 1 - first)
+            .map(|share| leaf_hash(namespace, share))
+            .collect();
+        let root = range_root(2 * width, proof.start..proof.end, leaves, &proof.nodes);
+        if root.as_deref() != Some(dah.row_roots[row].as_slice()) {
+            return Ok(Verdict::NotIncluded(format!(
+                "the shares in row {} don't match its root",
+                row
+            )));
+        }
+    }
+
+    Ok(Verdict::Included {
+        shares: shares.len(),
+        rows,
+        data_root: header.header.data_hash.to_uppercase(),
+    })
+}
+
+/// Splits a blob into the shares it occupies in the square: the first holds its length (and,
+/// from share version 1, its signer), the rest continue its data, and the last is zero-padded.
+pub fn blob_shares(
+    namespace: &[u8],
+    data: &[u8],
+    share_version: u8,
+    signer: Option<&[u8]>,
+) -> Vec<Vec<u8>> {
+    let mut shares = Vec::new();
+    let mut rest = data;
+    loop {
+        let first = shares.is_empty();
+        let mut share = Vec::with_capacity(SHARE_SIZE);
+        share.extend_from_slice(namespace);
+        share.push(share_version << 1 | u8::from(first));
+        if first {
+            share.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            if share_version == 1 {
+                let mut address = signer.unwrap_or_default().to_vec();
+                address.resize(SIGNER_SIZE, 0);
+                share.extend_from_slice(&address);
+            }
+        }
+        let (chunk, tail) = rest.split_at(rest.len().min(SHARE_SIZE - share.len()));
+        share.extend_from_slice(chunk);
+        share.resize(SHARE_SIZE, 0);
+        shares.push(share);
+
+        rest = tail;
+        if rest.is_empty() {
+            return shares;
+        }
+    }
+}
+
+/// The share commitment of a blob in `namespace` split into `shares`, as celestia-app computes
+/// it: the Merkle root of the NMT roots of the subtrees the shares are split into, each as wide
+/// as allowed for a blob of that many shares, then shrinking by powers of two for the rest.
+pub fn share_commitment(namespace: &[u8], shares: &[Vec<u8>]) -> [u8; 32] {
+    let width = subtree_width(shares.len());
+    let mut roots = Vec::new();
+    let mut rest = shares;
+    while !rest.is_empty() {
+        let size = if rest.len() >= width {
+            width
+        } else {
+            // The largest power of two that fits
+            1 << rest.len().ilog2()
+        };
+        let (subtree, tail) = rest.split_at(size);
+        let leaves: Vec<Vec<u8>> = subtree
+            .iter()
+            .map(|share| leaf_hash(namespace, share))
+            .collect();
+        roots.push(nmt_root(&leaves));
+        rest = tail;
+    }
+    data_root(roots.iter())
+}
+
+/// The width of the subtrees a blob of `shares` shares is committed to in: enough to keep
+/// within [`SUBTREE_ROOT_THRESHOLD`] roots, rounded up to a power of two, but no wider than the
+/// smallest square the blob fits in
+fn subtree_width(shares: usize) -> usize {
+    let width = shares.div_ceil(SUBTREE_ROOT_THRESHOLD).next_power_of_two();
+    let min_square = (shares as f64).sqrt().ceil() as usize;
+    width.min(min_square.next_power_of_two())
+}
+
+/// The NMT leaf of a share: its namespace as both bounds, and the hash of the namespaced share.
+pub fn leaf_hash(namespace: &[u8], share: &[u8]) -> Vec<u8> {
+    let digest = Sha256::new()
+        .chain_update([0x00])
+        .chain_update(namespace)
+        .chain_update(share)
+        .finalize();
+    [namespace, namespace, digest.as_slice()].concat()
+}
+
+/// An NMT inner node. Parity namespaces don't widen the range, so that a row's root bounds the
+/// namespaces of its original shares.
+pub fn node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let (left_min, left_max) = (
+        &left[..NAMESPACE_SIZE],
+        &left[NAMESPACE_SIZE..2 * NAMESPACE_SIZE],
+    );
+    let (right_min, right_max) = (
+        &right[..NAMESPACE_SIZE],
+        &right[NAMESPACE_SIZE..2 * NAMESPACE_SIZE],
+    );
+    let min = left_min.min(right_min);
+    let max = if left_min == PARITY_NAMESPACE {
+        &PARITY_NAMESPACE[..]
+    } else if right_min == PARITY_NAMESPACE {
+        left_max
+    } else {
+        left_max.max(right_max)
+    };
+    let digest = Sha256::new()
+        .chain_update([0x01])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize();
+    [min, max, digest.as_slice()].concat()
+}
+
+/// The root of an NMT over `leaves`.
+pub fn nmt_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+    match leaves {
+        [leaf] => leaf.clone(),
+        _ => {
+            let split = split_point(leaves.len());
+            node_hash(&nmt_root(&leaves[..split]), &nmt_root(&leaves[split..]))
+        }
+    }
+}
+
+/// Recomputes the root of an NMT of `size` leaves from the hashes of the leaves in `range` and
+/// the proof `nodes` covering the rest, or `None` if the proof doesn't fit the range.
+pub fn range_root(
+    size: usize,
+    range: std::ops::Range<usize>,
+    leaves: Vec<Vec<u8>>,
+    nodes: &[Vec<u8>],
+) -> Option<Vec<u8>> {
+    fn compute(
+        span: std::ops::Range<usize>,
+        range: &std::ops::Range<usize>,
+        leaves: &mut impl Iterator<Item = Vec<u8>>,
+        nodes: &mut impl Iterator<Item = Vec<u8>>,
+    ) -> Option<Vec<u8>> {
+        if span.end <= range.start || span.start >= range.end {
+            return nodes.next();
+        }
+        if span.len() == 1 {
+            return leaves.next();
+        }
+        let split = span.start + split_point(span.len());
+        let left = compute(span.start..split, range, leaves, nodes)?;
+        let right = compute(split..span.end, range, leaves, nodes)?;
+        Some(node_hash(&left, &right))
+    }
+
+    if range.is_empty() || range.end > size || leaves.len() != range.len() {
+        return None;
+    }
+    let mut leaves = leaves.into_iter();
+    let mut nodes = nodes.iter().cloned();
+    let root = compute(0..size, &range, &mut leaves, &mut nodes)?;
+    nodes.next().is_none().then_some(root)
+}
+
+/// The data root of a block: the RFC 6962 Merkle root of its row roots then column roots. Any
+/// other list of hashes is rooted the same way.
+pub fn data_root<'a>(roots: impl Iterator<Item = &'a Vec<u8>>) -> [u8; 32] {
+    fn root(items: &[&Vec<u8>]) -> [u8; 32] {
+        match items {
+            [] => Sha256::digest([]).into(),
+            [item] => Sha256::new()
+                .chain_update([0x00])
+                .chain_update(item)
+                .finalize()
+                .into(),
+            _ => {
+                let split = split_point(items.len());
+                Sha256::new()
+                    .chain_update([0x01])
+                    .chain_update(root(&items[..split]))
+                    .chain_update(root(&items[split..]))
+                    .finalize()
+                    .into()
+            }
+        }
+    }
+
+    root(&roots.collect::<Vec<_>>())
+}
+
+/// The size of the left subtree of a tree of `size` leaves: the largest power of two below it
+fn split_point(size: usize) -> usize {
+    size.next_power_of_two() / 2
+}
@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use rig::embeddings::{EmbeddingModel, EmbeddingsBuilder};
+use rig::vector_store::in_memory_store::{InMemoryVectorIndex, InMemoryVectorStore};
+use rig::vector_store::VectorStore;
+
+/// The Celestia documentation bundled with the crate, as `(name, markdown)` pairs.
+pub const SEED_DOCS: &[(&str, &str)] = &[
+    ("data-square", include_str!("../docs/data-square.md")),
+    (
+        "blobs-and-namespaces",
+        include_str!("../docs/blobs-and-namespaces.md"),
+    ),
+    (
+        "data-availability-sampling",
+        include_str!("../docs/data-availability-sampling.md"),
+    ),
+    ("fees-and-gas", include_str!("../docs/fees-and-gas.md")),
+    (
+        "tia-and-staking",
+        include_str!("../docs/tia-and-staking.md"),
+    ),
+    (
+        "block-production",
+        include_str!("../docs/block-production.md"),
+    ),
+];
+
+/// Appended to the preamble when documentation is available.
+pub const PREAMBLE: &str = "Answer conceptual questions about Celestia from the provided \
+    documentation. Use the tools for live or numeric chain data.";
+
+/// The number of documentation chunks added to the context of each prompt.
+pub const CONTEXT_SAMPLES: usize = 3;
+
+/// Chunks are built from whole paragraphs, up to roughly this many characters.
+const CHUNK_SIZE: usize = 1200;
+
+/// Captures the errors that may occur while building or loading the knowledge base.
+#[derive(Debug, thiserror::Error)]
+pub enum KnowledgeError {
+    #[error("Failed to embed documents: {0}")]
+    Embedding(#[from] rig::embeddings::EmbeddingError),
+    #[error("Vector store error: {0}")]
+    VectorStore(#[from] rig::vector_store::VectorStoreError),
+    #[error("Failed to read or write the index: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Index file is malformed: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Splits a document into paragraph-aligned chunks, identified as `<name>#<n>`.
+pub fn chunk(name: &str, text: &str) -> Vec<(String, String)> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if !current.is_empty() && current.len() + paragraph.len() > CHUNK_SIZE {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| (format!("{}#{}", name, i), chunk))
+        .collect()
+}
+
+/// Reads the markdown and text files in `dir`, as `(name, contents)` pairs.
+pub fn read_docs_dir(dir: &Path) -> Result<Vec<(String, String)>, KnowledgeError> {
+    let mut docs = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_doc = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("md" | "txt")
+        );
+        if !is_doc {
+            continue;
+        }
+
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        docs.push((name, std::fs::read_to_string(&path)?));
+    }
+
+    docs.sort();
+    Ok(docs)
+}
+
+/// Embeds the chunks of every document into a new vector store.
+pub async fn build_store<M: EmbeddingModel>(
+    model: M,
+    docs: &[(String, String)],
+) -> Result<InMemoryVectorStore, KnowledgeError> {
+    let chunks = docs
+        .iter()
+        .flat_map(|(name, text)| chunk(name, text))
+        .collect();
+    let embeddings = EmbeddingsBuilder::new(model)
+        .simple_documents(chunks)
+        .build()
+        .await?;
+
+    let mut store = InMemoryVectorStore::default();
+    store.add_documents(embeddings).await?;
+    Ok(store)
+}
+
+/// Writes the store to `path` as JSON.
+pub fn save(store: &InMemoryVectorStore, path: &Path) -> Result<(), KnowledgeError> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    Ok(serde_json::to_writer(file, store)?)
+}
+
+/// Loads a store written by [`save`], indexed for querying with `model`.
+pub fn load<M: EmbeddingModel>(
+    model: M,
+    path: &Path,
+) -> Result<InMemoryVectorIndex<M>, KnowledgeError> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let store: InMemoryVectorStore = serde_json::from_reader(file)?;
+    Ok(store.index(model))
+}
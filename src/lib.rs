@@ -0,0 +1,195 @@
+pub mod access;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod accounting;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod address_activity_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod address_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod alert;
+pub mod amount;
+pub mod analytics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod answer_cache;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod assistant;
+pub mod audit;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod azure;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod balance_history_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod batch;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bench;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod blob_cost_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod blob_fees_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod block_limits_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod block_rewards_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod block_txs_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod blockspace_forecast_tool;
+pub mod byte_size;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod capabilities_tool;
+pub mod celenium;
+pub mod celestia_search_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod chain_params_tool;
+pub mod chart;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod compare_blocks_tool;
+pub mod compat;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod countdown_tool;
+pub mod cursor;
+pub mod de;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod doctor;
+pub mod enums;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod estimate_time_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod event_search_tool;
+pub mod export;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fast_path;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fee_histogram_tool;
+pub mod fetcher;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fill_rate_trend_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod format;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gas_efficiency_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gas_stats_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gemini;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod head_context;
+#[cfg(feature = "node-rpc")]
+pub mod inclusion;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod knowledge;
+pub mod locale;
+pub mod lru;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "node-rpc")]
+pub mod mempool_tool;
+pub mod metrics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod namespace_blobs_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod namespace_tool;
+pub mod network;
+#[cfg(feature = "node-rpc")]
+pub mod node;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "node-rpc")]
+pub mod node_retention_tool;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "node-rpc")]
+pub mod node_status_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod notify;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pending_rewards_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pending_unbondings_tool;
+pub mod pfb_gas;
+pub mod postprocess;
+pub mod preamble;
+pub mod prefetch;
+pub mod price;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod proposal_votes_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod provenance;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod provider;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod registry;
+pub mod repl;
+pub mod replay;
+pub mod rest;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rollup_activity_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod router;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "node-rpc")]
+pub mod sampling_tool;
+pub mod sanitize;
+pub mod schedule;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod search_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod session;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod shutdown;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod slashing_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod square_size_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod square_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod staking_yield_tool;
+#[cfg(feature = "sqlite-cache")]
+pub mod store;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "sqlite-cache")]
+pub mod store_query_tool;
+pub mod structured;
+pub mod summarize;
+pub mod template;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "market-data")]
+pub mod tia_price_tool;
+pub mod time;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tool_timeout;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod top_accounts_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod top_namespaces_tool;
+pub mod trace;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod transcript;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tx_fee_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod upgrades_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod validator_directory;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod validator_rewards_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod validator_tool;
+pub mod verify;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "node-rpc")]
+pub mod verify_blob_tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watch;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "server")]
+pub mod webhook;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod who_proposed_tool;
+
+pub use celestia_search_tool::CelestiaResponseFields;
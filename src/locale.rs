@@ -0,0 +1,160 @@
+//! Answers in other languages than English, for `--lang`.
+//!
+//! The agent is told to answer in the language but to keep writing numbers as tool results
+//! do, so they can still be checked against them; they are only written the language's way
+//! when the answer is printed.
+
+/// A language answers can be given in, with how it writes numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Language {
+    /// The ISO 639-1 code it's chosen by, e.g. `de`.
+    pub code: &'static str,
+    /// Its English name, as the agent is told.
+    pub name: &'static str,
+    decimal: char,
+    grouping: char,
+}
+
+impl Language {
+    const fn new(code: &'static str, name: &'static str, decimal: char, grouping: char) -> Self {
+        Self {
+            code,
+            name,
+            decimal,
+            grouping,
+        }
+    }
+}
+
+pub const ENGLISH: Language = Language::new("en", "English", '.', ',');
+
+/// The languages answers can be given in.
+pub const LANGUAGES: [Language; 11] = [
+    ENGLISH,
+    Language::new("de", "German", ',', '.'),
+    Language::new("es", "Spanish", ',', '.'),
+    // A narrow no-break space separates thousands in French
+    Language::new("fr", "French", ',', '\u{202f}'),
+    Language::new("it", "Italian", ',', '.'),
+    Language::new("ja", "Japanese", '.', ','),
+    Language::new("ko", "Korean", '.', ','),
+    Language::new("pt", "Portuguese", ',', '.'),
+    Language::new("ru", "Russian", ',', '\u{a0}'),
+    Language::new("tr", "Turkish", ',', '.'),
+    Language::new("zh", "Chinese", '.', ','),
+];
+
+impl Default for Language {
+    fn default() -> Self {
+        ENGLISH
+    }
+}
+
+/// Parses a language by its code, ignoring case and any region (`pt-BR` is Portuguese).
+pub fn parse(code: &str) -> Result<Language, String> {
+    let language = code.trim().split(['-', '_']).next().unwrap_or_default();
+    LANGUAGES
+        .into_iter()
+        .find(|known| known.code.eq_ignore_ascii_case(language))
+        .ok_or_else(|| {
+            let codes: Vec<&str> = LANGUAGES.iter().map(|known| known.code).collect();
+            format!(
+                "unknown language `{}` (expected one of: {})",
+                code,
+                codes.join(", ")
+            )
+        })
+}
+
+impl Language {
+    /// What the agent is told about the language of its answers, if it isn't English.
+    pub fn instructions(self) -> Option<String> {
+        (self != ENGLISH).then(|| {
+            format!(
+                "Answer in {}, whatever the language of the question. Keep heights, hashes, \
+                 addresses, namespaces and units such as utia and TIA as they are, and write \
+                 numbers as the tool results do, with `.` as the decimal point; they are \
+                 written the {} way when the answer is printed.",
+                self.name, self.name
+            )
+        })
+    }
+
+    /// Writes the decimal numbers in `text`, and those with thousands separators, the
+    /// language's way, e.g. `1,234.5` as `1.234,5` in German.
+    ///
+    /// Plain integers such as heights are left as they are, as are numbers within words (hashes,
+    /// versions such as `v1.2`) and those in other forms, such as IP addresses.
+    pub fn localize(self, text: &str) -> String {
+        if self.decimal == '.' && self.grouping == ',' {
+            return text.to_string();
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        let mut output = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let starts_number = chars[i].is_ascii_digit()
+                && (i == 0 || !is_word_char(chars[i - 1]) && chars[i - 1] != '.');
+            if !starts_number {
+                output.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            let mut end = i;
+            while end < chars.len()
+                && (chars[end].is_ascii_digit() || matches!(chars[end], '.' | ','))
+            {
+                end += 1;
+            }
+            // A stop or comma ending the number ends the sentence or clause instead
+            while matches!(chars[end - 1], '.' | ',') {
+                end -= 1;
+            }
+            let number: String = chars[i..end].iter().collect();
+            let within_word = end < chars.len() && is_word_char(chars[end]);
+            match self.number(&number).filter(|_| !within_word) {
+                Some(localized) => output.push_str(&localized),
+                None => output.push_str(&number),
+            }
+            i = end;
+        }
+        output
+    }
+
+    /// `number` written the language's way, if it is a decimal or grouped number
+    fn number(self, number: &str) -> Option<String> {
+        let (integer, fraction) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (number, None),
+        };
+        if fraction.is_some_and(|fraction| fraction.is_empty() || !is_digits(fraction)) {
+            return None;
+        }
+        let groups: Vec<&str> = integer.split(',').collect();
+        let grouped = groups.len() > 1;
+        let valid_groups = groups[0].len() <= 3 && groups[1..].iter().all(|group| group.len() == 3);
+        if groups.iter().any(|group| !is_digits(group)) || grouped && !valid_groups {
+            return None;
+        }
+        if !grouped && fraction.is_none() {
+            return None;
+        }
+
+        let mut localized = groups.join(&self.grouping.to_string());
+        if let Some(fraction) = fraction {
+            localized.push(self.decimal);
+            localized.push_str(fraction);
+        }
+        Some(localized)
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_digit())
+}
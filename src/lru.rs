@@ -0,0 +1,103 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::metrics::metrics;
+
+/// An in-memory cache bounded by its number of entries and their approximate size, evicting the
+/// least recently used entries first.
+///
+/// Evictions are counted in [`Metrics::cache_evictions`](crate::metrics::Metrics), labelled by
+/// the cache's name and the bound that forced them (`entries` or `memory`).
+#[derive(Debug)]
+pub struct LruCache<V> {
+    name: &'static str,
+    max_entries: usize,
+    max_bytes: usize,
+    entries: HashMap<String, Slot<V>>,
+    /// Keys by when they were last used, least recently first
+    order: BTreeMap<u64, String>,
+    bytes: usize,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Slot<V> {
+    value: V,
+    size: usize,
+    used: u64,
+}
+
+impl<V> LruCache<V> {
+    /// Creates an empty cache holding at most `max_entries` entries of `max_bytes` in total.
+    pub fn new(name: &'static str, max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            name,
+            max_entries,
+            max_bytes,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            bytes: 0,
+            tick: 0,
+        }
+    }
+
+    /// The value cached under `key`, marking it as recently used.
+    pub fn get(&mut self, key: &str) -> Option<&V> {
+        let tick = self.next_tick();
+        let slot = self.entries.get_mut(key)?;
+        let key = self
+            .order
+            .remove(&slot.used)
+            .expect("cached keys are ordered");
+        self.order.insert(tick, key);
+        slot.used = tick;
+        Some(&slot.value)
+    }
+
+    /// Caches `value`, taking about `size` bytes, under `key`, then evicts the least recently
+    /// used entries until the cache is within its bounds again.
+    ///
+    /// A value larger than the whole cache is evicted straight away.
+    pub fn insert(&mut self, key: &str, value: V, size: usize) {
+        let used = self.next_tick();
+        let slot = Slot { value, size, used };
+        if let Some(replaced) = self.entries.insert(key.to_string(), slot) {
+            self.order.remove(&replaced.used);
+            self.bytes -= replaced.size;
+        }
+        self.order.insert(used, key.to_string());
+        self.bytes += size;
+
+        while self.entries.len() > self.max_entries || self.bytes > self.max_bytes {
+            let reason = match self.entries.len() > self.max_entries {
+                true => "entries",
+                false => "memory",
+            };
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.bytes -= evicted.size;
+            }
+            metrics().cache_evictions.inc(&[self.name, reason]);
+        }
+    }
+
+    /// How many entries are cached.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The approximate size of the cached entries, in bytes.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
@@ -1,27 +1,1275 @@
-mod celestia_search_tool;
+mod cli;
 
-use crate::celestia_search_tool::CelestiaSearchTool;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
-use rig::completion::Prompt;
+use celestia_search_assistant::accounting::{Ledger, PriceTable};
+use celestia_search_assistant::alert::{self, Condition};
+use celestia_search_assistant::answer_cache::AnswerCache;
+use celestia_search_assistant::assistant::{Assistant, GenerationParams, ToolCall, Turn};
+use celestia_search_assistant::audit::{self, AuditLog};
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::doctor::{self, Check};
+use celestia_search_assistant::enums::Enums;
+use celestia_search_assistant::fast_path::Shortcut;
+use celestia_search_assistant::format::{self, OutputFormat};
+#[cfg(feature = "grpc")]
+use celestia_search_assistant::grpc::GrpcClient;
+#[cfg(feature = "server")]
+use celestia_search_assistant::metrics;
+use celestia_search_assistant::network::Network;
+#[cfg(feature = "node-rpc")]
+use celestia_search_assistant::node::NodeClient;
+use celestia_search_assistant::notify::{self, Event, Notifier};
+use celestia_search_assistant::price::PriceFeed;
+use celestia_search_assistant::provider::{LlmClient, Provider, ProviderError, ReplayedModel};
+use celestia_search_assistant::registry::{ToolContext, ToolRegistry};
+use celestia_search_assistant::repl::{self, SlashCommand};
+use celestia_search_assistant::replay::{Recording, Tape};
+use celestia_search_assistant::router::{self, Route};
+use celestia_search_assistant::schedule::{self, Job};
+use celestia_search_assistant::session::{Session, SessionStore};
+#[cfg(feature = "sqlite-cache")]
+use celestia_search_assistant::store::BlockStore;
+use celestia_search_assistant::transcript::Transcript;
+use celestia_search_assistant::watch::ChangeFilter;
+#[cfg(feature = "server")]
+use celestia_search_assistant::webhook::{self, Payload};
+use celestia_search_assistant::{
+    batch, bench, byte_size, export, fetcher, head_context, knowledge, postprocess, preamble,
+    prefetch, provenance, shutdown, structured, summarize, template, trace, watch,
+};
+
+use crate::cli::{Cli, Command, SessionsCommand};
+
+use clap::Parser;
+use futures::StreamExt;
+use rig::completion::Message;
 use rig::providers::openai;
+use tokio::io::AsyncBufReadExt;
+
+const EMBEDDING_MODEL: &str = openai::TEXT_EMBEDDING_3_SMALL;
+
+/// Stands in for `--prompt` when neither it nor piped input is given.
+const DEFAULT_PROMPT: &str = "What is the gas fee of the Celestia block at height 9999?";
+
+/// How many tokens of each tool result `--verbose` shows for the steps of an answer.
+const STEP_PREVIEW_TOKENS: usize = 100;
+
+/// The answers kept for questions asked again, once `--answer-cache` sets it up.
+static ANSWERS: OnceLock<AnswerCache> = OnceLock::new();
+
+/// Where every question answered is logged, once `--audit-log` sets it up.
+static AUDIT: OnceLock<AuditLog> = OnceLock::new();
+
+/// How many webhook payloads may wait to be checked before new ones are held up.
+#[cfg(feature = "server")]
+const WEBHOOK_QUEUE: usize = 256;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let openai_client = openai::Client::from_env();
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let verbose = cli.verbose;
+    shutdown::install();
+    // A command still running when it should stop is dropped, cancelling its requests in flight
+    let result = tokio::select! {
+        result = run(cli) => result,
+        _ = shutdown::forced() => Err(shutdown::Interrupted.into()),
+    };
+    trace::flush().await;
+    if verbose {
+        eprintln!(
+            "{}",
+            celestia_search_assistant::metrics::metrics().bandwidth()
+        );
+    }
+
+    // Report failures on stderr with a non-zero status, so scripts can tell them apart
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.is::<shutdown::Interrupted>() => {
+            eprintln!("{}", e);
+            // Exit at once: a read of stdin still blocked would keep the runtime from shutting down
+            let _ = std::io::stdout().flush();
+            std::process::exit(shutdown::EXIT_INTERRUPTED.into())
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(mut cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    // Export spans of turns, tool calls and requests if a collector has been configured
+    trace::install_from_env();
+    byte_size::set_units(cli.byte_units);
+
+    // Expose Prometheus metrics if an address has been configured
+    #[cfg(feature = "server")]
+    if let Ok(addr) = std::env::var("CELESTIA_METRICS_ADDR") {
+        let addr = addr.parse()?;
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(addr).await {
+                eprintln!("Metrics endpoint failed: {}", e);
+            }
+        });
+    }
+
+    // The doctor reports what would go wrong below, instead of stopping at it
+    if let Some(Command::Doctor) = cli.command {
+        return run_doctor(&cli).await;
+    }
+
+    let config = match &cli.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+
+    // A preamble given on the command line takes precedence over the configured one
+    if let Some(custom) = config
+        .preamble
+        .as_ref()
+        .filter(|_| cli.preamble_file.is_none())
+    {
+        cli.preamble_file = Some(custom.file.clone());
+        cli.preamble_mode = cli.preamble_mode.or(Some(custom.mode));
+    }
+
+    #[cfg(feature = "sqlite-cache")]
+    let store = match &cli.db {
+        Some(path) => Some(Arc::new(BlockStore::open(path)?)),
+        None => None,
+    };
+    // A replay only answers from the recording
+    cli.offline |= cli.replay.is_some();
+    // A model asked again answers the recorded data as deterministically as it can
+    if cli.replay_model {
+        cli.temperature = 0.0;
+    }
+    let tape = match (&cli.record, &cli.replay) {
+        (Some(_), _) => Some(Tape::Record(Arc::default())),
+        (None, Some(path)) => Some(Tape::Replay(Arc::new(Recording::load(path)?))),
+        (None, None) => None,
+    };
+    if cli.answer_cache {
+        let _ = ANSWERS.set(AnswerCache::new(Duration::from_secs(cli.answer_ttl)));
+    }
+    if let Some(path) = &cli.audit_log {
+        let _ = AUDIT.set(AuditLog::new(path, cli.audit_max_bytes, cli.audit_keep));
+    }
+    let tool_context = ToolContext {
+        network: cli.network,
+        clients: Network::ALL
+            .into_iter()
+            .map(|network| {
+                let client = config.celenium_client(network)?;
+                let client = match &tape {
+                    Some(tape) => client.with_tape(tape.clone()),
+                    None => client,
+                };
+                Ok((network, client))
+            })
+            .collect::<Result<_, CelestiaSearchError>>()?,
+        rest_urls: Network::ALL
+            .into_iter()
+            .map(|network| (network, config.rest_url(network)))
+            .collect(),
+        #[cfg(feature = "grpc")]
+        grpc: match config.grpc.is_empty() {
+            true => None,
+            false => Some(Arc::new(GrpcClient::new(config.grpc.clone())?)),
+        },
+        #[cfg(feature = "node-rpc")]
+        node: config
+            .node
+            .as_ref()
+            .filter(|_| !cli.offline)
+            .map(|node| Arc::new(NodeClient::from_config(node))),
+        enums: None,
+        #[cfg(feature = "sqlite-cache")]
+        store,
+        price_feed: (!cli.no_fiat && !cli.offline).then(|| Arc::new(PriceFeed::default())),
+        offline: cli.offline,
+        tape,
+        timeouts: config.tools.clone(),
+        allowed: config.access.allowed(cli.api_key.as_deref())?,
+    };
+
+    // Warm the cache for the commands that answer questions, without holding up the first one
+    let answers = !matches!(
+        cli.command,
+        Some(
+            Command::Export { .. }
+                | Command::Watch { .. }
+                | Command::Alert { .. }
+                | Command::Sessions { .. }
+                | Command::IndexDocs { .. }
+                | Command::Bench { .. }
+        )
+    );
+    if cli.prefetch_blocks > 0 && answers && !tool_context.offline {
+        let (tool, count, verbose) = (tool_context.block_tool(), cli.prefetch_blocks, cli.verbose);
+        tokio::spawn(async move {
+            match prefetch::recent_blocks(&tool, count).await {
+                Ok(fetched) if verbose => eprintln!("Prefetched {} recent block(s)", fetched),
+                Ok(_) => {}
+                Err(e) => eprintln!("Could not prefetch recent blocks: {}", e),
+            }
+        });
+    }
+
+    let sessions = SessionStore::new(
+        cli.sessions_dir
+            .clone()
+            .unwrap_or_else(SessionStore::default_dir),
+    );
+
+    match cli.command {
+        Some(Command::Export {
+            from,
+            to,
+            out,
+            concurrency,
+        }) => return run_export(tool_context.block_tool(), from, to, out, concurrency).await,
+        Some(Command::Watch {
+            from,
+            interval,
+            diff,
+            only,
+        }) => return run_watch(tool_context.block_tool(), from, interval, diff, only).await,
+        Some(Command::Alert {
+            conditions,
+            from,
+            interval,
+            #[cfg(feature = "server")]
+            listen,
+            #[cfg(feature = "server")]
+            webhook_secret,
+        }) => {
+            let notifiers = notify::from_config(&config.notifiers);
+            #[cfg(feature = "server")]
+            if let Some(addr) = listen {
+                return run_webhook_alert(
+                    tool_context.block_tool(),
+                    conditions,
+                    notifiers,
+                    addr,
+                    webhook_secret,
+                )
+                .await;
+            }
+            return run_alert(
+                tool_context.block_tool(),
+                conditions,
+                notifiers,
+                from,
+                interval,
+            )
+            .await;
+        }
+        Some(Command::Chat) => {
+            let llm = llm_client(&cli, &config, &tool_context)?;
+            return run_chat(&cli, &llm, &tool_context, &sessions).await;
+        }
+        Some(Command::Sessions { action }) => return run_sessions(&sessions, action),
+        Some(Command::Daemon) => {
+            let llm = llm_client(&cli, &config, &tool_context)?;
+            return run_daemon(&cli, &llm, &tool_context, config).await;
+        }
+        #[cfg(feature = "tui")]
+        Some(Command::Tui { interval }) => {
+            let llm = llm_client(&cli, &config, &tool_context)?;
+            return run_tui(&cli, &llm, &tool_context, interval).await;
+        }
+        Some(Command::IndexDocs { out, dir }) => return run_index_docs(out, dir).await,
+        Some(Command::Bench { height, runs }) => {
+            return run_bench(&cli, &tool_context, height, runs).await
+        }
+        Some(Command::Doctor) => unreachable!("the doctor runs before the context is built"),
+        None => {}
+    }
+
+    let prices = PriceTable::from_env()?;
+    let mut ledger = Ledger::new(&cli.model);
+
+    if let Some(path) = &cli.batch {
+        let questions = batch::questions(&std::fs::read_to_string(path)?);
+        let llm = llm_client(&cli, &config, &tool_context)?;
+        run_batch(&cli, &llm, &tool_context, &mut ledger, questions).await?;
+        eprintln!("{}", ledger.summary(&prices));
+        return Ok(());
+    }
+
+    let prompt = match &cli.template {
+        Some(name) => template::render(name, &cli.params)?,
+        None => read_prompt(cli.prompt.as_deref())?,
+    };
+    let mut session = match &cli.session {
+        Some(name) => Some((name, sessions.load(name)?)),
+        None => None,
+    };
+    let history = session
+        .as_ref()
+        .map(|(_, session)| session.history())
+        .unwrap_or_default();
+
+    let llm = llm_client(&cli, &config, &tool_context)?;
+    let tool_context = with_enums(&tool_context).await;
+    let (route, turn) = ask(&cli, &llm, &tool_context, &mut ledger, &prompt, history).await?;
+
+    // Planned tool calls have no answer to build on
+    if let Some((name, session)) = &mut session {
+        if !cli.dry_run {
+            session.push_turn(&prompt, &postprocess::answer(&turn.output));
+            sessions.save(name, session)?;
+        }
+    }
+
+    // JSON and markdown output already include the tool data
+    if cli.show_tools && cli.output == OutputFormat::Text {
+        println!("Route: {}", route.name());
+        for call in turn.steps.iter().chain(&turn.tool_call) {
+            if let ToolCall {
+                name,
+                args,
+                output: Some(output),
+                ..
+            } = call
+            {
+                println!("Tool call: {} {}\nRaw result: {}\n", name, args, output);
+            }
+        }
+    }
+
+    if cli.verbose {
+        print_steps(&turn);
+    }
+    println!("{}", render(&cli, &prompt, &turn)?);
+    for warning in &turn.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if let Some(path) = &cli.transcript_out {
+        let mut transcript = Transcript::default();
+        transcript.push(&prompt, &turn);
+        transcript.write(path)?;
+    }
+    eprintln!("{}", ledger.summary(&prices));
+
+    Ok(())
+}
+
+/// Renders the answer in the chosen format, with its sources if asked for.
+fn render(cli: &Cli, prompt: &str, turn: &Turn) -> Result<String, Box<dyn std::error::Error>> {
+    let language = cli.lang.unwrap_or_default();
+    let mut output = format::render(prompt, turn, cli.output, cli.detail(), language)?;
+    // JSON output carries the tool calls themselves
+    let footed = matches!(cli.output, OutputFormat::Text | OutputFormat::Markdown);
+    if let Some(footer) = provenance::footer(turn, cli.network).filter(|_| cli.sources && footed) {
+        output.push_str(&format!("\n\n{}", footer));
+    }
+    Ok(output)
+}
+
+/// Prints each tool call the agent chained to answer, with a preview of its result, on stderr.
+fn print_steps(turn: &Turn) {
+    for (i, call) in turn.steps.iter().chain(&turn.tool_call).enumerate() {
+        let result = match &call.output {
+            Some(output) => summarize::fit(output, STEP_PREVIEW_TOKENS).into_owned(),
+            None => "not executed (dry run)".to_string(),
+        };
+        eprintln!(
+            "Step {}: {} {}\n  -> {}",
+            i + 1,
+            call.name,
+            call.args,
+            result
+        );
+    }
+}
 
-    let agent = openai_client
-        .agent("gpt-4o-mini")
-        .preamble("You are a helpful assistant.")
-        .tool(CelestiaSearchTool)
-        .build();
+/// Reads the question from stdin if `--prompt` is `-`, or if it's omitted and input is piped.
+fn read_prompt(arg: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+    let stdin = std::io::stdin();
+    match arg {
+        Some("-") => {}
+        Some(prompt) => return Ok(prompt.to_string()),
+        None if stdin.is_terminal() => return Ok(DEFAULT_PROMPT.to_string()),
+        None => {}
+    }
+
+    let prompt = std::io::read_to_string(stdin)?.trim().to_string();
+    if prompt.is_empty() {
+        return Err("no question was given on stdin".into());
+    }
+    Ok(prompt)
+}
+
+/// Creates the OpenAI client, failing rather than panicking if no API key is set.
+fn openai_client() -> Result<openai::Client, Box<dyn std::error::Error>> {
+    let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| "OPENAI_API_KEY is not set")?;
+    Ok(openai::Client::new(&api_key))
+}
+
+/// Adds Celenium's vocabularies to the context, once the assistant is known to be able to run.
+///
+/// Tools work without them, only unchecked, so a failure to fetch them is just reported, and
+/// they aren't fetched offline.
+async fn with_enums(tool_context: &ToolContext) -> ToolContext {
+    let mut tool_context = tool_context.clone();
+    if tool_context.offline {
+        return tool_context;
+    }
+    match Enums::fetch(&tool_context.block_tool()).await {
+        Ok(enums) => tool_context.enums = Some(Arc::new(enums)),
+        Err(e) => eprintln!("Could not fetch Celenium's enums: {}", e),
+    }
+    tool_context
+}
+
+/// Fails once the session has spent what `--max-session-usd` allows.
+fn within_budget(cli: &Cli, ledger: &Ledger) -> Result<(), Box<dyn std::error::Error>> {
+    match cli.max_session_usd {
+        Some(limit) => Ok(ledger.within_budget(&PriceTable::from_env()?, limit)?),
+        None => Ok(()),
+    }
+}
+
+/// The client of the configured provider, or on a replay one serving the recorded completions
+/// unless `--replay-model` asks the model again
+fn llm_client(
+    cli: &Cli,
+    config: &Config,
+    tool_context: &ToolContext,
+) -> Result<LlmClient, ProviderError> {
+    match tool_context.tape.as_ref().and_then(Tape::replaying) {
+        Some(recording) if !cli.replay_model => {
+            Ok(LlmClient::Replay(ReplayedModel::new(recording.clone())))
+        }
+        _ => LlmClient::new(cli.provider, config.azure.as_ref(), config.gemini.as_ref()),
+    }
+}
+
+/// Answers a prompt with the sub-agent the router picks, recording the tokens spent.
+async fn ask(
+    cli: &Cli,
+    llm: &LlmClient,
+    tool_context: &ToolContext,
+    ledger: &mut Ledger,
+    prompt: &str,
+    history: &[Message],
+) -> Result<(Route, Turn), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let answered = answer(cli, llm, tool_context, ledger, prompt, history).await;
+
+    if let Some(log) = AUDIT.get() {
+        let record = audit::Record {
+            prompt,
+            model: &cli.model,
+            network: cli.network,
+            elapsed: started.elapsed(),
+            outcome: match &answered {
+                Ok((route, turn, _)) => Ok((*route, turn)),
+                Err(e) => Err(e.to_string()),
+            },
+            cached: answered.as_ref().is_ok_and(|(.., cached)| *cached),
+        };
+        if let Err(e) = log.write(&record) {
+            eprintln!("Could not write to the audit log: {}", e);
+        }
+    }
+    answered.map(|(route, turn, _)| (route, turn))
+}
+
+/// Answers `prompt`, with whether the answer was kept from an earlier question
+async fn answer(
+    cli: &Cli,
+    llm: &LlmClient,
+    tool_context: &ToolContext,
+    ledger: &mut Ledger,
+    prompt: &str,
+    history: &[Message],
+) -> Result<(Route, Turn, bool), Box<dyn std::error::Error>> {
+    within_budget(cli, ledger)?;
+    // Follow-up questions depend on the conversation, so only opening ones are answered again
+    let answers = ANSWERS.get().filter(|_| history.is_empty() && !cli.dry_run);
+    let scope = answer_scope(cli, tool_context);
+    if let Some((route, turn)) = answers.and_then(|answers| answers.get(&scope, prompt)) {
+        return Ok((route, turn, true));
+    }
+    if let Some(shortcut) = shortcut(cli, tool_context, prompt) {
+        match shortcut.answer(&tool_context.block_tool()).await {
+            Ok(turn) => return Ok((Route::Live, turn, false)),
+            Err(e) if cli.verbose => eprintln!("Asking the agent, as the lookup failed: {}", e),
+            Err(_) => {}
+        }
+    }
+    let model = match &tool_context.tape {
+        Some(Tape::Record(recording)) => {
+            llm.completion_model(&cli.model).recorded(recording.clone())
+        }
+        _ => llm.completion_model(&cli.model),
+    };
+
+    // Let the router agent pick the sub-agent best suited to the question
+    let route = if cli.no_route {
+        Route::General
+    } else {
+        // Classification should be deterministic, whatever the answer's parameters
+        let router_agent = Assistant::builder(model.clone(), &cli.model)
+            .preamble(router::PREAMBLE)
+            .params(GenerationParams {
+                temperature: Some(0.0),
+                ..GenerationParams::default()
+            })
+            .build();
+        let (route, turn) = router::classify(&router_agent, prompt).await?;
+        ledger.record(turn.usage);
+        route
+    };
+
+    let tools =
+        ToolRegistry::with_builtin_tools()
+            .build(tool_context, cli.tools.as_deref(), |kind| route.uses(kind))?;
+
+    let mut builder = Assistant::builder(model, &cli.model)
+        .preamble(&preamble::load(
+            cli.preamble_file.as_deref(),
+            cli.preamble_mode.unwrap_or_default(),
+        )?)
+        .append_preamble(route.instructions())
+        .params(GenerationParams {
+            temperature: Some(cli.temperature),
+            max_tokens: cli.max_tokens,
+            top_p: cli.top_p,
+        })
+        .tools(tools)
+        .result_budget(cli.result_tokens)
+        .max_iterations(cli.max_iterations)
+        .verification(cli.verify)
+        .dry_run(cli.dry_run);
+    if cli.output == OutputFormat::Structured {
+        builder = builder
+            .append_preamble(structured::PREAMBLE)
+            .append_preamble(&structured::instructions())
+            .structured(true);
+    }
+    if let Some(limit) = cli.max_tool_calls {
+        builder = builder.max_tool_calls(limit);
+    }
+    if let Some(enums) = &tool_context.enums {
+        builder = builder.append_preamble(&enums.preamble());
+    }
+    if let Some(instructions) = cli.detail().instructions() {
+        builder = builder.append_preamble(instructions);
+    }
+    if let Some(instructions) = cli.lang.and_then(|language| language.instructions()) {
+        builder = builder.append_preamble(&instructions);
+    }
+    if cli.head_context && !cli.offline {
+        match head_context::describe(&tool_context.block_tool(), chrono::Utc::now()).await {
+            Ok(context) => builder = builder.append_preamble(&context),
+            Err(e) => eprintln!("Could not fetch the chain head for context: {}", e),
+        }
+    }
+
+    let docs_index = cli.docs_index.as_ref().filter(|_| route.uses_docs());
+    let turn = match docs_index {
+        Some(path) => {
+            // The docs are embedded with OpenAI's model whichever provider answers
+            let embeddings = openai_client()?.embedding_model(EMBEDDING_MODEL);
+            let index = knowledge::load(embeddings, path)?;
+            builder
+                .append_preamble(knowledge::PREAMBLE)
+                .dynamic_context(knowledge::CONTEXT_SAMPLES, index)
+                .build()
+                .chat(prompt, history)
+                .await?
+        }
+        None => builder.build().chat(prompt, history).await?,
+    };
+    ledger.record(turn.usage);
+    // Saved after every answer, so the recording covers the session however it ends
+    if let (Some(Tape::Record(recording)), Some(path)) = (&tool_context.tape, &cli.record) {
+        let mut recording = recording.lock().unwrap();
+        recording.record_turn(&turn);
+        recording.save(path)?;
+    }
+    if let Some(answers) = answers {
+        answers.insert(&scope, prompt, route, &turn);
+    }
+
+    Ok((route, turn, false))
+}
+
+/// The shortcut `prompt` can be answered with instead of the agent, unless the settings ask
+/// for an answer only the agent can give: planned calls, structured output, another language,
+/// another level of detail or without the block tool
+fn shortcut(cli: &Cli, tool_context: &ToolContext, prompt: &str) -> Option<Shortcut> {
+    let shortcut = Shortcut::parse(prompt)?;
+    let tool = shortcut.tool();
+    let plain = !cli.no_fast_path
+        && !cli.dry_run
+        && cli.output != OutputFormat::Structured
+        && cli
+            .lang
+            .and_then(|language| language.instructions())
+            .is_none()
+        && cli.detail().instructions().is_none()
+        && cli
+            .tools
+            .as_ref()
+            .is_none_or(|tools| tools.iter().any(|name| name == tool))
+        && tool_context.allowed.permits(tool);
+    plain.then_some(shortcut)
+}
+
+/// The settings an answer depends on besides the question, which a kept answer must share;
+/// callers of different roles don't share answers, as they aren't given the same tools
+fn answer_scope(cli: &Cli, tool_context: &ToolContext) -> String {
+    format!(
+        "{:?}|{}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{}|{:?}|{:?}|{:?}",
+        cli.provider,
+        cli.model,
+        cli.network,
+        cli.tools,
+        cli.no_route,
+        cli.output,
+        cli.detail(),
+        cli.lang,
+        cli.verify,
+        cli.temperature,
+        cli.preamble_file,
+        cli.docs_index,
+        tool_context.allowed
+    )
+}
+
+/// Answers questions read line by line, handling slash commands between them.
+async fn run_chat(
+    cli: &Cli,
+    llm: &LlmClient,
+    tool_context: &ToolContext,
+    sessions: &SessionStore,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let prices = PriceTable::from_env()?;
+
+    // Slash commands change these settings for the rest of the session
+    let mut cli = cli.clone();
+    let mut tool_context = with_enums(tool_context).await;
+
+    let mut session = match &cli.session {
+        Some(name) => sessions.load(name)?,
+        None => Session::default(),
+    };
+    let mut ledger = Ledger::new(&cli.model);
+    let mut transcript = Transcript::default();
+
+    // Ctrl-C ends the chat like /quit, abandoning the question being answered; the session is
+    // saved after every answer, so only the transcript and the ledger are left to write
+    let _graceful = shutdown::Graceful::new();
+    eprintln!("Asking about Celestia {}, /help for commands", cli.network);
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        eprint!("> ");
+        let Ok(line) = shutdown::interruptible(lines.next_line()).await else {
+            break;
+        };
+        let Some(line) = line? else {
+            break;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let command = match SlashCommand::parse(line) {
+            Some(Ok(command)) => command,
+            Some(Err(e)) => {
+                eprintln!("{}", e);
+                continue;
+            }
+            None => {
+                let Ok(answer) = shutdown::interruptible(ask(
+                    &cli,
+                    llm,
+                    &tool_context,
+                    &mut ledger,
+                    line,
+                    session.history(),
+                ))
+                .await
+                else {
+                    break;
+                };
+                match answer {
+                    Ok((_, turn)) => {
+                        if cli.verbose {
+                            print_steps(&turn);
+                        }
+                        println!("{}", render(&cli, line, &turn)?);
+                        for warning in &turn.warnings {
+                            eprintln!("Warning: {}", warning);
+                        }
+                        if !cli.dry_run {
+                            session.push_turn(line, &postprocess::answer(&turn.output));
+                        }
+                        transcript.push(line, &turn);
+                    }
+                    Err(e) => eprintln!("Error: {}", e),
+                }
+                if let Some(name) = &cli.session {
+                    sessions.save(name, &session)?;
+                }
+                continue;
+            }
+        };
+
+        match command {
+            SlashCommand::Model(None) => println!("Model: {}", cli.model),
+            SlashCommand::Model(Some(model)) => {
+                // The ledger prices a single model, so what it spent comes off the budget left
+                let summary = ledger.summary(&prices);
+                eprintln!("{}", summary);
+                if let (Some(limit), Some(spent)) = (cli.max_session_usd, summary.cost) {
+                    cli.max_session_usd = Some((limit - spent).max(0.0));
+                }
+                ledger = Ledger::new(&model);
+                println!("Switched to {}", model);
+                cli.model = model;
+            }
+            SlashCommand::Network(None) => println!("Network: {}", cli.network),
+            SlashCommand::Network(Some(network)) => {
+                tool_context.network = network;
+                println!("Switched to {}", network);
+                cli.network = network;
+            }
+            SlashCommand::Tools => {
+                for name in ToolRegistry::with_builtin_tools().names() {
+                    let enabled = cli
+                        .tools
+                        .as_ref()
+                        .is_none_or(|tools| tools.iter().any(|tool| tool == name));
+                    println!("{}{}", name, if enabled { "" } else { " (disabled)" });
+                }
+            }
+            SlashCommand::Clear => {
+                session = Session::default();
+                transcript.clear();
+                if let Some(name) = &cli.session {
+                    sessions.save(name, &session)?;
+                }
+                println!("Cleared the conversation");
+            }
+            SlashCommand::Export(path) => {
+                let path = path.unwrap_or_else(|| repl::DEFAULT_TRANSCRIPT.into());
+                match transcript.write(&path) {
+                    Ok(()) => println!(
+                        "Wrote {} question(s) to {}",
+                        transcript.entries().len(),
+                        path.display()
+                    ),
+                    Err(e) => eprintln!("Could not write {}: {}", path.display(), e),
+                }
+            }
+            SlashCommand::Help => println!("{}", repl::HELP),
+            SlashCommand::Quit => break,
+        }
+    }
+
+    if let Some(path) = &cli.transcript_out {
+        transcript.write(path)?;
+    }
+    eprintln!("{}", ledger.summary(&prices));
+    if shutdown::is_requested() {
+        return Err(shutdown::Interrupted.into());
+    }
+    Ok(())
+}
+
+/// Lists or deletes stored sessions.
+fn run_sessions(
+    sessions: &SessionStore,
+    action: SessionsCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        SessionsCommand::List => {
+            for info in sessions.list()? {
+                let updated = chrono::DateTime::<chrono::Utc>::from(info.updated);
+                println!(
+                    "{}\t{} questions\tupdated {}",
+                    info.name,
+                    info.turns,
+                    updated.format("%Y-%m-%d %H:%M UTC")
+                );
+            }
+        }
+        SessionsCommand::Delete { name } => {
+            sessions.delete(&name)?;
+            eprintln!("Deleted session {}", name);
+        }
+    }
+    Ok(())
+}
+
+/// Answers the batch questions, writing one JSON record per answer, and fails if any question
+/// did.
+async fn run_batch(
+    cli: &Cli,
+    llm: &LlmClient,
+    tool_context: &ToolContext,
+    ledger: &mut Ledger,
+    questions: Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tool_context = &with_enums(tool_context).await;
+    let answered = std::sync::Mutex::new(Ledger::new(&cli.model));
+
+    let total = questions.len();
+    let answers = batch::run(questions, cli.batch_concurrency, |prompt| {
+        let answered = &answered;
+        async move {
+            // Questions answered concurrently count against the batch's budget together
+            if let Err(e) = within_budget(cli, &answered.lock().unwrap()) {
+                return Err(e.to_string());
+            }
+            let mut own = Ledger::new(&cli.model);
+            let result = ask(cli, llm, tool_context, &mut own, &prompt, &[]).await;
+            answered.lock().unwrap().merge(own);
+            result.map(|(_, turn)| turn).map_err(|e| e.to_string())
+        }
+    })
+    .await;
+    ledger.merge(answered.into_inner().unwrap());
+
+    let mut out: Box<dyn Write> = match &cli.batch_out {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    for answer in &answers {
+        writeln!(out, "{}", answer.to_json())?;
+    }
+    out.flush()?;
+
+    let failed = answers.iter().filter(|answer| answer.turn.is_err()).count();
+    if failed > 0 {
+        return Err(format!("{} of {} questions failed", failed, total).into());
+    }
+    Ok(())
+}
+
+/// Dumps the stats of blocks `from..=to` as CSV.
+async fn run_export(
+    tool: CelestiaSearchTool,
+    from: u64,
+    to: u64,
+    out: Option<PathBuf>,
+    concurrency: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if from > to {
+        return Err(format!("invalid range: {} is above {}", from, to).into());
+    }
+
+    let rows = fetcher::fetch_range(&tool, from..=to, concurrency).await?;
+    let rows = rows.iter().map(|(_, stats)| stats);
+
+    match out {
+        Some(path) => export::write_csv(rows, BufWriter::new(File::create(path)?))?,
+        None => export::write_csv(rows, std::io::stdout().lock())?,
+    }
+
+    Ok(())
+}
+
+/// Times the backends, then every tool at `height` (or just below the chain head).
+async fn run_bench(
+    cli: &Cli,
+    tool_context: &ToolContext,
+    height: Option<u64>,
+    runs: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let height = match height {
+        Some(height) => height,
+        // Leave room for range tools, and stay clear of blocks still being indexed
+        None => tool_context
+            .block_tool()
+            .chain_head()
+            .await?
+            .saturating_sub(10)
+            .max(bench::WINDOW),
+    };
+
+    let endpoints = bench::endpoints(tool_context, runs).await;
+    println!(
+        "Backends ({} runs each):\n{}\n",
+        runs,
+        bench::report(&endpoints)
+    );
+
+    let tools =
+        ToolRegistry::with_builtin_tools().build(tool_context, cli.tools.as_deref(), |_| true)?;
+    let (timings, skipped) = bench::tools(&tools, height, runs).await;
+    println!(
+        "Tools at height {} ({} runs each):\n{}",
+        height,
+        runs,
+        bench::report(&timings)
+    );
+    if !skipped.is_empty() {
+        println!(
+            "\nNot benchmarked, as they need more than a height: {}",
+            skipped.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints a line per new block.
+async fn run_watch(
+    tool: CelestiaSearchTool,
+    from: Option<u64>,
+    interval: u64,
+    diff: bool,
+    only: Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let interval = Duration::from_secs(interval);
+    if diff {
+        let filter = ChangeFilter::parse(&only)?;
+        let deltas = watch::deltas(&tool, from, interval, filter);
+        futures::pin_mut!(deltas);
+        while let Some(delta) = deltas.next().await {
+            match delta {
+                Ok(delta) => println!("{}", delta),
+                Err(e) => eprintln!("Watch error: {}", e),
+            }
+        }
+        return Ok(());
+    }
+
+    let blocks = watch::blocks(&tool, from, interval);
+    futures::pin_mut!(blocks);
+
+    while let Some(block) = blocks.next().await {
+        match block {
+            Ok((_, stats)) => println!("{}", stats),
+            Err(e) => eprintln!("Watch error: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Notifies every notifier of each new block matching one of the conditions.
+async fn run_alert(
+    tool: CelestiaSearchTool,
+    conditions: Vec<String>,
+    notifiers: Vec<Box<dyn Notifier>>,
+    from: Option<u64>,
+    interval: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let conditions = conditions
+        .iter()
+        .map(|condition| Condition::parse(condition))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let blocks = watch::blocks(&tool, from, Duration::from_secs(interval));
+    futures::pin_mut!(blocks);
+
+    while let Some(block) = blocks.next().await {
+        match block {
+            Ok((height, stats)) => {
+                for alert in alert::check(&conditions, height, &stats) {
+                    notify_all(&notifiers, &Event::Alert(alert)).await;
+                }
+            }
+            Err(e) => eprintln!("Watch error: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Notifies every notifier of each block explorers post to the webhook that matches one of the
+/// conditions, fetching the stats of the blocks transactions landed in.
+#[cfg(feature = "server")]
+async fn run_webhook_alert(
+    tool: CelestiaSearchTool,
+    conditions: Vec<String>,
+    notifiers: Vec<Box<dyn Notifier>>,
+    addr: std::net::SocketAddr,
+    secret: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let conditions = conditions
+        .iter()
+        .map(|condition| Condition::parse(condition))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("Accepting webhooks on http://{}{}", addr, webhook::PATH);
+    let (sender, mut payloads) = tokio::sync::mpsc::channel(WEBHOOK_QUEUE);
+    let server = tokio::spawn(webhook::serve(listener, tool.network(), secret, sender));
+
+    let mut delivered = webhook::Delivered::default();
+    while let Some(payload) = payloads.recv().await {
+        let height = payload.height();
+        if delivered.contains(height) {
+            continue;
+        }
+        let stats = match payload {
+            Payload::Block(stats) => stats,
+            Payload::Tx { .. } => match tool.fetch_stats(height).await {
+                Ok(stats) => stats,
+                Err(e) => {
+                    eprintln!("Webhook error: block {}: {}", height, e);
+                    continue;
+                }
+            },
+        };
+        delivered.first(height);
+        for alert in alert::check(&conditions, height, &stats) {
+            notify_all(&notifiers, &Event::Alert(alert)).await;
+        }
+    }
+
+    // The server holds the sender, so payloads only stop coming when it failed
+    server.await??;
+    Ok(())
+}
+
+/// Runs the configured schedules forever, reporting each answer.
+async fn run_daemon(
+    cli: &Cli,
+    llm: &LlmClient,
+    tool_context: &ToolContext,
+    config: Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if config.schedules.is_empty() {
+        return Err("no schedules configured; add [[schedules]] to the config file".into());
+    }
+    let jobs = config
+        .schedules
+        .into_iter()
+        .map(Job::new)
+        .collect::<Result<Vec<_>, _>>()?;
+    let notifiers = notify::from_config(&config.notifiers);
+
+    let prices = PriceTable::from_env()?;
+    let tool_context = &with_enums(tool_context).await;
+    let mut ledger = Ledger::new(&cli.model);
+
+    loop {
+        // `--max-session-usd` caps what the daemon spends over its life, so it stops once spent
+        within_budget(cli, &ledger)?;
+
+        let now = chrono::Utc::now();
+        let Some((due, job)) = schedule::next_due(&jobs, &now) else {
+            return Err("no schedule will run again".into());
+        };
+        eprintln!("Next run: {} at {}", job.config.name, due);
+        tokio::time::sleep((due - now).to_std().unwrap_or_default()).await;
+
+        let answer = match ask(cli, llm, tool_context, &mut ledger, &job.config.prompt, &[]).await {
+            Ok((_, turn)) => postprocess::answer(&turn.output),
+            Err(e) => {
+                eprintln!("Scheduled query {} failed: {}", job.config.name, e);
+                continue;
+            }
+        };
+        eprintln!("{}", ledger.summary(&prices));
+
+        if let Some(path) = &job.config.output {
+            let written = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| {
+                    writeln!(file, "## {} ({})\n\n{}\n", job.config.name, due, answer)
+                });
+            if let Err(e) = written {
+                eprintln!("Failed to write {}: {}", path.display(), e);
+            }
+        }
+        if job.config.notify {
+            let report = Event::Report {
+                name: job.config.name.clone(),
+                prompt: job.config.prompt.clone(),
+                answer,
+            };
+            notify_all(&notifiers, &report).await;
+        }
+    }
+}
+
+/// Opens the dashboard, answering each question like a one-off prompt.
+#[cfg(feature = "tui")]
+async fn run_tui(
+    cli: &Cli,
+    llm: &LlmClient,
+    tool_context: &ToolContext,
+    interval: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tool_context = &with_enums(tool_context).await;
+    let tool = tool_context.block_tool();
+    let spent = std::sync::Mutex::new(Ledger::new(&cli.model));
+
+    celestia_search_assistant::tui::run(&tool, Duration::from_secs(interval), |prompt| {
+        let spent = &spent;
+        async move {
+            // Every question of the dashboard counts against its budget
+            if let Err(e) = within_budget(cli, &spent.lock().unwrap()) {
+                return Err(e.to_string());
+            }
+            let mut own = Ledger::new(&cli.model);
+            let result = ask(cli, llm, tool_context, &mut own, &prompt, &[]).await;
+            spent.lock().unwrap().merge(own);
+            result.map(|(_, turn)| turn).map_err(|e| e.to_string())
+        }
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Sends the event to every notifier, reporting (but not failing on) delivery errors.
+async fn notify_all(notifiers: &[Box<dyn Notifier>], event: &Event) {
+    for notifier in notifiers {
+        if let Err(e) = notifier.notify(event).await {
+            eprintln!("Failed to send notification: {}", e);
+        }
+    }
+}
+
+/// Checks the config, the LLM provider key, every Celenium endpoint and the local directories,
+/// printing a diagnosis, and fails if anything the assistant needs is broken.
+async fn run_doctor(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checks = Vec::new();
+
+    let config = match &cli.config {
+        Some(path) => match Config::load(path) {
+            Ok(config) => {
+                checks.push(Check::ok("Config", format!("{} is valid", path.display())));
+                config
+            }
+            Err(e) => {
+                checks.push(Check::failed(
+                    "Config",
+                    format!("{}: {}", path.display(), e),
+                ));
+                Config::default()
+            }
+        },
+        None => Config::default(),
+    };
+
+    checks.push(match cli.provider {
+        Provider::OpenAi => {
+            let api_key = std::env::var("OPENAI_API_KEY").ok();
+            doctor::check_openai(doctor::OPENAI_API_URL, api_key.as_deref()).await
+        }
+        Provider::Azure => doctor::check_azure(config.azure.as_ref()).await,
+        Provider::Gemini => doctor::check_gemini(config.gemini.as_ref()).await,
+    });
+
+    // Only the selected network has to answer; the others are used when questions ask for them
+    for network in Network::ALL {
+        let check = match config.celenium_client(network) {
+            Ok(client) => doctor::check_celenium(&client).await,
+            Err(e) => Check::failed(format!("Celenium {}", network), e.to_string()),
+        };
+        checks.push(match network == cli.network {
+            true => check,
+            false => check.optional(),
+        });
+    }
+
+    #[cfg(feature = "node-rpc")]
+    if let Some(node) = &config.node {
+        let client = NodeClient::from_config(node);
+        checks.push(
+            match client
+                .call::<serde_json::Value>("node.Info", serde_json::json!([]))
+                .await
+            {
+                Ok(_) => Check::ok("celestia-node", format!("{} answered", node.url)),
+                Err(e) => Check::failed("celestia-node", format!("{}: {}", node.url, e)),
+            },
+        );
+        if let Some(url) = &node.consensus_url {
+            checks.push(
+                match client
+                    .call_consensus::<serde_json::Value>("status", serde_json::json!({}))
+                    .await
+                {
+                    Ok(_) => Check::ok("consensus node", format!("{} answered", url)),
+                    Err(e) => Check::failed("consensus node", format!("{}: {}", url, e)),
+                },
+            );
+        }
+    }
+
+    let sessions_dir = cli
+        .sessions_dir
+        .clone()
+        .unwrap_or_else(SessionStore::default_dir);
+    checks.push(doctor::check_dir("Sessions directory", &sessions_dir).optional());
+    #[cfg(feature = "sqlite-cache")]
+    if let Some(path) = &cli.db {
+        checks.push(match BlockStore::open(path) {
+            Ok(_) => Check::ok("Block store", format!("{} opens", path.display())),
+            Err(e) => Check::failed("Block store", format!("{}: {}", path.display(), e)),
+        });
+    }
+    if let Some(path) = &cli.docs_index {
+        checks.push(match path.is_file() {
+            true => Check::ok("Docs index", format!("{} exists", path.display())),
+            false => Check::failed(
+                "Docs index",
+                format!("{} is missing; build it with `index-docs`", path.display()),
+            ),
+        });
+    }
+
+    for check in &checks {
+        println!("{}", check);
+    }
+    match doctor::problems(&checks) {
+        (0, 0) => println!("Everything looks good."),
+        (0, warnings) => println!("{} warning(s), but the assistant can run.", warnings),
+        (failures, _) => return Err(format!("{} check(s) failed", failures).into()),
+    }
+
+    Ok(())
+}
 
-    let response = agent
-        .prompt("What is the gas fee of the Celestia block at height 9999?")
-        .await?;
+/// Builds the documentation index used to answer conceptual questions.
+async fn run_index_docs(
+    out: PathBuf,
+    dirs: Vec<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut docs: Vec<(String, String)> = knowledge::SEED_DOCS
+        .iter()
+        .map(|(name, text)| (name.to_string(), text.to_string()))
+        .collect();
+    for dir in &dirs {
+        docs.extend(knowledge::read_docs_dir(dir)?);
+    }
 
-    let formatted_response: String = serde_json::from_str(&response)?;
+    let openai_client = openai_client()?;
+    let store =
+        knowledge::build_store(openai_client.embedding_model(EMBEDDING_MODEL), &docs).await?;
+    knowledge::save(&store, &out)?;
 
-    println!("Agent response:\n{}", formatted_response);
+    eprintln!(
+        "Indexed {} documents ({} chunks) into {}",
+        docs.len(),
+        store.len(),
+        out.display()
+    );
 
     Ok(())
 }
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::byte_size;
+use crate::metrics::metrics;
+use crate::node::{NodeClient, NodeError};
+
+/// The most pending transactions sampled, CometBFT's default page size.
+pub const MAX_SAMPLE: u64 = 30;
+
+/// The type id closing a `BlobTx`, which wraps a PFB with its blobs.
+const BLOB_TX_TYPE_ID: &[u8] = b"BLOB";
+
+/// How many pending transactions to sample.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MempoolArgs {
+    #[serde(default = "default_sample")]
+    sample: u64,
+}
+
+fn default_sample() -> u64 {
+    5
+}
+
+/// Reports how full the consensus node's mempool is, with a sample of its pending transactions.
+pub struct MempoolTool {
+    node: Arc<NodeClient>,
+}
+
+impl MempoolTool {
+    /// Creates a tool reading the mempool of `node`'s consensus node.
+    pub fn new(node: Arc<NodeClient>) -> Self {
+        Self { node }
+    }
+
+    async fn report(&self, args: MempoolArgs) -> Result<String, NodeError> {
+        let sample = args.sample.min(MAX_SAMPLE);
+        let mempool: Value = match sample {
+            0 => {
+                self.node
+                    .call_consensus("num_unconfirmed_txs", json!({}))
+                    .await?
+            }
+            sample => {
+                self.node
+                    .call_consensus("unconfirmed_txs", json!({ "limit": sample.to_string() }))
+                    .await?
+            }
+        };
+
+        let total = number(&mempool["total"]);
+        let bytes = number(&mempool["total_bytes"]);
+        if total == 0 {
+            return Ok(
+                "The mempool is empty, so a transaction submitted now should make the next \
+                       block."
+                    .to_string(),
+            );
+        }
+        let mut output = format!(
+            "{} transaction(s) totalling {} are pending in the mempool.",
+            total,
+            byte_size::format(bytes)
+        );
+
+        let txs: Vec<Pending> = mempool["txs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tx| BASE64.decode(tx.as_str()?).ok())
+            .map(|raw| Pending::decode(&raw))
+            .collect();
+        if !txs.is_empty() {
+            let blob_txs = txs.iter().filter(|tx| tx.blobs > 0).count();
+            output.push_str(&format!(
+                " Of the {} sampled, {} are PFBs carrying blobs:",
+                txs.len(),
+                blob_txs
+            ));
+            for tx in txs {
+                output.push_str(&format!(
+                    "\n- {}: {}",
+                    tx.hash,
+                    byte_size::format(tx.size as u64)
+                ));
+                if tx.blobs > 0 {
+                    output.push_str(&format!(
+                        ", a PFB with {} blob(s) of {}",
+                        tx.blobs,
+                        byte_size::format(tx.blob_bytes as u64)
+                    ));
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// A transaction waiting in the mempool
+struct Pending {
+    hash: String,
+    size: usize,
+    blobs: usize,
+    blob_bytes: usize,
+}
+
+impl Pending {
+    /// Reads `raw`, unwrapping a `BlobTx` to hash the transaction inside it, as the chain does
+    fn decode(raw: &[u8]) -> Self {
+        let (tx, blobs) = blob_tx(raw).unwrap_or((raw, Vec::new()));
+        Self {
+            hash: hex::encode_upper(Sha256::digest(tx)),
+            size: raw.len(),
+            blobs: blobs.len(),
+            blob_bytes: blobs.iter().map(|blob| blob.len()).sum(),
+        }
+    }
+}
+
+/// The transaction and blobs of a `BlobTx` (`tx = 1`, `blobs = 2`, `type_id = 3`), if `raw` is one
+fn blob_tx(raw: &[u8]) -> Option<(&[u8], Vec<&[u8]>)> {
+    let (mut tx, mut blobs, mut type_id) = (None, Vec::new(), None);
+    let mut rest = raw;
+    while !rest.is_empty() {
+        let key = varint(&mut rest)?;
+        // Every field of a BlobTx is length-delimited
+        if key & 0b111 != 2 {
+            return None;
+        }
+        let len = usize::try_from(varint(&mut rest)?).ok()?;
+        if len > rest.len() {
+            return None;
+        }
+        let (field, tail) = rest.split_at(len);
+        rest = tail;
+        match key >> 3 {
+            1 => tx = Some(field),
+            2 => blobs.push(field),
+            3 => type_id = Some(field),
+            _ => return None,
+        }
+    }
+    (type_id == Some(BLOB_TX_TYPE_ID)).then_some((tx?, blobs))
+}
+
+/// Reads a protobuf varint off the front of `bytes`
+fn varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// CometBFT gives counts as strings
+fn number(value: &Value) -> u64 {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|n| n.parse().ok()))
+        .unwrap_or(0)
+}
+
+impl Tool for MempoolTool {
+    const NAME: &'static str = "mempool";
+
+    type Args = MempoolArgs;
+    type Output = String;
+    type Error = NodeError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Check the mempool of the configured consensus node: how many \
+                          transactions and bytes are waiting to be included, and a sample of \
+                          the pending transactions with their hashes, sizes and blobs. Use it to \
+                          judge the pressure on block space before submitting a PFB."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "sample": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": MAX_SAMPLE,
+                        "description": "How many pending transactions to list",
+                        "examples": [5],
+                    },
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+#[cfg(feature = "server")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "server")]
+use tokio::net::TcpListener;
+
+use crate::byte_size;
+
+/// Upper bounds (in seconds) of the latency histogram buckets.
+const LATENCY_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// Returns the process-wide metrics registry.
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::default)
+}
+
+/// A monotonically increasing counter partitioned by a set of label values.
+#[derive(Default)]
+pub struct CounterVec {
+    values: Mutex<BTreeMap<Vec<String>, u64>>,
+}
+
+impl CounterVec {
+    pub fn inc(&self, labels: &[&str]) {
+        self.inc_by(labels, 1);
+    }
+
+    pub fn inc_by(&self, labels: &[&str], amount: u64) {
+        let key = labels.iter().map(|l| l.to_string()).collect();
+        *self.values.lock().unwrap().entry(key).or_insert(0) += amount;
+    }
+
+    /// The count for the given label values, zero if they were never counted.
+    pub fn get(&self, labels: &[&str]) -> u64 {
+        let key: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+        self.values.lock().unwrap().get(&key).copied().unwrap_or(0)
+    }
+}
+
+/// A cumulative histogram of observed durations.
+pub struct Histogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    /// Sum of all observations, in microseconds.
+    sum_micros: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: LATENCY_BUCKETS.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Histogram {
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            if seconds <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+/// The metrics exported by the assistant.
+#[derive(Default)]
+pub struct Metrics {
+    /// Tool invocations, labelled by tool name and outcome.
+    pub tool_invocations: CounterVec,
+    /// Latency of requests made to the Celenium indexer.
+    pub indexer_latency: Histogram,
+    /// Bytes of indexer responses, labelled by kind (received on the wire, or decoded).
+    pub indexer_bytes: CounterVec,
+    /// LLM tokens spent, labelled by model and kind (prompt or completion).
+    pub llm_tokens: CounterVec,
+    /// Cache lookups, labelled by cache and result (hit or miss).
+    pub cache_lookups: CounterVec,
+    /// Cache evictions, labelled by cache and the bound that forced them (entries or memory).
+    pub cache_evictions: CounterVec,
+    /// Indexer strings sanitized before reaching the model, labelled by outcome (escaped or
+    /// flagged as a possible prompt injection).
+    pub sanitized_strings: CounterVec,
+}
+
+/// How much indexer data was transferred, and how much compression saved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bandwidth {
+    /// Response bytes received on the wire.
+    pub received: u64,
+    /// Response bytes once decompressed.
+    pub decoded: u64,
+}
+
+impl Bandwidth {
+    /// The share of the decoded bytes compression kept off the wire, from 0 to 1.
+    pub fn saved(&self) -> f64 {
+        match self.decoded {
+            0 => 0.0,
+            decoded => 1.0 - self.received as f64 / decoded as f64,
+        }
+    }
+}
+
+impl fmt::Display for Bandwidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Indexer responses: {} received for {} of JSON ({:.0}% saved by compression)",
+            byte_size::format(self.received),
+            byte_size::format(self.decoded),
+            self.saved() * 100.0
+        )
+    }
+}
+
+impl Metrics {
+    /// The indexer bandwidth used so far.
+    pub fn bandwidth(&self) -> Bandwidth {
+        Bandwidth {
+            received: self.indexer_bytes.get(&["received"]),
+            decoded: self.indexer_bytes.get(&["decoded"]),
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        render_counter_vec(
+            &mut out,
+            "celestia_tool_invocations_total",
+            "Number of tool invocations made by the agent.",
+            &["tool", "outcome"],
+            &self.tool_invocations,
+        );
+        render_histogram(
+            &mut out,
+            "celestia_indexer_request_duration_seconds",
+            "Latency of requests to the Celenium indexer.",
+            &self.indexer_latency,
+        );
+        render_counter_vec(
+            &mut out,
+            "celestia_indexer_response_bytes_total",
+            "Bytes of indexer responses, as received and once decompressed.",
+            &["kind"],
+            &self.indexer_bytes,
+        );
+        render_counter_vec(
+            &mut out,
+            "celestia_llm_tokens_total",
+            "Number of LLM tokens spent by the agent.",
+            &["model", "kind"],
+            &self.llm_tokens,
+        );
+        render_counter_vec(
+            &mut out,
+            "celestia_cache_lookups_total",
+            "Number of cache lookups, by result.",
+            &["cache", "result"],
+            &self.cache_lookups,
+        );
+        render_counter_vec(
+            &mut out,
+            "celestia_cache_evictions_total",
+            "Number of entries evicted from bounded caches, by the bound that was reached.",
+            &["cache", "reason"],
+            &self.cache_evictions,
+        );
+        render_counter_vec(
+            &mut out,
+            "celestia_sanitized_strings_total",
+            "Number of indexer strings sanitized before reaching the model, by outcome.",
+            &["outcome"],
+            &self.sanitized_strings,
+        );
+
+        out
+    }
+}
+
+fn render_counter_vec(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    label_names: &[&str],
+    counter: &CounterVec,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for (labels, value) in counter.values.lock().unwrap().iter() {
+        let labels = label_names
+            .iter()
+            .zip(labels)
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape_label(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
+
+fn render_histogram(out: &mut String, name: &str, help: &str, histogram: &Histogram) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+        let _ = writeln!(
+            out,
+            "{}_bucket{{le=\"{}\"}} {}",
+            name,
+            bound,
+            bucket.load(Ordering::Relaxed)
+        );
+    }
+    let count = histogram.count.load(Ordering::Relaxed);
+    let sum = histogram.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+    let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+    let _ = writeln!(out, "{}_sum {}", name, sum);
+    let _ = writeln!(out, "{}_count {}", name, count);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves the `/metrics` endpoint on the given address until the process exits.
+#[cfg(feature = "server")]
+pub async fn serve(addr: SocketAddr) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+
+        tokio::spawn(async move {
+            // Only the request line matters, so a single read is enough
+            let mut buf = [0u8; 1024];
+            let n = match stream.read(&mut buf).await {
+                Ok(n) => n,
+                Err(_) => return,
+            };
+            let request = String::from_utf8_lossy(&buf[..n]);
+
+            let response = if request.starts_with("GET /metrics ") {
+                let body = metrics().render();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string()
+            };
+
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
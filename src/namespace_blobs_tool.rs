@@ -0,0 +1,188 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::byte_size;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::cursor;
+use crate::metrics::metrics;
+use crate::namespace_tool;
+use crate::network::{self, Network};
+
+/// The most blobs listed by a single call.
+pub const MAX_LIMIT: u64 = 100;
+
+/// The page of a namespace's blobs to list.
+#[derive(Deserialize)]
+pub struct NamespaceBlobsArgs {
+    /// The namespace in hex, with or without its leading version byte.
+    namespace: String,
+    /// How many blobs to list.
+    #[serde(default = "default_limit")]
+    limit: u64,
+    /// How many of the newest blobs to skip, to page through older ones.
+    #[serde(default)]
+    offset: u64,
+    /// The cursor ending the previous page, continuing from there instead of `offset`.
+    cursor: Option<String>,
+    /// The networks to list blobs on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_limit() -> u64 {
+    20
+}
+
+/// Lists the blobs most recently posted to a namespace, newest first.
+pub struct NamespaceBlobsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl NamespaceBlobsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn list(&self, args: NamespaceBlobsArgs) -> Result<String, CelestiaSearchError> {
+        let namespace = namespace_tool::validate_namespace(&args.namespace)?;
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+        let (namespace, next) = (namespace.as_str(), args.cursor.as_deref());
+        self.blocks
+            .across(&args.network, |blocks| async move {
+                let offset = match next {
+                    Some(next) => {
+                        cursor::decode(next, NamespaceBlobsTool::NAME, &scope(blocks, namespace))?
+                    }
+                    None => args.offset,
+                };
+                list_blobs(blocks, namespace, limit, offset).await
+            })
+            .await
+    }
+}
+
+/// What a cursor of the namespace's blobs continues listing
+fn scope(blocks: &CelestiaSearchTool, namespace: &str) -> String {
+    format!("{}/{}", blocks.network(), namespace)
+}
+
+/// Lists a page of the namespace's blobs with their heights, sizes and signers.
+pub(crate) async fn list_blobs(
+    blocks: &CelestiaSearchTool,
+    namespace: &str,
+    limit: u64,
+    offset: u64,
+) -> Result<String, CelestiaSearchError> {
+    // Celenium addresses namespaces by id and version separately
+    let (version, id) = namespace.split_at(2);
+    let version = u8::from_str_radix(version, 16).unwrap_or_default();
+    let endpoint = format!(
+        "/namespace/{}/{}/blobs?limit={}&offset={}&sort=desc",
+        id, version, limit, offset
+    );
+    let blobs = blocks.fetch(&endpoint).await?;
+    let blobs: Vec<&Value> = blobs.as_array().into_iter().flatten().collect();
+    if blobs.is_empty() {
+        return Ok(match offset {
+            0 => format!("Namespace {} has no blobs.", namespace),
+            _ => format!(
+                "Namespace {} has no blobs beyond the newest {}.",
+                namespace, offset
+            ),
+        });
+    }
+
+    let bytes: u64 = blobs.iter().filter_map(|blob| blob["size"].as_u64()).sum();
+    let mut output = format!(
+        "Blobs {} to {} of namespace {}, newest first ({} in total):",
+        offset + 1,
+        offset + blobs.len() as u64,
+        namespace,
+        byte_size::format(bytes)
+    );
+    for blob in &blobs {
+        output.push_str(&format!(
+            "\n- height {} ({}): {}, signed by {}, commitment {}",
+            blob["height"],
+            blob["time"].as_str().unwrap_or("unknown time"),
+            byte_size::format(blob["size"].as_u64().unwrap_or_default()),
+            blob["signer"].as_str().unwrap_or("an unknown signer"),
+            blob["commitment"].as_str().unwrap_or("unknown")
+        ));
+    }
+    if blobs.len() as u64 == limit {
+        let next = cursor::encode(
+            NamespaceBlobsTool::NAME,
+            &scope(blocks, namespace),
+            offset + limit,
+        );
+        output.push_str(&format!(
+            "\nOlder blobs may follow; call {} with cursor `{}` for the next page.",
+            NamespaceBlobsTool::NAME,
+            next
+        ));
+    }
+
+    Ok(output)
+}
+
+impl Tool for NamespaceBlobsTool {
+    const NAME: &'static str = "namespace_blobs";
+
+    type Args = NamespaceBlobsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "List the blobs most recently posted to a Celestia namespace, newest first, with \
+                 their heights, sizes, signers and commitments. Returns up to {} at a time; a \
+                 full page ends with a cursor to pass back for the next one.",
+                MAX_LIMIT
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "namespace": {
+                        "type": "string",
+                        "description": "The namespace in hex (28 bytes, or 29 with the version byte)",
+                        "examples": ["0000000000000000000000000000000000000000000000000000000000"],
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": "How many blobs to list",
+                        "examples": [20],
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "How many of the newest blobs to skip",
+                        "examples": [0, 20],
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "The cursor ending the previous page, to list the next one",
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["namespace"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.list(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::byte_size;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The longest window, in days, a single call reports on.
+pub const MAX_DAYS: u64 = 90;
+
+/// The daily series fetched, in the order they are reported.
+const SERIES: [&str; 3] = ["pfb_count", "size", "fee"];
+
+/// The namespace and window to report on.
+#[derive(Deserialize)]
+pub struct NamespaceStatsArgs {
+    /// The namespace in hex, with or without its leading version byte.
+    namespace: String,
+    /// How many days to report, ending today.
+    #[serde(default = "default_days")]
+    days: u64,
+    /// The networks to report on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_days() -> u64 {
+    7
+}
+
+/// Reports how much a namespace posted, and what it paid, per day.
+pub struct NamespaceStatsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl NamespaceStatsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn report(&self, args: NamespaceStatsArgs) -> Result<String, CelestiaSearchError> {
+        let namespace = validate_namespace(&args.namespace)?;
+        let days = args.days.clamp(1, MAX_DAYS);
+        self.blocks
+            .across(&args.network, |blocks| {
+                daily_usage(blocks, &namespace, days)
+            })
+            .await
+    }
+}
+
+/// Normalizes a namespace to the 29-byte hex form Celenium expects, assuming version 0 if the
+/// version byte is left out.
+pub(crate) fn validate_namespace(namespace: &str) -> Result<String, CelestiaSearchError> {
+    let trimmed = namespace.trim();
+    let hex = trimmed
+        .strip_prefix("0x")
+        .unwrap_or(trimmed)
+        .to_ascii_lowercase();
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(CelestiaSearchError::InvalidNamespace(namespace.to_string()));
+    }
+    match hex.len() {
+        58 => Ok(hex),
+        56 => Ok(format!("00{}", hex)),
+        _ => Err(CelestiaSearchError::InvalidNamespace(namespace.to_string())),
+    }
+}
+
+/// Totals and lists the namespace's blobs, bytes and fees for each of the last `days` days
+async fn daily_usage(
+    blocks: &CelestiaSearchTool,
+    namespace: &str,
+    days: u64,
+) -> Result<String, CelestiaSearchError> {
+    let to = Utc::now();
+    let from = to - Duration::days(days as i64);
+    let endpoint = |name: &str| {
+        format!(
+            "/stats/namespace/series/{}/day/{}?from={}&to={}",
+            namespace,
+            name,
+            from.timestamp(),
+            to.timestamp()
+        )
+    };
+    let (blobs, bytes, fees) = futures::try_join!(
+        blocks.fetch(&endpoint(SERIES[0])),
+        blocks.fetch(&endpoint(SERIES[1])),
+        blocks.fetch(&endpoint(SERIES[2])),
+    )?;
+
+    // Each series is a list of `{time, value}` points; merge them by day
+    let mut rows: BTreeMap<String, [f64; 3]> = BTreeMap::new();
+    for (i, series) in [blobs, bytes, fees].iter().enumerate() {
+        for point in series.as_array().into_iter().flatten() {
+            let Some(time) = point["time"].as_str() else {
+                continue;
+            };
+            let value = match &point["value"] {
+                serde_json::Value::String(value) => value.parse().unwrap_or(0.0),
+                value => value.as_f64().unwrap_or(0.0),
+            };
+            let day = time.split('T').next().unwrap_or(time).to_string();
+            rows.entry(day).or_default()[i] += value;
+        }
+    }
+    if rows.is_empty() {
+        return Ok(format!(
+            "Namespace {} posted no blobs in the last {} day(s).",
+            namespace, days
+        ));
+    }
+
+    let total = |i: usize| rows.values().map(|row| row[i]).sum::<f64>();
+    let mut output = format!(
+        "Namespace {} over the last {} day(s): {} blob(s), {}, {} in fees.",
+        namespace,
+        days,
+        total(0),
+        byte_size::format(total(1) as u64),
+        price::describe_utia(&total(2).to_string(), None)
+    );
+    for (day, [blobs, bytes, fees]) in &rows {
+        output.push_str(&format!(
+            "\n- {}: {} blob(s), {}, {} utia",
+            day,
+            blobs,
+            byte_size::format(*bytes as u64),
+            fees
+        ));
+    }
+
+    Ok(output)
+}
+
+impl Tool for NamespaceStatsTool {
+    const NAME: &'static str = "namespace_stats";
+
+    type Args = NamespaceStatsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Report how many blobs a Celestia namespace posted, their total size in bytes \
+                 and the fees paid for them, per day over up to {} days ending today.",
+                MAX_DAYS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "namespace": {
+                        "type": "string",
+                        "description": "The namespace in hex (28 bytes, or 29 with the version byte)",
+                        "examples": ["0000000000000000000000000000000000000000000000000000000000"],
+                    },
+                    "days": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_DAYS,
+                        "description": "How many days to report, ending today",
+                        "examples": [7, 30],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["namespace"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
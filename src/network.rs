@@ -0,0 +1,107 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+
+/// A Celestia network indexed by Celenium.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    #[default]
+    Mainnet,
+    /// The Mocha testnet
+    Mocha,
+    /// The Arabica devnet
+    Arabica,
+}
+
+impl Network {
+    pub const ALL: [Network; 3] = [Network::Mainnet, Network::Mocha, Network::Arabica];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|network| network.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Mocha => "mocha",
+            Network::Arabica => "arabica",
+        }
+    }
+
+    /// The Celenium API for the network.
+    pub fn base_url(self) -> String {
+        format!("https://api-{}.celenium.io/v1", self.name())
+    }
+
+    /// Celenium's explorer for the network, whose pages show what the API returns.
+    pub fn explorer_url(self) -> String {
+        match self {
+            Network::Mainnet => "https://celenium.io".to_string(),
+            network => format!("https://{}.celenium.io", network.name()),
+        }
+    }
+
+    /// A public Cosmos REST API for the network, for chain state Celenium doesn't index.
+    pub fn rest_url(self) -> String {
+        match self {
+            Network::Mainnet => "https://celestia-rest.publicnode.com",
+            Network::Mocha => "https://celestia-testnet-rest.publicnode.com",
+            Network::Arabica => "https://api.celestia-arabica-11.com",
+        }
+        .to_string()
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Deserializes the `network` argument of a tool call: one network name, or a list of them to
+/// query each.
+pub fn deserialize_networks<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Network>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(Network),
+        Many(Vec<Network>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(network) => vec![network],
+        OneOrMany::Many(networks) => networks,
+    })
+}
+
+/// The JSON schema of the `network` argument, shared by every tool querying block stats.
+pub fn schema() -> Value {
+    let names: Vec<&str> = Network::ALL.iter().map(|network| network.name()).collect();
+    json!({
+        "anyOf": [
+            { "type": "string", "enum": names },
+            { "type": "array", "items": { "type": "string", "enum": names }, "minItems": 1 },
+        ],
+        "description": "The network to query, or several to compare them side by side \
+            (e.g. [\"mainnet\", \"mocha\"]). Defaults to the configured network.",
+    })
+}
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::audit;
+
+/// How long to wait for the node before giving up on a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The connection to a celestia-node, from the `[node]` section of the config.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeConfig {
+    /// The node's JSON-RPC endpoint, e.g. `http://localhost:26658`.
+    pub url: String,
+    /// The node's auth token; read from `CELESTIA_NODE_AUTH_TOKEN` if left out.
+    pub auth_token: Option<String>,
+    /// The CometBFT RPC of a consensus node, e.g. `http://localhost:26657`, for the mempool, the
+    /// event index and the history it keeps.
+    pub consensus_url: Option<String>,
+}
+
+impl NodeConfig {
+    pub fn auth_token(&self) -> Option<String> {
+        self.auth_token
+            .clone()
+            .or_else(|| std::env::var("CELESTIA_NODE_AUTH_TOKEN").ok())
+    }
+}
+
+/// Captures the errors that may occur while calling the node.
+#[derive(Debug, thiserror::Error)]
+pub enum NodeError {
+    #[error("Node request failed: {0}")]
+    HttpRequestFailed(#[from] reqwest::Error),
+    #[error("Node returned an error for `{method}`: {message}")]
+    Rpc { method: String, message: String },
+    #[error("Unexpected response to `{method}`: {reason}")]
+    Deserialization { method: String, reason: String },
+}
+
+/// A JSON-RPC client of a celestia-node (light, full or bridge).
+///
+/// The mempool isn't served by celestia-node, so it is read from a consensus node's CometBFT
+/// RPC instead, when one is given.
+pub struct NodeClient {
+    client: reqwest::Client,
+    url: String,
+    auth_token: Option<String>,
+    consensus_url: Option<String>,
+}
+
+impl NodeClient {
+    pub fn new(url: &str, auth_token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            auth_token,
+            consensus_url: None,
+        }
+    }
+
+    pub fn from_config(config: &NodeConfig) -> Self {
+        let client = Self::new(&config.url, config.auth_token());
+        match &config.consensus_url {
+            Some(url) => client.with_consensus(url),
+            None => client,
+        }
+    }
+
+    /// Reads the mempool from the CometBFT RPC at `url`.
+    pub fn with_consensus(mut self, url: &str) -> Self {
+        self.consensus_url = Some(url.to_string());
+        self
+    }
+
+    /// Whether a consensus node is configured, for [`NodeClient::call_consensus`].
+    pub fn has_consensus(&self) -> bool {
+        self.consensus_url.is_some()
+    }
+
+    /// Calls `method` with positional `params`, parsing its result as `T`.
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, NodeError> {
+        self.request(&self.url, self.auth_token.as_deref(), method, params)
+            .await
+    }
+
+    /// Calls CometBFT's `method` on the consensus node with named `params`.
+    pub async fn call_consensus<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, NodeError> {
+        let url = self
+            .consensus_url
+            .as_deref()
+            .ok_or_else(|| NodeError::Rpc {
+                method: method.to_string(),
+                message: "no consensus node is configured".to_string(),
+            })?;
+        // The auth token is for celestia-node only
+        self.request(url, None, method, params).await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        auth_token: Option<&str>,
+        method: &str,
+        params: Value,
+    ) -> Result<T, NodeError> {
+        // JSON-RPC calls share their URL, so the method tells them apart
+        audit::upstream(&format!("{} {}", url, method));
+        let mut request = self.client.post(url).timeout(REQUEST_TIMEOUT).json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }));
+        if let Some(token) = auth_token {
+            request = request.bearer_auth(token);
+        }
+        let mut response: Value = request.send().await?.error_for_status()?.json().await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(NodeError::Rpc {
+                method: method.to_string(),
+                message: error["message"]
+                    .as_str()
+                    .unwrap_or("unknown error")
+                    .to_string(),
+            });
+        }
+        serde_json::from_value(response["result"].take()).map_err(|e| NodeError::Deserialization {
+            method: method.to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
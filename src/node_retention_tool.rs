@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::metrics::metrics;
+use crate::node::{NodeClient, NodeError};
+use crate::sampling_tool::height;
+
+/// A cheap query whose state exists at every height, to probe which heights' state is kept.
+const PROBE_PATH: &str = "/cosmos.bank.v1beta1.Query/TotalSupply";
+
+/// The most heights probed looking for the earliest state, enough to bisect any chain.
+const MAX_PROBES: u32 = 40;
+
+/// What to check the node's history for.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeRetentionArgs {
+    /// A height a question is about, to say whether the node still serves it.
+    height: Option<u64>,
+}
+
+/// Reports which blocks and state the consensus node still keeps, so historical queries it has
+/// pruned are sent to the indexer instead.
+pub struct NodeRetentionTool {
+    node: Arc<NodeClient>,
+}
+
+impl NodeRetentionTool {
+    pub fn new(node: Arc<NodeClient>) -> Self {
+        Self { node }
+    }
+
+    async fn report(&self, args: NodeRetentionArgs) -> Result<String, NodeError> {
+        let status: Value = self.node.call_consensus("status", json!({})).await?;
+        let sync_info = &status["sync_info"];
+        let latest = height(&sync_info["latest_block_height"]).unwrap_or(0);
+        let earliest = height(&sync_info["earliest_block_height"])
+            .unwrap_or(1)
+            .max(1);
+        let state = self.earliest_state(earliest, latest).await?;
+
+        let mut output = format!(
+            "The consensus node stores blocks {} to {}",
+            earliest, latest
+        );
+        match sync_info["earliest_block_time"].as_str() {
+            Some(time) if earliest > 1 => output.push_str(&format!(
+                " (its earliest from {}); blocks before were pruned, or never fetched if it \
+                 joined by state sync.",
+                time
+            )),
+            _ if earliest > 1 => output.push_str(
+                "; blocks before were pruned, or never fetched if it joined by state sync.",
+            ),
+            _ => output.push_str(", the whole chain."),
+        }
+        match state {
+            Some(state) if state <= earliest => output.push_str(
+                " It keeps the application state of every block it stores (pruning `nothing`).",
+            ),
+            Some(state) => output.push_str(&format!(
+                " Its application state goes back to height {}, the latest {} heights, as its \
+                 `pruning-keep-recent` setting keeps.",
+                state,
+                latest - state + 1
+            )),
+            None => output.push_str(" It answered no state query, even at its latest height."),
+        }
+        output.push_str(
+            " State-sync snapshot intervals are only set in its app.toml (`[state-sync] \
+             snapshot-interval`), which the RPC doesn't expose.",
+        );
+
+        if let Some(asked) = args.height {
+            let verdict = if asked > latest {
+                format!("Height {} is beyond the node's head.", asked)
+            } else if asked < earliest {
+                format!(
+                    "Height {} is before what the node keeps: query the indexer for it instead.",
+                    asked
+                )
+            } else if state.is_none_or(|state| asked < state) {
+                format!(
+                    "The node still has block {}, but not its state: balances and other state \
+                     at that height need an archive node or the indexer.",
+                    asked
+                )
+            } else {
+                format!(
+                    "The node serves both the block and the state at height {}.",
+                    asked
+                )
+            };
+            output.push(' ');
+            output.push_str(&verdict);
+        }
+        Ok(output)
+    }
+
+    /// Bisects `earliest..=latest` for the first height whose state the node still has, assuming
+    /// state is kept from some height up, as pruning keeps the latest heights
+    async fn earliest_state(&self, earliest: u64, latest: u64) -> Result<Option<u64>, NodeError> {
+        if latest == 0 || !self.has_state(latest).await? {
+            return Ok(None);
+        }
+        if self.has_state(earliest).await? {
+            return Ok(Some(earliest));
+        }
+        // The state at `low` is pruned and the state at `high` is kept
+        let (mut low, mut high) = (earliest, latest);
+        for _ in 0..MAX_PROBES {
+            if high - low <= 1 {
+                break;
+            }
+            let middle = low + (high - low) / 2;
+            match self.has_state(middle).await? {
+                true => high = middle,
+                false => low = middle,
+            }
+        }
+        Ok(Some(high))
+    }
+
+    /// Whether the node can answer a state query at `height`
+    async fn has_state(&self, height: u64) -> Result<bool, NodeError> {
+        let result: Value = self
+            .node
+            .call_consensus(
+                "abci_query",
+                json!({
+                    "path": PROBE_PATH,
+                    "data": "",
+                    "height": height.to_string(),
+                    "prove": false,
+                }),
+            )
+            .await?;
+        // Pruned heights fail with a non-zero code, such as "version does not exist"
+        Ok(result["response"]["code"].as_u64().unwrap_or(0) == 0)
+    }
+}
+
+impl Tool for NodeRetentionTool {
+    const NAME: &'static str = "node_retention";
+
+    type Args = NodeRetentionArgs;
+    type Output = String;
+    type Error = NodeError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Report which history the operator's consensus node still keeps: its \
+                          earliest stored block, how far back its application state goes under \
+                          its pruning settings, and, given a height, whether the node can still \
+                          answer about it or the question must go to the indexer instead."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "A height a question is about, to say whether the node \
+                                        still serves it",
+                        "examples": [1500000],
+                    },
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
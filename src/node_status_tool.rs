@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::metrics::metrics;
+use crate::node::{NodeClient, NodeError};
+use crate::sampling_tool::height;
+
+/// The node status takes no arguments: the node is given by the config.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeStatusArgs {}
+
+/// Reports the connected node's peers and header sync, and its consensus node's if configured.
+pub struct NodeStatusTool {
+    node: Arc<NodeClient>,
+}
+
+impl NodeStatusTool {
+    pub fn new(node: Arc<NodeClient>) -> Self {
+        Self { node }
+    }
+
+    async fn status(&self) -> Result<String, NodeError> {
+        let (peers, sync, local_head) = futures::try_join!(
+            self.node.call::<Vec<Value>>("p2p.Peers", json!([])),
+            self.node.call::<Value>("header.SyncState", json!([])),
+            self.node.call::<Value>("header.LocalHead", json!([])),
+        )?;
+        // Reading the node's type takes admin permissions, which the token may not grant
+        let info = self.node.call::<Value>("node.Info", json!([])).await.ok();
+
+        let kind = match info.as_ref().and_then(|info| node_type(&info["type"])) {
+            Some(kind) => format!("a {} node", kind),
+            None => "a node of unknown type".to_string(),
+        };
+        let synced = height(&sync["height"]).unwrap_or(0);
+        let target = height(&sync["to_height"]).unwrap_or(synced);
+        let local_head = height(&local_head["header"]["height"]).unwrap_or(synced);
+        let mut output = format!(
+            "The node is {} with {} peer(s). Its header sync is at height {} of {}{}, and its \
+             latest synced header is at height {}.",
+            kind,
+            peers.len(),
+            synced,
+            target,
+            if synced >= target {
+                " (done syncing)".to_string()
+            } else {
+                format!(" ({} block(s) to go)", target - synced)
+            },
+            local_head
+        );
+        if let Some(error) = sync["error"].as_str().filter(|error| !error.is_empty()) {
+            output.push_str(&format!(" Syncing failed: {}.", error));
+        }
+        if peers.is_empty() {
+            output.push_str(" With no peers, it can't follow the network.");
+        }
+
+        if self.node.has_consensus() {
+            let (status, net_info) = futures::try_join!(
+                self.node.call_consensus::<Value>("status", json!({})),
+                self.node.call_consensus::<Value>("net_info", json!({})),
+            )?;
+            let sync_info = &status["sync_info"];
+            output.push_str(&format!(
+                " Its consensus node has {} peer(s), is {}, and is at height {}.",
+                height(&net_info["n_peers"]).unwrap_or(0),
+                if sync_info["catching_up"].as_bool().unwrap_or(false) {
+                    "still catching up"
+                } else {
+                    "caught up"
+                },
+                height(&sync_info["latest_block_height"]).unwrap_or(0)
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+/// celestia-node reports its type as a number in some versions and a name in others
+fn node_type(value: &Value) -> Option<String> {
+    match value {
+        Value::String(name) => Some(name.to_lowercase()),
+        Value::Number(n) => match n.as_u64()? {
+            1 => Some("bridge".to_string()),
+            2 => Some("light".to_string()),
+            3 => Some("full".to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl Tool for NodeStatusTool {
+    const NAME: &'static str = "node_status";
+
+    type Args = NodeStatusArgs;
+    type Output = String;
+    type Error = NodeError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Check the connected celestia node's infrastructure: its type, how many \
+                          peers it has, how far its header sync has got and its latest synced \
+                          height, and the consensus node's peers and catching-up status when \
+                          one is configured. Use it to sanity-check an operator's setup."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {},
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.status().await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt;
+
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::alert::Alert;
+
+/// Captures the errors that may occur while sending a notification.
+#[derive(Debug, thiserror::Error)]
+pub enum NotifyError {
+    #[error("Notification request failed: {0}")]
+    HttpRequestFailed(#[from] reqwest::Error),
+}
+
+/// Something worth telling an operator about.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// An alert condition held for a block
+    Alert(Alert),
+    /// A scheduled query was answered
+    Report {
+        name: String,
+        prompt: String,
+        answer: String,
+    },
+}
+
+impl Event {
+    /// The event as a JSON object, with a `kind` tag and a human-readable `message`
+    pub fn payload(&self) -> Value {
+        match self {
+            Self::Alert(alert) => json!({
+                "kind": "alert",
+                "height": alert.height,
+                "condition": alert.condition,
+                "value": alert.value,
+                "message": self.to_string(),
+            }),
+            Self::Report {
+                name,
+                prompt,
+                answer,
+            } => json!({
+                "kind": "report",
+                "name": name,
+                "prompt": prompt,
+                "answer": answer,
+                "message": self.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Alert(alert) => write!(f, "Alert: {}", alert),
+            Self::Report { name, answer, .. } => write!(f, "Report {}:\n{}", name, answer),
+        }
+    }
+}
+
+/// A destination for events.
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<(), NotifyError>>;
+}
+
+/// Prints events to stdout.
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    fn notify<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<(), NotifyError>> {
+        Box::pin(async move {
+            println!("{}", event);
+            Ok(())
+        })
+    }
+}
+
+/// Posts events as JSON to an HTTP endpoint.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<(), NotifyError>> {
+        Box::pin(async move {
+            self.client
+                .post(&self.url)
+                .json(&event.payload())
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// Posts events to a Slack incoming webhook.
+pub struct SlackNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+impl Notifier for SlackNotifier {
+    fn notify<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<(), NotifyError>> {
+        let text = match event {
+            Event::Alert(alert) => format!(":rotating_light: {}", alert),
+            Event::Report { name, answer, .. } => format!("*{}*\n{}", name, answer),
+        };
+
+        Box::pin(async move {
+            self.client
+                .post(&self.webhook_url)
+                .json(&json!({ "text": text }))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// The most characters Discord accepts in a message.
+#[cfg(feature = "discord")]
+const DISCORD_MAX_CHARS: usize = 2000;
+
+/// Posts events to a Discord webhook.
+#[cfg(feature = "discord")]
+pub struct DiscordNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+#[cfg(feature = "discord")]
+impl DiscordNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "discord")]
+impl Notifier for DiscordNotifier {
+    fn notify<'a>(&'a self, event: &'a Event) -> BoxFuture<'a, Result<(), NotifyError>> {
+        let mut content = match event {
+            Event::Alert(alert) => format!(":rotating_light: {}", alert),
+            Event::Report { name, answer, .. } => format!("**{}**\n{}", name, answer),
+        };
+        // Longer messages are rejected, so long reports are cut short
+        if content.chars().count() > DISCORD_MAX_CHARS {
+            content = content.chars().take(DISCORD_MAX_CHARS - 1).collect();
+            content.push('…');
+        }
+
+        Box::pin(async move {
+            self.client
+                .post(&self.webhook_url)
+                .json(&json!({ "content": content }))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// A notifier as configured in the `[[notifiers]]` tables of the config file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum NotifierConfig {
+    Stdout,
+    Webhook {
+        url: String,
+    },
+    Slack {
+        webhook_url: String,
+    },
+    #[cfg(feature = "discord")]
+    Discord {
+        webhook_url: String,
+    },
+}
+
+impl NotifierConfig {
+    pub fn build(&self) -> Box<dyn Notifier> {
+        match self {
+            Self::Stdout => Box::new(StdoutNotifier),
+            Self::Webhook { url } => Box::new(WebhookNotifier::new(url)),
+            Self::Slack { webhook_url } => Box::new(SlackNotifier::new(webhook_url)),
+            #[cfg(feature = "discord")]
+            Self::Discord { webhook_url } => Box::new(DiscordNotifier::new(webhook_url)),
+        }
+    }
+}
+
+/// Builds the configured notifiers, falling back to stdout if there are none.
+pub fn from_config(configs: &[NotifierConfig]) -> Vec<Box<dyn Notifier>> {
+    if configs.is_empty() {
+        return vec![Box::new(StdoutNotifier)];
+    }
+    configs.iter().map(NotifierConfig::build).collect()
+}
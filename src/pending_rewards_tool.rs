@@ -0,0 +1,131 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::address_tool;
+use crate::amount::Utia;
+use crate::celestia_search_tool::CelestiaSearchError;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+use crate::rest::RestClient;
+
+/// The address to look up rewards for.
+#[derive(Deserialize)]
+pub struct PendingRewardsArgs {
+    /// The `celestia1...` account address of the delegator.
+    address: String,
+    /// The networks to look the rewards up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Reports the staking rewards an address can claim, per validator.
+///
+/// Unclaimed rewards are chain state that Celenium doesn't index, so they are read from the
+/// distribution module of a Cosmos REST API, or of celestia-app over gRPC where configured.
+pub struct PendingRewardsTool {
+    network: Network,
+    rest: RestClient,
+}
+
+impl PendingRewardsTool {
+    /// Creates a tool answering for `network` unless a call asks for another one.
+    pub fn new(network: Network, rest: RestClient) -> Self {
+        Self { network, rest }
+    }
+
+    async fn lookup(&self, args: PendingRewardsArgs) -> Result<String, CelestiaSearchError> {
+        let address = address_tool::validate_address(&args.address)?;
+
+        if args.network.is_empty() {
+            return self.rewards(self.network, address).await;
+        }
+        let outputs = args.network.iter().map(|&network| async move {
+            let output = self.rewards(network, address).await?;
+            Ok::<_, CelestiaSearchError>(format!("On {}: {}", network, output))
+        });
+        Ok(futures::future::try_join_all(outputs).await?.join("\n\n"))
+    }
+
+    /// Describes the rewards `address` can claim on `network`, largest first
+    async fn rewards(
+        &self,
+        network: Network,
+        address: &str,
+    ) -> Result<String, CelestiaSearchError> {
+        // Rewards accrue in fractions of utia that can't be claimed
+        let mut rewards: Vec<(String, Utia)> = self
+            .rest
+            .delegation_rewards(network, address)
+            .await?
+            .into_iter()
+            .map(|(validator, amount)| (validator, amount.truncate()))
+            .filter(|(_, amount)| *amount > Utia::ZERO)
+            .collect();
+        if rewards.is_empty() {
+            return Ok(format!(
+                "Address {} has no unclaimed staking rewards.",
+                address
+            ));
+        }
+        rewards.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+
+        let total: Utia = rewards.iter().map(|(_, amount)| amount).sum();
+        let mut output = format!(
+            "Address {} can claim {} in staking rewards from {} validator(s):",
+            address,
+            price::describe(total, None),
+            rewards.len()
+        );
+        for (validator, amount) in rewards {
+            output.push_str(&format!(
+                "\n- {}: {}",
+                validator,
+                price::describe(amount, None)
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+impl Tool for PendingRewardsTool {
+    const NAME: &'static str = "pending_rewards";
+
+    type Args = PendingRewardsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Look up the staking rewards a Celestia address can claim right now, \
+                          in total and per validator it delegates to."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "address": {
+                        "type": "string",
+                        "description": "The delegator's account address",
+                        "examples": ["celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p"],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["address"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.lookup(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
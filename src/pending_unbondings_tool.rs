@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::address_tool;
+use crate::amount::Utia;
+use crate::celestia_search_tool::CelestiaSearchError;
+use crate::estimate_time_tool;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+use crate::rest::RestClient;
+
+/// The address to look up unbondings for.
+#[derive(Deserialize)]
+pub struct PendingUnbondingsArgs {
+    /// The `celestia1...` account address of the delegator.
+    address: String,
+    /// The networks to look the unbondings up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Lists the stake an address is unbonding or redelegating, with when each completes.
+///
+/// Like rewards, these are chain state Celenium doesn't index, so they are read from the staking
+/// module of a Cosmos REST API.
+pub struct PendingUnbondingsTool {
+    network: Network,
+    rest: RestClient,
+}
+
+impl PendingUnbondingsTool {
+    /// Creates a tool answering for `network` unless a call asks for another one.
+    pub fn new(network: Network, rest: RestClient) -> Self {
+        Self { network, rest }
+    }
+
+    async fn lookup(&self, args: PendingUnbondingsArgs) -> Result<String, CelestiaSearchError> {
+        let address = address_tool::validate_address(&args.address)?;
+
+        if args.network.is_empty() {
+            return self.unbondings(self.network, address).await;
+        }
+        let outputs = args.network.iter().map(|&network| async move {
+            let output = self.unbondings(network, address).await?;
+            Ok::<_, CelestiaSearchError>(format!("On {}: {}", network, output))
+        });
+        Ok(futures::future::try_join_all(outputs).await?.join("\n\n"))
+    }
+
+    /// Describes the unbondings and redelegations of `address` on `network`, soonest first
+    async fn unbondings(
+        &self,
+        network: Network,
+        address: &str,
+    ) -> Result<String, CelestiaSearchError> {
+        let (mut unbondings, mut redelegations) = futures::try_join!(
+            self.rest.unbonding_delegations(network, address),
+            self.rest.redelegations(network, address),
+        )?;
+        if unbondings.is_empty() && redelegations.is_empty() {
+            return Ok(format!(
+                "Address {} has no pending unbondings or redelegations.",
+                address
+            ));
+        }
+        // RFC 3339 times in UTC sort chronologically
+        unbondings.sort_by(|a, b| a.completion_time.cmp(&b.completion_time));
+        redelegations.sort_by(|a, b| a.completion_time.cmp(&b.completion_time));
+        let now = Utc::now();
+
+        let mut sections = Vec::new();
+        if !unbondings.is_empty() {
+            let total: Utia = unbondings.iter().map(|unbonding| unbonding.balance).sum();
+            let mut section = format!(
+                "Address {} is unbonding {} ({} unbonding(s)), each liquid once it completes:",
+                address,
+                price::describe(total, None),
+                unbondings.len()
+            );
+            for unbonding in &unbondings {
+                section.push_str(&format!(
+                    "\n- {} from {} (started at height {}): {}",
+                    price::describe(unbonding.balance, None),
+                    unbonding.validator,
+                    unbonding.creation_height,
+                    completes(&unbonding.completion_time, now)
+                ));
+            }
+            sections.push(section);
+        }
+        if !redelegations.is_empty() {
+            let mut section = format!(
+                "{} redelegation(s), whose stake is already bonded to the new validator but \
+                 can't be redelegated again (or escape slashing of the old one) until they \
+                 complete:",
+                redelegations.len()
+            );
+            for redelegation in &redelegations {
+                section.push_str(&format!(
+                    "\n- {} from {} to {} (started at height {}): {}",
+                    price::describe(redelegation.balance, None),
+                    redelegation.source,
+                    redelegation.destination,
+                    redelegation.creation_height,
+                    completes(&redelegation.completion_time, now)
+                ));
+            }
+            sections.push(section);
+        }
+
+        Ok(sections.join("\n"))
+    }
+}
+
+/// When an entry completes, and how long that is from `now`
+fn completes(completion_time: &str, now: DateTime<Utc>) -> String {
+    let Some(time) = DateTime::parse_from_rfc3339(completion_time)
+        .ok()
+        .map(|time| time.to_utc())
+    else {
+        return "completion time unknown".to_string();
+    };
+    let seconds = (time - now).num_seconds();
+    match seconds {
+        seconds if seconds > 0 => format!(
+            "completes at {}, in about {}",
+            estimate_time_tool::rfc3339(time),
+            estimate_time_tool::span(seconds as f64)
+        ),
+        _ => format!(
+            "completed at {}, with the next block",
+            estimate_time_tool::rfc3339(time)
+        ),
+    }
+}
+
+impl Tool for PendingUnbondingsTool {
+    const NAME: &'static str = "pending_unbondings";
+
+    type Args = PendingUnbondingsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "List the stake a Celestia address is unbonding or redelegating, per \
+                          validator, with when each completes: when unbonding TIA becomes \
+                          liquid, and when redelegated TIA can be moved again."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "address": {
+                        "type": "string",
+                        "description": "The delegator's account address",
+                        "examples": ["celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p"],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["address"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.lookup(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
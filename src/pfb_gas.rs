@@ -0,0 +1,48 @@
+//! The gas a `MsgPayForBlobs` consumes, computed as celestia-app's blob module does, so the cost
+//! of posting blobs can be estimated without submitting or simulating anything.
+//!
+//! The constants are celestia-app's defaults; governance can change the gas per blob byte and the
+//! cost per transaction byte, so estimates drift if it does.
+
+/// The size of a share, the unit blobs are laid out in.
+pub const SHARE_SIZE: u64 = 512;
+
+/// The gas charged per byte of the shares a blob occupies.
+pub const GAS_PER_BLOB_BYTE: u64 = 8;
+
+/// The gas charged per byte of the transaction itself.
+pub const TX_SIZE_COST_PER_BYTE: u64 = 10;
+
+/// The bytes a blob adds to its PFB transaction, besides its data: namespace, commitment and size.
+pub const BYTES_PER_BLOB_INFO: u64 = 70;
+
+/// The gas every PFB consumes however many blobs it carries, for its signature and message.
+pub const PFB_GAS_FIXED_COST: u64 = 75_000;
+
+/// The data the first share of a blob holds, after its namespace, info byte and sequence length.
+const FIRST_SHARE_CONTENT: u64 = SHARE_SIZE - 29 - 1 - 4;
+
+/// The data each further share of a blob holds, after its namespace and info byte.
+pub(crate) const CONTINUATION_SHARE_CONTENT: u64 = SHARE_SIZE - 29 - 1;
+
+/// The shares a blob of `size` bytes occupies in the square.
+pub fn shares_needed(size: u64) -> u64 {
+    if size <= FIRST_SHARE_CONTENT {
+        return 1;
+    }
+    1 + (size - FIRST_SHARE_CONTENT).div_ceil(CONTINUATION_SHARE_CONTENT)
+}
+
+/// The gas a PFB carrying blobs of `sizes` consumes: their shares by the gas per blob byte, the
+/// bytes each blob adds to the transaction, and the fixed cost.
+pub fn estimate_gas(sizes: &[u64]) -> u64 {
+    let shares: u64 = sizes.iter().map(|&size| shares_needed(size)).sum();
+    shares * SHARE_SIZE * GAS_PER_BLOB_BYTE
+        + TX_SIZE_COST_PER_BYTE * BYTES_PER_BLOB_INFO * sizes.len() as u64
+        + PFB_GAS_FIXED_COST
+}
+
+/// The fee, in utia, of `gas` at `gas_price` utia per gas, rounded up as fees must cover it.
+pub fn fee(gas: u64, gas_price: f64) -> u64 {
+    (gas as f64 * gas_price).ceil() as u64
+}
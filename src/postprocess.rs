@@ -0,0 +1,63 @@
+use serde_json::Value;
+
+/// Turns the raw output of a turn into the answer to print.
+///
+/// Plain text, JSON-encoded tool outputs, JSON objects with an `answer` field and responses
+/// wrapped in a fenced code block are all reduced to their text, then normalized.
+pub fn answer(raw: &str) -> String {
+    let text = unfence(raw.trim());
+
+    let text = match serde_json::from_str::<Value>(text) {
+        Ok(Value::String(s)) => s,
+        Ok(Value::Object(object)) => match object.get("answer") {
+            Some(Value::String(s)) => s.clone(),
+            _ => serde_json::to_string_pretty(&object).unwrap_or_else(|_| text.to_string()),
+        },
+        _ => text.to_string(),
+    };
+
+    normalize(&text)
+}
+
+/// Returns the contents of a response that is entirely a fenced code block.
+pub(crate) fn unfence(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    let Some(body) = rest.strip_suffix("```") else {
+        return text;
+    };
+
+    // Skip the info string (e.g., `json`) on the opening line
+    match body.split_once('\n') {
+        Some((_, contents)) => contents.trim(),
+        None => body.trim(),
+    }
+}
+
+/// Normalizes line endings, trailing whitespace, blank lines and list markers.
+pub fn normalize(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut blank = false;
+
+    for line in text.trim().lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank = true;
+            continue;
+        }
+        if blank && !lines.is_empty() {
+            lines.push(String::new());
+        }
+        blank = false;
+
+        let indent = line.len() - line.trim_start().len();
+        let item = line.trim_start();
+        match item.strip_prefix("* ").or_else(|| item.strip_prefix("+ ")) {
+            Some(rest) => lines.push(format!("{}- {}", &line[..indent], rest)),
+            None => lines.push(line.to_string()),
+        }
+    }
+
+    lines.join("\n")
+}
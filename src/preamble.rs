@@ -0,0 +1,40 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// The preamble used unless a custom one is supplied.
+pub const DEFAULT: &str = include_str!("../prompts/preamble.md");
+
+/// How a custom preamble is combined with the built-in one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PreambleMode {
+    /// Use the custom preamble alone
+    #[default]
+    Replace,
+    /// Append the custom preamble to the built-in one, keeping its tool guidance
+    Extend,
+}
+
+/// A custom preamble, as configured in the `[preamble]` table of the config file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PreambleConfig {
+    /// The file the preamble is read from.
+    pub file: PathBuf,
+    #[serde(default)]
+    pub mode: PreambleMode,
+}
+
+/// Reads the preamble from `path`, combined with the default one as `mode` says, or returns
+/// the default one.
+pub fn load(path: Option<&Path>, mode: PreambleMode) -> std::io::Result<String> {
+    let Some(path) = path else {
+        return Ok(DEFAULT.to_string());
+    };
+    let custom = std::fs::read_to_string(path)?;
+    Ok(match mode {
+        PreambleMode::Replace => custom,
+        PreambleMode::Extend => format!("{}\n\n{}", DEFAULT.trim_end(), custom.trim()),
+    })
+}
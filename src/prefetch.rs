@@ -0,0 +1,30 @@
+//! Warms the cache with the latest blocks in the background, so that questions about what's
+//! happening right now don't wait on a request per block mid-conversation.
+
+use futures::{stream, StreamExt};
+
+use crate::celenium::BLOCK_CACHE_ENTRIES;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::DEFAULT_CONCURRENCY;
+
+/// The most blocks prefetched, so they don't evict each other from the cache.
+pub const MAX_BLOCKS: u64 = BLOCK_CACHE_ENTRIES as u64 / 2;
+
+/// Fetches the stats of the latest `count` blocks (up to [`MAX_BLOCKS`]) into the cache,
+/// returning how many were fetched.
+///
+/// Blocks that fail to fetch are left for the questions that need them.
+pub async fn recent_blocks(
+    tool: &CelestiaSearchTool,
+    count: u64,
+) -> Result<u64, CelestiaSearchError> {
+    let head = tool.chain_head().await?;
+    let count = count.min(MAX_BLOCKS).min(head);
+    let fetched = stream::iter(head - count + 1..=head)
+        .map(|height| tool.fetch_stats(height))
+        .buffer_unordered(DEFAULT_CONCURRENCY)
+        .filter(|result| std::future::ready(result.is_ok()))
+        .count()
+        .await;
+    Ok(fetched as u64)
+}
@@ -0,0 +1,153 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::amount::Utia;
+use crate::time::Instant;
+
+/// The CoinGecko API, used unless a different base URL is injected.
+pub const DEFAULT_BASE_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// The CoinGecko identifier of TIA.
+const COIN_ID: &str = "celestia";
+
+/// How long a fetched price is reused before it is refreshed.
+const PRICE_TTL: Duration = Duration::from_secs(60);
+
+/// The number of utia in one TIA.
+pub const UTIA_PER_TIA: f64 = 1_000_000.0;
+
+/// Captures the errors that may occur while fetching prices.
+#[derive(Debug, thiserror::Error)]
+pub enum PriceError {
+    #[error("Price request failed: {0}")]
+    HttpRequestFailed(#[from] reqwest::Error),
+    #[error("Price response has no `{0}` field")]
+    MissingField(&'static str),
+}
+
+/// A cached source of the TIA price in USD.
+pub struct PriceFeed {
+    client: reqwest::Client,
+    base_url: String,
+    cached: Mutex<Option<(f64, Instant)>>,
+}
+
+impl Default for PriceFeed {
+    fn default() -> Self {
+        Self::with_base_url(DEFAULT_BASE_URL)
+    }
+}
+
+impl PriceFeed {
+    /// Creates a feed that queries the CoinGecko API at `base_url`.
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Returns the price of one TIA in USD, refreshing it at most every [`PRICE_TTL`].
+    pub async fn usd_per_tia(&self) -> Result<f64, PriceError> {
+        if let Some((price, fetched_at)) = *self.cached.lock().unwrap() {
+            if fetched_at.elapsed() < PRICE_TTL {
+                return Ok(price);
+            }
+        }
+
+        let data = self.simple_price("").await?;
+        field(&data, "usd")
+    }
+
+    /// Fetches the current market data of TIA, which also refreshes the cached price.
+    pub async fn market_data(&self) -> Result<MarketData, PriceError> {
+        let data = self
+            .simple_price("&include_market_cap=true&include_24hr_vol=true&include_24hr_change=true")
+            .await?;
+
+        Ok(MarketData {
+            price_usd: field(&data, "usd")?,
+            change_24h_pct: field(&data, "usd_24h_change")?,
+            market_cap_usd: field(&data, "usd_market_cap")?,
+            volume_24h_usd: field(&data, "usd_24h_vol")?,
+        })
+    }
+
+    /// Queries the simple price endpoint, caching the price it returns
+    async fn simple_price(&self, options: &str) -> Result<Value, PriceError> {
+        let url = format!(
+            "{}/simple/price?ids={}&vs_currencies=usd{}",
+            self.base_url, COIN_ID, options
+        );
+        let data: Value = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let price = field(&data, "usd")?;
+        *self.cached.lock().unwrap() = Some((price, Instant::now()));
+        Ok(data)
+    }
+}
+
+/// The market data of TIA, in USD.
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct MarketData {
+    pub price_usd: f64,
+    pub change_24h_pct: f64,
+    pub market_cap_usd: f64,
+    pub volume_24h_usd: f64,
+}
+
+fn field(data: &Value, name: &'static str) -> Result<f64, PriceError> {
+    data[COIN_ID][name]
+        .as_f64()
+        .ok_or(PriceError::MissingField(name))
+}
+
+/// Describes an amount of utia with its TIA equivalent, and its USD value if a price is given.
+///
+/// Amounts that aren't numbers are returned as is.
+pub fn describe_utia(utia: &str, usd_per_tia: Option<f64>) -> String {
+    match utia.parse() {
+        Ok(amount) => describe(amount, usd_per_tia),
+        Err(_) => utia.to_string(),
+    }
+}
+
+/// Describes an amount with its TIA equivalent, and its USD value if a price is given.
+pub fn describe(amount: Utia, usd_per_tia: Option<f64>) -> String {
+    match usd_per_tia {
+        Some(price) => format!(
+            "{} utia ({} TIA, ~{})",
+            amount,
+            amount.tia(),
+            usd(amount.as_f64() / UTIA_PER_TIA * price)
+        ),
+        None => format!("{} utia ({} TIA)", amount, amount.tia()),
+    }
+}
+
+/// Formats a USD amount, keeping a few significant digits for amounts under a cent.
+fn usd(amount: f64) -> String {
+    if amount != 0.0 && amount.abs() < 0.01 {
+        format!("${}", trim_decimals(amount, 6))
+    } else {
+        format!("${:.2}", amount)
+    }
+}
+
+fn trim_decimals(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
@@ -0,0 +1,274 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::de::string_or_number;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// How many of the largest validators' votes are listed.
+pub const MAX_VALIDATORS: u64 = 50;
+
+/// The tallying parameters of the Cosmos SDK, for proposals Celenium reports none for
+const DEFAULT_QUORUM: f64 = 0.334;
+const DEFAULT_THRESHOLD: f64 = 0.5;
+const DEFAULT_VETO_THRESHOLD: f64 = 0.334;
+
+/// The proposal to break the votes of down.
+#[derive(Deserialize)]
+pub struct ProposalVotesArgs {
+    /// The id of the governance proposal.
+    proposal: u64,
+    /// How many of the largest validators' votes to list.
+    #[serde(default = "default_validators")]
+    validators: u64,
+    /// The networks the proposal is looked up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_validators() -> u64 {
+    10
+}
+
+/// Breaks a governance proposal's votes down by option, with its turnout and quorum.
+pub struct ProposalVotesTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl ProposalVotesTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn report(&self, args: ProposalVotesArgs) -> Result<String, CelestiaSearchError> {
+        let validators = args.validators.min(MAX_VALIDATORS);
+        self.blocks
+            .across(&args.network, |blocks| {
+                report(blocks, args.proposal, validators)
+            })
+            .await
+    }
+}
+
+/// The voting power behind each option
+struct Tally {
+    yes: f64,
+    no: f64,
+    veto: f64,
+    abstain: f64,
+}
+
+impl Tally {
+    fn of(proposal: &Value) -> Self {
+        let power = |option: &str| number(&proposal[format!("{}_voting_power", option)]);
+        Self {
+            yes: power("yes"),
+            no: power("no"),
+            veto: power("no_with_veto"),
+            abstain: power("abstain"),
+        }
+    }
+
+    fn voted(&self) -> f64 {
+        self.yes + self.no + self.veto + self.abstain
+    }
+
+    /// The power that took a side, which the thresholds are relative to
+    fn decided(&self) -> f64 {
+        self.yes + self.no + self.veto
+    }
+}
+
+async fn report(
+    blocks: &CelestiaSearchTool,
+    id: u64,
+    validators: u64,
+) -> Result<String, CelestiaSearchError> {
+    let proposal = blocks.fetch(&format!("/proposal/{}", id)).await?;
+    let tally = Tally::of(&proposal);
+    let total = number(&proposal["total_voting_power"]);
+    let share = |power: f64, of: f64| match of > 0.0 {
+        true => power / of,
+        false => 0.0,
+    };
+
+    let mut output = format!(
+        "Proposal #{} \"{}\" is {}.",
+        id,
+        proposal["title"].as_str().unwrap_or("untitled"),
+        status(&proposal)
+    );
+    if tally.voted() == 0.0 {
+        output.push_str(" No votes have been counted yet.");
+    } else {
+        let quorum = parameter(&proposal["quorum"], DEFAULT_QUORUM);
+        let threshold = parameter(&proposal["threshold"], DEFAULT_THRESHOLD);
+        let veto_threshold = parameter(&proposal["veto_quorum"], DEFAULT_VETO_THRESHOLD);
+        let (yes, veto) = (
+            share(tally.yes, tally.decided()),
+            share(tally.veto, tally.decided()),
+        );
+
+        output.push_str(&format!(
+            " By voting power, yes {}, no {}, no with veto {} and abstain {} of the votes.",
+            percent(share(tally.yes, tally.voted())),
+            percent(share(tally.no, tally.voted())),
+            percent(share(tally.veto, tally.voted())),
+            percent(share(tally.abstain, tally.voted()))
+        ));
+        if let Some(votes) = proposal["votes_count"].as_u64() {
+            output.push_str(&format!(" {} vote(s) were cast.", votes));
+        }
+        if total > 0.0 {
+            let turnout = share(tally.voted(), total);
+            output.push_str(&format!(
+                " Turnout is {} of the bonded voting power, so the {} quorum is {}.",
+                percent(turnout),
+                percent(quorum),
+                if turnout >= quorum {
+                    "reached"
+                } else {
+                    "not reached"
+                }
+            ));
+        }
+        output.push_str(&format!(
+            " Excluding abstentions, yes has {} against a {} threshold and no with veto {} \
+             against a {} veto threshold, so as tallied the proposal {}.",
+            percent(yes),
+            percent(threshold),
+            percent(veto),
+            percent(veto_threshold),
+            match (yes > threshold, veto > veto_threshold) {
+                (_, true) => "is vetoed",
+                (true, false) => "passes",
+                (false, false) => "fails",
+            }
+        ));
+    }
+
+    if validators > 0 {
+        let votes = blocks
+            .fetch(&format!(
+                "/proposal/{}/votes?voter=validator&limit={}",
+                id, MAX_VALIDATORS
+            ))
+            .await?;
+        let mut votes: Vec<(&str, f64, &str)> = votes
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|vote| {
+                let validator = &vote["validator"];
+                let name = validator["moniker"]
+                    .as_str()
+                    .or(vote["voter"]["hash"].as_str())
+                    .unwrap_or("unknown validator");
+                let power = Some(number(&vote["voting_power"]))
+                    .filter(|power| *power > 0.0)
+                    .unwrap_or_else(|| number(&validator["stake"]));
+                (name, power, vote["option"].as_str().unwrap_or("unknown"))
+            })
+            .collect();
+        votes.sort_by(|a, b| b.1.total_cmp(&a.1));
+        votes.truncate(validators as usize);
+
+        if votes.is_empty() {
+            output.push_str("\nNo validator has voted.");
+        } else {
+            output.push_str(&format!(
+                "\nVotes of the {} largest validators that voted:",
+                votes.len()
+            ));
+            for (name, power, option) in votes {
+                match total > 0.0 {
+                    true => output.push_str(&format!(
+                        "\n- {} ({} of the voting power): {}",
+                        name,
+                        percent(share(power, total)),
+                        option
+                    )),
+                    false => output.push_str(&format!("\n- {}: {}", name, option)),
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// The proposal's status, as Celenium or the gov module (`PROPOSAL_STATUS_...`) name it
+fn status(proposal: &Value) -> String {
+    let status = proposal["status"].as_str().unwrap_or("of unknown status");
+    let status = status.strip_prefix("PROPOSAL_STATUS_").unwrap_or(status);
+    match status.to_lowercase().as_str() {
+        "deposit_period" => "in its deposit period".to_string(),
+        "voting_period" => "in its voting period".to_string(),
+        status => status.replace('_', " "),
+    }
+}
+
+fn number(value: &Value) -> f64 {
+    string_or_number(value).unwrap_or_default()
+}
+
+/// A tallying parameter, or the SDK's default if the proposal has none
+fn parameter(value: &Value, default: f64) -> f64 {
+    Some(number(value)).filter(|n| *n > 0.0).unwrap_or(default)
+}
+
+fn percent(fraction: f64) -> String {
+    format!("{:.2}%", fraction * 100.0)
+}
+
+impl Tool for ProposalVotesTool {
+    const NAME: &'static str = "proposal_votes";
+
+    type Args = ProposalVotesArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Break down the votes on a Celestia governance proposal: the share of \
+                          voting power for yes, no, no with veto and abstain, turnout against \
+                          the quorum, whether it passes or is vetoed as tallied, and how the \
+                          largest validators voted."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "proposal": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The proposal id",
+                        "examples": [3],
+                    },
+                    "validators": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": MAX_VALIDATORS,
+                        "description": "How many of the largest validators' votes to list",
+                        "examples": [10],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["proposal"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,144 @@
+//! Where an answer's data came from: the explorer pages showing what each tool call of a turn
+//! looked up, so readers can check the answer's claims against Celenium.
+
+use serde_json::Value;
+
+use crate::assistant::{ToolCall, Turn};
+use crate::network::Network;
+
+/// What a tool call looked up on one network, with the explorer pages showing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Source {
+    pub tool: String,
+    pub network: Network,
+    /// The blocks, transactions, accounts and so on named by the call's arguments, each with its
+    /// explorer page if it has one.
+    pub subjects: Vec<(String, Option<String>)>,
+}
+
+impl Source {
+    fn line(&self) -> String {
+        let mut line = format!("- `{}` on {}", self.tool, self.network);
+        let subjects: Vec<String> = self
+            .subjects
+            .iter()
+            .map(|(subject, url)| match url {
+                Some(url) => format!("{} <{}>", subject, url),
+                None => subject.clone(),
+            })
+            .collect();
+        if !subjects.is_empty() {
+            line.push_str(&format!(": {}", subjects.join(", ")));
+        }
+        line
+    }
+}
+
+/// The sources of the tool calls made for `turn`, in order and without repeats; calls only
+/// planned in dry-run mode looked nothing up.
+pub fn sources(turn: &Turn, network: Network) -> Vec<Source> {
+    let mut sources: Vec<Source> = Vec::new();
+    let calls = turn.steps.iter().chain(&turn.tool_call);
+    for call in calls.filter(|call| call.output.is_some()) {
+        for source in call_sources(call, network) {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+    }
+    sources
+}
+
+/// A footer listing the sources of `turn`, or `None` if it made no tool calls.
+pub fn footer(turn: &Turn, network: Network) -> Option<String> {
+    let sources = sources(turn, network);
+    if sources.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = sources.iter().map(Source::line).collect();
+    Some(format!("Sources:\n{}", lines.join("\n")))
+}
+
+/// One source per network the call queried, `network` unless its arguments name others
+fn call_sources(call: &ToolCall, network: Network) -> Vec<Source> {
+    let networks = match &call.args["network"] {
+        Value::String(name) => Network::parse(name).into_iter().collect(),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(|name| name.as_str().and_then(Network::parse))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let networks = if networks.is_empty() {
+        vec![network]
+    } else {
+        networks
+    };
+
+    networks
+        .into_iter()
+        .map(|network| Source {
+            tool: call.name.clone(),
+            network,
+            subjects: subjects(&call.args, network),
+        })
+        .collect()
+}
+
+/// The things named by a tool call's arguments, with their explorer pages
+fn subjects(args: &Value, network: Network) -> Vec<(String, Option<String>)> {
+    let page = |path: String| Some(format!("{}/{}", network.explorer_url(), path));
+    let text = |key: &str| args[key].as_str().map(str::trim).filter(|s| !s.is_empty());
+
+    let mut subjects = Vec::new();
+    if let Some(height) = args["height"].as_u64() {
+        subjects.push((
+            format!("block {}", height),
+            page(format!("block/{}", height)),
+        ));
+    }
+    // Ranges are of heights wherever the arguments are integers
+    if let (Some(from), Some(to)) = (args["from"].as_u64(), args["to"].as_u64()) {
+        subjects.push((format!("block {}", from), page(format!("block/{}", from))));
+        subjects.push((format!("block {}", to), page(format!("block/{}", to))));
+    }
+    if let Some(hash) = text("hash") {
+        subjects.push((
+            format!("transaction {}", hash),
+            page(format!("tx/{}", hash)),
+        ));
+    }
+    if let Some(address) = text("address") {
+        subjects.push((
+            format!("address {}", address),
+            page(format!("address/{}", address)),
+        ));
+    }
+    if let Some(namespace) = text("namespace") {
+        subjects.push((
+            format!("namespace {}", namespace),
+            page(format!("namespace/{}", namespace)),
+        ));
+    }
+    // Validator pages are by Celenium id, which an operator address isn't
+    if let Some(validator) = text("validator") {
+        let url = validator
+            .parse::<u64>()
+            .ok()
+            .and_then(|id| page(format!("validator/{}", id)));
+        subjects.push((format!("validator {}", validator), url));
+    }
+    if let Some(rollup) = text("rollup") {
+        subjects.push((
+            format!("rollup {}", rollup),
+            page(format!("rollup/{}", rollup)),
+        ));
+    }
+    if let Some(proposal) = args["proposal"].as_u64() {
+        subjects.push((
+            format!("proposal {}", proposal),
+            page(format!("proposal/{}", proposal)),
+        ));
+    }
+    subjects
+}
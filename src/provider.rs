@@ -0,0 +1,297 @@
+use std::sync::{Arc, Mutex};
+
+use rig::completion::{
+    self, CompletionError, CompletionModel as _, CompletionRequest, ModelChoice,
+};
+use rig::providers::openai;
+
+use crate::azure::{self, AzureConfig};
+use crate::gemini::{self, GeminiConfig};
+use crate::replay::{RecordedCompletion, Recording};
+
+/// The service the assistant's models are served by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Provider {
+    /// The OpenAI API, with `OPENAI_API_KEY`
+    #[default]
+    #[value(name = "openai")]
+    OpenAi,
+    /// An Azure OpenAI deployment, configured in the `[azure]` section of the config
+    Azure,
+    /// Google's Gemini API, with `GEMINI_API_KEY` and the `[gemini]` section of the config
+    Gemini,
+}
+
+/// Captures the errors that may occur while setting up a provider.
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    #[error("{0} is not set")]
+    MissingKey(&'static str),
+    #[error("Azure OpenAI needs an [azure] section with its endpoint in the config")]
+    AzureNotConfigured,
+}
+
+/// A client of the configured provider.
+#[derive(Clone)]
+pub enum LlmClient {
+    OpenAi(openai::Client),
+    Azure {
+        client: azure::Client,
+        deployment: Option<String>,
+    },
+    Gemini {
+        client: gemini::Client,
+        model: Option<String>,
+    },
+    /// The completions of a recording, served instead of asking a model (`--replay`)
+    Replay(ReplayedModel),
+}
+
+impl LlmClient {
+    /// Creates a client of `provider`, failing rather than panicking if its key isn't set.
+    pub fn new(
+        provider: Provider,
+        azure: Option<&AzureConfig>,
+        gemini: Option<&GeminiConfig>,
+    ) -> Result<Self, ProviderError> {
+        match provider {
+            Provider::OpenAi => {
+                let api_key = std::env::var("OPENAI_API_KEY")
+                    .map_err(|_| ProviderError::MissingKey("OPENAI_API_KEY"))?;
+                Ok(Self::OpenAi(openai::Client::new(&api_key)))
+            }
+            Provider::Azure => {
+                let config = azure.ok_or(ProviderError::AzureNotConfigured)?;
+                let api_key = config
+                    .api_key()
+                    .ok_or(ProviderError::MissingKey("AZURE_OPENAI_API_KEY"))?;
+                Ok(Self::Azure {
+                    client: azure::Client::new(&config.endpoint, &api_key, config.api_version()),
+                    deployment: config.deployment.clone(),
+                })
+            }
+            Provider::Gemini => {
+                let config = gemini.cloned().unwrap_or_default();
+                let api_key = config
+                    .api_key()
+                    .ok_or(ProviderError::MissingKey("GEMINI_API_KEY"))?;
+                Ok(Self::Gemini {
+                    client: gemini::Client::from_config(&config, &api_key),
+                    model: config.model.clone(),
+                })
+            }
+        }
+    }
+
+    /// The completion model named `model`, or for Azure and Gemini the configured deployment
+    /// or model.
+    pub fn completion_model(&self, model: &str) -> Model {
+        match self {
+            Self::OpenAi(client) => Model::OpenAi(client.completion_model(model)),
+            Self::Azure { client, deployment } => {
+                Model::Azure(client.completion_model(deployment.as_deref().unwrap_or(model)))
+            }
+            Self::Gemini {
+                client,
+                model: configured,
+            } => Model::Gemini(client.completion_model(configured.as_deref().unwrap_or(model))),
+            Self::Replay(replayed) => Model::Replayed(replayed.clone()),
+        }
+    }
+}
+
+/// A completion model of any provider, so agents are built the same way whichever is used.
+#[derive(Clone)]
+pub enum Model {
+    OpenAi(openai::CompletionModel),
+    Azure(azure::CompletionModel),
+    Gemini(gemini::CompletionModel),
+    /// A model whose every completion is added to a recording (`--record`)
+    Recorded {
+        model: Box<Model>,
+        recording: Arc<Mutex<Recording>>,
+    },
+    Replayed(ReplayedModel),
+}
+
+impl Model {
+    /// The model, adding every completion it makes to `recording`.
+    pub fn recorded(self, recording: Arc<Mutex<Recording>>) -> Self {
+        let model = match self {
+            Self::Recorded { model, .. } => model,
+            model => Box::new(model),
+        };
+        Self::Recorded { model, recording }
+    }
+}
+
+/// The raw response of any provider's completion model.
+#[derive(Debug)]
+pub enum Response {
+    /// Given by OpenAI and Azure, whose APIs are the same
+    OpenAi(openai::CompletionResponse),
+    Gemini(gemini::GenerateContentResponse),
+    Replayed(RecordedCompletion),
+}
+
+impl completion::CompletionModel for Model {
+    type Response = Response;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
+        let Self::Recorded { model, recording } = self else {
+            return self.complete(request).await;
+        };
+        let prompt = request.prompt.clone();
+        let response = model.complete(request).await?;
+        recording
+            .lock()
+            .unwrap()
+            .completions
+            .push(RecordedCompletion {
+                prompt,
+                message: match &response.choice {
+                    ModelChoice::Message(message) => Some(message.clone()),
+                    ModelChoice::ToolCall(..) => None,
+                },
+                tool_calls: response.raw_response.tool_calls(),
+            });
+        Ok(response)
+    }
+}
+
+impl Model {
+    /// The completion of any model but a recorded one, which [`Model::recorded`] never nests.
+    async fn complete(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Response>, CompletionError> {
+        match self {
+            Self::OpenAi(model) => Ok(wrap(model.completion(request).await?, Response::OpenAi)),
+            Self::Azure(model) => Ok(wrap(model.completion(request).await?, Response::OpenAi)),
+            Self::Gemini(model) => Ok(wrap(model.completion(request).await?, Response::Gemini)),
+            Self::Recorded { .. } => unreachable!("recorded models aren't recorded again"),
+            Self::Replayed(replayed) => {
+                let completion = replayed.serve(&request.prompt)?;
+                let choice = match (&completion.message, completion.tool_calls.first()) {
+                    (Some(message), _) => ModelChoice::Message(message.clone()),
+                    (None, Some((name, args))) => ModelChoice::ToolCall(name.clone(), args.clone()),
+                    (None, None) => {
+                        return Err(CompletionError::ResponseError(
+                            "The recorded completion has neither a message nor a tool call"
+                                .to_string(),
+                        ))
+                    }
+                };
+                Ok(completion::CompletionResponse {
+                    choice,
+                    raw_response: Response::Replayed(completion),
+                })
+            }
+        }
+    }
+}
+
+/// Serves the completions of a recording in place of a model, each once, to the requests for
+/// the prompt they were recorded for.
+#[derive(Clone)]
+pub struct ReplayedModel {
+    recording: Arc<Recording>,
+    served: Arc<Mutex<Vec<bool>>>,
+}
+
+impl ReplayedModel {
+    pub fn new(recording: Arc<Recording>) -> Self {
+        let served = vec![false; recording.completions.len()];
+        Self {
+            recording,
+            served: Arc::new(Mutex::new(served)),
+        }
+    }
+
+    /// The first completion recorded for `prompt` that wasn't served yet.
+    fn serve(&self, prompt: &str) -> Result<RecordedCompletion, CompletionError> {
+        let mut served = self.served.lock().unwrap();
+        let (index, completion) = self
+            .recording
+            .completions
+            .iter()
+            .enumerate()
+            .find(|(index, completion)| !served[*index] && completion.prompt == prompt)
+            .ok_or_else(|| {
+                CompletionError::ProviderError(
+                    "The recording has no completion for this prompt; \
+                     replay with --replay-model to ask the model"
+                        .to_string(),
+                )
+            })?;
+        served[index] = true;
+        Ok(completion.clone())
+    }
+}
+
+fn wrap<R>(
+    response: completion::CompletionResponse<R>,
+    raw: impl FnOnce(R) -> Response,
+) -> completion::CompletionResponse<Response> {
+    completion::CompletionResponse {
+        choice: response.choice,
+        raw_response: raw(response.raw_response),
+    }
+}
+
+/// Implemented by raw responses, for the tool calls requested in them. rig only reports the
+/// first, while models may request several independent ones at once.
+pub trait ReportsToolCalls {
+    /// The name and arguments of every tool call requested, in order; calls whose arguments
+    /// aren't valid JSON are left out.
+    fn tool_calls(&self) -> Vec<(String, serde_json::Value)>;
+}
+
+impl ReportsToolCalls for openai::CompletionResponse {
+    fn tool_calls(&self) -> Vec<(String, serde_json::Value)> {
+        let Some(calls) = self
+            .choices
+            .first()
+            .and_then(|choice| choice.message.tool_calls.as_ref())
+        else {
+            return Vec::new();
+        };
+        calls
+            .iter()
+            .filter_map(|call| {
+                let args = serde_json::from_str(&call.function.arguments).ok()?;
+                Some((call.function.name.clone(), args))
+            })
+            .collect()
+    }
+}
+
+impl ReportsToolCalls for gemini::GenerateContentResponse {
+    fn tool_calls(&self) -> Vec<(String, serde_json::Value)> {
+        self.candidates
+            .first()
+            .and_then(|candidate| candidate.content.as_ref())
+            .map(|content| {
+                content
+                    .parts
+                    .iter()
+                    .filter_map(|part| part.function_call.as_ref())
+                    .map(|call| (call.name.clone(), call.args.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl ReportsToolCalls for Response {
+    fn tool_calls(&self) -> Vec<(String, serde_json::Value)> {
+        match self {
+            Self::OpenAi(response) => response.tool_calls(),
+            Self::Gemini(response) => response.tool_calls(),
+            Self::Replayed(completion) => completion.tool_calls.clone(),
+        }
+    }
+}
@@ -0,0 +1,494 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use rig::tool::{Tool, ToolDyn};
+
+use crate::access::Allowed;
+use crate::address_activity_tool::AddressActivityTool;
+use crate::address_tool::AddressTxsTool;
+use crate::balance_history_tool::BalanceHistoryTool;
+use crate::blob_cost_tool::BlobCostTool;
+use crate::blob_fees_tool::BlobFeesTool;
+use crate::block_limits_tool::BlockLimitsTool;
+use crate::block_rewards_tool::BlockRewardsTool;
+use crate::block_txs_tool::BlockTxsTool;
+use crate::blockspace_forecast_tool::BlockspaceForecastTool;
+use crate::capabilities_tool::CapabilitiesTool;
+use crate::celenium::CeleniumClient;
+use crate::celestia_search_tool::CelestiaSearchTool;
+use crate::chain_params_tool::ChainParamsTool;
+use crate::compare_blocks_tool::CompareBlocksTool;
+use crate::countdown_tool::CountdownTool;
+use crate::enums::Enums;
+use crate::estimate_time_tool::EstimateTimeTool;
+use crate::event_search_tool::EventSearchTool;
+use crate::fee_histogram_tool::FeeHistogramTool;
+use crate::fill_rate_trend_tool::FillRateTrendTool;
+use crate::gas_efficiency_tool::GasEfficiencyTool;
+use crate::gas_stats_tool::GasStatsTool;
+#[cfg(feature = "grpc")]
+use crate::grpc::GrpcClient;
+#[cfg(feature = "node-rpc")]
+use crate::mempool_tool::MempoolTool;
+use crate::namespace_blobs_tool::NamespaceBlobsTool;
+use crate::namespace_tool::NamespaceStatsTool;
+use crate::network::Network;
+#[cfg(feature = "node-rpc")]
+use crate::node::NodeClient;
+#[cfg(feature = "node-rpc")]
+use crate::node_retention_tool::NodeRetentionTool;
+#[cfg(feature = "node-rpc")]
+use crate::node_status_tool::NodeStatusTool;
+use crate::pending_rewards_tool::PendingRewardsTool;
+use crate::pending_unbondings_tool::PendingUnbondingsTool;
+use crate::price::PriceFeed;
+use crate::proposal_votes_tool::ProposalVotesTool;
+use crate::replay::{Replayed, Tape};
+use crate::rest::RestClient;
+use crate::rollup_activity_tool::RollupActivityTool;
+#[cfg(feature = "node-rpc")]
+use crate::sampling_tool::SamplingTool;
+use crate::search_tool::SearchTool;
+use crate::slashing_tool::SlashingTool;
+use crate::square_size_tool::SquareSizeTool;
+use crate::square_tool::SquareTool;
+use crate::staking_yield_tool::StakingYieldTool;
+#[cfg(feature = "sqlite-cache")]
+use crate::store::BlockStore;
+#[cfg(feature = "sqlite-cache")]
+use crate::store_query_tool::StoreQueryTool;
+#[cfg(feature = "market-data")]
+use crate::tia_price_tool::TiaPriceTool;
+use crate::tool_timeout::ToolTimeouts;
+use crate::top_accounts_tool::TopAccountsTool;
+use crate::top_namespaces_tool::TopNamespacesTool;
+use crate::tx_fee_tool::TxFeeTool;
+use crate::upgrades_tool::UpgradesTool;
+use crate::validator_rewards_tool::ValidatorRewardsTool;
+use crate::validator_tool::ValidatorTool;
+#[cfg(feature = "node-rpc")]
+use crate::verify_blob_tool::VerifyBlobTool;
+use crate::who_proposed_tool::WhoProposedTool;
+
+/// What a tool is for, which decides the routes it is offered on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToolKind {
+    /// Looks up specific on-chain data
+    Data,
+    /// Aggregates over many blocks
+    Analytics,
+    /// Describes the assistant itself, on every route
+    Meta,
+}
+
+impl ToolKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ToolKind::Data => "data",
+            ToolKind::Analytics => "analytics",
+            ToolKind::Meta => "meta",
+        }
+    }
+}
+
+/// The shared resources tools are built from.
+#[derive(Clone)]
+pub struct ToolContext {
+    /// The network tools query unless a call asks for another one.
+    pub network: Network,
+    /// The Celenium client of each network tools can be asked to query, shared by every tool.
+    pub clients: BTreeMap<Network, CeleniumClient>,
+    /// The Cosmos REST API of each network, for chain state Celenium doesn't index.
+    pub rest_urls: BTreeMap<Network, String>,
+    /// The celestia-app gRPC endpoints to read chain state from instead, if any are configured.
+    #[cfg(feature = "grpc")]
+    pub grpc: Option<Arc<GrpcClient>>,
+    /// The celestia-node to query, if one is configured.
+    #[cfg(feature = "node-rpc")]
+    pub node: Option<Arc<NodeClient>>,
+    /// Celenium's vocabularies, if they were fetched.
+    pub enums: Option<Arc<Enums>>,
+    /// The local index of fetched block stats, if one is configured.
+    #[cfg(feature = "sqlite-cache")]
+    pub store: Option<Arc<BlockStore>>,
+    /// The source of fiat prices and market data, unless external price calls are disabled.
+    pub price_feed: Option<Arc<PriceFeed>>,
+    /// Whether tools may only answer from cached and indexed data.
+    pub offline: bool,
+    /// The recording of the session, if it's being recorded or replayed; replayed tools answer
+    /// the calls it holds with their recorded output.
+    pub tape: Option<Tape>,
+    /// How long tool calls may take, and what they answer with when they run over.
+    pub timeouts: ToolTimeouts,
+    /// The tools the caller's role may use; the others are never built.
+    pub allowed: Allowed,
+}
+
+impl Default for ToolContext {
+    fn default() -> Self {
+        Self {
+            network: Network::default(),
+            clients: Network::ALL
+                .into_iter()
+                .map(|network| (network, public_client(network)))
+                .collect(),
+            rest_urls: Network::ALL
+                .into_iter()
+                .map(|network| (network, network.rest_url()))
+                .collect(),
+            #[cfg(feature = "grpc")]
+            grpc: None,
+            #[cfg(feature = "node-rpc")]
+            node: None,
+            enums: None,
+            #[cfg(feature = "sqlite-cache")]
+            store: None,
+            price_feed: None,
+            offline: false,
+            tape: None,
+            timeouts: ToolTimeouts::default(),
+            allowed: Allowed::All,
+        }
+    }
+}
+
+impl ToolContext {
+    /// Creates a block stats tool for the configured API, backed by the store if there is one.
+    /// The tools it queries the other networks with share its store and price feed.
+    pub fn block_tool(&self) -> CelestiaSearchTool {
+        let client = match self.clients.get(&self.network) {
+            Some(client) => client.clone(),
+            None => public_client(self.network),
+        };
+        let mut tool = self.equipped(CelestiaSearchTool::new(client));
+        for (&network, client) in &self.clients {
+            if network != self.network {
+                tool = tool.with_peer(
+                    network,
+                    self.equipped(CelestiaSearchTool::new(client.clone())),
+                );
+            }
+        }
+        tool
+    }
+
+    /// `tool` with the context's vocabularies, store and price feed, and offline if it is
+    fn equipped(&self, mut tool: CelestiaSearchTool) -> CelestiaSearchTool {
+        if let Some(enums) = &self.enums {
+            tool = tool.with_enums(enums.clone());
+        }
+        #[cfg(feature = "sqlite-cache")]
+        if let Some(store) = &self.store {
+            tool = tool.with_store(store.clone());
+        }
+        if let Some(feed) = &self.price_feed {
+            tool = tool.with_price_feed(feed.clone());
+        }
+        if self.offline {
+            tool = tool.offline();
+        }
+        tool
+    }
+
+    /// Creates a client of the configured REST APIs, and gRPC endpoints if there are any.
+    pub fn rest_client(&self) -> RestClient {
+        let mut client = RestClient::new(self.rest_urls.clone());
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc {
+            client = client.with_grpc(grpc.clone());
+        }
+        if self.offline {
+            client = client.offline();
+        }
+        client
+    }
+}
+
+/// A client of the public Celenium API of `network`, with the default options
+fn public_client(network: Network) -> CeleniumClient {
+    CeleniumClient::builder()
+        .network(network)
+        .build()
+        .expect("Celenium client with default options should build")
+}
+
+/// Builds a tool from the context, or returns `None` if the context lacks what it needs.
+type Factory = Box<dyn Fn(&ToolContext) -> Option<Box<dyn ToolDyn>> + Send + Sync>;
+
+struct Entry {
+    name: String,
+    kind: ToolKind,
+    factory: Factory,
+}
+
+/// Captures the errors that may occur while selecting tools.
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("Unknown tool `{0}` (available: {1})")]
+    UnknownTool(String, String),
+    #[error("The tool `{0}` is not allowed for this API key's role")]
+    Forbidden(String),
+}
+
+/// The tools the assistant can be given, by name.
+///
+/// Tools are registered as factories so they are only built for the agents that use them,
+/// and only once the shared [`ToolContext`] is known.
+#[derive(Default)]
+pub struct ToolRegistry {
+    entries: Vec<Entry>,
+}
+
+impl ToolRegistry {
+    /// A registry holding every tool that ships with the crate.
+    pub fn with_builtin_tools() -> Self {
+        let mut registry = Self::default();
+        registry
+            .register(CelestiaSearchTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(ctx.block_tool()))
+            })
+            .register(SearchTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(SearchTool::new(ctx.block_tool())))
+            });
+
+        #[cfg(feature = "sqlite-cache")]
+        registry.register(StoreQueryTool::NAME, ToolKind::Analytics, |ctx| {
+            let store = ctx.store.clone()?;
+            Some(Box::new(StoreQueryTool::new(store)))
+        });
+
+        registry
+            .register(FillRateTrendTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(FillRateTrendTool::new(ctx.block_tool())))
+            })
+            .register(BlockspaceForecastTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(BlockspaceForecastTool::new(ctx.block_tool())))
+            })
+            .register(GasStatsTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(GasStatsTool::new(ctx.block_tool())))
+            })
+            .register(GasEfficiencyTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(GasEfficiencyTool::new(ctx.block_tool())))
+            })
+            .register(BlobFeesTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(BlobFeesTool::new(ctx.block_tool())))
+            })
+            .register(BlobCostTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(BlobCostTool::new(ctx.block_tool())))
+            })
+            .register(FeeHistogramTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(FeeHistogramTool::new(ctx.block_tool())))
+            })
+            .register(SquareSizeTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(SquareSizeTool::new(ctx.block_tool())))
+            })
+            .register(BlockLimitsTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(BlockLimitsTool::new(ctx.block_tool())))
+            })
+            .register(CompareBlocksTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(CompareBlocksTool::new(ctx.block_tool())))
+            })
+            .register(BlockRewardsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(BlockRewardsTool::new(ctx.block_tool())))
+            })
+            .register(WhoProposedTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(WhoProposedTool::new(ctx.block_tool())))
+            })
+            .register(BlockTxsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(BlockTxsTool::new(ctx.block_tool())))
+            })
+            .register(EventSearchTool::NAME, ToolKind::Data, |ctx| {
+                let tool = EventSearchTool::new(ctx.block_tool());
+                // Only a consensus node has the `tx_search` index
+                #[cfg(feature = "node-rpc")]
+                let tool = match ctx.node.clone().filter(|node| node.has_consensus()) {
+                    Some(node) => tool.with_node(node),
+                    None => tool,
+                };
+                Some(Box::new(tool))
+            })
+            .register(ValidatorTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(ValidatorTool::new(ctx.block_tool())))
+            })
+            .register(SlashingTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(SlashingTool::new(ctx.block_tool())))
+            })
+            .register(ValidatorRewardsTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(ValidatorRewardsTool::new(ctx.block_tool())))
+            })
+            .register(StakingYieldTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(StakingYieldTool::new(ctx.block_tool())))
+            })
+            .register(AddressTxsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(AddressTxsTool::new(ctx.block_tool())))
+            })
+            .register(AddressActivityTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(AddressActivityTool::new(ctx.block_tool())))
+            })
+            .register(TxFeeTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(TxFeeTool::new(
+                    ctx.block_tool(),
+                    ctx.rest_client(),
+                )))
+            })
+            .register(BalanceHistoryTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(BalanceHistoryTool::new(
+                    ctx.block_tool(),
+                    ctx.rest_client(),
+                )))
+            })
+            .register(TopAccountsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(TopAccountsTool::new(ctx.block_tool())))
+            })
+            .register(NamespaceStatsTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(NamespaceStatsTool::new(ctx.block_tool())))
+            })
+            .register(TopNamespacesTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(TopNamespacesTool::new(ctx.block_tool())))
+            })
+            .register(RollupActivityTool::NAME, ToolKind::Analytics, |ctx| {
+                Some(Box::new(RollupActivityTool::new(ctx.block_tool())))
+            })
+            .register(NamespaceBlobsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(NamespaceBlobsTool::new(ctx.block_tool())))
+            })
+            .register(SquareTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(SquareTool::new(ctx.block_tool())))
+            })
+            .register(ProposalVotesTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(ProposalVotesTool::new(ctx.block_tool())))
+            })
+            .register(ChainParamsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(ChainParamsTool::new(ctx.block_tool())))
+            })
+            .register(UpgradesTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(UpgradesTool::new(ctx.block_tool())))
+            })
+            .register(EstimateTimeTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(EstimateTimeTool::new(ctx.block_tool())))
+            })
+            .register(CountdownTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(CountdownTool::new(ctx.block_tool())))
+            })
+            .register(PendingRewardsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(PendingRewardsTool::new(
+                    ctx.network,
+                    ctx.rest_client(),
+                )))
+            })
+            .register(PendingUnbondingsTool::NAME, ToolKind::Data, |ctx| {
+                Some(Box::new(PendingUnbondingsTool::new(
+                    ctx.network,
+                    ctx.rest_client(),
+                )))
+            });
+
+        #[cfg(feature = "node-rpc")]
+        registry
+            .register(VerifyBlobTool::NAME, ToolKind::Data, |ctx| {
+                let node = ctx.node.clone()?;
+                Some(Box::new(VerifyBlobTool::new(node)))
+            })
+            .register(SamplingTool::NAME, ToolKind::Data, |ctx| {
+                let node = ctx.node.clone()?;
+                Some(Box::new(SamplingTool::new(node, ctx.block_tool())))
+            })
+            .register(NodeStatusTool::NAME, ToolKind::Data, |ctx| {
+                let node = ctx.node.clone()?;
+                Some(Box::new(NodeStatusTool::new(node)))
+            })
+            .register(MempoolTool::NAME, ToolKind::Data, |ctx| {
+                let node = ctx.node.clone().filter(|node| node.has_consensus())?;
+                Some(Box::new(MempoolTool::new(node)))
+            })
+            .register(NodeRetentionTool::NAME, ToolKind::Data, |ctx| {
+                let node = ctx.node.clone().filter(|node| node.has_consensus())?;
+                Some(Box::new(NodeRetentionTool::new(node)))
+            });
+
+        #[cfg(feature = "market-data")]
+        registry.register(TiaPriceTool::NAME, ToolKind::Data, |ctx| {
+            let feed = ctx.price_feed.clone()?;
+            Some(Box::new(TiaPriceTool::new(feed)))
+        });
+
+        // Built by `build` instead, as it describes the tools built alongside it
+        registry.register(CapabilitiesTool::NAME, ToolKind::Meta, |_| None);
+
+        registry
+    }
+
+    /// Registers a tool, replacing any tool registered under the same name.
+    pub fn register<F>(&mut self, name: &str, kind: ToolKind, factory: F) -> &mut Self
+    where
+        F: Fn(&ToolContext) -> Option<Box<dyn ToolDyn>> + Send + Sync + 'static,
+    {
+        self.entries.retain(|entry| entry.name != name);
+        self.entries.push(Entry {
+            name: name.to_string(),
+            kind,
+            factory: Box::new(factory),
+        });
+        self
+    }
+
+    /// The names of the registered tools, in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    /// Builds the tools of the wanted kinds that the context allows, restricted to `enabled` if
+    /// it is given.
+    pub fn build(
+        &self,
+        ctx: &ToolContext,
+        enabled: Option<&[String]>,
+        wanted: impl Fn(ToolKind) -> bool,
+    ) -> Result<Vec<Box<dyn ToolDyn>>, RegistryError> {
+        if let Some(enabled) = enabled {
+            if let Some(unknown) = enabled
+                .iter()
+                .find(|name| !self.entries.iter().any(|entry| &entry.name == *name))
+            {
+                return Err(RegistryError::UnknownTool(
+                    unknown.clone(),
+                    self.names().join(", "),
+                ));
+            }
+            if let Some(forbidden) = enabled.iter().find(|name| !ctx.allowed.permits(name)) {
+                return Err(RegistryError::Forbidden(forbidden.clone()));
+            }
+        }
+
+        let selected: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| wanted(entry.kind))
+            .filter(|entry| ctx.allowed.permits(&entry.name))
+            .filter(|entry| enabled.is_none_or(|enabled| enabled.contains(&entry.name)))
+            .collect();
+        let mut tools: Vec<(ToolKind, Box<dyn ToolDyn>)> = selected
+            .iter()
+            .filter_map(|entry| Some((entry.kind, (entry.factory)(ctx)?)))
+            .collect();
+        if selected
+            .iter()
+            .any(|entry| entry.name == CapabilitiesTool::NAME)
+        {
+            tools = CapabilitiesTool::describing(tools, ctx);
+        }
+
+        let replaying = ctx.tape.as_ref().and_then(Tape::replaying);
+        Ok(tools
+            .into_iter()
+            .map(|(_, tool)| ctx.timeouts.apply(tool))
+            .map(|tool| match replaying {
+                Some(recording) => Box::new(Replayed::new(tool, recording.clone())),
+                None => tool,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use crate::network::Network;
+
+/// Where `/export` writes the transcript unless given a file.
+pub const DEFAULT_TRANSCRIPT: &str = "transcript.md";
+
+/// Lists the slash commands, for `/help`.
+pub const HELP: &str = "\
+/model [name]      Show or switch the model answering questions
+/network [name]    Show or switch the network (mainnet, mocha, arabica)
+/tools             List the tools available to the agent
+/clear             Forget the conversation so far
+/export [file]     Write the conversation so far to a Markdown report, or HTML for a .html
+                   file (transcript.md by default)
+/help              Show this help
+/quit              Leave the session";
+
+/// Captures the errors that may occur while parsing a slash command.
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum SlashCommandError {
+    #[error("Unknown command `/{0}`, see /help")]
+    UnknownCommand(String),
+    #[error("Unknown network `{0}` (available: mainnet, mocha, arabica)")]
+    UnknownNetwork(String),
+}
+
+/// A command to the REPL itself, as opposed to a question for the agent.
+#[derive(Debug, PartialEq)]
+pub enum SlashCommand {
+    /// Switches to the model, or shows the current one if `None`
+    Model(Option<String>),
+    /// Switches to the network, or shows the current one if `None`
+    Network(Option<Network>),
+    Tools,
+    Clear,
+    /// Writes the transcript to the file, or the default one if `None`
+    Export(Option<PathBuf>),
+    Help,
+    Quit,
+}
+
+impl SlashCommand {
+    /// Parses a line starting with `/`, returning `None` for anything else (to be sent to the
+    /// agent as a question).
+    pub fn parse(line: &str) -> Option<Result<Self, SlashCommandError>> {
+        let line = line.trim().strip_prefix('/')?;
+        let (name, arg) = match line.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, Some(arg.trim()).filter(|arg| !arg.is_empty())),
+            None => (line, None),
+        };
+
+        let command = match name.to_ascii_lowercase().as_str() {
+            "model" => Ok(SlashCommand::Model(arg.map(str::to_string))),
+            "network" => match arg {
+                Some(arg) => Network::parse(arg)
+                    .map(|network| SlashCommand::Network(Some(network)))
+                    .ok_or_else(|| SlashCommandError::UnknownNetwork(arg.to_string())),
+                None => Ok(SlashCommand::Network(None)),
+            },
+            "tools" => Ok(SlashCommand::Tools),
+            "clear" => Ok(SlashCommand::Clear),
+            "export" => Ok(SlashCommand::Export(arg.map(PathBuf::from))),
+            "help" => Ok(SlashCommand::Help),
+            "quit" | "exit" => Ok(SlashCommand::Quit),
+            _ => Err(SlashCommandError::UnknownCommand(name.to_string())),
+        };
+        Some(command)
+    }
+}
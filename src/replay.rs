@@ -0,0 +1,178 @@
+//! Recordings of a session's tool calls and indexer responses, to re-run the agent against the
+//! same data without querying anything and reproduce a bad answer (`--record` and `--replay`).
+//!
+//! Replayed calls the agent makes again with the same arguments return their recorded output,
+//! whichever tool they are. Other calls are answered offline, from the recorded responses of the
+//! indexer. The model's completions are recorded too and served in place of the model, so a
+//! replay asks no service at all; `--replay-model` asks the live model again instead, at
+//! temperature 0, to see how it now answers the same data.
+
+use std::collections::BTreeMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::future::Future;
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+#[cfg(not(target_arch = "wasm32"))]
+use rig::completion::ToolDefinition;
+#[cfg(not(target_arch = "wasm32"))]
+use rig::tool::{ToolDyn, ToolError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::assistant::Turn;
+
+/// A tool call of a recorded session, with what the tool returned.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    pub name: String,
+    pub args: Value,
+    /// The output as the tool returned it, JSON-encoded
+    pub output: String,
+}
+
+/// A completion of a recorded session, as the model chose it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCompletion {
+    /// The prompt the completion was requested for
+    pub prompt: String,
+    /// The model's message, if it didn't call a tool
+    #[serde(default)]
+    pub message: Option<String>,
+    /// The name and arguments of every tool call requested, in order
+    #[serde(default)]
+    pub tool_calls: Vec<(String, Value)>,
+}
+
+/// What a session's tools returned and the indexer responded, as a replay file holds it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Recording {
+    /// Every tool call made, in order
+    #[serde(default)]
+    pub calls: Vec<RecordedCall>,
+    /// The indexer's responses, by URL
+    #[serde(default)]
+    pub responses: BTreeMap<String, Value>,
+    /// Every completion of the model, in order
+    #[serde(default)]
+    pub completions: Vec<RecordedCompletion>,
+}
+
+/// Captures the errors that may occur while reading or writing a replay file.
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("Could not read the replay file {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("Could not write the replay file {path}: {reason}")]
+    Write { path: String, reason: String },
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let read_error = |reason: String| ReplayError::Read {
+            path: path.display().to_string(),
+            reason,
+        };
+        let text = std::fs::read_to_string(path).map_err(|e| read_error(e.to_string()))?;
+        serde_json::from_str(&text).map_err(|e| read_error(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let json = serde_json::to_string_pretty(self).expect("recordings should serialize");
+        std::fs::write(path, json).map_err(|e| ReplayError::Write {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Records the tool calls `turn` made; those only planned in dry-run mode have no output.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn record_turn(&mut self, turn: &Turn) {
+        for call in turn.steps.iter().chain(&turn.tool_call) {
+            if let Some(output) = &call.output {
+                self.calls.push(RecordedCall {
+                    name: call.name.clone(),
+                    args: call.args.clone(),
+                    output: output.clone(),
+                });
+            }
+        }
+    }
+
+    /// The output of the first recorded call of `name` with `args`.
+    pub fn output(&self, name: &str, args: &Value) -> Option<&str> {
+        self.calls
+            .iter()
+            .find(|call| call.name == name && &call.args == args)
+            .map(|call| call.output.as_str())
+    }
+
+    /// The recorded response to `url`.
+    pub fn response(&self, url: &str) -> Option<&Value> {
+        self.responses.get(url)
+    }
+}
+
+/// How a Celenium client takes part in a recording.
+#[derive(Clone, Debug)]
+pub enum Tape {
+    /// Every response the indexer sends is added to the recording
+    Record(Arc<Mutex<Recording>>),
+    /// Responses come from the recording instead of the indexer
+    Replay(Arc<Recording>),
+}
+
+impl Tape {
+    /// The recording being replayed, if it is.
+    pub fn replaying(&self) -> Option<&Arc<Recording>> {
+        match self {
+            Tape::Replay(recording) => Some(recording),
+            Tape::Record(_) => None,
+        }
+    }
+}
+
+/// A tool answering the calls of a recording with their recorded output, and making the others.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Replayed {
+    tool: Box<dyn ToolDyn>,
+    recording: Arc<Recording>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Replayed {
+    pub fn new(tool: Box<dyn ToolDyn>, recording: Arc<Recording>) -> Self {
+        Self { tool, recording }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ToolDyn for Replayed {
+    fn name(&self) -> String {
+        self.tool.name()
+    }
+
+    fn definition(
+        &self,
+        prompt: String,
+    ) -> Pin<Box<dyn Future<Output = ToolDefinition> + Send + Sync + '_>> {
+        self.tool.definition(prompt)
+    }
+
+    fn call(
+        &self,
+        args: String,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ToolError>> + Send + Sync + '_>> {
+        let recorded = serde_json::from_str(&args)
+            .ok()
+            .and_then(|parsed| self.recording.output(&self.tool.name(), &parsed))
+            .map(str::to_string);
+        match recorded {
+            Some(output) => Box::pin(async move { Ok(output) }),
+            None => self.tool.call(args),
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "grpc")]
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::amount::Utia;
+use crate::audit;
+use crate::celestia_search_tool::{CelestiaSearchError, REQUEST_TIMEOUT};
+use crate::de::string_or_number;
+#[cfg(feature = "grpc")]
+use crate::grpc::GrpcClient;
+use crate::network::Network;
+use crate::trace;
+
+/// The header asking a Cosmos REST API to answer as of a past height.
+const HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+/// Stake being unbonded from a validator, liquid once the unbonding completes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unbonding {
+    pub validator: String,
+    pub balance: Utia,
+    /// The height the unbonding started at
+    pub creation_height: u64,
+    /// When the stake becomes liquid, in RFC 3339
+    pub completion_time: String,
+}
+
+/// Stake moved between validators, which can't be moved again before the redelegation completes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Redelegation {
+    pub source: String,
+    pub destination: String,
+    pub balance: Utia,
+    pub creation_height: u64,
+    /// When the stake can be redelegated again, in RFC 3339
+    pub completion_time: String,
+}
+
+/// Who paid a transaction's fee, as its auth info records it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeePayment {
+    /// The account set to pay the fee, if not the first signer
+    pub payer: Option<String>,
+    /// The account whose fee grant paid the fee, if one did
+    pub granter: Option<String>,
+}
+
+/// A client of the Cosmos REST API of each network, for chain state Celenium doesn't index.
+///
+/// Balances and rewards are read over gRPC instead on networks a [`GrpcClient`] is given for.
+#[derive(Clone)]
+pub struct RestClient {
+    client: reqwest::Client,
+    base_urls: BTreeMap<Network, String>,
+    #[cfg(feature = "grpc")]
+    grpc: Option<Arc<GrpcClient>>,
+    offline: bool,
+}
+
+impl RestClient {
+    pub fn new(base_urls: BTreeMap<Network, String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_urls: base_urls
+                .into_iter()
+                .map(|(network, url)| (network, url.trim_end_matches('/').to_string()))
+                .collect(),
+            #[cfg(feature = "grpc")]
+            grpc: None,
+            offline: false,
+        }
+    }
+
+    /// Fails every query with [`CelestiaSearchError::NotCached`], as chain state isn't cached.
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /// Queries celestia-app over `grpc` on the networks it has an endpoint for.
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc(mut self, grpc: Arc<GrpcClient>) -> Self {
+        self.grpc = Some(grpc);
+        self
+    }
+
+    /// The gRPC client to query `network` with, if there is one
+    #[cfg(feature = "grpc")]
+    fn grpc(&self, network: Network) -> Option<&GrpcClient> {
+        self.grpc.as_deref().filter(|grpc| grpc.serves(network))
+    }
+
+    /// The spendable utia of `address` on `network`, as of `height` if one is given.
+    pub async fn balance(
+        &self,
+        network: Network,
+        address: &str,
+        height: Option<u64>,
+    ) -> Result<Utia, CelestiaSearchError> {
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = self.grpc(network).filter(|_| !self.offline) {
+            return grpc.balance(network, address, "utia", height).await;
+        }
+
+        let endpoint = format!(
+            "/cosmos/bank/v1beta1/balances/{}/by_denom?denom=utia",
+            address
+        );
+        let data = self.get_json(network, &endpoint, height).await?;
+        Ok(Utia::from_value(&data["balance"]["amount"]).unwrap_or_default())
+    }
+
+    /// The utia `delegator` can claim from each validator on `network`, fractions included.
+    pub async fn delegation_rewards(
+        &self,
+        network: Network,
+        delegator: &str,
+    ) -> Result<Vec<(String, Utia)>, CelestiaSearchError> {
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = self.grpc(network).filter(|_| !self.offline) {
+            return grpc.delegation_rewards(network, delegator).await;
+        }
+
+        let endpoint = format!(
+            "/cosmos/distribution/v1beta1/delegators/{}/rewards",
+            delegator
+        );
+        let data = self.get_json(network, &endpoint, None).await?;
+        Ok(data["rewards"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|reward| {
+                let validator = reward["validator_address"].as_str()?;
+                let amount = reward["reward"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|coin| coin["denom"] == "utia")
+                    .filter_map(|coin| Utia::from_value(&coin["amount"]))
+                    .sum();
+                Some((validator.to_string(), amount))
+            })
+            .collect())
+    }
+
+    /// The stake `delegator` is unbonding on `network`, one entry per unbonding started.
+    pub async fn unbonding_delegations(
+        &self,
+        network: Network,
+        delegator: &str,
+    ) -> Result<Vec<Unbonding>, CelestiaSearchError> {
+        let endpoint = format!(
+            "/cosmos/staking/v1beta1/delegators/{}/unbonding_delegations",
+            delegator
+        );
+        let data = self.get_json(network, &endpoint, None).await?;
+        Ok(data["unbonding_responses"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|unbonding| {
+                let validator = unbonding["validator_address"].as_str().unwrap_or_default();
+                unbonding["entries"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(move |entry| Unbonding {
+                        validator: validator.to_string(),
+                        balance: Utia::from_value(&entry["balance"]).unwrap_or_default(),
+                        creation_height: height(&entry["creation_height"]),
+                        completion_time: entry["completion_time"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                    })
+            })
+            .collect())
+    }
+
+    /// The stake `delegator` redelegated on `network` whose redelegations haven't completed.
+    pub async fn redelegations(
+        &self,
+        network: Network,
+        delegator: &str,
+    ) -> Result<Vec<Redelegation>, CelestiaSearchError> {
+        let endpoint = format!(
+            "/cosmos/staking/v1beta1/delegators/{}/redelegations",
+            delegator
+        );
+        let data = self.get_json(network, &endpoint, None).await?;
+        Ok(data["redelegation_responses"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|response| {
+                let redelegation = &response["redelegation"];
+                let source = redelegation["validator_src_address"]
+                    .as_str()
+                    .unwrap_or_default();
+                let destination = redelegation["validator_dst_address"]
+                    .as_str()
+                    .unwrap_or_default();
+                response["entries"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(move |entry| {
+                        let details = &entry["redelegation_entry"];
+                        Redelegation {
+                            source: source.to_string(),
+                            destination: destination.to_string(),
+                            balance: Utia::from_value(&entry["balance"]).unwrap_or_default(),
+                            creation_height: height(&details["creation_height"]),
+                            completion_time: details["completion_time"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                        }
+                    })
+            })
+            .collect())
+    }
+
+    /// The payer and granter of the fee of the transaction with `hash` on `network`.
+    pub async fn fee_payment(
+        &self,
+        network: Network,
+        hash: &str,
+    ) -> Result<FeePayment, CelestiaSearchError> {
+        let endpoint = format!("/cosmos/tx/v1beta1/txs/{}", hash);
+        let data = self.get_json(network, &endpoint, None).await?;
+        let fee = &data["tx"]["auth_info"]["fee"];
+        let address = |value: &Value| {
+            value
+                .as_str()
+                .filter(|address| !address.is_empty())
+                .map(str::to_string)
+        };
+
+        // The fee module emits the grant used, should the fee not name its granter
+        let granter = address(&fee["granter"]).or_else(|| {
+            data["tx_response"]["events"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|event| event["type"] == "use_feegrant")
+                .flat_map(|event| event["attributes"].as_array().into_iter().flatten())
+                .find(|attribute| attribute["key"] == "granter")
+                .and_then(|attribute| address(&attribute["value"]))
+        });
+        Ok(FeePayment {
+            payer: address(&fee["payer"]),
+            granter,
+        })
+    }
+
+    /// Fetches `endpoint` from the API of `network`, as of `height` if one is given.
+    ///
+    /// Past heights are only served by archive nodes; pruned ones answer with an error status.
+    pub async fn get_json(
+        &self,
+        network: Network,
+        endpoint: &str,
+        height: Option<u64>,
+    ) -> Result<Value, CelestiaSearchError> {
+        let base_url = self
+            .base_urls
+            .get(&network)
+            .ok_or(CelestiaSearchError::NetworkUnavailable(network))?;
+        let url = format!("{}{}", base_url, endpoint);
+        audit::upstream(&url);
+        if self.offline {
+            return Err(CelestiaSearchError::NotCached { url });
+        }
+
+        let span = trace::client_span("rest.request").with("url.full", url.clone());
+        trace::traced(span, self.request(url, height)).await
+    }
+
+    /// Sends a GET request to `url` and parses the JSON body, classifying failures
+    async fn request(
+        &self,
+        url: String,
+        height: Option<u64>,
+    ) -> Result<Value, CelestiaSearchError> {
+        let mut request = self.client.get(&url).timeout(REQUEST_TIMEOUT);
+        if let Some(height) = height {
+            request = request.header(HEIGHT_HEADER, height);
+        }
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                CelestiaSearchError::Timeout { url: url.clone() }
+            } else {
+                CelestiaSearchError::HttpRequestFailed(e.to_string())
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(CelestiaSearchError::Status {
+                status: status.as_u16(),
+                url,
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| CelestiaSearchError::Deserialization {
+                url,
+                reason: e.to_string(),
+            })
+    }
+}
+
+/// A height, which the REST API gives as a string
+fn height(value: &Value) -> u64 {
+    string_or_number(value).unwrap_or_default()
+}
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::byte_size;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::de::string_or_number;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The longest window, in days, a rollup's activity is reported over.
+pub const MAX_DAYS: u64 = 90;
+
+/// The rollup whose activity to report.
+#[derive(Deserialize)]
+pub struct RollupActivityArgs {
+    /// The rollup's name or Celenium slug.
+    rollup: String,
+    /// How many days to report, ending today.
+    #[serde(default = "default_days")]
+    days: u64,
+    /// The networks to report the rollup's activity on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_days() -> u64 {
+    7
+}
+
+/// Reports a rollup's daily blob count, bytes posted, fees paid and average blob size.
+///
+/// The rollup is looked up by its slug first, then with Celenium's search, so either its name
+/// or its slug will do.
+pub struct RollupActivityTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl RollupActivityTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn report(&self, args: RollupActivityArgs) -> Result<String, CelestiaSearchError> {
+        let rollup = args.rollup.trim();
+        let days = args.days.clamp(1, MAX_DAYS);
+        self.blocks
+            .across(&args.network, |blocks| report(blocks, rollup, days))
+            .await
+    }
+}
+
+/// A day of a rollup's activity
+#[derive(Default)]
+struct Day {
+    blobs: f64,
+    bytes: f64,
+    fees: f64,
+}
+
+async fn report(
+    blocks: &CelestiaSearchTool,
+    rollup: &str,
+    days: u64,
+) -> Result<String, CelestiaSearchError> {
+    let Some((id, name)) = find(blocks, rollup).await? else {
+        return Ok(format!("No rollup named `{}` was found.", rollup));
+    };
+
+    let to = Utc::now();
+    let from = to - Duration::days(days as i64);
+    let series = |name: &'static str| {
+        blocks.fetch(&format!(
+            "/rollup/{}/stats/{}/day?from={}&to={}",
+            id,
+            name,
+            from.timestamp(),
+            to.timestamp()
+        ))
+    };
+    let (blobs, bytes, fees) =
+        futures::try_join!(series("blobs_count"), series("size"), series("fee"))?;
+
+    // Each series holds the days with activity, keyed by their midnight
+    let mut daily: BTreeMap<String, Day> = BTreeMap::new();
+    let mut add = |points: &Value, field: fn(&mut Day) -> &mut f64| {
+        for point in points.as_array().into_iter().flatten() {
+            let Some(time) = point["time"].as_str() else {
+                continue;
+            };
+            let date = time.get(..10).unwrap_or(time).to_string();
+            *field(daily.entry(date).or_default()) += number(&point["value"]);
+        }
+    };
+    add(&blobs, |day| &mut day.blobs);
+    add(&bytes, |day| &mut day.bytes);
+    add(&fees, |day| &mut day.fees);
+    daily.retain(|_, day| day.blobs > 0.0);
+    if daily.is_empty() {
+        return Ok(format!(
+            "{} posted no blobs in the last {} day(s).",
+            name, days
+        ));
+    }
+
+    let mut output = format!("Daily activity of {} over the last {} day(s):", name, days);
+    let mut total = Day::default();
+    for (date, day) in &daily {
+        output.push_str(&format!("\n{}: {}", date, describe(day)));
+        total.blobs += day.blobs;
+        total.bytes += day.bytes;
+        total.fees += day.fees;
+    }
+    output.push_str(&format!(
+        "\nTotal over {} active day(s): {}",
+        daily.len(),
+        describe(&total)
+    ));
+    Ok(output)
+}
+
+/// The id and name of the rollup with the slug or name `rollup`, if there is one
+async fn find(
+    blocks: &CelestiaSearchTool,
+    rollup: &str,
+) -> Result<Option<(u64, String)>, CelestiaSearchError> {
+    let found = match blocks
+        .fetch(&format!("/rollup/slug/{}", slug(rollup)))
+        .await
+    {
+        Err(CelestiaSearchError::NotFound { .. }) => None,
+        found => Some(found?),
+    };
+    let found = match found {
+        Some(found) => Some(found),
+        None => {
+            let results = match blocks.fetch(&format!("/search?query={}", rollup)).await {
+                Err(CelestiaSearchError::NotFound { .. }) => Value::Null,
+                results => results?,
+            };
+            results
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|result| result["type"] == "rollup")
+                .map(|result| result["result"].clone())
+        }
+    };
+
+    Ok(found.and_then(|rollup| {
+        let id = rollup["id"].as_u64()?;
+        let name = rollup["name"].as_str().unwrap_or_default().to_string();
+        Some((id, name))
+    }))
+}
+
+/// The slug Celenium gives a rollup named `name`: lowercase, with dashes between words
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn describe(day: &Day) -> String {
+    format!(
+        "{:.0} blob(s), {} posted, {} in fees, {} per blob on average",
+        day.blobs,
+        byte_size::format(day.bytes as u64),
+        price::describe_utia(&format!("{:.0}", day.fees), None),
+        byte_size::format((day.bytes / day.blobs) as u64)
+    )
+}
+
+fn number(value: &Value) -> f64 {
+    string_or_number(value).unwrap_or_default()
+}
+
+impl Tool for RollupActivityTool {
+    const NAME: &'static str = "rollup_activity";
+
+    type Args = RollupActivityArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Report a rollup's daily blob count, bytes posted, fees paid and average blob \
+                 size over up to {} days ending today, with totals. Use it for a rollup's own \
+                 activity, such as a weekly summary of its chain.",
+                MAX_DAYS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "rollup": {
+                        "type": "string",
+                        "description": "The rollup's name or Celenium slug",
+                        "examples": ["Eclipse", "manta-pacific"],
+                    },
+                    "days": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_DAYS,
+                        "description": "How many days to report, ending today",
+                        "examples": [7, 30],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["rollup"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
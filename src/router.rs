@@ -0,0 +1,103 @@
+use rig::completion::{CompletionModel, PromptError};
+use rig::vector_store::VectorStoreIndex;
+
+use crate::accounting::ReportsUsage;
+use crate::assistant::{Assistant, Turn};
+use crate::provider::ReportsToolCalls;
+use crate::registry::ToolKind;
+
+/// The preamble of the router agent, which only classifies questions.
+pub const PREAMBLE: &str = "You route questions about the Celestia blockchain to the right \
+    specialist. Reply with exactly one word:\n\
+    - live: the question asks for specific chain or market data (a block, a fee, a height, the TIA price)\n\
+    - conceptual: the question asks how Celestia works or what something means\n\
+    - analytical: the question asks for aggregates, trends or comparisons over many blocks";
+
+/// The kinds of questions the router distinguishes between, each handled by a sub-agent with
+/// its own preamble and tool set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Route {
+    /// Lookups of specific on-chain data
+    Live,
+    /// Explanations of Celestia concepts
+    Conceptual,
+    /// Aggregates and trends over many blocks
+    Analytical,
+    /// Routing is disabled: a single agent gets every tool
+    General,
+}
+
+impl Route {
+    /// Parses the router's reply, ignoring case and surrounding punctuation.
+    pub fn parse(reply: &str) -> Option<Self> {
+        let word = reply
+            .trim()
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+
+        match word.as_str() {
+            "live" => Some(Self::Live),
+            "conceptual" => Some(Self::Conceptual),
+            "analytical" => Some(Self::Analytical),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Conceptual => "conceptual",
+            Self::Analytical => "analytical",
+            Self::General => "general",
+        }
+    }
+
+    /// Instructions for the sub-agent handling this route, appended to the main preamble.
+    pub fn instructions(self) -> &'static str {
+        match self {
+            Self::Live => {
+                "This question asks for specific on-chain data. Look it up with the \
+                tools."
+            }
+            Self::Conceptual => {
+                "This question asks how Celestia works. Explain it clearly and \
+                concisely."
+            }
+            Self::Analytical => {
+                "This question asks for aggregates or trends over blocks. \
+                Compute them with the tools, and state which blocks your numbers cover."
+            }
+            Self::General => "Use whichever tools fit the question.",
+        }
+    }
+
+    /// Whether the sub-agent is given tools of this kind.
+    pub fn uses(self, kind: ToolKind) -> bool {
+        match kind {
+            ToolKind::Data => self != Self::Conceptual,
+            ToolKind::Analytics => matches!(self, Self::Analytical | Self::General),
+            ToolKind::Meta => true,
+        }
+    }
+
+    /// Whether the sub-agent is given documentation context.
+    pub fn uses_docs(self) -> bool {
+        matches!(self, Self::Conceptual | Self::General)
+    }
+}
+
+/// Classifies the prompt with the router agent, falling back to live data if its reply is
+/// not one of the known routes.
+pub async fn classify<M, I>(
+    router: &Assistant<M, I>,
+    prompt: &str,
+) -> Result<(Route, Turn), PromptError>
+where
+    M: CompletionModel,
+    M::Response: ReportsUsage + ReportsToolCalls,
+    I: VectorStoreIndex,
+{
+    let turn = router.prompt(prompt).await?;
+    let route = Route::parse(&turn.output).unwrap_or(Route::Live);
+    Ok((route, turn))
+}
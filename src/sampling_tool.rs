@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::CelestiaSearchTool;
+use crate::metrics::metrics;
+use crate::node::{NodeClient, NodeError};
+
+/// A node sampling within this many blocks of the head is considered caught up.
+const CAUGHT_UP_BLOCKS: u64 = 10;
+
+/// The sampling status takes no arguments: the node is given by the config.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SamplingArgs {}
+
+/// Reports whether the connected node is sampling and keeping up with the chain.
+pub struct SamplingTool {
+    node: Arc<NodeClient>,
+    blocks: CelestiaSearchTool,
+}
+
+impl SamplingTool {
+    /// Creates a tool checking `node`, and comparing its head against the indexer's.
+    pub fn new(node: Arc<NodeClient>, blocks: CelestiaSearchTool) -> Self {
+        Self { node, blocks }
+    }
+
+    async fn status(&self) -> Result<String, NodeError> {
+        let (stats, local_head) = futures::try_join!(
+            self.node.call::<Value>("das.SamplingStats", json!([])),
+            self.node.call::<Value>("header.LocalHead", json!([])),
+        )?;
+
+        let sampled = height(&stats["head_of_sampled_chain"]).unwrap_or(0);
+        let network_head = height(&stats["network_head_height"]).unwrap_or(0);
+        let local_head = height(&local_head["header"]["height"]).unwrap_or(0);
+        let running = stats["is_running"].as_bool().unwrap_or(false);
+        let catch_up_done = stats["catch_up_done"].as_bool().unwrap_or(false);
+        let behind = network_head.saturating_sub(sampled);
+
+        let mut output = format!(
+            "The node {} sampling. It has sampled up to height {} of the {} it sees as the \
+             network head ({} block(s) behind), and synced headers up to {}.",
+            if running { "is" } else { "is NOT" },
+            sampled,
+            network_head,
+            behind,
+            local_head
+        );
+        if catch_up_done {
+            output.push_str(" It has finished catching up on past heights.");
+        } else {
+            output.push_str(&format!(
+                " It is still catching up on past heights (done up to {}).",
+                height(&stats["head_of_catchup"]).unwrap_or(0)
+            ));
+        }
+
+        // The indexer gives an independent view of the head, to catch a node stuck on a fork
+        // or cut off from its peers
+        let mut healthy = running && catch_up_done && behind <= CAUGHT_UP_BLOCKS;
+        match self.blocks.chain_head().await {
+            Ok(indexed) => {
+                let lag = indexed.saturating_sub(network_head);
+                healthy &= lag <= CAUGHT_UP_BLOCKS;
+                output.push_str(&format!(
+                    " The indexer's head is {}, so the node's view of the network is {} block(s) \
+                     behind it.",
+                    indexed, lag
+                ));
+            }
+            Err(e) => output.push_str(&format!(" The indexer's head is unknown ({}).", e)),
+        }
+        output.push_str(if healthy {
+            " The node looks healthy and caught up."
+        } else {
+            " The node is NOT caught up."
+        });
+
+        Ok(output)
+    }
+}
+
+/// Heights come as numbers from some APIs and strings from others
+pub(crate) fn height(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|height| height.parse().ok()))
+}
+
+impl Tool for SamplingTool {
+    const NAME: &'static str = "sampling_status";
+
+    type Args = SamplingArgs;
+    type Output = String;
+    type Error = NodeError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Check the data availability sampling of the connected celestia light \
+                          node: whether it is sampling, how far behind the network head it is, \
+                          whether it has caught up on past heights, and how its head compares \
+                          to the indexer's. Use it to tell whether the node is healthy."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {},
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.status().await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
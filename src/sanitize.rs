@@ -0,0 +1,114 @@
+//! Defuses text from the indexer before it reaches the model.
+//!
+//! Anyone can write the strings the indexer returns (validator monikers, memos, blob previews),
+//! so they are data, never instructions. Each one has its control and invisible formatting
+//! characters stripped, its line breaks and chat-template markers escaped so it cannot pass
+//! for a message of its own, and its length bounded. Strings that read like instructions to
+//! the model are flagged, marking them as untrusted in the tool's output.
+
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+use crate::metrics::metrics;
+
+/// The longest string passed on, in characters; longer ones are cut short.
+pub const MAX_CHARS: usize = 512;
+
+/// Prefixes strings that look like attempts to instruct the model.
+pub const FLAG: &str = "[flagged as a possible prompt injection]";
+
+/// Phrases of instructions aimed at the model rather than data, in lowercase
+const INJECTION_PATTERNS: [&str; 16] = [
+    "ignore previous",
+    "ignore all previous",
+    "ignore the above",
+    "ignore your instructions",
+    "disregard previous",
+    "disregard the above",
+    "disregard your instructions",
+    "forget your instructions",
+    "new instructions",
+    "system prompt",
+    "you are now",
+    "do not tell the user",
+    "system:",
+    "assistant:",
+    "[inst]",
+    "### instruction",
+];
+
+/// Markers of chat templates, which are escaped so they read as plain text
+const TEMPLATE_MARKERS: [(&str, &str); 3] = [("<|", "< |"), ("|>", "| >"), ("```", "'''")];
+
+/// Sanitizes every string in `value`, as returned by the indexer.
+pub fn value(value: &mut Value) {
+    match value {
+        Value::String(s) => {
+            if let Cow::Owned(sanitized) = text(s) {
+                *s = sanitized;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(self::value),
+        Value::Object(object) => object.values_mut().for_each(self::value),
+        _ => {}
+    }
+}
+
+/// `s` with invisible characters stripped, line breaks and template markers escaped, cut to
+/// [`MAX_CHARS`], and flagged if it reads like instructions.
+pub fn text(s: &str) -> Cow<'_, str> {
+    let clean = s.chars().count() <= MAX_CHARS
+        && !s.chars().any(|c| c.is_control() || is_invisible(c))
+        && !TEMPLATE_MARKERS
+            .iter()
+            .any(|(marker, _)| s.contains(marker));
+    if clean && !is_suspicious(s) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut sanitized: String = s
+        .chars()
+        .filter(|c| !is_invisible(*c))
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    for (marker, escaped) in TEMPLATE_MARKERS {
+        sanitized = sanitized.replace(marker, escaped);
+    }
+    if sanitized.chars().count() > MAX_CHARS {
+        sanitized = sanitized.chars().take(MAX_CHARS).collect();
+        sanitized.push('…');
+    }
+
+    // Check what's left, so that invisible characters can't hide a phrase
+    let outcome = match is_suspicious(&sanitized) {
+        true => {
+            sanitized = format!("{} {}", FLAG, sanitized);
+            "flagged"
+        }
+        false => "escaped",
+    };
+    metrics().sanitized_strings.inc(&[outcome]);
+    Cow::Owned(sanitized)
+}
+
+/// Whether `s` reads like instructions to the model.
+pub fn is_suspicious(s: &str) -> bool {
+    let words: Vec<String> = s.split_whitespace().map(str::to_lowercase).collect();
+    let normalized = words.join(" ");
+    INJECTION_PATTERNS
+        .iter()
+        .any(|pattern| normalized.contains(pattern))
+}
+
+/// Zero-width and bidirectional formatting characters, which can hide or reorder text
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+/// A natural-language query run on a schedule, as configured in the `[[schedules]]` tables of
+/// the config file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// Identifies the query in reports.
+    pub name: String,
+    /// When to run, as a cron expression in UTC (e.g., `0 8 * * *` for every day at 08:00).
+    pub cron: String,
+    /// The question to ask the agent.
+    pub prompt: String,
+    /// Append each answer to this file.
+    pub output: Option<PathBuf>,
+    /// Send each answer through the configured notifiers.
+    #[serde(default = "default_notify")]
+    pub notify: bool,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+/// Captures the errors that may occur while parsing a schedule.
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Invalid cron expression `{0}`: {1}")]
+    InvalidCron(String, cron::error::Error),
+}
+
+/// A schedule whose cron expression has been parsed.
+pub struct Job {
+    pub config: ScheduleConfig,
+    schedule: cron::Schedule,
+}
+
+impl Job {
+    /// Parses the cron expression of `config`, which may omit the leading seconds field.
+    pub fn new(config: ScheduleConfig) -> Result<Self, ScheduleError> {
+        let expression = match config.cron.split_whitespace().count() {
+            5 => format!("0 {}", config.cron),
+            _ => config.cron.clone(),
+        };
+        let schedule = cron::Schedule::from_str(&expression)
+            .map_err(|e| ScheduleError::InvalidCron(config.cron.clone(), e))?;
+
+        Ok(Self { config, schedule })
+    }
+
+    /// The first time the job is due strictly after `after`.
+    pub fn next_run(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule.after(after).next()
+    }
+}
+
+/// Returns the job due first after `after`, and when it's due.
+pub fn next_due<'a>(jobs: &'a [Job], after: &DateTime<Utc>) -> Option<(DateTime<Utc>, &'a Job)> {
+    jobs.iter()
+        .filter_map(|job| job.next_run(after).map(|at| (at, job)))
+        .min_by_key(|(at, _)| *at)
+}
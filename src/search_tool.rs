@@ -0,0 +1,183 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::byte_size;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::{address_tool, namespace_blobs_tool, price, validator_tool};
+
+/// How many transactions or blobs are listed for a matched address or namespace.
+const LISTED: u64 = 5;
+
+/// How many recent blocks a matched validator's uptime is checked over.
+const UPTIME_BLOCKS: u64 = 100;
+
+/// The identifier to resolve.
+#[derive(Deserialize)]
+pub struct SearchArgs {
+    /// A block height, transaction hash, address, validator, namespace or rollup name.
+    query: String,
+    /// The networks to search, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Resolves an arbitrary identifier and describes what it refers to.
+///
+/// The description comes from the same queries as the matching detail tool, so a pasted
+/// identifier is answered in a single call.
+pub struct SearchTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl SearchTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn resolve(&self, args: SearchArgs) -> Result<String, CelestiaSearchError> {
+        let query = args.query.trim();
+        self.blocks
+            .across(&args.network, |blocks| search(blocks, query))
+            .await
+    }
+}
+
+/// Looks `query` up with Celenium's search, then describes the best match
+async fn search(blocks: &CelestiaSearchTool, query: &str) -> Result<String, CelestiaSearchError> {
+    if let Ok(height) = query.parse() {
+        return describe_block(blocks, height).await;
+    }
+
+    let results = match blocks.fetch(&format!("/search?query={}", query)).await {
+        Err(CelestiaSearchError::NotFound { .. }) => Value::Null,
+        results => results?,
+    };
+    let Some(found) = results.as_array().and_then(|results| results.first()) else {
+        return Ok(format!("Nothing matches `{}`.", query));
+    };
+    let result = &found["result"];
+    let kind = found["type"].as_str().unwrap_or("unknown");
+
+    let detail = match kind {
+        "block" => match result["height"].as_u64() {
+            Some(height) => describe_block(blocks, height).await?,
+            None => result.to_string(),
+        },
+        "tx" => describe_tx(blocks, result["hash"].as_str().unwrap_or(query)).await?,
+        "address" => {
+            let address = result["hash"].as_str().unwrap_or(query);
+            address_tool::list_txs(blocks, address, LISTED, 0, None).await?
+        }
+        "validator" => {
+            let id = result["id"].to_string();
+            validator_tool::report(blocks, &id, UPTIME_BLOCKS).await?
+        }
+        "namespace" => {
+            let namespace = format!(
+                "{:02x}{}",
+                result["version"].as_u64().unwrap_or(0),
+                result["namespace_id"].as_str().unwrap_or_default()
+            );
+            namespace_blobs_tool::list_blobs(blocks, &namespace, LISTED, 0).await?
+        }
+        "rollup" => format!(
+            "Rollup {}: {}",
+            result["name"].as_str().unwrap_or(query),
+            result["description"].as_str().unwrap_or("no description")
+        ),
+        _ => result.to_string(),
+    };
+
+    Ok(format!("`{}` is a {}.\n{}", query, kind, detail))
+}
+
+/// The main stats of the block at `height`
+async fn describe_block(
+    blocks: &CelestiaSearchTool,
+    height: u64,
+) -> Result<String, CelestiaSearchError> {
+    let stats = blocks.fetch_stats(height).await?;
+    Ok(format!(
+        "Block {}: {} transaction(s), {} blob(s) totalling {}, fill rate {:.2}%, fees {}.",
+        height,
+        stats.tx_count,
+        stats.blobs_count,
+        byte_size::format(stats.blobs_size),
+        stats.fill_rate * 100.0,
+        price::describe(stats.fee, None)
+    ))
+}
+
+/// The height, status, fee and messages of the transaction with `hash`
+async fn describe_tx(
+    blocks: &CelestiaSearchTool,
+    hash: &str,
+) -> Result<String, CelestiaSearchError> {
+    let tx = blocks.fetch(&format!("/tx/{}", hash)).await?;
+    let kinds: Vec<&str> = tx["message_types"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    Ok(format!(
+        "Transaction {} at height {} ({}): {}, {}, fee {}, {} of {} gas used.",
+        hash,
+        tx["height"],
+        tx["time"].as_str().unwrap_or("unknown time"),
+        kinds.join(" + "),
+        tx["status"].as_str().unwrap_or("unknown status"),
+        price::describe_utia(tx["fee"].as_str().unwrap_or("0"), None),
+        tx["gas_used"],
+        tx["gas_wanted"]
+    ))
+}
+
+impl Tool for SearchTool {
+    const NAME: &'static str = "search_anything";
+
+    type Args = SearchArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Resolve any Celestia identifier the user pastes (a block height, \
+                          transaction hash, address, validator address, namespace or rollup \
+                          name) and describe what it refers to. Use it when it's unclear what \
+                          an identifier is."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "The identifier, as given",
+                        "examples": [
+                            "2000000",
+                            "celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p",
+                            "652452A670018D629CC116E510BA88C1CABE061336661B1F3D206D248BD558AF",
+                        ],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["query"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.resolve(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
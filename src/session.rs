@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use rig::completion::Message;
+use serde::{Deserialize, Serialize};
+
+/// The most recent messages of a session sent along with a new prompt. Older ones are kept on
+/// disk but not resent, to bound the size of each request.
+pub const MAX_HISTORY: usize = 20;
+
+/// Captures the errors that may occur while managing sessions.
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("Failed to access the session: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Session file is corrupt: {0}")]
+    Corrupt(#[from] serde_json::Error),
+    #[error("Invalid session name `{0}` (use letters, digits, `-` and `_`)")]
+    InvalidName(String),
+    #[error("No session named `{0}`")]
+    NotFound(String),
+}
+
+/// A named conversation, persisted so that follow-up questions can refer to earlier answers.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Session {
+    pub messages: Vec<Message>,
+}
+
+impl Session {
+    /// Records a question and its answer.
+    pub fn push_turn(&mut self, prompt: &str, answer: &str) {
+        self.messages.push(Message {
+            role: "user".to_string(),
+            content: prompt.to_string(),
+        });
+        self.messages.push(Message {
+            role: "assistant".to_string(),
+            content: answer.to_string(),
+        });
+    }
+
+    /// The messages to send as chat history, at most [`MAX_HISTORY`] of the latest.
+    pub fn history(&self) -> &[Message] {
+        &self.messages[self.messages.len().saturating_sub(MAX_HISTORY)..]
+    }
+
+    /// The number of questions asked in the session.
+    pub fn turns(&self) -> usize {
+        self.messages.iter().filter(|m| m.role == "user").count()
+    }
+}
+
+/// A summary of a stored session, for listing.
+#[derive(Debug)]
+pub struct SessionInfo {
+    pub name: String,
+    pub turns: usize,
+    pub updated: SystemTime,
+}
+
+/// A directory of sessions, one JSON file per session.
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$HOME/.celestia-search-assistant/sessions`, or a directory under the current one if
+    /// there's no home directory.
+    pub fn default_dir() -> PathBuf {
+        std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".celestia-search-assistant")
+            .join("sessions")
+    }
+
+    /// Loads the session, or an empty one if it doesn't exist yet.
+    pub fn load(&self, name: &str) -> Result<Session, SessionError> {
+        match std::fs::read_to_string(self.path(name)?) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Session::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, name: &str, session: &Session) -> Result<(), SessionError> {
+        let path = self.path(name)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(path, serde_json::to_string_pretty(session)?)?;
+        Ok(())
+    }
+
+    /// Lists the stored sessions, most recently updated first.
+    pub fn list(&self) -> Result<Vec<SessionInfo>, SessionError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut sessions = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let session: Session = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            sessions.push(SessionInfo {
+                name: name.to_string(),
+                turns: session.turns(),
+                updated: std::fs::metadata(&path)?.modified()?,
+            });
+        }
+        sessions.sort_by_key(|info| std::cmp::Reverse(info.updated));
+
+        Ok(sessions)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), SessionError> {
+        match std::fs::remove_file(self.path(name)?) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(SessionError::NotFound(name.to_string()))
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// The file of the session, rejecting names that could escape the directory
+    fn path(&self, name: &str) -> Result<PathBuf, SessionError> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(SessionError::InvalidName(name.to_string()));
+        }
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
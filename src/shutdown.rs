@@ -0,0 +1,98 @@
+//! Ctrl-C handling. The first Ctrl-C asks the running command to stop: commands with state to
+//! save, such as chat, stop waiting on their questions and save it before returning, and any
+//! other command is cancelled outright, dropping its requests in flight. A second Ctrl-C
+//! cancels whatever is left.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use tokio_util::sync::CancellationToken;
+
+/// The exit status of a command stopped by Ctrl-C, as shells report for SIGINT.
+pub const EXIT_INTERRUPTED: u8 = 130;
+
+static SHUTDOWN: OnceLock<Shutdown> = OnceLock::new();
+
+#[derive(Default)]
+struct Shutdown {
+    /// Cancelled on the first Ctrl-C.
+    requested: CancellationToken,
+    /// Cancelled once the running command should be dropped.
+    forced: CancellationToken,
+    /// How many [`Graceful`] guards are held.
+    graceful: AtomicUsize,
+}
+
+fn shutdown() -> &'static Shutdown {
+    SHUTDOWN.get_or_init(Shutdown::default)
+}
+
+/// The error of a command stopped by Ctrl-C.
+#[derive(Debug, thiserror::Error)]
+#[error("Interrupted")]
+pub struct Interrupted;
+
+/// Listens for Ctrl-C in the background, for the rest of the process.
+pub fn install() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            request();
+        }
+    });
+}
+
+/// Does what a Ctrl-C does: asks the running command to stop the first time, and forces it
+/// to when it's already been asked or holds no [`Graceful`] guard.
+pub fn request() {
+    let shutdown = shutdown();
+    if shutdown.requested.is_cancelled() || shutdown.graceful.load(Ordering::SeqCst) == 0 {
+        shutdown.forced.cancel();
+    }
+    shutdown.requested.cancel();
+}
+
+/// Whether Ctrl-C has been pressed.
+pub fn is_requested() -> bool {
+    shutdown().requested.is_cancelled()
+}
+
+/// Completes once Ctrl-C has been pressed.
+pub async fn requested() {
+    shutdown().requested.cancelled().await
+}
+
+/// Completes once the running command should be dropped without waiting for it to stop.
+pub async fn forced() {
+    shutdown().forced.cancelled().await
+}
+
+/// Runs `future` unless Ctrl-C is pressed first, failing with [`Interrupted`] if it is.
+pub async fn interruptible<F: std::future::Future>(future: F) -> Result<F::Output, Interrupted> {
+    tokio::select! {
+        output = future => Ok(output),
+        _ = requested() => Err(Interrupted),
+    }
+}
+
+/// Tells the first Ctrl-C that the running command stops by itself once [`requested`], so it
+/// isn't dropped before saving its state; held until dropped.
+pub struct Graceful(());
+
+impl Graceful {
+    pub fn new() -> Self {
+        shutdown().graceful.fetch_add(1, Ordering::SeqCst);
+        Graceful(())
+    }
+}
+
+impl Default for Graceful {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Graceful {
+    fn drop(&mut self) {
+        shutdown().graceful.fetch_sub(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,161 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+use crate::validator_tool;
+
+/// The most events listed by a single call.
+pub const MAX_EVENTS: u64 = 100;
+
+/// The slashing events to list.
+#[derive(Deserialize)]
+pub struct SlashingArgs {
+    /// Only list the events of this validator (by moniker, Celenium id or operator address).
+    validator: Option<String>,
+    /// How many of the most recent events to list.
+    #[serde(default = "default_limit")]
+    limit: u64,
+    /// The networks to list events on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_limit() -> u64 {
+    10
+}
+
+/// Lists recent jailing and slashing events, chain-wide or for one validator.
+pub struct SlashingTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl SlashingTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn list(&self, args: SlashingArgs) -> Result<String, CelestiaSearchError> {
+        let limit = args.limit.clamp(1, MAX_EVENTS);
+        self.blocks
+            .across(&args.network, |blocks| {
+                list(blocks, args.validator.as_deref(), limit)
+            })
+            .await
+    }
+}
+
+/// Describes the latest `limit` events, of `validator` only if given
+async fn list(
+    blocks: &CelestiaSearchTool,
+    validator: Option<&str>,
+    limit: u64,
+) -> Result<String, CelestiaSearchError> {
+    let (events, subject) = match validator {
+        Some(validator) => {
+            let id = validator_tool::resolve(blocks, validator).await?;
+            let endpoint = format!("/validator/{}/jails?limit={}", id, limit);
+            (
+                blocks.fetch(&endpoint).await?,
+                format!("validator {}", validator),
+            )
+        }
+        None => {
+            let endpoint = format!("/jails?limit={}", limit);
+            (blocks.fetch(&endpoint).await?, "the chain".to_string())
+        }
+    };
+
+    let events: Vec<String> = events
+        .as_array()
+        .into_iter()
+        .flatten()
+        .take(limit as usize)
+        .map(describe)
+        .collect();
+    if events.is_empty() {
+        return Ok(format!("No slashing events for {}.", subject));
+    }
+
+    Ok(format!(
+        "The {} most recent slashing event(s) for {}:\n{}",
+        events.len(),
+        subject,
+        events.join("\n")
+    ))
+}
+
+/// One line per event: when, who, why and how much was burned
+fn describe(event: &Value) -> String {
+    let mut line = format!(
+        "- height {} ({})",
+        event["height"],
+        event["time"].as_str().unwrap_or("unknown time")
+    );
+    if let Some(moniker) = event["validator"]["moniker"].as_str() {
+        line.push_str(&format!(": {}", moniker));
+    }
+    line.push_str(&format!(
+        " jailed for {}",
+        event["reason"].as_str().unwrap_or("an unknown reason")
+    ));
+    match Utia::from_value(&event["burned"]) {
+        Some(burned) if burned > Utia::ZERO => {
+            line.push_str(&format!(", slashed {}", price::describe(burned, None)));
+        }
+        _ => line.push_str(", nothing slashed"),
+    }
+    line
+}
+
+impl Tool for SlashingTool {
+    const NAME: &'static str = "slashing_events";
+
+    type Args = SlashingArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "List recent validator jailing and slashing events across the chain, \
+                or for a single validator, with their heights, reasons (e.g. downtime or double \
+                signing) and the amounts slashed."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "validator": {
+                        "type": "string",
+                        "description": "Only list this validator's events, by name (moniker), \
+                            operator address or Celenium id",
+                        "examples": ["P2P.ORG", "celestiavaloper1q3v5cugc8cdpud87u4zwy0a74uxkk6u4q4gx4p"],
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_EVENTS,
+                        "description": "How many of the most recent events to list",
+                        "examples": [10],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.list(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
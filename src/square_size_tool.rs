@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::analytics::correlation;
+use crate::block_limits_tool::Ceilings;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The maximum number of blocks in a single range.
+pub const MAX_RANGE: u64 = 500;
+
+/// The block range to bucket square sizes over.
+#[derive(Deserialize)]
+pub struct SquareSizeArgs {
+    /// The first height of the range.
+    from: u64,
+    /// The last height of the range (inclusive).
+    to: u64,
+    /// The networks to analyze, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Computes how often each square size occurs over a block range, and how it relates to fees.
+pub struct SquareSizeTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl SquareSizeTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn analyze(&self, args: SquareSizeArgs) -> Result<String, CelestiaSearchError> {
+        if args.from == 0 || args.from > args.to {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {}",
+                args.from, args.to
+            )));
+        }
+        if args.to - args.from >= MAX_RANGE {
+            return Err(CelestiaSearchError::InvalidRange(format!(
+                "{} to {} spans more than {} blocks",
+                args.from, args.to, MAX_RANGE
+            )));
+        }
+
+        self.blocks
+            .across(&args.network, |blocks| {
+                distribution(blocks, args.from, args.to)
+            })
+            .await
+    }
+}
+
+/// Buckets blocks `from..=to` by square size, with the average fee paid in each bucket
+async fn distribution(
+    blocks: &CelestiaSearchTool,
+    from: u64,
+    to: u64,
+) -> Result<String, CelestiaSearchError> {
+    let (range, ceilings) = futures::join!(
+        fetcher::fetch_range_partial(blocks, from..=to, DEFAULT_CONCURRENCY),
+        Ceilings::fetch(blocks),
+    );
+    let range = range?;
+    let rows = &range.rows;
+    let sizes: Vec<f64> = rows.iter().map(|(_, s)| s.square_size as f64).collect();
+    let fees: Vec<f64> = rows.iter().map(|(_, s)| s.fee.as_f64()).collect();
+
+    // Square sizes are powers of two, so each size is its own bucket
+    let mut buckets: BTreeMap<u64, (usize, f64)> = BTreeMap::new();
+    for (size, fee) in sizes.iter().zip(&fees) {
+        let bucket = buckets.entry(*size as u64).or_default();
+        bucket.0 += 1;
+        bucket.1 += fee;
+    }
+
+    let mut output = format!(
+        "Square sizes over blocks {} to {} ({} blocks):",
+        from,
+        to,
+        rows.len()
+    );
+    for (size, (count, fees)) in &buckets {
+        output.push_str(&format!(
+            "\n- {}x{}: {} block(s) ({:.2}%), average fee {}",
+            size,
+            size,
+            count,
+            *count as f64 / rows.len() as f64 * 100.0,
+            price::describe_utia(&format!("{:.0}", fees / *count as f64), None)
+        ));
+    }
+    // How much of the widest square allowed at each block's height it used
+    let used: Vec<f64> = rows
+        .iter()
+        .filter_map(|(height, stats)| {
+            let era = ceilings.at(*height)?;
+            Some((stats.square_size as f64 / era.max_square_size as f64).powi(2))
+        })
+        .collect();
+    if !used.is_empty() {
+        output.push_str(&format!(
+            "\nBlocks used {:.2}% of the shares of the widest square allowed at their height on \
+             average",
+            used.iter().sum::<f64>() / used.len() as f64 * 100.0
+        ));
+        output.push_str(&match used.len() < rows.len() {
+            true => format!(
+                " ({} of {} blocks; no limit is recorded for the others).",
+                used.len(),
+                rows.len()
+            ),
+            false => ".".to_string(),
+        });
+    }
+    match correlation(&sizes, &fees) {
+        Some(r) => output.push_str(&format!(
+            "\nCorrelation between square size and fees: {:.2}.",
+            r
+        )),
+        None => output
+            .push_str("\nSquare size and fees don't vary enough over the range to correlate them."),
+    }
+
+    Ok(range.annotate(output))
+}
+
+impl Tool for SquareSizeTool {
+    const NAME: &'static str = "square_size_distribution";
+
+    type Args = SquareSizeArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Count how often each data square size occurs over a range of Celestia blocks, \
+                 with the average fee paid at each size and the correlation between square \
+                 size and fees, e.g. to see how often the chain reaches larger squares. Ranges \
+                 span at most {} blocks.",
+                MAX_RANGE
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "First height of the range",
+                        "examples": [2000000],
+                    },
+                    "to": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Last height of the range (inclusive)",
+                        "examples": [2000099],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.analyze(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
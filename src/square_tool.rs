@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The block whose square to inspect.
+#[derive(Deserialize)]
+pub struct SquareArgs {
+    height: u64,
+    /// The networks to inspect the block on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Describes how a block's original data square was packed.
+pub struct SquareTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl SquareTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn inspect(&self, args: SquareArgs) -> Result<String, CelestiaSearchError> {
+        self.blocks
+            .across(&args.network, |blocks| describe_square(blocks, args.height))
+            .await
+    }
+}
+
+/// One namespace's run of shares, from the first to the last share it occupies
+struct Span<'a> {
+    namespace: &'a str,
+    kind: &'a str,
+    from: (u64, u64),
+    to: (u64, u64),
+}
+
+impl<'a> Span<'a> {
+    fn parse(item: &'a Value) -> Option<Self> {
+        let corner = |value: &Value| Some((value[0].as_u64()?, value[1].as_u64()?));
+        Some(Self {
+            namespace: item["namespace"].as_str()?,
+            kind: item["type"].as_str().unwrap_or("unknown"),
+            from: corner(&item["from"])?,
+            to: corner(&item["to"])?,
+        })
+    }
+
+    /// The number of shares in the span, which wraps around rows of `width` shares
+    fn shares(&self, width: u64) -> u64 {
+        (self.to.0 * width + self.to.1 + 1).saturating_sub(self.from.0 * width + self.from.1)
+    }
+}
+
+/// Lists the namespaces of the block's square, and which rows each occupies
+async fn describe_square(
+    blocks: &CelestiaSearchTool,
+    height: u64,
+) -> Result<String, CelestiaSearchError> {
+    let ods = blocks.fetch(&format!("/block/{}/ods", height)).await?;
+    let width = ods["width"].as_u64().unwrap_or(0);
+    let spans: Vec<Span> = ods["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Span::parse)
+        .collect();
+    if width == 0 || spans.is_empty() {
+        return Ok(format!("Block {} has an empty data square.", height));
+    }
+
+    let mut shares: BTreeMap<(&str, &str), u64> = BTreeMap::new();
+    let mut rows: Vec<Vec<&str>> = vec![Vec::new(); width as usize];
+    for span in &spans {
+        *shares.entry((span.namespace, span.kind)).or_default() += span.shares(width);
+        for row in span.from.0..=span.to.0.min(width - 1) {
+            let row = &mut rows[row as usize];
+            if row.last() != Some(&span.namespace) {
+                row.push(span.namespace);
+            }
+        }
+    }
+
+    let mut output = format!(
+        "Block {} has a {}x{} original data square ({} shares) holding {} namespace(s):",
+        height,
+        width,
+        width,
+        width * width,
+        shares.len()
+    );
+    for ((namespace, kind), count) in &shares {
+        output.push_str(&format!("\n- {} ({}): {} share(s)", namespace, kind, count));
+    }
+
+    // Consecutive rows holding the same namespaces are described together
+    output.push_str("\nNamespaces by row:");
+    let mut start = 0;
+    for end in 0..rows.len() {
+        if end + 1 < rows.len() && rows[end + 1] == rows[start] {
+            continue;
+        }
+        let range = if start == end {
+            format!("row {}", start)
+        } else {
+            format!("rows {}-{}", start, end)
+        };
+        let namespaces = if rows[start].is_empty() {
+            "padding".to_string()
+        } else {
+            rows[start].join(", ")
+        };
+        output.push_str(&format!("\n- {}: {}", range, namespaces));
+        start = end + 1;
+    }
+
+    Ok(output)
+}
+
+impl Tool for SquareTool {
+    const NAME: &'static str = "block_square";
+
+    type Args = SquareArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Describe how a Celestia block's original data square was packed: its \
+                          dimensions, the namespaces it holds with their share counts, and which \
+                          namespaces occupy each row."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Height of the block to inspect",
+                        "examples": [2000000],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["height"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.inspect(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
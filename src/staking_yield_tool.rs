@@ -0,0 +1,214 @@
+use chrono::{Duration, Utc};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::analytics::{self, Trend};
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::de::string_or_number;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The longest window, in days, a single call reports on.
+pub const MAX_DAYS: u64 = 180;
+
+/// The distribution module's default share of rewards kept by the community pool.
+const DEFAULT_COMMUNITY_TAX: f64 = 0.02;
+
+/// Changes in APR within this many percentage points across the window count as flat.
+const FLAT_TOLERANCE: f64 = 0.1;
+
+/// The window to report yields over.
+#[derive(Deserialize)]
+pub struct StakingYieldArgs {
+    /// How many days to report, ending today.
+    #[serde(default = "default_days")]
+    days: u64,
+    /// The networks to report on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_days() -> u64 {
+    30
+}
+
+/// Reports the inflation rate and the staking APR it pays, per day.
+///
+/// The APR of each day annualizes the rewards paid that day against the stake bonded now, and
+/// the implied inflation annualizes them against the supply, grossed up by the community tax.
+pub struct StakingYieldTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl StakingYieldTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn report(&self, args: StakingYieldArgs) -> Result<String, CelestiaSearchError> {
+        let days = args.days.clamp(1, MAX_DAYS);
+        self.blocks
+            .across(&args.network, |blocks| daily_yield(blocks, days))
+            .await
+    }
+}
+
+async fn daily_yield(
+    blocks: &CelestiaSearchTool,
+    days: u64,
+) -> Result<String, CelestiaSearchError> {
+    let to = Utc::now();
+    let from = to - Duration::days(days as i64);
+    let (rewards, head, constants) = futures::try_join!(
+        blocks.fetch(&format!(
+            "/stats/series/rewards/day?from={}&to={}",
+            from.timestamp(),
+            to.timestamp()
+        )),
+        blocks.fetch("/head"),
+        blocks.fetch("/constants"),
+    )?;
+    let stake = number(&head["total_stake"]);
+    let supply = number(&head["total_supply"]);
+    let tax = Some(number(
+        &constants["module"]["distribution"]["community_tax"],
+    ))
+    .filter(|tax| *tax > 0.0 && *tax < 1.0)
+    .unwrap_or(DEFAULT_COMMUNITY_TAX);
+
+    let mut output = String::new();
+    if let Some(height) = head["last_height"].as_u64() {
+        let stats = blocks.fetch_stats(height).await?;
+        if let Ok(inflation) = stats.inflation_rate.parse::<f64>() {
+            output.push_str(&format!(
+                "The protocol's inflation rate is {} at block {}.",
+                percent(inflation),
+                height
+            ));
+            if stake > 0.0 && supply > 0.0 {
+                output.push_str(&format!(
+                    " With {} of the supply bonded, stakers earn about {} a year before \
+                     commission.",
+                    percent(stake / supply),
+                    percent(inflation * (1.0 - tax) * supply / stake)
+                ));
+            }
+        }
+    }
+
+    let mut rows: Vec<(&str, f64)> = rewards
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|point| {
+            let time = point["time"].as_str()?;
+            Some((
+                time.split('T').next().unwrap_or(time),
+                number(&point["value"]),
+            ))
+        })
+        .collect();
+    rows.sort_by(|a, b| a.0.cmp(b.0));
+    if rows.is_empty() || stake <= 0.0 {
+        output.push_str(&format!(
+            " The indexer has no daily rewards for the last {} day(s) to chart yields with.",
+            days
+        ));
+        return Ok(output.trim_start().to_string());
+    }
+
+    let aprs: Vec<f64> = rows
+        .iter()
+        .map(|(_, rewards)| rewards * 365.0 / stake)
+        .collect();
+    let Some(summary) = analytics::summarize(&aprs) else {
+        unreachable!("there are rows to summarize")
+    };
+    let trend = match summary.trend(FLAT_TOLERANCE / 100.0) {
+        Trend::Flat => "flat".to_string(),
+        trend => format!(
+            "{} ({:+.2} percentage points across the window)",
+            trend.name(),
+            summary.fitted_change * 100.0
+        ),
+    };
+    output.push_str(&format!(
+        " Over the last {} day(s), the rewards paid were worth an APR of {} to {} at today's \
+         stake, {} on average, and the trend is {}. Daily rewards, APR and implied inflation:",
+        rows.len(),
+        percent(summary.min),
+        percent(summary.max),
+        percent(summary.mean),
+        trend
+    ));
+    for ((day, rewards), apr) in rows.iter().zip(&aprs) {
+        output.push_str(&format!(
+            "\n- {}: {:.0} TIA, {} APR",
+            day,
+            rewards / 1_000_000.0,
+            percent(*apr)
+        ));
+        if supply > 0.0 {
+            output.push_str(&format!(
+                ", {} inflation",
+                percent(rewards * 365.0 / (1.0 - tax) / supply)
+            ));
+        }
+    }
+
+    Ok(output.trim_start().to_string())
+}
+
+/// Numbers, and strings holding numbers (as Celenium reports amounts)
+fn number(value: &Value) -> f64 {
+    string_or_number(value).unwrap_or_default()
+}
+
+fn percent(fraction: f64) -> String {
+    format!("{:.2}%", fraction * 100.0)
+}
+
+impl Tool for StakingYieldTool {
+    const NAME: &'static str = "staking_yield";
+
+    type Args = StakingYieldArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Report Celestia's inflation rate and the staking APR it pays, with a daily \
+                 series of rewards, APR and implied inflation over up to {} days ending today \
+                 and the APR's trend. Use it for how staking yields have changed.",
+                MAX_DAYS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "days": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_DAYS,
+                        "description": "How many days to report, ending today",
+                        "examples": [30, 90],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
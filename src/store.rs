@@ -0,0 +1,255 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, ErrorCode, OpenFlags, OptionalExtension};
+use serde_json::{Map, Value};
+
+use crate::celestia_search_tool::CelestiaResponseFields;
+use crate::network::Network;
+
+/// The schema of the store. Monetary fields are stored as NUMERIC so they can be aggregated,
+/// while the raw response is kept to reconstruct the exact values. Blocks are keyed by their
+/// network as well, so one file can hold the blocks of every network queried.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS block_stats (
+    network        TEXT    NOT NULL,
+    height         INTEGER NOT NULL,
+    blobs_count    INTEGER NOT NULL,
+    blobs_size     INTEGER NOT NULL,
+    block_time     INTEGER NOT NULL,
+    bytes_in_block INTEGER NOT NULL,
+    commissions    NUMERIC NOT NULL,
+    events_count   INTEGER NOT NULL,
+    fee            NUMERIC NOT NULL,
+    fill_rate      NUMERIC NOT NULL,
+    gas_limit      INTEGER NOT NULL,
+    gas_used       INTEGER NOT NULL,
+    inflation_rate NUMERIC NOT NULL,
+    rewards        NUMERIC NOT NULL,
+    square_size    INTEGER NOT NULL,
+    supply_change  NUMERIC NOT NULL,
+    tx_count       INTEGER NOT NULL,
+    raw            TEXT    NOT NULL,
+    fetched_at     INTEGER NOT NULL,
+    PRIMARY KEY (network, height)
+);
+";
+
+/// The maximum number of rows returned by an aggregate query.
+pub const MAX_QUERY_ROWS: usize = 100;
+
+/// How many virtual machine steps a query may take before it is aborted, so that one without
+/// a bound (such as a recursive CTE) can't hold the store.
+pub const MAX_QUERY_STEPS: i32 = 10_000_000;
+
+/// Tells apart the in-memory stores of the process, which each share a database between
+/// their connections.
+static IN_MEMORY: AtomicU64 = AtomicU64::new(0);
+
+/// Captures the errors that may occur while using the local store.
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Stored response is corrupt: {0}")]
+    Corrupt(#[from] serde_json::Error),
+    #[error("Only read-only SELECT queries are allowed")]
+    NotReadOnly,
+    #[error("The query took more than {MAX_QUERY_STEPS} steps; add a bound or a LIMIT")]
+    TooLong,
+}
+
+/// A local SQLite index of every block stats response fetched so far.
+///
+/// Queries written by the model run on their own read-only connection, which may not attach
+/// other databases or run pragmas, and is aborted after [`MAX_QUERY_STEPS`].
+pub struct BlockStore {
+    conn: Mutex<Connection>,
+    reader: Mutex<Connection>,
+}
+
+impl BlockStore {
+    /// Opens (creating if needed) the store at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)?;
+        Self::create(&conn)?;
+        let reader = Connection::open_with_flags(path, Self::reader_flags())?;
+        Self::init(conn, reader)
+    }
+
+    /// Opens a store that only lives as long as the process.
+    pub fn in_memory() -> Result<Self, StoreError> {
+        // A named in-memory database, so the reader sees what the writer stores
+        let name = format!(
+            "file:/block-store-{}-{}?vfs=memdb",
+            std::process::id(),
+            IN_MEMORY.fetch_add(1, Ordering::Relaxed)
+        );
+        let conn = Connection::open_with_flags(&name, OpenFlags::default())?;
+        Self::create(&conn)?;
+        let reader = Connection::open_with_flags(&name, Self::reader_flags())?;
+        Self::init(conn, reader)
+    }
+
+    /// Creates the table if needed. Stats stored before blocks were keyed by network can't be
+    /// told apart, so they are dropped, to be fetched again as they're asked for.
+    fn create(conn: &Connection) -> Result<(), StoreError> {
+        let keyed: bool = conn.query_row(
+            "SELECT NOT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'block_stats')
+                 OR EXISTS (SELECT 1 FROM pragma_table_info('block_stats') WHERE name = 'network')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !keyed {
+            conn.execute_batch("DROP TABLE block_stats")?;
+        }
+        conn.execute_batch(SCHEMA)?;
+        Ok(())
+    }
+
+    fn reader_flags() -> OpenFlags {
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX
+    }
+
+    fn init(conn: Connection, reader: Connection) -> Result<Self, StoreError> {
+        reader.authorizer(Some(|context: AuthContext<'_>| match context.action {
+            AuthAction::Attach { .. } | AuthAction::Detach { .. } | AuthAction::Pragma { .. } => {
+                Authorization::Deny
+            }
+            _ => Authorization::Allow,
+        }));
+        // Called once a statement has taken the steps, which aborts it
+        reader.progress_handler(MAX_QUERY_STEPS, Some(|| true));
+        Ok(Self {
+            conn: Mutex::new(conn),
+            reader: Mutex::new(reader),
+        })
+    }
+
+    /// Returns the stored stats of the block at `height` on `network`, if it has been fetched
+    /// before.
+    pub fn get(
+        &self,
+        network: Network,
+        height: u64,
+    ) -> Result<Option<CelestiaResponseFields>, StoreError> {
+        let raw: Option<String> = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT raw FROM block_stats WHERE network = ?1 AND height = ?2",
+                params![network.name(), height],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match raw {
+            Some(raw) => Ok(Some(CelestiaResponseFields::from_json(
+                &serde_json::from_str(&raw)?,
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The highest height stored for `network`, if any of its blocks has been.
+    pub fn max_height(&self, network: Network) -> Result<Option<u64>, StoreError> {
+        Ok(self.conn.lock().unwrap().query_row(
+            "SELECT MAX(height) FROM block_stats WHERE network = ?1",
+            params![network.name()],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Persists the raw stats response of the block at `height` on `network`.
+    pub fn put(&self, network: Network, height: u64, raw: &Value) -> Result<(), StoreError> {
+        let stats = CelestiaResponseFields::from_json(raw)?;
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO block_stats (
+                network, height, blobs_count, blobs_size, block_time, bytes_in_block,
+                commissions, events_count, fee, fill_rate, gas_limit, gas_used, inflation_rate,
+                rewards, square_size, supply_change, tx_count, raw, fetched_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+            params![
+                network.name(),
+                height,
+                stats.blobs_count,
+                stats.blobs_size,
+                stats.block_time,
+                stats.bytes_in_block,
+                stats.commissions.to_string(),
+                stats.events_count,
+                stats.fee.to_string(),
+                stats.fill_rate,
+                stats.gas_limit,
+                stats.gas_used,
+                stats.inflation_rate,
+                stats.rewards.to_string(),
+                stats.square_size,
+                stats.supply_change.to_string(),
+                stats.tx_count,
+                raw.to_string(),
+                fetched_at,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Runs a read-only query, returning up to [`MAX_QUERY_ROWS`] rows as JSON objects.
+    pub fn query(&self, sql: &str) -> Result<Vec<Value>, StoreError> {
+        self.run_query(sql).map_err(|e| match e {
+            StoreError::Sqlite(e) => match e.sqlite_error_code() {
+                Some(ErrorCode::AuthorizationForStatementDenied) => StoreError::NotReadOnly,
+                Some(ErrorCode::OperationInterrupted) => StoreError::TooLong,
+                _ => StoreError::Sqlite(e),
+            },
+            e => e,
+        })
+    }
+
+    fn run_query(&self, sql: &str) -> Result<Vec<Value>, StoreError> {
+        let conn = self.reader.lock().unwrap();
+        let mut stmt = conn.prepare(sql)?;
+        if !stmt.readonly() {
+            return Err(StoreError::NotReadOnly);
+        }
+
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut rows = stmt.query([])?;
+        let mut results = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            if results.len() == MAX_QUERY_ROWS {
+                break;
+            }
+
+            let mut object = Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                let value = match row.get_ref(i)? {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Integer(n) => Value::from(n),
+                    ValueRef::Real(f) => Value::from(f),
+                    ValueRef::Text(t) => Value::from(String::from_utf8_lossy(t).into_owned()),
+                    ValueRef::Blob(_) => Value::from("<blob>"),
+                };
+                object.insert(column.clone(), value);
+            }
+            results.push(Value::Object(object));
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::metrics::metrics;
+use crate::store::{BlockStore, StoreError, MAX_QUERY_ROWS};
+
+/// The query the agent will run against the local store.
+#[derive(Deserialize)]
+pub struct StoreQueryArgs {
+    /// A read-only SQL query over the `block_stats` table.
+    sql: String,
+}
+
+/// Answers aggregate questions over block stats that have already been fetched.
+pub struct StoreQueryTool {
+    store: Arc<BlockStore>,
+}
+
+impl StoreQueryTool {
+    pub fn new(store: Arc<BlockStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Tool for StoreQueryTool {
+    const NAME: &'static str = "query_block_store";
+
+    type Args = StoreQueryArgs;
+    type Output = String;
+    type Error = StoreError;
+
+    /// Describes the table so the model can write queries against it
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Run a read-only SQLite SELECT over block stats that have already been fetched \
+                 locally, for aggregate questions (sums, averages, maxima, counts). The table \
+                 `block_stats` has columns: network, height, blobs_count, blobs_size, \
+                 block_time (ms), bytes_in_block, commissions, events_count, fee (utia), \
+                 fill_rate, gas_limit, gas_used, inflation_rate, rewards, square_size, \
+                 supply_change, tx_count. Only blocks that were queried before are present, from \
+                 every network queried, so filter on `network` ('mainnet', 'mocha' or \
+                 'arabica'). At most {} rows are returned.",
+                MAX_QUERY_ROWS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "sql": {
+                        "type": "string",
+                        "minLength": 1,
+                        "description": "A single SQLite SELECT statement over `block_stats`",
+                        "examples": [
+                            "SELECT AVG(fill_rate) FROM block_stats",
+                            "SELECT height, fee FROM block_stats ORDER BY fee DESC LIMIT 5",
+                        ],
+                    },
+                },
+                "required": ["sql"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    /// Runs the query and returns the rows as JSON
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.store.query(&args.sql);
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        Ok(Value::Array(result?).to_string())
+    }
+}
@@ -0,0 +1,108 @@
+//! Typed final answers, for programs consuming the assistant's output.
+//!
+//! In structured mode the agent's answer is a JSON object holding the answer text, the values
+//! it quotes by name and the sources they came from. Replies that don't match [`schema`] are
+//! re-prompted with the reason, like malformed tool calls.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::postprocess;
+
+/// Appended to the preamble in structured mode.
+pub const PREAMBLE: &str = "When you answer without calling a tool, reply with only a JSON \
+    object holding the answer, as described below.";
+
+/// A typed final answer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructuredAnswer {
+    /// The answer, in plain text
+    pub answer: String,
+    /// The values the answer quotes, by name, such as `fee_utia`
+    pub values: BTreeMap<String, Value>,
+    /// Where the values came from: tools called, or endpoints queried
+    pub sources: Vec<String>,
+}
+
+/// The JSON schema answers must match.
+pub fn schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "answer": { "type": "string", "minLength": 1 },
+            "values": {
+                "type": "object",
+                "additionalProperties": { "type": ["number", "string", "boolean"] },
+            },
+            "sources": { "type": "array", "items": { "type": "string" } },
+        },
+        "required": ["answer", "values", "sources"],
+        "additionalProperties": false,
+    })
+}
+
+/// Asks for the answer to `prompt` from `evidence` (a tool's output, or a draft answer) as a
+/// [`StructuredAnswer`].
+pub fn request(prompt: &str, evidence: &str) -> String {
+    format!(
+        "{}\n\nAnswer the question above from this data:\n{}\n\n{}",
+        prompt,
+        evidence,
+        instructions()
+    )
+}
+
+/// How to lay out the answer
+pub fn instructions() -> String {
+    format!(
+        "Reply with only a JSON object, without a code fence, matching this JSON schema: {}. \
+         `answer` is the answer in plain text, `values` holds the numbers and identifiers it \
+         quotes by name (e.g., {{\"fee_utia\": 2217}}), and `sources` lists the tools or \
+         endpoints the data came from (e.g., [\"search_blocks\"]).",
+        schema()
+    )
+}
+
+/// Parses a reply, or describes how it fails to match [`schema`].
+pub fn parse(reply: &str) -> Result<StructuredAnswer, String> {
+    let value: Value = serde_json::from_str(postprocess::unfence(reply.trim()))
+        .map_err(|e| format!("it was not valid JSON ({})", e))?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| "it was not a JSON object".to_string())?;
+
+    if let Some(key) = object
+        .keys()
+        .find(|key| !["answer", "values", "sources"].contains(&key.as_str()))
+    {
+        return Err(format!("it had an unexpected field `{}`", key));
+    }
+    match object.get("answer") {
+        Some(Value::String(answer)) if !answer.trim().is_empty() => {}
+        Some(Value::String(_)) => return Err("`answer` was empty".to_string()),
+        _ => return Err("`answer` was missing or not a string".to_string()),
+    }
+    match object.get("values") {
+        Some(Value::Object(values)) => {
+            if let Some((name, _)) = values
+                .iter()
+                .find(|(_, value)| !(value.is_number() || value.is_string() || value.is_boolean()))
+            {
+                return Err(format!(
+                    "value `{}` was not a number, string or boolean",
+                    name
+                ));
+            }
+        }
+        _ => return Err("`values` was missing or not an object".to_string()),
+    }
+    match object.get("sources") {
+        Some(Value::Array(sources)) if sources.iter().all(Value::is_string) => {}
+        _ => return Err("`sources` was missing or not an array of strings".to_string()),
+    }
+
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
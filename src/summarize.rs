@@ -0,0 +1,170 @@
+//! Keeps tool results within a token budget when they are sent back to the model.
+//!
+//! A result over the budget is reduced in steps: its largest JSON arrays are aggregated into
+//! their item count, the min, max, mean and sum of their numeric fields, and their first and
+//! last few items, which keeps totals and trends over long block ranges correct. Whatever is
+//! still too large is truncated. A note ending the result tells the model what was summarized.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+/// The tokens a single tool result may take up in a request, by default.
+pub const DEFAULT_RESULT_TOKENS: usize = 2000;
+
+/// A rough ratio, close enough for English and JSON with OpenAI's and Gemini's tokenizers
+const CHARS_PER_TOKEN: usize = 4;
+
+/// The items kept from each end of an aggregated array
+const KEPT_ITEMS: usize = 3;
+
+/// Estimates how many tokens `text` takes up.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// `result` if it is within `max_tokens`, otherwise a summary of it noting what was summarized.
+pub fn fit(result: &str, max_tokens: usize) -> Cow<'_, str> {
+    if estimate_tokens(result) <= max_tokens {
+        return Cow::Borrowed(result);
+    }
+
+    let mut notes = Vec::new();
+    let mut text = match serde_json::from_str::<Value>(result) {
+        Ok(mut value) => {
+            aggregate(&mut value, max_tokens, &mut notes);
+            value.to_string()
+        }
+        Err(_) => result.to_string(),
+    };
+
+    let max_chars = max_tokens * CHARS_PER_TOKEN;
+    let chars = text.chars().count();
+    if chars > max_chars {
+        text = text.chars().take(max_chars).collect();
+        notes.push(format!(
+            "the result was truncated to its first {} of {} characters",
+            max_chars, chars
+        ));
+    }
+
+    Cow::Owned(format!(
+        "{}\n\n[Summarized to fit in about {} tokens: {}.]",
+        text,
+        max_tokens,
+        notes.join("; ")
+    ))
+}
+
+/// Replaces the largest arrays of `value` with their aggregates until it fits in `max_tokens`
+fn aggregate(value: &mut Value, max_tokens: usize, notes: &mut Vec<String>) {
+    while estimate_tokens(&value.to_string()) > max_tokens {
+        let mut largest = None;
+        largest_array(value, String::new(), &mut largest);
+        let Some((_, pointer)) = largest else {
+            return;
+        };
+        let array = value
+            .pointer_mut(&pointer)
+            .expect("the pointer was just found");
+        let items = std::mem::take(array.as_array_mut().expect("the pointer is to an array"));
+        notes.push(format!(
+            "{} ({} items) was aggregated, keeping its first and last {}",
+            match pointer.is_empty() {
+                true => "the result".to_string(),
+                false => format!("`{}`", &pointer[1..]),
+            },
+            items.len(),
+            KEPT_ITEMS
+        ));
+        *array = summary(items);
+    }
+}
+
+/// Finds the JSON pointer of the largest array worth aggregating, by serialized size
+fn largest_array(value: &Value, pointer: String, largest: &mut Option<(usize, String)>) {
+    match value {
+        Value::Array(items) => {
+            if items.len() > 2 * KEPT_ITEMS {
+                let size = value.to_string().len();
+                if largest.as_ref().is_none_or(|(largest, _)| size > *largest) {
+                    *largest = Some((size, pointer.clone()));
+                }
+            }
+            for (i, item) in items.iter().enumerate() {
+                largest_array(item, format!("{}/{}", pointer, i), largest);
+            }
+        }
+        Value::Object(object) => {
+            for (key, item) in object {
+                let key = key.replace('~', "~0").replace('/', "~1");
+                largest_array(item, format!("{}/{}", pointer, key), largest);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The aggregate standing in for `items`
+fn summary(items: Vec<Value>) -> Value {
+    let mut stats = Map::new();
+    let numbers: Vec<f64> = items.iter().filter_map(number).collect();
+    if numbers.len() == items.len() {
+        stats.insert("values".to_string(), aggregates(&numbers));
+    } else {
+        // Numeric fields of object items, such as each block's fee
+        let mut fields: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+        for object in items.iter().filter_map(Value::as_object) {
+            for (key, value) in object {
+                if let Some(n) = number(value) {
+                    fields.entry(key).or_default().push(n);
+                }
+            }
+        }
+        for (field, numbers) in fields {
+            stats.insert(field.to_string(), aggregates(&numbers));
+        }
+    }
+
+    let count = items.len();
+    let first: Vec<Value> = items.iter().take(KEPT_ITEMS).cloned().collect();
+    let last: Vec<Value> = items.into_iter().skip(count - KEPT_ITEMS).collect();
+    json!({
+        "summarized_items": count,
+        "stats": stats,
+        "first": first,
+        "last": last,
+    })
+}
+
+/// A number, or a string of one as Celenium returns amounts
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok().filter(|n: &f64| n.is_finite()),
+        _ => None,
+    }
+}
+
+fn aggregates(numbers: &[f64]) -> Value {
+    let sum: f64 = numbers.iter().sum();
+    let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    json!({
+        "count": numbers.len(),
+        "min": whole(min),
+        "max": whole(max),
+        "mean": whole(sum / numbers.len() as f64),
+        "sum": whole(sum),
+    })
+}
+
+/// `n` as an integer if it is one, so that amounts aren't printed with a trailing `.0`
+fn whole(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 2f64.powi(53) {
+        json!(n as i64)
+    } else {
+        json!(n)
+    }
+}
@@ -0,0 +1,126 @@
+//! Named prompts for recurring questions, with `{{param}}` placeholders filled from
+//! `--param name=value`. A placeholder may give a default after a bar, as in `{{days|30}}`.
+
+use std::collections::BTreeMap;
+
+/// A built-in prompt template.
+#[derive(Clone, Copy, Debug)]
+pub struct Template {
+    pub name: &'static str,
+    pub text: &'static str,
+}
+
+/// The built-in templates, by name.
+pub const TEMPLATES: [Template; 3] = [
+    Template {
+        name: "daily-summary",
+        text: include_str!("../prompts/templates/daily-summary.md"),
+    },
+    Template {
+        name: "block-deep-dive",
+        text: include_str!("../prompts/templates/block-deep-dive.md"),
+    },
+    Template {
+        name: "validator-report",
+        text: include_str!("../prompts/templates/validator-report.md"),
+    },
+];
+
+/// Captures the errors that may occur while rendering a template.
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("Unknown template {0}; the templates are {names}", names = names())]
+    Unknown(String),
+    #[error("Template {0} needs --param {1}=...")]
+    MissingParam(&'static str, String),
+    #[error("Template {0} has no parameter {1}; its parameters are {params}", params = .2.join(", "))]
+    UnknownParam(&'static str, String, Vec<String>),
+    #[error("Template {0} has an unclosed placeholder")]
+    Unclosed(&'static str),
+}
+
+/// The built-in template called `name`.
+pub fn find(name: &str) -> Result<&'static Template, TemplateError> {
+    TEMPLATES
+        .iter()
+        .find(|template| template.name == name)
+        .ok_or_else(|| TemplateError::Unknown(name.to_string()))
+}
+
+/// Renders the template called `name` with `params`.
+pub fn render(name: &str, params: &[(String, String)]) -> Result<String, TemplateError> {
+    find(name)?.render(&params.iter().cloned().collect())
+}
+
+/// Parses a `--param` argument of the form `name=value`.
+pub fn parse_param(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("expected name=value, got {:?}", arg)),
+    }
+}
+
+fn names() -> String {
+    TEMPLATES.map(|template| template.name).join(", ")
+}
+
+impl Template {
+    /// The names of the template's placeholders, in order of first use.
+    pub fn params(&self) -> Vec<String> {
+        let mut params: Vec<String> = Vec::new();
+        let mut rest = self.text;
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else {
+                break;
+            };
+            let (name, _) = placeholder(&rest[start + 2..start + end]);
+            if !params.iter().any(|param| param == name) {
+                params.push(name.to_string());
+            }
+            rest = &rest[start + end + 2..];
+        }
+        params
+    }
+
+    /// Fills the placeholders with `params`, or their defaults, failing on parameters the
+    /// template doesn't take so that misspelled ones aren't silently dropped.
+    pub fn render(&self, params: &BTreeMap<String, String>) -> Result<String, TemplateError> {
+        let known = self.params();
+        if let Some(unknown) = params.keys().find(|name| !known.contains(name)) {
+            return Err(TemplateError::UnknownParam(
+                self.name,
+                unknown.clone(),
+                known,
+            ));
+        }
+
+        let mut output = String::new();
+        let mut rest = self.text;
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find("}}")
+                .ok_or(TemplateError::Unclosed(self.name))?;
+            let (name, default) = placeholder(&rest[start + 2..start + end]);
+            let value = params
+                .get(name)
+                .map(String::as_str)
+                .or(default)
+                .ok_or_else(|| TemplateError::MissingParam(self.name, name.to_string()))?;
+            output.push_str(value);
+            rest = &rest[start + end + 2..];
+        }
+        output.push_str(rest);
+        Ok(output.trim().to_string())
+    }
+}
+
+/// Splits a placeholder into its name and default value, if it has one
+fn placeholder(inner: &str) -> (&str, Option<&str>) {
+    match inner.split_once('|') {
+        Some((name, default)) => (name.trim(), Some(default.trim())),
+        None => (inner.trim(), None),
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::metrics::metrics;
+use crate::price::{PriceError, PriceFeed};
+
+/// The tool takes no arguments.
+#[derive(Deserialize)]
+pub struct TiaPriceArgs {}
+
+/// Answers basic market questions about TIA.
+pub struct TiaPriceTool {
+    feed: Arc<PriceFeed>,
+}
+
+impl TiaPriceTool {
+    pub fn new(feed: Arc<PriceFeed>) -> Self {
+        Self { feed }
+    }
+}
+
+impl Tool for TiaPriceTool {
+    const NAME: &'static str = "tia_price";
+
+    type Args = TiaPriceArgs;
+    type Output = String;
+    type Error = PriceError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get the current market data of TIA, Celestia's native token: its \
+                price, 24h price change, market cap and 24h trading volume, all in USD."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {},
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    /// Fetches the market data and describes it
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.feed.market_data().await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        let data = result?;
+        Ok(format!(
+            "TIA is trading at ${:.2} ({:+.2}% over 24h), with a market cap of ${:.0} and a \
+             24h volume of ${:.0}.",
+            data.price_usd, data.change_24h_pct, data.market_cap_usd, data.volume_24h_usd
+        ))
+    }
+}
@@ -0,0 +1,62 @@
+//! Clocks and timers that also work on `wasm32-unknown-unknown`.
+//!
+//! In the browser `std::time::Instant::now` panics and there is no tokio runtime to sleep on,
+//! so there the JavaScript clock and `setTimeout` are used instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::Instant;
+
+/// Waits for `duration` without blocking the thread.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep(duration: std::time::Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Waits for `duration` without blocking the thread.
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep(duration: std::time::Duration) {
+    browser::sleep(duration).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use std::time::Duration;
+
+    use wasm_bindgen::{JsCast, JsValue};
+
+    /// A point in time, in milliseconds since the Unix epoch.
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct Instant(f64);
+
+    impl Instant {
+        pub fn now() -> Self {
+            Self(js_sys::Date::now())
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Duration::from_secs_f64((js_sys::Date::now() - self.0).max(0.0) / 1000.0)
+        }
+    }
+
+    /// Resolves a promise through the global `setTimeout`, in windows and workers alike
+    pub async fn sleep(duration: Duration) {
+        let millis = duration.as_millis().min(i32::MAX as u128) as i32;
+        let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+            let set_timeout = js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())
+                .ok()
+                .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+            match set_timeout {
+                Some(set_timeout) => {
+                    let _ = set_timeout.call2(&JsValue::NULL, &resolve, &millis.into());
+                }
+                None => {
+                    let _ = resolve.call0(&JsValue::NULL);
+                }
+            }
+        });
+        let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+    }
+}
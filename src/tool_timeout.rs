@@ -0,0 +1,149 @@
+//! Per-tool time limits, from the `[tools]` section of the config, and what the agent is told
+//! when a tool runs over its limit.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures::future::{self, Either};
+use rig::completion::ToolDefinition;
+use rig::tool::{ToolDyn, ToolError};
+use serde::Deserialize;
+
+use crate::metrics::metrics;
+use crate::time;
+
+/// What a tool call that runs over its time limit answers with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeoutPolicy {
+    /// Make the call once more, then answer as [`Partial`](Self::Partial) does if it runs over
+    /// again; responses fetched by the first attempt are cached, so the second often finishes
+    Retry,
+    /// Tell the agent the data is missing, for it to answer from the other results with a
+    /// caveat that it's incomplete
+    #[default]
+    Partial,
+    /// Tell the agent to decline the question rather than answer without the data
+    Refuse,
+}
+
+/// Time limits of tool calls, none by default.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolTimeouts {
+    /// Seconds any tool may take, unless it has a limit of its own.
+    pub timeout_secs: Option<u64>,
+    /// Seconds each of the named tools may take, e.g. `fill_rate_trend = 90`.
+    #[serde(default)]
+    pub timeouts: BTreeMap<String, u64>,
+    /// What a call running over its limit answers with.
+    #[serde(default)]
+    pub on_timeout: TimeoutPolicy,
+}
+
+/// Whether a call's `output` is what a tool running over its limit answers with.
+pub fn timed_out(output: &str) -> bool {
+    output.starts_with("\"The `") && output.contains("` tool timed out after ")
+}
+
+impl ToolTimeouts {
+    /// How long the tool named `tool` may take, if it's limited.
+    pub fn timeout(&self, tool: &str) -> Option<Duration> {
+        self.timeouts
+            .get(tool)
+            .copied()
+            .or(self.timeout_secs)
+            .map(Duration::from_secs)
+    }
+
+    /// Limits `tool` to its timeout, if it has one.
+    pub fn apply(&self, tool: Box<dyn ToolDyn>) -> Box<dyn ToolDyn> {
+        match self.timeout(&tool.name()) {
+            Some(timeout) => Box::new(TimedTool {
+                tool,
+                timeout,
+                policy: self.on_timeout,
+            }),
+            None => tool,
+        }
+    }
+}
+
+/// A tool whose calls are cut off after a time limit, answering as the policy says instead.
+pub struct TimedTool {
+    tool: Box<dyn ToolDyn>,
+    timeout: Duration,
+    policy: TimeoutPolicy,
+}
+
+impl TimedTool {
+    /// Makes the call, or returns `None` if it ran over the limit
+    async fn attempt(&self, args: String) -> Option<Result<String, ToolError>> {
+        let call = self.tool.call(args);
+        let timer = Box::pin(time::sleep(self.timeout));
+        match future::select(call, timer).await {
+            Either::Left((result, _)) => Some(result),
+            Either::Right(_) => None,
+        }
+    }
+
+    /// What the agent is told instead of the tool's result, which [`timed_out`] recognizes
+    fn fallback(&self) -> String {
+        let name = self.tool.name();
+        let message = match self.policy {
+            TimeoutPolicy::Refuse => format!(
+                "The `{}` tool timed out after {}s, so this data is unavailable right now. Tell \
+                 the user the question can't be answered at the moment and to try again later, \
+                 without estimating the answer.",
+                name,
+                self.timeout.as_secs()
+            ),
+            TimeoutPolicy::Retry | TimeoutPolicy::Partial => format!(
+                "The `{}` tool timed out after {}s, so this data is missing. Answer from the \
+                 other tool results if they are enough, saying the answer is incomplete because \
+                 `{}` timed out; otherwise say the data is unavailable right now.",
+                name,
+                self.timeout.as_secs(),
+                name
+            ),
+        };
+        // Tools answer with JSON-encoded strings
+        serde_json::to_string(&message).expect("strings should serialize")
+    }
+}
+
+impl ToolDyn for TimedTool {
+    fn name(&self) -> String {
+        self.tool.name()
+    }
+
+    fn definition(
+        &self,
+        prompt: String,
+    ) -> Pin<Box<dyn Future<Output = ToolDefinition> + Send + Sync + '_>> {
+        self.tool.definition(prompt)
+    }
+
+    fn call(
+        &self,
+        args: String,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ToolError>> + Send + Sync + '_>> {
+        Box::pin(async move {
+            let attempts = match self.policy {
+                TimeoutPolicy::Retry => 2,
+                TimeoutPolicy::Partial | TimeoutPolicy::Refuse => 1,
+            };
+            for _ in 0..attempts {
+                if let Some(result) = self.attempt(args.clone()).await {
+                    return result;
+                }
+                metrics()
+                    .tool_invocations
+                    .inc(&[self.tool.name().as_str(), "timeout"]);
+            }
+            Ok(self.fallback())
+        })
+    }
+}
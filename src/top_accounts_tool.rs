@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The most accounts listed by a single call.
+pub const MAX_LIMIT: u64 = 100;
+
+/// The latest transactions whose fees are totalled per payer.
+const FEE_WINDOW: u64 = 100;
+
+/// What accounts are ranked by.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ranking {
+    /// Spendable TIA
+    #[default]
+    Balance,
+    /// TIA delegated to validators
+    Delegated,
+    /// Fees paid in the latest transactions
+    Fees,
+}
+
+/// The accounts to list.
+#[derive(Deserialize)]
+pub struct TopAccountsArgs {
+    #[serde(default)]
+    by: Ranking,
+    /// How many accounts to list.
+    #[serde(default = "default_limit")]
+    limit: u64,
+    /// The networks to rank accounts on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_limit() -> u64 {
+    10
+}
+
+/// Lists the largest holders, largest delegators or biggest fee payers.
+pub struct TopAccountsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl TopAccountsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn rank(&self, args: TopAccountsArgs) -> Result<String, CelestiaSearchError> {
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+        self.blocks
+            .across(&args.network, |blocks| async move {
+                match args.by {
+                    Ranking::Balance => by_balance(blocks, "spendable", limit).await,
+                    Ranking::Delegated => by_balance(blocks, "delegated", limit).await,
+                    Ranking::Fees => by_fees(blocks, limit).await,
+                }
+            })
+            .await
+    }
+}
+
+/// Ranks accounts by one of the `balance` fields Celenium sorts its address listing by
+async fn by_balance(
+    blocks: &CelestiaSearchTool,
+    field: &str,
+    limit: u64,
+) -> Result<String, CelestiaSearchError> {
+    let endpoint = format!("/address?limit={}&sort=desc&sort_by={}", limit, field);
+    let accounts = blocks.fetch(&endpoint).await?;
+    let accounts: Vec<(&str, String)> = accounts
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|account| {
+            let amount = match &account["balance"][field] {
+                Value::String(amount) => amount.clone(),
+                Value::Number(amount) => amount.to_string(),
+                _ => "0".to_string(),
+            };
+            Some((account["hash"].as_str()?, amount))
+        })
+        .collect();
+    if accounts.is_empty() {
+        return Ok("No accounts found.".to_string());
+    }
+
+    let mut output = format!(
+        "The {} accounts with the most {} TIA:",
+        accounts.len(),
+        field
+    );
+    for (rank, (address, amount)) in accounts.iter().enumerate() {
+        output.push_str(&format!(
+            "\n{}. {}: {}",
+            rank + 1,
+            address,
+            price::describe_utia(amount, None)
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Ranks the signers of the latest [`FEE_WINDOW`] transactions by the fees they paid
+async fn by_fees(blocks: &CelestiaSearchTool, limit: u64) -> Result<String, CelestiaSearchError> {
+    let txs = blocks
+        .fetch(&format!("/tx?limit={}&sort=desc", FEE_WINDOW))
+        .await?;
+    let txs: Vec<&Value> = txs.as_array().into_iter().flatten().collect();
+
+    // A transaction's fee is paid by its first signer
+    let mut fees: HashMap<&str, (Utia, usize)> = HashMap::new();
+    for tx in &txs {
+        let Some(payer) = tx["signers"][0].as_str() else {
+            continue;
+        };
+        let fee = Utia::from_value(&tx["fee"]).unwrap_or_default();
+        let entry = fees.entry(payer).or_default();
+        entry.0 += fee;
+        entry.1 += 1;
+    }
+    if fees.is_empty() {
+        return Ok("No recent transactions to rank fee payers by.".to_string());
+    }
+
+    let mut payers: Vec<(&str, (Utia, usize))> = fees.into_iter().collect();
+    payers.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.0.cmp(b.0)));
+    payers.truncate(limit as usize);
+
+    let mut output = format!(
+        "The {} accounts that paid the most fees in the latest {} transactions:",
+        payers.len(),
+        txs.len()
+    );
+    for (rank, (address, (fee, count))) in payers.iter().enumerate() {
+        output.push_str(&format!(
+            "\n{}. {}: {} over {} transaction(s)",
+            rank + 1,
+            address,
+            price::describe(*fee, None),
+            count
+        ));
+    }
+
+    Ok(output)
+}
+
+impl Tool for TopAccountsTool {
+    const NAME: &'static str = "top_accounts";
+
+    type Args = TopAccountsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "List the top Celestia accounts, up to {}: the largest holders of spendable TIA, \
+                 the largest delegators, or the accounts that paid the most fees in the latest \
+                 {} transactions.",
+                MAX_LIMIT, FEE_WINDOW
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "by": {
+                        "type": "string",
+                        "enum": ["balance", "delegated", "fees"],
+                        "description": "What to rank accounts by (balance by default)",
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": "How many accounts to list",
+                        "examples": [10],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.rank(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use futures::{stream, StreamExt, TryStreamExt};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::byte_size;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::de::string_or_number;
+use crate::fetcher::DEFAULT_CONCURRENCY;
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The most namespaces listed by a single call.
+pub const MAX_LIMIT: u64 = 20;
+
+/// The longest period, in days, namespaces are ranked over.
+pub const MAX_DAYS: u64 = 90;
+
+/// How many of the largest and of the most recently active namespaces are ranked.
+const CANDIDATES: u64 = 20;
+
+/// What namespaces are ranked by.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ranking {
+    /// Fees paid for their blobs
+    #[default]
+    Fees,
+    /// Bytes of blobs posted
+    Bytes,
+}
+
+/// The namespaces to list.
+#[derive(Deserialize)]
+pub struct TopNamespacesArgs {
+    #[serde(default)]
+    by: Ranking,
+    /// How many days to rank over, ending today.
+    #[serde(default = "default_days")]
+    days: u64,
+    /// How many namespaces to list.
+    #[serde(default = "default_limit")]
+    limit: u64,
+    /// The networks to rank namespaces on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_days() -> u64 {
+    30
+}
+
+fn default_limit() -> u64 {
+    10
+}
+
+/// Lists the namespaces that paid the most fees, or posted the most bytes, over a period.
+///
+/// Celenium sorts its namespace listing by all-time size and by activity only, so the largest
+/// and the most recently active namespaces are ranked by their daily series over the period.
+pub struct TopNamespacesTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl TopNamespacesTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn rank(&self, args: TopNamespacesArgs) -> Result<String, CelestiaSearchError> {
+        let days = args.days.clamp(1, MAX_DAYS);
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+        self.blocks
+            .across(&args.network, |blocks| rank(blocks, args.by, days, limit))
+            .await
+    }
+}
+
+/// A namespace's usage over the period
+struct Usage {
+    name: Option<String>,
+    fees: f64,
+    bytes: f64,
+}
+
+async fn rank(
+    blocks: &CelestiaSearchTool,
+    by: Ranking,
+    days: u64,
+    limit: u64,
+) -> Result<String, CelestiaSearchError> {
+    let listing = |sort_by: &str| {
+        format!(
+            "/namespace?limit={}&sort=desc&sort_by={}",
+            CANDIDATES, sort_by
+        )
+    };
+    let (largest, active) = futures::try_join!(
+        blocks.fetch(&listing("size")),
+        blocks.fetch(&listing("time")),
+    )?;
+    let mut candidates: BTreeMap<String, Option<String>> = BTreeMap::new();
+    for namespace in [&largest, &active]
+        .into_iter()
+        .filter_map(Value::as_array)
+        .flatten()
+    {
+        let Some(id) = namespace["namespace_id"].as_str() else {
+            continue;
+        };
+        let version = namespace["version"].as_u64().unwrap_or(0);
+        let name = namespace["name"]
+            .as_str()
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+        candidates.insert(format!("{:02x}{}", version, id), name);
+    }
+    if candidates.is_empty() {
+        return Ok("No namespaces found.".to_string());
+    }
+
+    let to = Utc::now();
+    let from = to - Duration::days(days as i64);
+    let total = |namespace: String, series: &'static str| async move {
+        let points = blocks
+            .fetch(&format!(
+                "/stats/namespace/series/{}/day/{}?from={}&to={}",
+                namespace,
+                series,
+                from.timestamp(),
+                to.timestamp()
+            ))
+            .await?;
+        let total: f64 = points
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|point| number(&point["value"]))
+            .sum();
+        Ok::<_, CelestiaSearchError>(total)
+    };
+    let mut usage: Vec<(String, Usage)> = stream::iter(candidates)
+        .map(|(namespace, name)| async move {
+            let (fees, bytes) = futures::try_join!(
+                total(namespace.clone(), "fee"),
+                total(namespace.clone(), "size")
+            )?;
+            Ok::<_, CelestiaSearchError>((namespace, Usage { name, fees, bytes }))
+        })
+        .buffered(DEFAULT_CONCURRENCY / 2)
+        .try_collect()
+        .await?;
+
+    let key = |usage: &Usage| match by {
+        Ranking::Fees => usage.fees,
+        Ranking::Bytes => usage.bytes,
+    };
+    usage.retain(|(_, usage)| key(usage) > 0.0);
+    if usage.is_empty() {
+        return Ok(format!(
+            "None of the largest or most recently active namespaces posted blobs in the last {} \
+             day(s).",
+            days
+        ));
+    }
+    usage.sort_by(|a, b| key(&b.1).total_cmp(&key(&a.1)).then(a.0.cmp(&b.0)));
+    usage.truncate(limit as usize);
+
+    let mut output = format!(
+        "The {} namespaces that {} in the last {} day(s), among the largest and most recently \
+         active:",
+        usage.len(),
+        match by {
+            Ranking::Fees => "paid the most fees",
+            Ranking::Bytes => "posted the most bytes",
+        },
+        days
+    );
+    for (rank, (namespace, usage)) in usage.iter().enumerate() {
+        let name = match &usage.name {
+            Some(name) => format!("{} ({})", name, namespace),
+            None => namespace.clone(),
+        };
+        output.push_str(&format!(
+            "\n{}. {}: {} in fees for {}",
+            rank + 1,
+            name,
+            price::describe_utia(&format!("{:.0}", usage.fees), None),
+            byte_size::format(usage.bytes as u64)
+        ));
+    }
+
+    Ok(output)
+}
+
+fn number(value: &Value) -> f64 {
+    string_or_number(value).unwrap_or_default()
+}
+
+impl Tool for TopNamespacesTool {
+    const NAME: &'static str = "top_namespaces";
+
+    type Args = TopNamespacesArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "List the top Celestia namespaces, up to {}, by the fees they paid or the bytes \
+                 of blobs they posted over up to {} days ending today, with both totals for \
+                 each. Use it for which rollups use Celestia the most.",
+                MAX_LIMIT, MAX_DAYS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "by": {
+                        "type": "string",
+                        "enum": ["fees", "bytes"],
+                        "description": "What to rank namespaces by (fees by default)",
+                    },
+                    "days": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_DAYS,
+                        "description": "How many days to rank over, ending today",
+                        "examples": [7, 30],
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": "How many namespaces to list",
+                        "examples": [10],
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.rank(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
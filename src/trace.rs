@@ -0,0 +1,359 @@
+//! Spans of agent turns, tool calls and HTTP requests, exported over OTLP.
+//!
+//! Spans are only recorded once an exporter is installed with [`install`], which
+//! [`install_from_env`] does when `OTEL_EXPORTER_OTLP_ENDPOINT` is set; until then they cost
+//! next to nothing. Finished spans are batched and sent as OTLP/HTTP JSON to the collector's
+//! `/v1/traces` endpoint.
+//!
+//! A span is the parent of the spans started while the future given to [`traced`] is being
+//! polled, which is how tool calls nest under the agent turn that made them.
+
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+/// How many finished spans are buffered between exports at most; the oldest are dropped first.
+const MAX_QUEUED: usize = 4096;
+
+/// How often buffered spans are sent.
+#[cfg(not(target_arch = "wasm32"))]
+const EXPORT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+static EXPORTER: OnceLock<Exporter> = OnceLock::new();
+
+thread_local! {
+    /// The span whose future is being polled on this thread, if any
+    static CURRENT: Cell<Option<SpanContext>> = const { Cell::new(None) };
+}
+
+/// Identifies a span, and the trace it belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpanContext {
+    pub trace_id: u128,
+    pub span_id: u64,
+}
+
+/// What a span measures, as OTLP tells them apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpanKind {
+    /// Work done by the assistant itself, such as an agent turn or a tool call
+    Internal,
+    /// A request to another service, such as Celenium or the LLM provider
+    Client,
+}
+
+/// A span being recorded; it ends when dropped.
+///
+/// Spans started without an exporter installed record nothing.
+#[derive(Debug)]
+pub struct Span {
+    recording: Option<Recording>,
+}
+
+#[derive(Debug)]
+struct Recording {
+    context: SpanContext,
+    parent: Option<u64>,
+    name: &'static str,
+    kind: SpanKind,
+    start: u64,
+    attributes: Vec<(&'static str, Value)>,
+    error: Option<String>,
+}
+
+/// A span that has ended, waiting to be exported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinishedSpan {
+    pub context: SpanContext,
+    pub parent: Option<u64>,
+    pub name: &'static str,
+    pub kind: SpanKind,
+    /// Nanoseconds since the Unix epoch
+    pub start: u64,
+    pub end: u64,
+    pub attributes: Vec<(&'static str, Value)>,
+    /// Why the work failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Starts an internal span, as a child of the current one if there is one.
+pub fn span(name: &'static str) -> Span {
+    Span::new(name, SpanKind::Internal)
+}
+
+/// Starts a span of a request to another service.
+pub fn client_span(name: &'static str) -> Span {
+    Span::new(name, SpanKind::Client)
+}
+
+impl Span {
+    fn new(name: &'static str, kind: SpanKind) -> Self {
+        if EXPORTER.get().is_none() {
+            return Self { recording: None };
+        }
+        let parent = CURRENT.with(Cell::get);
+        let context = SpanContext {
+            trace_id: match parent {
+                Some(parent) => parent.trace_id,
+                None => ((random() as u128) << 64) | random() as u128,
+            },
+            span_id: random(),
+        };
+        Self {
+            recording: Some(Recording {
+                context,
+                parent: parent.map(|parent| parent.span_id),
+                name,
+                kind,
+                start: now(),
+                attributes: Vec::new(),
+                error: None,
+            }),
+        }
+    }
+
+    /// Adds an attribute, such as `tool.name`.
+    pub fn with(mut self, key: &'static str, value: impl Into<Value>) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Sets an attribute once it is known, such as a response's status code.
+    pub fn set(&mut self, key: &'static str, value: impl Into<Value>) {
+        if let Some(recording) = &mut self.recording {
+            recording.attributes.retain(|(k, _)| *k != key);
+            recording.attributes.push((key, value.into()));
+        }
+    }
+
+    /// Marks the work the span measures as failed.
+    pub fn fail(&mut self, error: impl fmt::Display) {
+        if let Some(recording) = &mut self.recording {
+            recording.error = Some(error.to_string());
+        }
+    }
+
+    /// The span's identifiers, unless it records nothing.
+    pub fn context(&self) -> Option<SpanContext> {
+        self.recording.as_ref().map(|recording| recording.context)
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let (Some(recording), Some(exporter)) = (self.recording.take(), EXPORTER.get()) else {
+            return;
+        };
+        exporter.push(FinishedSpan {
+            context: recording.context,
+            parent: recording.parent,
+            name: recording.name,
+            kind: recording.kind,
+            start: recording.start,
+            end: now(),
+            attributes: recording.attributes,
+            error: recording.error,
+        });
+    }
+}
+
+/// Runs `future` within `span`, marking the span as failed if the future fails.
+pub async fn traced<F, T, E>(mut span: Span, future: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: fmt::Display,
+{
+    let result = Scoped {
+        future: Box::pin(future),
+        context: span.context(),
+    }
+    .await;
+    if let Err(e) = &result {
+        span.fail(e);
+    }
+    result
+}
+
+/// Makes a span the current one while its future is polled
+struct Scoped<F> {
+    future: Pin<Box<F>>,
+    context: Option<SpanContext>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Some(context) = self.context else {
+            return self.future.as_mut().poll(cx);
+        };
+        let outer = CURRENT.with(|current| current.replace(Some(context)));
+        let poll = self.future.as_mut().poll(cx);
+        CURRENT.with(|current| current.set(outer));
+        poll
+    }
+}
+
+/// Sends finished spans to an OTLP collector in batches.
+struct Exporter {
+    url: String,
+    service_name: String,
+    http: reqwest::Client,
+    queue: Mutex<VecDeque<FinishedSpan>>,
+}
+
+impl Exporter {
+    fn push(&self, span: FinishedSpan) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.len() >= MAX_QUEUED {
+            queue.pop_front();
+        }
+        queue.push_back(span);
+    }
+
+    async fn export(&self) -> Result<(), reqwest::Error> {
+        let spans = Vec::from(std::mem::take(&mut *self.queue.lock().unwrap()));
+        if spans.is_empty() {
+            return Ok(());
+        }
+        self.http
+            .post(&self.url)
+            .json(&encode(&self.service_name, &spans))
+            .timeout(crate::celestia_search_tool::REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Starts recording spans, to be sent to the OTLP/HTTP collector at `endpoint` (such as
+/// `http://localhost:4318`) as `service_name`.
+///
+/// Returns `false` if an exporter was already installed. Spans are sent every few seconds
+/// from a background task, which must be spawned from within a tokio runtime.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn install(endpoint: &str, service_name: &str) -> bool {
+    let exporter = Exporter {
+        url: format!("{}/v1/traces", endpoint.trim_end_matches('/')),
+        service_name: service_name.to_string(),
+        http: reqwest::Client::new(),
+        queue: Mutex::default(),
+    };
+    if EXPORTER.set(exporter).is_err() {
+        return false;
+    }
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(EXPORT_INTERVAL);
+        loop {
+            interval.tick().await;
+            flush().await;
+        }
+    });
+    true
+}
+
+/// Installs an exporter if `OTEL_EXPORTER_OTLP_ENDPOINT` is set, naming the service after
+/// `OTEL_SERVICE_NAME` or the crate.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn install_from_env() -> bool {
+    let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+        return false;
+    };
+    let service_name =
+        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| env!("CARGO_PKG_NAME").to_string());
+    install(&endpoint, &service_name)
+}
+
+/// Sends the spans finished so far, as the process does before it exits.
+pub async fn flush() {
+    if let Some(exporter) = EXPORTER.get() {
+        if let Err(e) = exporter.export().await {
+            eprintln!("Could not export traces to {}: {}", exporter.url, e);
+        }
+    }
+}
+
+/// The OTLP/HTTP JSON request exporting `spans`.
+pub fn encode(service_name: &str, spans: &[FinishedSpan]) -> Value {
+    let spans: Vec<Value> = spans
+        .iter()
+        .map(|span| {
+            let mut encoded = json!({
+                "traceId": format!("{:032x}", span.context.trace_id),
+                "spanId": format!("{:016x}", span.context.span_id),
+                "name": span.name,
+                "kind": match span.kind {
+                    SpanKind::Internal => 1,
+                    SpanKind::Client => 3,
+                },
+                "startTimeUnixNano": span.start.to_string(),
+                "endTimeUnixNano": span.end.to_string(),
+                "attributes": span
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| attribute(key, value))
+                    .collect::<Vec<_>>(),
+                "status": match &span.error {
+                    Some(message) => json!({ "code": 2, "message": message }),
+                    None => json!({ "code": 1 }),
+                },
+            });
+            if let Some(parent) = span.parent {
+                encoded["parentSpanId"] = format!("{:016x}", parent).into();
+            }
+            encoded
+        })
+        .collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": { "attributes": [attribute("service.name", &service_name.into())] },
+            "scopeSpans": [{
+                "scope": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+/// An OTLP key-value, its value tagged by type
+fn attribute(key: &str, value: &Value) -> Value {
+    let value = match value {
+        Value::Bool(b) => json!({ "boolValue": b }),
+        // 64-bit integers are strings in the JSON encoding of protobuf
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "intValue": n.to_string() }),
+        Value::Number(n) => json!({ "doubleValue": n }),
+        Value::String(s) => json!({ "stringValue": s }),
+        other => json!({ "stringValue": other.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+/// Nanoseconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// A random, non-zero id, as OTLP requires of span and trace ids
+fn random() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.finish().max(1)
+}
@@ -0,0 +1,197 @@
+//! Reports of a conversation, for sharing an analysis: each question with the tool calls made
+//! to answer it, their data, and the answer, as Markdown or a standalone HTML page.
+
+use std::path::Path;
+
+use crate::assistant::{ToolCall, Turn};
+use crate::chart::{self, Series};
+use crate::postprocess;
+
+const TITLE: &str = "Celestia search assistant transcript";
+
+/// How a transcript is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    Markdown,
+    Html,
+}
+
+impl TranscriptFormat {
+    /// The format of a transcript written to `path`: HTML for `.html` and `.htm` files, and
+    /// Markdown otherwise.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension)
+                if extension.eq_ignore_ascii_case("html")
+                    || extension.eq_ignore_ascii_case("htm") =>
+            {
+                TranscriptFormat::Html
+            }
+            _ => TranscriptFormat::Markdown,
+        }
+    }
+}
+
+/// A question and how it was answered.
+pub struct Entry {
+    pub question: String,
+    /// The tool calls made for the answer, in order.
+    pub calls: Vec<ToolCall>,
+    /// The answer, or `None` if the call was only planned in dry-run mode.
+    pub answer: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// The questions asked so far in a conversation, and their answers.
+#[derive(Default)]
+pub struct Transcript {
+    entries: Vec<Entry>,
+}
+
+impl Transcript {
+    /// Records the answer to `question`.
+    pub fn push(&mut self, question: &str, turn: &Turn) {
+        let calls: Vec<ToolCall> = turn.steps.iter().chain(&turn.tool_call).cloned().collect();
+        let planned = turn
+            .tool_call
+            .as_ref()
+            .is_some_and(|call| call.output.is_none());
+        self.entries.push(Entry {
+            question: question.to_string(),
+            calls,
+            answer: (!planned).then(|| postprocess::answer(&turn.output)),
+            warnings: turn.warnings.clone(),
+        });
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Forgets the questions recorded so far.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Writes the transcript to `path`, in the format its extension calls for.
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        let text = match TranscriptFormat::for_path(path) {
+            TranscriptFormat::Markdown => self.to_markdown(),
+            TranscriptFormat::Html => self.to_html(),
+        };
+        std::fs::write(path, text)
+    }
+
+    /// Renders the transcript as Markdown, with tool data that holds a series as a table.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# {}\n", TITLE);
+        for (i, entry) in self.entries.iter().enumerate() {
+            output.push_str(&format!("\n## {}. {}\n", i + 1, entry.question.trim()));
+            for call in &entry.calls {
+                output.push_str(&format!(
+                    "\n**Tool call:** `{}` with `{}`\n\n",
+                    call.name, call.args
+                ));
+                match call.output.as_deref() {
+                    None => output.push_str("_Planned in dry-run mode, not called._\n"),
+                    Some(data) => match chart::detect(data) {
+                        Some(series) => output.push_str(&markdown_table(&series)),
+                        None => output.push_str(&format!("```\n{}\n```\n", data.trim_end())),
+                    },
+                }
+            }
+            match &entry.answer {
+                Some(answer) => output.push_str(&format!("\n**Answer:**\n\n{}\n", answer.trim())),
+                None => output.push_str("\n**Answer:** _dry run, tool not called_\n"),
+            }
+            for warning in &entry.warnings {
+                output.push_str(&format!("\n> **Warning:** {}\n", warning));
+            }
+        }
+        output
+    }
+
+    /// Renders the transcript as a standalone HTML page.
+    pub fn to_html(&self) -> String {
+        let mut body = format!("<h1>{}</h1>\n", TITLE);
+        for (i, entry) in self.entries.iter().enumerate() {
+            body.push_str(&format!(
+                "<section>\n<h2>{}. {}</h2>\n",
+                i + 1,
+                escape(entry.question.trim())
+            ));
+            for call in &entry.calls {
+                body.push_str(&format!(
+                    "<p><strong>Tool call:</strong> <code>{}</code> with <code>{}</code></p>\n",
+                    escape(&call.name),
+                    escape(&call.args.to_string())
+                ));
+                match call.output.as_deref() {
+                    None => body.push_str("<p><em>Planned in dry-run mode, not called.</em></p>\n"),
+                    Some(data) => match chart::detect(data) {
+                        Some(series) => body.push_str(&html_table(&series)),
+                        None => body.push_str(&format!("<pre>{}</pre>\n", escape(data.trim_end()))),
+                    },
+                }
+            }
+            match &entry.answer {
+                Some(answer) => body.push_str(&format!(
+                    "<p><strong>Answer:</strong></p>\n<div class=\"answer\">{}</div>\n",
+                    escape(answer.trim()).replace('\n', "<br>\n")
+                )),
+                None => body.push_str(
+                    "<p><strong>Answer:</strong> <em>dry run, tool not called</em></p>\n",
+                ),
+            }
+            for warning in &entry.warnings {
+                body.push_str(&format!(
+                    "<p class=\"warning\"><strong>Warning:</strong> {}</p>\n",
+                    escape(warning)
+                ));
+            }
+            body.push_str("</section>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; max-width: 60em; margin: auto; }}\n\
+             pre {{ background: #f4f4f4; padding: 0.5em; overflow-x: auto; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             td, th {{ border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: right; }}\n\
+             .warning {{ color: #a15c00; }}\n</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            TITLE, body
+        )
+    }
+}
+
+fn markdown_table(series: &Series) -> String {
+    let mut table = format!("| point | {} |\n| --- | --- |\n", series.metric);
+    for (label, value) in &series.points {
+        table.push_str(&format!("| {} | {} |\n", label, value));
+    }
+    table
+}
+
+fn html_table(series: &Series) -> String {
+    let mut table = format!(
+        "<table>\n<tr><th>point</th><th>{}</th></tr>\n",
+        escape(&series.metric)
+    );
+    for (label, value) in &series.points {
+        table.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            escape(label),
+            value
+        ));
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+/// Escapes text for HTML element content and attribute values.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::Duration;
+
+use futures::StreamExt;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::mpsc;
+
+use crate::assistant::{ToolCall, Turn};
+use crate::celestia_search_tool::{CelestiaResponseFields, CelestiaSearchTool};
+use crate::shutdown;
+use crate::watch;
+
+/// The number of blocks kept in the ticker.
+const TICKER_LEN: usize = 50;
+
+/// The number of tool calls kept in the tool pane.
+const TOOL_CALLS_LEN: usize = 20;
+
+/// Who wrote a chat message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Author {
+    User,
+    Assistant,
+    /// Errors and status notes
+    System,
+}
+
+/// A tool call as shown in the tool pane.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolCallEntry {
+    pub name: String,
+    pub args: String,
+    pub output: Option<String>,
+}
+
+impl From<&ToolCall> for ToolCallEntry {
+    fn from(call: &ToolCall) -> Self {
+        Self {
+            name: call.name.clone(),
+            args: call.args.to_string(),
+            output: call.output.clone(),
+        }
+    }
+}
+
+/// The state of the dashboard, updated by key presses, new blocks and answers.
+#[derive(Default)]
+pub struct App {
+    pub messages: Vec<(Author, String)>,
+    pub input: String,
+    /// The latest blocks, newest first
+    pub blocks: VecDeque<(u64, CelestiaResponseFields)>,
+    /// The last error following new blocks, until the next block arrives
+    pub watch_error: Option<String>,
+    /// The latest tool calls, newest first
+    pub tool_calls: VecDeque<ToolCallEntry>,
+    /// Whether a question is being answered
+    pub pending: bool,
+    pub quit: bool,
+}
+
+impl App {
+    /// Applies a key press, returning the question to ask if one was submitted.
+    pub fn on_key(&mut self, key: KeyEvent) -> Option<String> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter if !self.pending => {
+                let prompt = std::mem::take(&mut self.input).trim().to_string();
+                if !prompt.is_empty() {
+                    self.messages.push((Author::User, prompt.clone()));
+                    self.pending = true;
+                    return Some(prompt);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Records the answer to the pending question.
+    pub fn on_answer(&mut self, answer: Result<Turn, String>) {
+        self.pending = false;
+        match answer {
+            Ok(turn) => {
+                if let Some(call) = &turn.tool_call {
+                    self.tool_calls.push_front(call.into());
+                    self.tool_calls.truncate(TOOL_CALLS_LEN);
+                }
+                self.messages.push((Author::Assistant, turn.output));
+            }
+            Err(e) => self
+                .messages
+                .push((Author::System, format!("Error: {}", e))),
+        }
+    }
+
+    /// Adds a block to the ticker.
+    pub fn on_block(&mut self, height: u64, stats: CelestiaResponseFields) {
+        self.watch_error = None;
+        self.blocks.push_front((height, stats));
+        self.blocks.truncate(TICKER_LEN);
+    }
+
+    /// Draws the chat on the left, and the block ticker above the tool calls on the right.
+    pub fn draw(&self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [chat, side] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(main);
+        let [ticker, tools] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(side);
+
+        self.draw_chat(frame, chat);
+        self.draw_ticker(frame, ticker);
+        self.draw_tool_calls(frame, tools);
+
+        let hint = if self.pending {
+            "Thinking…  Esc: quit"
+        } else {
+            "Enter: ask  Esc: quit"
+        };
+        frame.render_widget(
+            Line::from(hint).style(Style::new().fg(Color::DarkGray)),
+            status,
+        );
+    }
+
+    fn draw_chat(&self, frame: &mut Frame, area: Rect) {
+        let [history, input] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).areas(area);
+
+        let lines: Vec<Line> = self
+            .messages
+            .iter()
+            .flat_map(|(author, text)| {
+                let (name, color) = match author {
+                    Author::User => ("you", Color::Cyan),
+                    Author::Assistant => ("assistant", Color::Green),
+                    Author::System => ("system", Color::Red),
+                };
+                let mut lines = vec![Line::from(Span::styled(
+                    name,
+                    Style::new().fg(color).add_modifier(Modifier::BOLD),
+                ))];
+                lines.extend(text.lines().map(|line| Line::from(line.to_string())));
+                lines.push(Line::default());
+                lines
+            })
+            .collect();
+        // Keep the latest messages in view
+        let height = history.height.saturating_sub(2);
+        let scroll = (lines.len() as u16).saturating_sub(height);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(Block::bordered().title(" Chat "))
+                .wrap(Wrap { trim: false })
+                .scroll((scroll, 0)),
+            history,
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.input.as_str()).block(Block::bordered().title(" Ask ")),
+            input,
+        );
+        frame.set_cursor_position((input.x + 1 + self.input.chars().count() as u16, input.y + 1));
+    }
+
+    fn draw_ticker(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .blocks
+            .iter()
+            .map(|(height, stats)| {
+                ListItem::new(format!(
+                    "{:>9}  {:>4} txs  {:>3} blobs  fill {}",
+                    height, stats.tx_count, stats.blobs_count, stats.fill_rate
+                ))
+            })
+            .collect();
+        let title = match &self.watch_error {
+            Some(e) => format!(" Blocks (error: {}) ", e),
+            None => " Blocks ".to_string(),
+        };
+        frame.render_widget(List::new(items).block(Block::bordered().title(title)), area);
+    }
+
+    fn draw_tool_calls(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .tool_calls
+            .iter()
+            .map(|call| {
+                let output = call.output.as_deref().unwrap_or("(not executed)");
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::styled(call.name.as_str(), Style::new().fg(Color::Yellow)),
+                        Span::raw(" "),
+                        Span::raw(call.args.as_str()),
+                    ]),
+                    Line::from(Span::styled(
+                        output.lines().next().unwrap_or_default().to_string(),
+                        Style::new().fg(Color::DarkGray),
+                    )),
+                ])
+            })
+            .collect();
+        frame.render_widget(
+            List::new(items).block(Block::bordered().title(" Tool calls ")),
+            area,
+        );
+    }
+}
+
+/// Runs the dashboard until the user quits, answering each question with `ask` while following
+/// new blocks every `interval`.
+pub async fn run<F, Fut>(
+    tool: &CelestiaSearchTool,
+    interval: Duration,
+    ask: F,
+) -> std::io::Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Turn, String>>,
+{
+    // Quit on SIGINT as on the Ctrl-C key, so the terminal is restored
+    let _graceful = shutdown::Graceful::new();
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, tool, interval, ask).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop<F, Fut>(
+    terminal: &mut DefaultTerminal,
+    tool: &CelestiaSearchTool,
+    interval: Duration,
+    ask: F,
+) -> std::io::Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Turn, String>>,
+{
+    // Terminal events are read on a blocking thread, which ends with the process
+    let (keys_tx, mut keys) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if keys_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut app = App::default();
+    let mut blocks = std::pin::pin!(watch::blocks(tool, None, interval));
+    let mut answer = std::pin::pin!(futures::future::OptionFuture::from(None::<Fut>));
+
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        tokio::select! {
+            Some(event) = keys.recv() => {
+                if let Event::Key(key) = event {
+                    if let Some(prompt) = app.on_key(key) {
+                        answer.set(Some(ask(prompt)).into());
+                    }
+                }
+            }
+            Some(block) = blocks.next() => match block {
+                Ok((height, stats)) => app.on_block(height, stats),
+                Err(e) => app.watch_error = Some(e.to_string()),
+            },
+            Some(turn) = &mut answer => {
+                app.on_answer(turn);
+                answer.set(None.into());
+            }
+            _ = shutdown::requested() => app.quit = true,
+        }
+    }
+
+    Ok(())
+}
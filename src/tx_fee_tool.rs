@@ -0,0 +1,193 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+use crate::rest::{FeePayment, RestClient};
+
+/// The transaction to break the fee of down.
+#[derive(Deserialize)]
+pub struct TxFeeArgs {
+    /// The transaction hash, in hex.
+    hash: String,
+    /// The networks to look the transaction up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Breaks a transaction's fee down into gas, gas price, payer and fee grant.
+///
+/// Gas and the fee come from Celenium; the fee's payer and granter are in the transaction's
+/// auth info, which is read from a Cosmos REST API.
+pub struct TxFeeTool {
+    blocks: CelestiaSearchTool,
+    rest: RestClient,
+}
+
+impl TxFeeTool {
+    pub fn new(blocks: CelestiaSearchTool, rest: RestClient) -> Self {
+        Self { blocks, rest }
+    }
+
+    async fn breakdown(&self, args: TxFeeArgs) -> Result<String, CelestiaSearchError> {
+        let hash = validate_hash(&args.hash)?;
+        self.blocks
+            .across(&args.network, |blocks| breakdown(blocks, &self.rest, &hash))
+            .await
+    }
+}
+
+/// Checks that `hash` is 32 bytes of hex, returning it lowercase without a `0x` prefix.
+pub fn validate_hash(hash: &str) -> Result<String, CelestiaSearchError> {
+    let trimmed = hash.trim();
+    let hex = trimmed.strip_prefix("0x").unwrap_or(trimmed);
+    if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(CelestiaSearchError::InvalidTxHash(hash.to_string()));
+    }
+    Ok(hex.to_ascii_lowercase())
+}
+
+async fn breakdown(
+    blocks: &CelestiaSearchTool,
+    rest: &RestClient,
+    hash: &str,
+) -> Result<String, CelestiaSearchError> {
+    // Cosmos REST APIs index transactions by their uppercase hash
+    let upper = hash.to_uppercase();
+    let (tx, payment) = futures::join!(
+        blocks.fetch(&format!("/tx/{}", hash)),
+        rest.fee_payment(blocks.network(), &upper),
+    );
+    let tx = tx?;
+
+    let fee = Utia::from_value(&tx["fee"]).unwrap_or_default();
+    let gas_wanted = gas(&tx["gas_wanted"]);
+    let gas_used = gas(&tx["gas_used"]);
+    let mut output = format!(
+        "Transaction {} at height {} ({}) paid a fee of {} for {} gas wanted",
+        hash,
+        tx["height"],
+        tx["status"].as_str().unwrap_or("unknown status"),
+        price::describe(fee, None),
+        gas_wanted
+    );
+    if gas_wanted == 0 {
+        output.push('.');
+    } else {
+        let price = fee.as_f64() / gas_wanted as f64;
+        output.push_str(&format!(
+            ", of which {} ({:.2}%) was used. The effective gas price is {} utia per gas wanted",
+            gas_used,
+            gas_used as f64 / gas_wanted as f64 * 100.0,
+            gas_price(price)
+        ));
+        if gas_used > 0 {
+            output.push_str(&format!(
+                " ({} utia per gas used)",
+                gas_price(fee.as_f64() / gas_used as f64)
+            ));
+        }
+        // The fee covers the gas limit, as unused gas isn't refunded
+        let unused = gas_wanted.saturating_sub(gas_used);
+        output.push_str(&format!(
+            ". Unused gas isn't refunded, so {} utia paid for the {} gas left unused.",
+            (price * unused as f64).round(),
+            unused
+        ));
+    }
+
+    let signer = tx["signers"]
+        .as_array()
+        .and_then(|signers| signers.first())
+        .and_then(|signer| signer.as_str().or(signer["address"].as_str()));
+    match payment {
+        Ok(FeePayment { payer, granter }) => {
+            let payer = payer.as_deref().or(signer);
+            output.push_str(&match (payer, granter) {
+                (Some(payer), Some(granter)) => format!(
+                    " {} paid the fee through a fee grant from {}, whose allowance it used.",
+                    payer, granter
+                ),
+                (None, Some(granter)) => format!(
+                    " The fee was paid through a fee grant from {}, whose allowance it used.",
+                    granter
+                ),
+                (Some(payer), None) => format!(" {} paid the fee, without a fee grant.", payer),
+                (None, None) => {
+                    " The fee was paid by the first signer, without a fee grant.".to_string()
+                }
+            });
+        }
+        // Gas and the fee still answer most fee questions
+        Err(e) => output.push_str(&format!(
+            " The fee's payer and any fee grant are unknown, as the transaction's auth info \
+             couldn't be read: {}.",
+            e
+        )),
+    }
+
+    Ok(output)
+}
+
+/// Gas, which Celenium reports as a number or a string
+fn gas(value: &Value) -> u64 {
+    match value {
+        Value::String(gas) => gas.parse().unwrap_or_default(),
+        gas => gas.as_u64().unwrap_or_default(),
+    }
+}
+
+/// A gas price with the digits that matter at the chain's fractions of a utia
+fn gas_price(price: f64) -> String {
+    let formatted = format!("{:.6}", price);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+impl Tool for TxFeeTool {
+    const NAME: &'static str = "tx_fee";
+
+    type Args = TxFeeArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Break down the fee of a Celestia transaction: gas wanted versus \
+                          used, the fee in utia and TIA, the effective gas price, what unused \
+                          gas cost, who paid the fee and whether a fee grant paid it. Use it \
+                          for questions debugging a transaction's fees."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "hash": {
+                        "type": "string",
+                        "description": "The transaction hash, in hex",
+                        "examples": ["0b4f9a4c1a9f3e8d7c6b5a4938271605f4e3d2c1b0a99887766554433221100f"],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["hash"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.breakdown(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
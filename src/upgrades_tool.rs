@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::amount::Utia;
+use crate::byte_size;
+use crate::celestia_search_tool::{
+    CelestiaResponseFields, CelestiaSearchError, CelestiaSearchTool,
+};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+
+/// The upgrades bundled with the assistant.
+const UPGRADES: &str = include_str!("../data/upgrades.toml");
+
+/// How many blocks on each side of an upgrade are compared.
+pub const WINDOW: u64 = 25;
+
+/// A network upgrade, from the bundled dataset.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Upgrade {
+    pub name: String,
+    /// The consensus app version the upgrade activates.
+    pub app_version: u64,
+    pub summary: String,
+    /// What changed, one entry per change.
+    pub changes: Vec<String>,
+    /// The height the upgrade activated at, or is planned for, on each network.
+    #[serde(default)]
+    pub heights: BTreeMap<Network, u64>,
+    /// The day it activated on each network, as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub dates: BTreeMap<Network, String>,
+}
+
+#[derive(Deserialize)]
+struct Dataset {
+    upgrade: Vec<Upgrade>,
+}
+
+/// The bundled upgrades, in the order they activated.
+pub fn upgrades() -> &'static [Upgrade] {
+    static UPGRADES_PARSED: OnceLock<Vec<Upgrade>> = OnceLock::new();
+    UPGRADES_PARSED.get_or_init(|| {
+        toml::from_str::<Dataset>(UPGRADES)
+            .expect("the bundled upgrades are valid")
+            .upgrade
+    })
+}
+
+/// The upgrade to look up, if any.
+#[derive(Deserialize)]
+pub struct UpgradesArgs {
+    /// The upgrade's name, to compare blocks before and after it; all are listed without one.
+    name: Option<String>,
+    /// The networks to look the upgrades up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Lists Celestia's network upgrades, and compares block production before and after one.
+pub struct UpgradesTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl UpgradesTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn look_up(&self, args: UpgradesArgs) -> Result<String, CelestiaSearchError> {
+        let upgrade = match args.name.as_deref().map(str::trim) {
+            Some(name) => Some(find(name)?),
+            None => None,
+        };
+        self.blocks
+            .across(&args.network, |blocks| async move {
+                match upgrade {
+                    Some(upgrade) => describe(blocks, upgrade).await,
+                    None => list(blocks).await,
+                }
+            })
+            .await
+    }
+}
+
+/// The upgrade named `name`, ignoring case
+fn find(name: &str) -> Result<&'static Upgrade, CelestiaSearchError> {
+    upgrades()
+        .iter()
+        .find(|upgrade| upgrade.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| CelestiaSearchError::UnknownValue {
+            kind: "upgrade",
+            value: name.to_string(),
+            known: upgrades()
+                .iter()
+                .map(|upgrade| upgrade.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+}
+
+/// When the upgrade activated on the blocks' network, relative to the chain head
+fn activation(upgrade: &Upgrade, network: Network, head: u64) -> String {
+    let Some(&height) = upgrade.heights.get(&network) else {
+        return format!("has no recorded activation height on {}", network);
+    };
+    if height > head {
+        return format!(
+            "is planned for height {} on {}, {} block(s) from the head",
+            height,
+            network,
+            height - head
+        );
+    }
+    match upgrade.dates.get(&network) {
+        Some(date) => format!("activated at height {} on {} ({})", height, network, date),
+        None => format!("activated at height {} on {}", height, network),
+    }
+}
+
+/// Every upgrade, with when it activated
+async fn list(blocks: &CelestiaSearchTool) -> Result<String, CelestiaSearchError> {
+    let head = blocks.chain_head().await?;
+    let mut output = format!("Celestia's network upgrades on {}:", blocks.network());
+    for upgrade in upgrades() {
+        output.push_str(&format!(
+            "\n- {} (app version {}) {}: {}",
+            upgrade.name,
+            upgrade.app_version,
+            activation(upgrade, blocks.network(), head),
+            upgrade.summary
+        ));
+    }
+    Ok(output)
+}
+
+/// What the upgrade changed, and how blocks compare on either side of it
+async fn describe(
+    blocks: &CelestiaSearchTool,
+    upgrade: &Upgrade,
+) -> Result<String, CelestiaSearchError> {
+    let head = blocks.chain_head().await?;
+    let mut output = format!(
+        "{} (app version {}) {}. {}",
+        upgrade.name,
+        upgrade.app_version,
+        activation(upgrade, blocks.network(), head),
+        upgrade.summary
+    );
+    for change in &upgrade.changes {
+        output.push_str(&format!("\n- {}", change));
+    }
+
+    let Some(&height) = upgrade.heights.get(&blocks.network()) else {
+        return Ok(output);
+    };
+    if height <= WINDOW || height + WINDOW > head {
+        return Ok(output);
+    }
+    let (before, after) = futures::try_join!(
+        fetcher::fetch_range_partial(blocks, height - WINDOW..height, DEFAULT_CONCURRENCY),
+        fetcher::fetch_range_partial(blocks, height..height + WINDOW, DEFAULT_CONCURRENCY),
+    )?;
+    output.push_str(&format!(
+        "\nThe {} blocks before it (from {}): {}.\nThe {} blocks from it on (to {}): {}.",
+        before.rows.len(),
+        height - WINDOW,
+        averages(&before.rows),
+        after.rows.len(),
+        height + WINDOW - 1,
+        averages(&after.rows)
+    ));
+    if let Some(caveat) = before.caveat().or(after.caveat()) {
+        output.push_str(&format!("\n{}", caveat));
+    }
+
+    Ok(output)
+}
+
+/// The average block of `rows`
+fn averages(rows: &[(u64, CelestiaResponseFields)]) -> String {
+    let n = rows.len().max(1) as f64;
+    let mean = |value: fn(&CelestiaResponseFields) -> f64| {
+        rows.iter().map(|(_, stats)| value(stats)).sum::<f64>() / n
+    };
+    let fee = Utia::new(mean(|stats| stats.fee.as_f64()).round() as i64);
+    format!(
+        "on average {:.1}s apart, {:.2}% full, with {:.1} transaction(s) and {:.1} blob(s) of {} \
+         paying {} in fees",
+        mean(|stats| stats.block_time as f64) / 1000.0,
+        mean(|stats| stats.fill_rate) * 100.0,
+        mean(|stats| stats.tx_count as f64),
+        mean(|stats| stats.blobs_count as f64),
+        byte_size::format(mean(|stats| stats.blobs_size as f64).round() as u64),
+        price::describe(fee, None)
+    )
+}
+
+impl Tool for UpgradesTool {
+    const NAME: &'static str = "chain_upgrades";
+
+    type Args = UpgradesArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "List Celestia's network upgrades (such as Lemongrass and Ginger) with their \
+                 activation heights and dates, or, given an upgrade's name, what it changed and \
+                 how the {} blocks before and after it compare: block time, fill rate, \
+                 transactions, blobs and fees.",
+                WINDOW
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "The upgrade to describe; all are listed without one",
+                        "examples": upgrades().iter().map(|upgrade| &upgrade.name).collect::<Vec<_>>(),
+                    },
+                    "network": network::schema(),
+                },
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.look_up(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,175 @@
+//! Finds validators by the names users know them by ("P2P", "stakely") rather than their
+//! operator addresses: the indexer's validator list is fetched once per network and kept for
+//! [`LIST_TTL`], and monikers match loosely, forgiving case, punctuation and small typos.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+
+/// How long the validator list is kept before it is fetched again.
+pub const LIST_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The most validators listed by a single page of the indexer's list.
+pub const PAGE_SIZE: usize = 100;
+
+/// The most pages fetched, well beyond the validators any Celestia network has.
+const MAX_PAGES: usize = 10;
+
+/// The most candidates offered for a name matching several validators.
+pub const MAX_CANDIDATES: usize = 5;
+
+/// How similar a name and a moniker must be, from 0 to 1, for a typo to still match.
+const MIN_SIMILARITY: f64 = 0.7;
+
+/// A validator, as the indexer lists it.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Listed {
+    /// Celenium's id of the validator.
+    pub id: u64,
+    #[serde(default)]
+    pub moniker: String,
+    /// The validator's operator address (`celestiavaloper1...`).
+    pub address: Option<String>,
+}
+
+impl Listed {
+    /// The validator's moniker, with its operator address if known.
+    pub fn describe(&self) -> String {
+        match &self.address {
+            Some(address) => format!("{} ({})", self.moniker, address),
+            None => format!("{} (id {})", self.moniker, self.id),
+        }
+    }
+}
+
+/// What a name matches.
+#[derive(Debug, PartialEq)]
+pub enum Lookup<'a> {
+    /// A single validator
+    Found(&'a Listed),
+    /// Several validators, the closest first
+    Ambiguous(Vec<&'a Listed>),
+    NotFound,
+}
+
+/// The validators of `validators` whose monikers match `name`.
+///
+/// A moniker equal to the name, ignoring case and punctuation, is the match. Otherwise every
+/// moniker containing the name or within a typo or two of it is a candidate, and the name
+/// only resolves if there is just one.
+pub fn find<'a>(name: &str, validators: &'a [Listed]) -> Lookup<'a> {
+    let name = normalize(name);
+    if name.is_empty() {
+        return Lookup::NotFound;
+    }
+    let mut scored: Vec<(f64, &Listed)> = validators
+        .iter()
+        .map(|validator| (score(&name, &normalize(&validator.moniker)), validator))
+        .filter(|(score, _)| *score >= MIN_SIMILARITY)
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| a.1.moniker.cmp(&b.1.moniker))
+    });
+
+    let exact: Vec<&Listed> = scored
+        .iter()
+        .filter(|(score, _)| *score == 1.0)
+        .map(|(_, validator)| *validator)
+        .collect();
+    let matches = match exact.is_empty() {
+        true => scored.into_iter().map(|(_, validator)| validator).collect(),
+        false => exact,
+    };
+    match matches.as_slice() {
+        [] => Lookup::NotFound,
+        [validator] => Lookup::Found(validator),
+        _ => Lookup::Ambiguous(matches.into_iter().take(MAX_CANDIDATES).collect()),
+    }
+}
+
+/// Finds the Celenium id of the validator named `name` from the network's validator list.
+///
+/// Names matching no moniker are `Ok(None)`, so the caller can try the indexer's search.
+pub(crate) async fn resolve(
+    blocks: &CelestiaSearchTool,
+    name: &str,
+) -> Result<Option<u64>, CelestiaSearchError> {
+    let validators = blocks.validator_list().await?;
+    match find(name, &validators) {
+        Lookup::Found(validator) => Ok(Some(validator.id)),
+        Lookup::Ambiguous(candidates) => Err(CelestiaSearchError::AmbiguousValidator {
+            name: name.to_string(),
+            candidates: candidates
+                .iter()
+                .map(|validator| validator.describe())
+                .collect::<Vec<_>>()
+                .join("; "),
+        }),
+        Lookup::NotFound => Ok(None),
+    }
+}
+
+/// Every validator the indexer lists, page by page.
+pub(crate) async fn fetch_all(
+    blocks: &CelestiaSearchTool,
+) -> Result<Arc<Vec<Listed>>, CelestiaSearchError> {
+    let mut validators = Vec::new();
+    for page in 0..MAX_PAGES {
+        let endpoint = format!(
+            "/validators?limit={}&offset={}",
+            PAGE_SIZE,
+            page * PAGE_SIZE
+        );
+        let data = blocks.fetch(&endpoint).await?;
+        let listed: Vec<Listed> =
+            Vec::deserialize(&data).map_err(|e| CelestiaSearchError::Deserialization {
+                url: endpoint,
+                reason: e.to_string(),
+            })?;
+        let last = listed.len() < PAGE_SIZE;
+        validators.extend(listed);
+        if last {
+            break;
+        }
+    }
+    Ok(Arc::new(validators))
+}
+
+/// The letters and digits of `text`, lowercased
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// How well the normalized `name` matches the normalized `moniker`, from 0 to 1
+fn score(name: &str, moniker: &str) -> f64 {
+    if name == moniker {
+        return 1.0;
+    }
+    if moniker.contains(name) {
+        return 0.9;
+    }
+    let longest = name.chars().count().max(moniker.chars().count());
+    1.0 - edit_distance(name, moniker) as f64 / longest as f64
+}
+
+/// The Levenshtein distance between `a` and `b`, in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
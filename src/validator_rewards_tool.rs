@@ -0,0 +1,224 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::amount::Utia;
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::fetcher::{self, DEFAULT_CONCURRENCY};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::price;
+use crate::validator_tool;
+
+/// The most recent blocks whose rewards are totalled by a single call.
+pub const MAX_BLOCKS: u64 = 500;
+
+/// The most commission changes listed.
+const LISTED_CHANGES: u64 = 10;
+
+/// The validator to break down the earnings of.
+#[derive(Deserialize)]
+pub struct ValidatorRewardsArgs {
+    /// The validator's moniker, its `celestiavaloper1...` address, or its Celenium id.
+    validator: String,
+    /// How many recent blocks to total rewards and commissions over.
+    #[serde(default = "default_blocks")]
+    blocks: u64,
+    /// The networks the validator is looked up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_blocks() -> u64 {
+    100
+}
+
+/// Breaks down a validator's commission rate, its history and the rewards it earns.
+pub struct ValidatorRewardsTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl ValidatorRewardsTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn breakdown(&self, args: ValidatorRewardsArgs) -> Result<String, CelestiaSearchError> {
+        let window = args.blocks.clamp(1, MAX_BLOCKS);
+        self.blocks
+            .across(&args.network, |blocks| {
+                breakdown(blocks, &args.validator, window)
+            })
+            .await
+    }
+}
+
+/// Describes the validator's commission and earnings, and its estimated share of the rewards
+/// paid over the last `window` blocks
+async fn breakdown(
+    blocks: &CelestiaSearchTool,
+    validator: &str,
+    window: u64,
+) -> Result<String, CelestiaSearchError> {
+    let id = validator_tool::resolve(blocks, validator).await?;
+    let (info, head) = futures::try_join!(
+        blocks.fetch(&format!("/validator/{}", id)),
+        blocks.fetch("/head"),
+    )?;
+
+    let moniker = info["moniker"].as_str().unwrap_or(validator);
+    let mut output = format!(
+        "Validator {} charges a {} commission (at most {}, changing by at most {} a day). \
+         It has earned {} in commission and {} in rewards so far.",
+        moniker,
+        percent(&info["rate"]),
+        percent(&info["max_rate"]),
+        percent(&info["max_change_rate"]),
+        utia(&info["commissions"]),
+        utia(&info["rewards"]),
+    );
+
+    // Commission changes are the validator's edit messages
+    if let Some(operator) = info["delegator"].as_str() {
+        let endpoint = format!(
+            "/address/{}/messages?msg_type=MsgEditValidator&limit={}",
+            operator, LISTED_CHANGES
+        );
+        let changes: Vec<String> = blocks
+            .fetch(&endpoint)
+            .await?
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|message| {
+                let rate = &message["data"]["CommissionRate"];
+                (!rate.is_null()).then(|| {
+                    format!(
+                        "{} at height {} ({})",
+                        percent(rate),
+                        message["height"],
+                        message["time"].as_str().unwrap_or("unknown time")
+                    )
+                })
+            })
+            .collect();
+        match changes.is_empty() {
+            true => output.push_str(" Its commission rate has not changed."),
+            false => output.push_str(&format!(
+                " Commission rate changes: {}.",
+                changes.join("; ")
+            )),
+        }
+    }
+
+    // Block stats only report chain-wide totals, so estimate the validator's part by its stake
+    let Some(height) = head["last_height"].as_u64() else {
+        return Ok(output);
+    };
+    let from = height.saturating_sub(window - 1).max(1);
+    let range = fetcher::fetch_range_partial(blocks, from..=height, DEFAULT_CONCURRENCY).await?;
+    let rows = &range.rows;
+    let rewards: Utia = rows.iter().map(|(_, stats)| stats.rewards).sum();
+    let commissions: Utia = rows.iter().map(|(_, stats)| stats.commissions).sum();
+    output.push_str(&format!(
+        " Over blocks {} to {} the chain paid {} in rewards and {} in commissions",
+        from,
+        height,
+        price::describe(rewards, None),
+        price::describe(commissions, None),
+    ));
+
+    let stake = number(&info["stake"]);
+    let total_stake = number(&head["total_stake"]);
+    match (stake, total_stake) {
+        (Some(stake), Some(total_stake)) if total_stake > 0.0 => {
+            let share = stake / total_stake;
+            output.push_str(&format!(
+                "; with {:.2}% of the stake, about {} of the commissions went to this validator.",
+                share * 100.0,
+                price::describe_utia(&format!("{:.0}", commissions.as_f64() * share), None)
+            ));
+        }
+        _ => output.push('.'),
+    }
+
+    Ok(range.annotate(output))
+}
+
+/// Numbers, and strings holding numbers (as Celenium reports amounts and rates)
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// A fraction such as `"0.05"` as a percentage
+fn percent(value: &Value) -> String {
+    match number(value) {
+        Some(rate) => format!("{:.2}%", rate * 100.0),
+        None => "an unknown rate".to_string(),
+    }
+}
+
+fn utia(value: &Value) -> String {
+    match value {
+        Value::String(amount) => price::describe_utia(amount, None),
+        Value::Number(amount) => price::describe_utia(&amount.to_string(), None),
+        _ => "an unknown amount".to_string(),
+    }
+}
+
+impl Tool for ValidatorRewardsTool {
+    const NAME: &'static str = "validator_rewards";
+
+    type Args = ValidatorRewardsArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Break down a Celestia validator's earnings: its commission rate and how it \
+                 changed over time, the commission and rewards it has accumulated, and its \
+                 estimated share of the rewards and commissions paid over up to {} recent \
+                 blocks.",
+                MAX_BLOCKS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "validator": {
+                        "type": "string",
+                        "description": "The validator's name (moniker), operator address or \
+                                        Celenium id; a name matching several validators returns \
+                                        them to choose from",
+                        "examples": ["P2P.ORG", "celestiavaloper1q3v5cugc8cdpud87u4zwy0a74uxkk6u4q4gx4p", "12"],
+                    },
+                    "blocks": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_BLOCKS,
+                        "description": "How many recent blocks to total rewards over",
+                        "examples": [100],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["validator"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.breakdown(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
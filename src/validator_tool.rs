@@ -0,0 +1,219 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+use crate::validator_directory;
+
+/// The most recent blocks checked for signatures by a single call.
+pub const MAX_BLOCKS: u64 = 1000;
+
+/// Missed heights beyond this many are counted but not listed.
+const LISTED_MISSES: usize = 10;
+
+/// The prefix of validator operator addresses.
+const OPERATOR_PREFIX: &str = "celestiavaloper1";
+
+/// The validator to report on.
+#[derive(Deserialize)]
+pub struct ValidatorArgs {
+    /// The validator's moniker, its `celestiavaloper1...` address, or its Celenium id.
+    validator: String,
+    /// How many recent blocks to check for the validator's signature.
+    #[serde(default = "default_blocks")]
+    blocks: u64,
+    /// The networks the validator is looked up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+fn default_blocks() -> u64 {
+    100
+}
+
+/// Reports a validator's recent signing performance and jailing history.
+pub struct ValidatorTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl ValidatorTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn report(&self, args: ValidatorArgs) -> Result<String, CelestiaSearchError> {
+        let limit = args.blocks.clamp(1, MAX_BLOCKS);
+        self.blocks
+            .across(&args.network, |blocks| {
+                report(blocks, &args.validator, limit)
+            })
+            .await
+    }
+}
+
+/// Describes the validator's uptime over the last `limit` blocks and its jails.
+pub(crate) async fn report(
+    blocks: &CelestiaSearchTool,
+    validator: &str,
+    limit: u64,
+) -> Result<String, CelestiaSearchError> {
+    let id = resolve(blocks, validator).await?;
+    let (info, uptime, jails) = futures::try_join!(
+        blocks.fetch(&format!("/validator/{}", id)),
+        blocks.fetch(&format!("/validator/{}/uptime?limit={}", id, limit)),
+        blocks.fetch(&format!("/validator/{}/jails", id)),
+    )?;
+
+    let moniker = info["moniker"].as_str().unwrap_or(validator);
+    let status = if info["jailed"].as_bool().unwrap_or(false) {
+        "currently jailed"
+    } else {
+        "active"
+    };
+    let mut output = match info["address"].as_str() {
+        Some(address) => format!("Validator {} ({}) is {}.", moniker, address, status),
+        None => format!("Validator {} is {}.", moniker, status),
+    };
+
+    let signed: Vec<(u64, bool)> = uptime["blocks"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|block| Some((block["height"].as_u64()?, block["signed"].as_bool()?)))
+        .collect();
+    if !signed.is_empty() {
+        let missed: Vec<u64> = signed
+            .iter()
+            .filter(|(_, signed)| !signed)
+            .map(|(height, _)| *height)
+            .collect();
+        output.push_str(&format!(
+            " It signed {} of the last {} blocks ({:.2}% uptime)",
+            signed.len() - missed.len(),
+            signed.len(),
+            (signed.len() - missed.len()) as f64 / signed.len() as f64 * 100.0
+        ));
+        if missed.is_empty() {
+            output.push('.');
+        } else {
+            let listed: Vec<String> = missed
+                .iter()
+                .take(LISTED_MISSES)
+                .map(u64::to_string)
+                .collect();
+            let more = missed.len().saturating_sub(LISTED_MISSES);
+            output.push_str(&format!("; it missed heights {}", listed.join(", ")));
+            if more > 0 {
+                output.push_str(&format!(" and {} more", more));
+            }
+            output.push('.');
+        }
+    }
+
+    let jails: Vec<&Value> = jails.as_array().into_iter().flatten().collect();
+    if jails.is_empty() {
+        output.push_str(" It has never been jailed.");
+    } else {
+        let events: Vec<String> = jails
+            .iter()
+            .map(|jail| {
+                format!(
+                    "at height {} on {} ({})",
+                    jail["height"],
+                    jail["time"].as_str().unwrap_or("an unknown date"),
+                    jail["reason"].as_str().unwrap_or("unknown reason")
+                )
+            })
+            .collect();
+        output.push_str(&format!(
+            " It has been jailed {} time(s): {}.",
+            jails.len(),
+            events.join("; ")
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Finds the Celenium id of a validator given by id, operator address or moniker.
+pub(crate) async fn resolve(
+    blocks: &CelestiaSearchTool,
+    validator: &str,
+) -> Result<u64, CelestiaSearchError> {
+    let validator = validator.trim();
+    if let Ok(id) = validator.parse() {
+        return Ok(id);
+    }
+    if !validator.starts_with(OPERATOR_PREFIX) {
+        if let Some(id) = validator_directory::resolve(blocks, validator).await? {
+            return Ok(id);
+        }
+    }
+
+    let unknown = || CelestiaSearchError::UnknownValidator(validator.to_string());
+    let results = match blocks.fetch(&format!("/search?query={}", validator)).await {
+        Err(CelestiaSearchError::NotFound { .. }) => return Err(unknown()),
+        results => results?,
+    };
+    results
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|result| result["type"] == "validator")
+        .and_then(|result| result["result"]["id"].as_u64())
+        .ok_or_else(unknown)
+}
+
+impl Tool for ValidatorTool {
+    const NAME: &'static str = "validator_uptime";
+
+    type Args = ValidatorArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Report a Celestia validator's recent signing performance (uptime and missed \
+                 blocks over up to {} recent blocks) and its jailing history, e.g. to judge \
+                 whether it is reliable before delegating to it.",
+                MAX_BLOCKS
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "validator": {
+                        "type": "string",
+                        "description": "The validator's name (moniker), operator address or \
+                                        Celenium id; a name matching several validators returns \
+                                        them to choose from",
+                        "examples": ["P2P.ORG", "celestiavaloper1q3v5cugc8cdpud87u4zwy0a74uxkk6u4q4gx4p", "12"],
+                    },
+                    "blocks": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_BLOCKS,
+                        "description": "How many recent blocks to check for its signature",
+                        "examples": [100],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["validator"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.report(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,216 @@
+//! Checks the numbers an answer quotes against the tool results it was written from.
+//!
+//! A quoted number is verified if a number of the tool's result (or the question) rounds to it
+//! at the precision it is quoted with, as is, converted between utia and TIA, or between a
+//! fraction and a percentage. Numbers that aren't are reported, and with
+//! [`Verification::Correct`] replaced by the closest value of the result when one is near
+//! enough to be what the model meant to quote.
+
+/// How the numbers of answers restated by the model are checked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Verification {
+    /// Numbers are not checked
+    Off,
+    /// Numbers missing from the tool results are reported
+    #[default]
+    Warn,
+    /// Numbers close to a tool result's are replaced by it, and the others reported
+    Correct,
+}
+
+/// Scales a quoted number may differ from the result's by: utia and TIA, fractions and
+/// percentages
+const SCALES: [f64; 5] = [1.0, 1e-6, 1e6, 100.0, 0.01];
+
+/// How far off, relative to the result's value, a misquoted number may be corrected
+const MAX_CORRECTION: f64 = 0.1;
+
+/// Numbers with fewer significant digits are too ambiguous to correct
+const MIN_CORRECTED_DIGITS: usize = 3;
+
+/// A number quoted in text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quoted {
+    /// The number as written, e.g. `2,340,972`
+    pub text: String,
+    pub value: f64,
+    /// Digits after the decimal point
+    pub decimals: usize,
+    /// Byte offset of the number in the text
+    pub start: usize,
+}
+
+/// A quoted number no tool result backs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Discrepancy {
+    pub quoted: Quoted,
+    /// The result's number the quoted one is most likely a misreading of, if any
+    pub closest: Option<f64>,
+}
+
+/// The numbers in `text`, leaving out those within words such as hashes and addresses.
+pub fn numbers(text: &str) -> Vec<Quoted> {
+    let bytes = text.as_bytes();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let starts_number = bytes[i].is_ascii_digit()
+            && (i == 0 || !(bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_'));
+        if !starts_number {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let mut end = i;
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+        // Thousands separators, only in groups of three
+        while end + 3 < bytes.len()
+            && bytes[end] == b','
+            && bytes[end + 1..=end + 3].iter().all(u8::is_ascii_digit)
+            && bytes.get(end + 4).is_none_or(|b| !b.is_ascii_digit())
+        {
+            end += 4;
+        }
+        let mut decimals = 0;
+        if bytes.get(end) == Some(&b'.') && bytes.get(end + 1).is_some_and(u8::is_ascii_digit) {
+            end += 1;
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+                decimals += 1;
+            }
+        }
+
+        let within_word = bytes
+            .get(end)
+            .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_');
+        if within_word {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            continue;
+        }
+
+        let quoted = &text[start..end];
+        if let Ok(value) = quoted.replace(',', "").parse() {
+            numbers.push(Quoted {
+                text: quoted.to_string(),
+                value,
+                decimals,
+                start,
+            });
+        }
+        i = end;
+    }
+    numbers
+}
+
+/// The numbers of `answer` that none of `sources` back.
+pub fn check(answer: &str, sources: &[&str]) -> Vec<Discrepancy> {
+    let known: Vec<f64> = sources
+        .iter()
+        .flat_map(|source| numbers(source))
+        .map(|quoted| quoted.value)
+        .collect();
+
+    numbers(answer)
+        .into_iter()
+        .filter(|quoted| !known.iter().any(|&value| backs(value, quoted)))
+        .map(|quoted| Discrepancy {
+            closest: closest(&known, &quoted),
+            quoted,
+        })
+        .collect()
+}
+
+impl Discrepancy {
+    /// The closest value, written as the quoted number is.
+    pub fn correction(&self) -> Option<String> {
+        self.closest.map(|value| format_like(value, &self.quoted))
+    }
+
+    /// Tells the user about the discrepancy in the answer written from `tool`'s result,
+    /// whether or not it was `corrected`.
+    pub fn describe(&self, tool: &str, corrected: bool) -> String {
+        match (self.correction(), corrected) {
+            (Some(correction), true) => format!(
+                "corrected {} to {}, as the `{}` result has it",
+                self.quoted.text, correction, tool
+            ),
+            (Some(correction), false) => format!(
+                "{} is not in the `{}` result, which has {}",
+                self.quoted.text, tool, correction
+            ),
+            (None, _) => format!("{} is not in the `{}` result", self.quoted.text, tool),
+        }
+    }
+}
+
+/// `answer` with each discrepancy that has a closest value replaced by it.
+pub fn correct(answer: &str, discrepancies: &[Discrepancy]) -> String {
+    let mut corrected = answer.to_string();
+    // From the end, so that the offsets of earlier numbers still hold
+    for discrepancy in discrepancies.iter().rev() {
+        if let Some(value) = discrepancy.closest {
+            let quoted = &discrepancy.quoted;
+            let range = quoted.start..quoted.start + quoted.text.len();
+            corrected.replace_range(range, &format_like(value, quoted));
+        }
+    }
+    corrected
+}
+
+/// Whether `value` is `quoted`, at the precision it is quoted with and in any of the scales
+fn backs(value: f64, quoted: &Quoted) -> bool {
+    let tolerance = 0.5 * 10f64.powi(-(quoted.decimals as i32)) + 1e-9;
+    SCALES
+        .iter()
+        .any(|scale| (value * scale - quoted.value).abs() <= tolerance)
+}
+
+fn closest(known: &[f64], quoted: &Quoted) -> Option<f64> {
+    let digits = quoted.text.chars().filter(char::is_ascii_digit).count();
+    let leading_zeros = quoted
+        .text
+        .chars()
+        .take_while(|c| matches!(c, '0' | '.'))
+        .filter(|c| *c == '0')
+        .count();
+    let significant = digits - leading_zeros;
+    if significant < MIN_CORRECTED_DIGITS {
+        return None;
+    }
+    known
+        .iter()
+        .copied()
+        .filter(|value| *value != 0.0)
+        .map(|value| (value, ((quoted.value - value) / value).abs()))
+        .filter(|(_, off)| *off <= MAX_CORRECTION)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(value, _)| value)
+}
+
+/// `value` written as `quoted` is: with as many decimals, and thousands separators if it has them
+fn format_like(value: f64, quoted: &Quoted) -> String {
+    let formatted = format!("{:.*}", quoted.decimals, value);
+    if !quoted.text.contains(',') {
+        return formatted;
+    }
+    let (whole, fraction) = match formatted.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    match fraction {
+        Some(fraction) => format!("{}.{}", grouped, fraction),
+        None => grouped,
+    }
+}
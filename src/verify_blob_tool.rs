@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::inclusion::{self, Verdict, VerifyError};
+use crate::metrics::metrics;
+use crate::namespace_tool;
+use crate::node::NodeClient;
+
+/// The blob to verify.
+#[derive(Deserialize)]
+pub struct VerifyBlobArgs {
+    height: u64,
+    /// The blob's namespace in hex, with or without its leading version byte.
+    namespace: String,
+    /// The blob's share commitment, in base64 (as Celenium shows them) or hex.
+    commitment: String,
+}
+
+/// Verifies a blob's inclusion proof against the block's data root, using a celestia-node.
+pub struct VerifyBlobTool {
+    node: Arc<NodeClient>,
+}
+
+impl VerifyBlobTool {
+    pub fn new(node: Arc<NodeClient>) -> Self {
+        Self { node }
+    }
+
+    async fn verify(&self, args: VerifyBlobArgs) -> Result<String, VerifyError> {
+        let invalid = VerifyError::InvalidInput;
+        let namespace = namespace_tool::validate_namespace(&args.namespace)
+            .map_err(|e| invalid(e.to_string()))?;
+        let namespace = hex::decode(namespace).map_err(|e| invalid(e.to_string()))?;
+        let commitment = args.commitment.trim();
+        // Commitments are 32 bytes, so 64 characters long in hex and 44 in base64
+        let decoded = match commitment.len() {
+            64 => hex::decode(commitment).ok(),
+            _ => BASE64.decode(commitment).ok(),
+        };
+        let commitment = decoded.ok_or_else(|| {
+            invalid(format!(
+                "`{}` is not a base64 or hex commitment",
+                commitment
+            ))
+        })?;
+
+        let verdict = inclusion::verify(&self.node, args.height, &namespace, &commitment).await?;
+        Ok(match verdict {
+            Verdict::Included {
+                shares,
+                rows,
+                data_root,
+            } => format!(
+                "Blob {} at height {} is included: its {} share(s) in rows {} to {} verify \
+                 against the row roots, which hash to the block's data root {}.",
+                args.commitment,
+                args.height,
+                shares,
+                rows.start(),
+                rows.end(),
+                data_root
+            ),
+            Verdict::NotIncluded(reason) => format!(
+                "Blob {} at height {} could NOT be verified: {}.",
+                args.commitment, args.height, reason
+            ),
+        })
+    }
+}
+
+impl Tool for VerifyBlobTool {
+    const NAME: &'static str = "verify_blob";
+
+    type Args = VerifyBlobArgs;
+    type Output = String;
+    type Error = VerifyError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Verify that a blob is really included in a Celestia block: fetches its \
+                          inclusion proof from the connected celestia-node and checks it locally \
+                          against the block's data root. Needs the blob's height, namespace and \
+                          commitment."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Height of the block holding the blob",
+                        "examples": [2000000],
+                    },
+                    "namespace": {
+                        "type": "string",
+                        "description": "The namespace in hex (28 bytes, or 29 with the version byte)",
+                        "examples": ["0000000000000000000000000000000000000000000000000000000000"],
+                    },
+                    "commitment": {
+                        "type": "string",
+                        "description": "The blob's share commitment, in base64 or hex",
+                        "examples": ["0yVfnJ2ta2BFPMlnUfzQr6GZIOsKG3ozvfMXk6+7iAE="],
+                    },
+                },
+                "required": ["height", "namespace", "commitment"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.verify(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::time::Duration;
+
+use futures::{stream, Stream, StreamExt};
+use serde_json::{json, Value};
+
+use crate::alert::{Condition, ConditionError};
+use crate::amount::Utia;
+use crate::byte_size;
+use crate::celestia_search_tool::{
+    CelestiaResponseFields, CelestiaSearchError, CelestiaSearchTool,
+};
+use crate::network::Network;
+use crate::time;
+
+/// The delay between polls of the chain head when none is given, about one block time.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(6);
+
+/// The changes between blocks filters can be written against.
+pub const CHANGES: &[&str] = &[
+    "blobs_count_change",
+    "blobs_size_change",
+    "fee_change",
+    "fill_rate_change",
+    "new_namespaces",
+    "square_size_change",
+    "tx_count_change",
+];
+
+/// The changes denominated in utia, which accept a `TIA` or `utia` unit.
+const MONETARY_CHANGES: &[&str] = &["fee_change"];
+
+/// The most blobs listed by a single page of a block's blobs.
+const BLOBS_PAGE_SIZE: usize = 100;
+
+/// The most pages of a block's blobs fetched for its namespaces.
+const MAX_BLOB_PAGES: usize = 10;
+
+struct State {
+    next: Option<u64>,
+    head: u64,
+    polled: bool,
+    backoff: bool,
+}
+
+/// Streams the stats of every new block, starting at `start` (or the current head if `None`).
+///
+/// The chain head is polled every `interval` once the stream has caught up. Errors are yielded
+/// rather than ending the stream, and the failed step is retried after `interval`.
+pub fn blocks(
+    tool: &CelestiaSearchTool,
+    start: Option<u64>,
+    interval: Duration,
+) -> impl Stream<Item = Result<(u64, CelestiaResponseFields), CelestiaSearchError>> + '_ {
+    let state = State {
+        next: start,
+        head: 0,
+        polled: false,
+        backoff: false,
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if std::mem::take(&mut state.backoff) {
+                time::sleep(interval).await;
+            }
+
+            if let Some(height) = state.next.filter(|&height| height <= state.head) {
+                let result = tool.fetch_stats(height).await;
+                match result {
+                    Ok(_) => state.next = Some(height + 1),
+                    Err(_) => state.backoff = true,
+                }
+                return Some((result.map(|stats| (height, stats)), state));
+            }
+
+            // Caught up: wait for new blocks
+            if std::mem::replace(&mut state.polled, true) {
+                time::sleep(interval).await;
+            }
+            match tool.chain_head().await {
+                Ok(head) => {
+                    state.head = head;
+                    state.next.get_or_insert(head);
+                }
+                Err(e) => {
+                    state.backoff = true;
+                    return Some((Err(e), state));
+                }
+            }
+        }
+    })
+}
+
+/// How a block differs from the one before it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockDelta {
+    pub height: u64,
+    pub network: Network,
+    pub fee_change: Utia,
+    /// In the indexer's units, where 1 is a full block.
+    pub fill_rate_change: f64,
+    pub tx_count_change: i64,
+    pub blobs_count_change: i64,
+    pub blobs_size_change: i64,
+    pub square_size_change: i64,
+    /// The namespaces the block has blobs in that no earlier block of the watch had.
+    pub new_namespaces: Vec<String>,
+}
+
+impl BlockDelta {
+    /// The changes from `previous` to `current`, with the namespaces new in `current`.
+    pub fn between(
+        previous: &CelestiaResponseFields,
+        current: &CelestiaResponseFields,
+        new_namespaces: Vec<String>,
+    ) -> Self {
+        let change = |previous: u64, current: u64| current as i64 - previous as i64;
+        Self {
+            height: current.height,
+            network: current.network,
+            fee_change: current.fee - previous.fee,
+            fill_rate_change: current.fill_rate - previous.fill_rate,
+            tx_count_change: change(previous.tx_count, current.tx_count),
+            blobs_count_change: change(previous.blobs_count, current.blobs_count),
+            blobs_size_change: change(previous.blobs_size, current.blobs_size),
+            square_size_change: change(previous.square_size, current.square_size),
+            new_namespaces,
+        }
+    }
+
+    /// The changes as the JSON object filters are evaluated against, counting new namespaces.
+    fn fields(&self) -> Value {
+        json!({
+            "fee_change": self.fee_change,
+            "fill_rate_change": self.fill_rate_change,
+            "tx_count_change": self.tx_count_change,
+            "blobs_count_change": self.blobs_count_change,
+            "blobs_size_change": self.blobs_size_change,
+            "square_size_change": self.square_size_change,
+            "new_namespaces": self.new_namespaces.len(),
+        })
+    }
+}
+
+impl fmt::Display for BlockDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = |change: i64| if change < 0 { "-" } else { "+" };
+        write!(
+            f,
+            "Block {} on {}: fee {}{} utia, fill rate {:+.4}, {:+} txs, {:+} blobs ({}{}), \
+             square size {:+}",
+            self.height,
+            self.network,
+            if self.fee_change < Utia::default() {
+                ""
+            } else {
+                "+"
+            },
+            self.fee_change,
+            self.fill_rate_change,
+            self.tx_count_change,
+            self.blobs_count_change,
+            sign(self.blobs_size_change),
+            byte_size::format(self.blobs_size_change.unsigned_abs()),
+            self.square_size_change
+        )?;
+        if !self.new_namespaces.is_empty() {
+            write!(f, ", new namespaces: {}", self.new_namespaces.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Decides which block deltas are worth showing.
+pub trait DeltaFilter {
+    fn keep(&self, delta: &BlockDelta) -> bool;
+}
+
+impl<F: Fn(&BlockDelta) -> bool> DeltaFilter for F {
+    fn keep(&self, delta: &BlockDelta) -> bool {
+        self(delta)
+    }
+}
+
+/// Keeps the deltas for which any of its conditions holds, such as `fee_change > 1 TIA` or
+/// `new_namespaces > 0`, or every delta if it has none.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChangeFilter {
+    conditions: Vec<Condition>,
+}
+
+impl ChangeFilter {
+    pub fn parse<S: AsRef<str>>(conditions: &[S]) -> Result<Self, ConditionError> {
+        let conditions = conditions
+            .iter()
+            .map(|condition| {
+                Condition::parse_over(
+                    condition.as_ref(),
+                    CHANGES,
+                    MONETARY_CHANGES,
+                    ConditionError::UnknownChange,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { conditions })
+    }
+}
+
+impl DeltaFilter for ChangeFilter {
+    fn keep(&self, delta: &BlockDelta) -> bool {
+        let fields = delta.fields();
+        self.conditions.is_empty()
+            || self
+                .conditions
+                .iter()
+                .any(|condition| condition.evaluate_value(&fields).is_some())
+    }
+}
+
+struct DeltaState<S, F> {
+    blocks: S,
+    filter: F,
+    previous: Option<CelestiaResponseFields>,
+    seen: HashSet<String>,
+}
+
+/// Streams how every new block differs from the one before it, starting at `start` (or the
+/// current head if `None`), keeping the deltas `filter` keeps.
+///
+/// The first block only sets the baseline, so the first delta is the block after it; its
+/// namespaces count as seen. Errors are yielded rather than ending the stream, as with
+/// [`blocks`]; a block whose blobs couldn't be listed yields the error in place of its delta,
+/// but still becomes the baseline for the next.
+pub fn deltas<'a>(
+    tool: &'a CelestiaSearchTool,
+    start: Option<u64>,
+    interval: Duration,
+    filter: impl DeltaFilter + 'a,
+) -> impl Stream<Item = Result<BlockDelta, CelestiaSearchError>> + 'a {
+    let state = DeltaState {
+        blocks: Box::pin(blocks(tool, start, interval)),
+        filter,
+        previous: None,
+        seen: HashSet::new(),
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            let (height, stats) = match state.blocks.next().await? {
+                Ok(block) => block,
+                Err(e) => return Some((Err(e), state)),
+            };
+            let namespaces = match stats.blobs_count {
+                0 => Ok(Vec::new()),
+                _ => namespaces(tool, height).await,
+            };
+            let previous = state.previous.replace(stats);
+            let namespaces = match namespaces {
+                Ok(namespaces) => namespaces,
+                Err(e) => return Some((Err(e), state)),
+            };
+            let new_namespaces: Vec<String> = namespaces
+                .into_iter()
+                .filter(|namespace| state.seen.insert(namespace.clone()))
+                .collect();
+            let Some(previous) = previous else {
+                continue;
+            };
+
+            let current = state.previous.as_ref().expect("the block was just kept");
+            let delta = BlockDelta::between(&previous, current, new_namespaces);
+            if state.filter.keep(&delta) {
+                return Some((Ok(delta), state));
+            }
+        }
+    })
+}
+
+/// The namespaces (version and id, in hex) the block at `height` has blobs in, in order
+async fn namespaces(
+    tool: &CelestiaSearchTool,
+    height: u64,
+) -> Result<Vec<String>, CelestiaSearchError> {
+    let mut namespaces: Vec<String> = Vec::new();
+    for page in 0..MAX_BLOB_PAGES {
+        let endpoint = format!(
+            "/block/{}/blobs?limit={}&offset={}",
+            height,
+            BLOBS_PAGE_SIZE,
+            page * BLOBS_PAGE_SIZE
+        );
+        let blobs = tool.fetch(&endpoint).await?;
+        let blobs = blobs.as_array().map(Vec::as_slice).unwrap_or_default();
+        for blob in blobs {
+            let namespace = &blob["namespace"];
+            let Some(id) = namespace["namespace_id"].as_str() else {
+                continue;
+            };
+            let version = namespace["version"].as_u64().unwrap_or(0);
+            let namespace = format!("{:02x}{}", version, id);
+            if !namespaces.contains(&namespace) {
+                namespaces.push(namespace);
+            }
+        }
+        if blobs.len() < BLOBS_PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(namespaces)
+}
@@ -0,0 +1,354 @@
+//! Block and transaction payloads pushed by explorers and indexers, for alerting without polling
+//! the chain head.
+//!
+//! Payloads are posted to `/webhook` as JSON, in any of the shapes indexers send:
+//!
+//! - a block's stats, as `/block/{height}/stats` returns them, with its `height`;
+//! - a block, as `/block/{height}?stats=true` returns it, with the stats under `stats`;
+//! - a transaction, with the `height` of the block it landed in and its `hash`;
+//! - any of these under a `block`, `tx` or `data` key, or a list of them (or under `blocks` or
+//!   `txs`).
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::access::constant_time_eq;
+use crate::celestia_search_tool::CelestiaResponseFields;
+use crate::network::Network;
+
+/// The path payloads are posted to.
+pub const PATH: &str = "/webhook";
+
+/// How many bytes a payload may take.
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// How many bytes the request line and headers may take.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// How long a client may take to send its whole request, so stalled connections aren't held.
+pub const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many heights [`Delivered`] remembers.
+const DELIVERED_HEIGHTS: usize = 1024;
+
+/// Fields only block stats carry, telling them apart from a transaction's.
+const BLOCK_FIELDS: &[&str] = &[
+    "blobs_size",
+    "block_time",
+    "bytes_in_block",
+    "fill_rate",
+    "square_size",
+    "tx_count",
+];
+
+/// Captures the errors that may occur while reading a payload.
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("The payload is not JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("The payload holds no block or transaction (expected an object with a `height`)")]
+    Unrecognized,
+    #[error("Invalid block stats at height {height}: {reason}")]
+    InvalidStats { height: u64, reason: String },
+}
+
+/// A block or transaction reported by a webhook.
+pub enum Payload {
+    /// The stats of a block, as the alert conditions are evaluated against
+    Block(CelestiaResponseFields),
+    /// A transaction, whose block's stats have to be fetched
+    Tx { height: u64, hash: Option<String> },
+}
+
+impl Payload {
+    /// The height of the block the payload is about.
+    pub fn height(&self) -> u64 {
+        match self {
+            Self::Block(stats) => stats.height,
+            Self::Tx { height, .. } => *height,
+        }
+    }
+}
+
+/// Reads the blocks and transactions of a payload of `network`, in the order it lists them.
+pub fn normalize(body: &Value, network: Network) -> Result<Vec<Payload>, WebhookError> {
+    let mut payloads = Vec::new();
+    collect(body, network, &mut payloads)?;
+    match payloads.is_empty() {
+        true => Err(WebhookError::Unrecognized),
+        false => Ok(payloads),
+    }
+}
+
+fn collect(value: &Value, network: Network, out: &mut Vec<Payload>) -> Result<(), WebhookError> {
+    if let Some(items) = value.as_array() {
+        for item in items {
+            collect(item, network, out)?;
+        }
+        return Ok(());
+    }
+    let Some(object) = value.as_object() else {
+        return Err(WebhookError::Unrecognized);
+    };
+    for key in ["data", "block", "blocks"] {
+        if let Some(inner) = object.get(key).filter(|inner| !inner.is_null()) {
+            return collect(inner, network, out);
+        }
+    }
+    for key in ["tx", "txs"] {
+        if let Some(inner) = object.get(key).filter(|inner| !inner.is_null()) {
+            return collect_txs(inner, out);
+        }
+    }
+
+    let height = object
+        .get("height")
+        .and_then(height)
+        .ok_or(WebhookError::Unrecognized)?;
+    let stats = match object.get("stats").filter(|stats| stats.is_object()) {
+        // A block carries its stats, without their height
+        Some(stats) => Some(stats),
+        None if BLOCK_FIELDS.iter().any(|field| object.contains_key(*field)) => Some(value),
+        None => None,
+    };
+    match stats {
+        Some(stats) => {
+            let mut stats = CelestiaResponseFields::from_json(stats).map_err(|e| {
+                WebhookError::InvalidStats {
+                    height,
+                    reason: e.to_string(),
+                }
+            })?;
+            stats.height = height;
+            stats.network = network;
+            out.push(Payload::Block(stats));
+        }
+        None => out.push(tx(object, height)),
+    }
+    Ok(())
+}
+
+fn collect_txs(value: &Value, out: &mut Vec<Payload>) -> Result<(), WebhookError> {
+    if let Some(items) = value.as_array() {
+        for item in items {
+            collect_txs(item, out)?;
+        }
+        return Ok(());
+    }
+    let object = value.as_object().ok_or(WebhookError::Unrecognized)?;
+    let height = object
+        .get("height")
+        .and_then(height)
+        .ok_or(WebhookError::Unrecognized)?;
+    out.push(tx(object, height));
+    Ok(())
+}
+
+fn tx(object: &serde_json::Map<String, Value>, height: u64) -> Payload {
+    Payload::Tx {
+        height,
+        hash: object
+            .get("hash")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+/// Heights come as numbers from some indexers and strings from others; the genesis height
+/// isn't a block anyone would push
+fn height(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|height| height.parse().ok()))
+        .filter(|&height| height > 0)
+}
+
+/// The heights already checked, so that redelivered payloads and the other transactions of a
+/// block don't alert again.
+#[derive(Debug, Default)]
+pub struct Delivered {
+    heights: BTreeSet<u64>,
+}
+
+impl Delivered {
+    /// Whether `height` was already checked.
+    pub fn contains(&self, height: u64) -> bool {
+        self.heights.contains(&height)
+    }
+
+    /// Records `height` as checked, returning whether it's the first time. Only the most recent
+    /// heights are remembered.
+    pub fn first(&mut self, height: u64) -> bool {
+        if !self.heights.insert(height) {
+            return false;
+        }
+        if self.heights.len() > DELIVERED_HEIGHTS {
+            self.heights.pop_first();
+        }
+        true
+    }
+}
+
+/// Accepts payloads of `network` posted to [`PATH`] until the process exits, passing them on to
+/// `payloads` in the order they arrive.
+///
+/// With a `secret`, only requests sending it as `Authorization: Bearer <secret>` are accepted.
+/// Payloads are acknowledged with `202 Accepted` once read, before any alert is checked.
+pub async fn serve(
+    listener: TcpListener,
+    network: Network,
+    secret: Option<String>,
+    payloads: mpsc::Sender<Payload>,
+) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let (secret, payloads) = (secret.clone(), payloads.clone());
+        tokio::spawn(async move {
+            let _ = handle(stream, network, secret.as_deref(), &payloads).await;
+        });
+    }
+}
+
+async fn handle(
+    mut stream: TcpStream,
+    network: Network,
+    secret: Option<&str>,
+    payloads: &mpsc::Sender<Payload>,
+) -> std::io::Result<()> {
+    let read = tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await;
+    let (status, body) = match read {
+        Err(_) => ("408 Request Timeout", None),
+        Ok(read) => match read? {
+            Err(status) => (status, None),
+            Ok(request) => respond(request, network, secret, payloads).await,
+        },
+    };
+
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: \
+         close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await
+}
+
+async fn respond(
+    request: Request,
+    network: Network,
+    secret: Option<&str>,
+    payloads: &mpsc::Sender<Payload>,
+) -> (&'static str, Option<Value>) {
+    if request.path != PATH {
+        return ("404 Not Found", None);
+    }
+    if request.method != "POST" {
+        return ("405 Method Not Allowed", None);
+    }
+    if let Some(secret) = secret {
+        let token = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if !token.is_some_and(|token| constant_time_eq(token.as_bytes(), secret.as_bytes())) {
+            return ("401 Unauthorized", None);
+        }
+    }
+
+    let normalized = serde_json::from_slice(&request.body)
+        .map_err(WebhookError::from)
+        .and_then(|body| normalize(&body, network));
+    match normalized {
+        Ok(normalized) => {
+            let accepted = normalized.len();
+            for payload in normalized {
+                if payloads.send(payload).await.is_err() {
+                    return ("503 Service Unavailable", None);
+                }
+            }
+            ("202 Accepted", Some(json!({ "accepted": accepted })))
+        }
+        Err(e) => ("400 Bad Request", Some(json!({ "error": e.to_string() }))),
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    /// Header names are lowercased
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Reads a request with a `Content-Length`, or the status to refuse it with
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Result<Request, &'static str>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Ok(Err("431 Request Header Fields Too Large"));
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(Err("400 Bad Request"));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Ok(Err("400 Bad Request"));
+    };
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.split('?').next().unwrap_or(path).to_string(),
+        headers,
+        body: buf[head_end + 4..].to_vec(),
+    };
+    if request.method != "POST" {
+        return Ok(Ok(request));
+    }
+    let Some(length) = request
+        .header("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+    else {
+        return Ok(Err("411 Length Required"));
+    };
+    if length > MAX_BODY_BYTES {
+        return Ok(Err("413 Payload Too Large"));
+    }
+    while request.body.len() < length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(Err("400 Bad Request"));
+        }
+        request.body.extend_from_slice(&chunk[..n]);
+    }
+    request.body.truncate(length);
+    Ok(Ok(request))
+}
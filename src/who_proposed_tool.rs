@@ -0,0 +1,120 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use crate::metrics::metrics;
+use crate::network::{self, Network};
+
+/// The block whose proposer to look up.
+#[derive(Deserialize)]
+pub struct WhoProposedArgs {
+    /// The height of the block.
+    height: u64,
+    /// The networks to look the block up on, instead of the configured one.
+    #[serde(default, deserialize_with = "network::deserialize_networks")]
+    network: Vec<Network>,
+}
+
+/// Names the validator that proposed a block, with its operator and consensus addresses.
+pub struct WhoProposedTool {
+    blocks: CelestiaSearchTool,
+}
+
+impl WhoProposedTool {
+    pub fn new(blocks: CelestiaSearchTool) -> Self {
+        Self { blocks }
+    }
+
+    async fn lookup(&self, args: WhoProposedArgs) -> Result<String, CelestiaSearchError> {
+        self.blocks
+            .across(&args.network, |blocks| describe(blocks, args.height))
+            .await
+    }
+}
+
+/// Describes the proposer of block `height`
+async fn describe(blocks: &CelestiaSearchTool, height: u64) -> Result<String, CelestiaSearchError> {
+    let Some(mut proposer) = blocks.fetch_proposer(height).await? else {
+        return Ok(format!(
+            "Celenium doesn't report the proposer of block {}.",
+            height
+        ));
+    };
+
+    // The block only names the validator, whose operator address comes with its details
+    if proposer.address.is_none() {
+        if let Some(id) = proposer.id {
+            let validator = blocks.fetch(&format!("/validator/{}", id)).await?;
+            proposer.address = validator["address"].as_str().map(str::to_string);
+        }
+    }
+
+    let addresses: Vec<String> = [
+        proposer
+            .address
+            .as_ref()
+            .map(|address| format!("operator address {}", address)),
+        proposer
+            .cons_address
+            .as_ref()
+            .filter(|_| proposer.moniker.is_some())
+            .map(|address| format!("consensus address {}", address)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let name = proposer.name().unwrap_or("an unnamed validator");
+    let mut output = format!(
+        "Block {} on {} was proposed by {}",
+        height,
+        blocks.network(),
+        name
+    );
+    if !addresses.is_empty() {
+        output.push_str(&format!(" ({})", addresses.join(", ")));
+    }
+    output.push('.');
+    Ok(output)
+}
+
+impl Tool for WhoProposedTool {
+    const NAME: &'static str = "who_proposed";
+
+    type Args = WhoProposedArgs;
+    type Output = String;
+    type Error = CelestiaSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Name the validator that proposed a Celestia block, with its moniker, \
+                          operator address and consensus address."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "height": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "The block height",
+                        "examples": [2000000],
+                    },
+                    "network": network::schema(),
+                },
+                "required": ["height"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = self.lookup(args).await;
+
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().tool_invocations.inc(&[Self::NAME, outcome]);
+
+        result
+    }
+}
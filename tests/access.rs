@@ -0,0 +1,40 @@
+use celestia_search_assistant::access::{AccessError, Allowed};
+use celestia_search_assistant::config::Config;
+
+#[test]
+fn keys_get_the_tools_of_their_role() {
+    let config = Config::parse(
+        "[access]\ndefault_role = \"public\"\n\n\
+         [access.roles]\npublic = [\"search_blocks\", \"tx_fee\"]\nadmin = [\"*\"]\n\
+         broken = [\"mempool\"]\n\n\
+         [access.keys]\nadmin-key = \"admin\"\npublic-key = \"public\"\nstale-key = \"removed\"",
+    )
+    .unwrap();
+    let access = &config.access;
+
+    assert_eq!(access.allowed(Some("admin-key")).unwrap(), Allowed::All);
+    let public = access.allowed(Some("public-key")).unwrap();
+    assert!(public.permits("tx_fee"));
+    assert!(!public.permits("mempool"));
+    assert_eq!(access.allowed(None).unwrap(), public);
+    assert!(matches!(
+        access.allowed(Some("admin-kez")),
+        Err(AccessError::UnknownKey)
+    ));
+    assert!(matches!(
+        access.allowed(Some("stale-key")),
+        Err(AccessError::UnknownRole(role)) if role == "removed"
+    ));
+
+    // Without a default role, a key is required
+    let config = Config::parse("[access.roles]\nadmin = [\"*\"]").unwrap();
+    assert!(matches!(
+        config.access.allowed(None),
+        Err(AccessError::MissingKey)
+    ));
+    // Without access control, every tool is allowed to everyone
+    assert_eq!(
+        Config::default().access.allowed(None).unwrap(),
+        Allowed::All
+    );
+}
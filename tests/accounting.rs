@@ -0,0 +1,28 @@
+use celestia_search_assistant::accounting::{BudgetError, Ledger, PriceTable, TokenUsage};
+
+#[test]
+fn refuses_turns_once_the_budget_is_spent() {
+    let prices = PriceTable::default();
+    let mut ledger = Ledger::new("gpt-4o");
+    assert!(ledger.within_budget(&prices, 0.01).is_ok());
+
+    // 2000 prompt tokens at $2.50 and 500 completion tokens at $10 a million
+    ledger.record(TokenUsage {
+        prompt_tokens: 2000,
+        completion_tokens: 500,
+    });
+    assert!(ledger.within_budget(&prices, 0.02).is_ok());
+    match ledger.within_budget(&prices, 0.01) {
+        Err(error @ BudgetError::Exceeded { .. }) => {
+            assert!(error.to_string().contains("spent $0.0100 on gpt-4o"))
+        }
+        other => panic!("expected the budget to be spent, got {:?}", other),
+    }
+
+    // Spending on a model without a price can't be checked
+    let ledger = Ledger::new("my-local-model");
+    assert!(matches!(
+        ledger.within_budget(&prices, 1.0),
+        Err(BudgetError::Unpriced(model)) if model == "my-local-model"
+    ));
+}
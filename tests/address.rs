@@ -0,0 +1,285 @@
+use celestia_search_assistant::address_activity_tool::AddressActivityTool;
+use celestia_search_assistant::address_tool::AddressTxsTool;
+use celestia_search_assistant::balance_history_tool::BalanceHistoryTool;
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::chart;
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::rest::RestClient;
+use celestia_search_assistant::top_accounts_tool::TopAccountsTool;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ADDRESS: &str = "celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p";
+
+#[tokio::test]
+async fn lists_and_pages_transactions() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/txs", ADDRESS)))
+        .and(query_param("limit", "2"))
+        .and(query_param("offset", "4"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "height": 120,
+                "time": "2024-06-02T00:00:00Z",
+                "hash": "AA11",
+                "fee": "2000",
+                "status": "success",
+                "message_types": ["MsgPayForBlobs"],
+            },
+            {
+                "height": 110,
+                "time": "2024-06-01T00:00:00Z",
+                "hash": "BB22",
+                "fee": "1000",
+                "status": "failed",
+                "message_types": ["MsgSend", "MsgPayForBlobs"],
+            },
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/txs", ADDRESS)))
+        .and(query_param("offset", "6"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+    let tool = AddressTxsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args =
+        serde_json::from_value(json!({ "address": ADDRESS, "limit": 2, "offset": 4 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        format!(
+            "Transactions 5 to 6 of address {ADDRESS}, newest first (1 succeeded, 1 failed), \
+             paying 3000 utia (0.003 TIA) in fees; messages: 2 MsgPayForBlobs, 1 MsgSend.\n\
+             - height 120 (2024-06-02T00:00:00Z): MsgPayForBlobs, fee 2000 utia, success, hash \
+             AA11\n\
+             - height 110 (2024-06-01T00:00:00Z): MsgSend + MsgPayForBlobs, fee 1000 utia, \
+             failed, hash BB22\n\
+             Older transactions may follow; call address_txs with cursor \
+             `address_txs:6:d7af4bd3` for the next page."
+        )
+    );
+
+    // The cursor continues the listing, but not another one
+    let args =
+        serde_json::from_value(json!({ "address": ADDRESS, "cursor": "address_txs:6:d7af4bd3" }))
+            .unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        format!("Address {ADDRESS} has no transactions beyond the newest 6.")
+    );
+    let args = serde_json::from_value(json!({
+        "address": ADDRESS,
+        "message_type": "MsgSend",
+        "cursor": "address_txs:6:d7af4bd3",
+    }))
+    .unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::InvalidCursor(_)));
+
+    let args = serde_json::from_value(json!({ "address": "celestiavaloper1abc" })).unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::InvalidAddress(_)));
+}
+
+#[tokio::test]
+async fn traces_balance_as_a_chartable_series() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 30000 })))
+        .mount(&server)
+        .await;
+    for (height, amount) in [(1200, "5000000"), (15600, "2500000"), (30000, "7250000")] {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/cosmos/bank/v1beta1/balances/{}/by_denom",
+                ADDRESS
+            )))
+            .and(header("x-cosmos-block-height", height.to_string().as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "balance": { "denom": "utia", "amount": amount },
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    let rest = RestClient::new([(Network::Mainnet, server.uri())].into_iter().collect());
+    let tool = BalanceHistoryTool::new(CelestiaSearchTool::with_base_url(&server.uri()), rest);
+
+    let args = serde_json::from_value(json!({ "address": ADDRESS, "samples": 3 })).unwrap();
+    let output = tool.call(args).await.unwrap();
+    let series = chart::detect(&output).unwrap();
+    assert_eq!(series.metric, "balance_tia");
+    assert_eq!(
+        series.points,
+        [
+            ("1200".to_string(), 5.0),
+            ("15600".to_string(), 2.5),
+            ("30000".to_string(), 7.25)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn buckets_activity_by_hour_and_day_for_charts() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/txs", ADDRESS)))
+        .and(query_param("limit", "3"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "time": "2024-06-02T14:20:00Z",
+                "fee": "3000",
+                "message_types": ["MsgPayForBlobs"],
+            },
+            {
+                "time": "2024-06-01T14:05:00Z",
+                "fee": "2000",
+                "message_types": ["MsgPayForBlobs"],
+            },
+            {
+                "time": "2024-06-01T09:30:00Z",
+                "fee": "1000",
+                "message_types": ["MsgSend"],
+            },
+        ])))
+        .mount(&server)
+        .await;
+    let tool = AddressActivityTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = json!({ "address": ADDRESS, "limit": 3, "by": "day" });
+    let output = tool
+        .call(serde_json::from_value(args).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&output).unwrap(),
+        json!([
+            { "day": "2024-06-01", "txs": 2, "txs_with_blobs": 1, "utia_fees": "3000" },
+            { "day": "2024-06-02", "txs": 1, "txs_with_blobs": 1, "utia_fees": "3000" },
+        ])
+    );
+
+    // Every hour of the day is a point, quiet ones included
+    let args = json!({ "address": ADDRESS, "limit": 3 });
+    let output = tool
+        .call(serde_json::from_value(args).unwrap())
+        .await
+        .unwrap();
+    let series = chart::detect(&output).unwrap();
+    assert_eq!(series.metric, "txs");
+    assert_eq!(series.points.len(), 24);
+    assert_eq!(series.points[9], ("09:00".to_string(), 1.0));
+    assert_eq!(series.points[14], ("14:00".to_string(), 2.0));
+    assert_eq!(series.points[15], ("15:00".to_string(), 0.0));
+}
+
+#[tokio::test]
+async fn chain_state_is_not_cached_offline() {
+    let server = MockServer::start().await;
+    let rest = RestClient::new([(Network::Mainnet, server.uri())].into_iter().collect()).offline();
+
+    let err = rest
+        .balance(Network::Mainnet, ADDRESS, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::NotCached { .. }));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn ranks_holders_and_fee_payers() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/address"))
+        .and(query_param("sort_by", "spendable"))
+        .and(query_param("limit", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "hash": "celestia1whale", "balance": { "spendable": "9000000000000" } },
+            { "hash": "celestia1dolphin", "balance": { "spendable": "40000000" } },
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/tx"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "fee": "1500", "signers": ["celestia1rollup"] },
+            { "fee": "700", "signers": ["celestia1user"] },
+            { "fee": "2500", "signers": ["celestia1rollup"] },
+        ])))
+        .mount(&server)
+        .await;
+    let tool = TopAccountsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "limit": 2 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The 2 accounts with the most spendable TIA:\n\
+         1. celestia1whale: 9000000000000 utia (9000000 TIA)\n\
+         2. celestia1dolphin: 40000000 utia (40 TIA)"
+    );
+
+    let args = serde_json::from_value(json!({ "by": "fees" })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The 2 accounts that paid the most fees in the latest 3 transactions:\n\
+         1. celestia1rollup: 4000 utia (0.004 TIA) over 2 transaction(s)\n\
+         2. celestia1user: 700 utia (0.0007 TIA) over 1 transaction(s)"
+    );
+}
+
+#[cfg(feature = "grpc")]
+#[tokio::test]
+async fn reads_balances_over_grpc_where_configured() {
+    use std::sync::Arc;
+
+    use celestia_search_assistant::amount::Utia;
+    use celestia_search_assistant::grpc::GrpcClient;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/cosmos/bank/v1beta1/balances/{}/by_denom",
+            ADDRESS
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "balance": { "denom": "utia", "amount": "5000000" },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Only mocha has a gRPC endpoint, and nothing listens on it
+    let grpc = GrpcClient::new(
+        [(Network::Mocha, "http://127.0.0.1:1".to_string())]
+            .into_iter()
+            .collect(),
+    )
+    .unwrap();
+    let rest = RestClient::new(
+        [
+            (Network::Mainnet, server.uri()),
+            (Network::Mocha, server.uri()),
+        ]
+        .into_iter()
+        .collect(),
+    )
+    .with_grpc(Arc::new(grpc));
+
+    let balance = rest.balance(Network::Mainnet, ADDRESS, None).await.unwrap();
+    assert_eq!(balance, Utia::new(5_000_000));
+    let err = rest
+        .balance(Network::Mocha, ADDRESS, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::HttpRequestFailed(_)));
+
+    let endpoints = [(Network::Mainnet, "not a url".to_string())];
+    assert!(GrpcClient::new(endpoints.into_iter().collect()).is_err());
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use celestia_search_assistant::alert::{check, Alert, Condition, ConditionError};
+use celestia_search_assistant::celestia_search_tool::{CelestiaResponseFields, CelestiaSearchTool};
+use celestia_search_assistant::watch;
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::common::{fixture, replay};
+
+#[test]
+fn parses_conditions() {
+    assert!(Condition::parse("fill_rate > 0.9").is_ok());
+    assert!(Condition::parse("fee>=5 TIA").is_ok());
+    assert!(Condition::parse("rewards < 100000 utia").is_ok());
+    assert!(matches!(
+        Condition::parse("fill_rate is high"),
+        Err(ConditionError::MissingOperator(_))
+    ));
+    assert_eq!(
+        Condition::parse("gas > 10"),
+        Err(ConditionError::UnknownField("gas".to_string()))
+    );
+    assert_eq!(
+        Condition::parse("fill_rate > 0.9 TIA"),
+        Err(ConditionError::InvalidUnit("TIA".to_string()))
+    );
+    assert_eq!(
+        Condition::parse("fee > lots"),
+        Err(ConditionError::InvalidThreshold("lots".to_string()))
+    );
+}
+
+#[test]
+fn fires_matching_conditions() {
+    let stats = CelestiaResponseFields::from_json(&fixture("block_stats_2000000")).unwrap();
+    let conditions: Vec<Condition> = [
+        "fill_rate > 0.7",
+        "fee >= 2 TIA",
+        "fee > 3 TIA",
+        "fill_rate != 0.7252",
+    ]
+    .iter()
+    .map(|c| Condition::parse(c).unwrap())
+    .collect();
+
+    assert_eq!(
+        check(&conditions, 2000000, &stats),
+        vec![
+            Alert {
+                height: 2000000,
+                condition: "fill_rate > 0.7".to_string(),
+                value: 0.7252,
+            },
+            Alert {
+                height: 2000000,
+                condition: "fee >= 2 TIA".to_string(),
+                value: 2340972.0,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn watch_follows_the_head() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 2000000 })))
+        .mount(&server)
+        .await;
+    replay(&server, "/block/1999999/stats", "block_stats_9999").await;
+    replay(&server, "/block/2000000/stats", "block_stats_2000000").await;
+
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+    let heights: Vec<u64> = watch::blocks(&tool, Some(1999999), Duration::from_millis(10))
+        .take(2)
+        .map(|block| block.unwrap().0)
+        .collect()
+        .await;
+    assert_eq!(heights, [1999999, 2000000]);
+}
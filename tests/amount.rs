@@ -0,0 +1,78 @@
+use celestia_search_assistant::amount::{AmountError, Utia};
+use celestia_search_assistant::price::describe_utia;
+use serde_json::json;
+
+#[test]
+fn parses_and_displays_exactly() {
+    for amount in [
+        "0",
+        "2340972",
+        "110326765.952",
+        "-5",
+        "0.000000000000000001",
+    ] {
+        assert_eq!(amount.parse::<Utia>().unwrap().to_string(), amount);
+    }
+    assert_eq!(
+        "10569812.770".parse::<Utia>().unwrap().to_string(),
+        "10569812.77"
+    );
+    assert_eq!(".5".parse::<Utia>().unwrap().to_string(), "0.5");
+
+    // Past a u64, which the block stats used to be parsed into
+    let supply = "18446744073709551616.5";
+    assert_eq!(supply.parse::<Utia>().unwrap().to_string(), supply);
+}
+
+#[test]
+fn reads_grpc_decimals() {
+    assert_eq!(
+        Utia::from_atomics("1500000000000000000")
+            .unwrap()
+            .to_string(),
+        "1.5"
+    );
+    assert_eq!(Utia::from_atomics("0").unwrap(), Utia::ZERO);
+    assert_eq!(
+        Utia::from_atomics("1.5"),
+        Err(AmountError::Invalid("1.5".to_string()))
+    );
+}
+
+#[test]
+fn rejects_malformed_amounts() {
+    for amount in ["", ".", "1e6", "12 TIA", "1.2.3"] {
+        assert_eq!(
+            amount.parse::<Utia>(),
+            Err(AmountError::Invalid(amount.to_string()))
+        );
+    }
+    let huge = "1".repeat(30);
+    assert_eq!(
+        huge.parse::<Utia>(),
+        Err(AmountError::Overflow(huge.clone()))
+    );
+}
+
+#[test]
+fn sums_and_converts_to_tia() {
+    let total: Utia = ["0.6", "0.6", "1000000"]
+        .iter()
+        .map(|amount| amount.parse::<Utia>().unwrap())
+        .sum();
+    assert_eq!(total.to_string(), "1000001.2");
+    assert_eq!(total.truncate(), Utia::new(1000001));
+    assert_eq!(total.tia(), "1.000001");
+    assert_eq!(Utia::new(-2500000).tia(), "-2.5");
+    assert_eq!(total - Utia::new(1000000), "1.2".parse().unwrap());
+
+    assert_eq!(Utia::from_value(&json!("42.5")), "42.5".parse().ok());
+    assert_eq!(Utia::from_value(&json!(42)), Some(Utia::new(42)));
+    assert_eq!(Utia::from_value(&json!(null)), None);
+    assert_eq!(serde_json::to_value(total).unwrap(), json!("1000001.2"));
+
+    assert_eq!(
+        describe_utia("110326765.952", None),
+        "110326765.952 utia (110.326766 TIA)"
+    );
+}
@@ -0,0 +1,427 @@
+use celestia_search_assistant::analytics::{
+    correlation, fit, histogram, percentile, summarize, Bucket, Trend,
+};
+use celestia_search_assistant::blob_fees_tool::BlobFeesTool;
+use celestia_search_assistant::blockspace_forecast_tool::BlockspaceForecastTool;
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::compare_blocks_tool::CompareBlocksTool;
+use celestia_search_assistant::fee_histogram_tool::FeeHistogramTool;
+use celestia_search_assistant::fill_rate_trend_tool::FillRateTrendTool;
+use celestia_search_assistant::gas_efficiency_tool::GasEfficiencyTool;
+use celestia_search_assistant::gas_stats_tool::GasStatsTool;
+use celestia_search_assistant::square_size_tool::SquareSizeTool;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn summarizes_series() {
+    let summary = summarize(&[0.1, 0.2, 0.3, 0.4]).unwrap();
+    assert_eq!(summary.min, 0.1);
+    assert_eq!(summary.max, 0.4);
+    assert!((summary.mean - 0.25).abs() < 1e-9);
+    assert!((summary.fitted_change - 0.3).abs() < 1e-9);
+    assert_eq!(summary.trend(0.01), Trend::Rising);
+
+    let flat = summarize(&[0.5, 0.5]).unwrap();
+    assert_eq!(flat.trend(0.01), Trend::Flat);
+    assert_eq!(summarize(&[0.3]).unwrap().fitted_change, 0.0);
+    assert!(summarize(&[]).is_none());
+}
+
+#[tokio::test]
+async fn fill_rate_trend_samples_recent_blocks() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 3000 })))
+        .mount(&server)
+        .await;
+    // 60%, 40% and 20% of the genesis limit of 1974272 bytes, whatever the indexer's fill rate
+    for (height, bytes) in [(1000, 1184563), (2000, 789709), (3000, 394854)] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "fill_rate": "0.1",
+                "bytes_in_block": bytes,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    // The window stops at the first block rather than sampling below it
+    let tool = FillRateTrendTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "samples": 5, "step": 1000 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Fill rate of 3 blocks from 1000 to 3000 (every 1000 block(s)): min 20.00%, max 60.00%, \
+         average 40.00%. The trend is falling (-40.00 percentage points across the window). Each \
+         block is measured against the block size limit in force at its height."
+    );
+}
+
+#[tokio::test]
+async fn fill_rates_past_the_recorded_limits_are_the_indexers() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 10 })))
+        .mount(&server)
+        .await;
+    // The chain has since raised its limits, at a height the bundled history doesn't hold
+    Mock::given(method("GET"))
+        .and(path("/constants"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "module": { "blob": { "gov_max_square_size": "128" } },
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/block/10/stats"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "fill_rate": "0.3",
+            "bytes_in_block": 1974272,
+        })))
+        .mount(&server)
+        .await;
+
+    let tool = FillRateTrendTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "samples": 1 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Fill rate of 1 blocks from 10 to 10 (every 1 block(s)): min 30.00%, max 30.00%, average \
+         30.00%. The trend is flat. No block size limit is recorded for these heights, so the \
+         indexer's fill rate is used."
+    );
+}
+
+#[tokio::test]
+async fn forecasts_when_blocks_would_fill_up() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 300000 })))
+        .mount(&server)
+        .await;
+    // Fill rates rise linearly as blob bytes double, every 100000 blocks of 6 seconds
+    for (height, fill_rate, blobs_size) in [
+        (100000, "0.2", 1000),
+        (200000, "0.4", 2000),
+        (300000, "0.6", 4000),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "fill_rate": fill_rate,
+                "blobs_size": blobs_size,
+                "block_time": 6000,
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    let tool = BlockspaceForecastTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "samples": 5, "step": 100000 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Blockspace forecast from 3 blocks from 100000 to 300000 (every 100000 block(s)), about \
+         13d 21h of history: the fill rate is 60.00% of the maximum square size on its fitted \
+         trend (average 40.00%), changing by +2.880 percentage points a day. Blob bytes per \
+         block change by +10.50% a day. At the linear fill-rate trend, blocks would fill it in \
+         about 13d 21h, around block 500000. At the compounding growth of blob bytes, in about \
+         5d 2h. This extrapolates the sampled trend only; raising the maximum square size would \
+         extend the runway."
+    );
+}
+
+#[tokio::test]
+async fn no_saturation_is_forecast_while_fill_rates_fall() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 20 })))
+        .mount(&server)
+        .await;
+    for (height, fill_rate) in [(10, "0.5"), (20, "0.3")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "fill_rate": fill_rate })),
+            )
+            .mount(&server)
+            .await;
+    }
+
+    let tool = BlockspaceForecastTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "samples": 2, "step": 10 })).unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(output.contains("the fill rate is 30.00%"), "{}", output);
+    assert!(output.contains("at this trend blocks never fill the maximum square size"));
+    assert!(!output.contains("Blob bytes"));
+}
+
+#[test]
+fn nearest_rank_percentiles() {
+    let samples: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+    assert_eq!(percentile(&samples, 50.0), Some(50.0));
+    assert_eq!(percentile(&samples, 99.0), Some(99.0));
+    assert_eq!(percentile(&samples, 0.0), Some(1.0));
+    assert_eq!(percentile(&[7.0], 90.0), Some(7.0));
+    assert_eq!(percentile(&[], 50.0), None);
+}
+
+#[tokio::test]
+async fn gas_percentiles_over_range() {
+    let server = MockServer::start().await;
+    for (height, gas_used) in [(10, "100"), (11, "200"), (12, "300"), (13, "400")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "gas_used": gas_used,
+                "gas_limit": "1000",
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    let tool = GasStatsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "from": 10, "to": 13 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Gas over blocks 10 to 13 (4 blocks): gas used p50 200, p90 400, p99 400; gas used/limit \
+         p50 20.00%, p90 40.00%, p99 40.00%."
+    );
+
+    let args = serde_json::from_value(json!({ "from": 13, "to": 10 })).unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::InvalidRange(_)));
+}
+
+#[tokio::test]
+async fn range_tools_leave_out_a_few_failing_blocks() {
+    let server = MockServer::start().await;
+    for height in 1..=20 {
+        let response = match height {
+            7 => ResponseTemplate::new(500),
+            _ => ResponseTemplate::new(200).set_body_json(json!({
+                "gas_used": "100",
+                "gas_limit": "1000",
+            })),
+        };
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+    }
+    let tool = GasStatsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "from": 1, "to": 20 })).unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(output.starts_with("Gas over blocks 1 to 20 (19 blocks):"));
+    assert!(output.ends_with(&format!(
+        "\nCaveat: 1 of the 20 blocks could not be fetched and are left out of these figures \
+         (heights 7): Indexer returned status 500 for {}/block/7/stats: ",
+        server.uri()
+    )));
+
+    // Too many failures to answer from what's left
+    let args = serde_json::from_value(json!({ "from": 5, "to": 9 })).unwrap();
+    assert!(matches!(
+        tool.call(args).await.unwrap_err(),
+        CelestiaSearchError::Status { status: 500, .. }
+    ));
+}
+
+#[tokio::test]
+async fn gas_efficiency_report() {
+    let server = MockServer::start().await;
+    for (height, gas_used, gas_limit, fee) in [
+        (10, "500", "1000", "2000"),
+        (11, "900", "1000", "1000"),
+        (12, "0", "0", "0"),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "gas_used": gas_used,
+                "gas_limit": gas_limit,
+                "fee": fee,
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    // Block 12 had no transactions, so it's left out of the report
+    let tool = GasEfficiencyTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "from": 10, "to": 12 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Gas efficiency over blocks 10 to 12 (2 blocks with transactions):\n\
+         - gas used: 1400 of 2000 requested (70.00%)\n\
+         - wasted gas: 600\n\
+         - fees: 3000 utia (0.003 TIA), of which an estimated 1100 utia (0.0011 TIA) (36.67%) \
+         paid for unused gas\n\
+         - least efficient block: 10 (50.00% of its gas used)\n\
+         - correlation between gas utilization and fees: -1.00"
+    );
+}
+
+#[test]
+fn pearson_correlation() {
+    let xs = [1.0, 2.0, 3.0, 4.0];
+    assert!((correlation(&xs, &[2.0, 4.0, 6.0, 8.0]).unwrap() - 1.0).abs() < 1e-9);
+    assert!((correlation(&xs, &[8.0, 6.0, 4.0, 2.0]).unwrap() + 1.0).abs() < 1e-9);
+    assert_eq!(correlation(&xs, &[5.0, 5.0, 5.0, 5.0]), None);
+    assert_eq!(correlation(&[1.0], &[1.0]), None);
+}
+
+#[test]
+fn least_squares_fit() {
+    let (intercept, slope) = fit(&[1.0, 2.0, 3.0], &[5.0, 7.0, 9.0]).unwrap();
+    assert!((intercept - 3.0).abs() < 1e-9);
+    assert!((slope - 2.0).abs() < 1e-9);
+    assert_eq!(fit(&[2.0, 2.0], &[1.0, 3.0]), None);
+    assert_eq!(fit(&[1.0], &[1.0]), None);
+}
+
+#[test]
+fn equal_width_histogram() {
+    let bucket = |low, high, count| Bucket { low, high, count };
+    assert_eq!(
+        histogram(&[1.0, 2.0, 2.5, 9.0, 5.0], 2),
+        [bucket(1.0, 5.0, 3), bucket(5.0, 9.0, 2)]
+    );
+    assert_eq!(histogram(&[3.0, 3.0], 4), [bucket(3.0, 3.0, 2)]);
+    assert!(histogram(&[], 4).is_empty());
+}
+
+#[tokio::test]
+async fn buckets_the_fees_of_a_blocks_transactions() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 100 })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/block/50/stats"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "tx_count": "4" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/tx"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "fee": "1000" },
+            { "fee": "2000" },
+            { "fee": "3000" },
+            { "fee": "10000" },
+        ])))
+        .mount(&server)
+        .await;
+    let tool = FeeHistogramTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "height": 50, "buckets": 3 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Fees of the 4 transaction(s) in block 50: min 1000 utia (0.001 TIA), median 2000 utia \
+         (0.002 TIA), p90 10000 utia (0.01 TIA), max 10000 utia (0.01 TIA), mean 4000 utia \
+         (0.004 TIA).\n\
+         - 1000 to 4000 utia: 3 transaction(s) (75.00%)\n\
+         - 4000 to 7000 utia: 0 transaction(s) (0.00%)\n\
+         - 7000 to 10000 utia: 1 transaction(s) (25.00%)"
+    );
+}
+
+#[tokio::test]
+async fn blob_fees_are_priced_per_byte() {
+    let server = MockServer::start().await;
+    for (height, blobs_size, fee) in [
+        (10, "1000", "1500"),
+        (11, "2000", "2500"),
+        (12, "4000", "4500"),
+        (13, "0", "700"),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "blobs_size": blobs_size,
+                "blobs_count": "1",
+                "fee": fee,
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    // Block 13 posted no blobs, so its fees don't price any data
+    let tool = BlobFeesTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "from": 10, "to": 13 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Blob fees over blocks 10 to 13 (3 blocks with blobs):\n\
+         - blob data: 6.84 KiB in 3 blob(s)\n\
+         - fees paid in those blocks: 8500 utia (0.0085 TIA)\n\
+         - effective price: 1.2143 utia per byte (1.2733 TIA per MiB)\n\
+         - marginal price: 1.0000 utia per byte, on top of 500 utia per block that doesn't grow \
+         with blob size\n\
+         - correlation between blob size and fees: 1.00\n\
+         - price per byte by block: 1.1250 to 1.5000 utia, trend falling (-29.03% across the \
+         range)"
+    );
+}
+
+#[tokio::test]
+async fn square_sizes_are_bucketed() {
+    let server = MockServer::start().await;
+    for (height, square_size, fee) in [(10, "8", "1000"), (11, "8", "3000"), (12, "64", "20000")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "square_size": square_size,
+                "fee": fee,
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    let tool = SquareSizeTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "from": 10, "to": 12 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Square sizes over blocks 10 to 12 (3 blocks):\n\
+         - 8x8: 2 block(s) (66.67%), average fee 2000 utia (0.002 TIA)\n\
+         - 64x64: 1 block(s) (33.33%), average fee 20000 utia (0.02 TIA)\n\
+         Blocks used 34.38% of the shares of the widest square allowed at their height on \
+         average.\n\
+         Correlation between square size and fees: 1.00."
+    );
+}
+
+#[tokio::test]
+async fn compare_blocks_diffs_each_field() {
+    let server = MockServer::start().await;
+    for (height, fee, fill_rate, blobs) in [(100, "2000", "0.25", "4"), (200, "3000", "0.2", "4")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "fee": fee,
+                "fill_rate": fill_rate,
+                "blobs_count": blobs,
+                "gas_used": "500",
+                "gas_limit": "1000",
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    let tool = CompareBlocksTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "from": 100, "to": 200 })).unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(output.starts_with("Block 200 compared to block 100:"));
+    assert!(output.contains("\n- fee: 2000 utia -> 3000 utia (+50.00%)"));
+    assert!(output.contains("\n- fill_rate: 0.25 -> 0.2 (-20.00%)"));
+    assert!(output.contains("\n- blobs_count: 4 -> 4 (unchanged)"));
+    assert!(output.contains("\n- gas_used: 500 -> 500 (unchanged)"));
+}
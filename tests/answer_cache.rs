@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use celestia_search_assistant::accounting::TokenUsage;
+use celestia_search_assistant::answer_cache::{self, AnswerCache, Freshness};
+use celestia_search_assistant::assistant::{ToolCall, Turn};
+use celestia_search_assistant::router::Route;
+use serde_json::json;
+
+fn turn(calls: Vec<(&str, serde_json::Value)>) -> Turn {
+    let mut steps: Vec<ToolCall> = calls
+        .into_iter()
+        .map(|(name, args)| ToolCall {
+            name: name.to_string(),
+            args,
+            output: Some("\"done\"".to_string()),
+            ..ToolCall::default()
+        })
+        .collect();
+    let tool_call = steps.pop();
+    Turn {
+        output: "An answer".to_string(),
+        tool_call,
+        steps,
+        usage: TokenUsage {
+            prompt_tokens: 100,
+            completion_tokens: 20,
+        },
+        warnings: Vec::new(),
+    }
+}
+
+#[test]
+fn normalizes_case_spacing_punctuation_and_digit_separators() {
+    assert_eq!(
+        answer_cache::normalize("  What was the FEE of block 2,000,000?? "),
+        "what was the fee of block 2000000"
+    );
+    assert_eq!(
+        answer_cache::normalize("what was the fee of\tblock 2000000"),
+        answer_cache::normalize("What was the fee of block 2_000_000.")
+    );
+    // Commas that don't separate digits are kept
+    assert_eq!(answer_cache::normalize("Blocks 1, 2"), "blocks 1, 2");
+}
+
+#[test]
+fn answers_about_past_blocks_are_immutable() {
+    assert_eq!(answer_cache::freshness(&turn(vec![])), Freshness::Immutable);
+    let historical = turn(vec![
+        ("search_blocks", json!({ "height": 10 })),
+        ("gas_percentiles", json!({ "from": 1, "to": 10 })),
+        ("tx_fee", json!({ "hash": "AA11" })),
+    ]);
+    assert_eq!(answer_cache::freshness(&historical), Freshness::Immutable);
+
+    for live in [
+        turn(vec![
+            ("search_blocks", json!({ "height": 10 })),
+            ("tia_price", json!({})),
+        ]),
+        turn(vec![("fill_rate_trend", json!({ "blocks": 100 }))]),
+        turn(vec![("countdown", json!({ "height": 3000000 }))]),
+    ] {
+        assert_eq!(answer_cache::freshness(&live), Freshness::Live);
+    }
+}
+
+#[test]
+fn answers_valued_in_fiat_are_live() {
+    let mut priced = turn(vec![("search_blocks", json!({ "height": 10 }))]);
+    priced.output = "The fee of block 10 was 2000 utia (0.002 TIA, ~$0.01).".to_string();
+    assert_eq!(answer_cache::freshness(&priced), Freshness::Live);
+
+    let mut priced = turn(vec![("search_blocks", json!({ "height": 10 }))]);
+    priced.tool_call.as_mut().unwrap().output =
+        Some("\"    The gas fee is: 2000 utia (0.002 TIA, ~$0.01)\"".to_string());
+    assert_eq!(answer_cache::freshness(&priced), Freshness::Live);
+
+    let mut in_usd = turn(vec![("tx_fee", json!({ "hash": "AA11" }))]);
+    in_usd.output = "It paid 0.01 USD.".to_string();
+    assert_eq!(answer_cache::freshness(&in_usd), Freshness::Live);
+}
+
+#[test]
+fn answers_with_failed_calls_are_live() {
+    let mut failed = turn(vec![
+        ("search_blocks", json!({ "height": 10 })),
+        ("tx_fee", json!({ "hash": "AA11" })),
+    ]);
+    failed.steps[0].output = Some("Error: The indexer is unavailable".to_string());
+    assert_eq!(answer_cache::freshness(&failed), Freshness::Live);
+
+    let mut timed_out = turn(vec![("fill_rate_trend", json!({ "from": 1, "to": 10 }))]);
+    timed_out.tool_call.as_mut().unwrap().output = Some(
+        "\"The `fill_rate_trend` tool timed out after 30s, so this data is missing.\"".to_string(),
+    );
+    assert_eq!(answer_cache::freshness(&timed_out), Freshness::Live);
+}
+
+#[test]
+fn serves_kept_answers_until_they_expire() {
+    // Live answers expire at once
+    let cache = AnswerCache::new(Duration::ZERO);
+    let historical = turn(vec![("search_blocks", json!({ "height": 10 }))]);
+    cache.insert(
+        "gpt-4o|mainnet",
+        "Fee of block 10?",
+        Route::Live,
+        &historical,
+    );
+    cache.insert(
+        "gpt-4o|mainnet",
+        "Price of TIA?",
+        Route::Live,
+        &turn(vec![("tia_price", json!({}))]),
+    );
+
+    let (route, kept) = cache.get("gpt-4o|mainnet", "fee of block 10").unwrap();
+    assert_eq!(route, Route::Live);
+    assert_eq!(kept.output, "An answer");
+    assert_eq!(kept.usage.total(), 0);
+    assert!(cache.get("gpt-4o|mainnet", "price of tia").is_none());
+    // Answers are only served under the settings they were given under
+    assert!(cache.get("gpt-4o|mocha", "fee of block 10").is_none());
+
+    // Planned calls of a dry run answered nothing
+    let mut planned = turn(vec![("search_blocks", json!({ "height": 11 }))]);
+    planned.tool_call.as_mut().unwrap().output = None;
+    cache.insert("gpt-4o|mainnet", "Fee of block 11?", Route::Live, &planned);
+    assert!(cache.get("gpt-4o|mainnet", "Fee of block 11?").is_none());
+}
@@ -0,0 +1,479 @@
+use celestia_search_assistant::assistant::{Assistant, GenerationParams};
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::session::Session;
+use rig::completion::ToolDefinition;
+use rig::providers::openai;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A chat completion response holding a plain message.
+fn message(content: &str) -> Value {
+    json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "gpt-4o-mini",
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "logprobs": null,
+            "finish_reason": "stop",
+        }],
+        "usage": { "prompt_tokens": 12, "total_tokens": 20 },
+    })
+}
+
+/// A chat completion response holding a single tool call.
+fn tool_call(name: &str, arguments: &str) -> Value {
+    json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "gpt-4o-mini",
+        "choices": [{
+            "index": 0,
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call-1",
+                    "type": "function",
+                    "function": { "name": name, "arguments": arguments },
+                }],
+            },
+            "logprobs": null,
+            "finish_reason": "tool_calls",
+        }],
+        "usage": { "prompt_tokens": 12, "total_tokens": 20 },
+    })
+}
+
+/// A chat completion response requesting several tool calls at once.
+fn tool_calls(calls: &[(&str, &str)]) -> Value {
+    let mut response = tool_call(calls[0].0, calls[0].1);
+    response["choices"][0]["message"]["tool_calls"] = calls
+        .iter()
+        .enumerate()
+        .map(|(i, (name, arguments))| {
+            json!({
+                "id": format!("call-{}", i + 1),
+                "type": "function",
+                "function": { "name": name, "arguments": arguments },
+            })
+        })
+        .collect();
+    response
+}
+
+#[tokio::test]
+async fn sends_generation_params() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message("Hello")))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .params(GenerationParams {
+            temperature: Some(0.0),
+            max_tokens: Some(256),
+            top_p: None,
+        })
+        .build();
+
+    let turn = assistant.prompt("Hi").await.unwrap();
+    assert_eq!(turn.output, "Hello");
+    assert_eq!(turn.usage.total(), 20);
+
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["temperature"], 0.0);
+    assert_eq!(body["max_tokens"], 256);
+    assert!(body.get("top_p").is_none());
+}
+
+#[tokio::test]
+async fn reprompts_malformed_tool_calls() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(tool_call("search_blocks", "{\"height\":")),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(message("Block 10000 paid 2217 utia.")),
+        )
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant =
+        Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini").build();
+
+    let turn = assistant.prompt("Fee of block 10000?").await.unwrap();
+    assert_eq!(turn.output, "Block 10000 paid 2217 utia.");
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let retry: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    let content = retry["messages"].as_array().unwrap().last().unwrap()["content"]
+        .as_str()
+        .unwrap();
+    assert!(content.starts_with("Fee of block 10000?"));
+    assert!(content.contains("not valid JSON"));
+}
+
+#[tokio::test]
+async fn gives_up_after_max_reprompts() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tool_call("search_txs", "{}")))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .max_reprompts(1)
+        .build();
+
+    assert!(assistant.prompt("Latest transactions?").await.is_err());
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn chat_sends_history_before_the_prompt() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message("About 0.61 TIA")))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant =
+        Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini").build();
+
+    let mut session = Session::default();
+    session.push_turn("What was the fee of block 5?", "2000 utia");
+    assistant
+        .chat("And in TIA?", session.history())
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    let conversation: Vec<_> = body["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|message| message["role"] != "system")
+        .map(|message| (message["role"].clone(), message["content"].clone()))
+        .collect();
+    assert_eq!(
+        conversation,
+        [
+            (json!("user"), json!("What was the fee of block 5?")),
+            (json!("assistant"), json!("2000 utia")),
+            (json!("user"), json!("And in TIA?")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn summarizes_long_answers_in_the_history() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message("About 2505 utia.")))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .result_budget(200)
+        .build();
+
+    let fees: Vec<Value> = (1..=300)
+        .map(|height| json!({ "height": height, "fee": height * 10 }))
+        .collect();
+    let mut session = Session::default();
+    session.push_turn("Fees of the last 300 blocks?", &json!(fees).to_string());
+    assistant
+        .chat("What was their mean?", session.history())
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    let answer = body["messages"][2]["content"].as_str().unwrap();
+    assert!(answer.len() < 1000);
+    assert!(answer.contains("\"summarized_items\":300"));
+    assert!(
+        answer.ends_with("the result (300 items) was aggregated, keeping its first and last 3.]")
+    );
+}
+
+#[tokio::test]
+async fn chains_tool_calls_up_to_max_iterations() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(tool_call("lookup", "{\"height\":10}")),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(tool_call("lookup", "{\"height\":11}")),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(message("Block 11 paid more: 4000 utia.")),
+        )
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .tool(Lookup)
+        .max_iterations(3)
+        .build();
+
+    let turn = assistant
+        .prompt("Which of blocks 10 and 11 paid more?")
+        .await
+        .unwrap();
+    assert_eq!(turn.output, "Block 11 paid more: 4000 utia.");
+    assert_eq!(turn.steps.len(), 1);
+    assert_eq!(turn.steps[0].args, json!({ "height": 10 }));
+    assert_eq!(turn.tool_call.unwrap().args, json!({ "height": 11 }));
+    assert!(turn.warnings.is_empty());
+
+    // Each later request carries the question and the results so far
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    let last: Value = serde_json::from_slice(&requests[2].body).unwrap();
+    let contents: Vec<&str> = last["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|message| message["role"] != "system")
+        .map(|message| message["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(contents[0], "Which of blocks 10 and 11 paid more?");
+    assert!(contents[1].contains("\"fee of block 10: 2000 utia\""));
+    assert!(contents[2].contains("\"fee of block 11: 4000 utia\""));
+}
+
+#[tokio::test]
+async fn stops_repeated_tool_calls() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(tool_call("lookup", "{\"height\":10}")),
+        )
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .tool(Lookup)
+        .max_iterations(5)
+        .build();
+
+    let turn = assistant.prompt("Fee of block 10?").await.unwrap();
+    assert_eq!(turn.output, "\"fee of block 10: 2000 utia\"");
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    assert_eq!(turn.steps.len(), 1);
+    assert_eq!(turn.warnings.len(), 1);
+    assert!(turn.warnings[0].contains("`lookup` again with the same arguments"));
+}
+
+#[tokio::test]
+async fn makes_tool_calls_requested_at_once_together() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tool_calls(&[
+            ("lookup", "{\"height\":10}"),
+            ("lookup", "{\"height\":11}"),
+        ])))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .tool(Lookup)
+        .build();
+
+    // With a single round, the results are the answer, each under its call
+    let turn = assistant
+        .prompt("Which of blocks 10 and 11 paid more?")
+        .await
+        .unwrap();
+    assert_eq!(
+        turn.output,
+        "`lookup` with {\"height\":10}:\n\"fee of block 10: 2000 utia\"\n\n\
+         `lookup` with {\"height\":11}:\n\"fee of block 11: 4000 utia\""
+    );
+    assert_eq!(turn.steps.len(), 1);
+    assert_eq!(turn.steps[0].args, json!({ "height": 10 }));
+    assert_eq!(turn.tool_call.unwrap().args, json!({ "height": 11 }));
+    assert!(turn.warnings.is_empty());
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn keeps_the_errors_of_calls_made_at_once() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tool_calls(&[
+            ("lookup", "{\"height\":10}"),
+            ("missing", "{}"),
+        ])))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message("Block 10 paid 2000 utia.")))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .tool(Lookup)
+        .max_iterations(3)
+        .build();
+
+    let turn = assistant.prompt("Fee of block 10?").await.unwrap();
+    assert_eq!(turn.output, "Block 10 paid 2000 utia.");
+    let failed = turn.tool_call.unwrap();
+    assert_eq!(failed.name, "missing");
+    assert!(failed.output.unwrap().starts_with("Error: "));
+
+    // The model sees every result, the error included
+    let requests = server.received_requests().await.unwrap();
+    let last: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    let results = last["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|message| message["content"].as_str())
+        .find(|content| content.starts_with("Called 2 tools at once"))
+        .unwrap();
+    assert!(results.contains("\"fee of block 10: 2000 utia\""));
+    assert!(results.contains("`missing` with {}:\nError: "));
+}
+
+#[tokio::test]
+async fn fails_turns_over_the_tool_call_limit() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(tool_call("lookup", "{\"height\":10}")),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tool_calls(&[
+            ("lookup", "{\"height\":10}"),
+            ("lookup", "{\"height\":11}"),
+            ("lookup", "{\"height\":12}"),
+        ])))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .tool(Lookup)
+        .max_iterations(5)
+        .max_tool_calls(2)
+        .build();
+
+    // The repeated call would reuse its result, but the other two go over the limit
+    let Err(error) = assistant.prompt("Fees of blocks 10 to 12?").await else {
+        panic!("expected the turn to fail");
+    };
+    assert!(error
+        .to_string()
+        .contains("more than the 2 tool call(s) a turn may make"));
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+/// Looks up a made-up fee for a block.
+struct Lookup;
+
+#[derive(serde::Deserialize)]
+struct LookupArgs {
+    height: u64,
+}
+
+impl Tool for Lookup {
+    const NAME: &'static str = "lookup";
+
+    type Args = LookupArgs;
+    type Output = String;
+    type Error = std::convert::Infallible;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Look up the fee of a block".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": { "height": { "type": "integer" } },
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(format!(
+            "fee of block {}: {} utia",
+            args.height,
+            (args.height - 9) * 2000
+        ))
+    }
+}
+
+#[tokio::test]
+async fn tool_calls_record_their_latency_and_upstream_urls() {
+    let indexer = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/10/stats"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "fee": "2000" })))
+        .mount(&indexer)
+        .await;
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(tool_call("search_blocks", "{\"height\":10}")),
+        )
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .tool(CelestiaSearchTool::with_base_url(&indexer.uri()))
+        .build();
+
+    let turn = assistant.prompt("Fee of block 10?").await.unwrap();
+    let call = turn.tool_call.unwrap();
+    assert!(call.latency.is_some());
+    assert_eq!(
+        call.upstream,
+        [
+            format!("{}/head", indexer.uri()),
+            format!("{}/block/10/stats", indexer.uri())
+        ]
+    );
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use celestia_search_assistant::accounting::TokenUsage;
+use celestia_search_assistant::assistant::{ToolCall, Turn};
+use celestia_search_assistant::audit::{self, rotated, AuditLog, Record};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::router::Route;
+use serde_json::{json, Value};
+
+fn turn() -> Turn {
+    Turn {
+        output: "Block 10 paid 2000 utia in fees.".to_string(),
+        tool_call: Some(ToolCall {
+            name: "search_blocks".to_string(),
+            args: json!({ "height": 10 }),
+            output: Some("\"fee 2000 utia\"".to_string()),
+            latency: Some(Duration::from_millis(42)),
+            upstream: vec!["https://api-mainnet.celenium.io/v1/block/10/stats".to_string()],
+        }),
+        steps: Vec::new(),
+        usage: TokenUsage {
+            prompt_tokens: 120,
+            completion_tokens: 30,
+        },
+        warnings: Vec::new(),
+    }
+}
+
+fn record<'a>(prompt: &'a str, turn: &'a Turn) -> Record<'a> {
+    Record {
+        prompt,
+        model: "gpt-4o-mini",
+        network: Network::Mainnet,
+        elapsed: Duration::from_millis(900),
+        outcome: Ok((Route::Live, turn)),
+        cached: false,
+    }
+}
+
+#[tokio::test]
+async fn upstream_urls_are_collected_in_order_without_repeats() {
+    // Outside a collection, requests go unrecorded
+    audit::upstream("https://example.com/ignored");
+
+    let ((), urls) = audit::collecting(async {
+        audit::upstream("https://example.com/head");
+        audit::upstream("https://example.com/block/1/stats");
+        audit::upstream("https://example.com/head");
+    })
+    .await;
+    assert_eq!(
+        urls,
+        [
+            "https://example.com/head",
+            "https://example.com/block/1/stats"
+        ]
+    );
+}
+
+#[test]
+fn records_hold_the_turn() {
+    let turn = turn();
+    let mut logged = record("Fee of block 10?", &turn).to_json();
+    assert!(logged["time"].as_str().unwrap().ends_with('Z'));
+    logged.as_object_mut().unwrap().remove("time");
+    assert_eq!(
+        logged,
+        json!({
+            "prompt": "Fee of block 10?",
+            "model": "gpt-4o-mini",
+            "network": "mainnet",
+            "elapsed_ms": 900,
+            "cached": false,
+            "route": "live",
+            "tool_calls": [{
+                "name": "search_blocks",
+                "args": { "height": 10 },
+                "latency_ms": 42,
+                "upstream": ["https://api-mainnet.celenium.io/v1/block/10/stats"],
+            }],
+            "tokens": { "prompt": 120, "completion": 30, "total": 150 },
+            "answer": "Block 10 paid 2000 utia in fees.",
+            "warnings": [],
+        })
+    );
+
+    let failed = Record {
+        outcome: Err("The monthly budget is spent".to_string()),
+        ..record("Fee of block 10?", &turn)
+    };
+    let logged = failed.to_json();
+    assert_eq!(logged["error"], "The monthly budget is spent");
+    assert!(logged.get("answer").is_none());
+}
+
+#[test]
+fn logs_are_rotated_by_size() {
+    let dir = std::env::temp_dir().join(format!("celestia-audit-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("audit.jsonl");
+    let turn = turn();
+    let line = record("question 0", &turn).to_json().to_string().len() as u64 + 1;
+
+    // Two records fit in a file, and two rotated files are kept
+    let log = AuditLog::new(&path, line * 2, 2);
+    for i in 0..7 {
+        log.write(&record(&format!("question {}", i), &turn))
+            .unwrap();
+    }
+    let prompts = |path: &std::path::Path| -> Vec<String> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["prompt"].to_string())
+            .collect()
+    };
+    assert_eq!(prompts(&path), ["\"question 6\""]);
+    assert_eq!(
+        prompts(&rotated(&path, 1)),
+        ["\"question 4\"", "\"question 5\""]
+    );
+    assert_eq!(
+        prompts(&rotated(&path, 2)),
+        ["\"question 2\"", "\"question 3\""]
+    );
+    assert!(!rotated(&path, 3).exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,107 @@
+use celestia_search_assistant::azure::{self, DEFAULT_API_VERSION};
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::provider::{LlmClient, Provider, ProviderError};
+use rig::completion::{CompletionModel, ModelChoice, ToolDefinition};
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn completion(message: serde_json::Value) -> serde_json::Value {
+    json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 1700000000,
+        "model": "gpt-4o-mini",
+        "choices": [{ "index": 0, "message": message, "finish_reason": "stop" }],
+        "usage": { "prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15 },
+    })
+}
+
+#[tokio::test]
+async fn completes_through_the_deployment() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/assistant/chat/completions"))
+        .and(query_param("api-version", DEFAULT_API_VERSION))
+        .and(header("api-key", "secret"))
+        .and(body_partial_json(json!({
+            "messages": [
+                { "role": "system", "content": "Answer briefly." },
+                { "role": "user", "content": "What is the fee of block 9999?" },
+            ],
+            "tool_choice": "auto",
+            "tools": [{ "type": "function", "function": { "name": "search_blocks" } }],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion(json!({
+            "role": "assistant",
+            "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": { "name": "search_blocks", "arguments": "{\"height\":9999}" },
+            }],
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let model = azure::Client::new(&server.uri(), "secret", DEFAULT_API_VERSION)
+        .completion_model("assistant");
+    let response = model
+        .completion_request("What is the fee of block 9999?")
+        .preamble("Answer briefly.".to_string())
+        .tool(ToolDefinition {
+            name: "search_blocks".to_string(),
+            description: "Look up a block".to_string(),
+            parameters: json!({ "type": "object" }),
+        })
+        .send()
+        .await
+        .unwrap();
+    match response.choice {
+        ModelChoice::ToolCall(name, args) => {
+            assert_eq!(name, "search_blocks");
+            assert_eq!(args, json!({ "height": 9999 }));
+        }
+        ModelChoice::Message(message) => panic!("expected a tool call, got {}", message),
+    }
+}
+
+#[tokio::test]
+async fn reports_rejected_requests() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": { "code": "content_filter", "message": "The prompt was filtered." },
+        })))
+        .mount(&server)
+        .await;
+
+    let model = azure::Client::new(&server.uri(), "secret", DEFAULT_API_VERSION)
+        .completion_model("assistant");
+    let err = model
+        .completion_request("Hello")
+        .send()
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err.to_string(),
+        "ProviderError: Azure OpenAI answered with status 400 Bad Request: The prompt was filtered."
+    );
+}
+
+#[test]
+fn azure_needs_its_config_section() {
+    let err = LlmClient::new(Provider::Azure, None, None).err().unwrap();
+    assert!(matches!(err, ProviderError::AzureNotConfigured));
+
+    let config = Config::parse(
+        "[azure]\nendpoint = \"https://example.openai.azure.com\"\ndeployment = \"assistant\"\n\
+         api_key = \"secret\"\n",
+    )
+    .unwrap();
+    let azure = config.azure.unwrap();
+    assert_eq!(azure.api_version(), DEFAULT_API_VERSION);
+    assert!(LlmClient::new(Provider::Azure, Some(&azure), None).is_ok());
+}
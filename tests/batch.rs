@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use celestia_search_assistant::accounting::TokenUsage;
+use celestia_search_assistant::assistant::Turn;
+use celestia_search_assistant::batch;
+use serde_json::json;
+
+#[test]
+fn batch_files_hold_one_question_per_line() {
+    let text = "# Fees\nWhat is the fee of block 5?\n\n  How full was block 6?  \n";
+    assert_eq!(
+        batch::questions(text),
+        ["What is the fee of block 5?", "How full was block 6?"]
+    );
+}
+
+#[tokio::test]
+async fn answers_keep_question_order_and_failures() {
+    let questions = vec!["slow".to_string(), "fails".to_string(), "fast".to_string()];
+    let answers = batch::run(questions, 3, |prompt| async move {
+        match prompt.as_str() {
+            "fails" => Err("The indexer is rate-limiting requests, try again".to_string()),
+            _ => {
+                if prompt == "slow" {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+                Ok(Turn {
+                    output: format!("answered {}", prompt),
+                    tool_call: None,
+                    steps: Vec::new(),
+                    usage: TokenUsage::default(),
+                    warnings: Vec::new(),
+                })
+            }
+        }
+    })
+    .await;
+
+    let records: Vec<_> = answers.iter().map(|answer| answer.to_json()).collect();
+    assert_eq!(
+        records,
+        [
+            json!({ "prompt": "slow", "answer": "answered slow", "tool_call": null }),
+            json!({
+                "prompt": "fails",
+                "error": "The indexer is rate-limiting requests, try again",
+            }),
+            json!({ "prompt": "fast", "answer": "answered fast", "tool_call": null }),
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use celestia_search_assistant::bench::{self, Timings};
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use rig::tool::ToolDyn;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn millis(runs: &[u64]) -> Vec<Duration> {
+    runs.iter().copied().map(Duration::from_millis).collect()
+}
+
+#[test]
+fn later_runs_are_compared_with_the_first() {
+    let timings = Timings {
+        name: "search_blocks".to_string(),
+        runs: millis(&[100, 10, 20, 30, 40]),
+        ..Timings::default()
+    };
+    assert_eq!(timings.first(), Some(Duration::from_millis(100)));
+    assert_eq!(timings.percentile(50.0), Some(Duration::from_millis(20)));
+    assert_eq!(timings.percentile(95.0), Some(Duration::from_millis(40)));
+    assert_eq!(timings.speedup(), Some(5.0));
+
+    // A single run has nothing to compare with
+    let once = Timings {
+        runs: millis(&[100]),
+        ..timings.clone()
+    };
+    assert_eq!(once.percentile(50.0), Some(Duration::from_millis(100)));
+    assert_eq!(once.speedup(), None);
+
+    let failing = Timings {
+        name: "celenium /head".to_string(),
+        failures: 1,
+        error: Some("timed out".to_string()),
+        ..timings
+    };
+    assert_eq!(
+        bench::report(&[failing]),
+        "call                 first         p50         p95  speedup\n\
+         celenium /head    100.0 ms     20.0 ms     40.0 ms     5.0x (1 of 5 runs failed: \
+         timed out)"
+    );
+}
+
+#[tokio::test]
+async fn times_tools_that_take_a_height() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/100/stats"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "blobs_count": 1,
+            "blobs_size": 512,
+            "block_time": 6000,
+            "bytes_in_block": 1024,
+            "commissions": "0",
+            "events_count": 4,
+            "fee": "2000",
+            "fill_rate": "0.01",
+            "gas_limit": 100000,
+            "gas_used": 80000,
+            "inflation_rate": "0.08",
+            "rewards": "0",
+            "square_size": 4,
+            "supply_change": "0",
+            "tx_count": 2,
+        })))
+        .mount(&server)
+        .await;
+    let tools: Vec<Box<dyn ToolDyn>> =
+        vec![Box::new(CelestiaSearchTool::with_base_url(&server.uri()))];
+
+    let (timings, skipped) = bench::tools(&tools, 100, 3).await;
+    assert!(skipped.is_empty());
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].name, "search_blocks");
+    assert_eq!(timings[0].runs.len(), 3);
+    assert_eq!(timings[0].failures, 0, "{:?}", timings[0].error);
+
+    // Tools needing more than a height aren't benchmarked
+    assert_eq!(bench::args("tx_fee", 100), None);
+    assert_eq!(
+        bench::args("blob_fees", 100),
+        Some(json!({ "from": 81, "to": 100 }))
+    );
+}
@@ -0,0 +1,100 @@
+use celestia_search_assistant::blob_cost_tool::BlobCostTool;
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::pfb_gas;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const NAMESPACE: &str = "00000000000000000000000000000000000000000000000000736f76";
+
+#[test]
+fn estimates_pfb_gas_as_celestia_app_does() {
+    assert_eq!(pfb_gas::shares_needed(1), 1);
+    assert_eq!(pfb_gas::shares_needed(478), 1);
+    assert_eq!(pfb_gas::shares_needed(479), 2);
+    assert_eq!(pfb_gas::shares_needed(960), 2);
+    assert_eq!(pfb_gas::shares_needed(961), 3);
+    // 213 shares of 512 bytes at 8 gas, 70 transaction bytes at 10 gas and the fixed cost
+    assert_eq!(pfb_gas::estimate_gas(&[102_400]), 948_148);
+    assert_eq!(pfb_gas::estimate_gas(&[102_400, 102_400]), 1_821_296);
+    assert_eq!(pfb_gas::fee(948_148, 0.002), 1897);
+}
+
+#[tokio::test]
+async fn prices_batched_and_separate_pfbs_at_the_gas_trackers_prices() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/gas/price"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "slow": "0.002", "median": "0.004", "fast": "0.02" })),
+        )
+        .mount(&server)
+        .await;
+    let tool = BlobCostTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({
+        "namespaces": [NAMESPACE, format!("00{}", NAMESPACE)],
+        "blob_size": 102400,
+    }))
+    .unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(output.starts_with(
+        "Posting a 100.00 KiB blob (213 shares) to 2 namespace(s) takes 1821296 gas batched in \
+         one PFB, or 1896296 gas in a separate PFB per namespace (948148 each):\n\
+         - at the slow gas price of 0.002 utia: 3643 utia"
+    ), "{}", output);
+    assert!(output.contains("- at the median gas price of 0.004 utia: 7286 utia"));
+    assert!(output.contains("- at the fast gas price of 0.02 utia: 36426 utia"));
+
+    let args = serde_json::from_value(json!({
+        "namespaces": [NAMESPACE],
+        "blob_size": 100,
+        "gas_price": 0.1,
+    }))
+    .unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(
+        output.starts_with(
+            "Posting a 100 B blob (1 shares) to 1 namespace(s) takes a PFB of 79796 gas:\n\
+         - at the given gas price of 0.1 utia: 7980 utia"
+        ),
+        "{}",
+        output
+    );
+
+    // Fees past what an i64 holds are still positive
+    let args = serde_json::from_value(json!({
+        "namespaces": [NAMESPACE],
+        "blob_size": 100,
+        "gas_price": 1.2e14,
+    }))
+    .unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(
+        output.contains(
+            "- at the given gas price of 120000000000000 utia: 9575520000000000000 utia \
+             (9575520000000 TIA)"
+        ),
+        "{}",
+        output
+    );
+
+    for invalid in [
+        json!({ "namespaces": [], "blob_size": 100 }),
+        json!({ "namespaces": [NAMESPACE], "blob_size": 0 }),
+        json!({ "namespaces": [NAMESPACE], "blob_size": 100, "gas_price": -1.0 }),
+    ] {
+        let args = serde_json::from_value(invalid).unwrap();
+        assert!(matches!(
+            tool.call(args).await,
+            Err(CelestiaSearchError::InvalidEstimate(_))
+        ));
+    }
+    let args = serde_json::from_value(json!({ "namespaces": ["zz"], "blob_size": 100 })).unwrap();
+    assert!(matches!(
+        tool.call(args).await,
+        Err(CelestiaSearchError::InvalidNamespace(_))
+    ));
+}
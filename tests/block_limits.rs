@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use celestia_search_assistant::block_limits_tool::{eras_on, split, BlockLimitsTool, Era};
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::network::Network;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn era(name: &str, max_square_size: u64) -> Era {
+    Era {
+        name: name.to_string(),
+        summary: String::new(),
+        max_square_size,
+        max_bytes: max_square_size * max_square_size * 482,
+        heights: BTreeMap::new(),
+    }
+}
+
+/// Serves blocks of `(height, square size, bytes)` and a chain whose square size is now `current`
+async fn indexer(blocks: &[(u64, u64, u64)], current: u64) -> MockServer {
+    let server = MockServer::start().await;
+    for &(height, square_size, bytes) in blocks {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "square_size": square_size,
+                "bytes_in_block": bytes,
+                "fill_rate": "0.25",
+            })))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/constants"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "module": { "blob": { "gov_max_square_size": current.to_string() } },
+        })))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[test]
+fn bundled_eras_cover_every_network_from_genesis() {
+    for network in Network::ALL {
+        let eras = eras_on(network);
+        assert_eq!(eras[0].0, 1, "{}", network);
+        for pair in eras.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+        }
+    }
+}
+
+#[test]
+fn ranges_are_split_where_eras_start() {
+    let (first, second) = (era("first", 64), era("second", 128));
+    let eras = [(10, &first), (20, &second)];
+
+    let parts: Vec<(u64, u64, Option<&str>)> = split(&eras, 5, 25)
+        .into_iter()
+        .map(|(from, to, era)| (from, to, era.map(|era| era.name.as_str())))
+        .collect();
+    assert_eq!(
+        parts,
+        [
+            (5, 9, None),
+            (10, 19, Some("first")),
+            (20, 25, Some("second"))
+        ]
+    );
+
+    let parts = split(&eras, 12, 20);
+    assert_eq!(parts.len(), 2);
+    assert_eq!((parts[0].0, parts[0].1), (12, 19));
+    assert_eq!((parts[1].0, parts[1].1), (20, 20));
+    assert_eq!(split(&eras, 21, 22)[0].2.unwrap().name, "second");
+}
+
+#[tokio::test]
+async fn utilization_is_measured_against_the_eras_limits() {
+    let server = indexer(&[(100, 32, 493568), (101, 64, 1974272)], 64).await;
+    let tool = BlockLimitsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "from": 100, "to": 101 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Block size limits over blocks 100 to 101:\n\
+         - Blocks 100 to 101, Genesis era (squares up to 64x64, blocks up to 1.88 MiB): 2 \
+         block(s) averaging 1.18 MiB (62.50% of the limit, peaking at 100.00%), with squares up \
+         to 64x64 using 62.50% of the widest square's shares on average. The indexer's fill rate \
+         averaged 25.00%."
+    );
+}
+
+#[tokio::test]
+async fn limits_newer_than_the_bundled_history_are_flagged() {
+    let server = indexer(&[(100, 128, 7897088)], 128).await;
+    let tool = BlockLimitsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "from": 100, "to": 100 })).unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(output.contains(" 1 block(s) exceed these limits, so they were raised earlier"));
+    assert!(output.ends_with(
+        "The chain's current `gov_max_square_size` is 128, but the latest recorded era (Genesis, \
+         from height 1) has 64: it changed at a height the bundled history doesn't record yet, \
+         so limits after height 1 may be off."
+    ));
+}
+
+#[tokio::test]
+async fn testnets_are_measured_against_their_own_history() {
+    let server = indexer(&[(100, 32, 493568)], 64).await;
+    let tool = BlockLimitsTool::new(
+        CelestiaSearchTool::with_base_url(&server.uri()).with_network(Network::Mocha),
+    );
+
+    let args = serde_json::from_value(json!({ "from": 100, "to": 100 })).unwrap();
+    assert!(tool.call(args).await.unwrap().contains(
+        "- Blocks 100 to 100, Genesis era (squares up to 64x64, blocks up to 1.88 MiB): 1 \
+         block(s) averaging 482.00 KiB (25.00% of the limit"
+    ));
+}
+
+#[tokio::test]
+async fn ranges_are_validated() {
+    let tool = BlockLimitsTool::new(CelestiaSearchTool::with_base_url("http://localhost:1"));
+    for (from, to) in [(0, 10), (20, 10), (1, 1000)] {
+        let args = serde_json::from_value(json!({ "from": from, "to": to })).unwrap();
+        assert!(tool.call(args).await.is_err());
+    }
+}
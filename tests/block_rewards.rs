@@ -0,0 +1,61 @@
+mod common;
+
+use celestia_search_assistant::block_rewards_tool::BlockRewardsTool;
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::MockServer;
+
+async fn rewards_server(proposer: Value) -> MockServer {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/head",
+        json!({ "last_height": 3000000, "total_stake": "1000" }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/block/2000000/stats",
+        json!({ "rewards": "1000000", "commissions": "100000" }),
+    )
+    .await;
+    common::respond(&server, "/block/2000000", json!({ "proposer": proposer })).await;
+    common::respond(
+        &server,
+        "/validator/12",
+        json!({ "moniker": "Stakely", "stake": "100", "rate": "0.05" }),
+    )
+    .await;
+    server
+}
+
+#[tokio::test]
+async fn attributes_rewards_to_the_proposer() {
+    let server = rewards_server(json!({ "id": 12, "moniker": "Stakely" })).await;
+    let tool = BlockRewardsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "height": 2000000 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Block 2000000 distributed 1000000 utia (1 TIA) in staking rewards to the bonded \
+         validators, by stake: 100000 utia (0.1 TIA) kept by the validators as commission, and \
+         900000 utia (0.9 TIA) for their delegators. The block was proposed by Stakely, which \
+         earns no bonus for it, only its share of the rewards by stake: with 10.00% of the \
+         stake, about 100000 utia (0.1 TIA), of which its 5.00% commission is about 5000 utia \
+         (0.005 TIA) and the rest goes to its delegators."
+    );
+}
+
+#[tokio::test]
+async fn explains_rewards_without_a_proposer() {
+    let server = rewards_server(Value::Null).await;
+    let tool = BlockRewardsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "height": 2000000 })).unwrap();
+    assert!(tool
+        .call(args)
+        .await
+        .unwrap()
+        .ends_with("for their delegators. Celenium doesn't report the block's proposer."));
+}
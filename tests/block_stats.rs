@@ -0,0 +1,206 @@
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::network::Network;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn search(server: &MockServer, height: u64) -> Result<String, CelestiaSearchError> {
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+    let args = serde_json::from_value(json!({ "height": height })).unwrap();
+    tool.call(args).await
+}
+
+#[tokio::test]
+async fn returns_fee_on_success() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/9999/stats"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "height": 9999,
+            "tx_count": "2",
+            "fee": "2217",
+            "gas_limit": "200000",
+            "gas_used": "83741",
+            "fill_rate": "0.0012",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = search(&server, 9999).await.unwrap();
+    assert_eq!(output, "    The gas fee is: 2217");
+}
+
+#[tokio::test]
+async fn not_found_is_reported() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/99999999/stats"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "message": "not found" })))
+        .mount(&server)
+        .await;
+
+    let err = search(&server, 99999999).await.unwrap_err();
+    assert!(
+        matches!(err, CelestiaSearchError::NotFound { ref url } if url.ends_with("/block/99999999/stats"))
+    );
+}
+
+#[tokio::test]
+async fn server_error_carries_status() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("internal error"))
+        .mount(&server)
+        .await;
+
+    let err = search(&server, 1).await.unwrap_err();
+    assert!(matches!(
+        err,
+        CelestiaSearchError::Status { status: 500, ref body, .. } if body == "internal error"
+    ));
+}
+
+#[tokio::test]
+async fn rate_limit_is_reported() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .mount(&server)
+        .await;
+
+    let err = search(&server, 1).await.unwrap_err();
+    assert!(matches!(
+        err,
+        CelestiaSearchError::RateLimited {
+            retry_after: Some(1),
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "The indexer is rate-limiting requests, try again in 1s"
+    );
+}
+
+#[tokio::test]
+async fn malformed_json_is_rejected() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"fee\": "))
+        .mount(&server)
+        .await;
+
+    let err = search(&server, 1).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::Deserialization { .. }));
+}
+
+#[tokio::test]
+async fn error_payload_is_an_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "error": { "message": "bad height" } })),
+        )
+        .mount(&server)
+        .await;
+
+    let err = search(&server, 1).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::ApiError(ref msg) if msg == "bad height"));
+}
+
+#[tokio::test]
+async fn height_beyond_head_is_rejected() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 2000000 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/block/99999999/stats"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    // The second lookup is served from the cached head
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+    let err = tool.fetch_stats(99999999).await.err().unwrap();
+    assert!(matches!(
+        err,
+        CelestiaSearchError::HeightBeyondHead {
+            height: 99999999,
+            head: 2000000
+        }
+    ));
+    let err = tool.fetch_stats(99999999).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Height 99999999 is beyond the current head (2000000)"
+    );
+}
+
+#[tokio::test]
+async fn definition_constrains_height() {
+    let definition = CelestiaSearchTool::default()
+        .definition(String::new())
+        .await;
+    let height = &definition.parameters["properties"]["height"];
+    assert_eq!(height["type"], "integer");
+    assert_eq!(height["minimum"], 1);
+    assert!(height["examples"].is_array());
+}
+
+#[tokio::test]
+async fn network_argument_queries_each_network() {
+    let (mainnet, mocha) = (MockServer::start().await, MockServer::start().await);
+    for (server, fee) in [(&mainnet, "2217"), (&mocha, "40")] {
+        Mock::given(method("GET"))
+            .and(path("/block/9999/stats"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "fee": fee })))
+            .mount(server)
+            .await;
+    }
+
+    let tool = CelestiaSearchTool::with_base_url(&mainnet.uri()).with_peer(
+        Network::Mocha,
+        CelestiaSearchTool::with_base_url(&mocha.uri()).with_network(Network::Mocha),
+    );
+    let call = |args| {
+        let args = serde_json::from_value(args).unwrap();
+        tool.call(args)
+    };
+
+    assert_eq!(
+        call(json!({ "height": 9999, "network": ["mainnet", "mocha"] }))
+            .await
+            .unwrap(),
+        "On mainnet: The gas fee is: 2217\n\nOn mocha: The gas fee is: 40"
+    );
+    assert_eq!(
+        call(json!({ "height": 9999, "network": "mocha" }))
+            .await
+            .unwrap(),
+        "On mocha: The gas fee is: 40"
+    );
+    assert!(matches!(
+        call(json!({ "height": 9999, "network": "arabica" })).await,
+        Err(CelestiaSearchError::NetworkUnavailable(Network::Arabica))
+    ));
+}
+
+#[test]
+fn network_base_urls_can_be_configured() {
+    let config = Config::parse("[networks]\nmocha = \"http://localhost:8080/v1\"").unwrap();
+    assert_eq!(config.base_url(Network::Mocha), "http://localhost:8080/v1");
+    assert_eq!(
+        config.base_url(Network::Mainnet),
+        "https://api-mainnet.celenium.io/v1"
+    );
+    assert!(Config::parse("[networks]\ndevnet = \"http://localhost\"").is_err());
+}
@@ -0,0 +1,76 @@
+use celestia_search_assistant::block_txs_tool::BlockTxsTool;
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn lists_a_blocks_transactions_of_a_message_type() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/tx"))
+        .and(query_param("height", "2000000"))
+        .and(query_param("msg_type", "MsgPayForBlobs"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "hash": "AA11",
+                "fee": "2000",
+                "status": "success",
+                "message_types": ["MsgPayForBlobs"],
+                "signers": ["celestia1rollup"],
+            },
+            {
+                "hash": "BB22",
+                "fee": "1000",
+                "status": "failed",
+                "message_types": ["MsgPayForBlobs"],
+                "signers": [{ "hash": "celestia1other" }],
+            },
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/tx"))
+        .and(query_param("offset", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+    let tool = BlockTxsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({
+        "height": 2000000,
+        "message_type": "MsgPayForBlobs",
+        "limit": 2,
+    }))
+    .unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Transactions 1 to 2 of block 2000000 with a MsgPayForBlobs message, in block order (1 \
+         succeeded, 1 failed), paying 3000 utia (0.003 TIA) in fees:\n\
+         - MsgPayForBlobs: fee 2000 utia, signed by celestia1rollup, success, hash AA11\n\
+         - MsgPayForBlobs: fee 1000 utia, signed by celestia1other, failed, hash BB22\n\
+         More transactions may follow; call block_txs with cursor `block_txs:2:3087633b` for the \
+         next page."
+    );
+
+    // The cursor continues the listing, but not that of another message type
+    let args = serde_json::from_value(json!({
+        "height": 2000000,
+        "message_type": "MsgPayForBlobs",
+        "cursor": "block_txs:2:3087633b",
+    }))
+    .unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Block 2000000 has no transactions with a MsgPayForBlobs message beyond the first 2."
+    );
+    let args = serde_json::from_value(json!({
+        "height": 2000000,
+        "cursor": "block_txs:2:3087633b",
+    }))
+    .unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::InvalidCursor(_)));
+}
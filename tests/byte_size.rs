@@ -0,0 +1,20 @@
+use celestia_search_assistant::byte_size::{self, ByteUnits};
+
+#[test]
+fn formats_sizes_in_binary_or_decimal_units() {
+    assert_eq!(byte_size::format_in(0, ByteUnits::Binary), "0 B");
+    assert_eq!(byte_size::format_in(1023, ByteUnits::Binary), "1023 B");
+    assert_eq!(byte_size::format_in(1024, ByteUnits::Binary), "1.00 KiB");
+    assert_eq!(byte_size::format_in(1491229, ByteUnits::Binary), "1.42 MiB");
+    assert_eq!(byte_size::format_in(1491229, ByteUnits::Decimal), "1.49 MB");
+    assert_eq!(byte_size::format_in(999, ByteUnits::Decimal), "999 B");
+    assert_eq!(byte_size::format_in(8_000, ByteUnits::Decimal), "8.00 kB");
+    assert_eq!(byte_size::format_in(5 << 30, ByteUnits::Binary), "5.00 GiB");
+    assert_eq!(
+        byte_size::format_in(3 << 50, ByteUnits::Binary),
+        "3072.00 TiB"
+    );
+
+    // Sizes rounding up to the next unit are shown in it
+    assert_eq!(byte_size::format_in(1048575, ByteUnits::Binary), "1.00 MiB");
+}
@@ -0,0 +1,366 @@
+use celestia_search_assistant::celenium::{CeleniumClient, DEFAULT_USER_AGENT};
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchError;
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::metrics::Bandwidth;
+use celestia_search_assistant::network::Network;
+use serde_json::json;
+use wiremock::matchers::{header, headers, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn sends_api_key_and_user_agent() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .and(header("apikey", "secret"))
+        .and(header("user-agent", "dashboard/1.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 7 })))
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .api_key("secret")
+        .user_agent("dashboard/1.0")
+        .build()
+        .unwrap();
+    assert_eq!(client.get("/head").await.unwrap()["last_height"], 7);
+
+    // Without the key the mock doesn't match
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.get("/head").await,
+        Err(CelestiaSearchError::NotFound { .. })
+    ));
+    assert!(DEFAULT_USER_AGENT.starts_with("celestia-search-assistant/"));
+}
+
+#[tokio::test]
+async fn retries_transient_failures() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 7 })))
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .retries(1)
+        .build()
+        .unwrap();
+    assert_eq!(client.get("/head").await.unwrap()["last_height"], 7);
+}
+
+#[tokio::test]
+async fn does_not_retry_by_default() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.get("/head").await,
+        Err(CelestiaSearchError::Status { status: 503, .. })
+    ));
+}
+
+#[tokio::test]
+async fn waits_out_rate_limits() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 7 })))
+        .mount(&server)
+        .await;
+
+    // Without retries configured, as the indexer said when to try again
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    assert_eq!(client.get("/head").await.unwrap()["last_height"], 7);
+}
+
+#[tokio::test]
+async fn holds_off_once_the_limit_is_used_up() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "last_height": 7 }))
+                .insert_header("X-RateLimit-Remaining", "0")
+                .insert_header("X-RateLimit-Reset", "60"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    client.get("/head").await.unwrap();
+    // The limit resets too late to wait for, so the next request fails without being sent
+    assert!(matches!(
+        client.get("/blocks").await,
+        Err(CelestiaSearchError::RateLimited {
+            retry_after: Some(59..=60),
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn reads_retry_after_dates() {
+    let server = MockServer::start().await;
+    let at = chrono::Utc::now() + chrono::Duration::seconds(30);
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", at.to_rfc2822()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.get("/head").await,
+        Err(CelestiaSearchError::RateLimited {
+            retry_after: Some(28..=30),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn clients_are_built_from_the_config() {
+    let config = Config::parse(
+        "[celenium]\napi_key = \"secret\"\ntimeout_secs = 5\nretries = 2\ncompression = false\n\n\
+         [networks]\nmocha = \"http://localhost:8080/v1/\"",
+    )
+    .unwrap();
+    let client = config.celenium_client(Network::Mocha).unwrap();
+    assert_eq!(client.base_url(), "http://localhost:8080/v1");
+    assert_eq!(client.network(), Network::Mocha);
+
+    let client = config.celenium_client(Network::Arabica).unwrap();
+    assert_eq!(client.base_url(), Network::Arabica.base_url());
+    assert!(Config::parse("[celenium]\nkey = \"secret\"").is_err());
+}
+
+#[tokio::test]
+async fn negotiates_compressed_responses() {
+    use std::io::Write;
+
+    let json = json!({ "last_height": 7, "chain_id": "celestia" }).to_string();
+    let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gzip.write_all(json.as_bytes()).unwrap();
+    let gzip = gzip.finish().unwrap();
+    let mut brotli = Vec::new();
+    brotli::CompressorWriter::new(&mut brotli, 4096, 5, 22)
+        .write_all(json.as_bytes())
+        .unwrap();
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/gzip"))
+        .and(headers("accept-encoding", vec!["br", "gzip"]))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-encoding", "gzip")
+                .set_body_raw(gzip, "application/json"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/br"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-encoding", "br")
+                .set_body_raw(brotli, "application/json"),
+        )
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    assert_eq!(client.get("/gzip").await.unwrap()["last_height"], 7);
+    assert_eq!(client.get("/br").await.unwrap()["chain_id"], "celestia");
+
+    // Without compression the encoding isn't negotiated, so the gzip mock doesn't match
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .compression(false)
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.get("/gzip").await,
+        Err(CelestiaSearchError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn reports_bandwidth_saved() {
+    let bandwidth = Bandwidth {
+        received: 1_500,
+        decoded: 6_000,
+    };
+    assert_eq!(bandwidth.saved(), 0.75);
+    assert_eq!(
+        bandwidth.to_string(),
+        "Indexer responses: 1.46 KiB received for 5.86 KiB of JSON (75% saved by compression)"
+    );
+}
+
+#[tokio::test]
+async fn revalidates_responses_by_etag() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(json!({ "last_height": 7 })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Clones share the cached responses
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    assert_eq!(client.get("/head").await.unwrap()["last_height"], 7);
+    assert_eq!(client.clone().get("/head").await.unwrap()["last_height"], 7);
+}
+
+#[tokio::test]
+async fn unexpected_not_modified_is_fetched_again() {
+    let server = MockServer::start().await;
+    // A cache in between answers a request the client sent without an ETag
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(304))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .and(header("cache-control", "no-cache"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 7 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    assert_eq!(client.get("/head").await.unwrap()["last_height"], 7);
+}
+
+#[tokio::test]
+async fn coalesces_identical_requests_in_flight() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/7/stats"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_delay(std::time::Duration::from_millis(200))
+                .set_body_json(json!({ "fee": "42" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/block/8/stats"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let clone = client.clone();
+    let (first, second, third) = futures::join!(
+        client.get("/block/7/stats"),
+        clone.get("/block/7/stats"),
+        client.get("/block/7/stats"),
+    );
+    for result in [first, second, third] {
+        assert_eq!(result.unwrap()["fee"], "42");
+    }
+
+    // Errors are shared too, and finished requests aren't reused
+    let (first, second) =
+        futures::join!(client.get("/block/8/stats"), client.get("/block/8/stats"));
+    assert!(matches!(first, Err(CelestiaSearchError::NotFound { .. })));
+    assert!(matches!(second, Err(CelestiaSearchError::NotFound { .. })));
+    let third = client.get("/block/8/stats").await;
+    assert!(matches!(third, Err(CelestiaSearchError::NotFound { .. })));
+}
+
+#[tokio::test]
+async fn requests_over_the_budget_fail() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 7 })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .max_requests_per_minute(2)
+        .build()
+        .unwrap();
+    client.get("/head").await.unwrap();
+    client.get("/head").await.unwrap();
+    // Clones count against the same budget, without a request reaching the indexer
+    match client.clone().get("/head").await {
+        Err(
+            error @ CelestiaSearchError::RequestBudgetExceeded {
+                limit: 2,
+                retry_after,
+                ..
+            },
+        ) => {
+            assert!((1..=60).contains(&retry_after));
+            assert!(error.to_string().contains("at most 2 requests a minute"));
+        }
+        other => panic!("expected the budget to be used up, got {:?}", other),
+    }
+}
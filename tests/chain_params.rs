@@ -0,0 +1,68 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::chain_params_tool::ChainParamsTool;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::MockServer;
+
+async fn chain_server() -> MockServer {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/head",
+        json!({ "chain_id": "celestia", "last_height": 1100, "last_time": "2024-06-01T00:10:00Z" }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/constants",
+        json!({ "module": {
+            "blob": { "gas_per_blob_byte": "8", "gov_max_square_size": "128" },
+            "staking": { "max_validators": "100", "unbonding_time": "1814400s" },
+        }}),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/block/1",
+        json!({ "time": "2023-10-31T14:00:00Z" }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/block/1000",
+        json!({ "time": "2024-06-01T00:00:00Z" }),
+    )
+    .await;
+    server
+}
+
+#[tokio::test]
+async fn reports_chain_and_module_params() {
+    let server = chain_server().await;
+    let tool = ChainParamsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Chain celestia started at 2023-10-31T14:00:00Z (block 1). The maximum data square size \
+         is 128 (the blob module's `gov_max_square_size`). Blocks currently come every 6.00s on \
+         average (over the last 100 blocks).\n\
+         Module parameters:\n\
+         - blob: gas_per_blob_byte = 8, gov_max_square_size = 128\n\
+         - staking: max_validators = 100, unbonding_time = 1814400s"
+    );
+
+    let args = serde_json::from_value(json!({ "module": "staking" })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Parameters of the staking module:\n- max_validators: 100\n- unbonding_time: 1814400s"
+    );
+
+    let args = serde_json::from_value(json!({ "module": "ibc" })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "There is no `ibc` module. Modules with parameters: blob, staking."
+    );
+}
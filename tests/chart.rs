@@ -0,0 +1,49 @@
+use celestia_search_assistant::chart::{detect, render, sparkline, Series};
+
+#[test]
+fn detects_rows_with_a_numeric_column() {
+    let output =
+        r#"[{"height": 1, "fee": "200"}, {"height": 2, "fee": "100"}, {"height": 3, "fee": "0"}]"#;
+    let series = detect(output).unwrap();
+    assert_eq!(
+        series,
+        Series {
+            metric: "fee".to_string(),
+            points: vec![
+                ("1".to_string(), 200.0),
+                ("2".to_string(), 100.0),
+                ("3".to_string(), 0.0)
+            ],
+        }
+    );
+    assert_eq!(
+        render(&series),
+        format!(
+            "fee\n1 │{} 200\n2 │{} 100\n3 │ 0",
+            "█".repeat(40),
+            "█".repeat(20)
+        )
+    );
+}
+
+#[test]
+fn ignores_non_series() {
+    assert!(detect("    The gas fee is: 2217").is_none());
+    assert!(detect(r#"[{"avg": 0.5}]"#).is_none());
+    assert!(detect(r#"[{"name": "a"}, {"name": "b"}, {"name": "c"}]"#).is_none());
+}
+
+#[test]
+fn long_series_are_sparklines() {
+    assert_eq!(
+        sparkline(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]),
+        "▁▂▃▄▅▆▇█"
+    );
+    assert_eq!(sparkline(&[3.0, 3.0]), "▅▅");
+
+    let rows: Vec<String> = (0..30)
+        .map(|i| format!(r#"{{"blobs": {}}}"#, i % 8))
+        .collect();
+    let series = detect(&format!("[{}]", rows.join(","))).unwrap();
+    assert!(render(&series).starts_with("blobs: ▁▂▃▄▅▆▇█▁"));
+}
@@ -0,0 +1,223 @@
+use std::io::{Read, Write};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn run_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_celestia-search-assistant"))
+        .args(args)
+        .env_remove("OPENAI_API_KEY")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn empty_stdin_fails() {
+    let output = run_with_stdin(&["--prompt", "-", "--no-fiat"], "\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim(),
+        "Error: no question was given on stdin"
+    );
+}
+
+#[test]
+fn piped_prompt_is_read_before_failing_without_a_key() {
+    let output = run_with_stdin(&["--no-fiat"], "What is the fee of block 5?\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim(),
+        "Error: OPENAI_API_KEY is not set"
+    );
+}
+
+#[test]
+fn doctor_diagnoses_a_missing_key() {
+    let config = std::env::temp_dir().join(format!("celestia-doctor-{}.toml", std::process::id()));
+    std::fs::write(
+        &config,
+        "[networks]\nmainnet = \"http://127.0.0.1:1\"\nmocha = \"http://127.0.0.1:1\"\n\
+         arabica = \"http://127.0.0.1:1\"\n",
+    )
+    .unwrap();
+    let sessions = std::env::temp_dir().join(format!("celestia-doctor-{}", std::process::id()));
+    let output = run_with_stdin(
+        &[
+            "--sessions-dir",
+            sessions.to_str().unwrap(),
+            "doctor",
+            "--config",
+            config.to_str().unwrap(),
+        ],
+        "",
+    );
+    std::fs::remove_file(&config).unwrap();
+    let _ = std::fs::remove_dir_all(&sessions);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[  ok] Config:"));
+    assert!(stdout.contains("[FAIL] OpenAI API key: OPENAI_API_KEY is not set"));
+    assert!(stdout.contains("[FAIL] Celenium mainnet: http://127.0.0.1:1"));
+    assert!(stdout.contains("[warn] Celenium mocha:"));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim(),
+        "Error: 2 check(s) failed"
+    );
+}
+
+#[tokio::test]
+async fn daemon_stops_once_the_session_budget_is_spent() {
+    // Every completion costs $0.00021 at gpt-4o-mini prices, more than the budget
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1700000000,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "The chain is quiet." },
+                "finish_reason": "stop",
+            }],
+            "usage": { "prompt_tokens": 1000, "completion_tokens": 100, "total_tokens": 1100 },
+        })))
+        .mount(&server)
+        .await;
+    let config = std::env::temp_dir().join(format!("celestia-daemon-{}.toml", std::process::id()));
+    std::fs::write(
+        &config,
+        format!(
+            "[azure]\nendpoint = \"{uri}\"\napi_key = \"secret\"\n\n\
+             [networks]\nmainnet = \"{uri}\"\nmocha = \"{uri}\"\narabica = \"{uri}\"\n\n\
+             [[schedules]]\nname = \"pulse\"\ncron = \"* * * * * *\"\n\
+             prompt = \"How busy is the chain?\"\nnotify = false\n",
+            uri = server.uri()
+        ),
+    )
+    .unwrap();
+    let sessions = std::env::temp_dir().join(format!("celestia-daemon-{}", std::process::id()));
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_celestia-search-assistant"))
+        .args([
+            "--provider",
+            "azure",
+            "--max-session-usd",
+            "0.0001",
+            "--sessions-dir",
+            sessions.to_str().unwrap(),
+            "--config",
+            config.to_str().unwrap(),
+            "daemon",
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if started.elapsed() > Duration::from_secs(30) {
+            child.kill().unwrap();
+            panic!("the daemon kept running past its budget");
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    };
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    std::fs::remove_file(&config).unwrap();
+    let _ = std::fs::remove_dir_all(&sessions);
+
+    assert_eq!(status.code(), Some(1), "{}", stderr);
+    assert!(
+        stderr.contains("reaching its limit of $0.0001"),
+        "{}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn replays_answer_from_the_recorded_completions() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1700000000,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "The chain is quiet." },
+                "finish_reason": "stop",
+            }],
+            "usage": { "prompt_tokens": 1000, "completion_tokens": 100, "total_tokens": 1100 },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let id = std::process::id();
+    let config = std::env::temp_dir().join(format!("celestia-replay-{}.toml", id));
+    std::fs::write(
+        &config,
+        format!(
+            "[azure]\nendpoint = \"{uri}\"\napi_key = \"secret\"\n\n\
+             [networks]\nmainnet = \"{uri}\"\nmocha = \"{uri}\"\narabica = \"{uri}\"\n",
+            uri = server.uri()
+        ),
+    )
+    .unwrap();
+    let sessions = std::env::temp_dir().join(format!("celestia-replay-{}", id));
+    let recording = std::env::temp_dir().join(format!("celestia-replay-{}.json", id));
+    let ask = |tape: &str| {
+        run_with_stdin(
+            &[
+                "--provider",
+                "azure",
+                "--no-route",
+                "--no-fiat",
+                "--sessions-dir",
+                sessions.to_str().unwrap(),
+                "--config",
+                config.to_str().unwrap(),
+                tape,
+                recording.to_str().unwrap(),
+                "--prompt",
+                "How busy is the chain?",
+            ],
+            "",
+        )
+    };
+
+    let recorded = ask("--record");
+    assert!(recorded.status.success(), "{:?}", recorded);
+    // The replay is answered without the model being asked a second time
+    let replayed = ask("--replay");
+    std::fs::remove_file(&config).unwrap();
+    std::fs::remove_file(&recording).unwrap();
+    let _ = std::fs::remove_dir_all(&sessions);
+
+    assert!(replayed.status.success(), "{:?}", replayed);
+    assert_eq!(replayed.stdout, recorded.stdout);
+    assert!(String::from_utf8_lossy(&replayed.stdout).contains("The chain is quiet."));
+}
@@ -0,0 +1,38 @@
+//! Replays recorded Celenium responses (checked into `tests/fixtures/`), or serves given ones,
+//! through a mock server.
+
+// Each test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Loads the recorded response `tests/fixtures/<name>.json`.
+pub fn fixture(name: &str) -> Value {
+    let file = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let text = std::fs::read_to_string(&file).unwrap_or_else(|e| panic!("{}: {}", file, e));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {}", file, e))
+}
+
+/// Serves the named fixture for `GET <route>` on the mock server.
+pub async fn replay(server: &MockServer, route: &str, name: &str) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture(name)))
+        .mount(server)
+        .await;
+}
+
+/// Serves `body` for `GET <route>` on the mock server.
+pub async fn respond(server: &MockServer, route: &str, body: Value) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(server)
+        .await;
+}
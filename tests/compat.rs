@@ -0,0 +1,87 @@
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::compat::{self, ApiVersion};
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn detects_the_version_from_the_base_url() {
+    assert_eq!(
+        ApiVersion::detect("https://api-mainnet.celenium.io/v1"),
+        ApiVersion::V1
+    );
+    assert_eq!(
+        ApiVersion::detect("https://api-mainnet.celenium.io/v2/"),
+        ApiVersion::V2
+    );
+    assert_eq!(ApiVersion::detect("http://127.0.0.1:8080"), ApiVersion::V1);
+}
+
+#[test]
+fn adapts_v2_responses_to_v1() {
+    let response = json!({ "data": { "lastHeight": 42, "items": [{ "txCount": 1 }] } });
+    assert_eq!(
+        compat::normalize(ApiVersion::V2, response.clone()),
+        json!({ "last_height": 42, "items": [{ "tx_count": 1 }] })
+    );
+    assert_eq!(
+        compat::normalize(ApiVersion::V1, response.clone()),
+        response
+    );
+}
+
+#[test]
+fn rejects_stats_whose_fields_were_renamed() {
+    let stats = json!({ "height": 1, "fee": "10", "gas_fee": "10" });
+    let reason = compat::block_stats(ApiVersion::V2, stats).unwrap_err();
+    assert!(reason.contains("gas_fee"), "{}", reason);
+    assert!(reason.contains("v2 API"), "{}", reason);
+
+    // Fields Celenium leaves out are not a sign of a rename
+    let stats = json!({ "height": 1, "fees": "10" });
+    assert_eq!(
+        compat::block_stats(ApiVersion::V1, stats).unwrap(),
+        json!({ "height": 1, "fee": "10" })
+    );
+}
+
+async fn search(
+    server: &MockServer,
+    stats: serde_json::Value,
+) -> Result<String, CelestiaSearchError> {
+    Mock::given(method("GET"))
+        .and(path("/v2/block/9999/stats"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": stats })))
+        .mount(server)
+        .await;
+
+    let tool = CelestiaSearchTool::with_base_url(&format!("{}/v2", server.uri()));
+    let args = serde_json::from_value(json!({ "height": 9999 })).unwrap();
+    tool.call(args).await
+}
+
+#[tokio::test]
+async fn reads_fees_from_the_v2_api() {
+    let server = MockServer::start().await;
+    let output = search(
+        &server,
+        json!({ "height": 9999, "txCount": "2", "fees": "2217", "gasUsed": "83741" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(output, "    The gas fee is: 2217");
+}
+
+#[tokio::test]
+async fn unknown_renames_are_an_error_rather_than_zero() {
+    let server = MockServer::start().await;
+    let err = search(&server, json!({ "height": 9999, "totalFee": "2217" }))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, CelestiaSearchError::Deserialization { ref reason, .. } if reason.contains("total_fee")),
+        "{:?}",
+        err
+    );
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::countdown_tool::CountdownTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::MockServer;
+
+/// A chain at height 2000 whose blocks came every 6 seconds, and every 5 over the last 100
+async fn count_down(args: Value) -> String {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/head",
+        json!({ "last_height": 2000, "last_time": "2024-06-01T01:40:00Z" }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/block/1000",
+        json!({ "time": "2024-06-01T00:00:00Z" }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/block/1900",
+        json!({ "time": "2024-06-01T01:31:40Z" }),
+    )
+    .await;
+
+    let tool = CountdownTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    tool.call(serde_json::from_value(args).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn counts_down_to_an_upgrade() {
+    assert_eq!(
+        count_down(json!({ "height": 2600, "label": "the v3 upgrade" })).await,
+        "The v3 upgrade (block 2600) is 600 blocks away: about 1 hour to go, around \
+         2024-06-01T02:40:00Z (at 6.00s per block, the average of the last 1000 blocks). At the \
+         5.00s per block of the last 100 blocks, it would be reached around \
+         2024-06-01T02:30:00Z instead."
+    );
+    assert!(count_down(json!({ "height": 52000 }))
+        .await
+        .starts_with("Block 52000 is 50000 blocks away: about 3 days, 11 hours and 20 minutes"));
+}
+
+#[tokio::test]
+async fn reports_heights_already_reached() {
+    assert_eq!(
+        count_down(json!({ "height": 1500 })).await,
+        "Block 1500 has already been reached; the chain is 500 blocks past it (block 2000 at \
+         2024-06-01T01:40:00Z)."
+    );
+}
+
+#[tokio::test]
+async fn reports_heights_past_the_calendar() {
+    assert_eq!(
+        count_down(json!({ "height": u64::MAX })).await,
+        format!(
+            "Block {} is {} blocks away, too far in the future to estimate when it will be \
+             reached.",
+            u64::MAX,
+            u64::MAX - 2000
+        )
+    );
+}
@@ -0,0 +1,85 @@
+use celestia_search_assistant::celenium::CeleniumClient;
+use celestia_search_assistant::doctor::{self, Check, Status};
+use serde_json::json;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn checks_the_openai_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .and(header("authorization", "Bearer good"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let check = doctor::check_openai(&server.uri(), Some("good")).await;
+    assert_eq!(check.status, Status::Ok);
+    let check = doctor::check_openai(&server.uri(), Some("stale")).await;
+    assert_eq!(check.status, Status::Failed);
+    assert!(check.detail.contains("rejected"));
+    let check = doctor::check_openai(&server.uri(), None).await;
+    assert_eq!(
+        check.to_string(),
+        "[FAIL] OpenAI API key: OPENAI_API_KEY is not set"
+    );
+}
+
+#[tokio::test]
+async fn pings_celenium() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 42 })))
+        .mount(&server)
+        .await;
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let check = doctor::check_celenium(&client).await;
+    assert_eq!(
+        check.to_string(),
+        format!(
+            "[  ok] Celenium mainnet: {} (v1 API) is at height 42",
+            server.uri()
+        )
+    );
+
+    let client = CeleniumClient::builder()
+        .base_url("http://127.0.0.1:1")
+        .build()
+        .unwrap();
+    let check = doctor::check_celenium(&client).await;
+    assert_eq!(check.status, Status::Failed);
+    assert_eq!(check.optional().status, Status::Warning);
+}
+
+#[test]
+fn checks_directories_and_counts_problems() {
+    let dir = std::env::temp_dir().join(format!("celestia-doctor-{}", std::process::id()));
+    let check = doctor::check_dir("Sessions directory", &dir);
+    assert_eq!(check.status, Status::Ok);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // A file can't be used as a directory
+    let file = std::env::temp_dir().join(format!("celestia-doctor-{}.txt", std::process::id()));
+    std::fs::write(&file, "").unwrap();
+    let check = doctor::check_dir("Sessions directory", &file);
+    assert_eq!(check.status, Status::Failed);
+    std::fs::remove_file(&file).unwrap();
+
+    let checks = [
+        Check::ok("a", ""),
+        Check::warning("b", ""),
+        Check::failed("c", ""),
+        Check::failed("d", ""),
+    ];
+    assert_eq!(doctor::problems(&checks), (2, 1));
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use celestia_search_assistant::address_tool::AddressTxsTool;
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::enums::Enums;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ADDRESS: &str = "celestia1qnhxmw7nvd8cqpgpakyf2lstfz0kmqzw4g6a2p";
+
+#[tokio::test]
+async fn enums_check_arguments_and_extend_the_preamble() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/enums"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "message_type": ["MsgSend", "MsgPayForBlobs"],
+            "event_type": ["transfer"],
+            "categories": ["gaming", "finance"],
+            "status": ["success", "failed"],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/txs", ADDRESS)))
+        .and(query_param("msg_type", "MsgSend"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let blocks = CelestiaSearchTool::with_base_url(&server.uri());
+    let enums = Enums::fetch(&blocks).await.unwrap();
+    assert_eq!(
+        enums.preamble(),
+        "Celenium names these exactly as follows; use these names in tool arguments:\n\
+         - Message types: MsgSend, MsgPayForBlobs\n\
+         - Event types: transfer\n\
+         - Rollup categories: gaming, finance\n\
+         - Transaction statuses: success, failed"
+    );
+
+    let tool = AddressTxsTool::new(blocks.with_enums(Arc::new(enums)));
+    let args =
+        serde_json::from_value(json!({ "address": ADDRESS, "message_type": "MsgSend" })).unwrap();
+    assert!(tool.call(args).await.is_ok());
+
+    let args =
+        serde_json::from_value(json!({ "address": ADDRESS, "message_type": "MsgSnd" })).unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown message type `MsgSnd` (expected one of: MsgSend, MsgPayForBlobs)"
+    );
+    assert!(matches!(err, CelestiaSearchError::UnknownValue { .. }));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::estimate_time_tool::EstimateTimeTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::MockServer;
+
+/// A chain at height 1100 whose last 100 blocks came every 6 seconds
+async fn estimate(args: Value) -> String {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/head",
+        json!({ "last_height": 1100, "last_time": "2024-06-01T00:10:00Z" }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/block/1000",
+        json!({ "time": "2024-06-01T00:00:00Z" }),
+    )
+    .await;
+
+    let tool = EstimateTimeTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    tool.call(serde_json::from_value(args).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn past_blocks_have_their_recorded_time() {
+    assert_eq!(
+        estimate(json!({ "height": 1000 })).await,
+        "Block 1000 was produced at 2024-06-01T00:00:00Z (its recorded time)."
+    );
+}
+
+#[tokio::test]
+async fn estimates_when_future_blocks_come() {
+    assert_eq!(
+        estimate(json!({ "height": 1700 })).await,
+        "Block 1700 is 600 blocks past the head and should be produced around \
+         2024-06-01T01:10:00Z, in about 1h 0m (at 6.00s per block on average over the last 100 \
+         blocks, from block 1100 at 2024-06-01T00:10:00Z)."
+    );
+}
+
+#[tokio::test]
+async fn estimates_the_height_at_a_time() {
+    assert_eq!(
+        estimate(json!({ "time": "2024-06-01T01:10:00Z" })).await,
+        "At 2024-06-01T01:10:00Z the chain should be at about height 1700 (1h 0m after the head, \
+         at 6.00s per block on average over the last 100 blocks, from block 1100 at \
+         2024-06-01T00:10:00Z)."
+    );
+    assert!(estimate(json!({ "time": "2024-06-01" }))
+        .await
+        .starts_with("At 2024-06-01T00:00:00Z the chain was at about height 1000 (10m 0s before"));
+    assert!(estimate(json!({ "time": "midnight" }))
+        .await
+        .contains("should be at about height"));
+}
+
+#[tokio::test]
+async fn asks_for_a_height_or_a_time() {
+    assert_eq!(
+        estimate(json!({ "height": 1000, "time": "now" })).await,
+        "Give a height or a time to convert, but not both."
+    );
+    assert!(estimate(json!({ "time": "tomorrow" }))
+        .await
+        .starts_with("`tomorrow` is not a time"));
+}
+
+#[tokio::test]
+async fn rejects_times_beyond_the_calendar() {
+    assert_eq!(
+        estimate(json!({ "height": u64::MAX })).await,
+        format!(
+            "Block {} is too far in the future to estimate when it will be produced.",
+            u64::MAX
+        )
+    );
+    assert!(estimate(json!({ "time": "+9223372036854775807d" }))
+        .await
+        .starts_with("`+9223372036854775807d` is not a time"));
+}
@@ -0,0 +1,94 @@
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::event_search_tool::{parse_query, EventCondition, EventSearchTool};
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn parses_conditions_joined_by_and() {
+    assert_eq!(
+        parse_query("transfer.recipient=celestia1abc and message.sender").unwrap(),
+        vec![
+            EventCondition {
+                event_type: "transfer".to_string(),
+                attribute: "recipient".to_string(),
+                value: Some("celestia1abc".to_string()),
+            },
+            EventCondition {
+                event_type: "message".to_string(),
+                attribute: "sender".to_string(),
+                value: None,
+            },
+        ]
+    );
+    assert_eq!(
+        parse_query("transfer.amount='1000utia'").unwrap()[0]
+            .value
+            .as_deref(),
+        Some("1000utia")
+    );
+    for invalid in [
+        "",
+        "recipient=celestia1abc",
+        "transfer.recipient='x' OR 1=1'",
+        "a b.c=d",
+    ] {
+        assert!(
+            matches!(
+                parse_query(invalid),
+                Err(CelestiaSearchError::InvalidEventQuery(_))
+            ),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[tokio::test]
+async fn scans_block_events_for_matching_transactions() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/101/events"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "type": "transfer", "tx_id": 7, "data": { "recipient": "celestia1abc", "amount": "5utia" } },
+            { "type": "message", "tx_id": 7, "data": { "sender": "celestia1xyz" } },
+            { "type": "transfer", "tx_id": 8, "data": { "recipient": "celestia1other", "amount": "9utia" } },
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/block/100/events"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "type": "transfer", "data": { "recipient": "celestia1abc", "amount": "1utia" } },
+        ])))
+        .mount(&server)
+        .await;
+    let tool = EventSearchTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({
+        "query": "transfer.recipient=celestia1abc AND message.sender",
+        "from": 100,
+        "to": 101,
+    }))
+    .unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "1 transaction(s) between blocks 100 and 101 have events meeting the 2 condition(s), \
+         according to the indexer's block events:\n\
+         - tx with indexer id 7 at height 101: transfer{amount=5utia, recipient=celestia1abc}; \
+         message{sender=celestia1xyz}"
+    );
+
+    // Wider ranges need a consensus node
+    let args = serde_json::from_value(json!({
+        "query": "transfer.recipient=celestia1abc",
+        "from": 1,
+        "to": 101,
+    }))
+    .unwrap();
+    assert!(matches!(
+        tool.call(args).await,
+        Err(CelestiaSearchError::InvalidRange(reason)) if reason.contains("consensus_url")
+    ));
+}
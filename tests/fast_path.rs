@@ -0,0 +1,96 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::fast_path::{Shortcut, CHAIN_HEAD};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn simple_questions_are_shortcuts() {
+    for question in [
+        "What is the gas fee of the Celestia block at height 9999?",
+        "gas fee of block 9,999",
+        "Block #9999 fee",
+        "What’s the total fee for block 9999",
+    ] {
+        assert_eq!(
+            Shortcut::parse(question),
+            Some(Shortcut::BlockFee(9999)),
+            "{}",
+            question
+        );
+    }
+    for question in [
+        "current height",
+        "What is the latest block height?",
+        "chain head",
+    ] {
+        assert_eq!(
+            Shortcut::parse(question),
+            Some(Shortcut::ChainHead),
+            "{}",
+            question
+        );
+    }
+}
+
+#[test]
+fn anything_more_is_left_to_the_agent() {
+    for question in [
+        "What was the gas fee of block 9999 on mocha?",
+        "Compare the fees of block 9999 and block 10000",
+        "fee of block 0",
+        "What is the fee in TIA of block 9999?",
+        "What is the average fee of the latest blocks?",
+        "block height",
+        "",
+    ] {
+        assert_eq!(Shortcut::parse(question), None, "{}", question);
+    }
+}
+
+#[tokio::test]
+async fn shortcuts_are_answered_from_the_lookup() {
+    let server = MockServer::start().await;
+    common::replay(&server, "/block/9999/stats", "block_stats_9999").await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 2000000 })))
+        .mount(&server)
+        .await;
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+
+    let turn = Shortcut::BlockFee(9999).answer(&tool).await.unwrap();
+    assert_eq!(turn.output, "The gas fee of block 9999 on mainnet is 0.");
+    assert_eq!(turn.usage.total(), 0);
+    let call = turn.tool_call.unwrap();
+    assert_eq!(call.name, "search_blocks");
+    assert_eq!(call.args, json!({ "height": 9999 }));
+    assert!(call.upstream.last().unwrap().ends_with("/block/9999/stats"));
+
+    let turn = Shortcut::ChainHead.answer(&tool).await.unwrap();
+    assert_eq!(
+        turn.output,
+        "The latest block on mainnet is at height 2000000."
+    );
+    assert_eq!(turn.tool_call.unwrap().name, CHAIN_HEAD);
+}
+
+#[tokio::test]
+async fn failed_lookups_are_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/99999999/stats"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+
+    let err = Shortcut::BlockFee(99999999)
+        .answer(&tool)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, CelestiaSearchError::NotFound { .. }));
+}
@@ -0,0 +1,54 @@
+use celestia_search_assistant::accounting::TokenUsage;
+use celestia_search_assistant::assistant::{ToolCall, Turn};
+use celestia_search_assistant::format::{self, Detail, OutputFormat};
+use celestia_search_assistant::locale::ENGLISH;
+use serde_json::json;
+
+fn turn(output: &str) -> Turn {
+    Turn {
+        output: output.to_string(),
+        tool_call: Some(ToolCall {
+            name: "search_blocks".to_string(),
+            args: json!({ "height": 10 }),
+            output: Some(json!("fee: 1.5 TIA").to_string()),
+            ..ToolCall::default()
+        }),
+        steps: Vec::new(),
+        usage: TokenUsage::default(),
+        warnings: Vec::new(),
+    }
+}
+
+#[test]
+fn first_sentences_end_at_a_stop_before_whitespace() {
+    assert_eq!(
+        format::first_sentence("Block 10 paid 1.5 TIA in fees. That is above average."),
+        "Block 10 paid 1.5 TIA in fees."
+    );
+    assert_eq!(
+        format::first_sentence("Block 10 paid 1.5 TIA\n- 2 transactions"),
+        "Block 10 paid 1.5 TIA"
+    );
+    assert_eq!(format::first_sentence("  1.5 TIA  "), "1.5 TIA");
+}
+
+#[test]
+fn concise_answers_are_their_first_sentence_alone() {
+    let turn = turn("Block 10 paid 1.5 TIA in fees. Its two transactions paid 0.75 TIA each.");
+    let text = format::render("Fee?", &turn, OutputFormat::Text, Detail::Concise, ENGLISH).unwrap();
+    assert_eq!(text, "Block 10 paid 1.5 TIA in fees.");
+
+    let json = format::render("Fee?", &turn, OutputFormat::Json, Detail::Concise, ENGLISH).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["answer"], "Block 10 paid 1.5 TIA in fees.");
+}
+
+#[test]
+fn detailed_answers_are_followed_by_the_tool_data() {
+    let turn = turn("Block 10 paid 1.5 TIA in fees.");
+    assert_eq!(
+        format::render("Fee?", &turn, OutputFormat::Text, Detail::Detailed, ENGLISH).unwrap(),
+        "Agent response:\nBlock 10 paid 1.5 TIA in fees.\n\n\
+         Data from `search_blocks` with {\"height\":10}:\nfee: 1.5 TIA"
+    );
+}
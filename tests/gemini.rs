@@ -0,0 +1,140 @@
+use celestia_search_assistant::accounting::ReportsUsage;
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::gemini;
+use rig::completion::{CompletionModel, ModelChoice, ToolDefinition};
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn config(server: &MockServer) -> gemini::GeminiConfig {
+    let config = Config::parse(&format!(
+        "[gemini]\nmodel = \"gemini-2.0-flash\"\napi_key = \"secret\"\nbase_url = \"{}\"\n\
+         [gemini.safety_settings]\nHARM_CATEGORY_DANGEROUS_CONTENT = \"BLOCK_ONLY_HIGH\"\n",
+        server.uri()
+    ))
+    .unwrap();
+    config.gemini.unwrap()
+}
+
+#[tokio::test]
+async fn calls_tools_with_the_configured_safety_settings() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/models/gemini-2.0-flash:generateContent"))
+        .and(header("x-goog-api-key", "secret"))
+        .and(body_partial_json(json!({
+            "systemInstruction": { "parts": [{ "text": "Answer briefly." }] },
+            "contents": [
+                { "role": "user", "parts": [{ "text": "What is the fee of block 9999?" }] },
+            ],
+            "safetySettings": [
+                { "category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_ONLY_HIGH" },
+            ],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{ "functionCall": { "name": "search_blocks", "args": { "height": 9999 } } }],
+                },
+                "finishReason": "STOP",
+            }],
+            "usageMetadata": { "promptTokenCount": 12, "candidatesTokenCount": 3, "totalTokenCount": 15 },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = config(&server);
+    let model = gemini::Client::from_config(&config, "secret").completion_model("gemini-2.0-flash");
+    let response = model
+        .completion_request("What is the fee of block 9999?")
+        .preamble("Answer briefly.".to_string())
+        .tool(ToolDefinition {
+            name: "search_blocks".to_string(),
+            description: "Look up a block".to_string(),
+            parameters: json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "height": { "type": "integer", "examples": [9999] },
+                    "default": { "type": "boolean", "default": false },
+                },
+            }),
+        })
+        .send()
+        .await
+        .unwrap();
+    match response.choice {
+        ModelChoice::ToolCall(name, args) => {
+            assert_eq!(name, "search_blocks");
+            assert_eq!(args, json!({ "height": 9999 }));
+        }
+        ModelChoice::Message(message) => panic!("expected a tool call, got {}", message),
+    }
+    let usage = response.raw_response.token_usage().unwrap();
+    assert_eq!((usage.prompt_tokens, usage.completion_tokens), (12, 3));
+
+    // The keywords Gemini rejects are left out, but not properties named after them
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = requests[0].body_json().unwrap();
+    assert_eq!(
+        body["tools"][0]["functionDeclarations"][0]["parameters"],
+        json!({
+            "type": "object",
+            "properties": {
+                "height": { "type": "integer" },
+                "default": { "type": "boolean" },
+            },
+        })
+    );
+}
+
+#[tokio::test]
+async fn reports_blocked_prompts() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "promptFeedback": { "blockReason": "SAFETY" },
+        })))
+        .mount(&server)
+        .await;
+
+    let model = gemini::Client::from_config(&config(&server), "secret")
+        .completion_model("gemini-2.0-flash");
+    let err = model
+        .completion_request("Hello")
+        .send()
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err.to_string(),
+        "ProviderError: Gemini blocked the prompt (SAFETY)"
+    );
+}
+
+#[tokio::test]
+async fn reports_rejected_requests() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": { "code": 400, "message": "API key not valid.", "status": "INVALID_ARGUMENT" },
+        })))
+        .mount(&server)
+        .await;
+
+    let model = gemini::Client::new(&server.uri(), "wrong").completion_model("gemini-2.0-flash");
+    let err = model
+        .completion_request("Hello")
+        .send()
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err.to_string(),
+        "ProviderError: Gemini answered with status 400 Bad Request: API key not valid."
+    );
+}
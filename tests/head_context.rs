@@ -0,0 +1,45 @@
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::head_context;
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn describes_the_chain_head_reusing_it_for_a_while() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "last_height": 1100, "last_time": "2024-06-01T00:10:00Z" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/block/1000"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "time": "2024-06-01T00:00:00Z" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let blocks = CelestiaSearchTool::with_base_url(&server.uri());
+    let now = Utc.with_ymd_and_hms(2024, 6, 1, 0, 10, 4).unwrap();
+    let context = head_context::describe(&blocks, now).await.unwrap();
+    assert_eq!(
+        context,
+        "The chain head on mainnet was block 1100 at the start of this turn, produced at \
+         2024-06-01T00:10:00Z (4s before now, 2024-06-01T00:10:04Z). Blocks took 6.00s on \
+         average over the last 100 blocks, so work out heights relative to now from these \
+         rather than calling a tool."
+    );
+
+    // The next turn reuses the head, only telling the time anew
+    let later = Utc.with_ymd_and_hms(2024, 6, 1, 0, 10, 9).unwrap();
+    let context = head_context::describe(&blocks, later).await.unwrap();
+    assert!(context.contains("block 1100"));
+    assert!(context.contains("(9s before now, 2024-06-01T00:10:09Z)"));
+}
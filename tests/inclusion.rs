@@ -0,0 +1,155 @@
+#![cfg(feature = "node-rpc")]
+
+mod common;
+
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use celestia_search_assistant::inclusion::{blob_shares, share_commitment, NAMESPACE_SIZE};
+use celestia_search_assistant::node::NodeClient;
+use celestia_search_assistant::verify_blob_tool::VerifyBlobTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// The commitment of the blob in the `blob_inclusion` fixture
+const COMMITMENT: &str = "n2LEbPFNWzIIFKV7uJwiJ8xErsFlvKlZ6p7yKsXNDfE=";
+
+fn namespace() -> Vec<u8> {
+    let mut namespace = vec![0; NAMESPACE_SIZE];
+    namespace[27] = 0x0a;
+    namespace[28] = 0xbc;
+    namespace
+}
+
+fn blob_data() -> Vec<u8> {
+    (0..600).map(|i| (i % 251) as u8).collect()
+}
+
+async fn respond(server: &MockServer, rpc_method: &str, result: Value) {
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "method": rpc_method })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "id": 1, "result": result })),
+        )
+        .mount(server)
+        .await;
+}
+
+/// A node serving the `blob_inclusion` fixture: a 2x2 square whose first row holds a two-share
+/// blob, its header and the blob's proof, computed apart from this crate. The node reports the
+/// blob as holding `served` instead of the real data.
+async fn node_server(served: &[u8]) -> MockServer {
+    let fixture = common::fixture("blob_inclusion");
+    let mut blob = fixture["blob"].clone();
+    blob["data"] = json!(BASE64.encode(served));
+
+    let server = MockServer::start().await;
+    respond(&server, "header.GetByHeight", fixture["header"].clone()).await;
+    respond(&server, "blob.Get", blob).await;
+    respond(&server, "blob.GetProof", fixture["proof"].clone()).await;
+    server
+}
+
+fn args(commitment: &str) -> <VerifyBlobTool as Tool>::Args {
+    serde_json::from_value(json!({
+        "height": 100,
+        "namespace": hex::encode(namespace()),
+        "commitment": commitment,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn share_commitments_match_the_reference() {
+    // Blobs of 0xFF filling 1, 2, 3 and 130 shares, in a namespace of id 0x01 x 10
+    let mut namespace = vec![0; NAMESPACE_SIZE - 10];
+    namespace.extend([1; 10]);
+    for (shares, size, expected) in [
+        (
+            1,
+            478,
+            "5d5f75afdc818b8cd18e83682d016e34a82129cfdfe99a7e2eb035d1fe27b804",
+        ),
+        (
+            2,
+            960,
+            "6d7bfbd95c0c4799999678412b0dc93e0f4d3c7b3e8df816bcbde1ef2964c642",
+        ),
+        (
+            3,
+            1442,
+            "89a151de5c7e047cd7538a596c90d8dcca908429e26dbe666d4c8f2fc05ade58",
+        ),
+        (
+            130,
+            478 + 482 * 129,
+            "ff413ed856ebbae4ebbdf0f2d255aeefde6ebb7b85f9537672faf8869432da29",
+        ),
+    ] {
+        let split = blob_shares(&namespace, &vec![0xFF; size], 0, None);
+        assert_eq!(split.len(), shares);
+        assert_eq!(
+            hex::encode(share_commitment(&namespace, &split)),
+            expected,
+            "{} shares",
+            shares
+        );
+    }
+}
+
+#[tokio::test]
+async fn verifies_included_blobs() {
+    let server = node_server(&blob_data()).await;
+    let tool = VerifyBlobTool::new(Arc::new(NodeClient::new(&server.uri(), None)));
+
+    let output = tool.call(args(COMMITMENT)).await.unwrap();
+    assert!(
+        output.starts_with(&format!(
+            "Blob {} at height 100 is included: its 2 share(s) in rows 0 to 0 verify",
+            COMMITMENT
+        )),
+        "{}",
+        output
+    );
+}
+
+#[tokio::test]
+async fn rejects_blobs_of_another_commitment() {
+    let server = node_server(&blob_data()).await;
+    let tool = VerifyBlobTool::new(Arc::new(NodeClient::new(&server.uri(), None)));
+    let other = BASE64.encode([7; 32]);
+
+    assert_eq!(
+        tool.call(args(&other)).await.unwrap(),
+        format!(
+            "Blob {} at height 100 could NOT be verified: the blob the node served doesn't hash \
+             to the commitment.",
+            other
+        )
+    );
+}
+
+#[tokio::test]
+async fn rejects_tampered_blobs() {
+    let mut tampered = blob_data();
+    tampered[10] ^= 1;
+    let server = node_server(&tampered).await;
+    let tool = VerifyBlobTool::new(Arc::new(NodeClient::new(&server.uri(), None)));
+    // A node lying consistently about the blob still can't match the row roots
+    let commitment = BASE64.encode(share_commitment(
+        &namespace(),
+        &blob_shares(&namespace(), &tampered, 0, None),
+    ));
+
+    assert_eq!(
+        tool.call(args(&commitment)).await.unwrap(),
+        format!(
+            "Blob {} at height 100 could NOT be verified: the shares in row 0 don't match its \
+             root.",
+            commitment
+        )
+    );
+}
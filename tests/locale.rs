@@ -0,0 +1,36 @@
+use celestia_search_assistant::locale::{self, ENGLISH};
+
+#[test]
+fn parses_languages_by_code() {
+    assert_eq!(locale::parse("de").unwrap().name, "German");
+    assert_eq!(locale::parse("pt-BR").unwrap().name, "Portuguese");
+    assert_eq!(locale::parse(" FR ").unwrap().name, "French");
+    assert!(locale::parse("xx")
+        .unwrap_err()
+        .contains("expected one of: en, de"));
+    assert_eq!(ENGLISH.instructions(), None);
+    assert!(locale::parse("ja")
+        .unwrap()
+        .instructions()
+        .unwrap()
+        .starts_with("Answer in Japanese"));
+}
+
+#[test]
+fn writes_decimal_and_grouped_numbers_the_languages_way() {
+    let german = locale::parse("de").unwrap();
+    assert_eq!(
+        german.localize("Block 2000000 zahlte 1,234,567 utia (1.234567 TIA), also 5.00%."),
+        "Block 2000000 zahlte 1.234.567 utia (1,234567 TIA), also 5,00%."
+    );
+    // Numbers within hashes and versions, and other dotted forms, are left alone
+    assert_eq!(
+        german.localize("Hash 4BE5.31, v0.14.0, 127.0.0.1 und 12,34."),
+        "Hash 4BE5.31, v0.14.0, 127.0.0.1 und 12,34."
+    );
+    assert_eq!(
+        locale::parse("fr").unwrap().localize("2,048.5 octets"),
+        "2\u{202f}048,5 octets"
+    );
+    assert_eq!(ENGLISH.localize("1,234.5"), "1,234.5");
+}
@@ -0,0 +1,40 @@
+use celestia_search_assistant::lru::LruCache;
+use celestia_search_assistant::metrics::metrics;
+
+#[test]
+fn evicts_the_least_recently_used_entry() {
+    let mut cache = LruCache::new("test_entries", 2, 1000);
+    cache.insert("a", 1, 10);
+    cache.insert("b", 2, 10);
+    assert_eq!(cache.get("a"), Some(&1));
+
+    cache.insert("c", 3, 10);
+    assert_eq!(cache.get("b"), None);
+    assert_eq!(cache.get("a"), Some(&1));
+    assert_eq!(cache.get("c"), Some(&3));
+    assert_eq!((cache.len(), cache.bytes()), (2, 20));
+    assert_eq!(
+        metrics().cache_evictions.get(&["test_entries", "entries"]),
+        1
+    );
+}
+
+#[test]
+fn stays_within_its_memory_bound() {
+    let mut cache = LruCache::new("test_memory", 100, 100);
+    cache.insert("a", "small", 40);
+    cache.insert("b", "small", 40);
+    cache.insert("a", "replaced", 50);
+    assert_eq!(cache.bytes(), 90);
+
+    // Making room for `c` takes evicting `b`, then `a`
+    cache.insert("c", "large", 80);
+    assert_eq!(cache.get("a"), None);
+    assert_eq!(cache.get("b"), None);
+    assert_eq!((cache.len(), cache.bytes()), (1, 80));
+
+    cache.insert("d", "too large", 101);
+    assert!(cache.is_empty());
+    assert_eq!(cache.bytes(), 0);
+    assert_eq!(metrics().cache_evictions.get(&["test_memory", "memory"]), 4);
+}
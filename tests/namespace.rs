@@ -0,0 +1,202 @@
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::namespace_blobs_tool::NamespaceBlobsTool;
+use celestia_search_assistant::namespace_tool::NamespaceStatsTool;
+use celestia_search_assistant::square_tool::SquareTool;
+use celestia_search_assistant::top_namespaces_tool::TopNamespacesTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A version 0 namespace, without its version byte.
+const NAMESPACE: &str = "00000000000000000000000000000000000000000000000000000abc";
+
+async fn series(server: &MockServer, name: &str, points: Value) {
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/stats/namespace/series/00{}/day/{}",
+            NAMESPACE, name
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(points))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn reports_daily_namespace_usage() {
+    let server = MockServer::start().await;
+    series(
+        &server,
+        "pfb_count",
+        json!([
+            { "time": "2024-06-01T00:00:00Z", "value": "3" },
+            { "time": "2024-06-02T00:00:00Z", "value": "5" },
+        ]),
+    )
+    .await;
+    series(
+        &server,
+        "size",
+        json!([
+            { "time": "2024-06-01T00:00:00Z", "value": "1500" },
+            { "time": "2024-06-02T00:00:00Z", "value": "2500" },
+        ]),
+    )
+    .await;
+    series(
+        &server,
+        "fee",
+        json!([
+            { "time": "2024-06-01T00:00:00Z", "value": "600" },
+            { "time": "2024-06-02T00:00:00Z", "value": "1400" },
+        ]),
+    )
+    .await;
+    let tool = NamespaceStatsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "namespace": NAMESPACE, "days": 2 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        format!(
+            "Namespace 00{NAMESPACE} over the last 2 day(s): 8 blob(s), 3.91 KiB, 2000 utia \
+             (0.002 TIA) in fees.\n\
+             - 2024-06-01: 3 blob(s), 1.46 KiB, 600 utia\n\
+             - 2024-06-02: 5 blob(s), 2.44 KiB, 1400 utia"
+        )
+    );
+
+    let args = serde_json::from_value(json!({ "namespace": "not-hex" })).unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::InvalidNamespace(_)));
+}
+
+#[tokio::test]
+async fn lists_namespace_blobs() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/namespace/{}/0/blobs", NAMESPACE)))
+        .and(query_param("limit", "2"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "height": 300,
+                "time": "2024-06-02T00:00:00Z",
+                "size": 1200,
+                "signer": "celestia1rollup",
+                "commitment": "Y29tbWl0MQ==",
+            },
+            {
+                "height": 290,
+                "time": "2024-06-01T00:00:00Z",
+                "size": 800,
+                "signer": "celestia1rollup",
+                "commitment": "Y29tbWl0Mg==",
+            },
+        ])))
+        .mount(&server)
+        .await;
+    let tool = NamespaceBlobsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "namespace": NAMESPACE, "limit": 2 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        format!(
+            "Blobs 1 to 2 of namespace 00{NAMESPACE}, newest first (1.95 KiB in total):\n\
+             - height 300 (2024-06-02T00:00:00Z): 1.17 KiB, signed by celestia1rollup, \
+             commitment Y29tbWl0MQ==\n\
+             - height 290 (2024-06-01T00:00:00Z): 800 B, signed by celestia1rollup, \
+             commitment Y29tbWl0Mg==\n\
+             Older blobs may follow; call namespace_blobs with cursor \
+             `namespace_blobs:2:784f9004` for the next page."
+        )
+    );
+}
+
+#[tokio::test]
+async fn describes_square_layout() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/block/42/ods"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "width": 4,
+            "items": [
+                { "from": [0, 0], "to": [0, 1], "namespace": "pfb", "type": "pay_for_blob" },
+                { "from": [0, 2], "to": [1, 3], "namespace": "rollup-a", "type": "blob" },
+                { "from": [2, 0], "to": [2, 1], "namespace": "rollup-b", "type": "blob" },
+                { "from": [2, 2], "to": [3, 3], "namespace": "rollup-b", "type": "blob" },
+            ],
+        })))
+        .mount(&server)
+        .await;
+    let tool = SquareTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "height": 42 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Block 42 has a 4x4 original data square (16 shares) holding 3 namespace(s):\n\
+         - pfb (pay_for_blob): 2 share(s)\n\
+         - rollup-a (blob): 6 share(s)\n\
+         - rollup-b (blob): 8 share(s)\n\
+         Namespaces by row:\n\
+         - row 0: pfb, rollup-a\n\
+         - row 1: rollup-a\n\
+         - rows 2-3: rollup-b"
+    );
+}
+
+#[tokio::test]
+async fn ranks_namespaces_over_the_period() {
+    const OTHER: &str = "00000000000000000000000000000000000000000000000000000def";
+    let server = MockServer::start().await;
+    for (sort_by, listing) in [
+        (
+            "size",
+            json!([
+                { "namespace_id": NAMESPACE, "version": 0, "name": "" },
+                { "namespace_id": OTHER, "version": 0, "name": "rollup-b" },
+            ]),
+        ),
+        (
+            "time",
+            json!([{ "namespace_id": OTHER, "version": 0, "name": "rollup-b" }]),
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/namespace"))
+            .and(query_param("sort_by", sort_by))
+            .respond_with(ResponseTemplate::new(200).set_body_json(listing))
+            .mount(&server)
+            .await;
+    }
+    for (namespace, fee, size) in [(NAMESPACE, "600", "9000"), (OTHER, "1400", "2500")] {
+        for (name, value) in [("fee", fee), ("size", size)] {
+            Mock::given(method("GET"))
+                .and(path(format!(
+                    "/stats/namespace/series/00{}/day/{}",
+                    namespace, name
+                )))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                    { "time": "2024-06-01T00:00:00Z", "value": value },
+                ])))
+                .mount(&server)
+                .await;
+        }
+    }
+    let tool = TopNamespacesTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "days": 7 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        format!(
+            "The 2 namespaces that paid the most fees in the last 7 day(s), among the largest and \
+             most recently active:\n\
+             1. rollup-b (00{OTHER}): 1400 utia (0.0014 TIA) in fees for 2.44 KiB\n\
+             2. 00{NAMESPACE}: 600 utia (0.0006 TIA) in fees for 8.79 KiB"
+        )
+    );
+
+    let args = serde_json::from_value(json!({ "by": "bytes", "limit": 1 })).unwrap();
+    assert!(tool.call(args).await.unwrap().ends_with(&format!(
+        "\n1. 00{NAMESPACE}: 600 utia (0.0006 TIA) in fees for 8.79 KiB"
+    )));
+}
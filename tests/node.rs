@@ -0,0 +1,393 @@
+#![cfg(feature = "node-rpc")]
+
+use std::sync::Arc;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::event_search_tool::EventSearchTool;
+use celestia_search_assistant::mempool_tool::MempoolTool;
+use celestia_search_assistant::node::{NodeClient, NodeError};
+use celestia_search_assistant::node_retention_tool::NodeRetentionTool;
+use celestia_search_assistant::node_status_tool::NodeStatusTool;
+use celestia_search_assistant::sampling_tool::SamplingTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+async fn respond(server: &MockServer, rpc_method: &str, body: Value) {
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "method": rpc_method })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(server)
+        .await;
+}
+
+async fn indexer(head: u64) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": head })))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn reports_a_caught_up_node_as_healthy() {
+    let node = MockServer::start().await;
+    respond(
+        &node,
+        "das.SamplingStats",
+        json!({ "id": 1, "result": {
+            "head_of_sampled_chain": 1995,
+            "head_of_catchup": 1995,
+            "network_head_height": 2000,
+            "is_running": true,
+            "catch_up_done": true,
+        }}),
+    )
+    .await;
+    respond(
+        &node,
+        "header.LocalHead",
+        json!({ "id": 1, "result": { "header": { "height": "2000" } } }),
+    )
+    .await;
+    let indexer = indexer(2003).await;
+    let tool = SamplingTool::new(
+        Arc::new(NodeClient::new(&node.uri(), None)),
+        CelestiaSearchTool::with_base_url(&indexer.uri()),
+    );
+
+    let args = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The node is sampling. It has sampled up to height 1995 of the 2000 it sees as the \
+         network head (5 block(s) behind), and synced headers up to 2000. It has finished \
+         catching up on past heights. The indexer's head is 2003, so the node's view of the \
+         network is 3 block(s) behind it. The node looks healthy and caught up."
+    );
+}
+
+#[tokio::test]
+async fn flags_a_lagging_node() {
+    let node = MockServer::start().await;
+    respond(
+        &node,
+        "das.SamplingStats",
+        json!({ "id": 1, "result": {
+            "head_of_sampled_chain": 900,
+            "head_of_catchup": 400,
+            "network_head_height": 1000,
+            "is_running": true,
+            "catch_up_done": false,
+        }}),
+    )
+    .await;
+    respond(
+        &node,
+        "header.LocalHead",
+        json!({ "id": 1, "result": { "header": { "height": "1000" } } }),
+    )
+    .await;
+    let indexer = indexer(1000).await;
+    let tool = SamplingTool::new(
+        Arc::new(NodeClient::new(&node.uri(), None)),
+        CelestiaSearchTool::with_base_url(&indexer.uri()),
+    );
+
+    let output = tool
+        .call(serde_json::from_value(json!({})).unwrap())
+        .await
+        .unwrap();
+    assert!(output.contains("still catching up on past heights (done up to 400)"));
+    assert!(output.ends_with("The node is NOT caught up."));
+}
+
+#[tokio::test]
+async fn node_errors_and_auth_are_handled() {
+    let node = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(header("authorization", "Bearer secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": 1,
+            "error": { "code": 1, "message": "method not found" },
+        })))
+        .mount(&node)
+        .await;
+    let client = NodeClient::new(&node.uri(), Some("secret".to_string()));
+
+    let err = client
+        .call::<Value>("das.SamplingStats", json!([]))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, NodeError::Rpc { ref method, ref message } if method == "das.SamplingStats" && message == "method not found")
+    );
+}
+
+#[tokio::test]
+async fn samples_the_mempool_of_the_consensus_node() {
+    let consensus = MockServer::start().await;
+    respond(
+        &consensus,
+        "unconfirmed_txs",
+        json!({ "id": 1, "result": {
+            "n_txs": "2",
+            "total": "12",
+            "total_bytes": "48200",
+            "txs": [
+                "Cgxpbm5lciBwZmIgdHgSZHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHgaBEJMT0I=",
+                "YSBwbGFpbiBzZW5kIHR4",
+            ],
+        }}),
+    )
+    .await;
+    let node = NodeClient::new("http://localhost:26658", None).with_consensus(&consensus.uri());
+    let tool = MempoolTool::new(Arc::new(node));
+
+    let args = serde_json::from_value(json!({ "sample": 2 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "12 transaction(s) totalling 47.07 KiB are pending in the mempool. Of the 2 sampled, 1 \
+         are PFBs carrying blobs:\n\
+         - 4BE5318258F55A3C9757E0348367A6F460B08CFAB133CB80D6B1B672D38002D2: 122 B, a PFB \
+         with 1 blob(s) of 100 B\n\
+         - 277272993676250630D2700C2D93252A21015C31431598CB7E492555CA67DD71: 15 B"
+    );
+
+    let empty = MockServer::start().await;
+    respond(
+        &empty,
+        "num_unconfirmed_txs",
+        json!({ "id": 1, "result": { "n_txs": "0", "total": "0", "total_bytes": "0" } }),
+    )
+    .await;
+    let node = NodeClient::new("http://localhost:26658", None).with_consensus(&empty.uri());
+    let args = serde_json::from_value(json!({ "sample": 0 })).unwrap();
+    assert!(MempoolTool::new(Arc::new(node))
+        .call(args)
+        .await
+        .unwrap()
+        .starts_with("The mempool is empty"));
+}
+
+#[tokio::test]
+async fn searches_events_through_the_consensus_node() {
+    let consensus = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "method": "tx_search",
+            "params": {
+                "query": "transfer.recipient='celestia1abc' AND tx.height>=10 AND tx.height<=20",
+                "order_by": "desc",
+            },
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "id": 1, "result": {
+                "total_count": "3",
+                "txs": [
+                    {
+                        "hash": "AA11",
+                        "height": "18",
+                        "tx_result": { "code": 0, "events": [
+                            // CometBFT 0.34 encodes attributes in base64
+                            { "type": "transfer", "attributes": [
+                                { "key": "cmVjaXBpZW50", "value": "Y2VsZXN0aWExYWJj" },
+                                { "key": "YW1vdW50", "value": "NXV0aWE=" },
+                            ] },
+                            { "type": "tx", "attributes": [{ "key": "fee", "value": "2utia" }] },
+                        ] },
+                    },
+                    {
+                        "hash": "BB22",
+                        "height": "12",
+                        "tx_result": { "code": 5, "events": [
+                            { "type": "transfer", "attributes": [
+                                { "key": "recipient", "value": "celestia1abc" },
+                            ] },
+                        ] },
+                    },
+                ],
+            }})),
+        )
+        .mount(&consensus)
+        .await;
+    let node = NodeClient::new("http://localhost:26658", None).with_consensus(&consensus.uri());
+    let blocks = CelestiaSearchTool::with_base_url(&indexer(20).await.uri());
+    let tool = EventSearchTool::new(blocks).with_node(Arc::new(node));
+
+    let args = serde_json::from_value(json!({
+        "query": "transfer.recipient=celestia1abc",
+        "from": 10,
+        "limit": 2,
+    }))
+    .unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "3 transaction(s) between blocks 10 and 20 have events meeting the 1 condition(s), \
+         according to the consensus node's index; the latest 2 are listed:\n\
+         - tx AA11 at height 18: transfer{recipient=celestia1abc, amount=5utia}\n\
+         - tx BB22 at height 12 (failed): transfer{recipient=celestia1abc}"
+    );
+}
+
+/// A consensus node storing blocks from `earliest` to 2,000,000, with state from `state` on.
+struct Pruned {
+    earliest: u64,
+    state: u64,
+}
+
+impl Respond for Pruned {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let result = match body["method"].as_str().unwrap() {
+            "status" => json!({ "sync_info": {
+                "earliest_block_height": self.earliest.to_string(),
+                "earliest_block_time": "2024-01-01T00:00:00Z",
+                "latest_block_height": "2000000",
+            } }),
+            _ => {
+                let height: u64 = body["params"]["height"].as_str().unwrap().parse().unwrap();
+                match height >= self.state {
+                    true => json!({ "response": { "code": 0, "value": "" } }),
+                    false => json!({ "response": { "code": 26, "log": "version does not exist" } }),
+                }
+            }
+        };
+        ResponseTemplate::new(200).set_body_json(json!({ "id": 1, "result": result }))
+    }
+}
+
+#[tokio::test]
+async fn finds_how_far_back_the_node_keeps_blocks_and_state() {
+    let consensus = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(Pruned {
+            earliest: 1_000_000,
+            state: 1_637_121,
+        })
+        .mount(&consensus)
+        .await;
+    let node = NodeClient::new("http://localhost:26658", None).with_consensus(&consensus.uri());
+    let tool = NodeRetentionTool::new(Arc::new(node));
+
+    let args = serde_json::from_value(json!({ "height": 1_500_000 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The consensus node stores blocks 1000000 to 2000000 (its earliest from \
+         2024-01-01T00:00:00Z); blocks before were pruned, or never fetched if it joined by \
+         state sync. Its application state goes back to height 1637121, the latest 362880 \
+         heights, as its `pruning-keep-recent` setting keeps. State-sync snapshot intervals are \
+         only set in its app.toml (`[state-sync] snapshot-interval`), which the RPC doesn't \
+         expose. The node still has block 1500000, but not its state: balances and other state \
+         at that height need an archive node or the indexer."
+    );
+    let args = serde_json::from_value(json!({ "height": 900_000 })).unwrap();
+    assert!(tool.call(args).await.unwrap().ends_with(
+        "Height 900000 is before what the node keeps: query the indexer for it instead."
+    ));
+
+    let archive = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(Pruned {
+            earliest: 1,
+            state: 1,
+        })
+        .mount(&archive)
+        .await;
+    let node = NodeClient::new("http://localhost:26658", None).with_consensus(&archive.uri());
+    let args = serde_json::from_value(json!({ "height": 10 })).unwrap();
+    let output = NodeRetentionTool::new(Arc::new(node))
+        .call(args)
+        .await
+        .unwrap();
+    assert!(output.starts_with(
+        "The consensus node stores blocks 1 to 2000000, the whole chain. It keeps the \
+         application state of every block it stores (pruning `nothing`)."
+    ));
+    assert!(output.ends_with("The node serves both the block and the state at height 10."));
+}
+
+#[tokio::test]
+async fn reports_peers_and_sync_of_the_node_and_its_consensus_node() {
+    let node = MockServer::start().await;
+    respond(
+        &node,
+        "p2p.Peers",
+        json!({ "id": 1, "result": ["12D3KooWA", "12D3KooWB", "12D3KooWC"] }),
+    )
+    .await;
+    respond(
+        &node,
+        "header.SyncState",
+        json!({ "id": 1, "result": { "height": 2000, "to_height": 2000 } }),
+    )
+    .await;
+    respond(
+        &node,
+        "header.LocalHead",
+        json!({ "id": 1, "result": { "header": { "height": "2000" } } }),
+    )
+    .await;
+    respond(
+        &node,
+        "node.Info",
+        json!({ "id": 1, "result": { "type": 2 } }),
+    )
+    .await;
+    let consensus = MockServer::start().await;
+    respond(
+        &consensus,
+        "status",
+        json!({ "id": 1, "result": { "sync_info": {
+            "latest_block_height": "2001",
+            "catching_up": false,
+        }}}),
+    )
+    .await;
+    respond(
+        &consensus,
+        "net_info",
+        json!({ "id": 1, "result": { "n_peers": "40" } }),
+    )
+    .await;
+    let client = NodeClient::new(&node.uri(), None).with_consensus(&consensus.uri());
+    let tool = NodeStatusTool::new(Arc::new(client));
+
+    let args = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The node is a light node with 3 peer(s). Its header sync is at height 2000 of 2000 \
+         (done syncing), and its latest synced header is at height 2000. Its consensus node has \
+         40 peer(s), is caught up, and is at height 2001."
+    );
+}
+
+#[tokio::test]
+async fn flags_a_node_without_peers() {
+    // Without admin permissions, the node's type can't be read
+    let node = MockServer::start().await;
+    respond(&node, "p2p.Peers", json!({ "id": 1, "result": [] })).await;
+    respond(
+        &node,
+        "header.SyncState",
+        json!({ "id": 1, "result": { "height": 1500, "to_height": 2000 } }),
+    )
+    .await;
+    respond(
+        &node,
+        "header.LocalHead",
+        json!({ "id": 1, "result": { "header": { "height": "1500" } } }),
+    )
+    .await;
+    let tool = NodeStatusTool::new(Arc::new(NodeClient::new(&node.uri(), None)));
+
+    let args = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The node is a node of unknown type with 0 peer(s). Its header sync is at height 1500 of \
+         2000 (500 block(s) to go), and its latest synced header is at height 1500. With no \
+         peers, it can't follow the network."
+    );
+}
@@ -0,0 +1,167 @@
+use celestia_search_assistant::alert::Alert;
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::notify::{
+    Event, Notifier, NotifierConfig, SlackNotifier, WebhookNotifier,
+};
+use serde_json::json;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn alert() -> Alert {
+    Alert {
+        height: 2000000,
+        condition: "fill_rate > 0.7".to_string(),
+        value: 0.7252,
+    }
+}
+
+#[test]
+fn notifiers_are_configured_in_toml() {
+    let config = Config::parse(
+        r#"
+        [[notifiers]]
+        type = "stdout"
+
+        [[notifiers]]
+        type = "webhook"
+        url = "https://example.com/hooks/celestia"
+
+        [[notifiers]]
+        type = "slack"
+        webhook_url = "https://hooks.slack.com/services/T0/B0/X"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.notifiers,
+        [
+            NotifierConfig::Stdout,
+            NotifierConfig::Webhook {
+                url: "https://example.com/hooks/celestia".to_string()
+            },
+            NotifierConfig::Slack {
+                webhook_url: "https://hooks.slack.com/services/T0/B0/X".to_string()
+            },
+        ]
+    );
+    assert!(Config::parse("[[notifiers]]\ntype = \"email\"").is_err());
+}
+
+#[tokio::test]
+async fn webhook_and_slack_receive_alerts() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .and(body_json(json!({
+            "kind": "alert",
+            "height": 2000000,
+            "condition": "fill_rate > 0.7",
+            "value": 0.7252,
+            "message": "Alert: Block 2000000: fill_rate > 0.7 (observed 0.7252)",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/slack"))
+        .and(body_json(json!({
+            "text": ":rotating_light: Block 2000000: fill_rate > 0.7 (observed 0.7252)",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let webhook = WebhookNotifier::new(&format!("{}/webhook", server.uri()));
+    let slack = SlackNotifier::new(&format!("{}/slack", server.uri()));
+    let event = Event::Alert(alert());
+    webhook.notify(&event).await.unwrap();
+    slack.notify(&event).await.unwrap();
+}
+
+#[tokio::test]
+async fn webhook_receives_reports() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .and(body_json(json!({
+            "kind": "report",
+            "name": "daily",
+            "prompt": "Summarize yesterday's activity",
+            "answer": "Block times averaged 11.8s.",
+            "message": "Report daily:\nBlock times averaged 11.8s.",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = Event::Report {
+        name: "daily".to_string(),
+        prompt: "Summarize yesterday's activity".to_string(),
+        answer: "Block times averaged 11.8s.".to_string(),
+    };
+    WebhookNotifier::new(&format!("{}/webhook", server.uri()))
+        .notify(&report)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn example_config_parses() {
+    let path = format!("{}/config.example.toml", env!("CARGO_MANIFEST_DIR"));
+    let config = Config::load(path.as_ref()).unwrap();
+    assert_eq!(config.notifiers, [NotifierConfig::Stdout]);
+    assert!(config.schedules.is_empty());
+}
+
+#[cfg(feature = "discord")]
+#[tokio::test]
+async fn discord_receives_events_within_its_length_limit() {
+    use celestia_search_assistant::notify::DiscordNotifier;
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/discord"))
+        .and(body_json(json!({
+            "content": ":rotating_light: Block 2000000: fill_rate > 0.7 (observed 0.7252)",
+        })))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let discord = DiscordNotifier::new(&format!("{}/discord", server.uri()));
+    discord.notify(&Event::Alert(alert())).await.unwrap();
+
+    let config = Config::parse(
+        "[[notifiers]]\ntype = \"discord\"\nwebhook_url = \"https://discord.com/api/webhooks/1/x\"",
+    )
+    .unwrap();
+    assert_eq!(
+        config.notifiers,
+        [NotifierConfig::Discord {
+            webhook_url: "https://discord.com/api/webhooks/1/x".to_string()
+        }]
+    );
+
+    let long = Event::Report {
+        name: "daily".to_string(),
+        prompt: "Summarize yesterday's activity".to_string(),
+        answer: "x".repeat(5000),
+    };
+    Mock::given(method("POST"))
+        .and(path("/discord/long"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+    DiscordNotifier::new(&format!("{}/discord/long", server.uri()))
+        .notify(&long)
+        .await
+        .unwrap();
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = requests.last().unwrap().body_json().unwrap();
+    assert_eq!(body["content"].as_str().unwrap().chars().count(), 2000);
+}
@@ -0,0 +1,24 @@
+use celestia_search_assistant::postprocess::answer;
+
+#[test]
+fn decodes_tool_outputs() {
+    assert_eq!(
+        answer("\"    The gas fee is: 2217\""),
+        "The gas fee is: 2217"
+    );
+}
+
+#[test]
+fn unwraps_fenced_json() {
+    let raw = "```json\n{\"answer\": \"Block 10000 paid 2217 utia.\"}\n```";
+    assert_eq!(answer(raw), "Block 10000 paid 2217 utia.");
+}
+
+#[test]
+fn normalizes_plain_text() {
+    let raw = "  The fee was 2217 utia.  \r\n\r\n\r\n* fill rate: 0.12%\r\n+ txs: 2\n";
+    assert_eq!(
+        answer(raw),
+        "The fee was 2217 utia.\n\n- fill rate: 0.12%\n- txs: 2"
+    );
+}
@@ -0,0 +1,35 @@
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::preamble::{self, PreambleMode};
+
+#[test]
+fn custom_preambles_replace_or_extend_the_default() {
+    let path = std::env::temp_dir().join(format!("celestia-preamble-{}.md", std::process::id()));
+    std::fs::write(&path, "Answer in French.\n").unwrap();
+
+    assert_eq!(
+        preamble::load(Some(&path), PreambleMode::Replace).unwrap(),
+        "Answer in French.\n"
+    );
+    let extended = preamble::load(Some(&path), PreambleMode::Extend).unwrap();
+    assert!(extended.starts_with(preamble::DEFAULT.trim_end()));
+    assert!(extended.ends_with("\n\nAnswer in French."));
+    assert_eq!(
+        preamble::load(None, PreambleMode::Extend).unwrap(),
+        preamble::DEFAULT
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(preamble::load(Some(&path), PreambleMode::Replace).is_err());
+}
+
+#[test]
+fn config_sets_the_preamble() {
+    let config = Config::parse("[preamble]\nfile = \"team.md\"\nmode = \"extend\"").unwrap();
+    let custom = config.preamble.unwrap();
+    assert_eq!(custom.file.to_str(), Some("team.md"));
+    assert_eq!(custom.mode, PreambleMode::Extend);
+
+    let config = Config::parse("[preamble]\nfile = \"team.md\"").unwrap();
+    assert_eq!(config.preamble.unwrap().mode, PreambleMode::Replace);
+    assert!(Config::parse("[preamble]\nfile = \"team.md\"\nmode = \"append\"").is_err());
+}
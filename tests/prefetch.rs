@@ -0,0 +1,47 @@
+use celestia_search_assistant::celenium::CeleniumClient;
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::prefetch;
+use serde_json::json;
+use wiremock::matchers::{method, path, path_regex};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn prefetched_blocks_are_served_from_the_cache() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 100 })))
+        .mount(&server)
+        .await;
+    // Each block is fetched once, however often it's asked for afterwards
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/block/(98|99|100)/stats$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "tx_count": "2",
+            "fee": "2217",
+        })))
+        .expect(3)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/block/97/stats"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let tool = CelestiaSearchTool::new(client.clone());
+    assert_eq!(prefetch::recent_blocks(&tool, 4).await.unwrap(), 3);
+
+    // Other tools built from the same client share its cache
+    let other = CelestiaSearchTool::new(client);
+    for height in [98, 99, 100] {
+        assert_eq!(
+            other.fetch_stats(height).await.unwrap().fee.to_string(),
+            "2217"
+        );
+    }
+}
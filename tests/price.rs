@@ -0,0 +1,101 @@
+mod common;
+
+use std::sync::Arc;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::price::{describe_utia, PriceFeed};
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::common::{fixture, replay};
+
+async fn coingecko(usd: f64) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .and(query_param("ids", "celestia"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "celestia": { "usd": usd } })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn price_is_cached() {
+    let server = coingecko(5.25).await;
+    let feed = PriceFeed::with_base_url(&server.uri());
+
+    assert_eq!(feed.usd_per_tia().await.unwrap(), 5.25);
+    assert_eq!(feed.usd_per_tia().await.unwrap(), 5.25);
+}
+
+#[test]
+fn describes_utia_amounts() {
+    assert_eq!(
+        describe_utia("2340972", None),
+        "2340972 utia (2.340972 TIA)"
+    );
+    assert_eq!(
+        describe_utia("2340972", Some(5.0)),
+        "2340972 utia (2.340972 TIA, ~$11.70)"
+    );
+    assert_eq!(
+        describe_utia("2217", Some(1.0)),
+        "2217 utia (0.002217 TIA, ~$0.002217)"
+    );
+    assert_eq!(describe_utia("n/a", Some(5.0)), "n/a");
+}
+
+#[tokio::test]
+async fn fee_includes_usd_equivalent() {
+    let prices = coingecko(5.0).await;
+    let celenium = MockServer::start().await;
+    replay(&celenium, "/block/2000000/stats", "block_stats_2000000").await;
+    assert_eq!(fixture("block_stats_2000000")["fee"], "2340972");
+
+    let tool = CelestiaSearchTool::with_base_url(&celenium.uri())
+        .with_price_feed(Arc::new(PriceFeed::with_base_url(&prices.uri())));
+    let args = serde_json::from_value(json!({ "height": 2000000 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "    The gas fee is: 2340972 utia (2.340972 TIA, ~$11.70)"
+    );
+}
+
+#[cfg(feature = "market-data")]
+#[tokio::test]
+async fn tia_price_reports_market_data() {
+    use celestia_search_assistant::tia_price_tool::TiaPriceTool;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .and(query_param("include_24hr_change", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "celestia": {
+                "usd": 5.2491,
+                "usd_market_cap": 1103265549.4,
+                "usd_24h_vol": 80123456.7,
+                "usd_24h_change": -1.2345,
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let feed = Arc::new(PriceFeed::with_base_url(&server.uri()));
+    let tool = TiaPriceTool::new(feed.clone());
+    let args = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "TIA is trading at $5.25 (-1.23% over 24h), with a market cap of $1103265549 and a 24h \
+         volume of $80123457."
+    );
+
+    // The market data refreshed the cached price
+    assert_eq!(feed.usd_per_tia().await.unwrap(), 5.2491);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::proposal_votes_tool::ProposalVotesTool;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::MockServer;
+
+#[tokio::test]
+async fn breaks_down_votes_with_turnout_and_validators() {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/proposal/3",
+        json!({
+            "id": 3,
+            "title": "Raise the max square size",
+            "status": "active",
+            "votes_count": 1234,
+            "yes_voting_power": "300",
+            "no_voting_power": "50",
+            "no_with_veto_voting_power": "10",
+            "abstain_voting_power": "40",
+            "total_voting_power": "1000",
+        }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/proposal/3/votes",
+        json!([
+            { "option": "yes", "voting_power": "120", "validator": { "moniker": "Stakely" } },
+            { "option": "no", "voting_power": "200", "validator": { "moniker": "Everstake" } },
+            { "option": "abstain", "validator": { "moniker": "Small", "stake": "5" } },
+        ]),
+    )
+    .await;
+    let tool = ProposalVotesTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "proposal": 3 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Proposal #3 \"Raise the max square size\" is active. By voting power, yes 75.00%, no \
+         12.50%, no with veto 2.50% and abstain 10.00% of the votes. 1234 vote(s) were cast. \
+         Turnout is 40.00% of the bonded voting power, so the 33.40% quorum is reached. \
+         Excluding abstentions, yes has 83.33% against a 50.00% threshold and no with veto \
+         2.78% against a 33.40% veto threshold, so as tallied the proposal passes.\n\
+         Votes of the 3 largest validators that voted:\n\
+         - Everstake (20.00% of the voting power): no\n\
+         - Stakely (12.00% of the voting power): yes\n\
+         - Small (0.50% of the voting power): abstain"
+    );
+
+    let args = serde_json::from_value(json!({ "proposal": 3, "validators": 0 })).unwrap();
+    assert!(!tool.call(args).await.unwrap().contains("Everstake"));
+}
+
+#[tokio::test]
+async fn reports_proposals_without_votes() {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/proposal/4",
+        json!({ "id": 4, "title": "Spend from the pool", "status": "PROPOSAL_STATUS_VOTING_PERIOD" }),
+    )
+    .await;
+    common::respond(&server, "/proposal/4/votes", json!([])).await;
+    let tool = ProposalVotesTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "proposal": 4 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Proposal #4 \"Spend from the pool\" is in its voting period. No votes have been counted yet.\n\
+         No validator has voted."
+    );
+}
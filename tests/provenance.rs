@@ -0,0 +1,61 @@
+use celestia_search_assistant::accounting::TokenUsage;
+use celestia_search_assistant::assistant::{ToolCall, Turn};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::provenance;
+use serde_json::json;
+
+fn call(name: &str, args: serde_json::Value, output: Option<&str>) -> ToolCall {
+    ToolCall {
+        name: name.to_string(),
+        args,
+        output: output.map(str::to_string),
+        ..ToolCall::default()
+    }
+}
+
+fn turn(mut steps: Vec<ToolCall>) -> Turn {
+    let tool_call = steps.pop();
+    Turn {
+        output: "An answer".to_string(),
+        tool_call,
+        steps,
+        usage: TokenUsage::default(),
+        warnings: Vec::new(),
+    }
+}
+
+#[test]
+fn lists_the_explorer_pages_of_what_tool_calls_looked_up() {
+    let turn = turn(vec![
+        call("search_blocks", json!({ "height": 10 }), Some("fee: 2000")),
+        call("search_blocks", json!({ "height": 10 }), Some("fee: 2000")),
+        call(
+            "compare_blocks",
+            json!({ "from": 1, "to": 3, "network": ["mainnet", "mocha"] }),
+            Some("up"),
+        ),
+        call(
+            "validator",
+            json!({ "validator": "celestiavaloper1xyz" }),
+            Some("ok"),
+        ),
+        call("chain_params", json!({}), Some("params")),
+    ]);
+    assert_eq!(
+        provenance::footer(&turn, Network::Mainnet).unwrap(),
+        "Sources:\n\
+         - `search_blocks` on mainnet: block 10 <https://celenium.io/block/10>\n\
+         - `compare_blocks` on mainnet: block 1 <https://celenium.io/block/1>, \
+         block 3 <https://celenium.io/block/3>\n\
+         - `compare_blocks` on mocha: block 1 <https://mocha.celenium.io/block/1>, \
+         block 3 <https://mocha.celenium.io/block/3>\n\
+         - `validator` on mainnet: validator celestiavaloper1xyz\n\
+         - `chain_params` on mainnet"
+    );
+}
+
+#[test]
+fn planned_calls_are_no_sources() {
+    let planned = turn(vec![call("search_blocks", json!({ "height": 10 }), None)]);
+    assert_eq!(provenance::footer(&planned, Network::Mocha), None);
+}
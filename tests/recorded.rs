@@ -0,0 +1,99 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::{CelestiaResponseFields, CelestiaSearchTool};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::{export, fetcher};
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::MockServer;
+
+use crate::common::{fixture, replay};
+
+#[test]
+fn block_stats_deserialize() {
+    let stats = CelestiaResponseFields::from_json(&fixture("block_stats_2000000")).unwrap();
+
+    assert_eq!(stats.fee.to_string(), "2340972");
+    assert_eq!(stats.fill_rate, 0.7252);
+    assert_eq!(stats.inflation_rate, "0.0719999");
+    assert_eq!(stats.supply_change.to_string(), "107985794");
+    assert_eq!(stats.rewards.to_string(), "110326765.952");
+    assert_eq!(stats.commissions.to_string(), "10569812.77");
+
+    // The fixture sends counts as JSON numbers rather than strings
+    assert_eq!(stats.tx_count, 31);
+    assert_eq!(stats.gas_limit, 29614512);
+    assert_eq!(stats.square_size, 64);
+}
+
+#[tokio::test]
+async fn fetched_stats_carry_height_and_network() {
+    let server = MockServer::start().await;
+    replay(&server, "/block/2000000/stats", "block_stats_2000000").await;
+
+    let tool = CelestiaSearchTool::with_base_url(&server.uri()).with_network(Network::Mocha);
+    let stats = tool.fetch_stats(2000000).await.unwrap();
+    assert_eq!((stats.height, stats.network), (2000000, Network::Mocha));
+    assert_eq!(
+        stats.to_string(),
+        "Block 2000000 on mocha: 31 txs, 48 blobs (1.42 MiB), fill rate 0.7252, fee \
+         2340972 utia"
+    );
+}
+
+#[test]
+fn malformed_block_stats_are_errors() {
+    let stats = CelestiaResponseFields::from_json(&json!({ "tx_count": "3", "fee": 12 })).unwrap();
+    assert_eq!(
+        (stats.tx_count, stats.fee.to_string()),
+        (3, "12".to_string())
+    );
+    assert_eq!(stats.gas_used, 0);
+
+    for malformed in [
+        json!({ "tx_count": "many" }),
+        json!({ "fee": true }),
+        json!({ "fill_rate": "n/a" }),
+    ] {
+        assert!(CelestiaResponseFields::from_json(&malformed).is_err());
+    }
+}
+
+#[tokio::test]
+async fn block_stats_format() {
+    let server = MockServer::start().await;
+    replay(&server, "/block/9999/stats", "block_stats_9999").await;
+    replay(&server, "/block/2000000/stats", "block_stats_2000000").await;
+
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+    for (height, expected) in [
+        (9999, "    The gas fee is: 0"),
+        (2000000, "    The gas fee is: 2340972"),
+    ] {
+        let args = serde_json::from_value(json!({ "height": height })).unwrap();
+        assert_eq!(tool.call(args).await.unwrap(), expected);
+    }
+}
+
+#[tokio::test]
+async fn range_exports_as_csv() {
+    let server = MockServer::start().await;
+    replay(&server, "/block/9999/stats", "block_stats_9999").await;
+    replay(&server, "/block/10000/stats", "block_stats_9999").await;
+
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+    let rows = fetcher::fetch_range(&tool, 9999..=10000, 2).await.unwrap();
+
+    let mut csv = Vec::new();
+    export::write_csv(rows.iter().map(|(_, stats)| stats), &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("height,blobs_count,blobs_size,"));
+    assert!(lines[0].contains(",inflation_rate,network,rewards,"));
+    assert!(lines[1].starts_with("9999,"));
+    assert!(lines[2].starts_with("10000,"));
+    assert!(lines[2].contains(",mainnet,"));
+    assert!(lines[1].contains(",221506293.22391162603,"));
+}
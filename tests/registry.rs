@@ -0,0 +1,295 @@
+mod common;
+
+#[cfg(any(feature = "node-rpc", feature = "sqlite-cache"))]
+use std::sync::Arc;
+
+use celestia_search_assistant::access::Allowed;
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+#[cfg(feature = "node-rpc")]
+use celestia_search_assistant::node::NodeClient;
+use celestia_search_assistant::registry::{RegistryError, ToolContext, ToolKind, ToolRegistry};
+#[cfg(feature = "sqlite-cache")]
+use celestia_search_assistant::store::BlockStore;
+
+fn names(tools: &[Box<dyn rig::tool::ToolDyn>]) -> Vec<String> {
+    tools.iter().map(|tool| tool.name()).collect()
+}
+
+#[test]
+#[cfg(all(feature = "node-rpc", feature = "sqlite-cache"))]
+fn store_tool_requires_a_store() {
+    let registry = ToolRegistry::with_builtin_tools();
+
+    let tools = registry
+        .build(&ToolContext::default(), None, |_| true)
+        .unwrap();
+    assert_eq!(
+        names(&tools),
+        [
+            "search_blocks",
+            "search_anything",
+            "fill_rate_trend",
+            "blockspace_forecast",
+            "gas_percentiles",
+            "gas_efficiency",
+            "blob_fees",
+            "blob_cost",
+            "fee_histogram",
+            "square_size_distribution",
+            "block_limits",
+            "compare_blocks",
+            "block_rewards",
+            "who_proposed",
+            "block_txs",
+            "event_search",
+            "validator_uptime",
+            "slashing_events",
+            "validator_rewards",
+            "staking_yield",
+            "address_txs",
+            "address_activity",
+            "tx_fee",
+            "balance_history",
+            "top_accounts",
+            "namespace_stats",
+            "top_namespaces",
+            "rollup_activity",
+            "namespace_blobs",
+            "block_square",
+            "proposal_votes",
+            "chain_params",
+            "chain_upgrades",
+            "estimate_time",
+            "countdown",
+            "pending_rewards",
+            "pending_unbondings",
+            "capabilities"
+        ]
+    );
+
+    let ctx = ToolContext {
+        store: Some(Arc::new(BlockStore::in_memory().unwrap())),
+        ..ToolContext::default()
+    };
+    let tools = registry.build(&ctx, None, |_| true).unwrap();
+    assert_eq!(
+        names(&tools),
+        [
+            "search_blocks",
+            "search_anything",
+            "query_block_store",
+            "fill_rate_trend",
+            "blockspace_forecast",
+            "gas_percentiles",
+            "gas_efficiency",
+            "blob_fees",
+            "blob_cost",
+            "fee_histogram",
+            "square_size_distribution",
+            "block_limits",
+            "compare_blocks",
+            "block_rewards",
+            "who_proposed",
+            "block_txs",
+            "event_search",
+            "validator_uptime",
+            "slashing_events",
+            "validator_rewards",
+            "staking_yield",
+            "address_txs",
+            "address_activity",
+            "tx_fee",
+            "balance_history",
+            "top_accounts",
+            "namespace_stats",
+            "top_namespaces",
+            "rollup_activity",
+            "namespace_blobs",
+            "block_square",
+            "proposal_votes",
+            "chain_params",
+            "chain_upgrades",
+            "estimate_time",
+            "countdown",
+            "pending_rewards",
+            "pending_unbondings",
+            "capabilities"
+        ]
+    );
+
+    let ctx = ToolContext {
+        node: Some(Arc::new(NodeClient::new("http://localhost:26658", None))),
+        ..ctx
+    };
+    let tools = registry.build(&ctx, None, |_| true).unwrap();
+    assert!(names(&tools).ends_with(&[
+        "verify_blob".to_string(),
+        "sampling_status".to_string(),
+        "node_status".to_string(),
+        "capabilities".to_string()
+    ]));
+
+    let consensus = ToolContext {
+        node: Some(Arc::new(
+            NodeClient::new("http://localhost:26658", None)
+                .with_consensus("http://localhost:26657"),
+        )),
+        ..ctx.clone()
+    };
+    let tools = registry.build(&consensus, None, |_| true).unwrap();
+    assert!(names(&tools).ends_with(&[
+        "node_status".to_string(),
+        "mempool".to_string(),
+        "node_retention".to_string(),
+        "capabilities".to_string()
+    ]));
+
+    let tools = registry
+        .build(&ctx, None, |kind| kind == ToolKind::Data)
+        .unwrap();
+    assert_eq!(
+        names(&tools),
+        [
+            "search_blocks",
+            "search_anything",
+            "block_rewards",
+            "who_proposed",
+            "block_txs",
+            "event_search",
+            "validator_uptime",
+            "slashing_events",
+            "address_txs",
+            "tx_fee",
+            "top_accounts",
+            "namespace_blobs",
+            "block_square",
+            "proposal_votes",
+            "chain_params",
+            "chain_upgrades",
+            "estimate_time",
+            "countdown",
+            "pending_rewards",
+            "pending_unbondings",
+            "verify_blob",
+            "sampling_status",
+            "node_status"
+        ]
+    );
+}
+
+#[test]
+fn enabled_list_selects_tools() {
+    let mut registry = ToolRegistry::with_builtin_tools();
+    registry.register("mainnet_blocks", ToolKind::Data, |_| {
+        Some(Box::new(CelestiaSearchTool::default()))
+    });
+
+    let enabled = vec!["search_blocks".to_string()];
+    let tools = registry
+        .build(&ToolContext::default(), Some(&enabled), |_| true)
+        .unwrap();
+    assert_eq!(names(&tools), ["search_blocks"]);
+
+    let enabled = vec!["tx_search".to_string()];
+    let err = registry
+        .build(&ToolContext::default(), Some(&enabled), |_| true)
+        .err()
+        .unwrap();
+    assert!(matches!(err, RegistryError::UnknownTool(ref name, _) if name == "tx_search"));
+}
+
+#[test]
+fn tools_outside_the_callers_role_are_left_out() {
+    let ctx = ToolContext {
+        allowed: Allowed::Only(vec!["search_blocks".to_string(), "tx_fee".to_string()]),
+        ..ToolContext::default()
+    };
+    let registry = ToolRegistry::with_builtin_tools();
+    let tools = registry.build(&ctx, None, |_| true).unwrap();
+    assert_eq!(names(&tools), ["search_blocks", "tx_fee"]);
+
+    let enabled = vec!["search_blocks".to_string(), "top_accounts".to_string()];
+    let err = registry
+        .build(&ctx, Some(&enabled), |_| true)
+        .err()
+        .unwrap();
+    assert!(matches!(err, RegistryError::Forbidden(ref name) if name == "top_accounts"));
+}
+
+#[tokio::test]
+async fn capabilities_describe_the_tools_built_alongside() {
+    let registry = ToolRegistry::with_builtin_tools();
+    let enabled = vec!["search_blocks".to_string(), "capabilities".to_string()];
+    let tools = registry
+        .build(&ToolContext::default(), Some(&enabled), |_| true)
+        .unwrap();
+    assert_eq!(names(&tools), ["search_blocks", "capabilities"]);
+
+    let output = tools[1].call("{}".to_string()).await.unwrap();
+    let description: serde_json::Value =
+        serde_json::from_str(&serde_json::from_str::<String>(&output).unwrap()).unwrap();
+    assert_eq!(description["network"], "mainnet");
+    assert_eq!(description["networks"].as_array().unwrap().len(), 3);
+    assert!(description["freshness"]
+        .as_str()
+        .unwrap()
+        .starts_with("live"));
+    let search_blocks = &description["tools"][0];
+    assert_eq!(search_blocks["name"], "search_blocks");
+    assert_eq!(search_blocks["kind"], "data");
+    assert!(search_blocks["parameters"]
+        .as_array()
+        .unwrap()
+        .contains(&"height".into()));
+    assert_eq!(description["tools"].as_array().unwrap().len(), 1);
+
+    // Conceptual questions get no data tools, but can still be told what the others do
+    let tools = registry
+        .build(&ToolContext::default(), None, |kind| kind == ToolKind::Meta)
+        .unwrap();
+    assert_eq!(names(&tools), ["capabilities"]);
+}
+
+#[tokio::test]
+#[cfg(feature = "sqlite-cache")]
+async fn peers_share_the_store_and_price_feed() {
+    use celestia_search_assistant::celenium::CeleniumClient;
+    use celestia_search_assistant::network::Network;
+    use celestia_search_assistant::price::PriceFeed;
+    use rig::tool::Tool;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let celenium = MockServer::start().await;
+    common::replay(&celenium, "/block/2000000/stats", "block_stats_2000000").await;
+    let prices = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/simple/price"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "celestia": { "usd": 5.0 } })),
+        )
+        .mount(&prices)
+        .await;
+    let store = Arc::new(BlockStore::in_memory().unwrap());
+    let mut ctx = ToolContext {
+        store: Some(store.clone()),
+        price_feed: Some(Arc::new(PriceFeed::with_base_url(&prices.uri()))),
+        ..ToolContext::default()
+    };
+    let mocha = CeleniumClient::builder()
+        .base_url(&celenium.uri())
+        .network(Network::Mocha)
+        .build()
+        .unwrap();
+    ctx.clients.insert(Network::Mocha, mocha);
+
+    let args = serde_json::from_value(json!({ "height": 2000000, "network": ["mocha"] })).unwrap();
+    let output = ctx.block_tool().call(args).await.unwrap();
+    assert!(
+        output.ends_with("2340972 utia (2.340972 TIA, ~$11.70)"),
+        "{}",
+        output
+    );
+    assert_eq!(store.max_height(Network::Mocha).unwrap(), Some(2000000));
+}
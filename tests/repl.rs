@@ -0,0 +1,61 @@
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::repl::{SlashCommand, SlashCommandError};
+
+#[test]
+fn questions_are_not_commands() {
+    assert_eq!(SlashCommand::parse("What is the fee of block 5?"), None);
+    assert_eq!(SlashCommand::parse("Is 1/2 of blocks full?"), None);
+}
+
+#[test]
+fn slash_commands_parse_with_optional_arguments() {
+    assert_eq!(
+        SlashCommand::parse("/model gpt-4o"),
+        Some(Ok(SlashCommand::Model(Some("gpt-4o".to_string()))))
+    );
+    assert_eq!(
+        SlashCommand::parse("  /model  "),
+        Some(Ok(SlashCommand::Model(None)))
+    );
+    assert_eq!(
+        SlashCommand::parse("/network Mocha"),
+        Some(Ok(SlashCommand::Network(Some(Network::Mocha))))
+    );
+    assert_eq!(SlashCommand::parse("/tools"), Some(Ok(SlashCommand::Tools)));
+    assert_eq!(SlashCommand::parse("/clear"), Some(Ok(SlashCommand::Clear)));
+    assert_eq!(
+        SlashCommand::parse("/export report.html"),
+        Some(Ok(SlashCommand::Export(Some("report.html".into()))))
+    );
+    assert_eq!(
+        SlashCommand::parse("/export"),
+        Some(Ok(SlashCommand::Export(None)))
+    );
+    assert_eq!(SlashCommand::parse("/exit"), Some(Ok(SlashCommand::Quit)));
+}
+
+#[test]
+fn unknown_commands_and_networks_are_rejected() {
+    assert_eq!(
+        SlashCommand::parse("/temperature 0.5"),
+        Some(Err(SlashCommandError::UnknownCommand(
+            "temperature".to_string()
+        )))
+    );
+    assert_eq!(
+        SlashCommand::parse("/network devnet"),
+        Some(Err(SlashCommandError::UnknownNetwork("devnet".to_string())))
+    );
+}
+
+#[test]
+fn networks_map_to_their_celenium_api() {
+    assert_eq!(
+        Network::Mainnet.base_url(),
+        celestia_search_assistant::celestia_search_tool::DEFAULT_BASE_URL
+    );
+    assert_eq!(
+        Network::Arabica.base_url(),
+        "https://api-arabica.celenium.io/v1"
+    );
+}
@@ -0,0 +1,109 @@
+use std::sync::{Arc, Mutex};
+
+use celestia_search_assistant::celenium::CeleniumClient;
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchError;
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::provider::{LlmClient, ReplayedModel};
+use celestia_search_assistant::registry::{ToolContext, ToolRegistry};
+use celestia_search_assistant::replay::{RecordedCall, RecordedCompletion, Recording, Tape};
+use rig::completion::{CompletionModel, ModelChoice};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn records_and_replays_indexer_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 7 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let recording = Arc::new(Mutex::new(Recording::default()));
+    let client = CeleniumClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let recorder = client.clone().with_tape(Tape::Record(recording.clone()));
+    recorder.get("/head").await.unwrap();
+    let url = format!("{}/head", server.uri());
+    let recording = std::mem::take(&mut *recording.lock().unwrap());
+    assert_eq!(recording.responses[&url], json!({ "last_height": 7 }));
+
+    // The replay answers from the file, without a request reaching the indexer
+    let path = std::env::temp_dir().join(format!("celestia-replay-{}.json", std::process::id()));
+    recording.save(&path).unwrap();
+    let recording = Recording::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let replayer = client.with_tape(Tape::Replay(Arc::new(recording)));
+    assert_eq!(replayer.get("/head").await.unwrap()["last_height"], 7);
+    assert!(matches!(
+        replayer.get("/block/7/stats").await,
+        Err(CelestiaSearchError::NotRecorded { .. })
+    ));
+}
+
+#[tokio::test]
+async fn replayed_tools_return_recorded_outputs() {
+    let args = json!({ "height": 100 });
+    let recording = Recording {
+        calls: vec![RecordedCall {
+            name: "search_blocks".to_string(),
+            args: args.clone(),
+            output: "\"Block 100 on mainnet: 3 txs\"".to_string(),
+        }],
+        ..Recording::default()
+    };
+    let ctx = ToolContext {
+        network: Network::Mainnet,
+        offline: true,
+        tape: Some(Tape::Replay(Arc::new(recording))),
+        ..ToolContext::default()
+    };
+    let enabled = vec!["search_blocks".to_string()];
+    let tools = ToolRegistry::with_builtin_tools()
+        .build(&ctx, Some(&enabled), |_| true)
+        .unwrap();
+
+    assert_eq!(
+        tools[0].call(args.to_string()).await.unwrap(),
+        "\"Block 100 on mainnet: 3 txs\""
+    );
+    // Calls that weren't recorded are answered offline, from the recorded responses
+    let error = tools[0]
+        .call(json!({ "height": 101 }).to_string())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("not cached"));
+}
+
+#[tokio::test]
+async fn replayed_models_serve_each_recorded_completion_once() {
+    let recording = Recording {
+        completions: vec![RecordedCompletion {
+            prompt: "Fee of block 100?".to_string(),
+            tool_calls: vec![("search_blocks".to_string(), json!({ "height": 100 }))],
+            ..RecordedCompletion::default()
+        }],
+        ..Recording::default()
+    };
+    let llm = LlmClient::Replay(ReplayedModel::new(Arc::new(recording)));
+    let model = llm.completion_model("gpt-4o-mini");
+
+    let response = model
+        .completion_request("Fee of block 100?")
+        .send()
+        .await
+        .unwrap();
+    assert!(matches!(
+        response.choice,
+        ModelChoice::ToolCall(name, args) if name == "search_blocks" && args["height"] == 100
+    ));
+    // Asked again, or for another prompt, the recording has nothing left to serve
+    for prompt in ["Fee of block 100?", "Fee of block 101?"] {
+        let error = model.completion_request(prompt).send().await.unwrap_err();
+        assert!(error.to_string().contains("--replay-model"));
+    }
+}
@@ -0,0 +1,95 @@
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::rollup_activity_tool::RollupActivityTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn series(server: &MockServer, name: &str, points: Value) {
+    Mock::given(method("GET"))
+        .and(path(format!("/rollup/7/stats/{}/day", name)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(points))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn reports_a_rollups_daily_activity() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rollup/slug/manta-pacific"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "id": 7, "name": "Manta Pacific" })),
+        )
+        .mount(&server)
+        .await;
+    series(
+        &server,
+        "blobs_count",
+        json!([
+            { "time": "2024-06-01T00:00:00Z", "value": "4" },
+            { "time": "2024-06-02T00:00:00Z", "value": "6" },
+        ]),
+    )
+    .await;
+    series(
+        &server,
+        "size",
+        json!([
+            { "time": "2024-06-01T00:00:00Z", "value": "4096" },
+            { "time": "2024-06-02T00:00:00Z", "value": "30720" },
+        ]),
+    )
+    .await;
+    series(
+        &server,
+        "fee",
+        json!([
+            { "time": "2024-06-01T00:00:00Z", "value": "2000" },
+            { "time": "2024-06-02T00:00:00Z", "value": "8000" },
+        ]),
+    )
+    .await;
+    let tool = RollupActivityTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "rollup": "Manta Pacific" })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Daily activity of Manta Pacific over the last 7 day(s):\n\
+         2024-06-01: 4 blob(s), 4.00 KiB posted, 2000 utia (0.002 TIA) in fees, 1.00 KiB per blob on \
+         average\n\
+         2024-06-02: 6 blob(s), 30.00 KiB posted, 8000 utia (0.008 TIA) in fees, 5.00 KiB per blob on \
+         average\n\
+         Total over 2 active day(s): 10 blob(s), 34.00 KiB posted, 10000 utia (0.01 TIA) in fees, \
+         3.40 KiB per blob on average"
+    );
+}
+
+#[tokio::test]
+async fn finds_rollups_by_name_with_the_search() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("query", "Eclipse"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "type": "rollup", "result": { "id": 7, "name": "Eclipse" } },
+        ])))
+        .mount(&server)
+        .await;
+    for name in ["blobs_count", "size", "fee"] {
+        series(&server, name, json!([])).await;
+    }
+    let tool = RollupActivityTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "rollup": "Eclipse", "days": 30 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Eclipse posted no blobs in the last 30 day(s)."
+    );
+
+    let args = serde_json::from_value(json!({ "rollup": "nothing" })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "No rollup named `nothing` was found."
+    );
+}
@@ -0,0 +1,19 @@
+use celestia_search_assistant::registry::ToolKind;
+use celestia_search_assistant::router::Route;
+
+#[test]
+fn parses_router_replies() {
+    assert_eq!(Route::parse("live"), Some(Route::Live));
+    assert_eq!(Route::parse(" Conceptual.\n"), Some(Route::Conceptual));
+    assert_eq!(Route::parse("**ANALYTICAL**"), Some(Route::Analytical));
+    assert_eq!(Route::parse("general"), None);
+    assert_eq!(Route::parse("I think this is live data"), None);
+}
+
+#[test]
+fn conceptual_agent_has_no_data_tools() {
+    assert!(!Route::Conceptual.uses(ToolKind::Data));
+    assert!(Route::Conceptual.uses_docs());
+    assert!(!Route::Live.uses(ToolKind::Analytics));
+    assert!(Route::General.uses(ToolKind::Data) && Route::General.uses(ToolKind::Analytics));
+}
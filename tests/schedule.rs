@@ -0,0 +1,51 @@
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::schedule::{self, Job, ScheduleConfig};
+use chrono::{TimeZone, Utc};
+
+fn schedule_config(name: &str, cron: &str) -> ScheduleConfig {
+    ScheduleConfig {
+        name: name.to_string(),
+        cron: cron.to_string(),
+        prompt: "Summarize yesterday's Celestia activity".to_string(),
+        output: None,
+        notify: true,
+    }
+}
+
+#[test]
+fn schedules_are_configured_in_toml() {
+    let config = Config::parse(
+        r#"
+        [[schedules]]
+        name = "daily"
+        cron = "0 8 * * *"
+        prompt = "Summarize yesterday's Celestia activity"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.schedules, [schedule_config("daily", "0 8 * * *")]);
+    assert!(Config::parse("[[schedules]]\nname = \"daily\"").is_err());
+}
+
+#[test]
+fn jobs_run_at_their_next_cron_time() {
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+    let daily = Job::new(schedule_config("daily", "0 8 * * *")).unwrap();
+    let hourly = Job::new(schedule_config("hourly", "15 * * * *")).unwrap();
+
+    assert_eq!(
+        daily.next_run(&now),
+        Some(Utc.with_ymd_and_hms(2024, 5, 2, 8, 0, 0).unwrap())
+    );
+
+    let jobs = [daily, hourly];
+    let (at, job) = schedule::next_due(&jobs, &now).unwrap();
+    assert_eq!(at, Utc.with_ymd_and_hms(2024, 5, 1, 10, 15, 0).unwrap());
+    assert_eq!(job.config.name, "hourly");
+}
+
+#[test]
+fn invalid_cron_is_rejected() {
+    assert!(Job::new(schedule_config("broken", "every morning")).is_err());
+}
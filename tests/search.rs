@@ -0,0 +1,104 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::search_tool::SearchTool;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn search(server: &MockServer, query: &str) -> String {
+    let tool = SearchTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+    let args = serde_json::from_value(json!({ "query": query })).unwrap();
+    tool.call(args).await.unwrap()
+}
+
+#[tokio::test]
+async fn heights_are_looked_up_directly() {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/block/42/stats",
+        json!({
+            "tx_count": "3",
+            "blobs_count": "2",
+            "blobs_size": "1024",
+            "fill_rate": "0.125",
+            "fee": "1750000",
+        }),
+    )
+    .await;
+
+    assert_eq!(
+        search(&server, " 42 ").await,
+        "Block 42: 3 transaction(s), 2 blob(s) totalling 1.00 KiB, fill rate 12.50%, fees \
+         1750000 utia (1.75 TIA)."
+    );
+}
+
+#[tokio::test]
+async fn routes_matches_to_their_details() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("query", "AA11"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "type": "tx", "result": { "hash": "AA11" } },
+        ])))
+        .mount(&server)
+        .await;
+    common::respond(
+        &server,
+        "/tx/AA11",
+        json!({
+            "height": 120,
+            "time": "2024-06-02T00:00:00Z",
+            "status": "success",
+            "fee": "2000",
+            "gas_used": 60000,
+            "gas_wanted": 80000,
+            "message_types": ["MsgPayForBlobs"],
+        }),
+    )
+    .await;
+    assert_eq!(
+        search(&server, "AA11").await,
+        "`AA11` is a tx.\nTransaction AA11 at height 120 (2024-06-02T00:00:00Z): MsgPayForBlobs, \
+         success, fee 2000 utia (0.002 TIA), 60000 of 80000 gas used."
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("query", "bob"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "type": "namespace",
+                "result": { "version": 0, "namespace_id": "0000000000000000000000000000000000000000626f62" },
+            },
+        ])))
+        .mount(&server)
+        .await;
+    common::respond(
+        &server,
+        "/namespace/0000000000000000000000000000000000000000626f62/0/blobs",
+        json!([]),
+    )
+    .await;
+    let output = search(&server, "bob").await;
+    assert!(output.starts_with("`bob` is a namespace.\n"), "{}", output);
+}
+
+#[tokio::test]
+async fn reports_unmatched_queries() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    assert_eq!(
+        search(&server, "nothing").await,
+        "Nothing matches `nothing`."
+    );
+}
@@ -0,0 +1,58 @@
+use celestia_search_assistant::session::{Session, SessionError, SessionStore, MAX_HISTORY};
+
+fn store(test: &str) -> SessionStore {
+    let dir =
+        std::env::temp_dir().join(format!("celestia-sessions-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    SessionStore::new(dir)
+}
+
+#[test]
+fn sessions_are_saved_listed_and_deleted() {
+    let store = store("lifecycle");
+    assert!(store.list().unwrap().is_empty());
+    assert!(store.load("fees").unwrap().messages.is_empty());
+
+    let mut fees = Session::default();
+    fees.push_turn("What was the fee of block 5?", "2000 utia");
+    fees.push_turn("And block 6?", "2500 utia");
+    store.save("fees", &fees).unwrap();
+    store.save("blobs", &Session::default()).unwrap();
+
+    assert_eq!(store.load("fees").unwrap().messages.len(), 4);
+    let mut listed: Vec<_> = store
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|info| (info.name, info.turns))
+        .collect();
+    listed.sort();
+    assert_eq!(listed, [("blobs".to_string(), 0), ("fees".to_string(), 2)]);
+
+    store.delete("fees").unwrap();
+    assert!(matches!(
+        store.delete("fees"),
+        Err(SessionError::NotFound(name)) if name == "fees"
+    ));
+    assert_eq!(store.list().unwrap().len(), 1);
+}
+
+#[test]
+fn names_cannot_escape_the_directory() {
+    let store = store("names");
+    assert!(matches!(
+        store.load("../secrets"),
+        Err(SessionError::InvalidName(_))
+    ));
+}
+
+#[test]
+fn history_is_capped_to_the_latest_messages() {
+    let mut session = Session::default();
+    for i in 0..MAX_HISTORY {
+        session.push_turn(&format!("question {}", i), "answer");
+    }
+    let history = session.history();
+    assert_eq!(history.len(), MAX_HISTORY);
+    assert_eq!(history[0].content, format!("question {}", MAX_HISTORY / 2));
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use celestia_search_assistant::shutdown::{self, Graceful, Interrupted};
+
+// Ctrl-C is process-wide, so its stages are checked in order in a single test
+#[tokio::test]
+async fn first_ctrl_c_asks_graceful_commands_to_stop_and_second_forces_them() {
+    assert_eq!(shutdown::interruptible(async { 7 }).await.ok(), Some(7));
+    assert!(!shutdown::is_requested());
+
+    let graceful = Graceful::new();
+    shutdown::request();
+    assert!(shutdown::is_requested());
+    assert!(matches!(
+        shutdown::interruptible(std::future::pending::<()>()).await,
+        Err(Interrupted)
+    ));
+    // The command stopping by itself isn't dropped before it's done saving
+    let forced = tokio::time::timeout(Duration::from_millis(50), shutdown::forced()).await;
+    assert!(forced.is_err());
+
+    shutdown::request();
+    let forced = tokio::time::timeout(Duration::from_secs(1), shutdown::forced()).await;
+    assert!(forced.is_ok());
+    drop(graceful);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::staking_yield_tool::StakingYieldTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::MockServer;
+
+async fn yield_server(rewards: Value) -> MockServer {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/head",
+        json!({
+            "last_height": 3000000,
+            "total_stake": "500000000000000",
+            "total_supply": "1000000000000000",
+        }),
+    )
+    .await;
+    common::respond(&server, "/constants", json!({ "module": {} })).await;
+    common::respond(
+        &server,
+        "/block/3000000/stats",
+        json!({ "inflation_rate": "0.08" }),
+    )
+    .await;
+    common::respond(&server, "/stats/series/rewards/day", rewards).await;
+    server
+}
+
+#[tokio::test]
+async fn charts_inflation_and_apr_by_day() {
+    let server = yield_server(json!([
+        { "time": "2024-06-03T00:00:00Z", "value": "240000000000" },
+        { "time": "2024-06-01T00:00:00Z", "value": "200000000000" },
+        { "time": "2024-06-02T00:00:00Z", "value": "220000000000" },
+    ]))
+    .await;
+    let tool = StakingYieldTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "days": 3 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The protocol's inflation rate is 8.00% at block 3000000. With 50.00% of the supply \
+         bonded, stakers earn about 15.68% a year before commission. Over the last 3 day(s), the \
+         rewards paid were worth an APR of 14.60% to 17.52% at today's stake, 16.06% on average, \
+         and the trend is rising (+2.92 percentage points across the window). Daily rewards, APR \
+         and implied inflation:\n\
+         - 2024-06-01: 200000 TIA, 14.60% APR, 7.45% inflation\n\
+         - 2024-06-02: 220000 TIA, 16.06% APR, 8.19% inflation\n\
+         - 2024-06-03: 240000 TIA, 17.52% APR, 8.94% inflation"
+    );
+}
+
+#[tokio::test]
+async fn reports_inflation_without_a_series() {
+    let server = yield_server(json!([])).await;
+    let tool = StakingYieldTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({})).unwrap();
+    assert!(tool.call(args).await.unwrap().ends_with(
+        "The indexer has no daily rewards for the last 30 day(s) to chart yields with."
+    ));
+}
@@ -0,0 +1,136 @@
+#![cfg(feature = "sqlite-cache")]
+
+mod common;
+
+use std::sync::Arc;
+
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::store::{BlockStore, StoreError};
+use serde_json::json;
+use wiremock::MockServer;
+
+use crate::common::{fixture, replay};
+
+#[tokio::test]
+async fn repeated_queries_are_served_locally() {
+    let server = MockServer::start().await;
+    replay(&server, "/block/2000000/stats", "block_stats_2000000").await;
+
+    let store = Arc::new(BlockStore::in_memory().unwrap());
+    let tool = CelestiaSearchTool::with_base_url(&server.uri()).with_store(store.clone());
+
+    let first = tool.fetch_stats(2000000).await.unwrap();
+    let second = tool.fetch_stats(2000000).await.unwrap();
+    assert_eq!(first.fee, second.fee);
+    assert_eq!(second.rewards.to_string(), "110326765.952");
+    let stats_requests = server
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|request| request.url.path() == "/block/2000000/stats")
+        .count();
+    assert_eq!(stats_requests, 1);
+}
+
+#[tokio::test]
+async fn answers_offline_from_stored_blocks() {
+    let server = MockServer::start().await;
+    let store = Arc::new(BlockStore::in_memory().unwrap());
+    store
+        .put(Network::Mainnet, 9999, &fixture("block_stats_9999"))
+        .unwrap();
+    store
+        .put(Network::Mainnet, 2000000, &fixture("block_stats_2000000"))
+        .unwrap();
+    let tool = CelestiaSearchTool::with_base_url(&server.uri())
+        .with_store(store)
+        .offline();
+
+    let stats = tool.fetch_stats(2000000).await.unwrap();
+    assert_eq!(stats.rewards.to_string(), "110326765.952");
+    assert_eq!(tool.chain_head().await.unwrap(), 2000000);
+
+    let result = tool.fetch_stats(2000001).await;
+    assert!(
+        matches!(result, Err(CelestiaSearchError::NotCached { url }) if url.ends_with("/block/2000001/stats"))
+    );
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn blocks_are_stored_per_network() {
+    let server = MockServer::start().await;
+    replay(&server, "/block/9999/stats", "block_stats_9999").await;
+    let store = Arc::new(BlockStore::in_memory().unwrap());
+    store
+        .put(Network::Mocha, 2000000, &fixture("block_stats_2000000"))
+        .unwrap();
+
+    let tool = CelestiaSearchTool::with_base_url(&server.uri())
+        .with_store(store.clone())
+        .offline();
+    assert!(store.get(Network::Mainnet, 2000000).unwrap().is_none());
+    assert_eq!(store.max_height(Network::Mainnet).unwrap(), None);
+    assert!(tool.fetch_stats(2000000).await.is_err());
+
+    let mocha = CelestiaSearchTool::with_base_url(&server.uri())
+        .with_network(Network::Mocha)
+        .with_store(store.clone())
+        .offline();
+    let stats = mocha.fetch_stats(2000000).await.unwrap();
+    assert_eq!(stats.network, Network::Mocha);
+    assert_eq!(store.max_height(Network::Mocha).unwrap(), Some(2000000));
+}
+
+#[test]
+fn aggregates_over_stored_blocks() {
+    let store = BlockStore::in_memory().unwrap();
+    store
+        .put(Network::Mainnet, 9999, &fixture("block_stats_9999"))
+        .unwrap();
+    store
+        .put(Network::Mainnet, 2000000, &fixture("block_stats_2000000"))
+        .unwrap();
+
+    let rows = store
+        .query("SELECT COUNT(*) AS blocks, SUM(fee) AS fees, MAX(fill_rate) AS max_fill FROM block_stats")
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![json!({ "blocks": 2, "fees": 2340972, "max_fill": 0.7252 })]
+    );
+}
+
+#[test]
+fn rejects_writes() {
+    let store = BlockStore::in_memory().unwrap();
+    let err = store.query("DELETE FROM block_stats").unwrap_err();
+    assert!(matches!(err, StoreError::NotReadOnly));
+}
+
+#[test]
+fn rejects_attaching_other_databases() {
+    let dir = std::env::temp_dir().join(format!("celestia-store-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let store = BlockStore::open(dir.join("store.db")).unwrap();
+    let other = dir.join("other.db");
+
+    let sql = format!("ATTACH DATABASE '{}' AS other", other.display());
+    let err = store.query(&sql).unwrap_err();
+    assert!(matches!(err, StoreError::NotReadOnly));
+    assert!(!other.exists());
+    let err = store.query("PRAGMA table_info(block_stats)").unwrap_err();
+    assert!(matches!(err, StoreError::NotReadOnly));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn aborts_unbounded_queries() {
+    let store = BlockStore::in_memory().unwrap();
+    let sql = "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n) \
+               SELECT MAX(i) FROM n";
+    let err = store.query(sql).unwrap_err();
+    assert!(matches!(err, StoreError::TooLong));
+}
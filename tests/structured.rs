@@ -0,0 +1,105 @@
+use celestia_search_assistant::assistant::Assistant;
+use celestia_search_assistant::structured::{self, StructuredAnswer};
+use rig::providers::openai;
+use serde_json::{json, Value};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A chat completion response holding a plain message.
+fn message(content: &str) -> Value {
+    json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "gpt-4o-mini",
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "logprobs": null,
+            "finish_reason": "stop",
+        }],
+        "usage": { "prompt_tokens": 12, "total_tokens": 20 },
+    })
+}
+
+#[test]
+fn parses_answers_matching_the_schema() {
+    let reply = "```json\n{\"answer\": \"Block 10000 paid 2217 utia.\", \"values\": \
+                 {\"fee_utia\": 2217}, \"sources\": [\"search_blocks\"]}\n```";
+    assert_eq!(
+        structured::parse(reply).unwrap(),
+        StructuredAnswer {
+            answer: "Block 10000 paid 2217 utia.".to_string(),
+            values: [("fee_utia".to_string(), json!(2217))].into(),
+            sources: vec!["search_blocks".to_string()],
+        }
+    );
+}
+
+#[test]
+fn explains_mismatches() {
+    let cases = [
+        ("Block 10000 paid 2217 utia.", "it was not valid JSON"),
+        ("[1]", "it was not a JSON object"),
+        (
+            r#"{"answer": "", "values": {}, "sources": []}"#,
+            "`answer` was empty",
+        ),
+        (
+            r#"{"answer": "2217 utia", "values": {"fee": [2217]}, "sources": []}"#,
+            "value `fee` was not a number, string or boolean",
+        ),
+        (
+            r#"{"answer": "2217 utia", "values": {}}"#,
+            "`sources` was missing or not an array of strings",
+        ),
+        (
+            r#"{"answer": "2217 utia", "values": {}, "sources": [], "confidence": 1}"#,
+            "it had an unexpected field `confidence`",
+        ),
+    ];
+    for (reply, reason) in cases {
+        let err = structured::parse(reply).unwrap_err();
+        assert!(err.starts_with(reason), "{}: {}", reply, err);
+    }
+}
+
+#[tokio::test]
+async fn reprompts_answers_not_matching_the_schema() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message("It paid 2217 utia.")))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message(
+            r#"{"answer": "Block 10000 paid 2217 utia.", "values": {"fee_utia": 2217}, "sources": ["search_blocks"]}"#,
+        )))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .structured(true)
+        .build();
+
+    let turn = assistant.prompt("Fee of block 10000?").await.unwrap();
+    let answer = structured::parse(&turn.output).unwrap();
+    assert_eq!(answer.values["fee_utia"], 2217);
+    assert_eq!(turn.usage.total(), 60);
+
+    // The draft answer is restated, then re-prompted with why it didn't match
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    let last = |i: usize| {
+        let body: Value = serde_json::from_slice(&requests[i].body).unwrap();
+        let messages = body["messages"].as_array().unwrap().clone();
+        messages.last().unwrap()["content"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    assert!(last(1).contains("Your draft answer:\nIt paid 2217 utia."));
+    assert!(last(2).ends_with("Your previous response could not be used: it was not valid JSON (expected value at line 1 column 1)."));
+}
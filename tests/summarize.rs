@@ -0,0 +1,52 @@
+use celestia_search_assistant::summarize::{self, estimate_tokens};
+use serde_json::{json, Value};
+
+fn blocks(count: u64) -> Value {
+    let blocks: Vec<Value> = (1..=count)
+        .map(|height| json!({ "height": height, "fee": (height * 10).to_string(), "hash": "ab".repeat(32) }))
+        .collect();
+    json!({ "network": "mainnet", "blocks": blocks })
+}
+
+#[test]
+fn leaves_results_within_the_budget() {
+    let result = blocks(2).to_string();
+    assert_eq!(summarize::fit(&result, 2000), result);
+}
+
+#[test]
+fn aggregates_long_block_ranges() {
+    let result = blocks(500).to_string();
+    assert!(estimate_tokens(&result) > 500);
+
+    let fitted = summarize::fit(&result, 500);
+    let (summary, note) = fitted.split_once("\n\n").unwrap();
+    assert!(estimate_tokens(summary) <= 500);
+    assert_eq!(
+        note,
+        "[Summarized to fit in about 500 tokens: `blocks` (500 items) was aggregated, keeping \
+         its first and last 3.]"
+    );
+
+    let summary: Value = serde_json::from_str(summary).unwrap();
+    assert_eq!(summary["network"], "mainnet");
+    let blocks = &summary["blocks"];
+    assert_eq!(blocks["summarized_items"], 500);
+    assert_eq!(
+        blocks["stats"]["fee"],
+        json!({ "count": 500, "min": 10, "max": 5000, "mean": 2505, "sum": 1252500 })
+    );
+    assert_eq!(blocks["first"][0]["height"], 1);
+    assert_eq!(blocks["last"][2]["height"], 500);
+}
+
+#[test]
+fn truncates_what_cannot_be_aggregated() {
+    let result = "fee ".repeat(1000);
+    let fitted = summarize::fit(&result, 100);
+    assert!(fitted.starts_with(&"fee ".repeat(100)));
+    assert!(fitted.ends_with(
+        "[Summarized to fit in about 100 tokens: the result was truncated to its first 400 of \
+         4000 characters.]"
+    ));
+}
@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+
+use celestia_search_assistant::template::{self, TemplateError, TEMPLATES};
+
+#[test]
+fn renders_templates_with_params_and_defaults() {
+    let params = [("height".to_string(), "2000000".to_string())];
+    let prompt = template::render("block-deep-dive", &params).unwrap();
+    assert!(prompt.starts_with("Give a deep dive into the Celestia block at height 2000000:"));
+
+    let validator = template::find("validator-report").unwrap();
+    assert_eq!(validator.params(), ["validator", "days"]);
+    let params = BTreeMap::from([("validator".to_string(), "Figment".to_string())]);
+    let prompt = validator.render(&params).unwrap();
+    assert!(prompt.contains("validator Figment:"));
+    assert!(prompt.ends_with("over the last 30 days."));
+
+    // Every template renders once its required parameters are given
+    for template in TEMPLATES {
+        assert!(!template.text.trim().is_empty());
+        let params = template
+            .params()
+            .into_iter()
+            .map(|param| (param, "1".to_string()))
+            .collect();
+        assert!(!template.render(&params).unwrap().contains("{{"));
+    }
+}
+
+#[test]
+fn rejects_unknown_templates_and_params() {
+    assert_eq!(
+        template::render("weekly", &[]).unwrap_err().to_string(),
+        "Unknown template weekly; the templates are daily-summary, block-deep-dive, \
+         validator-report"
+    );
+    assert_eq!(
+        template::render("block-deep-dive", &[]),
+        Err(TemplateError::MissingParam(
+            "block-deep-dive",
+            "height".to_string()
+        ))
+    );
+    let params = [("hieght".to_string(), "1".to_string())];
+    assert_eq!(
+        template::render("block-deep-dive", &params)
+            .unwrap_err()
+            .to_string(),
+        "Template block-deep-dive has no parameter hieght; its parameters are height"
+    );
+}
+
+#[test]
+fn parses_params() {
+    assert_eq!(
+        template::parse_param("height = 2000000"),
+        Ok(("height".to_string(), "2000000".to_string()))
+    );
+    assert!(template::parse_param("height").is_err());
+    assert!(template::parse_param("=1").is_err());
+}
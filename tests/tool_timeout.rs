@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use celestia_search_assistant::config::Config;
+use celestia_search_assistant::tool_timeout::{self, TimeoutPolicy, ToolTimeouts};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde_json::json;
+
+/// Answers after a second, the first `slow_calls` times it's called, and at once after.
+struct Slow {
+    calls: Arc<AtomicUsize>,
+    slow_calls: usize,
+}
+
+impl Tool for Slow {
+    const NAME: &'static str = "slow";
+
+    type Args = serde_json::Value;
+    type Output = String;
+    type Error = std::convert::Infallible;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Answer slowly".to_string(),
+            parameters: json!({ "type": "object" }),
+        }
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) < self.slow_calls {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+        Ok("done".to_string())
+    }
+}
+
+fn timeouts(policy: TimeoutPolicy) -> ToolTimeouts {
+    ToolTimeouts {
+        timeouts: [("slow".to_string(), 1)].into(),
+        on_timeout: policy,
+        ..ToolTimeouts::default()
+    }
+}
+
+#[test]
+fn timeouts_are_read_from_the_config() {
+    let config = Config::parse(
+        "[tools]\ntimeout_secs = 30\non_timeout = \"refuse\"\n\n[tools.timeouts]\n\
+         fill_rate_trend = 90",
+    )
+    .unwrap();
+    assert_eq!(config.tools.on_timeout, TimeoutPolicy::Refuse);
+    assert_eq!(
+        config.tools.timeout("fill_rate_trend"),
+        Some(Duration::from_secs(90))
+    );
+    assert_eq!(
+        config.tools.timeout("search_blocks"),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(Config::default().tools.timeout("search_blocks"), None);
+    assert!(Config::parse("[tools]\non_timeout = \"wait\"").is_err());
+}
+
+#[tokio::test]
+async fn calls_over_the_limit_answer_as_the_policy_says() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let slow = |slow_calls| {
+        Box::new(Slow {
+            calls: calls.clone(),
+            slow_calls,
+        })
+    };
+
+    let tool = timeouts(TimeoutPolicy::Partial).apply(slow(1));
+    let output = tool.call("{}".to_string()).await.unwrap();
+    assert!(output.contains("`slow` tool timed out after 1s, so this data is missing"));
+    assert!(tool_timeout::timed_out(&output));
+
+    let tool = timeouts(TimeoutPolicy::Refuse).apply(slow(2));
+    let output = tool.call("{}".to_string()).await.unwrap();
+    assert!(output.contains("can't be answered at the moment"));
+    assert!(tool_timeout::timed_out(&output));
+
+    // The second attempt answers at once
+    calls.store(0, Ordering::SeqCst);
+    let tool = timeouts(TimeoutPolicy::Retry).apply(slow(1));
+    assert_eq!(tool.call("{}".to_string()).await.unwrap(), "\"done\"");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // Tools without a limit are left as they are
+    let tool = ToolTimeouts::default().apply(slow(0));
+    assert_eq!(tool.call("{}".to_string()).await.unwrap(), "\"done\"");
+}
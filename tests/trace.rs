@@ -0,0 +1,72 @@
+use celestia_search_assistant::celenium::CeleniumClient;
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchError;
+use celestia_search_assistant::trace;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// The exported spans named `name`
+fn spans<'a>(request: &'a Value, name: &str) -> Vec<&'a Value> {
+    request["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|span| span["name"] == name)
+        .collect()
+}
+
+#[tokio::test]
+async fn exports_nested_spans_over_otlp() {
+    let celenium = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 7 })))
+        .mount(&celenium)
+        .await;
+    let collector = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/traces"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&collector)
+        .await;
+    assert!(trace::install(&collector.uri(), "assistant-test"));
+
+    let client = CeleniumClient::builder()
+        .base_url(&celenium.uri())
+        .build()
+        .unwrap();
+    let span = trace::span("tool.call").with("tool.name", "search_blocks");
+    let result: Result<(), CelestiaSearchError> = trace::traced(span, async {
+        client.get("/head").await?;
+        Err(CelestiaSearchError::ApiError("no answer".to_string()))
+    })
+    .await;
+    assert!(result.is_err());
+    trace::flush().await;
+
+    let requests = collector.received_requests().await.unwrap();
+    let request: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(
+        request["resourceSpans"][0]["resource"]["attributes"][0],
+        json!({ "key": "service.name", "value": { "stringValue": "assistant-test" } })
+    );
+    let (tool, http) = (
+        spans(&request, "tool.call"),
+        spans(&request, "celenium.request"),
+    );
+    let (tool, http) = (tool[0], http[0]);
+    assert_eq!(http["traceId"], tool["traceId"]);
+    assert_eq!(http["parentSpanId"], tool["spanId"]);
+    assert!(tool.get("parentSpanId").is_none());
+    assert_eq!(http["kind"], 3);
+    assert_eq!(
+        tool["attributes"][0],
+        json!({ "key": "tool.name", "value": { "stringValue": "search_blocks" } })
+    );
+    assert_eq!(
+        tool["status"],
+        json!({ "code": 2, "message": "API error: no answer" })
+    );
+    assert_eq!(http["status"], json!({ "code": 1 }));
+}
@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use celestia_search_assistant::accounting::TokenUsage;
+use celestia_search_assistant::assistant::{ToolCall, Turn};
+use celestia_search_assistant::transcript::{Transcript, TranscriptFormat};
+use serde_json::json;
+
+fn turn(output: &str, calls: Vec<ToolCall>) -> Turn {
+    let mut steps = calls;
+    let tool_call = steps.pop();
+    Turn {
+        output: output.to_string(),
+        tool_call,
+        steps,
+        usage: TokenUsage::default(),
+        warnings: Vec::new(),
+    }
+}
+
+fn call(name: &str, args: serde_json::Value, output: Option<&str>) -> ToolCall {
+    ToolCall {
+        name: name.to_string(),
+        args,
+        output: output.map(str::to_string),
+        ..ToolCall::default()
+    }
+}
+
+fn transcript() -> Transcript {
+    let mut transcript = Transcript::default();
+    transcript.push(
+        "Fee of block 10?",
+        &turn(
+            "Block 10 paid <2000> utia.",
+            vec![call(
+                "search_blocks",
+                json!({ "height": 10 }),
+                Some("fee: 2000"),
+            )],
+        ),
+    );
+    let series = r#"[{"height":1,"fee":5},{"height":2,"fee":7},{"height":3,"fee":6}]"#;
+    let mut chained = turn(
+        "Fees rose, then fell.",
+        vec![
+            call("compare_blocks", json!({ "from": 1, "to": 3 }), Some("up")),
+            call(
+                "query_block_store",
+                json!({ "sql": "SELECT" }),
+                Some(series),
+            ),
+        ],
+    );
+    chained
+        .warnings
+        .push("7 isn't backed by a tool result".to_string());
+    transcript.push("How did fees move?", &chained);
+    transcript
+}
+
+#[test]
+fn renders_questions_tool_calls_and_answers_as_markdown() {
+    assert_eq!(
+        transcript().to_markdown(),
+        "# Celestia search assistant transcript\n\
+         \n## 1. Fee of block 10?\n\
+         \n**Tool call:** `search_blocks` with `{\"height\":10}`\n\n\
+         ```\nfee: 2000\n```\n\
+         \n**Answer:**\n\nBlock 10 paid <2000> utia.\n\
+         \n## 2. How did fees move?\n\
+         \n**Tool call:** `compare_blocks` with `{\"from\":1,\"to\":3}`\n\n\
+         ```\nup\n```\n\
+         \n**Tool call:** `query_block_store` with `{\"sql\":\"SELECT\"}`\n\n\
+         | point | fee |\n| --- | --- |\n| 1 | 5 |\n| 2 | 7 |\n| 3 | 6 |\n\
+         \n**Answer:**\n\nFees rose, then fell.\n\
+         \n> **Warning:** 7 isn't backed by a tool result\n"
+    );
+}
+
+#[test]
+fn renders_an_escaped_html_page() {
+    let html = transcript().to_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h2>1. Fee of block 10?</h2>"));
+    assert!(html.contains("<div class=\"answer\">Block 10 paid &lt;2000&gt; utia.</div>"));
+    assert!(html.contains("<tr><td>2</td><td>7</td></tr>"));
+    assert!(html.contains("<code>{&quot;height&quot;:10}</code>"));
+}
+
+#[test]
+fn planned_calls_have_no_answer() {
+    let mut transcript = Transcript::default();
+    transcript.push(
+        "Fee of block 10?",
+        &turn(
+            "Would call search_blocks",
+            vec![call("search_blocks", json!({ "height": 10 }), None)],
+        ),
+    );
+    assert_eq!(transcript.entries()[0].answer, None);
+    assert!(transcript.to_markdown().ends_with(
+        "_Planned in dry-run mode, not called._\n\n**Answer:** _dry run, tool not called_\n"
+    ));
+}
+
+#[test]
+fn writes_markdown_or_html_by_extension() {
+    assert_eq!(
+        TranscriptFormat::for_path(Path::new("report.HTML")),
+        TranscriptFormat::Html
+    );
+    assert_eq!(
+        TranscriptFormat::for_path(Path::new("report.md")),
+        TranscriptFormat::Markdown
+    );
+
+    let path =
+        std::env::temp_dir().join(format!("celestia-transcript-{}.html", std::process::id()));
+    transcript().write(&path).unwrap();
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .contains("<h2>2. How did fees move?</h2>"));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "tui")]
+
+use celestia_search_assistant::accounting::TokenUsage;
+use celestia_search_assistant::assistant::{ToolCall, Turn};
+use celestia_search_assistant::celestia_search_tool::CelestiaResponseFields;
+use celestia_search_assistant::tui::{App, Author};
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Terminal;
+use serde_json::json;
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        app.on_key(KeyEvent::from(KeyCode::Char(c)));
+    }
+}
+
+fn stats(tx_count: u64) -> CelestiaResponseFields {
+    CelestiaResponseFields::from_json(&json!({
+        "tx_count": tx_count.to_string(),
+        "blobs_count": "3",
+        "fill_rate": "0.25",
+    }))
+    .unwrap()
+}
+
+#[test]
+fn enter_submits_one_question_at_a_time() {
+    let mut app = App::default();
+    type_text(&mut app, "What is the fee of block 5?");
+    assert_eq!(
+        app.on_key(KeyEvent::from(KeyCode::Enter)),
+        Some("What is the fee of block 5?".to_string())
+    );
+    assert!(app.pending && app.input.is_empty());
+
+    type_text(&mut app, "And block 6?");
+    assert_eq!(app.on_key(KeyEvent::from(KeyCode::Enter)), None);
+
+    app.on_answer(Ok(Turn {
+        output: "The gas fee is: 2000 utia".to_string(),
+        tool_call: Some(ToolCall {
+            name: "search_blocks".to_string(),
+            args: json!({ "height": 5 }),
+            output: Some("The gas fee is: 2000 utia".to_string()),
+            ..ToolCall::default()
+        }),
+        steps: Vec::new(),
+        usage: TokenUsage::default(),
+        warnings: Vec::new(),
+    }));
+    assert!(!app.pending);
+    assert_eq!(app.messages.last().unwrap().0, Author::Assistant);
+    assert_eq!(app.tool_calls[0].name, "search_blocks");
+    assert_eq!(
+        app.on_key(KeyEvent::from(KeyCode::Enter)),
+        Some("And block 6?".to_string())
+    );
+}
+
+#[test]
+fn ticker_keeps_the_latest_blocks() {
+    let mut app = App::default();
+    for height in 1..=60 {
+        app.on_block(height, stats(height));
+    }
+    assert_eq!(app.blocks.len(), 50);
+    assert_eq!(app.blocks.front().unwrap().0, 60);
+}
+
+#[test]
+fn dashboard_draws_every_pane() {
+    let mut app = App::default();
+    app.on_block(2000000, stats(12));
+    type_text(&mut app, "How full are blocks?");
+    app.on_key(KeyEvent::from(KeyCode::Enter));
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    for text in [
+        "Chat",
+        "Blocks",
+        "Tool calls",
+        "How full are blocks?",
+        "2000000",
+        "12 txs",
+        "Thinking",
+    ] {
+        assert!(screen.contains(text), "missing {text:?}");
+    }
+}
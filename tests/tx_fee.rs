@@ -0,0 +1,88 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::rest::RestClient;
+use celestia_search_assistant::tx_fee_tool::TxFeeTool;
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::MockServer;
+
+const HASH: &str = "0b4f9a4c1a9f3e8d7c6b5a4938271605f4e3d2c1b0a99887766554433221100f";
+const SIGNER: &str = "celestia1qnhx3v7ztqg4vzquzwaq0xmpwqgh3g7kh8sz3r";
+const GRANTER: &str = "celestia1gr4nt3rqwl9ht8x4ue3v8v5ydj3f3xhz6ucvl0";
+
+async fn tx_server() -> MockServer {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        &format!("/tx/{}", HASH),
+        json!({
+            "height": 120,
+            "status": "success",
+            "fee": "2000",
+            "gas_used": 60000,
+            "gas_wanted": 80000,
+            "signers": [SIGNER],
+        }),
+    )
+    .await;
+    server
+}
+
+fn tool(server: &MockServer) -> TxFeeTool {
+    let rest = RestClient::new([(Network::Mainnet, server.uri())].into_iter().collect());
+    TxFeeTool::new(CelestiaSearchTool::with_base_url(&server.uri()), rest)
+}
+
+#[tokio::test]
+async fn breaks_down_gas_and_fee_grants() {
+    let server = tx_server().await;
+    common::respond(
+        &server,
+        &format!("/cosmos/tx/v1beta1/txs/{}", HASH.to_uppercase()),
+        json!({
+            "tx": { "auth_info": { "fee": { "payer": "", "granter": "" } } },
+            "tx_response": {
+                "events": [{
+                    "type": "use_feegrant",
+                    "attributes": [
+                        { "key": "granter", "value": GRANTER },
+                        { "key": "grantee", "value": SIGNER },
+                    ],
+                }],
+            },
+        }),
+    )
+    .await;
+
+    let args =
+        serde_json::from_value(json!({ "hash": format!("0x{}", HASH.to_uppercase()) })).unwrap();
+    assert_eq!(
+        tool(&server).call(args).await.unwrap(),
+        format!(
+            "Transaction {} at height 120 (success) paid a fee of 2000 utia (0.002 TIA) for 80000 \
+             gas wanted, of which 60000 (75.00%) was used. The effective gas price is 0.025 utia \
+             per gas wanted (0.033333 utia per gas used). Unused gas isn't refunded, so 500 utia \
+             paid for the 20000 gas left unused. {} paid the fee through a fee grant from {}, \
+             whose allowance it used.",
+            HASH, SIGNER, GRANTER
+        )
+    );
+}
+
+#[tokio::test]
+async fn answers_without_the_auth_info() {
+    let server = tx_server().await;
+
+    let args = serde_json::from_value(json!({ "hash": HASH })).unwrap();
+    let output = tool(&server).call(args).await.unwrap();
+    assert!(output.contains("0.025 utia per gas wanted"));
+    assert!(output.contains("The fee's payer and any fee grant are unknown"));
+
+    let args = serde_json::from_value(json!({ "hash": "AA11" })).unwrap();
+    assert!(matches!(
+        tool(&server).call(args).await,
+        Err(CelestiaSearchError::InvalidTxHash(_))
+    ));
+}
@@ -0,0 +1,94 @@
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::upgrades_tool::{upgrades, UpgradesTool, WINDOW};
+use rig::tool::Tool;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const GINGER: u64 = 2993219;
+
+async fn indexer(head: u64) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": head })))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[test]
+fn bundled_upgrades_activate_in_order() {
+    let upgrades = upgrades();
+    assert_eq!(upgrades[0].name, "Lemongrass");
+    for pair in upgrades.windows(2) {
+        assert!(pair[0].app_version < pair[1].app_version);
+        assert!(pair[0].heights[&Network::Mainnet] < pair[1].heights[&Network::Mainnet]);
+    }
+}
+
+#[tokio::test]
+async fn lists_upgrades_with_upcoming_ones() {
+    let server = indexer(2500000).await;
+    let tool = UpgradesTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let output = tool
+        .call(serde_json::from_value(json!({})).unwrap())
+        .await
+        .unwrap();
+    assert!(output.starts_with("Celestia's network upgrades on mainnet:\n"));
+    assert!(output.contains(
+        "- Lemongrass (app version 2) activated at height 2371495 on mainnet (2024-09-18): "
+    ));
+    assert!(output.contains(
+        "- Ginger (app version 3) is planned for height 2993219 on mainnet, 493219 block(s) \
+         from the head: "
+    ));
+}
+
+#[tokio::test]
+async fn compares_blocks_before_and_after_an_upgrade() {
+    let server = indexer(3000000).await;
+    for height in GINGER - WINDOW..GINGER + WINDOW {
+        let block_time = if height < GINGER { 12000 } else { 6000 };
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "block_time": block_time,
+                "fill_rate": "0.05",
+                "tx_count": 4,
+                "blobs_count": 2,
+                "blobs_size": 2048,
+                "fee": "3000",
+            })))
+            .mount(&server)
+            .await;
+    }
+    let tool = UpgradesTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "name": "ginger" })).unwrap();
+    let output = tool.call(args).await.unwrap();
+    assert!(output.starts_with(
+        "Ginger (app version 3) activated at height 2993219 on mainnet (2024-12-04). Halved the \
+         block time and bounded transaction sizes.\n- The target block time drops"
+    ));
+    assert!(output.ends_with(
+        "\nThe 25 blocks before it (from 2993194): on average 12.0s apart, 5.00% full, with 4.0 \
+         transaction(s) and 2.0 blob(s) of 2.00 KiB paying 3000 utia (0.003 TIA) in fees.\n\
+         The 25 blocks from it on (to 2993243): on average 6.0s apart, 5.00% full, with 4.0 \
+         transaction(s) and 2.0 blob(s) of 2.00 KiB paying 3000 utia (0.003 TIA) in fees."
+    ));
+}
+
+#[tokio::test]
+async fn unknown_upgrades_list_the_known_ones() {
+    let tool = UpgradesTool::new(CelestiaSearchTool::with_base_url("http://localhost:1"));
+    let args = serde_json::from_value(json!({ "name": "Basil" })).unwrap();
+    match tool.call(args).await {
+        Err(CelestiaSearchError::UnknownValue { known, .. }) => {
+            assert_eq!(known, "Lemongrass, Ginger")
+        }
+        other => panic!("expected an unknown upgrade, got {:?}", other.map(|_| ())),
+    }
+}
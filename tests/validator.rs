@@ -0,0 +1,409 @@
+mod common;
+
+use celestia_search_assistant::celestia_search_tool::{CelestiaSearchError, CelestiaSearchTool};
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::pending_rewards_tool::PendingRewardsTool;
+use celestia_search_assistant::pending_unbondings_tool::PendingUnbondingsTool;
+use celestia_search_assistant::rest::RestClient;
+use celestia_search_assistant::slashing_tool::SlashingTool;
+use celestia_search_assistant::validator_directory::{find, Listed, Lookup};
+use celestia_search_assistant::validator_rewards_tool::ValidatorRewardsTool;
+use celestia_search_assistant::validator_tool::ValidatorTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ADDRESS: &str = "celestiavaloper1q3v5cugc8cdpud87u4zwy0a74uxkk6u4q4gx4p";
+
+async fn validator_server() -> MockServer {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/validator/12",
+        json!({ "id": 12, "moniker": "Stakely", "address": ADDRESS, "jailed": false }),
+    )
+    .await;
+    let blocks: Vec<Value> = (101..=104)
+        .map(|height| json!({ "height": height, "signed": height != 103 }))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/validator/12/uptime"))
+        .and(query_param("limit", "4"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "uptime": "0.75", "blocks": blocks })),
+        )
+        .mount(&server)
+        .await;
+    common::respond(
+        &server,
+        "/validator/12/jails",
+        json!([{ "height": 50, "time": "2024-03-01T10:00:00Z", "reason": "missing_signature" }]),
+    )
+    .await;
+    server
+}
+
+#[tokio::test]
+async fn reports_uptime_and_jails() {
+    let server = validator_server().await;
+    let tool = ValidatorTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "validator": "12", "blocks": 4 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        format!(
+            "Validator Stakely ({}) is active. It signed 3 of the last 4 blocks (75.00% uptime); \
+             it missed heights 103. It has been jailed 1 time(s): at height 50 on \
+             2024-03-01T10:00:00Z (missing_signature).",
+            ADDRESS
+        )
+    );
+}
+
+#[tokio::test]
+async fn resolves_operator_addresses() {
+    let server = validator_server().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("query", ADDRESS))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "type": "validator", "result": { "id": 12 } },
+        ])))
+        .mount(&server)
+        .await;
+    let tool = ValidatorTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "validator": ADDRESS, "blocks": 4 })).unwrap();
+    assert!(tool
+        .call(args)
+        .await
+        .unwrap()
+        .starts_with("Validator Stakely"));
+
+    let args = serde_json::from_value(json!({ "validator": "celestiavaloper1unknown" })).unwrap();
+    assert!(matches!(
+        tool.call(args).await,
+        Err(CelestiaSearchError::UnknownValidator(_))
+    ));
+}
+
+fn listed(id: u64, moniker: &str) -> Listed {
+    Listed {
+        id,
+        moniker: moniker.to_string(),
+        address: Some(format!("celestiavaloper1{}", id)),
+    }
+}
+
+#[test]
+fn monikers_match_loosely() {
+    let validators = [
+        listed(1, "P2P.ORG - P2P Validator"),
+        listed(2, "Stakely"),
+        listed(3, "Stake P2P"),
+        listed(4, "Nodes.Guru"),
+    ];
+    let found = |name| match find(name, &validators) {
+        Lookup::Found(validator) => Some(validator.id),
+        _ => None,
+    };
+    assert_eq!(found("stakely"), Some(2));
+    assert_eq!(found("  STAKELY "), Some(2));
+    assert_eq!(found("stakley"), Some(2));
+    assert_eq!(found("nodes guru"), Some(4));
+    assert_eq!(found("guru"), Some(4));
+
+    let Lookup::Ambiguous(candidates) = find("P2P", &validators) else {
+        panic!("`P2P` names two validators");
+    };
+    let ids: Vec<u64> = candidates.iter().map(|validator| validator.id).collect();
+    assert_eq!(ids, [1, 3]);
+
+    assert_eq!(find("everstake", &validators), Lookup::NotFound);
+    assert_eq!(find("...", &validators), Lookup::NotFound);
+}
+
+#[tokio::test]
+async fn resolves_monikers_from_a_cached_validator_list() {
+    let server = validator_server().await;
+    Mock::given(method("GET"))
+        .and(path("/validators"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "id": 12, "moniker": "Stakely", "address": ADDRESS },
+            { "id": 20, "moniker": "P2P.ORG - P2P Validator", "address": "celestiavaloper1p2p" },
+            { "id": 21, "moniker": "Stake P2P", "address": "celestiavaloper1stake" },
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let tool = ValidatorTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "validator": "stakely", "blocks": 4 })).unwrap();
+    assert!(tool
+        .call(args)
+        .await
+        .unwrap()
+        .starts_with("Validator Stakely"));
+
+    let args = serde_json::from_value(json!({ "validator": "p2p" })).unwrap();
+    let error = tool.call(args).await.unwrap_err();
+    assert!(matches!(
+        error,
+        CelestiaSearchError::AmbiguousValidator { .. }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "`p2p` matches several validators: P2P.ORG - P2P Validator (celestiavaloper1p2p); Stake \
+         P2P (celestiavaloper1stake). Ask which one is meant, or give its operator address"
+    );
+}
+
+#[tokio::test]
+async fn lists_slashing_events() {
+    let server = validator_server().await;
+    Mock::given(method("GET"))
+        .and(path("/jails"))
+        .and(query_param("limit", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "height": 900,
+                "time": "2024-05-02T08:00:00Z",
+                "reason": "double_sign",
+                "burned": "5000000",
+                "validator": { "moniker": "Stakely" },
+            },
+            {
+                "height": 800,
+                "time": "2024-04-01T08:00:00Z",
+                "reason": "missing_signature",
+                "burned": "0",
+                "validator": { "moniker": "Nodes" },
+            },
+        ])))
+        .mount(&server)
+        .await;
+    let tool = SlashingTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "limit": 2 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "The 2 most recent slashing event(s) for the chain:\n\
+         - height 900 (2024-05-02T08:00:00Z): Stakely jailed for double_sign, slashed 5000000 \
+         utia (5 TIA)\n\
+         - height 800 (2024-04-01T08:00:00Z): Nodes jailed for missing_signature, nothing slashed"
+    );
+
+    let args = serde_json::from_value(json!({ "validator": "12" })).unwrap();
+    assert!(tool
+        .call(args)
+        .await
+        .unwrap()
+        .starts_with("The 1 most recent slashing event(s) for validator 12:\n- height 50"));
+}
+
+#[tokio::test]
+async fn breaks_down_commission_and_rewards() {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/validator/12",
+        json!({
+            "moniker": "Stakely",
+            "delegator": "celestia1operator",
+            "rate": "0.05",
+            "max_rate": "0.2",
+            "max_change_rate": "0.01",
+            "commissions": "2000000",
+            "rewards": "30000000",
+            "stake": "250",
+        }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/head",
+        json!({ "last_height": 11, "total_stake": "1000" }),
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/address/celestia1operator/messages"))
+        .and(query_param("msg_type", "MsgEditValidator"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "height": 7,
+                "time": "2024-06-01T00:00:00Z",
+                "data": { "CommissionRate": "0.05" },
+            },
+            { "height": 5, "time": "2024-05-01T00:00:00Z", "data": {} },
+        ])))
+        .mount(&server)
+        .await;
+    for height in 10..=11 {
+        common::respond(
+            &server,
+            &format!("/block/{}/stats", height),
+            json!({ "rewards": "3000000", "commissions": "400000" }),
+        )
+        .await;
+    }
+    let tool = ValidatorRewardsTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "validator": "12", "blocks": 2 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Validator Stakely charges a 5.00% commission (at most 20.00%, changing by at most 1.00% \
+         a day). It has earned 2000000 utia (2 TIA) in commission and 30000000 utia (30 TIA) in \
+         rewards so far. Commission rate changes: 5.00% at height 7 (2024-06-01T00:00:00Z). Over \
+         blocks 10 to 11 the chain paid 6000000 utia (6 TIA) in rewards and 800000 utia (0.8 TIA) \
+         in commissions; with 25.00% of the stake, about 200000 utia (0.2 TIA) of the \
+         commissions went to this validator."
+    );
+}
+
+#[tokio::test]
+async fn lists_pending_rewards_per_validator() {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/cosmos/distribution/v1beta1/delegators/celestia1delegator/rewards",
+        json!({
+            "rewards": [
+                {
+                    "validator_address": "celestiavaloper1small",
+                    "reward": [{ "denom": "utia", "amount": "250000.750000000000000000" }],
+                },
+                {
+                    "validator_address": "celestiavaloper1large",
+                    "reward": [{ "denom": "utia", "amount": "1500000.100000000000000000" }],
+                },
+                { "validator_address": "celestiavaloper1none", "reward": [] },
+            ],
+        }),
+    )
+    .await;
+    let rest_urls = [(Network::Mainnet, server.uri())].into_iter().collect();
+    let tool = PendingRewardsTool::new(Network::Mainnet, RestClient::new(rest_urls));
+
+    let args = serde_json::from_value(json!({ "address": "celestia1delegator" })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Address celestia1delegator can claim 1750000 utia (1.75 TIA) in staking rewards from 2 \
+         validator(s):\n\
+         - celestiavaloper1large: 1500000 utia (1.5 TIA)\n\
+         - celestiavaloper1small: 250000 utia (0.25 TIA)"
+    );
+
+    let args = serde_json::from_value(json!({ "address": ADDRESS })).unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert!(matches!(err, CelestiaSearchError::InvalidAddress(_)));
+
+    let args =
+        serde_json::from_value(json!({ "address": "celestia1delegator", "network": "mocha" }))
+            .unwrap();
+    let err = tool.call(args).await.unwrap_err();
+    assert!(matches!(
+        err,
+        CelestiaSearchError::NetworkUnavailable(Network::Mocha)
+    ));
+}
+
+#[tokio::test]
+async fn lists_unbondings_and_redelegations_by_completion() {
+    let server = MockServer::start().await;
+    common::respond(
+        &server,
+        "/cosmos/staking/v1beta1/delegators/celestia1delegator/unbonding_delegations",
+        json!({
+            "unbonding_responses": [{
+                "validator_address": "celestiavaloper1large",
+                "entries": [
+                    {
+                        "creation_height": "2100000",
+                        "completion_time": "2999-01-22T00:00:00Z",
+                        "initial_balance": "3000000",
+                        "balance": "3000000",
+                    },
+                    {
+                        "creation_height": "2000000",
+                        "completion_time": "2024-01-01T00:00:00Z",
+                        "initial_balance": "1000000",
+                        "balance": "1000000",
+                    },
+                ],
+            }],
+        }),
+    )
+    .await;
+    common::respond(
+        &server,
+        "/cosmos/staking/v1beta1/delegators/celestia1delegator/redelegations",
+        json!({
+            "redelegation_responses": [{
+                "redelegation": {
+                    "validator_src_address": "celestiavaloper1small",
+                    "validator_dst_address": "celestiavaloper1large",
+                },
+                "entries": [{
+                    "redelegation_entry": {
+                        "creation_height": 2050000,
+                        "completion_time": "2999-01-01T00:00:00Z",
+                    },
+                    "balance": "500000",
+                }],
+            }],
+        }),
+    )
+    .await;
+    let rest_urls = [(Network::Mainnet, server.uri())].into_iter().collect();
+    let tool = PendingUnbondingsTool::new(Network::Mainnet, RestClient::new(rest_urls));
+
+    let args = serde_json::from_value(json!({ "address": "celestia1delegator" })).unwrap();
+    let output = tool.call(args).await.unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        lines[..2],
+        [
+            "Address celestia1delegator is unbonding 4000000 utia (4 TIA) (2 unbonding(s)), each \
+             liquid once it completes:",
+            "- 1000000 utia (1 TIA) from celestiavaloper1large (started at height 2000000): \
+             completed at 2024-01-01T00:00:00Z, with the next block",
+        ]
+    );
+    // Soonest first, whatever order the API lists them in
+    assert!(lines[2].starts_with(
+        "- 3000000 utia (3 TIA) from celestiavaloper1large (started at height 2100000): \
+         completes at 2999-01-22T00:00:00Z, in about "
+    ));
+    assert!(lines[3].starts_with("1 redelegation(s)"));
+    assert!(lines[4].starts_with(
+        "- 500000 utia (0.5 TIA) from celestiavaloper1small to celestiavaloper1large (started at \
+         height 2050000): completes at 2999-01-01T00:00:00Z, in about "
+    ));
+}
+
+#[tokio::test]
+async fn reports_addresses_without_unbondings() {
+    let server = MockServer::start().await;
+    for kind in ["unbonding_delegations", "redelegations"] {
+        common::respond(
+            &server,
+            &format!(
+                "/cosmos/staking/v1beta1/delegators/celestia1delegator/{}",
+                kind
+            ),
+            json!({ "pagination": { "next_key": null, "total": "0" } }),
+        )
+        .await;
+    }
+    let rest_urls = [(Network::Mainnet, server.uri())].into_iter().collect();
+    let tool = PendingUnbondingsTool::new(Network::Mainnet, RestClient::new(rest_urls));
+
+    let args = serde_json::from_value(json!({ "address": "celestia1delegator" })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Address celestia1delegator has no pending unbondings or redelegations."
+    );
+}
@@ -0,0 +1,138 @@
+use celestia_search_assistant::assistant::Assistant;
+use celestia_search_assistant::structured;
+use celestia_search_assistant::verify::{self, Verification};
+use rig::completion::ToolDefinition;
+use rig::providers::openai;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RESULT: &str = "Block 10000: fee 2340972 utia, fill rate 0.4213, 3 blobs, hash AA11";
+
+#[test]
+fn finds_numbers_outside_words() {
+    let numbers: Vec<String> =
+        verify::numbers("Block 2,000,000 paid 1.5 TIA (hash AA11, 64x64), 3.")
+            .into_iter()
+            .map(|quoted| quoted.text)
+            .collect();
+    assert_eq!(numbers, ["2,000,000", "1.5", "3"]);
+}
+
+#[test]
+fn accepts_rounded_and_converted_numbers() {
+    let answer =
+        "Block 10,000 paid 2,340,972 utia (about 2.34 TIA) and was 42.1% full, with 3 blobs.";
+    assert_eq!(verify::check(answer, &[RESULT]), []);
+}
+
+#[test]
+fn reports_and_corrects_misquoted_numbers() {
+    let answer = "Block 10000 paid 2,430,972 utia over 7 blobs.";
+    let discrepancies = verify::check(answer, &[RESULT]);
+    let quoted: Vec<&str> = discrepancies
+        .iter()
+        .map(|d| d.quoted.text.as_str())
+        .collect();
+    assert_eq!(quoted, ["2,430,972", "7"]);
+    assert_eq!(
+        discrepancies[0].describe("search_blocks", false),
+        "2,430,972 is not in the `search_blocks` result, which has 2,340,972"
+    );
+    // Single digits are too ambiguous to correct
+    assert_eq!(discrepancies[1].closest, None);
+    assert_eq!(
+        verify::correct(answer, &discrepancies),
+        "Block 10000 paid 2,340,972 utia over 7 blobs."
+    );
+}
+
+/// A chat completion response holding a plain message, or a single tool call.
+fn completion(content: Option<&str>, tool_call: Option<Value>) -> Value {
+    let tool_calls = tool_call.map(|call| vec![call]);
+    json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "gpt-4o-mini",
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content, "tool_calls": tool_calls },
+            "logprobs": null,
+            "finish_reason": "stop",
+        }],
+        "usage": { "prompt_tokens": 12, "total_tokens": 20 },
+    })
+}
+
+struct Blocks;
+
+#[derive(Deserialize)]
+struct BlocksArgs {}
+
+impl Tool for Blocks {
+    const NAME: &'static str = "search_blocks";
+
+    type Args = BlocksArgs;
+    type Output = String;
+    type Error = std::io::Error;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Look up a block".to_string(),
+            parameters: json!({ "type": "object" }),
+        }
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(RESULT.to_string())
+    }
+}
+
+#[tokio::test]
+async fn corrects_structured_answers_from_the_tool_result() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion(
+            None,
+            Some(json!({
+                "id": "call-1",
+                "type": "function",
+                "function": { "name": "search_blocks", "arguments": "{}" },
+            })),
+        )))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion(
+            Some(
+                r#"{"answer": "Block 10000 paid 2,340,927 utia.", "values": {"fee_utia": 2340927}, "sources": ["search_blocks"]}"#,
+            ),
+            None,
+        )))
+        .mount(&server)
+        .await;
+
+    let client = openai::Client::from_url("test-key", &server.uri());
+    let assistant = Assistant::builder(client.completion_model("gpt-4o-mini"), "gpt-4o-mini")
+        .tool(Blocks)
+        .structured(true)
+        .verification(Verification::Correct)
+        .build();
+
+    let turn = assistant.prompt("Fee of block 10000?").await.unwrap();
+    let answer = structured::parse(&turn.output).unwrap();
+    assert_eq!(answer.answer, "Block 10000 paid 2,340,972 utia.");
+    assert_eq!(answer.values["fee_utia"], 2340972);
+    assert_eq!(
+        turn.warnings,
+        [
+            "corrected 2,340,927 to 2,340,972, as the `search_blocks` result has it",
+            "corrected 2340927 to 2340972, as the `search_blocks` result has it",
+        ]
+    );
+}
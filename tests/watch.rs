@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use celestia_search_assistant::alert::ConditionError;
+use celestia_search_assistant::amount::Utia;
+use celestia_search_assistant::celestia_search_tool::{CelestiaResponseFields, CelestiaSearchTool};
+use celestia_search_assistant::watch::{self, BlockDelta, ChangeFilter, DeltaFilter};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn stats(height: u64, fee: i64, fill_rate: &str, blobs_count: u64) -> Value {
+    json!({
+        "height": height,
+        "time": "2024-06-01T00:00:00Z",
+        "blobs_count": blobs_count,
+        "blobs_size": blobs_count * 1000,
+        "block_time": 6000,
+        "bytes_in_block": 20000,
+        "commissions": "0",
+        "events_count": 10,
+        "fee": fee.to_string(),
+        "fill_rate": fill_rate,
+        "gas_limit": 1000000,
+        "gas_used": 500000,
+        "inflation_rate": "0.08",
+        "rewards": "0",
+        "square_size": 8,
+        "supply_change": "0",
+        "tx_count": 2,
+    })
+}
+
+fn blob(id: &str) -> Value {
+    json!({ "namespace": { "namespace_id": id, "version": 0 }, "size": 1000 })
+}
+
+fn delta(fee_change: i64, fill_rate_change: f64, new_namespaces: &[&str]) -> BlockDelta {
+    let previous = CelestiaResponseFields::from_json(&stats(1, 0, "0", 0)).unwrap();
+    let mut current = CelestiaResponseFields::from_json(&stats(2, fee_change, "0", 0)).unwrap();
+    current.fill_rate = fill_rate_change;
+    BlockDelta::between(
+        &previous,
+        &current,
+        new_namespaces.iter().map(|id| id.to_string()).collect(),
+    )
+}
+
+#[test]
+fn deltas_are_the_changes_from_the_previous_block() {
+    let previous = CelestiaResponseFields::from_json(&stats(9, 2000, "0.5", 3)).unwrap();
+    let current = CelestiaResponseFields::from_json(&stats(10, 1500, "0.25", 1)).unwrap();
+    let delta = BlockDelta::between(&previous, &current, vec!["00abc".to_string()]);
+
+    assert_eq!(delta.height, 10);
+    assert_eq!(delta.fee_change, Utia::new(-500));
+    assert_eq!(delta.fill_rate_change, -0.25);
+    assert_eq!(delta.blobs_count_change, -2);
+    assert_eq!(delta.tx_count_change, 0);
+    assert_eq!(
+        delta.to_string(),
+        "Block 10 on mainnet: fee -500 utia, fill rate -0.2500, +0 txs, -2 blobs (-1.95 KiB), \
+         square size +0, new namespaces: 00abc"
+    );
+}
+
+#[test]
+fn change_filters_keep_deltas_matching_any_condition() {
+    let filter = ChangeFilter::parse(&["fee_change > 1 TIA", "new_namespaces > 0"]).unwrap();
+    assert!(filter.keep(&delta(2_000_000, 0.0, &[])));
+    assert!(filter.keep(&delta(0, 0.0, &["00abc"])));
+    assert!(!filter.keep(&delta(1_000_000, 0.5, &[])));
+
+    assert!(ChangeFilter::default().keep(&delta(0, 0.0, &[])));
+    let closure = |delta: &BlockDelta| delta.fill_rate_change.abs() > 0.1;
+    assert!(closure.keep(&delta(0, -0.2, &[])));
+
+    assert_eq!(
+        ChangeFilter::parse(&["fee > 1"]),
+        Err(ConditionError::UnknownChange("fee".to_string()))
+    );
+    assert_eq!(
+        ChangeFilter::parse(&["tx_count_change > 1 TIA"]),
+        Err(ConditionError::InvalidUnit("TIA".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn deltas_follow_the_head_and_report_new_namespaces() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/head"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "last_height": 103 })))
+        .mount(&server)
+        .await;
+    let blocks = [
+        (100, 1000, "0.1", vec![blob("aa")]),
+        (101, 1000, "0.1", vec![blob("aa")]),
+        (102, 3000, "0.4", vec![blob("aa"), blob("bb"), blob("bb")]),
+        (103, 3000, "0.4", vec![blob("cc")]),
+    ];
+    for (height, fee, fill_rate, blobs) in blocks {
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/stats", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(stats(
+                height,
+                fee,
+                fill_rate,
+                blobs.len() as u64,
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/block/{}/blobs", height)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(blobs))
+            .mount(&server)
+            .await;
+    }
+
+    let tool = CelestiaSearchTool::with_base_url(&server.uri());
+    let all: Vec<BlockDelta> = watch::deltas(
+        &tool,
+        Some(100),
+        Duration::from_millis(10),
+        ChangeFilter::default(),
+    )
+    .take(3)
+    .map(|delta| delta.unwrap())
+    .collect()
+    .await;
+    assert_eq!(
+        all.iter().map(|delta| delta.height).collect::<Vec<_>>(),
+        [101, 102, 103]
+    );
+    assert!(all[0].new_namespaces.is_empty());
+    assert_eq!(all[1].fee_change, Utia::new(2000));
+    assert_eq!(all[1].new_namespaces, ["00bb"]);
+    assert_eq!(all[2].new_namespaces, ["00cc"]);
+
+    let filter = ChangeFilter::parse(&["fill_rate_change > 0.2"]).unwrap();
+    let meaningful: Vec<u64> = watch::deltas(&tool, Some(100), Duration::from_millis(10), filter)
+        .take(1)
+        .map(|delta| delta.unwrap().height)
+        .collect()
+        .await;
+    assert_eq!(meaningful, [102]);
+}
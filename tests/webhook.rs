@@ -0,0 +1,139 @@
+#![cfg(feature = "server")]
+
+use celestia_search_assistant::network::Network;
+use celestia_search_assistant::webhook::{self, Delivered, Payload, WebhookError};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+#[test]
+fn normalizes_block_and_tx_payloads() {
+    // A block with its stats, as Celenium returns it
+    let block = json!({
+        "height": "120",
+        "hash": "ABCD",
+        "stats": { "tx_count": 4, "fill_rate": "0.9500", "fee": "25000" },
+    });
+    let payloads = webhook::normalize(&json!({ "data": block }), Network::Mocha).unwrap();
+    let [Payload::Block(stats)] = &payloads[..] else {
+        panic!("expected a single block");
+    };
+    assert_eq!(
+        (stats.height, stats.tx_count, stats.network),
+        (120, 4, Network::Mocha)
+    );
+    assert_eq!(stats.fill_rate, 0.95);
+
+    // Stats on their own, and the transactions of a block
+    let batch = json!([
+        { "height": 121, "tx_count": 2, "square_size": 8 },
+        { "txs": [{ "height": 121, "hash": "EF01", "gas_wanted": 90000 }] },
+        { "hash": "2345", "height": "122", "fee": "2000" },
+    ]);
+    let payloads = webhook::normalize(&batch, Network::Mainnet).unwrap();
+    let heights: Vec<u64> = payloads.iter().map(Payload::height).collect();
+    assert_eq!(heights, [121, 121, 122]);
+    assert!(matches!(&payloads[1], Payload::Tx { hash: Some(hash), .. } if hash == "EF01"));
+    assert!(matches!(&payloads[2], Payload::Tx { .. }));
+
+    assert!(matches!(
+        webhook::normalize(&json!({ "event": "ping" }), Network::Mainnet),
+        Err(WebhookError::Unrecognized)
+    ));
+    assert!(matches!(
+        webhook::normalize(
+            &json!({ "height": 5, "tx_count": "many" }),
+            Network::Mainnet
+        ),
+        Err(WebhookError::InvalidStats { height: 5, .. })
+    ));
+}
+
+#[test]
+fn remembers_delivered_heights() {
+    let mut delivered = Delivered::default();
+    assert!(delivered.first(10));
+    assert!(delivered.contains(10));
+    assert!(!delivered.first(10));
+
+    for height in 11..2000 {
+        delivered.first(height);
+    }
+    assert!(!delivered.contains(10));
+    assert!(delivered.contains(1999));
+}
+
+#[tokio::test]
+async fn accepts_posted_payloads_with_the_secret() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), webhook::PATH);
+    let (sender, mut payloads) = mpsc::channel(16);
+    tokio::spawn(webhook::serve(
+        listener,
+        Network::Mainnet,
+        Some("hunter2".to_string()),
+        sender,
+    ));
+
+    let client = reqwest::Client::new();
+    let body = json!({ "block": { "height": 300, "stats": { "fill_rate": "0.99" } } });
+    let response = client.post(&url).json(&body).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    let response = client
+        .post(&url)
+        .bearer_auth("hunter2")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    assert_eq!(
+        response.json::<serde_json::Value>().await.unwrap(),
+        json!({ "accepted": 1 })
+    );
+    let Some(Payload::Block(stats)) = payloads.recv().await else {
+        panic!("expected the block to be passed on");
+    };
+    assert_eq!((stats.height, stats.fill_rate), (300, 0.99));
+
+    let response = client
+        .post(&url)
+        .bearer_auth("hunter2")
+        .body("not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 405);
+}
+
+#[tokio::test]
+async fn stalled_requests_time_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (sender, _payloads) = mpsc::channel(16);
+    tokio::spawn(webhook::serve(listener, Network::Mainnet, None, sender));
+
+    // The headers are never finished, so the request is refused once the timeout passes
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"POST /webhook HTTP/1.1\r\nContent-Length: 2\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    tokio::time::timeout(
+        webhook::READ_TIMEOUT * 2,
+        stream.read_to_string(&mut response),
+    )
+    .await
+    .expect("the server should answer stalled requests")
+    .unwrap();
+    assert!(
+        response.starts_with("HTTP/1.1 408 Request Timeout"),
+        "{}",
+        response
+    );
+}
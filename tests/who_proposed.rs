@@ -0,0 +1,58 @@
+use celestia_search_assistant::celestia_search_tool::CelestiaSearchTool;
+use celestia_search_assistant::who_proposed_tool::WhoProposedTool;
+use rig::tool::Tool;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn proposer_server(proposer: Value) -> MockServer {
+    let server = MockServer::start().await;
+    for (route, body) in [
+        ("/head", json!({ "last_height": 3000000 })),
+        (
+            "/block/2000000",
+            json!({ "height": 2000000, "proposer": proposer }),
+        ),
+        (
+            "/validator/12",
+            json!({ "address": "celestiavaloper1stakely" }),
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+    }
+    server
+}
+
+#[tokio::test]
+async fn names_the_proposer_with_its_addresses() {
+    let server = proposer_server(json!({
+        "id": 12,
+        "moniker": "Stakely",
+        "cons_address": "celestiavalcons1stakely",
+    }))
+    .await;
+    let tool = WhoProposedTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "height": 2000000 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Block 2000000 on mainnet was proposed by Stakely (operator address \
+         celestiavaloper1stakely, consensus address celestiavalcons1stakely)."
+    );
+}
+
+#[tokio::test]
+async fn says_when_the_proposer_is_unknown() {
+    let server = proposer_server(Value::Null).await;
+    let tool = WhoProposedTool::new(CelestiaSearchTool::with_base_url(&server.uri()));
+
+    let args = serde_json::from_value(json!({ "height": 2000000 })).unwrap();
+    assert_eq!(
+        tool.call(args).await.unwrap(),
+        "Celenium doesn't report the proposer of block 2000000."
+    );
+}